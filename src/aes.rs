@@ -0,0 +1,498 @@
+// aes.rs
+// The AES block cipher, as specified in FIPS 197, with 128-bit, 192-bit, and
+// 256-bit keys, and the ECB, CBC, and CTR modes of operation of SP 800-38A.
+// Instead of the usual hard-coded lookup tables, the S-box is computed from its
+// definition, the multiplicative inverse in GF(2^8), followed by an affine
+// transformation, and the column mixing multiplies in the field directly, which
+// is slower, but shows where the numbers come from. Each round substitutes the
+// bytes of the 4x4 state, shifts its rows, mixes its columns, and adds a round
+// key, expanded from the cipher key, for 10, 12, or 14 rounds. ECB encrypts
+// each block on its own, so equal blocks of plaintext give equal blocks of
+// ciphertext, which leaks the structure of the data: it is only here for the
+// demonstration, and should never be used. CBC chains the blocks together with
+// an initialization vector, and pads the plaintext with PKCS#7, while CTR turns
+// the cipher into a stream cipher, by encrypting successive counter values.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+
+pub const BLOCK_SIZE: usize = 16;
+
+pub type Block = [u8; BLOCK_SIZE];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AesError {
+    InvalidKeyLength(usize),
+    InvalidLength(usize),
+    InvalidPadding,
+}
+
+impl fmt::Display for AesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AesError::InvalidKeyLength(length) => write!(
+                f,
+                "the key must be 16, 24, or 32 bytes long, not {}",
+                length
+            ),
+            AesError::InvalidLength(length) => write!(
+                f,
+                "the ciphertext must be a positive multiple of {} bytes long, not {}",
+                BLOCK_SIZE, length
+            ),
+            AesError::InvalidPadding => write!(f, "invalid padding"),
+        }
+    }
+}
+
+impl std::error::Error for AesError {}
+
+// Multiplication in GF(2^8), modulo the polynomial x^8 + x^4 + x^3 + x + 1.
+fn multiply(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80 != 0;
+        a <<= 1;
+        if carry {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+// The inverse is a^254, since a^255 = 1 for every nonzero element, and the
+// inverse of zero is defined to be zero, which this also gives.
+fn inverse(a: u8) -> u8 {
+    let mut result = 1;
+    let mut power = a;
+    let mut exponent = 254;
+    while exponent != 0 {
+        if exponent & 1 != 0 {
+            result = multiply(result, power);
+        }
+        power = multiply(power, power);
+        exponent >>= 1;
+    }
+    result
+}
+
+fn substitute(a: u8) -> u8 {
+    let b = inverse(a);
+    b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63
+}
+
+pub struct Aes {
+    round_keys: Vec<Block>,
+    sbox: [u8; 256],
+    inverse_sbox: [u8; 256],
+}
+
+impl Aes {
+    pub fn new(key: &[u8]) -> Result<Self, AesError> {
+        if ![16, 24, 32].contains(&key.len()) {
+            return Err(AesError::InvalidKeyLength(key.len()));
+        }
+        let mut sbox = [0; 256];
+        let mut inverse_sbox = [0; 256];
+        for value in 0..=255u8 {
+            sbox[value as usize] = substitute(value);
+            inverse_sbox[substitute(value) as usize] = value;
+        }
+
+        // The key schedule works on 32-bit words, and every word is the XOR of
+        // the previous one and the one a key length earlier, where the previous
+        // one is rotated, substituted, and mixed with a round constant at the
+        // start of each key length, and only substituted in the middle of it
+        // for 256-bit keys.
+        let length = key.len() / 4;
+        let rounds = length + 6;
+        let mut words: Vec<[u8; 4]> = key
+            .chunks_exact(4)
+            .map(|word| [word[0], word[1], word[2], word[3]])
+            .collect();
+        let mut round_constant = 1;
+        for i in length..4 * (rounds + 1) {
+            let mut word = words[i - 1];
+            if i % length == 0 {
+                word.rotate_left(1);
+                word = word.map(|byte| sbox[byte as usize]);
+                word[0] ^= round_constant;
+                round_constant = multiply(round_constant, 2);
+            } else if length > 6 && i % length == 4 {
+                word = word.map(|byte| sbox[byte as usize]);
+            }
+            let earlier = words[i - length];
+            words.push([0, 1, 2, 3].map(|j| word[j] ^ earlier[j]));
+        }
+        let round_keys = words
+            .chunks_exact(4)
+            .map(|chunk| {
+                let mut round_key = [0; BLOCK_SIZE];
+                for (bytes, word) in round_key.chunks_exact_mut(4).zip(chunk) {
+                    bytes.copy_from_slice(word);
+                }
+                round_key
+            })
+            .collect();
+
+        Ok(Self {
+            round_keys,
+            sbox,
+            inverse_sbox,
+        })
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+
+    // The state is stored column by column, so the byte at row r and column c
+    // is at index r + 4c, which is the order of the bytes in the input.
+    pub fn encrypt_block(&self, block: &mut Block) {
+        add_round_key(block, &self.round_keys[0]);
+        for round in 1..=self.rounds() {
+            for byte in block.iter_mut() {
+                *byte = self.sbox[*byte as usize];
+            }
+            shift_rows(block);
+            if round != self.rounds() {
+                mix_columns(block, [2, 3, 1, 1]);
+            }
+            add_round_key(block, &self.round_keys[round]);
+        }
+    }
+
+    pub fn decrypt_block(&self, block: &mut Block) {
+        add_round_key(block, &self.round_keys[self.rounds()]);
+        for round in (0..self.rounds()).rev() {
+            inverse_shift_rows(block);
+            for byte in block.iter_mut() {
+                *byte = self.inverse_sbox[*byte as usize];
+            }
+            add_round_key(block, &self.round_keys[round]);
+            if round != 0 {
+                mix_columns(block, [14, 11, 13, 9]);
+            }
+        }
+    }
+
+    // Insecure, as equal plaintext blocks give equal ciphertext blocks, so it
+    // is only for demonstration.
+    pub fn encrypt_ecb(&self, plaintext: &[u8]) -> Vec<u8> {
+        let mut data = pkcs7_pad(plaintext);
+        for chunk in data.chunks_exact_mut(BLOCK_SIZE) {
+            let block: &mut Block = chunk.try_into().unwrap();
+            self.encrypt_block(block);
+        }
+        data
+    }
+
+    pub fn decrypt_ecb(&self, ciphertext: &[u8]) -> Result<Vec<u8>, AesError> {
+        check_length(ciphertext)?;
+        let mut data = ciphertext.to_vec();
+        for chunk in data.chunks_exact_mut(BLOCK_SIZE) {
+            let block: &mut Block = chunk.try_into().unwrap();
+            self.decrypt_block(block);
+        }
+        pkcs7_unpad(data)
+    }
+
+    // Each plaintext block is XOR-ed with the previous ciphertext block, or
+    // with the initialization vector, which must be unpredictable.
+    pub fn encrypt_cbc(&self, iv: &Block, plaintext: &[u8]) -> Vec<u8> {
+        let mut data = pkcs7_pad(plaintext);
+        let mut previous = *iv;
+        for chunk in data.chunks_exact_mut(BLOCK_SIZE) {
+            let block: &mut Block = chunk.try_into().unwrap();
+            add_round_key(block, &previous);
+            self.encrypt_block(block);
+            previous = *block;
+        }
+        data
+    }
+
+    pub fn decrypt_cbc(&self, iv: &Block, ciphertext: &[u8]) -> Result<Vec<u8>, AesError> {
+        check_length(ciphertext)?;
+        let mut data = ciphertext.to_vec();
+        let mut previous = *iv;
+        for chunk in data.chunks_exact_mut(BLOCK_SIZE) {
+            let block: &mut Block = chunk.try_into().unwrap();
+            let encrypted = *block;
+            self.decrypt_block(block);
+            add_round_key(block, &previous);
+            previous = encrypted;
+        }
+        pkcs7_unpad(data)
+    }
+
+    // Encrypts or decrypts the data, with the initial counter block, which is
+    // incremented as a 128-bit big-endian number, and must never repeat.
+    pub fn apply_ctr(&self, counter: &Block, data: &[u8]) -> Vec<u8> {
+        let mut counter = u128::from_be_bytes(*counter);
+        let mut output = data.to_vec();
+        for chunk in output.chunks_mut(BLOCK_SIZE) {
+            let mut keystream = counter.to_be_bytes();
+            self.encrypt_block(&mut keystream);
+            for (byte, key) in chunk.iter_mut().zip(keystream) {
+                *byte ^= key;
+            }
+            counter = counter.wrapping_add(1);
+        }
+        output
+    }
+}
+
+fn add_round_key(block: &mut Block, round_key: &Block) {
+    for (byte, key) in block.iter_mut().zip(round_key) {
+        *byte ^= key;
+    }
+}
+
+// Row r is rotated to the left by r bytes.
+fn shift_rows(block: &mut Block) {
+    let old = *block;
+    for row in 1..4 {
+        for column in 0..4 {
+            block[row + 4 * column] = old[row + 4 * ((column + row) % 4)];
+        }
+    }
+}
+
+fn inverse_shift_rows(block: &mut Block) {
+    let old = *block;
+    for row in 1..4 {
+        for column in 0..4 {
+            block[row + 4 * ((column + row) % 4)] = old[row + 4 * column];
+        }
+    }
+}
+
+// Multiplies each column with the circulant matrix of the coefficients, which
+// are [2, 3, 1, 1] for encryption, and its inverse, [14, 11, 13, 9], for
+// decryption.
+fn mix_columns(block: &mut Block, coefficients: [u8; 4]) {
+    for column in block.chunks_exact_mut(4) {
+        let old = [column[0], column[1], column[2], column[3]];
+        for (row, byte) in column.iter_mut().enumerate() {
+            *byte = (0..4).fold(0, |acc, i| {
+                acc ^ multiply(coefficients[(4 + i - row) % 4], old[i])
+            });
+        }
+    }
+}
+
+fn check_length(ciphertext: &[u8]) -> Result<(), AesError> {
+    if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(BLOCK_SIZE) {
+        return Err(AesError::InvalidLength(ciphertext.len()));
+    }
+    Ok(())
+}
+
+// Appends n bytes of the value n, where n is between 1 and the block size, so
+// that the padding can always be removed unambiguously.
+pub fn pkcs7_pad(data: &[u8]) -> Vec<u8> {
+    let padding = BLOCK_SIZE - data.len() % BLOCK_SIZE;
+    let mut padded = data.to_vec();
+    padded.resize(data.len() + padding, padding as u8);
+    padded
+}
+
+pub fn pkcs7_unpad(mut data: Vec<u8>) -> Result<Vec<u8>, AesError> {
+    let padding = *data.last().ok_or(AesError::InvalidPadding)? as usize;
+    if padding == 0 || padding > BLOCK_SIZE || padding > data.len() {
+        return Err(AesError::InvalidPadding);
+    }
+    if data[data.len() - padding..]
+        .iter()
+        .any(|byte| *byte as usize != padding)
+    {
+        return Err(AesError::InvalidPadding);
+    }
+    data.truncate(data.len() - padding);
+    Ok(data)
+}
+
+// Demonstration
+
+use crate::encoding::HEX;
+use crate::harness::Harness;
+
+fn decode(hex: &str) -> Vec<u8> {
+    HEX.decode(hex).unwrap()
+}
+
+fn block(hex: &str) -> Block {
+    decode(hex).try_into().unwrap()
+}
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "the S-box computed from its definition",
+        (substitute(0x00), substitute(0x53), inverse(0x53)),
+        (0x63, 0xed, 0xca)
+    );
+
+    // The example vectors of appendix C of FIPS 197.
+    let plaintext = block("00112233445566778899aabbccddeeff");
+    let vectors = [
+        (
+            "000102030405060708090a0b0c0d0e0f",
+            "69c4e0d86a7b0430d8cdb78070b4c55a",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f1011121314151617",
+            "dda97ca4864cdfe06eaf70a0ec0d7191",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+            "8ea2b7ca516745bfeafc49904b496089",
+        ),
+    ];
+    for (key, expected) in vectors {
+        let aes = Aes::new(&decode(key)).unwrap();
+        let mut encrypted = plaintext;
+        aes.encrypt_block(&mut encrypted);
+        let mut decrypted = encrypted;
+        aes.decrypt_block(&mut decrypted);
+        let bits = key.len() * 4;
+        let description = format!("AES-{} matches the FIPS 197 vector", bits);
+        test!(harness, &description, HEX.encode(&encrypted), expected);
+        let description = format!("AES-{} decryption inverts encryption", bits);
+        test!(harness, &description, decrypted, plaintext);
+    }
+    test!(
+        harness,
+        "the number of rounds",
+        [16, 24, 32].map(|length| Aes::new(&vec![0; length]).unwrap().rounds()),
+        [10, 12, 14]
+    );
+    test!(
+        harness,
+        "rejecting a key of invalid length",
+        Aes::new(&[0; 20]).err().map(|error| error.to_string()),
+        Some("the key must be 16, 24, or 32 bytes long, not 20".to_string())
+    );
+
+    // The example vectors of appendix F of SP 800-38A, which are four blocks
+    // long, so padding only adds a fifth block to the CBC ciphertext.
+    let aes128 = Aes::new(&decode("2b7e151628aed2a6abf7158809cf4f3c")).unwrap();
+    let aes256 = Aes::new(&decode(
+        "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4",
+    ))
+    .unwrap();
+    let iv = block("000102030405060708090a0b0c0d0e0f");
+    let counter = block("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff");
+    let message = decode(
+        "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+         30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710",
+    );
+    test!(
+        harness,
+        "AES-128 in CBC mode matches the SP 800-38A vector",
+        HEX.encode(&aes128.encrypt_cbc(&iv, &message)[..64]),
+        "7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b2\
+         73bed6b8e3c1743b7116e69e222295163ff1caa1681fac09120eca307586e1a7"
+    );
+    test!(
+        harness,
+        "AES-256 in CBC mode matches the SP 800-38A vector",
+        HEX.encode(&aes256.encrypt_cbc(&iv, &message)[..64]),
+        "f58c4c04d6e5f1ba779eabfb5f7bfbd69cfc4e967edb808d679f777bc6702c7d\
+         39f23369a9d9bacfa530e26304231461b2eb05e2c39be9fcda6c19078c6a9d1b"
+    );
+    test!(
+        harness,
+        "AES-128 in CTR mode matches the SP 800-38A vector",
+        HEX.encode(&aes128.apply_ctr(&counter, &message)),
+        "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff\
+         5ae4df3edbd5d35e5b4f09020db03eab1e031dda2fbe03d1792170a0f3009cee"
+    );
+    test!(
+        harness,
+        "AES-256 in CTR mode matches the SP 800-38A vector",
+        HEX.encode(&aes256.apply_ctr(&counter, &message)),
+        "601ec313775789a5b7a7f504bbf3d228f443e3ca4d62b59aca84e990cacaf5c5\
+         2b0930daa23de94ce87017ba2d84988ddfc9c58db67aada613c2dd08457941a6"
+    );
+    test!(
+        harness,
+        "the CTR counter carries across 64-bit halves",
+        HEX.encode(&aes128.apply_ctr(&block("00000000000000ffffffffffffffffff"), &[0; 40])),
+        "dacc9148febbffe342d5805537ea155ff644566de02f529aa57d9a6064ac0ab6\
+         81bca66820a2754a"
+    );
+
+    let text = b"Two blocks of data, with padding";
+    let encrypted = aes128.encrypt_cbc(&iv, text);
+    test!(
+        harness,
+        "CBC with PKCS#7 padding",
+        (HEX.encode(&encrypted), aes128.decrypt_cbc(&iv, &encrypted)),
+        (
+            "642f9c619e4880a1aea36012815d0fe99b41765ffd3040c82d893acf24cbf9fb\
+             7333b7b7dc3913e43340685c395a4d54"
+                .to_string(),
+            Ok(text.to_vec())
+        )
+    );
+    test!(
+        harness,
+        "padding and unpadding",
+        (
+            pkcs7_pad(b"abc").len(),
+            pkcs7_pad(&[0; 16]).len(),
+            pkcs7_unpad(pkcs7_pad(b"abc"))
+        ),
+        (16, 32, Ok(b"abc".to_vec()))
+    );
+    let mut tampered = encrypted.clone();
+    tampered[31] ^= 0x01;
+    test!(
+        harness,
+        "rejecting invalid padding and lengths",
+        (
+            aes128.decrypt_cbc(&iv, &tampered),
+            aes128.decrypt_cbc(&iv, &encrypted[..20]),
+            pkcs7_unpad(vec![1, 2, 3, 0])
+        ),
+        (
+            Err(AesError::InvalidPadding),
+            Err(AesError::InvalidLength(20)),
+            Err(AesError::InvalidPadding)
+        )
+    );
+
+    let repeated = b"YELLOW SUBMARINEYELLOW SUBMARINEYELLOW SUBMARINE";
+    let ecb = aes128.encrypt_ecb(repeated);
+    let cbc = aes128.encrypt_cbc(&iv, repeated);
+    test!(
+        harness,
+        "ECB leaks repeated blocks, while CBC hides them",
+        (ecb[..16] == ecb[16..32], cbc[..16] == cbc[16..32]),
+        (true, false)
+    );
+    test!(
+        harness,
+        "ECB decryption",
+        aes128.decrypt_ecb(&ecb),
+        Ok(repeated.to_vec())
+    );
+
+    note!(harness, "Three equal blocks, encrypted with AES-128:");
+    for (mode, ciphertext) in [("ECB", &ecb), ("CBC", &cbc)] {
+        let blocks: Vec<String> = ciphertext
+            .chunks(BLOCK_SIZE)
+            .map(|block| HEX.encode(block))
+            .collect();
+        note!(harness, "    {}: {}", mode, blocks.join(" "));
+    }
+}
@@ -0,0 +1,470 @@
+// ansi.rs
+// Styling terminal output with ANSI escape sequences: the 16 basic colors, the
+// 256 colors of the extended palette, and 24-bit truecolor, for the foreground
+// and the background, along with bold, underlined, and reversed text. The
+// attributes of a style are combined into one Select Graphic Rendition (SGR)
+// sequence, like ESC [ 1 ; 31 m, which is followed by the text, and a reset.
+// Cursor movement and clearing the screen are Control Sequence Introducer (CSI)
+// sequences too. When the standard output is not a terminal, the sequences are
+// left out, so redirecting the output to a file gives plain text. The NO_COLOR
+// environment variable, and the dumb terminal turn them off too, and both
+// decisions can be overridden by the program.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    // An index into the palette of 256 colors: the 16 basic colors, a 6x6x6
+    // color cube, and 24 shades of gray.
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    pub const BASIC: [Color; 16] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+        Color::BrightBlack,
+        Color::BrightRed,
+        Color::BrightGreen,
+        Color::BrightYellow,
+        Color::BrightBlue,
+        Color::BrightMagenta,
+        Color::BrightCyan,
+        Color::BrightWhite,
+    ];
+
+    // The parameters of the SGR sequence, where the background codes of the
+    // basic colors are 10 more than the foreground ones.
+    fn parameters(self, background: bool) -> String {
+        let offset = if background { 10 } else { 0 };
+        match self {
+            Color::Indexed(index) => format!("{};5;{}", 38 + offset, index),
+            Color::Rgb(r, g, b) => format!("{};2;{};{};{}", 38 + offset, r, g, b),
+            basic => {
+                let index = Color::BASIC.iter().position(|c| *c == basic).unwrap();
+                let base = if index < 8 { 30 } else { 90 - 8 };
+                (base + index + offset).to_string()
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: bool,
+    pub underline: bool,
+    pub reverse: bool,
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn fg(mut self, color: Color) -> Self {
+        self.foreground = Some(color);
+        self
+    }
+
+    pub fn bg(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    pub fn reverse(mut self) -> Self {
+        self.reverse = true;
+        self
+    }
+
+    pub fn is_plain(&self) -> bool {
+        *self == Self::default()
+    }
+
+    // The SGR sequence which turns the style on, or an empty string for the
+    // plain style.
+    pub fn sequence(&self) -> String {
+        let mut parameters = Vec::new();
+        for (enabled, code) in [(self.bold, "1"), (self.underline, "4"), (self.reverse, "7")] {
+            if enabled {
+                parameters.push(code.to_string());
+            }
+        }
+        parameters.extend(self.foreground.map(|color| color.parameters(false)));
+        parameters.extend(self.background.map(|color| color.parameters(true)));
+        if parameters.is_empty() {
+            return String::new();
+        }
+        format!("\x1b[{}m", parameters.join(";"))
+    }
+
+    pub fn paint<T: fmt::Display>(self, content: T) -> Styled<T> {
+        Styled {
+            content,
+            style: self,
+        }
+    }
+}
+
+pub const RESET: &str = "\x1b[0m";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Styled<T> {
+    pub content: T,
+    pub style: Style,
+}
+
+impl<T: fmt::Display> Styled<T> {
+    pub fn fg(self, color: Color) -> Self {
+        self.style.fg(color).paint(self.content)
+    }
+
+    pub fn bg(self, color: Color) -> Self {
+        self.style.bg(color).paint(self.content)
+    }
+
+    pub fn bold(self) -> Self {
+        self.style.bold().paint(self.content)
+    }
+
+    pub fn underline(self) -> Self {
+        self.style.underline().paint(self.content)
+    }
+
+    pub fn reverse(self) -> Self {
+        self.style.reverse().paint(self.content)
+    }
+
+    pub fn render(&self, enabled: bool) -> String {
+        if !enabled || self.style.is_plain() {
+            return self.content.to_string();
+        }
+        format!("{}{}{}", self.style.sequence(), self.content, RESET)
+    }
+}
+
+// Displaying a styled value includes the sequences only if they are enabled.
+impl<T: fmt::Display> fmt::Display for Styled<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !enabled() || self.style.is_plain() {
+            return self.content.fmt(f);
+        }
+        write!(f, "{}{}{}", self.style.sequence(), self.content, RESET)
+    }
+}
+
+// Styling anything which can be displayed, like "text".fg(Color::Red).bold().
+pub trait Stylize: fmt::Display + Sized {
+    fn styled(self, style: Style) -> Styled<Self> {
+        style.paint(self)
+    }
+
+    fn fg(self, color: Color) -> Styled<Self> {
+        Style::new().fg(color).paint(self)
+    }
+
+    fn bg(self, color: Color) -> Styled<Self> {
+        Style::new().bg(color).paint(self)
+    }
+
+    fn bold(self) -> Styled<Self> {
+        Style::new().bold().paint(self)
+    }
+
+    fn underline(self) -> Styled<Self> {
+        Style::new().underline().paint(self)
+    }
+
+    fn reverse(self) -> Styled<Self> {
+        Style::new().reverse().paint(self)
+    }
+}
+
+impl<T: fmt::Display> Stylize for T {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    Up(u16),
+    Down(u16),
+    Forward(u16),
+    Back(u16),
+    // The column, starting from 1.
+    Column(u16),
+    // The row and the column, starting from 1.
+    Position(u16, u16),
+    SavePosition,
+    RestorePosition,
+    HideCursor,
+    ShowCursor,
+    ClearScreen,
+    ClearLine,
+    // Clears from the cursor to the end of the line.
+    ClearToEndOfLine,
+}
+
+impl Control {
+    pub fn sequence(&self) -> String {
+        match self {
+            Control::Up(n) => format!("\x1b[{}A", n),
+            Control::Down(n) => format!("\x1b[{}B", n),
+            Control::Forward(n) => format!("\x1b[{}C", n),
+            Control::Back(n) => format!("\x1b[{}D", n),
+            Control::Column(column) => format!("\x1b[{}G", column),
+            Control::Position(row, column) => format!("\x1b[{};{}H", row, column),
+            Control::SavePosition => "\x1b7".to_string(),
+            Control::RestorePosition => "\x1b8".to_string(),
+            Control::HideCursor => "\x1b[?25l".to_string(),
+            Control::ShowCursor => "\x1b[?25h".to_string(),
+            Control::ClearScreen => "\x1b[2J\x1b[H".to_string(),
+            Control::ClearLine => "\x1b[2K".to_string(),
+            Control::ClearToEndOfLine => "\x1b[K".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Control {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if enabled() {
+            write!(f, "{}", self.sequence())?;
+        }
+        Ok(())
+    }
+}
+
+const UNDECIDED: u8 = 0;
+const DISABLED: u8 = 1;
+const ENABLED: u8 = 2;
+
+static STATE: AtomicU8 = AtomicU8::new(UNDECIDED);
+
+// Whether the sequences should be written to the standard output, which is
+// decided on the first call, unless it was set before.
+pub fn enabled() -> bool {
+    match STATE.load(Ordering::Relaxed) {
+        UNDECIDED => {
+            let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+            let dumb = std::env::var_os("TERM").is_some_and(|term| term == "dumb");
+            let detected = std::io::stdout().is_terminal() && !no_color && !dumb;
+            set_enabled(detected);
+            detected
+        }
+        state => state == ENABLED,
+    }
+}
+
+pub fn set_enabled(enabled: bool) {
+    STATE.store(if enabled { ENABLED } else { DISABLED }, Ordering::Relaxed);
+}
+
+// Removes the CSI sequences, and the two-character escape sequences.
+pub fn strip(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('\x40'..='\x7e').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    out
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    let styled = "warning".fg(Color::Yellow).bold();
+    test!(
+        harness,
+        "a bold, yellow text",
+        styled.render(true),
+        "\x1b[1;33mwarning\x1b[0m"
+    );
+    test!(
+        harness,
+        "the sequences are left out when disabled",
+        styled.render(false),
+        "warning"
+    );
+    test!(
+        harness,
+        "the bright colors, in the foreground and the background",
+        Style::new()
+            .fg(Color::BrightWhite)
+            .bg(Color::BrightBlack)
+            .sequence(),
+        "\x1b[97;100m"
+    );
+    test!(
+        harness,
+        "an underlined, reversed color from the palette",
+        42.underline()
+            .reverse()
+            .fg(Color::Indexed(208))
+            .render(true),
+        "\x1b[4;7;38;5;208m42\x1b[0m"
+    );
+    test!(
+        harness,
+        "a truecolor background",
+        "sky".bg(Color::Rgb(135, 206, 235)).render(true),
+        "\x1b[48;2;135;206;235msky\x1b[0m"
+    );
+    test!(
+        harness,
+        "the plain style adds nothing",
+        Style::new().paint("plain").render(true),
+        "plain"
+    );
+    test!(
+        harness,
+        "moving the cursor",
+        (
+            Control::Up(3).sequence(),
+            Control::Position(12, 40).sequence(),
+            Control::ClearLine.sequence()
+        ),
+        (
+            "\x1b[3A".to_string(),
+            "\x1b[12;40H".to_string(),
+            "\x1b[2K".to_string()
+        )
+    );
+    test!(
+        harness,
+        "stripping the sequences",
+        strip(&format!(
+            "{}{}{} done",
+            Control::HideCursor.sequence(),
+            styled.render(true),
+            Control::Column(1).sequence()
+        )),
+        "warning done"
+    );
+
+    // The chart is written with the detected setting, so it only has colors
+    // on a terminal.
+    if !enabled() {
+        note!(
+            harness,
+            "The output is not a terminal, so the chart has no colors.\n"
+        );
+    }
+    let mut basic = String::new();
+    for (index, color) in Color::BASIC.iter().enumerate() {
+        let label = format!(" {:>2} ", index);
+        let foreground = if index == 0 || index == 8 {
+            Color::White
+        } else {
+            Color::Black
+        };
+        basic.push_str(&label.bg(*color).fg(foreground).to_string());
+        if index == 7 {
+            basic.push('\n');
+        }
+    }
+    note!(harness, "The basic colors:\n{}\n", basic);
+
+    let mut cube = String::new();
+    for green in 0..6 {
+        for red in 0..6 {
+            for blue in 0..6 {
+                let index = 16 + 36 * red + 6 * green + blue;
+                cube.push_str(&"  ".bg(Color::Indexed(index)).to_string());
+            }
+            cube.push(' ');
+        }
+        cube.push('\n');
+    }
+    for gray in 232..=255 {
+        cube.push_str(&"  ".bg(Color::Indexed(gray)).to_string());
+    }
+    note!(
+        harness,
+        "The color cube and the grays of the palette:\n{}\n",
+        cube
+    );
+
+    let mut gradient = String::new();
+    for row in 0..4 {
+        for column in 0..72u32 {
+            let hue = column * 360 / 72;
+            let (r, g, b) = hue_to_rgb(hue, 255 - row * 48);
+            gradient.push_str(&" ".bg(Color::Rgb(r, g, b)).to_string());
+        }
+        gradient.push('\n');
+    }
+    note!(harness, "A truecolor gradient:\n{}", gradient);
+    note!(
+        harness,
+        "{}, {}, and {} text\n",
+        "Bold".bold(),
+        "underlined".underline(),
+        "reversed".reverse()
+    );
+}
+
+// A fully saturated color of the hue, in degrees, at the given value.
+fn hue_to_rgb(hue: u32, value: u32) -> (u8, u8, u8) {
+    let sector = hue / 60;
+    let rising = value * (hue % 60) / 60;
+    let falling = value - rising;
+    let (r, g, b) = match sector {
+        0 => (value, rising, 0),
+        1 => (falling, value, 0),
+        2 => (0, value, rising),
+        3 => (0, falling, value),
+        4 => (rising, 0, value),
+        _ => (value, 0, falling),
+    };
+    (r as u8, g as u8, b as u8)
+}
@@ -0,0 +1,519 @@
+// args.rs
+// A small declarative command-line argument parser. The accepted arguments are
+// described up front, as flags, which are either present or not, and can be
+// repeated to count them, as options, which take a value, and as positional
+// arguments. The parser then accepts the usual conventions: short flags can be
+// grouped (-vvq), short options take their value either attached (-ofile) or as
+// the next argument, long options either after an equals sign or as the next
+// argument, and a double dash ends the options. The help text is generated from
+// the same description, and -h or --help is always accepted.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArgsError {
+    HelpRequested,
+    UnknownOption(String),
+    MissingValue(String),
+    UnexpectedValue(String),
+    MissingPositional(String),
+    UnexpectedPositional(String),
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArgsError::HelpRequested => write!(f, "the help text was requested"),
+            ArgsError::UnknownOption(option) => write!(f, "unknown option {}", option),
+            ArgsError::MissingValue(option) => write!(f, "the option {} requires a value", option),
+            ArgsError::UnexpectedValue(option) => {
+                write!(f, "the flag {} does not take a value", option)
+            }
+            ArgsError::MissingPositional(name) => write!(f, "the argument {} is missing", name),
+            ArgsError::UnexpectedPositional(value) => {
+                write!(f, "unexpected argument {:?}", value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArgsError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Flag,
+    Option,
+    Positional { required: bool, multiple: bool },
+}
+
+// Flags and options are identified by their long name, positional
+// arguments by their name, which is shown in the usage.
+#[derive(Clone, Debug)]
+struct Spec {
+    name: &'static str,
+    short: Option<char>,
+    value_name: &'static str,
+    help: &'static str,
+    kind: Kind,
+}
+
+#[derive(Clone, Debug)]
+pub struct Parser {
+    program: String,
+    about: &'static str,
+    specs: Vec<Spec>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Matches {
+    counts: BTreeMap<&'static str, usize>,
+    values: BTreeMap<&'static str, Vec<String>>,
+}
+
+impl Matches {
+    pub fn flag(&self, name: &str) -> bool {
+        self.count(name) > 0
+    }
+
+    pub fn count(&self, name: &str) -> usize {
+        self.counts.get(name).copied().unwrap_or(0)
+    }
+
+    // The last value, if the option was repeated.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.values(name).last().map(|value| value.as_str())
+    }
+
+    pub fn value_or<'a>(&'a self, name: &str, default: &'a str) -> &'a str {
+        self.value(name).unwrap_or(default)
+    }
+
+    pub fn values(&self, name: &str) -> &[String] {
+        self.values
+            .get(name)
+            .map_or(&[], |values| values.as_slice())
+    }
+}
+
+impl Parser {
+    pub fn new(program: &str, about: &'static str) -> Self {
+        Self {
+            program: program.to_string(),
+            about,
+            specs: Vec::new(),
+        }
+    }
+
+    pub fn flag(mut self, short: Option<char>, long: &'static str, help: &'static str) -> Self {
+        self.specs.push(Spec {
+            name: long,
+            short,
+            value_name: "",
+            help,
+            kind: Kind::Flag,
+        });
+        self
+    }
+
+    pub fn option(
+        mut self,
+        short: Option<char>,
+        long: &'static str,
+        value_name: &'static str,
+        help: &'static str,
+    ) -> Self {
+        self.specs.push(Spec {
+            name: long,
+            short,
+            value_name,
+            help,
+            kind: Kind::Option,
+        });
+        self
+    }
+
+    fn add_positional(
+        mut self,
+        name: &'static str,
+        help: &'static str,
+        required: bool,
+        multiple: bool,
+    ) -> Self {
+        assert!(
+            !self
+                .specs
+                .iter()
+                .any(|spec| matches!(spec.kind, Kind::Positional { multiple: true, .. })),
+            "no positional argument can follow the one which takes the rest"
+        );
+        self.specs.push(Spec {
+            name,
+            short: None,
+            value_name: name,
+            help,
+            kind: Kind::Positional { required, multiple },
+        });
+        self
+    }
+
+    pub fn positional(self, name: &'static str, help: &'static str) -> Self {
+        self.add_positional(name, help, true, false)
+    }
+
+    pub fn optional(self, name: &'static str, help: &'static str) -> Self {
+        self.add_positional(name, help, false, false)
+    }
+
+    // Collects every remaining positional argument.
+    pub fn rest(self, name: &'static str, help: &'static str) -> Self {
+        self.add_positional(name, help, false, true)
+    }
+
+    fn find_long(&self, long: &str) -> Option<&Spec> {
+        self.specs
+            .iter()
+            .filter(|spec| !matches!(spec.kind, Kind::Positional { .. }))
+            .find(|spec| spec.name == long)
+    }
+
+    fn find_short(&self, short: char) -> Option<&Spec> {
+        self.specs.iter().find(|spec| spec.short == Some(short))
+    }
+
+    pub fn parse<I, S>(&self, arguments: I) -> Result<Matches, ArgsError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut matches = Matches::default();
+        let mut positionals = Vec::new();
+        let mut arguments = arguments.into_iter().map(Into::into);
+        let mut only_positionals = false;
+
+        while let Some(argument) = arguments.next() {
+            if only_positionals || argument == "-" || !argument.starts_with('-') {
+                positionals.push(argument);
+            } else if argument == "--" {
+                only_positionals = true;
+            } else if let Some(long) = argument.strip_prefix("--") {
+                let (name, attached) = match long.split_once('=') {
+                    Some((name, value)) => (name, Some(value.to_string())),
+                    None => (long, None),
+                };
+                if name == "help" {
+                    return Err(ArgsError::HelpRequested);
+                }
+                let option = format!("--{}", name);
+                let spec = self
+                    .find_long(name)
+                    .ok_or_else(|| ArgsError::UnknownOption(option.clone()))?;
+                match spec.kind {
+                    Kind::Flag if attached.is_some() => {
+                        return Err(ArgsError::UnexpectedValue(option));
+                    }
+                    Kind::Flag => *matches.counts.entry(spec.name).or_default() += 1,
+                    _ => {
+                        let value = attached
+                            .or_else(|| arguments.next())
+                            .ok_or(ArgsError::MissingValue(option))?;
+                        matches.values.entry(spec.name).or_default().push(value);
+                    }
+                }
+            } else {
+                // A group of short flags, where the first option takes the
+                // rest of the group, or the next argument as its value.
+                let group = &argument[1..];
+                for (index, short) in group.char_indices() {
+                    if short == 'h' && self.find_short('h').is_none() {
+                        return Err(ArgsError::HelpRequested);
+                    }
+                    let option = format!("-{}", short);
+                    let spec = self
+                        .find_short(short)
+                        .ok_or_else(|| ArgsError::UnknownOption(option.clone()))?;
+                    if spec.kind == Kind::Flag {
+                        *matches.counts.entry(spec.name).or_default() += 1;
+                        continue;
+                    }
+                    let attached = &group[index + short.len_utf8()..];
+                    let value = if attached.is_empty() {
+                        arguments.next().ok_or(ArgsError::MissingValue(option))?
+                    } else {
+                        attached.to_string()
+                    };
+                    matches.values.entry(spec.name).or_default().push(value);
+                    break;
+                }
+            }
+        }
+
+        let mut positionals = positionals.into_iter();
+        for spec in &self.specs {
+            let Kind::Positional { required, multiple } = spec.kind else {
+                continue;
+            };
+            let values: Vec<String> = match multiple {
+                true => positionals.by_ref().collect(),
+                false => positionals.next().into_iter().collect(),
+            };
+            if values.is_empty() && required {
+                return Err(ArgsError::MissingPositional(spec.name.to_string()));
+            }
+            if !values.is_empty() {
+                matches.values.insert(spec.name, values);
+            }
+        }
+        if let Some(extra) = positionals.next() {
+            return Err(ArgsError::UnexpectedPositional(extra));
+        }
+        Ok(matches)
+    }
+
+    // Parses the arguments for a binary: the help text is printed, and so is
+    // the error with the usage, and either of them gives the exit code to end
+    // the program with.
+    pub fn parse_or_report<I, S>(&self, arguments: I) -> Result<Matches, i32>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        match self.parse(arguments) {
+            Ok(matches) => Ok(matches),
+            Err(ArgsError::HelpRequested) => {
+                print!("{}", self.help());
+                Err(0)
+            }
+            Err(error) => {
+                eprintln!("{}: {}\n{}", self.program, error, self.usage());
+                Err(2)
+            }
+        }
+    }
+
+    pub fn usage(&self) -> String {
+        let mut usage = format!("Usage: {} [OPTIONS]", self.program);
+        for spec in &self.specs {
+            match spec.kind {
+                Kind::Positional {
+                    required: true,
+                    multiple: false,
+                } => usage.push_str(&format!(" {}", spec.name)),
+                Kind::Positional {
+                    multiple: false, ..
+                } => usage.push_str(&format!(" [{}]", spec.name)),
+                Kind::Positional { multiple: true, .. } => {
+                    usage.push_str(&format!(" [{}...]", spec.name))
+                }
+                _ => {}
+            }
+        }
+        usage
+    }
+
+    pub fn help(&self) -> String {
+        let mut arguments = Vec::new();
+        let mut options = Vec::new();
+        for spec in &self.specs {
+            let short = match spec.short {
+                Some(short) => format!("-{}, ", short),
+                None => "    ".to_string(),
+            };
+            match spec.kind {
+                Kind::Flag => options.push((format!("{}--{}", short, spec.name), spec.help)),
+                Kind::Option => options.push((
+                    format!("{}--{} <{}>", short, spec.name, spec.value_name),
+                    spec.help,
+                )),
+                Kind::Positional { .. } => arguments.push((spec.name.to_string(), spec.help)),
+            }
+        }
+        if self.find_short('h').is_none() {
+            options.push(("-h, --help".to_string(), "Print this help text"));
+        } else {
+            options.push(("    --help".to_string(), "Print this help text"));
+        }
+
+        let width = arguments
+            .iter()
+            .chain(&options)
+            .map(|(name, _)| name.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut help = format!("{}\n\n{}\n", self.about, self.usage());
+        for (title, entries) in [("Arguments", &arguments), ("Options", &options)] {
+            if entries.is_empty() {
+                continue;
+            }
+            help.push_str(&format!("\n{}:\n", title));
+            for (name, description) in entries {
+                help.push_str(&format!(
+                    "  {:<width$}  {}\n",
+                    name,
+                    description,
+                    width = width
+                ));
+            }
+        }
+        help
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+fn example() -> Parser {
+    Parser::new("compress", "Compresses files with a chosen algorithm.")
+        .flag(
+            Some('v'),
+            "verbose",
+            "Print more details, repeat for even more",
+        )
+        .flag(Some('k'), "keep", "Keep the input files")
+        .flag(None, "dry-run", "Only print what would be done")
+        .option(Some('o'), "output", "FILE", "Write the output to FILE")
+        .option(Some('l'), "level", "N", "The compression level")
+        .option(None, "exclude", "GLOB", "Skip the matching files")
+        .positional("ALGORITHM", "The algorithm, rle or lz77")
+        .rest("FILES", "The files to compress")
+}
+
+pub fn demo(harness: &mut Harness) {
+    let parser = example();
+    let matches = parser
+        .parse([
+            "-vvk",
+            "--level=9",
+            "-ofile.out",
+            "lz77",
+            "a.txt",
+            "--exclude",
+            "*.tmp",
+            "--exclude=*.bak",
+            "b.txt",
+            "--",
+            "-v",
+        ])
+        .unwrap();
+    test!(
+        harness,
+        "grouped short flags are counted",
+        (matches.count("verbose"), matches.flag("keep")),
+        (2, true)
+    );
+    test!(
+        harness,
+        "a missing flag is not set",
+        matches.flag("dry-run"),
+        false
+    );
+    test!(
+        harness,
+        "option values are attached in both forms",
+        (matches.value("level"), matches.value("output")),
+        (Some("9"), Some("file.out"))
+    );
+    test!(
+        harness,
+        "repeated options collect every value",
+        matches.values("exclude").to_vec(),
+        vec!["*.tmp", "*.bak"]
+    );
+    test!(
+        harness,
+        "positional arguments are collected around the options",
+        (matches.value("ALGORITHM"), matches.values("FILES").to_vec()),
+        (
+            Some("lz77"),
+            vec!["a.txt".to_string(), "b.txt".to_string(), "-v".to_string()]
+        )
+    );
+    test!(
+        harness,
+        "a default value for a missing option",
+        parser.parse(["rle"]).unwrap().value_or("level", "5"),
+        "5"
+    );
+    test!(
+        harness,
+        "the value of a short option can be the next argument",
+        parser.parse(["-l", "3", "rle"]).unwrap().value("level"),
+        Some("3")
+    );
+    test!(
+        harness,
+        "a single dash is a positional argument",
+        parser.parse(["rle", "-"]).unwrap().values("FILES").to_vec(),
+        vec!["-"]
+    );
+
+    let errors: [(&[&str], ArgsError); 7] = [
+        (&["-x", "rle"], ArgsError::UnknownOption("-x".to_string())),
+        (
+            &["--fast", "rle"],
+            ArgsError::UnknownOption("--fast".to_string()),
+        ),
+        (
+            &["rle", "--output"],
+            ArgsError::MissingValue("--output".to_string()),
+        ),
+        (&["rle", "-vo"], ArgsError::MissingValue("-o".to_string())),
+        (
+            &["--keep=yes", "rle"],
+            ArgsError::UnexpectedValue("--keep".to_string()),
+        ),
+        (
+            &["-v"],
+            ArgsError::MissingPositional("ALGORITHM".to_string()),
+        ),
+        (&["-vh"], ArgsError::HelpRequested),
+    ];
+    let mut messages = Vec::new();
+    for (arguments, expected) in errors {
+        let error = parser.parse(arguments.iter().copied()).unwrap_err();
+        test!(
+            harness,
+            &format!("the error for {:?}", arguments),
+            error.clone(),
+            expected
+        );
+        messages.push((arguments, error.to_string()));
+    }
+    let strict = Parser::new("echo", "Echoes one word.").positional("WORD", "The word");
+    test!(
+        harness,
+        "extra positional arguments are rejected",
+        strict.parse(["a", "b"]),
+        Err(ArgsError::UnexpectedPositional("b".to_string()))
+    );
+
+    let help = parser.help();
+    test!(
+        harness,
+        "the usage lists the positional arguments",
+        parser.usage(),
+        "Usage: compress [OPTIONS] ALGORITHM [FILES...]"
+    );
+    test!(
+        harness,
+        "the help text aligns the descriptions",
+        (
+            help.contains("\n  -o, --output <FILE>   Write the output to FILE\n"),
+            help.contains("\n      --dry-run         Only print what would be done\n"),
+            help.contains("\n  FILES                 The files to compress\n")
+        ),
+        (true, true, true)
+    );
+
+    note!(harness, "{}", help);
+    for (arguments, message) in messages {
+        note!(harness, "{:<24} {}", format!("{:?}", arguments), message);
+    }
+    note!(harness);
+}
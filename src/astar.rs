@@ -0,0 +1,384 @@
+// astar.rs
+// The A* search algorithm, finding the shortest path between two cells of a
+// grid with walls. Like Dijkstra's algorithm, it expands the cell with the
+// lowest cost from a heap, but it adds an estimate of the remaining cost to the
+// key, so the search is drawn towards the goal, and explores fewer cells. The
+// path stays the shortest, as long as the estimate never exceeds the real cost.
+// Moving to a neighbouring cell costs 10, and moving diagonally costs 14, an
+// approximation of 10 times the square root of 2, which keeps the arithmetic in
+// integers. With four-way movement, the Manhattan distance is the right
+// estimate, while eight-way movement needs the diagonal (octile) distance, as
+// the Manhattan distance would overestimate diagonal paths. Diagonal moves may
+// not cut the corners of walls. Ties between equal keys are broken in favour
+// of the cell closer to the goal, which avoids exploring plateaus.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::heap::{DaryHeap, Handle};
+
+pub type Point = (usize, usize);
+
+pub const STRAIGHT_COST: u32 = 10;
+pub const DIAGONAL_COST: u32 = 14;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Movement {
+    FourWay,
+    EightWay,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Heuristic {
+    // Without an estimate, A* is Dijkstra's algorithm.
+    Zero,
+    Manhattan,
+    Diagonal,
+}
+
+impl Heuristic {
+    pub fn estimate(self, from: Point, to: Point) -> u32 {
+        let dx = from.0.abs_diff(to.0) as u32;
+        let dy = from.1.abs_diff(to.1) as u32;
+        match self {
+            Heuristic::Zero => 0,
+            Heuristic::Manhattan => STRAIGHT_COST * (dx + dy),
+            Heuristic::Diagonal => {
+                STRAIGHT_COST * dx.max(dy) + (DIAGONAL_COST - STRAIGHT_COST) * dx.min(dy)
+            }
+        }
+    }
+}
+
+// Cells marked with '#' are walls, anything else is free.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid {
+    width: usize,
+    height: usize,
+    cells: Vec<char>,
+}
+
+impl Grid {
+    // Shorter rows are padded with free cells.
+    pub fn from_rows(rows: &[&str]) -> Self {
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut cells = Vec::with_capacity(width * rows.len());
+        for row in rows {
+            cells.extend(row.chars());
+            cells.extend(std::iter::repeat_n(' ', width - row.chars().count()));
+        }
+        Self {
+            width,
+            height: rows.len(),
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn cell(&self, (x, y): Point) -> char {
+        self.cells[y * self.width + x]
+    }
+
+    pub fn set_cell(&mut self, (x, y): Point, cell: char) {
+        self.cells[y * self.width + x] = cell;
+    }
+
+    pub fn is_wall(&self, point: Point) -> bool {
+        self.cell(point) == '#'
+    }
+
+    // The first cell with the given marker, in row-major order.
+    pub fn find(&self, marker: char) -> Option<Point> {
+        let index = self.cells.iter().position(|cell| *cell == marker)?;
+        Some((index % self.width, index / self.width))
+    }
+
+    fn is_free(&self, x: isize, y: isize) -> bool {
+        x >= 0
+            && y >= 0
+            && (x as usize) < self.width
+            && (y as usize) < self.height
+            && !self.is_wall((x as usize, y as usize))
+    }
+
+    pub fn neighbours(&self, (x, y): Point, movement: Movement) -> Vec<(Point, u32)> {
+        let (x, y) = (x as isize, y as isize);
+        let mut neighbours = Vec::new();
+        for (dx, dy) in [(0, -1), (1, 0), (0, 1), (-1, 0)] {
+            if self.is_free(x + dx, y + dy) {
+                let point = ((x + dx) as usize, (y + dy) as usize);
+                neighbours.push((point, STRAIGHT_COST));
+            }
+        }
+        if movement == Movement::EightWay {
+            for (dx, dy) in [(1, -1), (1, 1), (-1, 1), (-1, -1)] {
+                let corners = self.is_free(x + dx, y) && self.is_free(x, y + dy);
+                if corners && self.is_free(x + dx, y + dy) {
+                    let point = ((x + dx) as usize, (y + dy) as usize);
+                    neighbours.push((point, DIAGONAL_COST));
+                }
+            }
+        }
+        neighbours
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Search {
+    pub path: Option<Vec<Point>>,
+    pub cost: Option<u32>,
+    // The expanded cells, in the order they were taken from the heap.
+    pub explored: Vec<Point>,
+}
+
+// An expanded cell is never expanded again, which is only correct if the
+// heuristic is consistent, like the matching estimate of each movement.
+pub fn find_path(
+    grid: &Grid,
+    start: Point,
+    goal: Point,
+    movement: Movement,
+    heuristic: Heuristic,
+) -> Search {
+    let index = |(x, y): Point| y * grid.width + x;
+    let count = grid.width * grid.height;
+    let mut costs: Vec<Option<u32>> = vec![None; count];
+    let mut previous: Vec<Option<Point>> = vec![None; count];
+    let mut handles: Vec<Option<Handle>> = vec![None; count];
+    let mut closed = vec![false; count];
+    let mut explored = Vec::new();
+    let mut heap = DaryHeap::new(4);
+    let estimate = heuristic.estimate(start, goal);
+    costs[index(start)] = Some(0);
+    handles[index(start)] = Some(heap.push((estimate, estimate, start)));
+    while let Some((_, _, point)) = heap.pop() {
+        closed[index(point)] = true;
+        explored.push(point);
+        let cost = costs[index(point)].unwrap();
+        if point == goal {
+            let mut path = vec![goal];
+            while let Some(before) = previous[index(*path.last().unwrap())] {
+                path.push(before);
+            }
+            path.reverse();
+            return Search {
+                path: Some(path),
+                cost: Some(cost),
+                explored,
+            };
+        }
+        for (next, step) in grid.neighbours(point, movement) {
+            let candidate = cost + step;
+            let known = costs[index(next)];
+            if closed[index(next)] || known.is_some_and(|known| known <= candidate) {
+                continue;
+            }
+            costs[index(next)] = Some(candidate);
+            previous[index(next)] = Some(point);
+            let estimate = heuristic.estimate(next, goal);
+            let key = (candidate + estimate, estimate, next);
+            match handles[index(next)] {
+                Some(handle) if heap.contains(handle) => heap.decrease_key(handle, key).unwrap(),
+                _ => handles[index(next)] = Some(heap.push(key)),
+            }
+        }
+    }
+    Search {
+        path: None,
+        cost: None,
+        explored,
+    }
+}
+
+// Free cells which were explored are marked with dots, and the path with
+// asterisks, leaving the markers of its ends in place.
+pub fn render(grid: &Grid, search: &Search) -> String {
+    let mut canvas = grid.clone();
+    for point in &search.explored {
+        if canvas.cell(*point) == ' ' {
+            canvas.set_cell(*point, '.');
+        }
+    }
+    for point in search.path.iter().flatten() {
+        if matches!(canvas.cell(*point), ' ' | '.') {
+            canvas.set_cell(*point, '*');
+        }
+    }
+    canvas
+        .cells
+        .chunks(canvas.width)
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect()
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+#[rustfmt::skip]
+const MAP: [&str; 12] = [
+    "##############################",
+    "#S       #           #       #",
+    "#        #     #     #   #   #",
+    "#   ######     #     #   #   #",
+    "#        #     #         #   #",
+    "#        #     ########  #   #",
+    "#              #         #   #",
+    "#####   ########   #######   #",
+    "#          #       #         #",
+    "#   ####   #   #####   ##### #",
+    "#      #       #           #G#",
+    "##############################",
+];
+
+// Checks that the path is made of free, neighbouring cells, and that its steps
+// add up to the cost of the search.
+fn is_valid_path(grid: &Grid, search: &Search, movement: Movement) -> bool {
+    let (Some(path), Some(cost)) = (&search.path, search.cost) else {
+        return false;
+    };
+    let mut total = 0;
+    for pair in path.windows(2) {
+        let neighbours = grid.neighbours(pair[0], movement);
+        let Some((_, step)) = neighbours.iter().find(|(point, _)| *point == pair[1]) else {
+            return false;
+        };
+        total += step;
+    }
+    total == cost
+}
+
+pub fn demo(harness: &mut Harness) {
+    let grid = Grid::from_rows(&MAP);
+    let (start, goal) = (grid.find('S').unwrap(), grid.find('G').unwrap());
+
+    test!(
+        harness,
+        "the Manhattan distance",
+        Heuristic::Manhattan.estimate((1, 2), (4, 8)),
+        90
+    );
+    test!(
+        harness,
+        "the diagonal distance",
+        Heuristic::Diagonal.estimate((1, 2), (4, 8)),
+        72
+    );
+    test!(
+        harness,
+        "diagonal moves do not cut corners",
+        Grid::from_rows(&["  ", " #"]).neighbours((0, 0), Movement::EightWay),
+        vec![((1, 0), STRAIGHT_COST), ((0, 1), STRAIGHT_COST)]
+    );
+
+    let open = Grid::from_rows(&[" ".repeat(10).as_str(); 10]);
+    test!(
+        harness,
+        "crossing an open grid with four-way movement",
+        find_path(
+            &open,
+            (0, 0),
+            (9, 9),
+            Movement::FourWay,
+            Heuristic::Manhattan
+        )
+        .cost,
+        Some(180)
+    );
+    let diagonal = find_path(
+        &open,
+        (0, 0),
+        (9, 9),
+        Movement::EightWay,
+        Heuristic::Diagonal,
+    );
+    test!(
+        harness,
+        "crossing an open grid with eight-way movement",
+        (diagonal.cost, diagonal.explored.len()),
+        (Some(126), 10)
+    );
+
+    let searches = [
+        (Movement::FourWay, Heuristic::Zero),
+        (Movement::FourWay, Heuristic::Manhattan),
+        (Movement::EightWay, Heuristic::Zero),
+        (Movement::EightWay, Heuristic::Diagonal),
+    ]
+    .map(|(movement, heuristic)| {
+        let search = find_path(&grid, start, goal, movement, heuristic);
+        (movement, heuristic, search)
+    });
+    for (movement, heuristic, search) in &searches {
+        test!(
+            harness,
+            &format!(
+                "a valid path with {:?} movement and {:?} heuristic",
+                movement, heuristic
+            ),
+            is_valid_path(&grid, search, *movement),
+            true
+        );
+    }
+    for pair in searches.chunks(2) {
+        let (dijkstra, astar) = (&pair[0].2, &pair[1].2);
+        test!(
+            harness,
+            &format!("A* finds the shortest path with {:?} movement", pair[0].0),
+            astar.cost,
+            dijkstra.cost
+        );
+        test!(
+            harness,
+            &format!("A* explores fewer cells with {:?} movement", pair[0].0),
+            astar.explored.len() < dijkstra.explored.len(),
+            true
+        );
+    }
+
+    let mut walled = grid.clone();
+    walled.set_cell((28, 9), '#');
+    let free = MAP
+        .iter()
+        .flat_map(|row| row.chars())
+        .filter(|c| *c != '#')
+        .count();
+    let unreachable = find_path(
+        &walled,
+        start,
+        goal,
+        Movement::EightWay,
+        Heuristic::Diagonal,
+    );
+    test!(
+        harness,
+        "an enclosed goal has no path, after exploring everything else",
+        (unreachable.path, unreachable.explored.len()),
+        (None, free - 2)
+    );
+
+    for (movement, heuristic, search) in &searches {
+        note!(
+            harness,
+            "{:?} movement, {:?} heuristic: cost {}, {} cells explored",
+            movement,
+            heuristic,
+            search.cost.unwrap(),
+            search.explored.len()
+        );
+        note!(harness, "{}", render(&grid, search));
+    }
+}
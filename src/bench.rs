@@ -0,0 +1,399 @@
+// bench.rs
+// A stopwatch, and a micro-benchmark runner for timing the other snippets. The
+// runner first calls the routine a few times without measuring, to warm up the
+// caches and the branch predictors, then takes a number of samples, each of
+// which times a batch of calls, so routines faster than the clock can still be
+// measured. Inputs can be prepared outside of the measured time, which keeps
+// copying the input of a sort out of its timings. The report gives the fastest,
+// the median, and the mean time per call, along with the standard deviation,
+// and benchmarks can be compared relative to the fastest of them, in a table.
+// The black box hides a value from the optimizer, which could otherwise remove
+// a computation whose result is never used.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::table::{Alignment, Table};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+// The optimizer has to assume that the value is used, and may be changed, so
+// it can neither see through it, nor remove the code which computed it. The
+// standard library provides this on a best-effort basis, which is enough for
+// benchmarks.
+pub fn black_box<T>(value: T) -> T {
+    std::hint::black_box(value)
+}
+
+#[derive(Clone, Debug)]
+pub struct Stopwatch {
+    start: Instant,
+    lap_start: Instant,
+    laps: Vec<Duration>,
+}
+
+impl Stopwatch {
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            start: now,
+            lap_start: now,
+            laps: Vec::new(),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    // Ends the current lap, returning its time, and starts the next one.
+    pub fn lap(&mut self) -> Duration {
+        let now = Instant::now();
+        let lap = now - self.lap_start;
+        self.lap_start = now;
+        self.laps.push(lap);
+        lap
+    }
+
+    pub fn laps(&self) -> &[Duration] {
+        &self.laps
+    }
+
+    pub fn restart(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for Stopwatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Writes the time with three significant digits, in the largest fitting unit.
+pub fn format_time(time: Duration) -> String {
+    let nanoseconds = time.as_secs_f64() * 1e9;
+    let (value, unit) = if nanoseconds < 1e3 {
+        (nanoseconds, "ns")
+    } else if nanoseconds < 1e6 {
+        (nanoseconds / 1e3, "µs")
+    } else if nanoseconds < 1e9 {
+        (nanoseconds / 1e6, "ms")
+    } else {
+        (nanoseconds / 1e9, "s")
+    };
+    let decimals = if value < 10.0 {
+        2
+    } else if value < 100.0 {
+        1
+    } else {
+        0
+    };
+    format!("{:.*} {}", decimals, value, unit)
+}
+
+// The times per call of each sample, in ascending order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report {
+    samples: Vec<Duration>,
+}
+
+impl Report {
+    pub fn new(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "a report needs at least one sample");
+        samples.sort();
+        Self { samples }
+    }
+
+    pub fn samples(&self) -> &[Duration] {
+        &self.samples
+    }
+
+    pub fn min(&self) -> Duration {
+        self.samples[0]
+    }
+
+    pub fn max(&self) -> Duration {
+        self.samples[self.samples.len() - 1]
+    }
+
+    pub fn median(&self) -> Duration {
+        let middle = self.samples.len() / 2;
+        if self.samples.len().is_multiple_of(2) {
+            (self.samples[middle - 1] + self.samples[middle]) / 2
+        } else {
+            self.samples[middle]
+        }
+    }
+
+    // Divided as a float, as the count may not fit in the u32 that a duration
+    // is divided by.
+    pub fn mean(&self) -> Duration {
+        self.samples
+            .iter()
+            .sum::<Duration>()
+            .div_f64(self.samples.len() as f64)
+    }
+
+    // The sample standard deviation, which is zero for a single sample.
+    pub fn stddev(&self) -> Duration {
+        let count = self.samples.len();
+        if count < 2 {
+            return Duration::ZERO;
+        }
+        let mean = self.mean().as_secs_f64();
+        let squares: f64 = self
+            .samples
+            .iter()
+            .map(|sample| (sample.as_secs_f64() - mean).powi(2))
+            .sum();
+        Duration::from_secs_f64((squares / (count - 1) as f64).sqrt())
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "median {}, mean {} ± {}, min {}, {} samples",
+            format_time(self.median()),
+            format_time(self.mean()),
+            format_time(self.stddev()),
+            format_time(self.min()),
+            self.samples.len()
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bench {
+    warmup: usize,
+    samples: usize,
+    batch: usize,
+}
+
+impl Bench {
+    pub fn new() -> Self {
+        Self {
+            warmup: 3,
+            samples: 25,
+            batch: 1,
+        }
+    }
+
+    // The number of unmeasured calls before the samples.
+    pub fn warmup(mut self, calls: usize) -> Self {
+        self.warmup = calls;
+        self
+    }
+
+    pub fn samples(mut self, samples: usize) -> Self {
+        self.samples = samples.max(1);
+        self
+    }
+
+    // The number of calls timed together in each sample.
+    pub fn batch(mut self, calls: usize) -> Self {
+        self.batch = calls.max(1);
+        self
+    }
+
+    pub fn run<T>(&self, mut routine: impl FnMut() -> T) -> Report {
+        self.run_with_input(|| (), |()| routine())
+    }
+
+    // The inputs of a sample are all prepared before its clock starts, and the
+    // results are only dropped after it stops.
+    pub fn run_with_input<I, T>(
+        &self,
+        mut setup: impl FnMut() -> I,
+        mut routine: impl FnMut(I) -> T,
+    ) -> Report {
+        for _ in 0..self.warmup {
+            black_box(routine(black_box(setup())));
+        }
+        let mut samples = Vec::with_capacity(self.samples);
+        let mut results = Vec::with_capacity(self.batch);
+        for _ in 0..self.samples {
+            let inputs: Vec<I> = (0..self.batch).map(|_| setup()).collect();
+            let start = Instant::now();
+            for input in inputs {
+                results.push(black_box(routine(black_box(input))));
+            }
+            samples.push(start.elapsed().div_f64(self.batch as f64));
+            results.clear();
+        }
+        Report::new(samples)
+    }
+}
+
+impl Default for Bench {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// A table of the benchmarks, with their medians relative to the fastest one.
+pub fn compare(results: &[(&str, Report)]) -> String {
+    let fastest = results
+        .iter()
+        .map(|(_, report)| report.median())
+        .min()
+        .unwrap_or_default()
+        .as_secs_f64();
+    let mut table =
+        Table::new().header(&["benchmark", "median", "mean ± stddev", "min", "relative"]);
+    for (name, report) in results {
+        let relative = if fastest > 0.0 {
+            report.median().as_secs_f64() / fastest
+        } else {
+            1.0
+        };
+        table.push(&[
+            name.to_string(),
+            format_time(report.median()),
+            format!(
+                "{} ± {}",
+                format_time(report.mean()),
+                format_time(report.stddev())
+            ),
+            format_time(report.min()),
+            format!("{:.2}x", relative),
+        ]);
+    }
+    (1..5)
+        .fold(table, |table, column| table.align(column, Alignment::Right))
+        .render()
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "the black box returns its value unchanged",
+        (black_box(42), black_box(vec![1, 2, 3])),
+        (42, vec![1, 2, 3])
+    );
+
+    let micros = |values: &[u64]| {
+        Report::new(
+            values
+                .iter()
+                .map(|&value| Duration::from_micros(value))
+                .collect(),
+        )
+    };
+    let report = micros(&[4, 10, 1, 3, 2]);
+    test!(
+        harness,
+        "the report sorts its samples",
+        report.samples(),
+        micros(&[1, 2, 3, 4, 10]).samples()
+    );
+    test!(
+        harness,
+        "the minimum, median, mean, and maximum of the samples",
+        [report.min(), report.median(), report.mean(), report.max()],
+        [1, 3, 4, 10].map(Duration::from_micros)
+    );
+    test!(
+        harness,
+        "the sample standard deviation",
+        (report.stddev().as_secs_f64() * 1e6 - 12.5f64.sqrt()).abs() < 1e-3,
+        true
+    );
+    test!(
+        harness,
+        "the median of an even number of samples is the mean of the middle two",
+        [micros(&[1, 2, 4, 8]).median(), micros(&[5]).stddev()],
+        [Duration::from_micros(3), Duration::ZERO]
+    );
+    test!(
+        harness,
+        "times are written with three significant digits",
+        [
+            Duration::from_nanos(7),
+            Duration::from_nanos(123),
+            Duration::from_nanos(4567),
+            Duration::from_micros(45_678),
+            Duration::from_millis(2500)
+        ]
+        .map(format_time),
+        ["7.00 ns", "123 ns", "4.57 µs", "45.7 ms", "2.50 s"]
+    );
+    test!(
+        harness,
+        "a report is summarized in one line",
+        report.to_string(),
+        "median 3.00 µs, mean 4.00 µs ± 3.54 µs, min 1.00 µs, 5 samples"
+    );
+
+    let mut setups = 0;
+    let mut calls = 0;
+    let report = Bench::new().warmup(2).samples(5).batch(3).run_with_input(
+        || {
+            setups += 1;
+        },
+        |()| {
+            calls += 1;
+        },
+    );
+    test!(
+        harness,
+        "the runner warms up, then takes batches of calls as samples",
+        [setups, calls, report.samples().len()],
+        [2 + 5 * 3, 2 + 5 * 3, 5]
+    );
+
+    let mut stopwatch = Stopwatch::new();
+    let first = stopwatch.lap();
+    let second = stopwatch.lap();
+    test!(
+        harness,
+        "the laps of a stopwatch add up to at most its elapsed time",
+        (
+            stopwatch.laps() == [first, second],
+            first + second <= stopwatch.elapsed()
+        ),
+        (true, true)
+    );
+
+    if harness.is_quiet() {
+        return;
+    }
+    let bench = Bench::new().batch(100);
+    let numbers: Vec<u64> = (0..1000).collect();
+    let results = [
+        (
+            "sum by loop",
+            bench.run(|| {
+                let mut sum = 0u64;
+                for number in black_box(&numbers) {
+                    sum = sum.wrapping_add(*number);
+                }
+                sum
+            }),
+        ),
+        (
+            "sum by iterator",
+            bench.run(|| black_box(&numbers).iter().sum::<u64>()),
+        ),
+        (
+            "sum by formula",
+            bench.run(|| {
+                let count = black_box(numbers.len()) as u64;
+                count * (count - 1) / 2
+            }),
+        ),
+    ];
+    note!(
+        harness,
+        "Summing a thousand numbers:\n{}",
+        compare(&results)
+    );
+}
@@ -0,0 +1,593 @@
+// bencode.rs
+// An encoder and a decoder of bencode, the format of the metainfo files of
+// BitTorrent. There are four kinds of values: integers, written as i42e, byte
+// strings, prefixed with their length, like 4:spam, and lists and
+// dictionaries, which are l and d, then the elements, then e. The keys of the
+// dictionaries are byte strings, and they must be sorted, so that every value
+// has exactly one encoding, which matters, as the identity of a torrent is the
+// SHA-1 hash of the encoded info dictionary. The decoder is strict about this,
+// and also rejects the leading zeros, the negative zero, duplicate keys, and
+// trailing data, with the position of the offending byte, and it limits the
+// depth of nesting, so a malicious file can not overflow the stack. The
+// byte strings are kept as bytes, as some of them, like the hashes of the
+// pieces, are binary. The demonstration decodes a sample metainfo file.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+// The keys of a BTreeMap are kept sorted by their bytes, just as the format
+// requires.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BencodeValue {
+    Integer(i64),
+    Bytes(Vec<u8>),
+    List(Vec<BencodeValue>),
+    Dict(BTreeMap<Vec<u8>, BencodeValue>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnexpectedEnd,
+    UnexpectedByte(u8),
+    InvalidInteger,
+    LeadingZero,
+    NegativeZero,
+    InvalidLength,
+    UnsortedKeys,
+    DuplicateKey,
+    TooDeep,
+    TrailingData,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ErrorKind::UnexpectedByte(byte) => write!(f, "unexpected byte 0x{:02x}", byte),
+            ErrorKind::InvalidInteger => write!(f, "invalid integer"),
+            ErrorKind::LeadingZero => write!(f, "leading zero in a number"),
+            ErrorKind::NegativeZero => write!(f, "negative zero"),
+            ErrorKind::InvalidLength => write!(f, "invalid string length"),
+            ErrorKind::UnsortedKeys => write!(f, "dictionary keys are not sorted"),
+            ErrorKind::DuplicateKey => write!(f, "duplicate dictionary key"),
+            ErrorKind::TooDeep => write!(f, "too deeply nested"),
+            ErrorKind::TrailingData => write!(f, "trailing data after the value"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BencodeError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for BencodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at position {}", self.kind, self.position)
+    }
+}
+
+impl std::error::Error for BencodeError {}
+
+pub const MAX_DEPTH: usize = 64;
+
+struct Decoder<'a> {
+    input: &'a [u8],
+    position: usize,
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn error(&self, kind: ErrorKind) -> BencodeError {
+        BencodeError {
+            kind,
+            position: self.position,
+        }
+    }
+
+    fn peek(&self) -> Result<u8, BencodeError> {
+        match self.input.get(self.position) {
+            Some(byte) => Ok(*byte),
+            None => Err(self.error(ErrorKind::UnexpectedEnd)),
+        }
+    }
+
+    // The digits up to the terminator, with an optional minus sign, which
+    // are checked for the canonical form.
+    fn number(&mut self, terminator: u8, signed: bool) -> Result<i64, BencodeError> {
+        let start = self.position;
+        let negative = signed && self.peek()? == b'-';
+        if negative {
+            self.position += 1;
+        }
+        let digits_start = self.position;
+        while self.peek()?.is_ascii_digit() {
+            self.position += 1;
+        }
+        let digits = &self.input[digits_start..self.position];
+        let kind = if signed {
+            ErrorKind::InvalidInteger
+        } else {
+            ErrorKind::InvalidLength
+        };
+        let byte = self.peek()?;
+        if byte != terminator {
+            return Err(self.error(ErrorKind::UnexpectedByte(byte)));
+        }
+        if digits.is_empty() {
+            return Err(self.error(kind));
+        }
+        if digits.len() > 1 && digits[0] == b'0' {
+            return Err(BencodeError {
+                kind: ErrorKind::LeadingZero,
+                position: digits_start,
+            });
+        }
+        if negative && digits == b"0" {
+            return Err(BencodeError {
+                kind: ErrorKind::NegativeZero,
+                position: start,
+            });
+        }
+        // The digits are ASCII, and the minus sign is right before them.
+        let text = std::str::from_utf8(&self.input[start..self.position]).unwrap();
+        let value = text.parse::<i64>().map_err(|_| BencodeError {
+            kind,
+            position: start,
+        })?;
+        self.position += 1;
+        Ok(value)
+    }
+
+    fn bytes(&mut self) -> Result<Vec<u8>, BencodeError> {
+        let start = self.position;
+        let length = self.number(b':', false)? as usize;
+        if self.input.len() - self.position < length {
+            return Err(BencodeError {
+                kind: ErrorKind::UnexpectedEnd,
+                position: start,
+            });
+        }
+        let bytes = self.input[self.position..self.position + length].to_vec();
+        self.position += length;
+        Ok(bytes)
+    }
+
+    fn value(&mut self) -> Result<BencodeValue, BencodeError> {
+        match self.peek()? {
+            b'i' => {
+                self.position += 1;
+                self.number(b'e', true).map(BencodeValue::Integer)
+            }
+            b'0'..=b'9' => self.bytes().map(BencodeValue::Bytes),
+            b'l' | b'd' => {
+                self.depth += 1;
+                if self.depth > MAX_DEPTH {
+                    return Err(self.error(ErrorKind::TooDeep));
+                }
+                let value = if self.peek()? == b'l' {
+                    self.list()?
+                } else {
+                    self.dict()?
+                };
+                self.depth -= 1;
+                Ok(value)
+            }
+            byte => Err(self.error(ErrorKind::UnexpectedByte(byte))),
+        }
+    }
+
+    fn list(&mut self) -> Result<BencodeValue, BencodeError> {
+        self.position += 1;
+        let mut elements = Vec::new();
+        while self.peek()? != b'e' {
+            elements.push(self.value()?);
+        }
+        self.position += 1;
+        Ok(BencodeValue::List(elements))
+    }
+
+    fn dict(&mut self) -> Result<BencodeValue, BencodeError> {
+        self.position += 1;
+        let mut entries = BTreeMap::new();
+        let mut previous: Option<Vec<u8>> = None;
+        while self.peek()? != b'e' {
+            let position = self.position;
+            let byte = self.peek()?;
+            if !byte.is_ascii_digit() {
+                return Err(self.error(ErrorKind::UnexpectedByte(byte)));
+            }
+            let key = self.bytes()?;
+            if let Some(previous) = &previous {
+                let kind = match key.cmp(previous) {
+                    std::cmp::Ordering::Less => Some(ErrorKind::UnsortedKeys),
+                    std::cmp::Ordering::Equal => Some(ErrorKind::DuplicateKey),
+                    std::cmp::Ordering::Greater => None,
+                };
+                if let Some(kind) = kind {
+                    return Err(BencodeError { kind, position });
+                }
+            }
+            let value = self.value()?;
+            previous = Some(key.clone());
+            entries.insert(key, value);
+        }
+        self.position += 1;
+        Ok(BencodeValue::Dict(entries))
+    }
+}
+
+pub fn decode(input: &[u8]) -> Result<BencodeValue, BencodeError> {
+    let mut decoder = Decoder {
+        input,
+        position: 0,
+        depth: 0,
+    };
+    let value = decoder.value()?;
+    if decoder.position != input.len() {
+        return Err(decoder.error(ErrorKind::TrailingData));
+    }
+    Ok(value)
+}
+
+impl BencodeValue {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output = Vec::new();
+        self.encode_into(&mut output);
+        output
+    }
+
+    pub fn encode_into(&self, output: &mut Vec<u8>) {
+        match self {
+            BencodeValue::Integer(value) => output.extend(format!("i{}e", value).bytes()),
+            BencodeValue::Bytes(bytes) => {
+                output.extend(format!("{}:", bytes.len()).bytes());
+                output.extend_from_slice(bytes);
+            }
+            BencodeValue::List(elements) => {
+                output.push(b'l');
+                for element in elements {
+                    element.encode_into(output);
+                }
+                output.push(b'e');
+            }
+            BencodeValue::Dict(entries) => {
+                output.push(b'd');
+                for (key, value) in entries {
+                    output.extend(format!("{}:", key.len()).bytes());
+                    output.extend_from_slice(key);
+                    value.encode_into(output);
+                }
+                output.push(b'e');
+            }
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            BencodeValue::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            BencodeValue::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        self.as_bytes()
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+    }
+
+    pub fn as_list(&self) -> Option<&[BencodeValue]> {
+        match self {
+            BencodeValue::List(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    pub fn as_dict(&self) -> Option<&BTreeMap<Vec<u8>, BencodeValue>> {
+        match self {
+            BencodeValue::Dict(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&BencodeValue> {
+        self.as_dict()
+            .and_then(|entries| entries.get(key.as_bytes()))
+    }
+}
+
+impl From<i64> for BencodeValue {
+    fn from(value: i64) -> Self {
+        BencodeValue::Integer(value)
+    }
+}
+
+impl From<&str> for BencodeValue {
+    fn from(value: &str) -> Self {
+        BencodeValue::Bytes(value.as_bytes().to_vec())
+    }
+}
+
+impl From<Vec<BencodeValue>> for BencodeValue {
+    fn from(value: Vec<BencodeValue>) -> Self {
+        BencodeValue::List(value)
+    }
+}
+
+impl<const N: usize> From<[(&str, BencodeValue); N]> for BencodeValue {
+    fn from(entries: [(&str, BencodeValue); N]) -> Self {
+        BencodeValue::Dict(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.as_bytes().to_vec(), value))
+                .collect(),
+        )
+    }
+}
+
+// A readable form, with the text strings quoted, and the binary ones as
+// their length.
+impl fmt::Display for BencodeValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bytes = |f: &mut fmt::Formatter, bytes: &[u8]| match std::str::from_utf8(bytes) {
+            Ok(text) => write!(f, "{:?}", text),
+            Err(_) => write!(f, "<{} bytes>", bytes.len()),
+        };
+        match self {
+            BencodeValue::Integer(value) => write!(f, "{}", value),
+            BencodeValue::Bytes(value) => bytes(f, value),
+            BencodeValue::List(elements) => {
+                write!(f, "[")?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]")
+            }
+            BencodeValue::Dict(entries) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    bytes(f, key)?;
+                    write!(f, ": {}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+// The interesting parts of a single-file metainfo.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Metainfo {
+    pub announce: String,
+    pub name: String,
+    pub length: u64,
+    pub piece_length: u64,
+    pub pieces: Vec<[u8; 20]>,
+    pub info_hash: Vec<u8>,
+}
+
+impl Metainfo {
+    pub fn from_bytes(input: &[u8]) -> Result<Self, String> {
+        let root = decode(input).map_err(|error| error.to_string())?;
+        let info = root.get("info").ok_or("missing info dictionary")?;
+        let text = |value: &BencodeValue, key: &str| {
+            value
+                .get(key)
+                .and_then(BencodeValue::as_str)
+                .map(String::from)
+                .ok_or(format!("missing or invalid {}", key))
+        };
+        let number = |key: &str| {
+            info.get(key)
+                .and_then(BencodeValue::as_integer)
+                .and_then(|value| u64::try_from(value).ok())
+                .ok_or(format!("missing or invalid {}", key))
+        };
+        let pieces = info
+            .get("pieces")
+            .and_then(BencodeValue::as_bytes)
+            .filter(|pieces| pieces.len() % 20 == 0)
+            .ok_or("missing or invalid pieces")?;
+        let metainfo = Self {
+            announce: text(&root, "announce")?,
+            name: text(info, "name")?,
+            length: number("length")?,
+            piece_length: number("piece length")?,
+            pieces: pieces
+                .chunks(20)
+                .map(|chunk| chunk.try_into().unwrap())
+                .collect(),
+            info_hash: crate::sha1::Sha1::digest(&info.encode()),
+        };
+        if metainfo.piece_length == 0
+            || metainfo.length.div_ceil(metainfo.piece_length) != metainfo.pieces.len() as u64
+        {
+            return Err("the pieces do not cover the length".to_string());
+        }
+        Ok(metainfo)
+    }
+}
+
+// Demonstration
+
+use crate::encoding::HEX;
+use crate::harness::Harness;
+
+const SAMPLE: &[u8] = b"\
+d8:announce40:http://tracker.example.com:6969/announce13:announce-list\
+    ll40:http://tracker.example.com:6969/announceel29:udp://backup.example\
+    .org:1337ee7:comment40:A sample torrent for the bencode snippet10:crea\
+    ted by8:snippets13:creation datei1767225600e4:infod6:lengthi600000e4:n\
+    ame17:snippets-2026.tar12:piece lengthi262144e6:pieces60:\xc78\xaf\xa7\
+    H\xfd\xe2.\xa4\xa5\xb7}\xa6\x221\xd1\x08\xa1\x86\xcf\xb7\x97@\xd9\x06\
+    \xcfD1\x0b\x83e>]N\x1f(c%\x0e\xcbX/\xcc2A&{nXf\x1f\xfbm\x83\xeb(Qc\xbb\
+    \x83ee";
+
+pub fn demo(harness: &mut Harness) {
+    let text = |value: &str| BencodeValue::from(value);
+    test!(
+        harness,
+        "the four kinds of values are decoded",
+        [
+            decode(b"i42e"),
+            decode(b"i-7e"),
+            decode(b"4:spam"),
+            decode(b"0:"),
+            decode(b"l4:spami42ee"),
+            decode(b"d3:cow3:moo4:spam4:eggse"),
+        ],
+        [
+            Ok(BencodeValue::Integer(42)),
+            Ok(BencodeValue::Integer(-7)),
+            Ok(text("spam")),
+            Ok(text("")),
+            Ok(BencodeValue::from(vec![
+                text("spam"),
+                BencodeValue::from(42)
+            ])),
+            Ok(BencodeValue::from([
+                ("cow", text("moo")),
+                ("spam", text("eggs"))
+            ])),
+        ]
+    );
+    test!(
+        harness,
+        "the keys are encoded sorted, whatever order they were given in",
+        String::from_utf8(
+            BencodeValue::from([
+                ("zebra", BencodeValue::from(1)),
+                ("apple", BencodeValue::from(vec![])),
+                (
+                    "mango",
+                    BencodeValue::from([("b", text("x")), ("a", text("y"))])
+                ),
+            ])
+            .encode()
+        ),
+        Ok("d5:applele5:mangod1:a1:y1:b1:xe5:zebrai1ee".to_string())
+    );
+    let error = |kind, position| Err(BencodeError { kind, position });
+    test!(
+        harness,
+        "non-canonical and broken inputs are rejected where they go wrong",
+        [
+            decode(b"i03e"),
+            decode(b"i-0e"),
+            decode(b"ie"),
+            decode(b"i12"),
+            decode(b"03:abc"),
+            decode(b"5:abc"),
+            decode(b"d1:b0:1:a0:e"),
+            decode(b"d1:a0:1:a0:e"),
+            decode(b"di1e0:e"),
+            decode(b"i1ei2e"),
+            decode(b"x"),
+            decode(b"i99999999999999999999e"),
+        ],
+        [
+            error(ErrorKind::LeadingZero, 1),
+            error(ErrorKind::NegativeZero, 1),
+            error(ErrorKind::InvalidInteger, 1),
+            error(ErrorKind::UnexpectedEnd, 3),
+            error(ErrorKind::LeadingZero, 0),
+            error(ErrorKind::UnexpectedEnd, 0),
+            error(ErrorKind::UnsortedKeys, 6),
+            error(ErrorKind::DuplicateKey, 6),
+            error(ErrorKind::UnexpectedByte(b'i'), 1),
+            error(ErrorKind::TrailingData, 3),
+            error(ErrorKind::UnexpectedByte(b'x'), 0),
+            error(ErrorKind::InvalidInteger, 1),
+        ]
+    );
+    let nested = [b"l".repeat(MAX_DEPTH + 1), b"e".repeat(MAX_DEPTH + 1)].concat();
+    test!(
+        harness,
+        "the depth of nesting is limited",
+        (
+            decode(&nested[1..nested.len() - 1]).is_ok(),
+            decode(&nested)
+        ),
+        (true, error(ErrorKind::TooDeep, MAX_DEPTH))
+    );
+
+    let root = decode(SAMPLE);
+    test!(
+        harness,
+        "the sample metainfo is decoded and encoded back to the same bytes",
+        root.as_ref().map(BencodeValue::encode),
+        Ok(SAMPLE.to_vec())
+    );
+    let metainfo = Metainfo::from_bytes(SAMPLE);
+    test!(
+        harness,
+        "the sample metainfo describes a single file",
+        metainfo.as_ref().map(|metainfo| {
+            (
+                metainfo.announce.as_str(),
+                metainfo.name.as_str(),
+                metainfo.length,
+                metainfo.piece_length,
+                metainfo.pieces.len(),
+            )
+        }),
+        Ok((
+            "http://tracker.example.com:6969/announce",
+            "snippets-2026.tar",
+            600000,
+            262144,
+            3,
+        ))
+    );
+    test!(
+        harness,
+        "the info hash is the SHA-1 of the encoded info dictionary",
+        metainfo
+            .as_ref()
+            .map(|metainfo| HEX.encode(&metainfo.info_hash)),
+        Ok("26dff9f564575920efae13cad789dbd3c55303ce".to_string())
+    );
+    let mut corrupt = SAMPLE.to_vec();
+    let index = corrupt
+        .windows(7)
+        .position(|window| window == b"600000e")
+        .unwrap();
+    corrupt[index] = b'9';
+    test!(
+        harness,
+        "a metainfo whose pieces do not cover the file is rejected",
+        Metainfo::from_bytes(&corrupt),
+        Err("the pieces do not cover the length".to_string())
+    );
+
+    if let Ok(root) = root {
+        let mut printed = String::new();
+        for (key, value) in root.as_dict().unwrap() {
+            if key != b"info" {
+                printed += &format!("\n  {}: {}", String::from_utf8_lossy(key), value);
+            }
+        }
+        for (key, value) in root.get("info").and_then(BencodeValue::as_dict).unwrap() {
+            printed += &format!("\n  info.{}: {}", String::from_utf8_lossy(key), value);
+        }
+        note!(harness, "The sample metainfo:{}", printed);
+    }
+}
@@ -0,0 +1,571 @@
+// bigint.rs
+// An arbitrary-precision unsigned integer, stored as a vector of 32-bit digits,
+// or limbs, from the least significant one, without leading zero limbs, so that
+// zero is the empty vector. The operations work like on paper, in base 2^32:
+// addition and subtraction carry limb by limb, multiplication is the schoolbook
+// method, and division is binary long division, except for single-limb
+// divisors, which are divided limb by limb, and are used for converting to
+// decimal.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Rem, Shl, Shr, Sub};
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    InvalidDigit { position: usize, character: char },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "the number has no digits"),
+            ParseError::InvalidDigit {
+                position,
+                character,
+            } => write!(f, "invalid digit {:?} at position {}", character, position),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self { limbs: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        Self::from(1u32)
+    }
+
+    fn from_limbs(limbs: Vec<u32>) -> Self {
+        let mut result = Self { limbs };
+        result.normalize();
+        result
+    }
+
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    // The number of significant bits.
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            Some(last) => self.limbs.len() * 32 - last.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        self.limbs
+            .get(index / 32)
+            .is_some_and(|limb| (limb >> (index % 32)) & 1 == 1)
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        if *self < *other {
+            return None;
+        }
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = false;
+        for (i, limb) in self.limbs.iter().enumerate() {
+            let subtrahend = other.limbs.get(i).copied().unwrap_or(0);
+            let (difference, overflow1) = limb.overflowing_sub(subtrahend);
+            let (difference, overflow2) = difference.overflowing_sub(borrow as u32);
+            limbs.push(difference);
+            borrow = overflow1 || overflow2;
+        }
+        Some(Self::from_limbs(limbs))
+    }
+
+    // Divides by a single limb, from the most significant limb down, carrying
+    // the remainder into the next limb, like short division on paper.
+    pub fn div_rem_small(&self, divisor: u32) -> (Self, u32) {
+        assert!(divisor != 0, "division by zero");
+        let mut limbs = vec![0; self.limbs.len()];
+        let mut remainder = 0u64;
+        for (i, limb) in self.limbs.iter().enumerate().rev() {
+            let current = (remainder << 32) | *limb as u64;
+            limbs[i] = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        (Self::from_limbs(limbs), remainder as u32)
+    }
+
+    // Binary long division: the bits of the dividend are brought down one by
+    // one, and the divisor is subtracted whenever it fits.
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "division by zero");
+        if divisor.limbs.len() == 1 {
+            let (quotient, remainder) = self.div_rem_small(divisor.limbs[0]);
+            return (quotient, Self::from(remainder));
+        }
+        if self < divisor {
+            return (Self::zero(), self.clone());
+        }
+
+        let mut quotient = vec![0u32; self.limbs.len()];
+        let mut remainder = Self::zero();
+        for i in (0..self.bits()).rev() {
+            remainder = &remainder << 1;
+            if self.bit(i) {
+                if remainder.limbs.is_empty() {
+                    remainder.limbs.push(1);
+                } else {
+                    remainder.limbs[0] |= 1;
+                }
+            }
+            if let Some(difference) = remainder.checked_sub(divisor) {
+                remainder = difference;
+                quotient[i / 32] |= 1 << (i % 32);
+            }
+        }
+        (Self::from_limbs(quotient), remainder)
+    }
+
+    // Exponentiation by squaring.
+    pub fn pow(&self, exponent: u32) -> Self {
+        let mut result = Self::one();
+        let mut base = self.clone();
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            base = &base * &base;
+            exponent >>= 1;
+        }
+        result
+    }
+
+    pub fn from_str_radix(text: &str, radix: u32) -> Result<Self, ParseError> {
+        assert!((2..=36).contains(&radix), "the radix must be from 2 to 36");
+        if text.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        let mut result = Self::zero();
+        for (position, character) in text.char_indices() {
+            let digit = character.to_digit(radix).ok_or(ParseError::InvalidDigit {
+                position,
+                character,
+            })?;
+            result = result.mul_add_small(radix, digit);
+        }
+        Ok(result)
+    }
+
+    // Computes self * factor + addend, which builds a number digit by digit.
+    fn mul_add_small(&self, factor: u32, addend: u32) -> Self {
+        let mut limbs = Vec::with_capacity(self.limbs.len() + 1);
+        let mut carry = addend as u64;
+        for limb in &self.limbs {
+            let current = *limb as u64 * factor as u64 + carry;
+            limbs.push(current as u32);
+            carry = current >> 32;
+        }
+        limbs.push(carry as u32);
+        Self::from_limbs(limbs)
+    }
+
+    // The number is divided by the largest power of the radix fitting into a
+    // limb, so that most of the work is done on single limbs.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        assert!((2..=36).contains(&radix), "the radix must be from 2 to 36");
+        if self.is_zero() {
+            return String::from("0");
+        }
+
+        let mut chunk = radix;
+        let mut chunk_digits = 1;
+        while let Some(next) = chunk.checked_mul(radix) {
+            chunk = next;
+            chunk_digits += 1;
+        }
+
+        let mut digits = Vec::new();
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            let (quotient, mut remainder) = rest.div_rem_small(chunk);
+            rest = quotient;
+            for _ in 0..chunk_digits {
+                if rest.is_zero() && remainder == 0 {
+                    break;
+                }
+                digits.push(std::char::from_digit(remainder % radix, radix).unwrap_or('?'));
+                remainder /= radix;
+            }
+        }
+        digits.iter().rev().collect()
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(value: u32) -> Self {
+        Self::from_limbs(vec![value])
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        Self::from_limbs(vec![value as u32, (value >> 32) as u32])
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        Self::from_str_radix(text, 10)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(true, "", &self.to_str_radix(10))
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(true, "0x", &self.to_str_radix(16))
+    }
+}
+
+impl fmt::UpperHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(true, "0x", &self.to_str_radix(16).to_uppercase())
+    }
+}
+
+// With no leading zero limbs, a longer number is always greater, and numbers
+// of the same length compare from their most significant limb.
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let length = self.limbs.len().max(other.limbs.len());
+        let mut limbs = Vec::with_capacity(length + 1);
+        let mut carry = 0u64;
+        for i in 0..length {
+            let a = self.limbs.get(i).copied().unwrap_or(0) as u64;
+            let b = other.limbs.get(i).copied().unwrap_or(0) as u64;
+            let sum = a + b + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        limbs.push(carry as u32);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+// Unsigned numbers can not go below zero, so this panics like the primitive
+// unsigned types do in debug builds.
+impl Sub for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: &BigUint) -> BigUint {
+        self.checked_sub(other)
+            .expect("attempt to subtract with overflow")
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, b) in other.limbs.iter().enumerate() {
+                let current = limbs[i + j] as u64 + *a as u64 * *b as u64 + carry;
+                limbs[i + j] = current as u32;
+                carry = current >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Div for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).1
+    }
+}
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shl(self, shift: usize) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+        let (whole, bits) = (shift / 32, shift % 32);
+        let mut limbs = vec![0u32; whole];
+        let mut carry = 0u32;
+        for limb in &self.limbs {
+            if bits == 0 {
+                limbs.push(*limb);
+            } else {
+                limbs.push((limb << bits) | carry);
+                carry = limb >> (32 - bits);
+            }
+        }
+        limbs.push(carry);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shr(self, shift: usize) -> BigUint {
+        let (whole, bits) = (shift / 32, shift % 32);
+        if whole >= self.limbs.len() {
+            return BigUint::zero();
+        }
+        let source = &self.limbs[whole..];
+        let limbs = source
+            .iter()
+            .enumerate()
+            .map(|(i, limb)| {
+                let high = source.get(i + 1).copied().unwrap_or(0);
+                if bits == 0 {
+                    *limb
+                } else {
+                    (limb >> bits) | (high << (32 - bits))
+                }
+            })
+            .collect();
+        BigUint::from_limbs(limbs)
+    }
+}
+
+// The operators on owned values forward to the ones on references.
+macro_rules! forward_owned {
+    ($($trait:ident $method:ident),*) => {
+        $(
+            impl $trait for BigUint {
+                type Output = BigUint;
+
+                fn $method(self, other: BigUint) -> BigUint {
+                    (&self).$method(&other)
+                }
+            }
+        )*
+    };
+}
+
+forward_owned!(Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+// Demonstration
+
+use crate::harness::Harness;
+use crate::rng::{RandomSource, SplitMix64};
+
+fn factorial(n: u32) -> BigUint {
+    (2..=n).fold(BigUint::one(), |product, i| &product * &BigUint::from(i))
+}
+
+fn fibonacci(n: usize) -> BigUint {
+    let (mut a, mut b) = (BigUint::zero(), BigUint::one());
+    for _ in 0..n {
+        let next = &a + &b;
+        a = b;
+        b = next;
+    }
+    a
+}
+
+pub fn demo(harness: &mut Harness) {
+    let max = BigUint::from(u64::MAX);
+    test!(
+        harness,
+        "adding one to the largest u64 carries into a new limb",
+        (&max + &BigUint::one()).to_string(),
+        "18446744073709551616"
+    );
+    test!(
+        harness,
+        "subtracting borrows across limbs",
+        &(&max + &BigUint::one()) - &BigUint::one(),
+        max.clone()
+    );
+    test!(
+        harness,
+        "subtracting a larger number fails",
+        BigUint::one().checked_sub(&max),
+        None
+    );
+    test!(
+        harness,
+        "the square of the largest u64",
+        (&max * &max).to_string(),
+        "340282366920938463426481119284349108225"
+    );
+    test!(
+        harness,
+        "2^128 - 1 in hexadecimal",
+        format!("{:x}", &(&BigUint::one() << 128) - &BigUint::one()),
+        "ffffffffffffffffffffffffffffffff"
+    );
+    test!(
+        harness,
+        "formatting honors the alternate flag and the width",
+        format!("{:#X}|{:>8}", BigUint::from(48879u32), BigUint::from(42u32)),
+        "0xBEEF|      42"
+    );
+    test!(
+        harness,
+        "parsing hexadecimal",
+        BigUint::from_str_radix("DeadBeefCafeBabe0123456789", 16).map(|n| n.to_string()),
+        Ok(String::from("17642423813161689323077271644041"))
+    );
+    test!(
+        harness,
+        "parsing rejects invalid digits",
+        "12x4".parse::<BigUint>(),
+        Err(ParseError::InvalidDigit {
+            position: 2,
+            character: 'x'
+        })
+    );
+    test!(
+        harness,
+        "parsing rejects empty input",
+        "".parse::<BigUint>(),
+        Err(ParseError::Empty)
+    );
+    test!(
+        harness,
+        "zero is printed as a digit",
+        (BigUint::zero().to_string(), BigUint::zero().to_str_radix(2)),
+        (String::from("0"), String::from("0"))
+    );
+    test!(
+        harness,
+        "numbers compare by value",
+        (
+            BigUint::from(1u64 << 40) > BigUint::from(u32::MAX),
+            "1000000000000".parse::<BigUint>().unwrap() < "999999999999".parse().unwrap()
+        ),
+        (true, false)
+    );
+
+    let dividend: BigUint = "123456789012345678901234567890123456789".parse().unwrap();
+    let divisor: BigUint = "987654321987654321".parse().unwrap();
+    let (quotient, remainder) = dividend.div_rem(&divisor);
+    test!(
+        harness,
+        "long division gives the quotient and the remainder",
+        (quotient.to_string(), remainder.to_string()),
+        (
+            String::from("124999998748437501153"),
+            String::from("142745764920524676")
+        )
+    );
+
+    // Division is checked against multiplication on random numbers.
+    let mut random = SplitMix64::new(2026);
+    let mut random_number =
+        |limbs: usize| BigUint::from_limbs((0..limbs).map(|_| random.next_u32()).collect());
+    let mut consistent = true;
+    for i in 0..50 {
+        let a = random_number(1 + i % 7);
+        let b = random_number(1 + i % 4);
+        if b.is_zero() {
+            continue;
+        }
+        let (q, r) = a.div_rem(&b);
+        consistent &= &(&q * &b) + &r == a && r < b;
+        consistent &= (&(&a * &b) / &b) == a;
+        consistent &= &(&a << (i * 3)) >> (i * 3) == a;
+    }
+    test!(
+        harness,
+        "random divisions satisfy a = q * b + r, with r < b",
+        consistent,
+        true
+    );
+
+    let hundred = factorial(100);
+    test!(
+        harness,
+        "100! is correct",
+        hundred.to_string(),
+        "93326215443944152681699238856266700490715968264381621468592963895217599993229\
+         915608941463976156518286253697920827223758251185210916864000000000000000000000000"
+    );
+    test!(
+        harness,
+        "100! divided by 98! is 9900",
+        &hundred / &factorial(98),
+        BigUint::from(9900u32)
+    );
+    note!(harness, "100! = {}", hundred);
+    note!(
+        harness,
+        "100! has {} bits, and {} digits",
+        hundred.bits(),
+        hundred.to_string().len()
+    );
+
+    let fibonacci = fibonacci(1000);
+    test!(
+        harness,
+        "the 1000th Fibonacci number is correct",
+        fibonacci.to_string(),
+        "43466557686937456435688527675040625802564660517371780402481729089536555417949\
+         05189040387984007925516929592259308032263477520968962323987332247116164299644\
+         0906533187938298969649928516003704476137795166849228875"
+    );
+    note!(harness, "F(1000) = {}", fibonacci);
+    note!(harness, "F(1000) = {:#x}\n", fibonacci);
+}
@@ -0,0 +1,3 @@
+fn main() {
+    std::process::exit(snippets::httpclient::main(std::env::args()));
+}
@@ -0,0 +1,3 @@
+fn main() {
+    std::process::exit(snippets::ntp::main(std::env::args()));
+}
@@ -0,0 +1,3 @@
+fn main() {
+    std::process::exit(snippets::portscan::main(std::env::args()));
+}
@@ -0,0 +1,3 @@
+fn main() {
+    std::process::exit(snippets::tcpecho::main(std::env::args()));
+}
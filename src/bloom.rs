@@ -0,0 +1,214 @@
+// bloom.rs
+// A Bloom filter, a probabilistic set, which can tell that an item is surely
+// not in the set, or that it probably is. Each item sets k bits in a bit array
+// of m bits, and an item is reported present if all of its bits are set, which
+// can happen for items never inserted, if other items have set the same bits.
+// The number of bits and hashes are derived from the expected number of items,
+// and the desired false positive rate. Instead of k independent hash functions,
+// the bit positions are taken from two hashes, with the double hashing
+// technique of Kirsch and Mitzenmacher, which gives the same false positive
+// rate.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::hashmap::Fnv1a;
+use std::collections::hash_map::DefaultHasher;
+use std::f64::consts::LN_2;
+use std::hash::{Hash, Hasher};
+
+pub struct BloomFilter {
+    words: Vec<u64>,
+    bits: usize,
+    hashes: usize,
+    items: usize,
+}
+
+impl BloomFilter {
+    // The optimal number of bits is -n ln p / (ln 2)^2, and the optimal number
+    // of hashes for that is m / n ln 2.
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        assert!(
+            false_positive_rate > 0.0 && false_positive_rate < 1.0,
+            "the false positive rate must be between 0 and 1"
+        );
+        let items = expected_items.max(1) as f64;
+        let bits = (-items * false_positive_rate.ln() / (LN_2 * LN_2)).ceil() as usize;
+        let hashes = (bits as f64 / items * LN_2).round() as usize;
+        Self::with_parameters(bits, hashes)
+    }
+
+    pub fn with_parameters(bits: usize, hashes: usize) -> Self {
+        let bits = bits.max(1);
+        Self {
+            words: vec![0; bits.div_ceil(64)],
+            bits,
+            hashes: hashes.max(1),
+            items: 0,
+        }
+    }
+
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    pub fn hashes(&self) -> usize {
+        self.hashes
+    }
+
+    // The number of insertions, which may count the same item more than once.
+    pub fn len(&self) -> usize {
+        self.items
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items == 0
+    }
+
+    // The i-th position is h1 + i * h2, the double hashing of Kirsch and
+    // Mitzenmacher. The h2 is odd, so it is never zero, which would make every
+    // position the same. The sizes are arbitrary, so the positions may still
+    // repeat when h2 shares a factor with the size, which costs some accuracy.
+    fn positions<T: Hash + ?Sized>(&self, item: &T) -> impl Iterator<Item = usize> {
+        let mut first = Fnv1a::new();
+        item.hash(&mut first);
+        let mut second = DefaultHasher::new();
+        item.hash(&mut second);
+
+        let h1 = first.finish();
+        let h2 = second.finish() | 1;
+        let bits = self.bits as u64;
+        (0..self.hashes as u64).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % bits) as usize)
+    }
+
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+        let positions: Vec<usize> = self.positions(item).collect();
+        for position in positions {
+            self.words[position / 64] |= 1 << (position % 64);
+        }
+        self.items += 1;
+    }
+
+    pub fn contains<T: Hash + ?Sized>(&self, item: &T) -> bool {
+        self.positions(item)
+            .all(|position| self.words[position / 64] & (1 << (position % 64)) != 0)
+    }
+
+    pub fn fill_ratio(&self) -> f64 {
+        let set: u32 = self.words.iter().map(|word| word.count_ones()).sum();
+        set as f64 / self.bits as f64
+    }
+
+    // After n insertions, a bit is still unset with a probability of
+    // (1 - 1/m)^kn, which is about e^(-kn/m), and a false positive needs all
+    // k bits to be set.
+    pub fn expected_false_positive_rate(&self) -> f64 {
+        let k = self.hashes as f64;
+        let unset = (-k * self.items as f64 / self.bits as f64).exp();
+        (1.0 - unset).powf(k)
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    let filter = BloomFilter::new(1000, 0.01);
+    test!(
+        harness,
+        "1000 items at 1% need 9586 bits and 7 hashes",
+        (filter.bits(), filter.hashes()),
+        (9586, 7)
+    );
+
+    let mut filter = BloomFilter::new(100, 0.01);
+    test!(
+        harness,
+        "an empty filter contains nothing",
+        filter.contains("apple"),
+        false
+    );
+    for fruit in ["apple", "banana", "cherry"] {
+        filter.insert(fruit);
+    }
+    test!(
+        harness,
+        "inserted items are reported present",
+        ["apple", "banana", "cherry"]
+            .iter()
+            .all(|fruit| filter.contains(fruit)),
+        true
+    );
+    test!(
+        harness,
+        "items of different types can be inserted",
+        {
+            filter.insert(&42u32);
+            (filter.contains(&42u32), filter.len())
+        },
+        (true, 4)
+    );
+
+    const ITEMS: usize = 10000;
+    const QUERIES: usize = 100000;
+    note!(
+        harness,
+        "{:>8} {:>8} {:>6} {:>7} {:>10} {:>10}",
+        "Target",
+        "Bits",
+        "Hashes",
+        "Filled",
+        "Expected",
+        "Measured"
+    );
+    let mut results = Vec::new();
+    for target in [0.1, 0.01, 0.001] {
+        let mut filter = BloomFilter::new(ITEMS, target);
+        for i in 0..ITEMS {
+            filter.insert(&format!("member-{}", i));
+        }
+        let missed = (0..ITEMS)
+            .filter(|i| !filter.contains(&format!("member-{}", i)))
+            .count();
+        let false_positives = (0..QUERIES)
+            .filter(|i| filter.contains(&format!("stranger-{}", i)))
+            .count();
+        let measured = false_positives as f64 / QUERIES as f64;
+        let expected = filter.expected_false_positive_rate();
+        note!(
+            harness,
+            "{:>7.1}% {:>8} {:>6} {:>6.1}% {:>9.3}% {:>9.3}%",
+            target * 100.0,
+            filter.bits(),
+            filter.hashes(),
+            filter.fill_ratio() * 100.0,
+            expected * 100.0,
+            measured * 100.0
+        );
+        results.push((target, missed, measured, expected));
+    }
+    note!(harness);
+
+    for (target, missed, measured, expected) in results {
+        let description = format!("there are no false negatives at {}%", target * 100.0);
+        test!(harness, &description, missed, 0);
+        let description = format!(
+            "the measured false positive rate is close to the expected one at {}%",
+            target * 100.0
+        );
+        test!(
+            harness,
+            &description,
+            (measured - expected).abs() < expected * 0.25,
+            true
+        );
+        let description = format!(
+            "the expected false positive rate does not exceed {}% at capacity",
+            target * 100.0
+        );
+        test!(harness, &description, expected <= target * 1.01, true);
+    }
+}
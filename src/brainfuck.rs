@@ -0,0 +1,696 @@
+// brainfuck.rs
+// An interpreter of Brainfuck, the esoteric language of eight commands, which
+// move a pointer over a tape of cells (< and >), change the current cell (+ and
+// -), read and write it (, and .), and loop while it is not zero ([ and ]). Any
+// other character is a comment. The program is compiled into instructions
+// first, where each bracket knows the position of its pair, so a jump does not
+// need to search for it. The optional optimizer collapses runs of the same
+// command into a single instruction, and replaces the [-] idiom with clearing
+// the cell. The tape grows on demand to the right, and the width of the cells,
+// and whether they wrap around or report an error on overflow, can be set.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnmatchedOpen,
+    UnmatchedClose,
+    CellOverflow,
+    CellUnderflow,
+    TapeUnderflow,
+    StepLimit,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnmatchedOpen => write!(f, "unmatched ["),
+            ErrorKind::UnmatchedClose => write!(f, "unmatched ]"),
+            ErrorKind::CellOverflow => write!(f, "cell overflow"),
+            ErrorKind::CellUnderflow => write!(f, "cell underflow"),
+            ErrorKind::TapeUnderflow => write!(f, "moved left of the first cell"),
+            ErrorKind::StepLimit => write!(f, "step limit reached"),
+        }
+    }
+}
+
+// The position is counted in characters of the source, including comments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at position {}", self.kind, self.position)
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    Increment(u32),
+    Decrement(u32),
+    Right(usize),
+    Left(usize),
+    Output,
+    Input,
+    Clear,
+    // The index of the matching bracket.
+    LoopStart(usize),
+    LoopEnd(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    Wrap,
+    Error,
+}
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub cell_bits: u32,
+    pub overflow: Overflow,
+    pub step_limit: Option<u64>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            cell_bits: 8,
+            overflow: Overflow::Wrap,
+            step_limit: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Execution {
+    pub output: Vec<u8>,
+    pub steps: u64,
+    pub cells: usize,
+}
+
+impl Execution {
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.output).into_owned()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Program {
+    instructions: Vec<Instruction>,
+    // The position of each instruction in the source, for the errors.
+    positions: Vec<usize>,
+}
+
+impl Program {
+    pub fn compile(source: &str, optimize: bool) -> Result<Self, Error> {
+        let commands: Vec<(usize, char)> = source
+            .chars()
+            .enumerate()
+            .filter(|(_, c)| "+-<>.,[]".contains(*c))
+            .collect();
+        let mut instructions = Vec::new();
+        let mut positions = Vec::new();
+        let mut open = Vec::new();
+        let mut index = 0;
+        while index < commands.len() {
+            let (position, c) = commands[index];
+            let mut length = 1;
+            if optimize && "+-<>".contains(c) {
+                while commands.get(index + length).map(|(_, next)| *next) == Some(c) {
+                    length += 1;
+                }
+            }
+            let is_clear = optimize
+                && c == '['
+                && commands.get(index + 1).map(|(_, c)| *c) == Some('-')
+                && commands.get(index + 2).map(|(_, c)| *c) == Some(']');
+            if is_clear {
+                length = 3;
+            }
+            let instruction = match c {
+                _ if is_clear => Instruction::Clear,
+                '+' => Instruction::Increment(length as u32),
+                '-' => Instruction::Decrement(length as u32),
+                '>' => Instruction::Right(length),
+                '<' => Instruction::Left(length),
+                '.' => Instruction::Output,
+                ',' => Instruction::Input,
+                '[' => {
+                    open.push(instructions.len());
+                    Instruction::LoopStart(0)
+                }
+                _ => {
+                    let start = open.pop().ok_or(Error {
+                        kind: ErrorKind::UnmatchedClose,
+                        position,
+                    })?;
+                    instructions[start] = Instruction::LoopStart(instructions.len());
+                    Instruction::LoopEnd(start)
+                }
+            };
+            instructions.push(instruction);
+            positions.push(position);
+            index += length;
+        }
+        if let Some(start) = open.pop() {
+            return Err(Error {
+                kind: ErrorKind::UnmatchedOpen,
+                position: positions[start],
+            });
+        }
+        Ok(Self {
+            instructions,
+            positions,
+        })
+    }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    // Reading past the end of the input leaves the cell unchanged.
+    pub fn run(&self, input: &[u8], config: &Config) -> Result<Execution, Error> {
+        let modulus = 1u64 << config.cell_bits;
+        let mut tape = vec![0u32; 1];
+        let mut pointer = 0;
+        let mut input = input.iter();
+        let mut output = Vec::new();
+        let mut steps = 0;
+        let mut counter = 0;
+        while counter < self.instructions.len() {
+            let error = |kind| Error {
+                kind,
+                position: self.positions[counter],
+            };
+            steps += 1;
+            if config.step_limit.is_some_and(|limit| steps > limit) {
+                return Err(error(ErrorKind::StepLimit));
+            }
+            let cell = tape[pointer] as u64;
+            match self.instructions[counter] {
+                Instruction::Increment(amount) => {
+                    let value = cell + amount as u64;
+                    if value >= modulus && config.overflow == Overflow::Error {
+                        return Err(error(ErrorKind::CellOverflow));
+                    }
+                    tape[pointer] = (value % modulus) as u32;
+                }
+                Instruction::Decrement(amount) => {
+                    let amount = amount as u64;
+                    if amount > cell && config.overflow == Overflow::Error {
+                        return Err(error(ErrorKind::CellUnderflow));
+                    }
+                    tape[pointer] = ((cell + modulus - amount % modulus) % modulus) as u32;
+                }
+                Instruction::Right(distance) => {
+                    pointer += distance;
+                    if pointer >= tape.len() {
+                        tape.resize(pointer + 1, 0);
+                    }
+                }
+                Instruction::Left(distance) => {
+                    if distance > pointer {
+                        return Err(error(ErrorKind::TapeUnderflow));
+                    }
+                    pointer -= distance;
+                }
+                Instruction::Output => output.push(cell as u8),
+                Instruction::Input => {
+                    if let Some(byte) = input.next() {
+                        tape[pointer] = *byte as u32;
+                    }
+                }
+                Instruction::Clear => tape[pointer] = 0,
+                Instruction::LoopStart(end) if cell == 0 => counter = end,
+                Instruction::LoopEnd(start) if cell != 0 => counter = start,
+                Instruction::LoopStart(_) | Instruction::LoopEnd(_) => {}
+            }
+            counter += 1;
+        }
+        Ok(Execution {
+            output,
+            steps,
+            cells: tape.len(),
+        })
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+#[rustfmt::skip]
+const HELLO_WORLD: &str =
+    "++++++++[>++++[>++>+++>+++>+<<<<-]>+>+>->>+[<]<-]>>.>---.+++++++..+++.>>.<-.<.+++.------.--------.>>+.>++.";
+
+// This one relies on the cells wrapping around: it starts from 255, and
+// subtracts 7 until reaching zero, which takes 73 rounds.
+const WRAPPING: &str = "-[------->+<]>-.+.";
+
+const REVERSE: &str = ">,[>,]<[.<]";
+
+// A tiny macro assembler, which tracks the position of the pointer, so that the
+// cells can be addressed by their index.
+struct Generator {
+    code: String,
+    pointer: usize,
+}
+
+impl Generator {
+    fn goto(&mut self, cell: usize) {
+        let (c, distance) = if cell > self.pointer {
+            ('>', cell - self.pointer)
+        } else {
+            ('<', self.pointer - cell)
+        };
+        self.code.extend(std::iter::repeat_n(c, distance));
+        self.pointer = cell;
+    }
+
+    fn add(&mut self, cell: usize, amount: i32) {
+        self.goto(cell);
+        let c = if amount > 0 { '+' } else { '-' };
+        self.code
+            .extend(std::iter::repeat_n(c, amount.unsigned_abs() as usize));
+    }
+
+    fn clear(&mut self, cell: usize) {
+        self.goto(cell);
+        self.code.push_str("[-]");
+    }
+
+    fn set(&mut self, cell: usize, value: i32) {
+        self.clear(cell);
+        self.add(cell, value);
+    }
+
+    fn repeat(&mut self, cell: usize, body: impl FnOnce(&mut Self)) {
+        self.goto(cell);
+        self.code.push('[');
+        body(self);
+        self.goto(cell);
+        self.code.push(']');
+    }
+
+    // Adds the source cell to the targets, multiplied by the factors, and
+    // clears the source.
+    fn transfer(&mut self, source: usize, targets: &[(usize, i32)]) {
+        self.repeat(source, |g| {
+            g.add(source, -1);
+            for (target, factor) in targets {
+                g.add(*target, *factor);
+            }
+        });
+    }
+
+    fn copy(&mut self, source: usize, target: usize, temporary: usize) {
+        self.transfer(source, &[(target, 1), (temporary, 1)]);
+        self.transfer(temporary, &[(source, 1)]);
+    }
+
+    // Consumes the flag, running the body if it was set.
+    fn when(&mut self, flag: usize, body: impl FnOnce(&mut Self)) {
+        self.repeat(flag, |g| {
+            g.clear(flag);
+            body(g);
+        });
+    }
+
+    fn when_else(
+        &mut self,
+        flag: usize,
+        other: usize,
+        then: impl FnOnce(&mut Self),
+        otherwise: impl FnOnce(&mut Self),
+    ) {
+        self.set(other, 1);
+        self.when(flag, |g| {
+            then(g);
+            g.add(other, -1);
+        });
+        self.when(other, otherwise);
+    }
+
+    // Runs the body if the cell is zero, without changing it. The two cells
+    // after it have to be zero, and the pointer ends up on the second one in
+    // both cases.
+    fn when_zero(&mut self, cell: usize, body: impl FnOnce(&mut Self)) {
+        self.add(cell + 1, 1);
+        self.goto(cell);
+        self.code.push_str("[>-]>[");
+        self.pointer = cell + 1;
+        body(self);
+        self.add(cell + 1, -1);
+        self.goto(cell + 2);
+        self.code.push(']');
+    }
+}
+
+// Draws the Mandelbrot set with fixed point arithmetic, where the unit is
+// SCALE. The coordinates are stored with an added OFFSET, so that the cells
+// never go negative, and the squares are computed from the magnitudes.
+fn mandelbrot(rows: i32, columns: i32, iterations: i32) -> String {
+    const SCALE: i32 = 16;
+    const OFFSET: i32 = 128;
+    #[rustfmt::skip]
+    let [row, column, cx, cy, x, y, iteration, outside, escaped, other,
+         mx, my, sx, sy, negative, counter, flag, square_x, square_y, product,
+         double, sum, small, temporary, character, work] = std::array::from_fn(|i| i);
+    let mut g = Generator {
+        code: String::new(),
+        pointer: 0,
+    };
+
+    // Splits a coordinate into its magnitude and its sign, counting down the
+    // offset, and checking whether the coordinate reaches zero first.
+    let magnitude = |g: &mut Generator, source: usize, target: usize, sign: usize| {
+        g.copy(source, work, temporary);
+        g.set(counter, OFFSET);
+        g.repeat(counter, |g| {
+            g.add(counter, -1);
+            g.set(flag, 1);
+            g.when_zero(work, |g| {
+                g.add(negative, 1);
+                g.set(sign, 1);
+                g.add(flag, -1);
+            });
+            g.when(flag, |g| g.add(work, -1));
+        });
+        g.transfer(negative, &[(work, 1)]);
+        // A magnitude over 2 escapes anyway, so it is capped, which keeps the
+        // multiplications short.
+        g.set(counter, 2 * SCALE + 1);
+        g.repeat(counter, |g| {
+            g.add(counter, -1);
+            g.set(flag, 1);
+            g.when_zero(work, |g| {
+                g.clear(counter);
+                g.add(flag, -1);
+            });
+            g.when(flag, |g| {
+                g.add(work, -1);
+                g.add(target, 1);
+            });
+        });
+        g.clear(work);
+    };
+    let multiply = |g: &mut Generator, a: usize, b: usize| {
+        g.copy(a, counter, temporary);
+        g.repeat(counter, |g| {
+            g.add(counter, -1);
+            g.copy(b, product, temporary);
+        });
+    };
+    let divide = |g: &mut Generator, target: usize, divisor: i32| {
+        g.set(work, divisor);
+        g.repeat(product, |g| {
+            g.add(product, -1);
+            g.add(work, -1);
+            g.when_zero(work, |g| {
+                g.add(target, 1);
+                g.add(work, divisor);
+            });
+        });
+        g.clear(work);
+    };
+
+    g.set(cy, OFFSET - SCALE);
+    g.set(row, rows);
+    g.repeat(row, |g| {
+        g.add(row, -1);
+        g.set(cx, OFFSET - 2 * SCALE - 2);
+        g.set(column, columns);
+        g.repeat(column, |g| {
+            g.add(column, -1);
+            g.set(x, OFFSET);
+            g.set(y, OFFSET);
+            g.clear(outside);
+            g.set(iteration, iterations);
+            g.repeat(iteration, |g| {
+                g.add(iteration, -1);
+                for cell in [mx, my, sx, sy, square_x, square_y, double] {
+                    g.clear(cell);
+                }
+                magnitude(g, x, mx, sx);
+                magnitude(g, y, my, sy);
+                multiply(g, mx, mx);
+                divide(g, square_x, SCALE);
+                multiply(g, my, my);
+                divide(g, square_y, SCALE);
+
+                // The point escapes if the square of its distance exceeds 4.
+                g.copy(square_x, sum, temporary);
+                g.copy(square_y, sum, temporary);
+                g.transfer(sum, &[(work, 1)]);
+                g.clear(small);
+                g.set(counter, 4 * SCALE + 1);
+                g.repeat(counter, |g| {
+                    g.add(counter, -1);
+                    g.set(flag, 1);
+                    g.when_zero(work, |g| {
+                        g.set(small, 1);
+                        g.clear(counter);
+                        g.add(flag, -1);
+                    });
+                    g.when(flag, |g| g.add(work, -1));
+                });
+                g.clear(work);
+                g.set(escaped, 1);
+                g.when(small, |g| g.add(escaped, -1));
+
+                g.when_else(
+                    escaped,
+                    other,
+                    |g| {
+                        g.clear(iteration);
+                        g.set(outside, 1);
+                    },
+                    |g| {
+                        // x = x^2 - y^2 + cx, and y = 2xy + cy.
+                        g.clear(x);
+                        g.copy(cx, x, temporary);
+                        g.transfer(square_x, &[(x, 1)]);
+                        g.transfer(square_y, &[(x, -1)]);
+                        multiply(g, mx, my);
+                        divide(g, double, SCALE / 2);
+                        g.clear(y);
+                        g.copy(cy, y, temporary);
+                        g.when_else(
+                            sy,
+                            other,
+                            |g| {
+                                g.set(temporary, 1);
+                                g.transfer(sx, &[(temporary, -1)]);
+                                g.transfer(temporary, &[(sx, 1)]);
+                            },
+                            |_| {},
+                        );
+                        g.when_else(
+                            sx,
+                            other,
+                            |g| g.transfer(double, &[(y, -1)]),
+                            |g| g.transfer(double, &[(y, 1)]),
+                        );
+                    },
+                );
+            });
+            g.when_else(
+                outside,
+                other,
+                |g| g.set(character, ' ' as i32),
+                |g| g.set(character, '*' as i32),
+            );
+            g.goto(character);
+            g.code.push('.');
+            g.add(cx, 1);
+        });
+        g.set(character, '\n' as i32);
+        g.code.push('.');
+        g.add(cy, 2);
+    });
+    g.code
+}
+
+pub fn demo(harness: &mut Harness) {
+    let run = |source: &str, input: &[u8], optimize: bool, config: &Config| {
+        Program::compile(source, optimize)?.run(input, config)
+    };
+    let bytes = Config::default();
+    let checked = Config {
+        overflow: Overflow::Error,
+        ..Config::default()
+    };
+
+    let plain = run(HELLO_WORLD, b"", false, &bytes).unwrap();
+    let optimized = run(HELLO_WORLD, b"", true, &bytes).unwrap();
+    test!(harness, "Hello World", plain.text(), "Hello World!\n");
+    test!(
+        harness,
+        "the optimized program prints the same",
+        optimized.text(),
+        plain.text()
+    );
+    test!(
+        harness,
+        "the optimized program takes fewer steps",
+        optimized.steps < plain.steps,
+        true
+    );
+    test!(
+        harness,
+        "Hello World does not need wrapping cells",
+        run(HELLO_WORLD, b"", true, &checked).map(|e| e.text()),
+        Ok("Hello World!\n".to_string())
+    );
+    test!(
+        harness,
+        "counting around with wrapping cells",
+        run(WRAPPING, b"", true, &bytes).map(|e| e.text()),
+        Ok("HI".to_string())
+    );
+    test!(
+        harness,
+        "counting around with checked cells",
+        run(WRAPPING, b"", true, &checked).map_err(|e| e.to_string()),
+        Err("cell underflow at position 0".to_string())
+    );
+    let reversed = run(REVERSE, b"stressed", true, &bytes).unwrap();
+    test!(
+        harness,
+        "reversing the input",
+        (reversed.text(), reversed.cells),
+        ("desserts".to_string(), 10)
+    );
+    test!(
+        harness,
+        "a 16-bit cell does not overflow at 256",
+        run(
+            &"+".repeat(300),
+            b"",
+            true,
+            &Config {
+                cell_bits: 16,
+                ..checked.clone()
+            }
+        )
+        .map(|e| e.cells),
+        Ok(1)
+    );
+    test!(
+        harness,
+        "an overflowing byte",
+        run(&"+".repeat(300), b"", true, &checked).map_err(|e| e.to_string()),
+        Err("cell overflow at position 0".to_string())
+    );
+    test!(
+        harness,
+        "the step limit stops an infinite loop",
+        run(
+            "+[]",
+            b"",
+            true,
+            &Config {
+                step_limit: Some(1000),
+                ..Config::default()
+            }
+        )
+        .map_err(|e| e.kind),
+        Err(ErrorKind::StepLimit)
+    );
+    test!(
+        harness,
+        "moving left of the first cell",
+        run("><<", b"", true, &bytes).map_err(|e| e.to_string()),
+        Err("moved left of the first cell at position 1".to_string())
+    );
+    test!(
+        harness,
+        "an unmatched opening bracket",
+        Program::compile("+[[-]", true).map(|_| ()),
+        Err(Error {
+            kind: ErrorKind::UnmatchedOpen,
+            position: 1
+        })
+    );
+    test!(
+        harness,
+        "an unmatched closing bracket",
+        Program::compile("comment ] here", true).map(|_| ()),
+        Err(Error {
+            kind: ErrorKind::UnmatchedClose,
+            position: 8
+        })
+    );
+
+    let source = mandelbrot(17, 44, 12);
+    let wide = Config {
+        cell_bits: 16,
+        overflow: Overflow::Error,
+        step_limit: None,
+    };
+    let picture = run(&source, b"", true, &wide).unwrap();
+    let lines: Vec<String> = picture.text().lines().map(str::to_string).collect();
+    test!(
+        harness,
+        "the Mandelbrot set has the expected size",
+        (lines.len(), lines[0].chars().count()),
+        (17, 44)
+    );
+    test!(
+        harness,
+        "the Mandelbrot set is symmetric",
+        lines.iter().rev().cloned().collect::<Vec<_>>(),
+        lines.clone()
+    );
+    test!(
+        harness,
+        "the real axis is inside the set from -2 to about 0.2",
+        &lines[8][2..38],
+        "*".repeat(36)
+    );
+
+    note!(harness, "{}", picture.text());
+    note!(
+        harness,
+        "{:<18} {:>8} {:>14} {:>12}",
+        "program",
+        "length",
+        "instructions",
+        "steps"
+    );
+    let row = |name: &str, source: &str, program: &Program, execution: &Execution| {
+        format!(
+            "{:<18} {:>8} {:>14} {:>12}",
+            name,
+            source.len(),
+            program.instructions().len(),
+            execution.steps
+        )
+    };
+    for (name, source) in [("Hello World", HELLO_WORLD), ("Wrapping", WRAPPING)] {
+        for optimize in [false, true] {
+            let program = Program::compile(source, optimize).unwrap();
+            let execution = program.run(b"", &bytes).unwrap();
+            let name = if optimize { "  optimized" } else { name };
+            note!(harness, "{}", row(name, source, &program, &execution));
+        }
+    }
+    // The unoptimized Mandelbrot program takes about four times as many
+    // steps, which would make the demonstration slow.
+    let program = Program::compile(&source, true).unwrap();
+    note!(
+        harness,
+        "{}\n",
+        row("Mandelbrot (opt.)", &source, &program, &picture)
+    );
+}
@@ -0,0 +1,483 @@
+// bst.rs
+// A binary search tree with owned, boxed nodes, and an AVL tree, which is the
+// same tree, except that after every insertion and deletion it is rebalanced on
+// the way back up, by rotating the nodes where the heights of the two subtrees
+// differ by more than one. The unbalanced tree degenerates into a linked list,
+// when the keys are inserted in order, while the height of the AVL tree stays
+// logarithmic, so its operations stay fast. Both trees share the node type, and
+// the recursive operations, which only rebalance for the AVL tree.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    // The height of the subtree rooted at this node, a leaf being 1.
+    height: usize,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+impl<K, V> Node<K, V> {
+    fn update_height(&mut self) {
+        self.height = 1 + height(&self.left).max(height(&self.right));
+    }
+
+    fn balance_factor(&self) -> isize {
+        height(&self.left) as isize - height(&self.right) as isize
+    }
+}
+
+fn height<K, V>(link: &Link<K, V>) -> usize {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+//     node            left
+//    /    \          /    \
+//  left    c  ->    a     node
+//  /  \                   /  \
+// a    b                 b    c
+fn rotate_right<K, V>(link: &mut Link<K, V>) {
+    if let Some(mut node) = link.take() {
+        if let Some(mut left) = node.left.take() {
+            node.left = left.right.take();
+            node.update_height();
+            left.right = Some(node);
+            left.update_height();
+            *link = Some(left);
+        } else {
+            *link = Some(node);
+        }
+    }
+}
+
+fn rotate_left<K, V>(link: &mut Link<K, V>) {
+    if let Some(mut node) = link.take() {
+        if let Some(mut right) = node.right.take() {
+            node.right = right.left.take();
+            node.update_height();
+            right.left = Some(node);
+            right.update_height();
+            *link = Some(right);
+        } else {
+            *link = Some(node);
+        }
+    }
+}
+
+// If the left subtree is too high because of its right subtree, a single
+// rotation would only move the problem to the other side, so the left subtree
+// is rotated first, and the same applies to the mirrored case.
+fn rebalance<K, V>(link: &mut Link<K, V>) {
+    let Some(node) = link.as_mut() else {
+        return;
+    };
+    node.update_height();
+    let factor = node.balance_factor();
+    if factor > 1 {
+        if node
+            .left
+            .as_ref()
+            .is_some_and(|left| left.balance_factor() < 0)
+        {
+            rotate_left(&mut node.left);
+        }
+        rotate_right(link);
+    } else if factor < -1 {
+        if node
+            .right
+            .as_ref()
+            .is_some_and(|right| right.balance_factor() > 0)
+        {
+            rotate_right(&mut node.right);
+        }
+        rotate_left(link);
+    }
+}
+
+fn fix<K, V>(link: &mut Link<K, V>, balanced: bool) {
+    if balanced {
+        rebalance(link);
+    } else if let Some(node) = link.as_mut() {
+        node.update_height();
+    }
+}
+
+fn insert<K: Ord, V>(link: &mut Link<K, V>, key: K, value: V, balanced: bool) -> Option<V> {
+    let old = match link {
+        None => {
+            *link = Some(Box::new(Node {
+                key,
+                value,
+                height: 1,
+                left: None,
+                right: None,
+            }));
+            return None;
+        }
+        Some(node) => match key.cmp(&node.key) {
+            Ordering::Less => insert(&mut node.left, key, value, balanced),
+            Ordering::Greater => insert(&mut node.right, key, value, balanced),
+            Ordering::Equal => return Some(std::mem::replace(&mut node.value, value)),
+        },
+    };
+    fix(link, balanced);
+    old
+}
+
+fn remove_min<K, V>(link: &mut Link<K, V>, balanced: bool) -> Option<(K, V)> {
+    let node = link.as_mut()?;
+    if node.left.is_some() {
+        let result = remove_min(&mut node.left, balanced);
+        fix(link, balanced);
+        return result;
+    }
+    let node = link.take()?;
+    *link = node.right;
+    Some((node.key, node.value))
+}
+
+// A node with two children is replaced by its successor, the smallest node
+// of its right subtree, which has no left child, so it is easy to remove.
+fn remove<K: Ord, V>(link: &mut Link<K, V>, key: &K, balanced: bool) -> Option<V> {
+    let node = link.as_mut()?;
+    let result = match key.cmp(&node.key) {
+        Ordering::Less => remove(&mut node.left, key, balanced),
+        Ordering::Greater => remove(&mut node.right, key, balanced),
+        Ordering::Equal => {
+            let node = link.take()?;
+            let Node {
+                value, left, right, ..
+            } = *node;
+            *link = match (left, right) {
+                (None, right) => right,
+                (left, None) => left,
+                (left, right) => {
+                    let mut right = right;
+                    remove_min(&mut right, balanced).map(|(key, value)| {
+                        Box::new(Node {
+                            key,
+                            value,
+                            height: 0,
+                            left,
+                            right,
+                        })
+                    })
+                }
+            };
+            Some(value)
+        }
+    };
+    fix(link, balanced);
+    result
+}
+
+fn render<K: Display, V>(link: &Link<K, V>, prefix: &str, last: bool, output: &mut String) {
+    let branch = if last { "└── " } else { "├── " };
+    match link {
+        None => output.push_str(&format!("{}{}·\n", prefix, branch)),
+        Some(node) => {
+            output.push_str(&format!("{}{}{}\n", prefix, branch, node.key));
+            if node.left.is_some() || node.right.is_some() {
+                let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+                render(&node.left, &prefix, false, output);
+                render(&node.right, &prefix, true, output);
+            }
+        }
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    stack: Vec<&'a Node<K, V>>,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    fn new(root: &'a Link<K, V>) -> Self {
+        let mut iterator = Self { stack: Vec::new() };
+        iterator.push_left_spine(root);
+        iterator
+    }
+
+    fn push_left_spine(&mut self, mut link: &'a Link<K, V>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = &node.left;
+        }
+    }
+}
+
+// The stack holds the nodes whose left subtrees are being visited, so the top
+// of the stack is always the next node in order.
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left_spine(&node.right);
+        Some((&node.key, &node.value))
+    }
+}
+
+macro_rules! search_tree {
+    ($name:ident, $balanced:expr) => {
+        pub struct $name<K, V> {
+            root: Link<K, V>,
+            length: usize,
+        }
+
+        impl<K: Ord, V> $name<K, V> {
+            pub fn new() -> Self {
+                Self {
+                    root: None,
+                    length: 0,
+                }
+            }
+
+            pub fn len(&self) -> usize {
+                self.length
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.length == 0
+            }
+
+            pub fn height(&self) -> usize {
+                height(&self.root)
+            }
+
+            // Returns the previous value, if the key was already present.
+            pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+                let old = insert(&mut self.root, key, value, $balanced);
+                if old.is_none() {
+                    self.length += 1;
+                }
+                old
+            }
+
+            pub fn get(&self, key: &K) -> Option<&V> {
+                let mut link = &self.root;
+                while let Some(node) = link {
+                    link = match key.cmp(&node.key) {
+                        Ordering::Less => &node.left,
+                        Ordering::Greater => &node.right,
+                        Ordering::Equal => return Some(&node.value),
+                    };
+                }
+                None
+            }
+
+            pub fn contains_key(&self, key: &K) -> bool {
+                self.get(key).is_some()
+            }
+
+            pub fn remove(&mut self, key: &K) -> Option<V> {
+                let removed = remove(&mut self.root, key, $balanced);
+                if removed.is_some() {
+                    self.length -= 1;
+                }
+                removed
+            }
+
+            pub fn iter(&self) -> Iter<'_, K, V> {
+                Iter::new(&self.root)
+            }
+
+            // The children of each node are listed below it, the left one
+            // first, and a missing child is shown as a dot.
+            pub fn render(&self) -> String
+            where
+                K: Display,
+            {
+                let mut output = String::new();
+                if self.root.is_some() {
+                    render(&self.root, "", true, &mut output);
+                }
+                output
+            }
+        }
+
+        impl<K: Ord, V> Default for $name<K, V> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<K: Ord, V> FromIterator<(K, V)> for $name<K, V> {
+            fn from_iter<I: IntoIterator<Item = (K, V)>>(items: I) -> Self {
+                let mut tree = Self::new();
+                for (key, value) in items {
+                    tree.insert(key, value);
+                }
+                tree
+            }
+        }
+    };
+}
+
+search_tree!(Bst, false);
+search_tree!(AvlTree, true);
+
+// Demonstration
+
+use crate::harness::Harness;
+use crate::rng::{RandomSource, SplitMix64};
+use std::collections::BTreeMap;
+
+// Checks the search tree property, the stored heights, and the AVL balance.
+fn is_valid<K: Ord, V>(link: &Link<K, V>, low: Option<&K>, high: Option<&K>, avl: bool) -> bool {
+    let Some(node) = link else {
+        return true;
+    };
+    low.is_none_or(|low| *low < node.key)
+        && high.is_none_or(|high| node.key < *high)
+        && node.height == 1 + height(&node.left).max(height(&node.right))
+        && (!avl || node.balance_factor().abs() <= 1)
+        && is_valid(&node.left, low, Some(&node.key), avl)
+        && is_valid(&node.right, Some(&node.key), high, avl)
+}
+
+pub fn demo(harness: &mut Harness) {
+    let mut tree = Bst::new();
+    for key in [5, 3, 8, 1, 4, 7, 9, 2, 6] {
+        tree.insert(key, key * 10);
+    }
+    test!(harness, "the tree counts its elements", tree.len(), 9);
+    test!(harness, "a present key is found", tree.get(&4), Some(&40));
+    test!(harness, "a missing key is not found", tree.get(&10), None);
+    test!(
+        harness,
+        "inserting an existing key replaces its value",
+        (tree.insert(4, 44), tree.get(&4), tree.len()),
+        (Some(40), Some(&44), 9)
+    );
+    test!(
+        harness,
+        "the in-order iterator visits the keys in ascending order",
+        tree.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+        (1..=9).collect::<Vec<_>>()
+    );
+    note!(
+        harness,
+        "Binary search tree of 5, 3, 8, 1, 4, 7, 9, 2, 6:\n{}",
+        tree.render()
+    );
+
+    test!(harness, "removing a leaf", tree.remove(&2), Some(20));
+    test!(
+        harness,
+        "removing a node with one child",
+        tree.remove(&1),
+        Some(10)
+    );
+    test!(
+        harness,
+        "removing a node with two children",
+        tree.remove(&5),
+        Some(50)
+    );
+    test!(harness, "removing a missing key", tree.remove(&5), None);
+    test!(
+        harness,
+        "the remaining keys are still in order",
+        tree.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+        vec![3, 4, 6, 7, 8, 9]
+    );
+    test!(
+        harness,
+        "the tree is still a valid search tree",
+        is_valid(&tree.root, None, None, false),
+        true
+    );
+    note!(harness, "After removing 2, 1, and 5:\n{}", tree.render());
+
+    let sorted: Bst<u32, ()> = (1..=7).map(|key| (key, ())).collect();
+    let balanced: AvlTree<u32, ()> = (1..=7).map(|key| (key, ())).collect();
+    note!(
+        harness,
+        "Binary search tree of 1 to 7, inserted in order:\n{}",
+        sorted.render()
+    );
+    note!(
+        harness,
+        "AVL tree of 1 to 7, inserted in order:\n{}",
+        balanced.render()
+    );
+    test!(
+        harness,
+        "keys inserted in order degenerate the binary search tree",
+        sorted.height(),
+        7
+    );
+    test!(
+        harness,
+        "keys inserted in order are balanced in the AVL tree",
+        balanced.height(),
+        3
+    );
+
+    let many: AvlTree<u32, ()> = (0..1000).map(|key| (key, ())).collect();
+    test!(
+        harness,
+        "the AVL tree of 1000 sorted keys has logarithmic height",
+        many.height() <= 11,
+        true
+    );
+
+    // Random operations, compared to the ordered map of the standard library.
+    let mut random = SplitMix64::new(2026);
+    let mut avl = AvlTree::new();
+    let mut bst = Bst::new();
+    let mut reference = BTreeMap::new();
+    let mut agrees = true;
+    let mut valid = true;
+    for _ in 0..2000 {
+        let key = random.next_below(200);
+        if random.next_below(3) == 0 {
+            let expected = reference.remove(&key);
+            agrees &= avl.remove(&key) == expected && bst.remove(&key) == expected;
+        } else {
+            let expected = reference.insert(key, key);
+            agrees &= avl.insert(key, key) == expected && bst.insert(key, key) == expected;
+        }
+        valid &= is_valid(&avl.root, None, None, true) && is_valid(&bst.root, None, None, false);
+    }
+    test!(
+        harness,
+        "random insertions and removals agree with BTreeMap",
+        agrees,
+        true
+    );
+    test!(
+        harness,
+        "the trees stay valid, and the AVL tree stays balanced",
+        valid,
+        true
+    );
+    test!(
+        harness,
+        "the iterators agree with BTreeMap",
+        (
+            avl.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+            bst.iter().map(|(key, _)| *key).collect::<Vec<_>>()
+        ),
+        (
+            reference.keys().copied().collect(),
+            reference.keys().copied().collect()
+        )
+    );
+    note!(
+        harness,
+        "After random operations, {} keys: the AVL tree has height {}, the unbalanced one {}\n",
+        avl.len(),
+        avl.height(),
+        bst.height()
+    );
+}
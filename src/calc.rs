@@ -0,0 +1,487 @@
+// calc.rs
+// An evaluator of arithmetic expressions, using Dijkstra's shunting-yard
+// algorithm. The expression is split into tokens first, which are then
+// reordered into reverse Polish notation: operands go to the output directly,
+// while operators wait on a stack until an operator of lower precedence, or a
+// closing parenthesis arrives. The result is evaluated with a stack of numbers.
+// Operators have the usual precedence, and exponentiation is right-associative,
+// binding tighter than the unary minus, so -2^2 is -4. Variables can be
+// assigned (x = 3), and there are built-in functions like sqrt, sin, or max.
+// Each error points at the position in the expression where it was found.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ErrorKind {
+    UnexpectedCharacter(char),
+    InvalidNumber,
+    UnexpectedToken,
+    MissingOperand,
+    UnbalancedParenthesis,
+    UnknownVariable(String),
+    UnknownFunction(String),
+    WrongArgumentCount {
+        function: String,
+        expected: usize,
+        got: usize,
+    },
+    DivisionByZero,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character {:?}", c),
+            ErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ErrorKind::UnexpectedToken => write!(f, "unexpected token"),
+            ErrorKind::MissingOperand => write!(f, "missing operand"),
+            ErrorKind::UnbalancedParenthesis => write!(f, "unbalanced parenthesis"),
+            ErrorKind::UnknownVariable(name) => write!(f, "unknown variable {}", name),
+            ErrorKind::UnknownFunction(name) => write!(f, "unknown function {}", name),
+            ErrorKind::WrongArgumentCount {
+                function,
+                expected,
+                got,
+            } => write!(
+                f,
+                "{} takes {} argument{}, but got {}",
+                function,
+                expected,
+                if *expected == 1 { "" } else { "s" },
+                got
+            ),
+            ErrorKind::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+// The position is counted in characters of the expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CalcError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl CalcError {
+    // The expression, with a caret under the position of the error.
+    pub fn render(&self, expression: &str) -> String {
+        format!(
+            "{}\n{}^ {}",
+            expression,
+            " ".repeat(self.position),
+            self.kind
+        )
+    }
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at position {}", self.kind, self.position)
+    }
+}
+
+impl std::error::Error for CalcError {}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Identifier(String),
+    Operator(char),
+    Open,
+    Close,
+    Comma,
+}
+
+fn tokenize(expression: &str) -> Result<Vec<(Token, usize)>, CalcError> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut position = 0;
+    while position < chars.len() {
+        let start = position;
+        let c = chars[position];
+        position += 1;
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '0'..='9' | '.' => {
+                // Digits, a fraction, and an exponent, which the standard
+                // parser validates.
+                while position < chars.len() {
+                    let c = chars[position];
+                    let exponent_sign =
+                        (c == '+' || c == '-') && matches!(chars[position - 1], 'e' | 'E');
+                    if !(c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || exponent_sign) {
+                        break;
+                    }
+                    position += 1;
+                }
+                let text: String = chars[start..position].iter().collect();
+                let value = text.parse().map_err(|_| CalcError {
+                    kind: ErrorKind::InvalidNumber,
+                    position: start,
+                })?;
+                Token::Number(value)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while position < chars.len()
+                    && (chars[position].is_alphanumeric() || chars[position] == '_')
+                {
+                    position += 1;
+                }
+                Token::Identifier(chars[start..position].iter().collect())
+            }
+            '+' | '-' | '*' | '/' | '%' | '^' | '=' => Token::Operator(c),
+            '(' => Token::Open,
+            ')' => Token::Close,
+            ',' => Token::Comma,
+            c => {
+                return Err(CalcError {
+                    kind: ErrorKind::UnexpectedCharacter(c),
+                    position: start,
+                })
+            }
+        };
+        tokens.push((token, start));
+    }
+    Ok(tokens)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Item {
+    Number(f64),
+    Variable(String),
+    Negate,
+    Binary(char),
+    Call(String, usize),
+}
+
+// The entries of the operator stack.
+#[derive(Clone, Debug, PartialEq)]
+enum Pending {
+    Negate,
+    Binary(char),
+    Open,
+    Function(String),
+}
+
+// The unary minus sits between the multiplicative operators and the power.
+fn precedence(pending: &Pending) -> u8 {
+    match pending {
+        Pending::Binary('+' | '-') => 1,
+        Pending::Binary('^') => 4,
+        Pending::Negate => 3,
+        Pending::Binary(_) => 2,
+        _ => 0,
+    }
+}
+
+fn to_postfix(tokens: &[(Token, usize)], end: usize) -> Result<Vec<(Item, usize)>, CalcError> {
+    let mut output = Vec::new();
+    let mut stack: Vec<(Pending, usize)> = Vec::new();
+    // The argument counts of the open function calls, and of the groups,
+    // which are only allowed one.
+    let mut arguments: Vec<usize> = Vec::new();
+    let mut expect_operand = true;
+    let error = |kind, position| CalcError { kind, position };
+
+    let pop = |stack: &mut Vec<(Pending, usize)>, output: &mut Vec<(Item, usize)>| {
+        let (pending, position) = stack.pop().unwrap();
+        let item = match pending {
+            Pending::Negate => Item::Negate,
+            Pending::Binary(operator) => Item::Binary(operator),
+            _ => unreachable!(),
+        };
+        output.push((item, position));
+    };
+
+    for (index, (token, position)) in tokens.iter().enumerate() {
+        let position = *position;
+        match (token, expect_operand) {
+            (Token::Number(value), true) => {
+                output.push((Item::Number(*value), position));
+                expect_operand = false;
+            }
+            (Token::Identifier(name), true) => {
+                if tokens.get(index + 1).map(|(token, _)| token) == Some(&Token::Open) {
+                    stack.push((Pending::Function(name.clone()), position));
+                } else {
+                    output.push((Item::Variable(name.clone()), position));
+                    expect_operand = false;
+                }
+            }
+            (Token::Operator('-'), true) => stack.push((Pending::Negate, position)),
+            (Token::Operator('+'), true) => {}
+            (Token::Operator(operator), false) if *operator != '=' => {
+                let current = Pending::Binary(*operator);
+                while let Some((top, _)) = stack.last() {
+                    let higher = precedence(top) > precedence(&current);
+                    let left = precedence(top) == precedence(&current) && *operator != '^';
+                    if !(higher || left) || precedence(top) == 0 {
+                        break;
+                    }
+                    pop(&mut stack, &mut output);
+                }
+                stack.push((current, position));
+                expect_operand = true;
+            }
+            (Token::Open, true) => {
+                stack.push((Pending::Open, position));
+                arguments.push(0);
+            }
+            (Token::Close, true)
+                if index > 0
+                    && tokens[index - 1].0 == Token::Open
+                    && stack.len() >= 2
+                    && matches!(stack[stack.len() - 2].0, Pending::Function(_)) =>
+            {
+                // An empty argument list.
+                stack.pop();
+                arguments.pop();
+                let (function, position) = stack.pop().unwrap();
+                let Pending::Function(name) = function else {
+                    unreachable!()
+                };
+                output.push((Item::Call(name, 0), position));
+                expect_operand = false;
+            }
+            (Token::Comma | Token::Close, false) => {
+                while let Some((top, _)) = stack.last() {
+                    if *top == Pending::Open {
+                        break;
+                    }
+                    pop(&mut stack, &mut output);
+                }
+                if stack.is_empty() {
+                    return Err(error(ErrorKind::UnbalancedParenthesis, position));
+                }
+                let count = arguments.last_mut().unwrap();
+                *count += 1;
+                let is_call =
+                    stack.len() >= 2 && matches!(stack[stack.len() - 2].0, Pending::Function(_));
+                if *token == Token::Comma {
+                    if !is_call {
+                        return Err(error(ErrorKind::UnexpectedToken, position));
+                    }
+                    expect_operand = true;
+                    continue;
+                }
+                let count = arguments.pop().unwrap();
+                stack.pop();
+                if is_call {
+                    let (function, position) = stack.pop().unwrap();
+                    let Pending::Function(name) = function else {
+                        unreachable!()
+                    };
+                    output.push((Item::Call(name, count), position));
+                }
+            }
+            (Token::Comma | Token::Close | Token::Operator(_), true) => {
+                return Err(error(ErrorKind::MissingOperand, position));
+            }
+            _ => return Err(error(ErrorKind::UnexpectedToken, position)),
+        }
+    }
+
+    if expect_operand {
+        return Err(error(ErrorKind::MissingOperand, end));
+    }
+    while let Some((top, position)) = stack.last() {
+        if *top == Pending::Open {
+            return Err(error(ErrorKind::UnbalancedParenthesis, *position));
+        }
+        pop(&mut stack, &mut output);
+    }
+    Ok(output)
+}
+
+type Function = (&'static str, usize, fn(&[f64]) -> f64);
+
+#[rustfmt::skip]
+const FUNCTIONS: [Function; 13] = [
+    ("sqrt", 1, |a| a[0].sqrt()),
+    ("abs", 1, |a| a[0].abs()),
+    ("sin", 1, |a| a[0].sin()),
+    ("cos", 1, |a| a[0].cos()),
+    ("tan", 1, |a| a[0].tan()),
+    ("exp", 1, |a| a[0].exp()),
+    ("ln", 1, |a| a[0].ln()),
+    ("log", 2, |a| a[1].log(a[0])),
+    ("floor", 1, |a| a[0].floor()),
+    ("ceil", 1, |a| a[0].ceil()),
+    ("min", 2, |a| a[0].min(a[1])),
+    ("max", 2, |a| a[0].max(a[1])),
+    ("hypot", 2, |a| a[0].hypot(a[1])),
+];
+
+pub struct Calculator {
+    variables: BTreeMap<String, f64>,
+}
+
+impl Calculator {
+    pub fn new() -> Self {
+        let mut variables = BTreeMap::new();
+        variables.insert("pi".to_string(), std::f64::consts::PI);
+        variables.insert("e".to_string(), std::f64::consts::E);
+        Self { variables }
+    }
+
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.variables.get(name).copied()
+    }
+
+    pub fn set(&mut self, name: &str, value: f64) {
+        self.variables.insert(name.to_string(), value);
+    }
+
+    // An assignment stores the value, and evaluates to it as well.
+    pub fn evaluate(&mut self, expression: &str) -> Result<f64, CalcError> {
+        let tokens = tokenize(expression)?;
+        let end = expression.chars().count();
+        if let [(Token::Identifier(name), _), (Token::Operator('='), _), rest @ ..] =
+            tokens.as_slice()
+        {
+            let value = self.evaluate_tokens(rest, end)?;
+            self.set(name, value);
+            return Ok(value);
+        }
+        self.evaluate_tokens(&tokens, end)
+    }
+
+    fn evaluate_tokens(&self, tokens: &[(Token, usize)], end: usize) -> Result<f64, CalcError> {
+        let mut stack: Vec<f64> = Vec::new();
+        for (item, position) in to_postfix(tokens, end)? {
+            let error = |kind| CalcError { kind, position };
+            let value = match item {
+                Item::Number(value) => value,
+                Item::Variable(name) => self
+                    .get(&name)
+                    .ok_or_else(|| error(ErrorKind::UnknownVariable(name)))?,
+                Item::Negate => -stack.pop().unwrap(),
+                Item::Binary(operator) => {
+                    let right = stack.pop().unwrap();
+                    let left = stack.pop().unwrap();
+                    match operator {
+                        '+' => left + right,
+                        '-' => left - right,
+                        '*' => left * right,
+                        '/' | '%' if right == 0.0 => return Err(error(ErrorKind::DivisionByZero)),
+                        '/' => left / right,
+                        '%' => left % right,
+                        '^' => left.powf(right),
+                        _ => unreachable!(),
+                    }
+                }
+                Item::Call(name, count) => {
+                    let (_, expected, function) = FUNCTIONS
+                        .iter()
+                        .find(|(function, _, _)| *function == name)
+                        .ok_or_else(|| error(ErrorKind::UnknownFunction(name.clone())))?;
+                    if count != *expected {
+                        return Err(error(ErrorKind::WrongArgumentCount {
+                            function: name,
+                            expected: *expected,
+                            got: count,
+                        }));
+                    }
+                    let arguments = stack.split_off(stack.len() - count);
+                    function(&arguments)
+                }
+            };
+            stack.push(value);
+        }
+        Ok(stack.pop().unwrap())
+    }
+}
+
+impl Default for Calculator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    let mut calculator = Calculator::new();
+    #[rustfmt::skip]
+    let expressions: [(&str, f64); 18] = [
+        ("1 + 2 * 3", 7.0),
+        ("(1 + 2) * 3", 9.0),
+        ("10 - 4 - 3", 3.0),
+        ("2 ^ 3 ^ 2", 512.0),
+        ("-2 ^ 2", -4.0),
+        ("(-2) ^ 2", 4.0),
+        ("2 * -3", -6.0),
+        ("--3", 3.0),
+        ("7 % 4 + 1.5e1", 18.0),
+        ("sqrt(16) + abs(-2)", 6.0),
+        ("max(3, min(10, 4)) * 2", 8.0),
+        ("log(2, 1024)", 10.0),
+        ("hypot(3, 4)", 5.0),
+        ("floor(pi * 100) + 0.5", 314.5),
+        ("sin(pi / 2) + cos(0)", 2.0),
+        ("ln(e ^ 3)", 3.0),
+        ("x = 2 ^ 10", 1024.0),
+        ("x / 4 + 1", 257.0),
+    ];
+    let mut rows = Vec::new();
+    for (expression, expected) in expressions {
+        let result = calculator.evaluate(expression);
+        test!(
+            harness,
+            &format!("{} = {}", expression, expected),
+            result.clone().map(|value| (value - expected).abs() < 1e-12),
+            Ok(true)
+        );
+        rows.push((
+            expression,
+            result.map_or_else(|e| e.to_string(), |v| v.to_string()),
+        ));
+    }
+
+    #[rustfmt::skip]
+    let broken: [(&str, &str); 10] = [
+        ("1 +", "missing operand at position 3"),
+        ("(1 + 2", "unbalanced parenthesis at position 0"),
+        ("1 + 2)", "unbalanced parenthesis at position 5"),
+        ("2 * * 3", "missing operand at position 4"),
+        ("2 3", "unexpected token at position 2"),
+        ("4 $ 2", "unexpected character '$' at position 2"),
+        ("1.2.3 + 1", "invalid number at position 0"),
+        ("y * 2", "unknown variable y at position 0"),
+        ("max(1)", "max takes 2 arguments, but got 1 at position 0"),
+        ("1 / (2 - 2)", "division by zero at position 2"),
+    ];
+    let mut errors = Vec::new();
+    for (expression, expected) in broken {
+        let error = calculator.evaluate(expression).unwrap_err();
+        test!(
+            harness,
+            &format!("the error in {:?}", expression),
+            error.to_string(),
+            expected
+        );
+        errors.push(error.render(expression));
+    }
+    test!(
+        harness,
+        "an unknown function",
+        calculator.evaluate("foo(1)").map_err(|error| error.kind),
+        Err(ErrorKind::UnknownFunction("foo".to_string()))
+    );
+
+    for (expression, result) in rows {
+        note!(harness, "{:<24} = {}", expression, result);
+    }
+    note!(harness);
+    for error in errors {
+        note!(harness, "{}\n", error);
+    }
+}
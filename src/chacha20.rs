@@ -0,0 +1,272 @@
+// chacha20.rs
+// The ChaCha20 stream cipher, as specified in RFC 8439, and its XChaCha20
+// variant with an extended nonce. The state of the cipher is a 4x4 matrix of
+// 32-bit words, holding four constants, the 256-bit key, a 32-bit block
+// counter, and a 96-bit nonce, which is scrambled by 20 rounds of additions,
+// rotations, and XOR operations, alternating between the columns and the
+// diagonals of the matrix. Adding the original state to the result gives a
+// 64-byte block of keystream, which is XOR-ed onto the data, so encryption and
+// decryption are the same operation. A nonce must never be reused with the same
+// key, which is hard to guarantee for random 96-bit nonces, so XChaCha20 first
+// derives a subkey from the key and half of a 192-bit nonce with HChaCha20,
+// which is the same permutation, without the final addition, and then uses the
+// other half of the nonce with the subkey.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+// The ASCII string "expand 32-byte k", as little-endian words.
+const CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+pub const KEY_SIZE: usize = 32;
+pub const NONCE_SIZE: usize = 12;
+pub const EXTENDED_NONCE_SIZE: usize = 24;
+pub const BLOCK_SIZE: usize = 64;
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+// Ten double rounds, each one made of a column round and a diagonal round.
+fn permute(state: &mut [u32; 16]) {
+    for _ in 0..10 {
+        quarter_round(state, 0, 4, 8, 12);
+        quarter_round(state, 1, 5, 9, 13);
+        quarter_round(state, 2, 6, 10, 14);
+        quarter_round(state, 3, 7, 11, 15);
+        quarter_round(state, 0, 5, 10, 15);
+        quarter_round(state, 1, 6, 11, 12);
+        quarter_round(state, 2, 7, 8, 13);
+        quarter_round(state, 3, 4, 9, 14);
+    }
+}
+
+fn read_words<const N: usize>(bytes: &[u8]) -> [u32; N] {
+    let mut words = [0; N];
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+        *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    words
+}
+
+// The state, where the counter and the nonce take the last four words.
+fn initial_state(key: &[u8; KEY_SIZE], input: [u32; 4]) -> [u32; 16] {
+    let mut state = [0; 16];
+    state[..4].copy_from_slice(&CONSTANTS);
+    state[4..12].copy_from_slice(&read_words::<8>(key));
+    state[12..].copy_from_slice(&input);
+    state
+}
+
+pub fn block(key: &[u8; KEY_SIZE], counter: u32, nonce: &[u8; NONCE_SIZE]) -> [u8; BLOCK_SIZE] {
+    let [n0, n1, n2] = read_words::<3>(nonce);
+    let initial = initial_state(key, [counter, n0, n1, n2]);
+    let mut state = initial;
+    permute(&mut state);
+    let mut output = [0; BLOCK_SIZE];
+    for ((bytes, word), original) in output.chunks_exact_mut(4).zip(state).zip(initial) {
+        bytes.copy_from_slice(&word.wrapping_add(original).to_le_bytes());
+    }
+    output
+}
+
+pub fn hchacha20(key: &[u8; KEY_SIZE], nonce: &[u8; 16]) -> [u8; KEY_SIZE] {
+    let mut state = initial_state(key, read_words::<4>(nonce));
+    permute(&mut state);
+    let mut subkey = [0; KEY_SIZE];
+    let words = state[..4].iter().chain(&state[12..]);
+    for (bytes, word) in subkey.chunks_exact_mut(4).zip(words) {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    subkey
+}
+
+pub struct ChaCha20 {
+    key: [u8; KEY_SIZE],
+    nonce: [u8; NONCE_SIZE],
+    counter: u32,
+    keystream: [u8; BLOCK_SIZE],
+    position: usize,
+}
+
+impl ChaCha20 {
+    pub fn new(key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE], counter: u32) -> Self {
+        Self {
+            key: *key,
+            nonce: *nonce,
+            counter,
+            keystream: [0; BLOCK_SIZE],
+            position: BLOCK_SIZE,
+        }
+    }
+
+    // XChaCha20, where the first 16 bytes of the nonce derive the subkey, and
+    // the last 8 bytes are the end of the nonce for ChaCha20.
+    pub fn extended(key: &[u8; KEY_SIZE], nonce: &[u8; EXTENDED_NONCE_SIZE], counter: u32) -> Self {
+        let mut head = [0; 16];
+        head.copy_from_slice(&nonce[..16]);
+        let mut tail = [0; NONCE_SIZE];
+        tail[4..].copy_from_slice(&nonce[16..]);
+        Self::new(&hchacha20(key, &head), &tail, counter)
+    }
+
+    // Encrypts or decrypts the data in place, continuing the keystream from
+    // where the previous call left it, so the data can be streamed in pieces.
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        for byte in data {
+            if self.position == BLOCK_SIZE {
+                self.keystream = block(&self.key, self.counter, &self.nonce);
+                self.counter = self
+                    .counter
+                    .checked_add(1)
+                    .expect("the block counter must not overflow");
+                self.position = 0;
+            }
+            *byte ^= self.keystream[self.position];
+            self.position += 1;
+        }
+    }
+}
+
+pub fn chacha20(
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    counter: u32,
+    data: &[u8],
+) -> Vec<u8> {
+    let mut output = data.to_vec();
+    ChaCha20::new(key, nonce, counter).apply_keystream(&mut output);
+    output
+}
+
+pub fn xchacha20(
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; EXTENDED_NONCE_SIZE],
+    counter: u32,
+    data: &[u8],
+) -> Vec<u8> {
+    let mut output = data.to_vec();
+    ChaCha20::extended(key, nonce, counter).apply_keystream(&mut output);
+    output
+}
+
+// Demonstration
+
+use crate::encoding::HEX;
+use crate::harness::Harness;
+
+fn array<const N: usize>(hex: &str) -> [u8; N] {
+    let mut bytes = [0; N];
+    bytes.copy_from_slice(&HEX.decode(hex).unwrap());
+    bytes
+}
+
+const SUNSCREEN: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only \
+                           one tip for the future, sunscreen would be it.";
+
+const DHOLE: &[u8] = b"The dhole (pronounced \"dole\") is also known as the Asiatic wild dog, \
+                       red dog, and whistling dog.";
+
+pub fn demo(harness: &mut Harness) {
+    let mut key = [0; KEY_SIZE];
+    for (index, byte) in key.iter_mut().enumerate() {
+        *byte = index as u8;
+    }
+
+    let mut state = [0; 16];
+    state[..4].copy_from_slice(&[0x11111111, 0x01020304, 0x9b8d6f43, 0x01234567]);
+    quarter_round(&mut state, 0, 1, 2, 3);
+    test!(
+        harness,
+        "the quarter round of section 2.1.1 of the RFC",
+        &state[..4],
+        &[0xea2a92f4, 0xcb1cf8ce, 0x4581472e, 0x5881c4bb]
+    );
+    test!(
+        harness,
+        "the block function of section 2.3.2 of the RFC",
+        HEX.encode(&block(&key, 1, &array("000000090000004a00000000"))),
+        "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4e\
+         d2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e"
+    );
+    test!(
+        harness,
+        "the keystream of an all-zero key and nonce",
+        HEX.encode(&block(&[0; KEY_SIZE], 0, &[0; NONCE_SIZE])),
+        "76b8e0ada0f13d90405d6ae55386bd28bdd219b8a08ded1aa836efcc8b770dc7\
+         da41597c5157488d7724e03fb8d84a376a43b8f41518a11cc387b669b2ee6586"
+    );
+
+    let nonce = array("000000000000004a00000000");
+    let ciphertext = chacha20(&key, &nonce, 1, SUNSCREEN);
+    test!(
+        harness,
+        "the encryption of section 2.4.2 of the RFC",
+        HEX.encode(&ciphertext),
+        "6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0b\
+         f91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d8\
+         07ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab7793736\
+         5af90bbf74a35be6b40b8eedf2785e42874d"
+    );
+    test!(
+        harness,
+        "decryption is the same as encryption",
+        chacha20(&key, &nonce, 1, &ciphertext),
+        SUNSCREEN.to_vec()
+    );
+    let mut streamed = SUNSCREEN.to_vec();
+    let mut cipher = ChaCha20::new(&key, &nonce, 1);
+    for piece in streamed.chunks_mut(13) {
+        cipher.apply_keystream(piece);
+    }
+    test!(
+        harness,
+        "encrypting in pieces continues the keystream",
+        streamed,
+        ciphertext
+    );
+
+    test!(
+        harness,
+        "the HChaCha20 vector of the XChaCha20 draft",
+        HEX.encode(&hchacha20(&key, &array("000000090000004a0000000031415927"))),
+        "82413b4227b27bfed30e42508a877d73a0f9e4d58a74a853c12ec41326d3ecdc"
+    );
+    let extended_key = array("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f");
+    let extended_nonce = array("404142434445464748494a4b4c4d4e4f5051525354555658");
+    let ciphertext = xchacha20(&extended_key, &extended_nonce, 0, DHOLE);
+    test!(
+        harness,
+        "an XChaCha20 encryption",
+        HEX.encode(&ciphertext),
+        "4559abba4e48c16102e8bb2c05e6947f50a786de162f9b0b7e592a9b53d0d4e9\
+         8d8d6410d540a1a6375b26d80dace4fab52384c731acbf16a5923c0c48d3575d\
+         4d0d2c673b666faa731061277701093a6bf7a158a8864292a41c48e3a9b4c0da"
+    );
+    test!(
+        harness,
+        "an XChaCha20 decryption",
+        xchacha20(&extended_key, &extended_nonce, 0, &ciphertext),
+        DHOLE.to_vec()
+    );
+
+    let message = "Attack at dawn!";
+    let encrypted = xchacha20(&extended_key, &extended_nonce, 1, message.as_bytes());
+    let decrypted = xchacha20(&extended_key, &extended_nonce, 1, &encrypted);
+    note!(harness, "Plaintext:  {}", message);
+    note!(harness, "Ciphertext: {}", HEX.encode(&encrypted));
+    note!(
+        harness,
+        "Decrypted:  {}",
+        String::from_utf8_lossy(&decrypted)
+    );
+}
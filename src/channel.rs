@@ -0,0 +1,464 @@
+// channel.rs
+// A bounded, blocking channel, with any number of senders and receivers, built
+// from a mutex and two condition variables. The messages wait in a queue of a
+// fixed capacity, behind the mutex: a sender waits on the first condition while
+// the queue is full, a receiver waits on the second one while it is empty, and
+// each of them wakes the other side up after changing the queue. The bound is
+// what gives a pipeline its backpressure, as a fast producer is held back, and
+// can not run ahead of a slow consumer. The channel counts its senders and its
+// receivers, so it is disconnected when either side is gone: receiving drains
+// the remaining messages first, and sending gives the message back. Each of the
+// operations also has a variant which does not block, and one with a timeout.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    Empty,
+    Full,
+    Timeout,
+    Disconnected,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            ErrorKind::Empty => "the channel is empty",
+            ErrorKind::Full => "the channel is full",
+            ErrorKind::Timeout => "timed out",
+            ErrorKind::Disconnected => "the channel is disconnected",
+        };
+        f.write_str(message)
+    }
+}
+
+// A message which could not be sent is given back.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SendError<T> {
+    pub kind: ErrorKind,
+    pub value: T,
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SendError")
+            .field("kind", &self.kind)
+            .finish()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "sending failed: {}", self.kind)
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecvError {
+    pub kind: ErrorKind,
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "receiving failed: {}", self.kind)
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+struct State<T> {
+    queue: VecDeque<T>,
+    senders: usize,
+    receivers: usize,
+}
+
+struct Shared<T> {
+    capacity: usize,
+    state: Mutex<State<T>>,
+    not_full: Condvar,
+    not_empty: Condvar,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap()
+    }
+}
+
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+pub fn bounded<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "the capacity must be positive");
+    let shared = Arc::new(Shared {
+        capacity,
+        state: Mutex::new(State {
+            // The queue grows as needed, as the capacity is only a limit, which
+            // may be far larger than what fits in memory.
+            queue: VecDeque::new(),
+            senders: 1,
+            receivers: 1,
+        }),
+        not_full: Condvar::new(),
+        not_empty: Condvar::new(),
+    });
+    let sender = Sender {
+        shared: Arc::clone(&shared),
+    };
+    (sender, Receiver { shared })
+}
+
+// Waits on the condition until the predicate no longer holds, or until the
+// deadline, if there is one. The state is returned either way, along with
+// whether the wait timed out.
+fn wait_while<'a, T>(
+    condition: &Condvar,
+    mut state: MutexGuard<'a, State<T>>,
+    deadline: Option<Instant>,
+    predicate: impl Fn(&State<T>) -> bool,
+) -> (MutexGuard<'a, State<T>>, bool) {
+    while predicate(&state) {
+        match deadline {
+            None => state = condition.wait(state).unwrap(),
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return (state, true);
+                }
+                state = condition.wait_timeout(state, deadline - now).unwrap().0;
+            }
+        }
+    }
+    (state, false)
+}
+
+impl<T> Sender<T> {
+    fn send_until(&self, value: T, deadline: Option<Instant>) -> Result<(), SendError<T>> {
+        let shared = &self.shared;
+        let (mut state, timed_out) =
+            wait_while(&shared.not_full, shared.lock(), deadline, |state| {
+                state.queue.len() == shared.capacity && state.receivers > 0
+            });
+        let kind = if state.receivers == 0 {
+            ErrorKind::Disconnected
+        } else if timed_out {
+            ErrorKind::Timeout
+        } else {
+            state.queue.push_back(value);
+            drop(state);
+            shared.not_empty.notify_one();
+            return Ok(());
+        };
+        Err(SendError { kind, value })
+    }
+
+    // Blocks while the channel is full.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.send_until(value, None)
+    }
+
+    pub fn send_timeout(&self, value: T, timeout: Duration) -> Result<(), SendError<T>> {
+        self.send_until(value, Some(Instant::now() + timeout))
+    }
+
+    pub fn try_send(&self, value: T) -> Result<(), SendError<T>> {
+        self.send_until(value, Some(Instant::now()))
+            .map_err(|error| SendError {
+                kind: match error.kind {
+                    ErrorKind::Timeout => ErrorKind::Full,
+                    kind => kind,
+                },
+                value: error.value,
+            })
+    }
+
+    pub fn len(&self) -> usize {
+        self.shared.lock().queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+// The last sender wakes up every waiting receiver, to see the disconnection.
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            drop(state);
+            self.shared.not_empty.notify_all();
+        }
+    }
+}
+
+impl<T> Receiver<T> {
+    fn recv_until(&self, deadline: Option<Instant>) -> Result<T, RecvError> {
+        let shared = &self.shared;
+        let (mut state, timed_out) =
+            wait_while(&shared.not_empty, shared.lock(), deadline, |state| {
+                state.queue.is_empty() && state.senders > 0
+            });
+        let kind = match state.queue.pop_front() {
+            Some(value) => {
+                drop(state);
+                shared.not_full.notify_one();
+                return Ok(value);
+            }
+            None if state.senders == 0 => ErrorKind::Disconnected,
+            None if timed_out => ErrorKind::Timeout,
+            None => unreachable!("woken up without a message"),
+        };
+        Err(RecvError { kind })
+    }
+
+    // Blocks while the channel is empty, and fails only when the channel is
+    // both empty and disconnected.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.recv_until(None)
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvError> {
+        self.recv_until(Some(Instant::now() + timeout))
+    }
+
+    pub fn try_recv(&self) -> Result<T, RecvError> {
+        self.recv_until(Some(Instant::now()))
+            .map_err(|error| RecvError {
+                kind: match error.kind {
+                    ErrorKind::Timeout => ErrorKind::Empty,
+                    kind => kind,
+                },
+            })
+    }
+
+    // Receives the messages until the channel is disconnected.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(|| self.recv().ok())
+    }
+
+    pub fn len(&self) -> usize {
+        self.shared.lock().queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        self.shared.lock().receivers += 1;
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+// The last receiver wakes up every waiting sender, to see the disconnection.
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.receivers -= 1;
+        if state.receivers == 0 {
+            drop(state);
+            self.shared.not_full.notify_all();
+        }
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+pub fn demo(harness: &mut Harness) {
+    let (sender, receiver) = bounded(2);
+    test!(
+        harness,
+        "receiving from an empty channel does not block",
+        receiver.try_recv(),
+        Err(RecvError {
+            kind: ErrorKind::Empty
+        })
+    );
+    sender.send(1).unwrap();
+    sender.send(2).unwrap();
+    test!(
+        harness,
+        "sending to a full channel gives the message back",
+        sender
+            .try_send(3)
+            .map_err(|error| (error.kind, error.value)),
+        Err((ErrorKind::Full, 3))
+    );
+    let start = Instant::now();
+    let result = sender.send_timeout(3, Duration::from_millis(20));
+    test!(
+        harness,
+        "sending to a full channel times out",
+        (
+            result.map_err(|error| error.kind),
+            start.elapsed() >= Duration::from_millis(20)
+        ),
+        (Err(ErrorKind::Timeout), true)
+    );
+    test!(
+        harness,
+        "the messages are received in order",
+        [receiver.recv(), receiver.try_recv()],
+        [Ok(1), Ok(2)]
+    );
+    let (huge, huge_receiver) = bounded(usize::MAX);
+    test!(
+        harness,
+        "the capacity is not allocated up front",
+        (huge.try_send(7).is_ok(), huge_receiver.try_recv()),
+        (true, Ok(7))
+    );
+    test!(
+        harness,
+        "receiving from an empty channel times out",
+        receiver.recv_timeout(Duration::from_millis(10)),
+        Err(RecvError {
+            kind: ErrorKind::Timeout
+        })
+    );
+
+    // A blocked sender is woken up by a receiver on another thread.
+    sender.send(10).unwrap();
+    sender.send(20).unwrap();
+    let blocked = thread::spawn({
+        let sender = sender.clone();
+        move || sender.send(30).is_ok()
+    });
+    thread::sleep(Duration::from_millis(10));
+    test!(harness, "the sender waits for room", receiver.len(), 2);
+    let received = receiver.recv();
+    test!(
+        harness,
+        "receiving unblocks the sender",
+        (received, blocked.join().unwrap(), receiver.len()),
+        (Ok(10), true, 2)
+    );
+
+    drop(sender);
+    test!(
+        harness,
+        "the remaining messages are drained after the senders are gone",
+        receiver.iter().collect::<Vec<_>>(),
+        [20, 30]
+    );
+    test!(
+        harness,
+        "then the channel is disconnected",
+        receiver.try_recv(),
+        Err(RecvError {
+            kind: ErrorKind::Disconnected
+        })
+    );
+    let (sender, receiver) = bounded(1);
+    drop(receiver);
+    test!(
+        harness,
+        "sending without receivers gives the message back",
+        sender
+            .send("hello")
+            .map_err(|error| (error.kind, error.value)),
+        Err((ErrorKind::Disconnected, "hello"))
+    );
+
+    // A pipeline of three producers, two workers which square the numbers,
+    // and a consumer which adds them up, over two small channels.
+    let (numbers, numbers_receiver) = bounded::<u64>(4);
+    let (squares, squares_receiver) = bounded::<u64>(4);
+    let producers: Vec<_> = (0..3)
+        .map(|producer| {
+            let numbers = numbers.clone();
+            thread::spawn(move || {
+                for number in (1..=300).filter(|number| number % 3 == producer) {
+                    numbers.send(number).unwrap();
+                }
+            })
+        })
+        .collect();
+    drop(numbers);
+    let processed = Arc::new(AtomicUsize::new(0));
+    let workers: Vec<_> = (0..2)
+        .map(|_| {
+            let numbers = numbers_receiver.clone();
+            let squares = squares.clone();
+            let processed = Arc::clone(&processed);
+            thread::spawn(move || {
+                for number in numbers.iter() {
+                    squares.send(number * number).unwrap();
+                    processed.fetch_add(1, Ordering::Relaxed);
+                }
+            })
+        })
+        .collect();
+    drop((numbers_receiver, squares));
+    let mut count = 0;
+    let mut most_waiting = 0;
+    let mut sum = 0;
+    for square in squares_receiver.iter() {
+        most_waiting = most_waiting.max(squares_receiver.len());
+        count += 1;
+        sum += square;
+    }
+    for thread in producers.into_iter().chain(workers) {
+        thread.join().unwrap();
+    }
+    test!(
+        harness,
+        "the pipeline delivers every square once",
+        (count, sum, processed.load(Ordering::Relaxed)),
+        (300, 300 * 301 * 601 / 6, 300)
+    );
+    test!(
+        harness,
+        "the channel never holds more than its capacity",
+        most_waiting <= 4,
+        true
+    );
+    note!(
+        harness,
+        "The sum of the squares from 1 to 300 is {}, with at most {} waiting\n",
+        sum,
+        most_waiting
+    );
+}
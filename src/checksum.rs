@@ -0,0 +1,337 @@
+// checksum.rs
+// Simple checksums, which detect accidental errors, but not deliberate ones.
+// The Luhn algorithm protects identification numbers, like credit card numbers,
+// with a check digit: doubling every second digit from the right, and summing
+// the digits of the results with the rest, gives a multiple of ten for a valid
+// number, which catches any single wrong digit, and most swaps of neighbouring
+// digits. Card numbers are also checked for the prefixes and lengths of the
+// major networks. Fletcher's checksums keep two running sums, one of the data,
+// and one of the first sum, modulo 255 for 8-bit, or 65535 for 16-bit words,
+// so that the second sum depends on the order of the data, too. Adler-32, the
+// checksum of zlib, is the same idea with bytes, and modulo the prime 65521,
+// where the reduction can be deferred for thousands of bytes without overflow.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+
+// The digits of the number, ignoring spaces and hyphens, or None if there is
+// any other character.
+fn digits(number: &str) -> Option<Vec<u32>> {
+    number
+        .chars()
+        .filter(|c| *c != ' ' && *c != '-')
+        .map(|c| c.to_digit(10))
+        .collect()
+}
+
+// The sum of the digits, where every second digit is doubled, starting from
+// the rightmost one if the doubling starts there.
+fn luhn_sum(digits: &[u32], double_rightmost: bool) -> u32 {
+    digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, digit)| {
+            if (index % 2 == 0) == double_rightmost {
+                let doubled = digit * 2;
+                doubled / 10 + doubled % 10
+            } else {
+                *digit
+            }
+        })
+        .sum()
+}
+
+pub fn luhn_valid(number: &str) -> bool {
+    match digits(number) {
+        Some(digits) if digits.len() >= 2 => luhn_sum(&digits, false).is_multiple_of(10),
+        _ => false,
+    }
+}
+
+// The digit which makes the number valid when appended to it.
+pub fn luhn_check_digit(number: &str) -> Option<char> {
+    let digits = digits(number).filter(|digits| !digits.is_empty())?;
+    let check = (10 - luhn_sum(&digits, true) % 10) % 10;
+    char::from_digit(check, 10)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Network {
+    Visa,
+    Mastercard,
+    AmericanExpress,
+    Discover,
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Network::Visa => "Visa",
+            Network::Mastercard => "Mastercard",
+            Network::AmericanExpress => "American Express",
+            Network::Discover => "Discover",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CardError {
+    InvalidCharacter,
+    UnknownNetwork,
+    InvalidLength(Network, usize),
+    InvalidCheckDigit,
+}
+
+impl fmt::Display for CardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CardError::InvalidCharacter => write!(f, "the number has invalid characters"),
+            CardError::UnknownNetwork => write!(f, "the number belongs to no known network"),
+            CardError::InvalidLength(network, length) => {
+                write!(f, "{} numbers can not be {} digits long", network, length)
+            }
+            CardError::InvalidCheckDigit => write!(f, "the check digit is wrong"),
+        }
+    }
+}
+
+impl std::error::Error for CardError {}
+
+// The network of the number by its prefix, and the lengths it allows.
+fn network(digits: &[u32]) -> Option<(Network, &'static [usize])> {
+    let prefix = |length: usize| {
+        digits
+            .iter()
+            .take(length)
+            .fold(0, |acc, digit| acc * 10 + digit)
+    };
+    match (prefix(1), prefix(2), prefix(3), prefix(4)) {
+        (4, _, _, _) => Some((Network::Visa, &[13, 16, 19])),
+        (_, 51..=55, _, _) | (_, _, _, 2221..=2720) => Some((Network::Mastercard, &[16])),
+        (_, 34 | 37, _, _) => Some((Network::AmericanExpress, &[15])),
+        (_, 65, _, _) | (_, _, 644..=649, _) | (_, _, _, 6011) => {
+            Some((Network::Discover, &[16, 19]))
+        }
+        _ => None,
+    }
+}
+
+pub fn validate_card(number: &str) -> Result<Network, CardError> {
+    let digits = digits(number).ok_or(CardError::InvalidCharacter)?;
+    let (network, lengths) = network(&digits).ok_or(CardError::UnknownNetwork)?;
+    if !lengths.contains(&digits.len()) {
+        return Err(CardError::InvalidLength(network, digits.len()));
+    }
+    if !luhn_sum(&digits, false).is_multiple_of(10) {
+        return Err(CardError::InvalidCheckDigit);
+    }
+    Ok(network)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    const MODULUS: u32 = 65521;
+    // The most bytes which can be summed before b could overflow 32 bits.
+    const CHUNK: usize = 5552;
+
+    pub fn new() -> Self {
+        Self { a: 1, b: 0 }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(Self::CHUNK) {
+            for byte in chunk {
+                self.a += *byte as u32;
+                self.b += self.a;
+            }
+            self.a %= Self::MODULUS;
+            self.b %= Self::MODULUS;
+        }
+    }
+
+    pub fn finish(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+}
+
+impl Default for Adler32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn adler32(data: &[u8]) -> u32 {
+    let mut adler = Adler32::new();
+    adler.update(data);
+    adler.finish()
+}
+
+pub fn fletcher16(data: &[u8]) -> u16 {
+    let (mut a, mut b) = (0u16, 0u16);
+    for byte in data {
+        a = (a + *byte as u16) % 255;
+        b = (b + a) % 255;
+    }
+    (b << 8) | a
+}
+
+// The data is read as little-endian 16-bit words, where an odd byte at the end
+// is padded with zero.
+pub fn fletcher32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (0u32, 0u32);
+    for word in data.chunks(2) {
+        let word = word[0] as u32 | (*word.get(1).unwrap_or(&0) as u32) << 8;
+        a = (a + word) % 65535;
+        b = (b + a) % 65535;
+    }
+    (b << 16) | a
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "a valid number",
+        (luhn_valid("79927398713"), luhn_valid("7992 7398 713")),
+        (true, true)
+    );
+    test!(
+        harness,
+        "any single wrong digit is detected",
+        (0..11)
+            .flat_map(|position| (1..10).map(move |delta| (position, delta)))
+            .all(|(position, delta)| {
+                let mut number: Vec<u32> = digits("79927398713").unwrap();
+                number[position] = (number[position] + delta) % 10;
+                let text: String = number.iter().map(|d| d.to_string()).collect();
+                !luhn_valid(&text)
+            }),
+        true
+    );
+    test!(
+        harness,
+        "invalid numbers",
+        (
+            luhn_valid("79927398710"),
+            luhn_valid("7992x7398713"),
+            luhn_valid("0"),
+            luhn_valid("")
+        ),
+        (false, false, false, false)
+    );
+    test!(
+        harness,
+        "generating the check digit",
+        (
+            luhn_check_digit("7992739871"),
+            luhn_check_digit("4111 1111 1111 111"),
+            luhn_check_digit("")
+        ),
+        (Some('3'), Some('1'), None)
+    );
+    test!(
+        harness,
+        "validating card numbers of the major networks",
+        [
+            "4111 1111 1111 1111",
+            "5555-5555-5555-4444",
+            "2223 0031 2200 3222",
+            "3782 822463 10005",
+            "6011 1111 1111 1117",
+        ]
+        .map(validate_card),
+        [
+            Ok(Network::Visa),
+            Ok(Network::Mastercard),
+            Ok(Network::Mastercard),
+            Ok(Network::AmericanExpress),
+            Ok(Network::Discover)
+        ]
+    );
+    test!(
+        harness,
+        "rejecting invalid card numbers",
+        [
+            "4111 1111 1111 1112",
+            "4111 1111 1111 11",
+            "1234 5678 9012 3452",
+            "4111 1111 1111 111O",
+        ]
+        .map(validate_card),
+        [
+            Err(CardError::InvalidCheckDigit),
+            Err(CardError::InvalidLength(Network::Visa, 14)),
+            Err(CardError::UnknownNetwork),
+            Err(CardError::InvalidCharacter)
+        ]
+    );
+
+    test!(
+        harness,
+        "Adler-32 of \"Wikipedia\"",
+        adler32(b"Wikipedia"),
+        0x11e60398
+    );
+    test!(
+        harness,
+        "Adler-32 of nothing, and of a long input",
+        (adler32(b""), adler32(&[b'a'; 100_000])),
+        (0x00000001, 0x79660b4d)
+    );
+    let mut streamed = Adler32::new();
+    for piece in b"Wikipedia".chunks(2) {
+        streamed.update(piece);
+    }
+    test!(harness, "streaming Adler-32", streamed.finish(), 0x11e60398);
+    test!(
+        harness,
+        "Fletcher-16",
+        [&b"abcde"[..], b"abcdef", b"abcdefgh"].map(fletcher16),
+        [0xc8f0, 0x2057, 0x0627]
+    );
+    test!(
+        harness,
+        "Fletcher-32",
+        [&b"abcde"[..], b"abcdef", b"abcdefgh"].map(fletcher32),
+        [0xf04fc729, 0x56502d2a, 0xebe19591]
+    );
+    test!(
+        harness,
+        "Fletcher's sums depend on the order of the data",
+        fletcher16(b"ab") == fletcher16(b"ba"),
+        false
+    );
+
+    for number in [
+        "4012 8888 8888 1881",
+        "3714 496353 98431",
+        "5105 1051 0510 5106",
+    ] {
+        match validate_card(number) {
+            Ok(network) => note!(harness, "{}: valid {} number", number, network),
+            Err(error) => note!(harness, "{}: {}", number, error),
+        }
+    }
+    let data = b"The quick brown fox jumps over the lazy dog";
+    note!(
+        harness,
+        "Adler-32: {:08x}, Fletcher-16: {:04x}, Fletcher-32: {:08x}",
+        adler32(data),
+        fletcher16(data),
+        fletcher32(data)
+    );
+}
@@ -0,0 +1,281 @@
+// classical.rs
+// Toy historical ciphers, which are easy to break, and are only of historical
+// and educational interest: the Caesar cipher, which shifts each letter by the
+// same amount, the Vigenère cipher, which shifts the letters by the letters of
+// a repeating key, the repeating-key XOR cipher, which is its counterpart for
+// bytes, and the rail fence cipher, which writes the text in a zigzag over a
+// number of rails, and reads it off rail by rail. The substitution ciphers keep
+// the case of the letters, and leave everything else alone. The Caesar cipher
+// and single-byte XOR are cracked by frequency analysis: trying every key, and
+// choosing the one which makes the result look the most like English, which is
+// measured by the chi-squared statistic of the letter frequencies for Caesar,
+// and by a score of the letters, spaces, and unprintable bytes for XOR.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+// The relative frequencies of the letters in English text, in percent.
+#[rustfmt::skip]
+const ENGLISH: [f64; 26] = [
+    8.167, 1.492, 2.782, 4.253, 12.702, 2.228, 2.015, 6.094, 6.966, 0.153, 0.772, 4.025, 2.406,
+    6.749, 7.507, 1.929, 0.095, 5.987, 6.327, 9.056, 2.758, 0.978, 2.360, 0.150, 1.974, 0.074,
+];
+
+fn shift_letter(c: char, shift: u8) -> char {
+    let base = match c {
+        'a'..='z' => b'a',
+        'A'..='Z' => b'A',
+        _ => return c,
+    };
+    (base + (c as u8 - base + shift % 26) % 26) as char
+}
+
+pub fn caesar_encrypt(text: &str, shift: u8) -> String {
+    text.chars().map(|c| shift_letter(c, shift)).collect()
+}
+
+pub fn caesar_decrypt(text: &str, shift: u8) -> String {
+    caesar_encrypt(text, 26 - shift % 26)
+}
+
+// The key only advances on letters, and its characters which are not letters
+// are ignored, so a key without letters leaves the text unchanged.
+fn vigenere(text: &str, key: &str, decrypt: bool) -> String {
+    let shifts: Vec<u8> = key
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_lowercase() as u8 - b'a')
+        .map(|shift| if decrypt { (26 - shift) % 26 } else { shift })
+        .collect();
+    if shifts.is_empty() {
+        return text.to_string();
+    }
+    let mut index = 0;
+    text.chars()
+        .map(|c| {
+            if !c.is_ascii_alphabetic() {
+                return c;
+            }
+            let shifted = shift_letter(c, shifts[index % shifts.len()]);
+            index += 1;
+            shifted
+        })
+        .collect()
+}
+
+pub fn vigenere_encrypt(text: &str, key: &str) -> String {
+    vigenere(text, key, false)
+}
+
+pub fn vigenere_decrypt(text: &str, key: &str) -> String {
+    vigenere(text, key, true)
+}
+
+// Encryption and decryption are the same.
+pub fn xor(data: &[u8], key: &[u8]) -> Vec<u8> {
+    if key.is_empty() {
+        return data.to_vec();
+    }
+    data.iter()
+        .zip(key.iter().cycle())
+        .map(|(byte, key)| byte ^ key)
+        .collect()
+}
+
+// The rails of the characters, going down and up, like 0, 1, 2, 1, 0, 1, ...
+fn zigzag(length: usize, rails: usize) -> Vec<usize> {
+    let period = 2 * (rails.max(2) - 1);
+    (0..length)
+        .map(|index| {
+            let phase = index % period;
+            if phase < rails {
+                phase
+            } else {
+                period - phase
+            }
+        })
+        .map(|rail| rail.min(rails.saturating_sub(1)))
+        .collect()
+}
+
+pub fn rail_fence_encrypt(text: &str, rails: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let pattern = zigzag(chars.len(), rails);
+    let mut order: Vec<usize> = (0..chars.len()).collect();
+    order.sort_by_key(|index| pattern[*index]);
+    order.into_iter().map(|index| chars[index]).collect()
+}
+
+// The characters of each rail are in the order of their positions, so sorting
+// the positions by their rails gives where each character of the ciphertext
+// goes.
+pub fn rail_fence_decrypt(text: &str, rails: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let pattern = zigzag(chars.len(), rails);
+    let mut order: Vec<usize> = (0..chars.len()).collect();
+    order.sort_by_key(|index| pattern[*index]);
+    let mut plain = vec![' '; chars.len()];
+    for (c, index) in chars.into_iter().zip(order) {
+        plain[index] = c;
+    }
+    plain.into_iter().collect()
+}
+
+// Lower is more like English.
+pub fn chi_squared(text: &str) -> f64 {
+    let mut counts = [0usize; 26];
+    for c in text.chars().filter(char::is_ascii_alphabetic) {
+        counts[(c.to_ascii_lowercase() as u8 - b'a') as usize] += 1;
+    }
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return f64::INFINITY;
+    }
+    counts
+        .iter()
+        .zip(ENGLISH)
+        .map(|(count, frequency)| {
+            let expected = total as f64 * frequency / 100.0;
+            (*count as f64 - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+// The shift, and the decrypted text.
+pub fn crack_caesar(ciphertext: &str) -> (u8, String) {
+    (0..26)
+        .map(|shift| (shift, caesar_decrypt(ciphertext, shift)))
+        .min_by(|(_, a), (_, b)| chi_squared(a).total_cmp(&chi_squared(b)))
+        .unwrap()
+}
+
+// Higher is more like English text, where spaces count as the most frequent
+// letter, and control characters and bytes outside ASCII are penalized.
+fn english_score(data: &[u8]) -> f64 {
+    data.iter()
+        .map(|byte| match byte {
+            b'a'..=b'z' => ENGLISH[(byte - b'a') as usize],
+            b'A'..=b'Z' => ENGLISH[(byte - b'A') as usize] / 2.0,
+            b' ' => 13.0,
+            b'\n' | b'\t' | 0x21..=0x7e => 0.0,
+            _ => -20.0,
+        })
+        .sum()
+}
+
+// The key byte, and the decrypted data.
+pub fn crack_single_byte_xor(ciphertext: &[u8]) -> (u8, Vec<u8>) {
+    (0..=255)
+        .map(|key| (key, xor(ciphertext, &[key])))
+        .max_by(|(_, a), (_, b)| english_score(a).total_cmp(&english_score(b)))
+        .unwrap()
+}
+
+// Demonstration
+
+use crate::encoding::HEX;
+use crate::harness::Harness;
+
+const SENTENCE: &str = "It was the best of times, it was the worst of times, it was the age of \
+                        wisdom, it was the age of foolishness, it was the epoch of belief.";
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "Caesar's own shift of three",
+        caesar_encrypt("Veni, vidi, vici! XYZ", 3),
+        "Yhql, ylgl, ylfl! ABC"
+    );
+    test!(
+        harness,
+        "ROT13 is its own inverse",
+        caesar_encrypt(&caesar_encrypt("Hello, World!", 13), 13),
+        "Hello, World!"
+    );
+    test!(
+        harness,
+        "Caesar decryption",
+        caesar_decrypt("Yhql, ylgl, ylfl! ABC", 3),
+        "Veni, vidi, vici! XYZ"
+    );
+    test!(
+        harness,
+        "the classic Vigenère example",
+        vigenere_encrypt("ATTACKATDAWN", "LEMON"),
+        "LXFOPVEFRNHR"
+    );
+    test!(
+        harness,
+        "Vigenère skips other characters, and keeps case",
+        (
+            vigenere_encrypt("Attack at dawn!", "Lemon"),
+            vigenere_decrypt("Lxfopv ef rnhr!", "LEMON")
+        ),
+        ("Lxfopv ef rnhr!".to_string(), "Attack at dawn!".to_string())
+    );
+    test!(
+        harness,
+        "repeating-key XOR",
+        HEX.encode(&xor(b"Burning 'em, if you ain't quick and nimble", b"ICE")),
+        "0b3637272a2b2e63622c2e69692a23693a2a3c6324202d623d63343c2a26226324272765272a282b2f20"
+    );
+    test!(
+        harness,
+        "the rail fence cipher with three rails",
+        rail_fence_encrypt("WEAREDISCOVEREDFLEEATONCE", 3),
+        "WECRLTEERDSOEEFEAOCAIVDEN"
+    );
+    test!(
+        harness,
+        "rail fence decryption",
+        [2, 3, 4, 7, 30].map(|rails| {
+            rail_fence_decrypt(&rail_fence_encrypt(SENTENCE, rails), rails) == SENTENCE
+        }),
+        [true; 5]
+    );
+    test!(
+        harness,
+        "a single rail leaves the text unchanged",
+        rail_fence_encrypt("abcdef", 1),
+        "abcdef"
+    );
+
+    let enciphered = caesar_encrypt(SENTENCE, 17);
+    test!(
+        harness,
+        "cracking a Caesar cipher with frequency analysis",
+        crack_caesar(&enciphered),
+        (17, SENTENCE.to_string())
+    );
+    let encrypted = xor(SENTENCE.as_bytes(), &[0x5a]);
+    test!(
+        harness,
+        "cracking single-byte XOR with frequency analysis",
+        crack_single_byte_xor(&encrypted),
+        (0x5a, SENTENCE.as_bytes().to_vec())
+    );
+
+    note!(harness, "Enciphered: {}", enciphered);
+    let mut scores: Vec<(u8, f64)> = (0..26)
+        .map(|shift| (shift, chi_squared(&caesar_decrypt(&enciphered, shift))))
+        .collect();
+    scores.sort_by(|a, b| a.1.total_cmp(&b.1));
+    note!(
+        harness,
+        "The most likely shifts, by their chi-squared statistics:"
+    );
+    for (shift, score) in &scores[..3] {
+        let candidate = caesar_decrypt(&enciphered, *shift);
+        note!(
+            harness,
+            "    {:>2} {:>9.2}  {}...",
+            shift,
+            score,
+            &candidate[..40]
+        );
+    }
+    let (shift, cracked) = crack_caesar(&enciphered);
+    note!(harness, "Cracked with a shift of {}: {}", shift, cracked);
+}
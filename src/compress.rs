@@ -0,0 +1,328 @@
+// compress.rs
+// Two simple lossless compression schemes: run-length encoding, which replaces
+// runs of the same byte with the length of the run and the byte, and a toy LZ77
+// compressor, which replaces repeated sequences with a reference to an earlier
+// occurrence inside a fixed sliding window, given by its distance and length.
+// The LZ77 tokens are packed into bytes like in LZSS: a flag byte tells which
+// of the next eight tokens are literals, and which are references.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecompressError {
+    Truncated,
+    EmptyRun { position: usize },
+    InvalidDistance { position: usize, distance: usize },
+}
+
+impl fmt::Display for DecompressError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecompressError::Truncated => write!(f, "the compressed data is truncated"),
+            DecompressError::EmptyRun { position } => {
+                write!(f, "run of zero length at position {}", position)
+            }
+            DecompressError::InvalidDistance { position, distance } => write!(
+                f,
+                "reference at position {} points {} bytes before the start",
+                position, distance
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DecompressError {}
+
+// Runs longer than 255 bytes are split into multiple runs.
+pub fn rle_encode(data: &[u8]) -> Vec<u8> {
+    let mut result = Vec::new();
+    let mut i = 0;
+    while i < data.len() {
+        let byte = data[i];
+        let run = data[i..]
+            .iter()
+            .take(255)
+            .take_while(|other| **other == byte)
+            .count();
+        result.push(run as u8);
+        result.push(byte);
+        i += run;
+    }
+    result
+}
+
+pub fn rle_decode(data: &[u8]) -> Result<Vec<u8>, DecompressError> {
+    if !data.len().is_multiple_of(2) {
+        return Err(DecompressError::Truncated);
+    }
+
+    let mut result = Vec::new();
+    for (i, pair) in data.chunks_exact(2).enumerate() {
+        if pair[0] == 0 {
+            return Err(DecompressError::EmptyRun { position: i * 2 });
+        }
+        result.extend(std::iter::repeat_n(pair[1], pair[0] as usize));
+    }
+    Ok(result)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Token {
+    Literal(u8),
+    Reference { distance: usize, length: usize },
+}
+
+// A reference is packed into 16 bits, 12 for the distance, and 4 for the
+// length. References shorter than three bytes would not be shorter than the
+// literals they replace.
+pub const WINDOW_SIZE: usize = 4096;
+pub const MIN_MATCH: usize = 3;
+pub const MAX_MATCH: usize = MIN_MATCH + 15;
+
+pub fn lz77_tokenize(data: &[u8]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut position = 0;
+    while position < data.len() {
+        // The longest match in the window is searched for by brute force. The
+        // match may overlap the current position, which encodes runs.
+        let start = position.saturating_sub(WINDOW_SIZE);
+        let limit = MAX_MATCH.min(data.len() - position);
+        let mut best = (0, 0);
+        for candidate in start..position {
+            let length = (0..limit)
+                .take_while(|i| data[candidate + i] == data[position + i])
+                .count();
+            if length > best.1 {
+                best = (position - candidate, length);
+            }
+        }
+
+        if best.1 >= MIN_MATCH {
+            tokens.push(Token::Reference {
+                distance: best.0,
+                length: best.1,
+            });
+            position += best.1;
+        } else {
+            tokens.push(Token::Literal(data[position]));
+            position += 1;
+        }
+    }
+    tokens
+}
+
+pub fn lz77_detokenize(tokens: &[Token]) -> Result<Vec<u8>, DecompressError> {
+    let mut result = Vec::new();
+    for token in tokens {
+        match *token {
+            Token::Literal(byte) => result.push(byte),
+            Token::Reference { distance, length } => {
+                if distance == 0 || distance > result.len() {
+                    return Err(DecompressError::InvalidDistance {
+                        position: result.len(),
+                        distance,
+                    });
+                }
+                // Copied byte by byte, as the source may overlap the
+                // destination.
+                let start = result.len() - distance;
+                for i in 0..length {
+                    result.push(result[start + i]);
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
+pub fn lz77_encode(data: &[u8]) -> Vec<u8> {
+    let mut result = Vec::new();
+    for group in lz77_tokenize(data).chunks(8) {
+        let flags_position = result.len();
+        result.push(0);
+        for (bit, token) in group.iter().enumerate() {
+            match *token {
+                Token::Literal(byte) => result.push(byte),
+                Token::Reference { distance, length } => {
+                    result[flags_position] |= 1 << bit;
+                    let packed = ((distance - 1) << 4) | (length - MIN_MATCH);
+                    result.extend_from_slice(&(packed as u16).to_be_bytes());
+                }
+            }
+        }
+    }
+    result
+}
+
+pub fn lz77_decode(data: &[u8]) -> Result<Vec<u8>, DecompressError> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < data.len() {
+        let flags = data[i];
+        i += 1;
+        for bit in 0..8 {
+            if i == data.len() {
+                break;
+            }
+            if flags & (1 << bit) == 0 {
+                tokens.push(Token::Literal(data[i]));
+                i += 1;
+            } else {
+                if i + 2 > data.len() {
+                    return Err(DecompressError::Truncated);
+                }
+                let packed = u16::from_be_bytes([data[i], data[i + 1]]) as usize;
+                tokens.push(Token::Reference {
+                    distance: (packed >> 4) + 1,
+                    length: (packed & 0xf) + MIN_MATCH,
+                });
+                i += 2;
+            }
+        }
+    }
+    lz77_detokenize(&tokens)
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use crate::rng::{RandomSource, SplitMix64};
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "run-length encoding stores the length and the byte of each run",
+        rle_encode(b"aaabccdddd"),
+        b"\x03a\x01b\x02c\x04d".to_vec()
+    );
+    test!(
+        harness,
+        "runs longer than 255 bytes are split",
+        rle_encode(&[7; 300]),
+        vec![255, 7, 45, 7]
+    );
+    test!(
+        harness,
+        "run-length decoding rejects an odd number of bytes",
+        rle_decode(&[3, b'a', 1]),
+        Err(DecompressError::Truncated)
+    );
+    test!(
+        harness,
+        "run-length decoding rejects runs of zero length",
+        rle_decode(&[3, b'a', 0, b'b']),
+        Err(DecompressError::EmptyRun { position: 2 })
+    );
+
+    test!(
+        harness,
+        "LZ77 replaces a repetition with a reference",
+        lz77_tokenize(b"abcabcabcx"),
+        vec![
+            Token::Literal(b'a'),
+            Token::Literal(b'b'),
+            Token::Literal(b'c'),
+            Token::Reference {
+                distance: 3,
+                length: 6
+            },
+            Token::Literal(b'x'),
+        ]
+    );
+    test!(
+        harness,
+        "LZ77 decoding rejects references before the start",
+        lz77_detokenize(&[
+            Token::Literal(b'a'),
+            Token::Reference {
+                distance: 2,
+                length: 3
+            }
+        ]),
+        Err(DecompressError::InvalidDistance {
+            position: 1,
+            distance: 2
+        })
+    );
+    test!(
+        harness,
+        "LZ77 decoding rejects a reference cut in half",
+        lz77_decode(&[0x02, b'a', 0x00]),
+        Err(DecompressError::Truncated)
+    );
+
+    let text = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do \
+                eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim \
+                ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut \
+                aliquip ex ea commodo consequat. Duis aute irure dolor in \
+                reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla \
+                pariatur. Excepteur sint occaecat cupidatat non proident, sunt in \
+                culpa qui officia deserunt mollit anim id est laborum."
+        .repeat(3);
+    let counter: Vec<u8> = (0..1024u32).flat_map(|i| (i / 16).to_le_bytes()).collect();
+    let mut image = vec![0u8; 2048];
+    for (i, pixel) in image.iter_mut().enumerate() {
+        if (i % 64) / 8 == (i / 64) % 8 {
+            *pixel = 0xff;
+        }
+    }
+    let mut noise = vec![0u8; 2048];
+    SplitMix64::new(2026).fill_bytes(&mut noise);
+
+    let inputs: [(&str, &[u8]); 5] = [
+        ("empty", b""),
+        ("text", text.as_bytes()),
+        ("counter", &counter),
+        ("bitmap", &image),
+        ("noise", &noise),
+    ];
+    let mut rows = Vec::new();
+    for (name, data) in inputs {
+        let rle = rle_encode(data);
+        let lz77 = lz77_encode(data);
+        let description = format!("run-length encoding round-trips the {} input", name);
+        test!(harness, &description, rle_decode(&rle), Ok(data.to_vec()));
+        let description = format!("LZ77 round-trips the {} input", name);
+        test!(harness, &description, lz77_decode(&lz77), Ok(data.to_vec()));
+        rows.push((name, data.len(), rle.len(), lz77.len()));
+    }
+
+    note!(
+        harness,
+        "{:<8} {:>8} {:>8} {:>8} {:>8}",
+        "Input",
+        "Size",
+        "RLE",
+        "LZ77",
+        "Ratio"
+    );
+    for (name, size, rle, lz77) in rows {
+        let ratio = if size == 0 {
+            1.0
+        } else {
+            lz77 as f64 / size as f64
+        };
+        note!(
+            harness,
+            "{:<8} {:>8} {:>8} {:>8} {:>7.1}%",
+            name,
+            size,
+            rle,
+            lz77,
+            ratio * 100.0
+        );
+    }
+    note!(harness);
+
+    test!(
+        harness,
+        "LZ77 compresses repetitive text to less than half of its size",
+        lz77_encode(text.as_bytes()).len() < text.len() / 2,
+        true
+    );
+}
@@ -0,0 +1,324 @@
+// crc.rs
+// Table-driven cyclic redundancy check calculation, which is generic over the
+// width of the register (8, 16, 32, or 64 bits). Every CRC algorithm is
+// described by its parameters in the Rocksoft model: the polynomial, the
+// initial value of the register, whether the input bytes and the output value
+// are reflected, and the value the result is XORed with. A few well-known
+// algorithms are available as presets.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt::{Debug, LowerHex};
+use std::ops::{BitAnd, BitXor, Shl, Shr};
+
+pub trait Register:
+    Copy
+    + Eq
+    + Debug
+    + LowerHex
+    + BitAnd<Output = Self>
+    + BitXor<Output = Self>
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+{
+    const BITS: u32;
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn from_byte(byte: u8) -> Self;
+    fn low_byte(self) -> u8;
+    fn reverse(self) -> Self;
+    // Shifting all bits out of the register overflows for the primitive shift
+    // operators, so the 8-bit register needs special treatment.
+    fn shift_left_byte(self) -> Self;
+    fn shift_right_byte(self) -> Self;
+}
+
+macro_rules! impl_register {
+    ($type:ty) => {
+        impl Register for $type {
+            const BITS: u32 = <$type>::BITS;
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn from_byte(byte: u8) -> Self {
+                byte as Self
+            }
+
+            fn low_byte(self) -> u8 {
+                self as u8
+            }
+
+            fn reverse(self) -> Self {
+                self.reverse_bits()
+            }
+
+            fn shift_left_byte(self) -> Self {
+                self.checked_shl(8).unwrap_or(0)
+            }
+
+            fn shift_right_byte(self) -> Self {
+                self.checked_shr(8).unwrap_or(0)
+            }
+        }
+    };
+}
+
+impl_register!(u8);
+impl_register!(u16);
+impl_register!(u32);
+impl_register!(u64);
+
+#[derive(Clone, Copy, Debug)]
+pub struct CrcParameters<R: Register> {
+    pub name: &'static str,
+    pub polynomial: R,
+    pub init: R,
+    pub reflect_input: bool,
+    pub reflect_output: bool,
+    pub xor_output: R,
+    // The checksum of the ASCII string "123456789".
+    pub check: R,
+}
+
+pub const CRC_8_SMBUS: CrcParameters<u8> = CrcParameters {
+    name: "CRC-8/SMBUS",
+    polynomial: 0x07,
+    init: 0x00,
+    reflect_input: false,
+    reflect_output: false,
+    xor_output: 0x00,
+    check: 0xf4,
+};
+
+pub const CRC_8_MAXIM: CrcParameters<u8> = CrcParameters {
+    name: "CRC-8/MAXIM",
+    polynomial: 0x31,
+    init: 0x00,
+    reflect_input: true,
+    reflect_output: true,
+    xor_output: 0x00,
+    check: 0xa1,
+};
+
+pub const CRC_16_CCITT: CrcParameters<u16> = CrcParameters {
+    name: "CRC-16/CCITT",
+    polynomial: 0x1021,
+    init: 0x0000,
+    reflect_input: true,
+    reflect_output: true,
+    xor_output: 0x0000,
+    check: 0x2189,
+};
+
+pub const CRC_16_CCITT_FALSE: CrcParameters<u16> = CrcParameters {
+    name: "CRC-16/CCITT-FALSE",
+    polynomial: 0x1021,
+    init: 0xffff,
+    reflect_input: false,
+    reflect_output: false,
+    xor_output: 0x0000,
+    check: 0x29b1,
+};
+
+pub const CRC_32_IEEE: CrcParameters<u32> = CrcParameters {
+    name: "CRC-32/IEEE",
+    polynomial: 0x04c11db7,
+    init: 0xffffffff,
+    reflect_input: true,
+    reflect_output: true,
+    xor_output: 0xffffffff,
+    check: 0xcbf43926,
+};
+
+pub const CRC_32C: CrcParameters<u32> = CrcParameters {
+    name: "CRC-32C",
+    polynomial: 0x1edc6f41,
+    init: 0xffffffff,
+    reflect_input: true,
+    reflect_output: true,
+    xor_output: 0xffffffff,
+    check: 0xe3069283,
+};
+
+pub const CRC_64_ECMA: CrcParameters<u64> = CrcParameters {
+    name: "CRC-64/ECMA",
+    polynomial: 0x42f0e1eba9ea3693,
+    init: 0x0000000000000000,
+    reflect_input: false,
+    reflect_output: false,
+    xor_output: 0x0000000000000000,
+    check: 0x6c40df5f0b497347,
+};
+
+pub const CRC_64_XZ: CrcParameters<u64> = CrcParameters {
+    name: "CRC-64/XZ",
+    polynomial: 0x42f0e1eba9ea3693,
+    init: 0xffffffffffffffff,
+    reflect_input: true,
+    reflect_output: true,
+    xor_output: 0xffffffffffffffff,
+    check: 0x995dc9bbdf1939fa,
+};
+
+pub struct Crc<R: Register> {
+    parameters: CrcParameters<R>,
+    table: [R; 256],
+}
+
+impl<R: Register> Crc<R> {
+    pub fn new(parameters: CrcParameters<R>) -> Self {
+        let mut table = [R::ZERO; 256];
+        let top_bit = R::ONE << (R::BITS - 1);
+
+        // Reflected algorithms shift the register to the right, so the table
+        // is built from the reflected polynomial, and the byte is placed into
+        // the low bits of the register instead of the high bits.
+        for (i, entry) in table.iter_mut().enumerate() {
+            let mut register;
+            if parameters.reflect_input {
+                let polynomial = parameters.polynomial.reverse();
+                register = R::from_byte(i as u8);
+                for _ in 0..8 {
+                    register = if register & R::ONE != R::ZERO {
+                        (register >> 1) ^ polynomial
+                    } else {
+                        register >> 1
+                    };
+                }
+            } else {
+                register = R::from_byte(i as u8) << (R::BITS - 8);
+                for _ in 0..8 {
+                    register = if register & top_bit != R::ZERO {
+                        (register << 1) ^ parameters.polynomial
+                    } else {
+                        register << 1
+                    };
+                }
+            }
+            *entry = register;
+        }
+
+        Self { parameters, table }
+    }
+
+    pub fn parameters(&self) -> &CrcParameters<R> {
+        &self.parameters
+    }
+
+    pub fn digest(&self) -> CrcDigest<'_, R> {
+        let register = if self.parameters.reflect_input {
+            self.parameters.init.reverse()
+        } else {
+            self.parameters.init
+        };
+        CrcDigest {
+            crc: self,
+            register,
+        }
+    }
+
+    pub fn checksum(&self, data: &[u8]) -> R {
+        let mut digest = self.digest();
+        digest.update(data);
+        digest.finalize()
+    }
+}
+
+pub struct CrcDigest<'a, R: Register> {
+    crc: &'a Crc<R>,
+    register: R,
+}
+
+impl<R: Register> CrcDigest<'_, R> {
+    pub fn update(&mut self, data: &[u8]) {
+        let table = &self.crc.table;
+        for byte in data {
+            self.register = if self.crc.parameters.reflect_input {
+                let index = (self.register.low_byte() ^ byte) as usize;
+                table[index] ^ self.register.shift_right_byte()
+            } else {
+                let index = ((self.register >> (R::BITS - 8)).low_byte() ^ byte) as usize;
+                table[index] ^ self.register.shift_left_byte()
+            };
+        }
+    }
+
+    pub fn finalize(self) -> R {
+        let parameters = &self.crc.parameters;
+        let register = if parameters.reflect_input != parameters.reflect_output {
+            self.register.reverse()
+        } else {
+            self.register
+        };
+        register ^ parameters.xor_output
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+fn check<R: Register>(harness: &mut Harness, parameters: CrcParameters<R>) {
+    let crc = Crc::new(parameters);
+    let checksum = crc.checksum(b"123456789");
+    let description = format!("{} of \"123456789\" is the check value", parameters.name);
+    test!(harness, &description, checksum, parameters.check);
+
+    let mut digest = crc.digest();
+    for chunk in b"123456789".chunks(2) {
+        digest.update(chunk);
+    }
+    let description = format!("{} calculated in chunks is the same", parameters.name);
+    test!(harness, &description, digest.finalize(), checksum);
+}
+
+pub fn demo(harness: &mut Harness) {
+    check(harness, CRC_8_SMBUS);
+    check(harness, CRC_8_MAXIM);
+    check(harness, CRC_16_CCITT);
+    check(harness, CRC_16_CCITT_FALSE);
+    check(harness, CRC_32_IEEE);
+    check(harness, CRC_32C);
+    check(harness, CRC_64_ECMA);
+    check(harness, CRC_64_XZ);
+
+    let crc32 = Crc::new(CRC_32_IEEE);
+    test!(
+        harness,
+        "CRC-32/IEEE of the empty input is zero",
+        crc32.checksum(b""),
+        0
+    );
+    test!(
+        harness,
+        "CRC-32/IEEE of the quick brown fox matches the known value",
+        crc32.checksum(b"The quick brown fox jumps over the lazy dog"),
+        0x414fa339
+    );
+
+    let custom = Crc::<u16>::new(CrcParameters {
+        name: "CRC-16/ARC",
+        polynomial: 0x8005,
+        init: 0x0000,
+        reflect_input: true,
+        reflect_output: true,
+        xor_output: 0x0000,
+        check: 0xbb3d,
+    });
+    note!(
+        harness,
+        "{} with a custom configuration of 123456789: {:#06x}\n",
+        custom.parameters().name,
+        custom.checksum(b"123456789")
+    );
+    test!(
+        harness,
+        "a custom configuration (CRC-16/ARC) yields its check value",
+        custom.checksum(b"123456789"),
+        custom.parameters().check
+    );
+}
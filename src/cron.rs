@@ -0,0 +1,436 @@
+// cron.rs
+// Parsing the five-field expressions of cron, and computing when they fire.
+// The fields are the minute, the hour, the day of the month, the month, and
+// the day of the week, each of which is a comma-separated list of values,
+// ranges like 9-17, or the asterisk for every value, optionally followed by a
+// step, like */15 for every fifteenth minute. Months and weekdays can also be
+// given by the first three letters of their English names, and Sunday is both
+// 0 and 7. The common shorthands, like @daily, stand for their expressions.
+// Each field is stored as a bit set of the values it allows. As in the cron of
+// Paul Vixie, a date matches if either of the day fields does, when both of
+// them are restricted, but both of them otherwise. The next fire times are
+// found with the calendar of the datetime snippet, going day by day, skipping
+// the days which do not match, and then searching the hours and minutes.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::datetime::{Date, DateTime, Time};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    WrongFieldCount,
+    InvalidValue,
+    OutOfRange,
+    InvalidRange,
+    InvalidStep,
+    UnknownShorthand,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CronError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for CronError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.kind {
+            ErrorKind::WrongFieldCount => "expected five fields",
+            ErrorKind::InvalidValue => "invalid value",
+            ErrorKind::OutOfRange => "value out of range",
+            ErrorKind::InvalidRange => "range ends before it starts",
+            ErrorKind::InvalidStep => "invalid step",
+            ErrorKind::UnknownShorthand => "unknown shorthand",
+        };
+        write!(f, "{} at position {}", message, self.position)
+    }
+}
+
+impl std::error::Error for CronError {}
+
+struct FieldSpec {
+    minimum: u32,
+    maximum: u32,
+    // The names of the values from the minimum.
+    names: &'static [&'static str],
+}
+
+#[rustfmt::skip]
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+// The weekdays go up to 7, which is Sunday again.
+const FIELDS: [FieldSpec; 5] = [
+    FieldSpec {
+        minimum: 0,
+        maximum: 59,
+        names: &[],
+    },
+    FieldSpec {
+        minimum: 0,
+        maximum: 23,
+        names: &[],
+    },
+    FieldSpec {
+        minimum: 1,
+        maximum: 31,
+        names: &[],
+    },
+    FieldSpec {
+        minimum: 1,
+        maximum: 12,
+        names: &MONTHS,
+    },
+    FieldSpec {
+        minimum: 0,
+        maximum: 7,
+        names: &WEEKDAYS,
+    },
+];
+
+const SHORTHANDS: [(&str, &str); 7] = [
+    ("@yearly", "0 0 1 1 *"),
+    ("@annually", "0 0 1 1 *"),
+    ("@monthly", "0 0 1 * *"),
+    ("@weekly", "0 0 * * 0"),
+    ("@daily", "0 0 * * *"),
+    ("@midnight", "0 0 * * *"),
+    ("@hourly", "0 * * * *"),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+fn value(text: &str, spec: &FieldSpec, position: usize) -> Result<u32, CronError> {
+    let value = match spec
+        .names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(text))
+    {
+        Some(index) => index as u32 + spec.minimum,
+        None => text.parse().map_err(|_| CronError {
+            kind: ErrorKind::InvalidValue,
+            position,
+        })?,
+    };
+    if value < spec.minimum || value > spec.maximum {
+        return Err(CronError {
+            kind: ErrorKind::OutOfRange,
+            position,
+        });
+    }
+    Ok(value)
+}
+
+// The bit set of a field, where the offset is the position of the field in the
+// expression.
+fn field(text: &str, spec: &FieldSpec, offset: usize) -> Result<u64, CronError> {
+    let mut bits = 0;
+    let mut start = offset;
+    for item in text.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let position = start + range.len() + 1;
+                let step: u32 = step.parse().map_err(|_| CronError {
+                    kind: ErrorKind::InvalidStep,
+                    position,
+                })?;
+                if step == 0 {
+                    return Err(CronError {
+                        kind: ErrorKind::InvalidStep,
+                        position,
+                    });
+                }
+                (range, step)
+            }
+            None => (item, 1),
+        };
+        let (first, last) = if range == "*" {
+            (spec.minimum, spec.maximum)
+        } else if let Some((first, last)) = range.split_once('-') {
+            let low = value(first, spec, start)?;
+            let high = value(last, spec, start + first.len() + 1)?;
+            if high < low {
+                return Err(CronError {
+                    kind: ErrorKind::InvalidRange,
+                    position: start,
+                });
+            }
+            (low, high)
+        } else {
+            let value = value(range, spec, start)?;
+            // A single value with a step runs to the end of the field.
+            (value, if step > 1 { spec.maximum } else { value })
+        };
+        for value in (first..=last).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+        start += item.len() + 1;
+    }
+    Ok(bits)
+}
+
+impl Schedule {
+    pub fn parse(expression: &str) -> Result<Self, CronError> {
+        let trimmed = expression.trim();
+        if trimmed.starts_with('@') {
+            let (_, expanded) = SHORTHANDS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(trimmed))
+                .ok_or(CronError {
+                    kind: ErrorKind::UnknownShorthand,
+                    position: trimmed.as_ptr() as usize - expression.as_ptr() as usize,
+                })?;
+            return Self::parse(expanded);
+        }
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != FIELDS.len() {
+            return Err(CronError {
+                kind: ErrorKind::WrongFieldCount,
+                position: 0,
+            });
+        }
+        let mut bits = [0; 5];
+        for ((bits, text), spec) in bits.iter_mut().zip(&fields).zip(&FIELDS) {
+            let offset = text.as_ptr() as usize - expression.as_ptr() as usize;
+            *bits = field(text, spec, offset)?;
+        }
+        // Sunday is both 0 and 7.
+        let weekdays = (bits[4] | bits[4] >> 7) & 0x7f;
+        Ok(Self {
+            minutes: bits[0],
+            hours: bits[1],
+            days: bits[2],
+            months: bits[3],
+            weekdays,
+            days_restricted: !fields[2].starts_with('*'),
+            weekdays_restricted: !fields[4].starts_with('*'),
+        })
+    }
+
+    fn matches_date(&self, date: &Date) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << (date.weekday().number() % 7)) != 0;
+        if self.days_restricted && self.weekdays_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    pub fn matches(&self, time: &DateTime) -> bool {
+        self.matches_date(&time.date)
+            && self.hours & (1 << time.time.hour) != 0
+            && self.minutes & (1 << time.time.minute) != 0
+    }
+
+    // The first time strictly after the given one, in the same offset, or None
+    // if there is no such time in the next 400 years, like on February 30th.
+    pub fn next_after(&self, after: &DateTime) -> Option<DateTime> {
+        let mut date = after.date;
+        let mut first = after.time.hour as u32 * 60 + after.time.minute as u32 + 1;
+        // The calendar repeats itself every 400 years.
+        for _ in 0..=146097 {
+            if first < 24 * 60 && self.matches_date(&date) {
+                for hour in first / 60..24 {
+                    if self.hours & (1 << hour) == 0 {
+                        continue;
+                    }
+                    let start = if hour == first / 60 { first % 60 } else { 0 };
+                    if let Some(minute) = (start..60).find(|m| self.minutes & (1 << m) != 0) {
+                        return Some(DateTime {
+                            date,
+                            time: Time::new(hour as u8, minute as u8, 0, 0).unwrap(),
+                            offset: after.offset,
+                        });
+                    }
+                }
+            }
+            date = date.add_days(1);
+            first = 0;
+        }
+        None
+    }
+
+    pub fn upcoming(&self, after: &DateTime, count: usize) -> Vec<DateTime> {
+        let mut times = Vec::new();
+        let mut last = *after;
+        while times.len() < count {
+            match self.next_after(&last) {
+                Some(next) => {
+                    times.push(next);
+                    last = next;
+                }
+                None => break,
+            }
+        }
+        times
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = CronError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        Schedule::parse(expression)
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+fn times(schedule: &str, after: &str, count: usize) -> Vec<String> {
+    let schedule = Schedule::parse(schedule).unwrap();
+    schedule
+        .upcoming(&after.parse().unwrap(), count)
+        .iter()
+        .map(|time| time.to_string())
+        .collect()
+}
+
+pub fn demo(harness: &mut Harness) {
+    let now = "2026-10-14T10:17:00Z";
+    test!(
+        harness,
+        "steps within ranges on weekdays",
+        times("*/15 9-17 * * MON-FRI", now, 4),
+        [
+            "2026-10-14T10:30:00Z",
+            "2026-10-14T10:45:00Z",
+            "2026-10-14T11:00:00Z",
+            "2026-10-14T11:15:00Z"
+        ]
+    );
+    test!(
+        harness,
+        "skipping to the next working day",
+        times("*/15 9-17 * * MON-FRI", "2026-10-16T17:50:00Z", 1),
+        ["2026-10-19T09:00:00Z"]
+    );
+    test!(
+        harness,
+        "either day field matches when both are restricted",
+        times("30 4 1,15 * 5", now, 5),
+        [
+            "2026-10-15T04:30:00Z",
+            "2026-10-16T04:30:00Z",
+            "2026-10-23T04:30:00Z",
+            "2026-10-30T04:30:00Z",
+            "2026-11-01T04:30:00Z"
+        ]
+    );
+    test!(
+        harness,
+        "shorthands and names",
+        [
+            times("@weekly", now, 2),
+            times("0 0 1 jan *", now, 1),
+            times("0 12 * * 7", now, 1)
+        ]
+        .concat(),
+        [
+            "2026-10-18T00:00:00Z",
+            "2026-10-25T00:00:00Z",
+            "2027-01-01T00:00:00Z",
+            "2026-10-18T12:00:00Z"
+        ]
+    );
+    test!(
+        harness,
+        "leap days, and days that never come",
+        (
+            times("0 0 29 2 *", now, 2),
+            times("0 0 30 2 *", now, 1).len()
+        ),
+        (
+            vec![
+                "2028-02-29T00:00:00Z".to_string(),
+                "2032-02-29T00:00:00Z".to_string()
+            ],
+            0
+        )
+    );
+    test!(
+        harness,
+        "the times are strictly after the start, in its offset",
+        times("17 10 * * *", "2026-10-14T10:17:00+02:00", 1),
+        ["2026-10-15T10:17:00+02:00"]
+    );
+    test!(
+        harness,
+        "a single value with a step runs to the end of the field",
+        times("5/20 3 * * *", now, 3),
+        [
+            "2026-10-15T03:05:00Z",
+            "2026-10-15T03:25:00Z",
+            "2026-10-15T03:45:00Z"
+        ]
+    );
+    let schedule = Schedule::parse("0 9 * * 1-5").unwrap();
+    test!(
+        harness,
+        "matching a time",
+        (
+            schedule.matches(&"2026-10-14T09:00:00Z".parse().unwrap()),
+            schedule.matches(&"2026-10-17T09:00:00Z".parse().unwrap())
+        ),
+        (true, false)
+    );
+
+    let error = |kind, position| Err::<Schedule, _>(CronError { kind, position });
+    test!(
+        harness,
+        "invalid expressions",
+        [
+            Schedule::parse("* * * *"),
+            Schedule::parse("60 * * * *"),
+            Schedule::parse("* * * FOO *"),
+            Schedule::parse("* 17-9 * * *"),
+            Schedule::parse("*/0 * * * *"),
+            Schedule::parse("0 0 1,2,x * *"),
+            Schedule::parse("@sometimes"),
+        ],
+        [
+            error(ErrorKind::WrongFieldCount, 0),
+            error(ErrorKind::OutOfRange, 0),
+            error(ErrorKind::InvalidValue, 6),
+            error(ErrorKind::InvalidRange, 2),
+            error(ErrorKind::InvalidStep, 2),
+            error(ErrorKind::InvalidValue, 8),
+            error(ErrorKind::UnknownShorthand, 0)
+        ]
+    );
+
+    for expression in [
+        "*/15 9-17 * * MON-FRI",
+        "0 0 1 */3 *",
+        "30 2 * * SUN",
+        "0 0 13 * FRI",
+        "@hourly",
+    ] {
+        note!(harness, "{}:", expression);
+        for time in times(expression, now, 3) {
+            note!(harness, "    {}", time);
+        }
+    }
+}
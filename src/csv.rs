@@ -0,0 +1,299 @@
+// csv.rs
+// Reading and writing comma-separated values, following the quoting rules of
+// RFC 4180. Each record is a line of fields, separated by commas. A field which
+// contains a comma, a double quote, or a line break has to be enclosed in
+// double quotes, and the quotes inside it are doubled. Therefore, a record can
+// span multiple lines of text. The parser is a small state machine, processing
+// the input one character at a time, which accepts both CRLF and LF line
+// endings. The writer quotes only the fields that need it, and ends records
+// with CRLF.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnterminatedQuote,
+    QuoteInUnquotedField,
+    CharacterAfterQuote(char),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnterminatedQuote => write!(f, "the quoted field is not closed"),
+            ErrorKind::QuoteInUnquotedField => write!(f, "quote in an unquoted field"),
+            ErrorKind::CharacterAfterQuote(c) => {
+                write!(f, "unexpected character {:?} after a closing quote", c)
+            }
+        }
+    }
+}
+
+// The position is the line and the column of the text, not the number of
+// the record, as quoted fields can contain line breaks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ErrorKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.kind, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type Record = Vec<String>;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    FieldStart,
+    Unquoted,
+    Quoted,
+    QuoteInQuoted,
+}
+
+pub fn parse(text: &str) -> Result<Vec<Record>, ParseError> {
+    parse_with(text, ',')
+}
+
+pub fn parse_with(text: &str, delimiter: char) -> Result<Vec<Record>, ParseError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut state = State::FieldStart;
+    // The record has started, even if all of its fields are empty so far.
+    let mut started = false;
+    let (mut line, mut column) = (1, 0);
+    let mut quote_position = (0, 0);
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        column += 1;
+        let error = |kind| ParseError { kind, line, column };
+        let line_break = c == '\n' || (c == '\r' && chars.peek() == Some(&'\n'));
+        if c == '\r' && line_break {
+            chars.next();
+        }
+        if state != State::Quoted {
+            if line_break {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+                (line, column) = (line + 1, 0);
+                state = State::FieldStart;
+                started = false;
+                continue;
+            }
+            if c == delimiter {
+                record.push(std::mem::take(&mut field));
+                state = State::FieldStart;
+                started = true;
+                continue;
+            }
+        }
+        started = true;
+        state = match (state, c) {
+            (State::FieldStart, '"') => {
+                quote_position = (line, column);
+                State::Quoted
+            }
+            (State::Unquoted, '"') => {
+                return Err(error(ErrorKind::QuoteInUnquotedField));
+            }
+            (State::FieldStart | State::Unquoted, c) => {
+                field.push(c);
+                State::Unquoted
+            }
+            (State::Quoted, '"') => State::QuoteInQuoted,
+            (State::Quoted, c) => {
+                field.push(c);
+                if line_break {
+                    (line, column) = (line + 1, 0);
+                    if c == '\r' {
+                        field.push('\n');
+                    }
+                }
+                State::Quoted
+            }
+            (State::QuoteInQuoted, '"') => {
+                field.push('"');
+                State::Quoted
+            }
+            (State::QuoteInQuoted, c) => return Err(error(ErrorKind::CharacterAfterQuote(c))),
+        };
+    }
+
+    if state == State::Quoted {
+        let (line, column) = quote_position;
+        return Err(ParseError {
+            kind: ErrorKind::UnterminatedQuote,
+            line,
+            column,
+        });
+    }
+    if started {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+pub fn needs_quotes(field: &str, delimiter: char) -> bool {
+    field.contains([delimiter, '"', '\r', '\n'])
+}
+
+pub fn write(records: &[Record]) -> String {
+    write_with(records, ',')
+}
+
+pub fn write_with(records: &[Record], delimiter: char) -> String {
+    let mut out = String::new();
+    for record in records {
+        for (index, field) in record.iter().enumerate() {
+            if index > 0 {
+                out.push(delimiter);
+            }
+            if needs_quotes(field, delimiter) {
+                out.push('"');
+                out.push_str(&field.replace('"', "\"\""));
+                out.push('"');
+            } else {
+                out.push_str(field);
+            }
+        }
+        out.push_str("\r\n");
+    }
+    out
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+fn records(rows: &[&[&str]]) -> Vec<Record> {
+    rows.iter()
+        .map(|row| row.iter().map(|field| field.to_string()).collect())
+        .collect()
+}
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "simple records",
+        parse("name,age\nAlice,30\nBob,25\n"),
+        Ok(records(&[
+            &["name", "age"],
+            &["Alice", "30"],
+            &["Bob", "25"]
+        ]))
+    );
+    test!(
+        harness,
+        "CRLF line endings, and no line break after the last record",
+        parse("a,b\r\nc,d"),
+        Ok(records(&[&["a", "b"], &["c", "d"]]))
+    );
+    test!(
+        harness,
+        "empty fields are kept",
+        parse(",a,,\n"),
+        Ok(records(&[&["", "a", "", ""]]))
+    );
+    test!(
+        harness,
+        "quoted fields contain commas, quotes, and line breaks",
+        parse("\"Doe, John\",\"say \"\"hi\"\"\",\"two\r\nlines\"\n"),
+        Ok(records(&[&["Doe, John", "say \"hi\"", "two\r\nlines"]]))
+    );
+    test!(
+        harness,
+        "spaces are part of the field",
+        parse(" a , b "),
+        Ok(records(&[&[" a ", " b "]]))
+    );
+    test!(
+        harness,
+        "another delimiter",
+        parse_with("a;b,c;\"d;e\"", ';'),
+        Ok(records(&[&["a", "b,c", "d;e"]]))
+    );
+    test!(
+        harness,
+        "the empty text has no records",
+        parse(""),
+        Ok(Vec::new())
+    );
+
+    let tricky = records(&[
+        &["id", "text", "note"],
+        &["1", "plain", ""],
+        &["2", "comma, inside", "\"quoted\""],
+        &["3", "multi\nline\nfield", "trailing space "],
+        &["4", "", "\"\""],
+        &["5", "árvíztűrő tükörfúrógép", "🦀,🦀"],
+    ]);
+    let written = write(&tricky);
+    test!(
+        harness,
+        "only the fields which need it are quoted",
+        written.lines().nth(2),
+        Some("2,\"comma, inside\",\"\"\"quoted\"\"\"")
+    );
+    test!(
+        harness,
+        "tricky records survive a round trip",
+        parse(&written),
+        Ok(tricky.clone())
+    );
+    test!(
+        harness,
+        "the written text does not change on a second round trip",
+        write(&parse(&written).unwrap()),
+        written.clone()
+    );
+
+    let broken = [
+        (
+            "a,\"b\nc,d",
+            "the quoted field is not closed at line 1, column 3",
+        ),
+        ("a,b\"c\n", "quote in an unquoted field at line 1, column 4"),
+        (
+            "x\n\"a\"b,c",
+            "unexpected character 'b' after a closing quote at line 2, column 4",
+        ),
+        (
+            "\"multi\nline\" ,x",
+            "unexpected character ' ' after a closing quote at line 2, column 6",
+        ),
+    ];
+    let mut errors = Vec::new();
+    for (text, expected) in broken {
+        let error = parse(text).unwrap_err().to_string();
+        test!(
+            harness,
+            &format!("the error in {:?}", text),
+            error.clone(),
+            expected
+        );
+        errors.push((text, error));
+    }
+
+    note!(harness, "{}", written.replace("\r\n", "\u{21b5}\n"));
+    for (text, error) in errors {
+        note!(harness, "{:<20} {}", format!("{:?}", text), error);
+    }
+    note!(harness);
+}
@@ -0,0 +1,771 @@
+// datetime.rs
+// Dates and times of the proleptic Gregorian calendar, without any external
+// dependencies. Dates are converted to and from the number of days since the
+// Unix epoch of 1970-01-01 with the algorithms of Howard Hinnant, which shift
+// the start of the year to March, so that the leap day is the last day of the
+// year, and split the days into eras of 400 years, which always have 146097
+// days. Everything else builds on these: the weekday is the day count modulo
+// seven, the ISO week date is counted from the Monday of the week with the
+// first Thursday of the year, and date differences are subtractions of day
+// counts. Calendar differences in years, months, and days, and adding months,
+// clamp the day to the end of shorter months, like most libraries do. Date and
+// time values, with optional UTC offsets, are parsed from, and formatted to the
+// common extended form of ISO 8601, and converted to Unix timestamps.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+use std::str::FromStr;
+
+pub fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// The number of days since 1970-01-01, which is negative before it.
+pub fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let year = year as i64 - (month <= 2) as i64;
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = month as i64;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+// None if the year does not fit in an i32, about 784 billion days from 1970.
+pub fn civil_from_days(days: i64) -> Option<(i32, u8, u8)> {
+    let days = days.checked_add(719468)?;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u8;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u8;
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    Some((i32::try_from(year).ok()?, month, day))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    // The number of the day in ISO 8601, from 1 for Monday to 7 for Sunday.
+    pub fn number(&self) -> u8 {
+        *self as u8 + 1
+    }
+}
+
+impl fmt::Display for Weekday {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+// A calendar difference, where all fields have the same sign.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Period {
+    pub years: i32,
+    pub months: i32,
+    pub days: i32,
+}
+
+// The fields are ordered from the year, so comparisons are chronological.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Self { year, month, day })
+    }
+
+    pub fn from_days(days: i64) -> Option<Self> {
+        let (year, month, day) = civil_from_days(days)?;
+        Some(Self { year, month, day })
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn days(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day)
+    }
+
+    // 1970-01-01 was a Thursday.
+    pub fn weekday(&self) -> Weekday {
+        Weekday::ALL[(self.days() + 3).rem_euclid(7) as usize]
+    }
+
+    pub fn day_of_year(&self) -> u16 {
+        (self.days() - days_from_civil(self.year, 1, 1) + 1) as u16
+    }
+
+    // The ISO week-numbering year, and the number of the week, where the first
+    // week of a year is the one with its first Thursday.
+    // Panics for the first and last few days of the years of an i32, whose
+    // Thursday is out of range.
+    pub fn iso_week(&self) -> (i32, u8) {
+        let thursday = Date::from_days(self.days() - self.weekday() as i64 + 3)
+            .expect("the Thursday of the week is in range");
+        (thursday.year, ((thursday.day_of_year() - 1) / 7 + 1) as u8)
+    }
+
+    // Panics if the result is out of the years of an i32, like the arithmetic
+    // of the integers.
+    pub fn add_days(&self, days: i64) -> Self {
+        self.days()
+            .checked_add(days)
+            .and_then(Date::from_days)
+            .expect("the date is in range")
+    }
+
+    // Clamps the day to the length of the month, like 2024-01-31 plus a month
+    // is 2024-02-29. Panics if the year is out of the range of an i32.
+    pub fn add_months(&self, months: i32) -> Self {
+        let total = self.year as i64 * 12 + self.month as i64 - 1 + months as i64;
+        let year = i32::try_from(total.div_euclid(12)).expect("the year is in range");
+        let month = total.rem_euclid(12) as u8 + 1;
+        let day = self.day.min(days_in_month(year, month));
+        Self { year, month, day }
+    }
+
+    pub fn days_until(&self, other: &Date) -> i64 {
+        other.days() - self.days()
+    }
+
+    // The whole months and years between the dates, and the days left over,
+    // which are negative if the other date is earlier.
+    pub fn period_until(&self, other: &Date) -> Period {
+        if other < self {
+            let period = other.period_until(self);
+            return Period {
+                years: -period.years,
+                months: -period.months,
+                days: -period.days,
+            };
+        }
+        let mut months = (other.year - self.year) * 12 + other.month as i32 - self.month as i32;
+        if self.add_months(months) > *other {
+            months -= 1;
+        }
+        Period {
+            years: months / 12,
+            months: months % 12,
+            days: self.add_months(months).days_until(other) as i32,
+        }
+    }
+}
+
+// Years outside of 0000 to 9999 have a sign, and at least four digits.
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if (0..=9999).contains(&self.year) {
+            write!(f, "{:04}", self.year)?;
+        } else {
+            write!(f, "{:+05}", self.year)?;
+        }
+        write!(f, "-{:02}-{:02}", self.month, self.day)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub nanosecond: u32,
+}
+
+impl Time {
+    pub fn new(hour: u8, minute: u8, second: u8, nanosecond: u32) -> Option<Self> {
+        if hour > 23 || minute > 59 || second > 59 || nanosecond > 999_999_999 {
+            return None;
+        }
+        Some(Self {
+            hour,
+            minute,
+            second,
+            nanosecond,
+        })
+    }
+
+    pub fn seconds(&self) -> i64 {
+        self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64
+    }
+}
+
+// The fraction of the second is written in groups of three digits, as few as
+// needed.
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        let mut fraction = self.nanosecond;
+        let mut digits = 9;
+        while fraction != 0 && fraction.is_multiple_of(1000) {
+            fraction /= 1000;
+            digits -= 3;
+        }
+        if fraction != 0 {
+            write!(f, ".{:0width$}", fraction, width = digits)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DateTime {
+    pub date: Date,
+    pub time: Time,
+    // The offset from UTC in minutes, or None for local time.
+    pub offset: Option<i16>,
+}
+
+impl DateTime {
+    // Local times are treated as UTC.
+    pub fn unix_timestamp(&self) -> i64 {
+        self.date.days() * 86400 + self.time.seconds() - self.offset.unwrap_or(0) as i64 * 60
+    }
+
+    // Panics beyond the years of an i32, about 68 million billion seconds from
+    // the epoch, which is less than the range of the timestamps.
+    pub fn from_unix_timestamp(timestamp: i64) -> Self {
+        let (days, seconds) = (timestamp.div_euclid(86400), timestamp.rem_euclid(86400));
+        Self {
+            date: Date::from_days(days).expect("the date is in range"),
+            time: Time {
+                hour: (seconds / 3600) as u8,
+                minute: (seconds / 60 % 60) as u8,
+                second: (seconds % 60) as u8,
+                nanosecond: 0,
+            },
+            offset: Some(0),
+        }
+    }
+
+    // The same instant, at the given offset from UTC.
+    pub fn with_offset(&self, offset: i16) -> Self {
+        let shifted = Self::from_unix_timestamp(self.unix_timestamp() + offset as i64 * 60);
+        Self {
+            time: Time {
+                nanosecond: self.time.nanosecond,
+                ..shifted.time
+            },
+            offset: Some(offset),
+            ..shifted
+        }
+    }
+
+    // The difference in seconds, ignoring the fractions.
+    pub fn seconds_until(&self, other: &DateTime) -> i64 {
+        other.unix_timestamp() - self.unix_timestamp()
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}T{}", self.date, self.time)?;
+        match self.offset {
+            Some(0) => write!(f, "Z"),
+            Some(offset) => {
+                let sign = if offset < 0 { '-' } else { '+' };
+                let minutes = offset.unsigned_abs();
+                write!(f, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnexpectedCharacter,
+    UnexpectedEnd,
+    InvalidDate,
+    InvalidTime,
+    InvalidOffset,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.kind {
+            ErrorKind::UnexpectedCharacter => "unexpected character",
+            ErrorKind::UnexpectedEnd => "unexpected end of input",
+            ErrorKind::InvalidDate => "invalid date",
+            ErrorKind::InvalidTime => "invalid time",
+            ErrorKind::InvalidOffset => "invalid offset",
+        };
+        write!(f, "{} at position {}", message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, kind: ErrorKind) -> ParseError {
+        let kind = if self.position >= self.bytes.len() && kind == ErrorKind::UnexpectedCharacter {
+            ErrorKind::UnexpectedEnd
+        } else {
+            kind
+        };
+        ParseError {
+            kind,
+            position: self.position,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), ParseError> {
+        if self.peek() != Some(byte) {
+            return Err(self.error(ErrorKind::UnexpectedCharacter));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    // A number of exactly the given number of digits.
+    fn digits(&mut self, count: usize) -> Result<u32, ParseError> {
+        let mut value = 0;
+        for _ in 0..count {
+            match self.peek() {
+                Some(byte) if byte.is_ascii_digit() => value = value * 10 + (byte - b'0') as u32,
+                _ => return Err(self.error(ErrorKind::UnexpectedCharacter)),
+            }
+            self.position += 1;
+        }
+        Ok(value)
+    }
+
+    // A year of four digits, or of at least four digits with a sign.
+    fn year(&mut self) -> Result<i32, ParseError> {
+        let sign = match self.peek() {
+            Some(b'+') => 1,
+            Some(b'-') => -1,
+            _ => return Ok(self.digits(4)? as i32),
+        };
+        self.position += 1;
+        let start = self.position;
+        let mut year: i32 = self.digits(4)? as i32;
+        while let Some(byte) = self.peek().filter(u8::is_ascii_digit) {
+            year = year
+                .checked_mul(10)
+                .and_then(|year| year.checked_add((byte - b'0') as i32))
+                .ok_or(ParseError {
+                    kind: ErrorKind::InvalidDate,
+                    position: start,
+                })?;
+            self.position += 1;
+        }
+        Ok(sign * year)
+    }
+
+    fn date(&mut self) -> Result<Date, ParseError> {
+        let start = self.position;
+        let year = self.year()?;
+        self.expect(b'-')?;
+        let month = self.digits(2)? as u8;
+        self.expect(b'-')?;
+        let day = self.digits(2)? as u8;
+        Date::new(year, month, day).ok_or(ParseError {
+            kind: ErrorKind::InvalidDate,
+            position: start,
+        })
+    }
+
+    // The fraction of the second can have any number of digits, but only the
+    // first nine are kept.
+    fn time(&mut self) -> Result<Time, ParseError> {
+        let start = self.position;
+        let hour = self.digits(2)? as u8;
+        self.expect(b':')?;
+        let minute = self.digits(2)? as u8;
+        let mut second = 0;
+        let mut nanosecond = 0;
+        if self.peek() == Some(b':') {
+            self.position += 1;
+            second = self.digits(2)? as u8;
+            if let Some(b'.' | b',') = self.peek() {
+                self.position += 1;
+                if !self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+                    return Err(self.error(ErrorKind::UnexpectedCharacter));
+                }
+                let mut scale = 100_000_000;
+                while let Some(byte) = self.peek().filter(u8::is_ascii_digit) {
+                    nanosecond += (byte - b'0') as u32 * scale;
+                    scale /= 10;
+                    self.position += 1;
+                }
+            }
+        }
+        Time::new(hour, minute, second, nanosecond).ok_or(ParseError {
+            kind: ErrorKind::InvalidTime,
+            position: start,
+        })
+    }
+
+    fn offset(&mut self) -> Result<Option<i16>, ParseError> {
+        let sign = match self.peek() {
+            Some(b'Z' | b'z') => {
+                self.position += 1;
+                return Ok(Some(0));
+            }
+            Some(b'+') => 1,
+            Some(b'-') => -1,
+            _ => return Ok(None),
+        };
+        let start = self.position;
+        self.position += 1;
+        let hours = self.digits(2)? as i16;
+        if self.peek() == Some(b':') {
+            self.position += 1;
+        }
+        let minutes = self.digits(2)? as i16;
+        if hours > 23 || minutes > 59 {
+            return Err(ParseError {
+                kind: ErrorKind::InvalidOffset,
+                position: start,
+            });
+        }
+        Ok(Some(sign * (hours * 60 + minutes)))
+    }
+
+    fn end(&self) -> Result<(), ParseError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.error(ErrorKind::UnexpectedCharacter)),
+        }
+    }
+}
+
+impl FromStr for Date {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            bytes: text.as_bytes(),
+            position: 0,
+        };
+        let date = parser.date()?;
+        parser.end()?;
+        Ok(date)
+    }
+}
+
+// The date and the time are separated by a T, or by a space, and the time is
+// midnight if it is missing.
+impl FromStr for DateTime {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            bytes: text.as_bytes(),
+            position: 0,
+        };
+        let date = parser.date()?;
+        let mut time = Time::default();
+        if let Some(b'T' | b't' | b' ') = parser.peek() {
+            parser.position += 1;
+            time = parser.time()?;
+        }
+        let offset = parser.offset()?;
+        parser.end()?;
+        Ok(DateTime { date, time, offset })
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "the leap year rules",
+        [1900, 2000, 2023, 2024, 2100, 2400].map(is_leap_year),
+        [false, true, false, true, false, true]
+    );
+    test!(
+        harness,
+        "days from the civil dates",
+        (
+            days_from_civil(1970, 1, 1),
+            days_from_civil(2000, 3, 1),
+            days_from_civil(2026, 10, 14),
+            days_from_civil(1, 1, 1)
+        ),
+        (0, 11017, 20740, -719162)
+    );
+    test!(
+        harness,
+        "civil dates from days",
+        [0, 11017, -1, -719162].map(civil_from_days),
+        [(1970, 1, 1), (2000, 3, 1), (1969, 12, 31), (1, 1, 1)].map(Some)
+    );
+    test!(
+        harness,
+        "the conversions are inverses of each other",
+        (-800_000..800_000).step_by(7).all(|days| {
+            let (year, month, day) = civil_from_days(days).unwrap();
+            days_from_civil(year, month, day) == days && Date::new(year, month, day).is_some()
+        }),
+        true
+    );
+    let (first, last) = (
+        days_from_civil(i32::MIN, 1, 1),
+        days_from_civil(i32::MAX, 12, 31),
+    );
+    test!(
+        harness,
+        "the day counts beyond the years of an i32",
+        [first, last, first - 1, last + 1, i64::MIN, i64::MAX].map(civil_from_days),
+        [
+            Some((i32::MIN, 1, 1)),
+            Some((i32::MAX, 12, 31)),
+            None,
+            None,
+            None,
+            None
+        ]
+    );
+    test!(
+        harness,
+        "invalid dates",
+        [
+            Date::new(2023, 2, 29),
+            Date::new(2026, 13, 1),
+            Date::new(2026, 4, 31),
+            Date::new(2026, 1, 0)
+        ],
+        [None; 4]
+    );
+
+    let today = Date::new(2026, 10, 14).unwrap();
+    test!(
+        harness,
+        "weekdays",
+        (
+            today.weekday(),
+            Date::new(1970, 1, 1).unwrap().weekday(),
+            Date::new(1, 1, 1).unwrap().weekday()
+        ),
+        (Weekday::Wednesday, Weekday::Thursday, Weekday::Monday)
+    );
+    test!(
+        harness,
+        "ISO week dates",
+        [
+            Date::new(2026, 10, 14),
+            Date::new(2021, 1, 3),
+            Date::new(2008, 12, 29)
+        ]
+        .map(|date| date.unwrap().iso_week()),
+        [(2026, 42), (2020, 53), (2009, 1)]
+    );
+    test!(
+        harness,
+        "adding days and months",
+        (
+            today.add_days(80).to_string(),
+            Date::new(2024, 1, 31).unwrap().add_months(1).to_string(),
+            Date::new(2024, 3, 31).unwrap().add_months(-13).to_string()
+        ),
+        (
+            "2027-01-02".to_string(),
+            "2024-02-29".to_string(),
+            "2023-02-28".to_string()
+        )
+    );
+    let landing = Date::new(1969, 7, 20).unwrap();
+    test!(
+        harness,
+        "differences of dates",
+        (
+            landing.days_until(&today),
+            landing.period_until(&today),
+            today.period_until(&landing)
+        ),
+        (
+            20905,
+            Period {
+                years: 57,
+                months: 2,
+                days: 24
+            },
+            Period {
+                years: -57,
+                months: -2,
+                days: -24
+            }
+        )
+    );
+
+    test!(
+        harness,
+        "parsing and formatting dates",
+        ["2026-10-14", "0000-02-29", "+12026-01-01", "-0044-03-15"]
+            .map(|text| text.parse::<Date>().map(|date| date.to_string())),
+        ["2026-10-14", "0000-02-29", "+12026-01-01", "-0044-03-15"]
+            .map(|text| Ok(text.to_string()))
+    );
+    let stamps = [
+        "2026-10-14T12:00:00+02:00",
+        "2038-01-19T03:14:07Z",
+        "1969-12-31T23:59:59.5-05:30",
+        "2026-10-14T08:30:00",
+    ];
+    test!(
+        harness,
+        "parsing and formatting date and time values",
+        stamps.map(|text| text.parse::<DateTime>().map(|value| value.to_string())),
+        [
+            "2026-10-14T12:00:00+02:00",
+            "2038-01-19T03:14:07Z",
+            "1969-12-31T23:59:59.500-05:30",
+            "2026-10-14T08:30:00"
+        ]
+        .map(|text| Ok(text.to_string()))
+    );
+    test!(
+        harness,
+        "lenient forms of ISO 8601",
+        [
+            "2026-10-14 07:05",
+            "2026-10-14t07:05:00,25+0100",
+            "2026-10-14"
+        ]
+        .map(|text| text.parse::<DateTime>().map(|value| value.to_string())),
+        [
+            "2026-10-14T07:05:00",
+            "2026-10-14T07:05:00.250+01:00",
+            "2026-10-14T00:00:00"
+        ]
+        .map(|text| Ok(text.to_string()))
+    );
+    test!(
+        harness,
+        "Unix timestamps",
+        (
+            stamps[0].parse::<DateTime>().unwrap().unix_timestamp(),
+            DateTime::from_unix_timestamp(i32::MAX as i64).to_string()
+        ),
+        (1791972000, stamps[1].to_string())
+    );
+    let noon: DateTime = stamps[0].parse().unwrap();
+    test!(
+        harness,
+        "changing the offset keeps the instant",
+        (
+            noon.with_offset(-420).to_string(),
+            noon.seconds_until(&noon.with_offset(330))
+        ),
+        ("2026-10-14T03:00:00-07:00".to_string(), 0)
+    );
+
+    let error = |kind, position| Err::<DateTime, _>(ParseError { kind, position });
+    test!(
+        harness,
+        "invalid date and time values",
+        [
+            "2026-10-1".parse::<DateTime>(),
+            "2026/10/14".parse(),
+            "2026-02-30".parse(),
+            "2026-10-14T24:00".parse(),
+            "2026-10-14T12:00+25:00".parse(),
+            "2026-10-14T12:00:00Zulu".parse(),
+        ],
+        [
+            error(ErrorKind::UnexpectedEnd, 9),
+            error(ErrorKind::UnexpectedCharacter, 4),
+            error(ErrorKind::InvalidDate, 0),
+            error(ErrorKind::InvalidTime, 11),
+            error(ErrorKind::InvalidOffset, 16),
+            error(ErrorKind::UnexpectedCharacter, 20)
+        ]
+    );
+
+    note!(
+        harness,
+        "Today is {}, {}, day {} of the year, week {} of {}.",
+        today.weekday(),
+        today,
+        today.day_of_year(),
+        today.iso_week().1,
+        today.iso_week().0
+    );
+    let period = landing.period_until(&today);
+    note!(
+        harness,
+        "The first Moon landing was {} days, or {} years, {} months, and {} days ago.",
+        landing.days_until(&today),
+        period.years,
+        period.months,
+        period.days
+    );
+    for offset in [0, 60, -300, 540] {
+        note!(harness, "{}", noon.with_offset(offset));
+    }
+}
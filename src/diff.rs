@@ -0,0 +1,344 @@
+// diff.rs
+// Myers' diff algorithm, which finds the shortest edit script between two
+// sequences, made of deletions from the first one, and insertions from the
+// second one. It searches the edit graph breadth-first by the number of edits
+// D, keeping only the furthest reaching path on each diagonal, and following
+// the runs of equal elements (snakes) for free, which takes O((N + M) D) time,
+// so it is fast on similar inputs. The paths of each round are saved, so the
+// edits can be recovered by walking back from the end. Diffing the lines of two
+// texts gives the unified format of the diff and patch tools: hunks of changes,
+// with a few lines of context around them, where hunks closer than twice the
+// context are merged. The lines keep their line breaks, so a missing one at the
+// end of a file shows up as a change, with the usual marker. The output can be
+// colorized with the ANSI snippet.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::ansi::{Color, Stylize};
+
+// The indices of the elements in the old and the new sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edit {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+pub fn diff<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Edit> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = n + m;
+    // The furthest x reached on each diagonal k = x - y, offset by max.
+    let index = |k: isize| (k + max) as usize;
+    let mut furthest = vec![0isize; 2 * max as usize + 2];
+    let mut trace = Vec::new();
+    'search: for d in 0..=max {
+        trace.push(furthest.clone());
+        for k in (-d..=d).step_by(2) {
+            let down = k == -d || (k != d && furthest[index(k - 1)] < furthest[index(k + 1)]);
+            let mut x = if down {
+                furthest[index(k + 1)]
+            } else {
+                furthest[index(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            furthest[index(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, furthest) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let down = k == -d || (k != d && furthest[index(k - 1)] < furthest[index(k + 1)]);
+        let previous_k = if down { k + 1 } else { k - 1 };
+        let previous_x = furthest[index(previous_k)];
+        let previous_y = previous_x - previous_k;
+        while x > previous_x && y > previous_y {
+            x -= 1;
+            y -= 1;
+            edits.push(Edit::Equal(x as usize, y as usize));
+        }
+        if d > 0 {
+            if down {
+                edits.push(Edit::Insert(previous_y as usize));
+            } else {
+                edits.push(Edit::Delete(previous_x as usize));
+            }
+        }
+        (x, y) = (previous_x, previous_y);
+    }
+    edits.reverse();
+    edits
+}
+
+// The number of deletions and insertions.
+pub fn distance(edits: &[Edit]) -> usize {
+    edits
+        .iter()
+        .filter(|edit| !matches!(edit, Edit::Equal(_, _)))
+        .count()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hunk {
+    // The first lines of the hunk, starting from 0.
+    pub old_start: usize,
+    pub new_start: usize,
+    pub old_count: usize,
+    pub new_count: usize,
+    pub edits: Vec<Edit>,
+}
+
+pub fn hunks(edits: &[Edit], context: usize) -> Vec<Hunk> {
+    let changes: Vec<usize> = (0..edits.len())
+        .filter(|index| !matches!(edits[*index], Edit::Equal(_, _)))
+        .collect();
+    let mut groups: Vec<(usize, usize)> = Vec::new();
+    for change in changes {
+        match groups.last_mut() {
+            Some((_, last)) if change - *last - 1 <= context.saturating_mul(2) => *last = change,
+            _ => groups.push((change, change)),
+        }
+    }
+
+    // The positions in both sequences before each edit.
+    let mut positions = Vec::with_capacity(edits.len());
+    let (mut old, mut new) = (0, 0);
+    for edit in edits {
+        positions.push((old, new));
+        match edit {
+            Edit::Equal(_, _) => (old, new) = (old + 1, new + 1),
+            Edit::Delete(_) => old += 1,
+            Edit::Insert(_) => new += 1,
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(first, last)| {
+            let start = first.saturating_sub(context);
+            let end = last
+                .saturating_add(context)
+                .saturating_add(1)
+                .min(edits.len());
+            let edits = edits[start..end].to_vec();
+            let old_count = edits
+                .iter()
+                .filter(|edit| !matches!(edit, Edit::Insert(_)))
+                .count();
+            let new_count = edits
+                .iter()
+                .filter(|edit| !matches!(edit, Edit::Delete(_)))
+                .count();
+            Hunk {
+                old_start: positions[start].0,
+                new_start: positions[start].1,
+                old_count,
+                new_count,
+                edits,
+            }
+        })
+        .collect()
+}
+
+// The range of a hunk header, where the start is the line before an empty
+// range, and the count is left out if it is one.
+fn range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    }
+}
+
+fn push_line(out: &mut String, prefix: char, line: &str, color: Option<Color>, colorize: bool) {
+    let text = format!("{}{}", prefix, line.strip_suffix('\n').unwrap_or(line));
+    match color {
+        Some(color) => out.push_str(&text.fg(color).render(colorize)),
+        None => out.push_str(&text),
+    }
+    out.push('\n');
+    if !line.ends_with('\n') {
+        out.push_str("\\ No newline at end of file\n");
+    }
+}
+
+// An empty string for identical texts.
+pub fn unified(
+    old_name: &str,
+    new_name: &str,
+    old_text: &str,
+    new_text: &str,
+    context: usize,
+    colorize: bool,
+) -> String {
+    let old: Vec<&str> = old_text.split_inclusive('\n').collect();
+    let new: Vec<&str> = new_text.split_inclusive('\n').collect();
+    let hunks = hunks(&diff(&old, &new), context);
+    if hunks.is_empty() {
+        return String::new();
+    }
+    let mut out = String::new();
+    for header in [format!("--- {}", old_name), format!("+++ {}", new_name)] {
+        out.push_str(&header.bold().render(colorize));
+        out.push('\n');
+    }
+    for hunk in hunks {
+        let header = format!(
+            "@@ -{} +{} @@",
+            range(hunk.old_start, hunk.old_count),
+            range(hunk.new_start, hunk.new_count)
+        );
+        out.push_str(&header.fg(Color::Cyan).render(colorize));
+        out.push('\n');
+        for edit in hunk.edits {
+            match edit {
+                Edit::Equal(index, _) => push_line(&mut out, ' ', old[index], None, colorize),
+                Edit::Delete(index) => {
+                    push_line(&mut out, '-', old[index], Some(Color::Red), colorize)
+                }
+                Edit::Insert(index) => {
+                    push_line(&mut out, '+', new[index], Some(Color::Green), colorize)
+                }
+            }
+        }
+    }
+    out
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+const OLD: &str = "[package]
+name = \"snippets\"
+version = \"0.1.0\"
+edition = \"2018\"
+
+[dependencies]
+rand = \"0.7\"
+
+[profile.release]
+opt-level = 3
+debug = false
+lto = false
+codegen-units = 16
+";
+
+const NEW: &str = "[package]
+name = \"snippets\"
+version = \"0.2.0\"
+edition = \"2021\"
+license = \"MIT\"
+
+[dependencies]
+
+[profile.release]
+opt-level = 3
+debug = false
+lto = true
+codegen-units = 1";
+
+fn apply<'a>(edits: &[Edit], old: &[&'a str], new: &[&'a str]) -> Vec<&'a str> {
+    edits
+        .iter()
+        .filter_map(|edit| match edit {
+            Edit::Equal(index, _) => Some(old[*index]),
+            Edit::Delete(_) => None,
+            Edit::Insert(index) => Some(new[*index]),
+        })
+        .collect()
+}
+
+pub fn demo(harness: &mut Harness) {
+    let (a, b): (Vec<char>, Vec<char>) = ("ABCABBA".chars().collect(), "CBABAC".chars().collect());
+    let edits = diff(&a, &b);
+    test!(
+        harness,
+        "the shortest edit script of the example in the paper",
+        distance(&edits),
+        5
+    );
+    let old: Vec<&str> = OLD.split_inclusive('\n').collect();
+    let new: Vec<&str> = NEW.split_inclusive('\n').collect();
+    test!(
+        harness,
+        "applying the edits gives the new lines",
+        apply(&diff(&old, &new), &old, &new),
+        new.clone()
+    );
+    test!(
+        harness,
+        "inserting into an empty sequence",
+        diff(&[], &[1, 2]),
+        vec![Edit::Insert(0), Edit::Insert(1)]
+    );
+    test!(
+        harness,
+        "deleting everything",
+        diff(&[1, 2], &[]),
+        vec![Edit::Delete(0), Edit::Delete(1)]
+    );
+    test!(
+        harness,
+        "identical texts have no diff",
+        unified("a", "b", OLD, OLD, 3, false),
+        ""
+    );
+    test!(
+        harness,
+        "a hunk with one line of context",
+        unified(
+            "a",
+            "b",
+            "one\ntwo\nthree\nfour\n",
+            "one\n2\nthree\nfour\n",
+            1,
+            false
+        ),
+        "--- a\n+++ b\n@@ -1,3 +1,3 @@\n one\n-two\n+2\n three\n"
+    );
+    test!(
+        harness,
+        "the hunk header of an insertion into an empty file",
+        unified("a", "b", "", "new\n", 3, false),
+        "--- a\n+++ b\n@@ -0,0 +1 @@\n+new\n"
+    );
+    test!(
+        harness,
+        "a missing line break at the end of the file",
+        unified("a", "b", "end\n", "end", 3, false),
+        "--- a\n+++ b\n@@ -1 +1 @@\n-end\n+end\n\\ No newline at end of file\n"
+    );
+    let edits = diff(&old, &new);
+    test!(
+        harness,
+        "distant changes make separate hunks",
+        [0, 1, 3, usize::MAX].map(|context| hunks(&edits, context).len()),
+        [3, 2, 1, 1]
+    );
+    test!(
+        harness,
+        "colorizing the removed and the added lines",
+        unified("a", "b", "x\n", "y\n", 0, true)
+            .lines()
+            .skip(3)
+            .collect::<Vec<&str>>(),
+        vec!["\x1b[31m-x\x1b[0m", "\x1b[32m+y\x1b[0m"]
+    );
+
+    let colorize = crate::ansi::enabled();
+    let patch = unified("Cargo.toml.orig", "Cargo.toml", OLD, NEW, 1, colorize);
+    note!(harness, "{}", patch);
+}
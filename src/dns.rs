@@ -0,0 +1,1136 @@
+// dns.rs
+// A DNS stub resolver, which builds its query packets by hand, sends them to a
+// resolver over UDP, and parses the answers. A message is a header with an ID,
+// flags, and counts, then the questions, and then the answer, authority, and
+// additional records. A name is a sequence of labels, each prefixed with its
+// length, and ended by the empty label of the root. To save space, a name may
+// end with a pointer to an earlier occurrence of its suffix in the message:
+// the encoder remembers where each suffix was written, and the decoder follows
+// the pointers, which have to point backwards, so a malicious message can not
+// send it around in a loop. The records of the A, AAAA, CNAME, and TXT types
+// are decoded, and the others are kept as raw bytes. A response which does not
+// fit into a datagram is truncated, and the query is repeated over TCP, where
+// each message is prefixed with its length. The binary queries the resolver of
+// the system, or the one it is given, and prints the records in a table, with
+// the table snippet, and without a name, it runs the demonstration, against a
+// local server with a small zone of its own.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::hashmap::HashMap;
+use crate::rng::{RandomSource, SplitMix64};
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, UdpSocket};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    InvalidName,
+    LabelTooLong,
+    NameTooLong,
+    Truncated,
+    BadPointer,
+    BadRecord,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DnsError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for DnsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.kind {
+            ErrorKind::InvalidName => "invalid name",
+            ErrorKind::LabelTooLong => "label longer than 63 bytes",
+            ErrorKind::NameTooLong => "name longer than 255 bytes",
+            ErrorKind::Truncated => "message ends early",
+            ErrorKind::BadPointer => "compression pointer does not point backwards",
+            ErrorKind::BadRecord => "malformed record data",
+        };
+        write!(f, "{} at position {}", message, self.position)
+    }
+}
+
+impl std::error::Error for DnsError {}
+
+fn error(kind: ErrorKind, position: usize) -> DnsError {
+    DnsError { kind, position }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RecordType {
+    A,
+    Aaaa,
+    Cname,
+    Txt,
+    Other(u16),
+}
+
+impl RecordType {
+    pub fn code(self) -> u16 {
+        match self {
+            RecordType::A => 1,
+            RecordType::Cname => 5,
+            RecordType::Txt => 16,
+            RecordType::Aaaa => 28,
+            RecordType::Other(code) => code,
+        }
+    }
+
+    pub fn from_code(code: u16) -> Self {
+        match code {
+            1 => RecordType::A,
+            5 => RecordType::Cname,
+            16 => RecordType::Txt,
+            28 => RecordType::Aaaa,
+            code => RecordType::Other(code),
+        }
+    }
+}
+
+impl fmt::Display for RecordType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordType::A => write!(f, "A"),
+            RecordType::Aaaa => write!(f, "AAAA"),
+            RecordType::Cname => write!(f, "CNAME"),
+            RecordType::Txt => write!(f, "TXT"),
+            RecordType::Other(code) => write!(f, "TYPE{}", code),
+        }
+    }
+}
+
+// The mnemonic of the type, regardless of its case, or its number, in the
+// generic TYPE1234 form.
+impl FromStr for RecordType {
+    type Err = ();
+
+    fn from_str(text: &str) -> Result<Self, ()> {
+        let text = text.to_ascii_uppercase();
+        match text.as_str() {
+            "A" => Ok(RecordType::A),
+            "AAAA" => Ok(RecordType::Aaaa),
+            "CNAME" => Ok(RecordType::Cname),
+            "TXT" => Ok(RecordType::Txt),
+            _ => text
+                .strip_prefix("TYPE")
+                .and_then(|code| code.parse().ok())
+                .map(RecordType::from_code)
+                .ok_or(()),
+        }
+    }
+}
+
+pub const CLASS_IN: u16 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecordData {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Cname(String),
+    Txt(Vec<String>),
+    Other(u16, Vec<u8>),
+}
+
+impl RecordData {
+    pub fn record_type(&self) -> RecordType {
+        match self {
+            RecordData::A(_) => RecordType::A,
+            RecordData::Aaaa(_) => RecordType::Aaaa,
+            RecordData::Cname(_) => RecordType::Cname,
+            RecordData::Txt(_) => RecordType::Txt,
+            RecordData::Other(code, _) => RecordType::from_code(*code),
+        }
+    }
+}
+
+// In the presentation format of zone files, with the unknown types in the
+// generic form of RFC 3597.
+impl fmt::Display for RecordData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordData::A(address) => write!(f, "{}", address),
+            RecordData::Aaaa(address) => write!(f, "{}", address),
+            RecordData::Cname(name) => write!(f, "{}.", name),
+            RecordData::Txt(strings) => {
+                let quoted: Vec<String> = strings
+                    .iter()
+                    .map(|text| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"")))
+                    .collect();
+                write!(f, "{}", quoted.join(" "))
+            }
+            RecordData::Other(_, bytes) => {
+                write!(f, "\\# {}", bytes.len())?;
+                if !bytes.is_empty() {
+                    write!(f, " ")?;
+                }
+                bytes.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Question {
+    pub name: String,
+    pub record_type: RecordType,
+    pub class: u16,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub name: String,
+    pub class: u16,
+    pub ttl: u32,
+    pub data: RecordData,
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let class = if self.class == CLASS_IN {
+            "IN"
+        } else {
+            "CLASS?"
+        };
+        write!(
+            f,
+            "{}. {} {} {} {}",
+            self.name,
+            self.ttl,
+            class,
+            self.data.record_type(),
+            self.data
+        )
+    }
+}
+
+pub fn rcode_name(rcode: u8) -> &'static str {
+    match rcode {
+        0 => "NOERROR",
+        1 => "FORMERR",
+        2 => "SERVFAIL",
+        3 => "NXDOMAIN",
+        4 => "NOTIMP",
+        5 => "REFUSED",
+        _ => "UNKNOWN",
+    }
+}
+
+pub const NXDOMAIN: u8 = 3;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Message {
+    pub id: u16,
+    pub response: bool,
+    pub opcode: u8,
+    pub authoritative: bool,
+    pub truncated: bool,
+    pub recursion_desired: bool,
+    pub recursion_available: bool,
+    pub rcode: u8,
+    pub questions: Vec<Question>,
+    pub answers: Vec<Record>,
+    pub authorities: Vec<Record>,
+    pub additionals: Vec<Record>,
+}
+
+// The labels of the name, without the dot of the root at the end. The root
+// itself has no labels.
+fn labels(name: &str) -> Result<Vec<&str>, DnsError> {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    if name.is_empty() {
+        return Ok(Vec::new());
+    }
+    if name.len() > 253 {
+        return Err(error(ErrorKind::NameTooLong, 253));
+    }
+    let mut position = 0;
+    let labels: Vec<&str> = name.split('.').collect();
+    for label in &labels {
+        if label.is_empty() {
+            return Err(error(ErrorKind::InvalidName, position));
+        }
+        if label.len() > 63 {
+            return Err(error(ErrorKind::LabelTooLong, position));
+        }
+        position += label.len() + 1;
+    }
+    Ok(labels)
+}
+
+// A name on its own, without compression.
+pub fn encode_name(name: &str) -> Result<Vec<u8>, DnsError> {
+    let mut bytes = Vec::new();
+    for label in labels(name)? {
+        bytes.push(label.len() as u8);
+        bytes.extend_from_slice(label.as_bytes());
+    }
+    bytes.push(0);
+    Ok(bytes)
+}
+
+// Decodes the name at the offset, and returns it with the offset after it,
+// which is after the first pointer, if the name has one. Each pointer has to
+// point before the labels read so far, so the offsets keep decreasing, and
+// the decoding always ends.
+pub fn decode_name(packet: &[u8], offset: usize) -> Result<(String, usize), DnsError> {
+    let mut labels = Vec::new();
+    let mut position = offset;
+    let mut limit = offset;
+    let mut end = None;
+    let mut length = 0;
+    loop {
+        let byte = *packet
+            .get(position)
+            .ok_or(error(ErrorKind::Truncated, position))?;
+        match byte >> 6 {
+            0 if byte == 0 => break,
+            0 => {
+                let start = position + 1;
+                let label = packet
+                    .get(start..start + byte as usize)
+                    .ok_or(error(ErrorKind::Truncated, packet.len()))?;
+                length += label.len() + 1;
+                if length > 255 {
+                    return Err(error(ErrorKind::NameTooLong, position));
+                }
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                position = start + label.len();
+            }
+            3 => {
+                let low = *packet
+                    .get(position + 1)
+                    .ok_or(error(ErrorKind::Truncated, position + 1))?;
+                let target = ((byte as usize & 0x3f) << 8) | low as usize;
+                if target >= limit {
+                    return Err(error(ErrorKind::BadPointer, position));
+                }
+                end.get_or_insert(position + 2);
+                position = target;
+                limit = target;
+            }
+            // The extended label types were never deployed.
+            _ => return Err(error(ErrorKind::InvalidName, position)),
+        }
+    }
+    Ok((labels.join("."), end.unwrap_or(position + 1)))
+}
+
+// Writes the message, and remembers the offset of each name suffix, for the
+// later names which end the same way. Names compare regardless of case.
+struct Encoder {
+    bytes: Vec<u8>,
+    suffixes: HashMap<String, u16>,
+}
+
+impl Encoder {
+    fn u16(&mut self, value: u16) {
+        self.bytes.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn name(&mut self, name: &str) -> Result<(), DnsError> {
+        let labels = labels(name)?;
+        for index in 0..labels.len() {
+            let suffix = labels[index..].join(".").to_ascii_lowercase();
+            if let Some(offset) = self.suffixes.get(&suffix) {
+                let pointer = 0xc000 | *offset;
+                self.u16(pointer);
+                return Ok(());
+            }
+            // A pointer has 14 bits for the offset.
+            if self.bytes.len() < 0x4000 {
+                self.suffixes.insert(suffix, self.bytes.len() as u16);
+            }
+            self.bytes.push(labels[index].len() as u8);
+            self.bytes.extend_from_slice(labels[index].as_bytes());
+        }
+        self.bytes.push(0);
+        Ok(())
+    }
+
+    fn record(&mut self, record: &Record) -> Result<(), DnsError> {
+        self.name(&record.name)?;
+        self.u16(record.data.record_type().code());
+        self.u16(record.class);
+        self.bytes.extend_from_slice(&record.ttl.to_be_bytes());
+        // The length of the data is filled in after it.
+        let length_at = self.bytes.len();
+        self.u16(0);
+        match &record.data {
+            RecordData::A(address) => self.bytes.extend_from_slice(&address.octets()),
+            RecordData::Aaaa(address) => self.bytes.extend_from_slice(&address.octets()),
+            RecordData::Cname(name) => self.name(name)?,
+            RecordData::Txt(strings) => {
+                for text in strings {
+                    if text.len() > 255 {
+                        return Err(error(ErrorKind::BadRecord, self.bytes.len()));
+                    }
+                    self.bytes.push(text.len() as u8);
+                    self.bytes.extend_from_slice(text.as_bytes());
+                }
+            }
+            RecordData::Other(_, bytes) => self.bytes.extend_from_slice(bytes),
+        }
+        let length = self.bytes.len() - length_at - 2;
+        let length = u16::try_from(length).map_err(|_| error(ErrorKind::BadRecord, length_at))?;
+        self.bytes[length_at..length_at + 2].copy_from_slice(&length.to_be_bytes());
+        Ok(())
+    }
+}
+
+struct Decoder<'a> {
+    packet: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], DnsError> {
+        let bytes = self
+            .packet
+            .get(self.position..self.position + count)
+            .ok_or(error(ErrorKind::Truncated, self.packet.len()))?;
+        self.position += count;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, DnsError> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, DnsError> {
+        Ok(((self.u16()? as u32) << 16) | self.u16()? as u32)
+    }
+
+    fn name(&mut self) -> Result<String, DnsError> {
+        let (name, end) = decode_name(self.packet, self.position)?;
+        self.position = end;
+        Ok(name)
+    }
+
+    fn question(&mut self) -> Result<Question, DnsError> {
+        Ok(Question {
+            name: self.name()?,
+            record_type: RecordType::from_code(self.u16()?),
+            class: self.u16()?,
+        })
+    }
+
+    fn record(&mut self) -> Result<Record, DnsError> {
+        let name = self.name()?;
+        let code = self.u16()?;
+        let class = self.u16()?;
+        let ttl = self.u32()?;
+        let length = self.u16()? as usize;
+        let start = self.position;
+        let bytes = self.bytes(length)?;
+        let bad = error(ErrorKind::BadRecord, start);
+        let data = match RecordType::from_code(code) {
+            RecordType::A => RecordData::A(<[u8; 4]>::try_from(bytes).map_err(|_| bad)?.into()),
+            RecordType::Aaaa => {
+                RecordData::Aaaa(<[u8; 16]>::try_from(bytes).map_err(|_| bad)?.into())
+            }
+            // The name may point anywhere before it in the message, but has
+            // to end where the data does.
+            RecordType::Cname => match decode_name(self.packet, start)? {
+                (name, end) if end == start + length => RecordData::Cname(name),
+                _ => return Err(bad),
+            },
+            RecordType::Txt => {
+                let mut strings = Vec::new();
+                let mut rest = bytes;
+                while let Some((&size, tail)) = rest.split_first() {
+                    let text = tail.get(..size as usize).ok_or(bad)?;
+                    strings.push(String::from_utf8_lossy(text).into_owned());
+                    rest = &tail[size as usize..];
+                }
+                RecordData::Txt(strings)
+            }
+            RecordType::Other(code) => RecordData::Other(code, bytes.to_vec()),
+        };
+        Ok(Record {
+            name,
+            class,
+            ttl,
+            data,
+        })
+    }
+}
+
+impl Message {
+    // A query for one record of the name, which asks for recursion.
+    pub fn query(id: u16, name: &str, record_type: RecordType) -> Self {
+        Self {
+            id,
+            recursion_desired: true,
+            questions: vec![Question {
+                name: name.strip_suffix('.').unwrap_or(name).to_string(),
+                record_type,
+                class: CLASS_IN,
+            }],
+            ..Self::default()
+        }
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, DnsError> {
+        let mut encoder = Encoder {
+            bytes: Vec::with_capacity(512),
+            suffixes: HashMap::new(),
+        };
+        let flags = (self.response as u16) << 15
+            | (self.opcode as u16 & 0xf) << 11
+            | (self.authoritative as u16) << 10
+            | (self.truncated as u16) << 9
+            | (self.recursion_desired as u16) << 8
+            | (self.recursion_available as u16) << 7
+            | (self.rcode as u16 & 0xf);
+        encoder.u16(self.id);
+        encoder.u16(flags);
+        encoder.u16(self.questions.len() as u16);
+        encoder.u16(self.answers.len() as u16);
+        encoder.u16(self.authorities.len() as u16);
+        encoder.u16(self.additionals.len() as u16);
+        for question in &self.questions {
+            encoder.name(&question.name)?;
+            encoder.u16(question.record_type.code());
+            encoder.u16(question.class);
+        }
+        for record in self
+            .answers
+            .iter()
+            .chain(&self.authorities)
+            .chain(&self.additionals)
+        {
+            encoder.record(record)?;
+        }
+        Ok(encoder.bytes)
+    }
+
+    pub fn parse(packet: &[u8]) -> Result<Self, DnsError> {
+        let mut decoder = Decoder {
+            packet,
+            position: 0,
+        };
+        let id = decoder.u16()?;
+        let flags = decoder.u16()?;
+        let counts = [
+            decoder.u16()?,
+            decoder.u16()?,
+            decoder.u16()?,
+            decoder.u16()?,
+        ];
+        let questions = (0..counts[0])
+            .map(|_| decoder.question())
+            .collect::<Result<_, _>>()?;
+        let mut sections = [Vec::new(), Vec::new(), Vec::new()];
+        for (section, count) in sections.iter_mut().zip(&counts[1..]) {
+            for _ in 0..*count {
+                section.push(decoder.record()?);
+            }
+        }
+        let [answers, authorities, additionals] = sections;
+        Ok(Self {
+            id,
+            response: flags & 0x8000 != 0,
+            opcode: (flags >> 11 & 0xf) as u8,
+            authoritative: flags & 0x0400 != 0,
+            truncated: flags & 0x0200 != 0,
+            recursion_desired: flags & 0x0100 != 0,
+            recursion_available: flags & 0x0080 != 0,
+            rcode: (flags & 0xf) as u8,
+            questions,
+            answers,
+            authorities,
+            additionals,
+        })
+    }
+
+    // Whether this is the response to the query: the same ID alone is easy to
+    // guess for an attacker, who is less likely to know the question, too.
+    pub fn answers_query(&self, query: &Message) -> bool {
+        self.response
+            && self.id == query.id
+            && self.questions.len() == query.questions.len()
+            && self.questions.iter().zip(&query.questions).all(|(a, b)| {
+                a.name.eq_ignore_ascii_case(&b.name)
+                    && a.record_type == b.record_type
+                    && a.class == b.class
+            })
+    }
+}
+
+// The first nameserver in the configuration of the system.
+pub fn system_server() -> Option<SocketAddr> {
+    let configuration = std::fs::read_to_string("/etc/resolv.conf").ok()?;
+    configuration.lines().find_map(|line| {
+        let address = line.trim().strip_prefix("nameserver")?.trim();
+        let address: std::net::IpAddr = address.parse().ok()?;
+        Some(SocketAddr::new(address, 53))
+    })
+}
+
+fn invalid_data(error: DnsError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[derive(Clone, Debug)]
+pub struct Resolver {
+    server: SocketAddr,
+    timeout: Duration,
+    attempts: usize,
+    next_id: u16,
+}
+
+impl Resolver {
+    pub fn new(server: SocketAddr) -> Self {
+        // The IDs only need to be hard to guess from the outside.
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |time| time.as_nanos() as u64);
+        let mut random = SplitMix64::new(seed ^ std::process::id() as u64);
+        Self {
+            server,
+            timeout: Duration::from_secs(2),
+            attempts: 3,
+            next_id: random.next_u32() as u16,
+        }
+    }
+
+    // The limit on each attempt.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn attempts(mut self, attempts: usize) -> Self {
+        self.attempts = attempts.max(1);
+        self
+    }
+
+    pub fn server(&self) -> SocketAddr {
+        self.server
+    }
+
+    // Sends the query over UDP, and repeats it over TCP, if the response is
+    // truncated. Datagrams which do not answer the query are ignored, as they
+    // may be late answers to earlier queries, or forged ones.
+    pub fn query(&mut self, name: &str, record_type: RecordType) -> io::Result<Message> {
+        self.next_id = self.next_id.wrapping_add(0x9e37);
+        let query = Message::query(self.next_id, name, record_type);
+        let packet = query.encode().map_err(invalid_data)?;
+        let local: SocketAddr = if self.server.is_ipv4() {
+            "0.0.0.0:0".parse().unwrap()
+        } else {
+            "[::]:0".parse().unwrap()
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(self.server)?;
+        let mut buffer = [0; 4096];
+        for _ in 0..self.attempts {
+            socket.send(&packet)?;
+            let deadline = Instant::now() + self.timeout;
+            while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+                socket.set_read_timeout(Some(remaining.max(Duration::from_millis(1))))?;
+                let received = match socket.recv(&mut buffer) {
+                    Ok(received) => received,
+                    Err(error)
+                        if matches!(
+                            error.kind(),
+                            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                        ) =>
+                    {
+                        break
+                    }
+                    Err(error) => return Err(error),
+                };
+                match Message::parse(&buffer[..received]) {
+                    Ok(response) if response.answers_query(&query) => {
+                        if response.truncated {
+                            return self.query_tcp(&query, &packet);
+                        }
+                        return Ok(response);
+                    }
+                    _ => continue,
+                }
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("no response from {}", self.server),
+        ))
+    }
+
+    fn query_tcp(&self, query: &Message, packet: &[u8]) -> io::Result<Message> {
+        let mut stream = TcpStream::connect_timeout(&self.server, self.timeout)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        let mut framed = (packet.len() as u16).to_be_bytes().to_vec();
+        framed.extend_from_slice(packet);
+        stream.write_all(&framed)?;
+        let mut length = [0; 2];
+        stream.read_exact(&mut length)?;
+        let mut response = vec![0; u16::from_be_bytes(length) as usize];
+        stream.read_exact(&mut response)?;
+        let response = Message::parse(&response).map_err(invalid_data)?;
+        if !response.answers_query(query) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the response does not answer the query",
+            ));
+        }
+        Ok(response)
+    }
+
+    // The records of the type for the name, following the aliases among the
+    // answers, the way a resolver returns them along with the alias.
+    pub fn lookup(&mut self, name: &str, record_type: RecordType) -> io::Result<Vec<RecordData>> {
+        let response = self.query(name, record_type)?;
+        if response.rcode != 0 {
+            return Err(io::Error::other(format!(
+                "{} for {}",
+                rcode_name(response.rcode),
+                name
+            )));
+        }
+        let mut current = name.strip_suffix('.').unwrap_or(name).to_string();
+        for _ in 0..8 {
+            let records: Vec<&Record> = response
+                .answers
+                .iter()
+                .filter(|record| record.name.eq_ignore_ascii_case(&current))
+                .collect();
+            let alias = records.iter().find_map(|record| match &record.data {
+                RecordData::Cname(target) if record_type != RecordType::Cname => Some(target),
+                _ => None,
+            });
+            match alias {
+                Some(target) => current = target.clone(),
+                None => {
+                    return Ok(records
+                        .into_iter()
+                        .filter(|record| record.data.record_type() == record_type)
+                        .map(|record| record.data.clone())
+                        .collect())
+                }
+            }
+        }
+        Err(io::Error::other(format!("too many aliases for {}", name)))
+    }
+}
+
+fn records_table(records: &[Record]) -> String {
+    let mut table = crate::table::Table::new()
+        .header(&["name", "ttl", "type", "data"])
+        .align(1, crate::table::Alignment::Right);
+    for record in records {
+        table.push(&[
+            format!("{}.", record.name),
+            record.ttl.to_string(),
+            record.data.record_type().to_string(),
+            record.data.to_string(),
+        ]);
+    }
+    table.render()
+}
+
+// The entry point of the binary.
+pub fn main(mut arguments: impl Iterator<Item = String>) -> i32 {
+    use crate::args::Parser;
+    let program = crate::harness::program_name(arguments.next());
+    let parser = Parser::new(&program, "A DNS stub resolver.")
+        .optional(
+            "NAME",
+            "Look up the name, instead of running the demonstration",
+        )
+        .option(
+            Some('t'),
+            "type",
+            "TYPE",
+            "Ask for records of this type, which can be repeated (default: A and AAAA)",
+        )
+        .option(
+            Some('s'),
+            "server",
+            "ADDRESS",
+            "Ask this resolver, instead of the one of the system",
+        )
+        .flag(
+            Some('q'),
+            "quiet",
+            "Only report the failed checks of the demonstration",
+        );
+    let matches = match parser.parse_or_report(arguments) {
+        Ok(matches) => matches,
+        Err(code) => return code,
+    };
+    let Some(name) = matches.value("NAME") else {
+        return crate::harness::run_demo(demo, matches.flag("quiet"));
+    };
+    let types: Result<Vec<RecordType>, ()> = match matches.values("type") {
+        [] => Ok(vec![RecordType::A, RecordType::Aaaa]),
+        types => types.iter().map(|text| text.parse()).collect(),
+    };
+    let Ok(types) = types else {
+        eprintln!("{}: unknown record type", program);
+        return 2;
+    };
+    let server = match matches.value("server") {
+        // A bare address means the usual port.
+        Some(server) => server
+            .parse::<SocketAddr>()
+            .ok()
+            .or_else(|| Some(SocketAddr::new(server.parse().ok()?, 53))),
+        None => system_server(),
+    };
+    let Some(server) = server else {
+        eprintln!("{}: no resolver to ask", program);
+        return 2;
+    };
+    let mut resolver = Resolver::new(server);
+    let mut status = 0;
+    for record_type in types {
+        match resolver.query(name, record_type) {
+            Ok(response) => {
+                println!(
+                    ";; {} {} from {}: {}, {} answers",
+                    name,
+                    record_type,
+                    server,
+                    rcode_name(response.rcode),
+                    response.answers.len()
+                );
+                if !response.answers.is_empty() {
+                    print!("{}", records_table(&response.answers));
+                }
+            }
+            Err(error) => {
+                eprintln!("{}: {}", program, error);
+                status = 1;
+            }
+        }
+    }
+    status
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+const MAX_DATAGRAM: usize = 512;
+
+fn zone(name: &str, record_type: RecordType) -> Option<Vec<Record>> {
+    let record = |name: &str, data| Record {
+        name: name.to_string(),
+        class: CLASS_IN,
+        ttl: 300,
+        data,
+    };
+    let apex = "example.test";
+    let records = match name.to_ascii_lowercase().as_str() {
+        "example.test" => vec![
+            record(apex, RecordData::A(Ipv4Addr::new(192, 0, 2, 1))),
+            record(apex, RecordData::A(Ipv4Addr::new(192, 0, 2, 2))),
+            record(apex, RecordData::Aaaa("2001:db8::1".parse().unwrap())),
+            record(apex, RecordData::Txt(vec![String::from("v=spf1 -all")])),
+        ],
+        "www.example.test" => vec![record(
+            "www.example.test",
+            RecordData::Cname(String::from("web.example.test")),
+        )],
+        "web.example.test" => vec![record(
+            "web.example.test",
+            RecordData::Cname(String::from(apex)),
+        )],
+        "big.example.test" => (0..12)
+            .map(|index| {
+                let text = format!("{:02} {}", index, "x".repeat(60));
+                record("big.example.test", RecordData::Txt(vec![text]))
+            })
+            .collect(),
+        "silent.example.test" => return Some(Vec::new()),
+        _ => return None,
+    };
+    let (mut matching, aliases): (Vec<Record>, Vec<Record>) = records
+        .into_iter()
+        .partition(|record| record.data.record_type() == record_type);
+    // An alias is answered with the records of its target, too.
+    if let Some(Record {
+        data: RecordData::Cname(target),
+        ..
+    }) = aliases.first()
+    {
+        if record_type != RecordType::Cname {
+            let target = target.clone();
+            matching = aliases;
+            matching.extend(zone(&target, record_type).unwrap_or_default());
+        }
+    }
+    Some(matching)
+}
+
+// The response of the local server, and whether it should be sent at all.
+fn respond(query: &Message, datagram: bool) -> Option<Vec<u8>> {
+    let question = query.questions.first()?;
+    if question.name == "silent.example.test" {
+        return None;
+    }
+    let mut response = Message {
+        id: query.id,
+        response: true,
+        authoritative: true,
+        recursion_desired: query.recursion_desired,
+        questions: query.questions.clone(),
+        ..Message::default()
+    };
+    match zone(&question.name, question.record_type) {
+        Some(answers) => response.answers = answers,
+        None => response.rcode = NXDOMAIN,
+    }
+    let packet = response.encode().ok()?;
+    if datagram && packet.len() > MAX_DATAGRAM {
+        response.answers.clear();
+        response.truncated = true;
+        return response.encode().ok();
+    }
+    Some(packet)
+}
+
+pub fn demo(harness: &mut Harness) {
+    let query = Message::query(0x1234, "www.example.com", RecordType::A);
+    test!(
+        harness,
+        "a query is encoded by hand",
+        query.encode(),
+        Ok(vec![
+            0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0, 3, b'w', b'w', b'w', 7, b'e', b'x',
+            b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm', 0, 0, 1, 0, 1
+        ])
+    );
+    test!(
+        harness,
+        "invalid names are rejected",
+        [
+            encode_name("a..b").map_err(|error| error.kind),
+            encode_name(&"x".repeat(64)).map_err(|error| error.kind),
+            encode_name(&["abc"; 70].join(".")).map_err(|error| error.kind),
+            encode_name(".").map_err(|error| error.kind),
+        ],
+        [
+            Err(ErrorKind::InvalidName),
+            Err(ErrorKind::LabelTooLong),
+            Err(ErrorKind::NameTooLong),
+            Ok(vec![0])
+        ]
+    );
+
+    // The example of RFC 1035: F.ISI.ARPA at 20, FOO.F.ISI.ARPA at 40, which
+    // points to the former, and the root at 64.
+    let mut packet = vec![0; 70];
+    packet[20..32].copy_from_slice(b"\x01F\x03ISI\x04ARPA\x00");
+    packet[40..46].copy_from_slice(b"\x03FOO\xc0\x14");
+    packet[64] = 0;
+    test!(
+        harness,
+        "compressed names are decoded",
+        [20, 40, 64].map(|offset| decode_name(&packet, offset)),
+        [
+            Ok((String::from("F.ISI.ARPA"), 32)),
+            Ok((String::from("FOO.F.ISI.ARPA"), 46)),
+            Ok((String::new(), 65))
+        ]
+    );
+    let looping = [0xc0, 0x02, 0xc0, 0x00];
+    test!(
+        harness,
+        "pointers which do not point backwards are rejected",
+        (decode_name(&looping, 0), decode_name(&looping, 2)),
+        (
+            Err(error(ErrorKind::BadPointer, 0)),
+            Err(error(ErrorKind::BadPointer, 0))
+        )
+    );
+
+    let mut response = Message {
+        id: 7,
+        response: true,
+        recursion_available: true,
+        ..Message::query(7, "www.example.test", RecordType::A)
+    };
+    response.answers = zone("www.example.test", RecordType::A).unwrap();
+    response.additionals = vec![Record {
+        name: String::from("example.test"),
+        class: CLASS_IN,
+        ttl: 60,
+        data: RecordData::Other(99, vec![1, 2, 3]),
+    }];
+    let encoded = response.encode().unwrap();
+    test!(
+        harness,
+        "a response survives encoding and parsing",
+        Message::parse(&encoded),
+        Ok(response.clone())
+    );
+    let mut twice = Message::query(1, "example.test", RecordType::A);
+    twice
+        .questions
+        .extend(Message::query(1, "www.example.test", RecordType::A).questions);
+    test!(
+        harness,
+        "a repeated suffix is replaced by a pointer to its first occurrence",
+        twice.encode().map(|bytes| bytes[30..].to_vec()),
+        Ok(b"\x03www\xc0\x0c\x00\x01\x00\x01".to_vec())
+    );
+    test!(
+        harness,
+        "a truncated message is an error",
+        Message::parse(&encoded[..encoded.len() - 2]).map_err(|error| error.kind),
+        Err(ErrorKind::Truncated)
+    );
+    test!(
+        harness,
+        "records are printed in the zone file format",
+        [
+            response.answers[0].to_string(),
+            response.answers[2].to_string(),
+            response.additionals[0].to_string(),
+            RecordData::Txt(vec![String::from("a \"quoted\""), String::from("b")]).to_string(),
+        ],
+        [
+            "www.example.test. 300 IN CNAME web.example.test.",
+            "example.test. 300 IN A 192.0.2.1",
+            "example.test. 60 IN TYPE99 \\# 3 010203",
+            "\"a \\\"quoted\\\"\" \"b\"",
+        ]
+        .map(String::from)
+    );
+
+    // A local server for the zone, over UDP and TCP, on the same port.
+    let udp = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let server = udp.local_addr().unwrap();
+    let tcp = TcpListener::bind(server).unwrap();
+    let stop = Arc::new(AtomicBool::new(false));
+    let udp_thread = {
+        let stop = Arc::clone(&stop);
+        udp.set_read_timeout(Some(Duration::from_millis(20)))
+            .unwrap();
+        thread::spawn(move || {
+            let mut buffer = [0; MAX_DATAGRAM];
+            while !stop.load(Ordering::SeqCst) {
+                let Ok((received, peer)) = udp.recv_from(&mut buffer) else {
+                    continue;
+                };
+                let reply = Message::parse(&buffer[..received])
+                    .ok()
+                    .and_then(|query| respond(&query, true));
+                if let Some(reply) = reply {
+                    let _ = udp.send_to(&reply, peer);
+                }
+            }
+        })
+    };
+    let tcp_thread = {
+        let stop = Arc::clone(&stop);
+        thread::spawn(move || {
+            for stream in tcp.incoming() {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(mut stream) = stream else { continue };
+                let mut length = [0; 2];
+                let mut query = Vec::new();
+                let read = stream.read_exact(&mut length).and_then(|_| {
+                    query.resize(u16::from_be_bytes(length) as usize, 0);
+                    stream.read_exact(&mut query)
+                });
+                let reply = read
+                    .ok()
+                    .and_then(|_| Message::parse(&query).ok())
+                    .and_then(|query| respond(&query, false));
+                if let Some(reply) = reply {
+                    let mut framed = (reply.len() as u16).to_be_bytes().to_vec();
+                    framed.extend_from_slice(&reply);
+                    let _ = stream.write_all(&framed);
+                }
+            }
+        })
+    };
+
+    let mut resolver = Resolver::new(server).timeout(Duration::from_secs(2));
+    test!(
+        harness,
+        "the addresses of a name are resolved",
+        resolver
+            .lookup("example.test", RecordType::A)
+            .map_err(|error| error.kind()),
+        Ok(vec![
+            RecordData::A(Ipv4Addr::new(192, 0, 2, 1)),
+            RecordData::A(Ipv4Addr::new(192, 0, 2, 2))
+        ])
+    );
+    test!(
+        harness,
+        "a chain of aliases is followed",
+        resolver
+            .lookup("WWW.example.test.", RecordType::Aaaa)
+            .map_err(|error| error.kind()),
+        Ok(vec![RecordData::Aaaa("2001:db8::1".parse().unwrap())])
+    );
+    test!(
+        harness,
+        "text records are resolved",
+        resolver
+            .lookup("example.test", RecordType::Txt)
+            .map_err(|error| error.kind()),
+        Ok(vec![RecordData::Txt(vec![String::from("v=spf1 -all")])])
+    );
+    let missing = resolver
+        .query("nowhere.example.test", RecordType::A)
+        .unwrap();
+    test!(
+        harness,
+        "a missing name is reported by the response code",
+        (rcode_name(missing.rcode), missing.answers.len()),
+        ("NXDOMAIN", 0)
+    );
+    let big = resolver.query("big.example.test", RecordType::Txt).unwrap();
+    test!(
+        harness,
+        "a truncated response is repeated over TCP",
+        (big.truncated, big.answers.len()),
+        (false, 12)
+    );
+    test!(
+        harness,
+        "a server which does not answer times out",
+        Resolver::new(server)
+            .timeout(Duration::from_millis(50))
+            .attempts(2)
+            .query("silent.example.test", RecordType::A)
+            .map_err(|error| error.kind()),
+        Err(io::ErrorKind::TimedOut)
+    );
+
+    for name in ["example.test", "www.example.test"] {
+        for record_type in [RecordType::A, RecordType::Aaaa, RecordType::Txt] {
+            let response = resolver.query(name, record_type).unwrap();
+            note!(harness, ";; {} {}:", name, record_type);
+            note!(harness, "{}", records_table(&response.answers));
+        }
+    }
+
+    stop.store(true, Ordering::SeqCst);
+    let _ = TcpStream::connect(server);
+    udp_thread.join().unwrap();
+    tcp_thread.join().unwrap();
+}
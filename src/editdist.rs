@@ -0,0 +1,163 @@
+// editdist.rs
+// Levenshtein distance between two strings, calculated with the classic dynamic
+// programming matrix, where each cell holds the distance between two prefixes.
+// Walking back through the matrix from the bottom right corner reconstructs an
+// edit script, the list of insertions, deletions, and substitutions which turn
+// the first string into the second one.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edit {
+    Keep(char),
+    Insert(char),
+    Delete(char),
+    Substitute(char, char),
+}
+
+impl fmt::Display for Edit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Edit::Keep(c) => write!(f, "keep '{}'", c),
+            Edit::Insert(c) => write!(f, "insert '{}'", c),
+            Edit::Delete(c) => write!(f, "delete '{}'", c),
+            Edit::Substitute(from, to) => write!(f, "substitute '{}' with '{}'", from, to),
+        }
+    }
+}
+
+pub fn matrix(from: &[char], to: &[char]) -> Vec<Vec<usize>> {
+    let mut matrix = vec![vec![0; to.len() + 1]; from.len() + 1];
+    for (i, row) in matrix.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in matrix[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=from.len() {
+        for j in 1..=to.len() {
+            let substitution = if from[i - 1] == to[j - 1] { 0 } else { 1 };
+            matrix[i][j] = (matrix[i - 1][j - 1] + substitution)
+                .min(matrix[i - 1][j] + 1)
+                .min(matrix[i][j - 1] + 1);
+        }
+    }
+    matrix
+}
+
+pub fn distance(from: &str, to: &str) -> usize {
+    let from: Vec<char> = from.chars().collect();
+    let to: Vec<char> = to.chars().collect();
+    matrix(&from, &to)[from.len()][to.len()]
+}
+
+pub fn edit_script(from: &str, to: &str) -> Vec<Edit> {
+    let from: Vec<char> = from.chars().collect();
+    let to: Vec<char> = to.chars().collect();
+    let matrix = matrix(&from, &to);
+
+    // Any step back which is consistent with the costs in the matrix is part
+    // of an optimal script. Diagonal steps are preferred, to keep the script
+    // short and readable.
+    let mut script = Vec::new();
+    let (mut i, mut j) = (from.len(), to.len());
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && from[i - 1] == to[j - 1] && matrix[i][j] == matrix[i - 1][j - 1] {
+            script.push(Edit::Keep(from[i - 1]));
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && j > 0 && matrix[i][j] == matrix[i - 1][j - 1] + 1 {
+            script.push(Edit::Substitute(from[i - 1], to[j - 1]));
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && matrix[i][j] == matrix[i - 1][j] + 1 {
+            script.push(Edit::Delete(from[i - 1]));
+            i -= 1;
+        } else {
+            script.push(Edit::Insert(to[j - 1]));
+            j -= 1;
+        }
+    }
+    script.reverse();
+    script
+}
+
+// Prints the two strings aligned above each other, with a line marking the
+// substituted (S), inserted (I), and deleted (D) characters below them.
+pub fn format_alignment(script: &[Edit]) -> String {
+    let mut top = String::new();
+    let mut bottom = String::new();
+    let mut marks = String::new();
+    for edit in script {
+        let (upper, lower, mark) = match *edit {
+            Edit::Keep(c) => (c, c, ' '),
+            Edit::Insert(c) => ('-', c, 'I'),
+            Edit::Delete(c) => (c, '-', 'D'),
+            Edit::Substitute(from, to) => (from, to, 'S'),
+        };
+        top.push(upper);
+        bottom.push(lower);
+        marks.push(mark);
+    }
+    format!("{}\n{}\n{}", top, bottom, marks.trim_end())
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    const PAIRS: [(&str, &str, usize); 7] = [
+        ("kitten", "sitting", 3),
+        ("flaw", "lawn", 2),
+        ("saturday", "sunday", 3),
+        ("intention", "execution", 5),
+        ("", "abc", 3),
+        ("same", "same", 0),
+        ("árvíztűrő", "tükörfúrógép", 11),
+    ];
+
+    for (from, to, expected) in PAIRS {
+        let description = format!(
+            "the distance between {:?} and {:?} is {}",
+            from, to, expected
+        );
+        test!(harness, &description, distance(from, to), expected);
+
+        let script = edit_script(from, to);
+        let cost = script
+            .iter()
+            .filter(|edit| !matches!(edit, Edit::Keep(_)))
+            .count();
+        let description = format!("the edit script from {:?} to {:?} is optimal", from, to);
+        test!(harness, &description, cost, expected);
+
+        let mut applied = String::new();
+        for edit in &script {
+            match edit {
+                Edit::Keep(c) | Edit::Insert(c) | Edit::Substitute(_, c) => applied.push(*c),
+                Edit::Delete(_) => {}
+            }
+        }
+        let description = format!("applying the edit script to {:?} yields {:?}", from, to);
+        test!(harness, &description, applied, to);
+    }
+
+    let script = edit_script("kitten", "sitting");
+    note!(harness, "Edit script from \"kitten\" to \"sitting\":");
+    for edit in script.iter().filter(|edit| !matches!(edit, Edit::Keep(_))) {
+        note!(harness, "  {}", edit);
+    }
+    note!(harness, "\n{}\n", format_alignment(&script));
+    note!(
+        harness,
+        "{}\n",
+        format_alignment(&edit_script("intention", "execution"))
+    );
+}
@@ -0,0 +1,328 @@
+// encoding.rs
+// Base64 (standard and URL-safe), Base32, and Base16 (hexadecimal) encoding and
+// decoding, as described in RFC 4648. All of them work the same way: the input
+// is treated as a stream of bits, which is cut into groups of 6, 5, or 4 bits,
+// and each group is mapped to a character of the alphabet. The output is padded
+// to the length of a full block, which is 4, 8, or 2 characters long.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    InvalidCharacter { position: usize, character: char },
+    InvalidPadding { position: usize },
+    InvalidLength { length: usize },
+    TrailingBits,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::InvalidCharacter {
+                position,
+                character,
+            } => write!(
+                f,
+                "invalid character {:?} at position {}",
+                character, position
+            ),
+            DecodeError::InvalidPadding { position } => {
+                write!(f, "invalid padding at position {}", position)
+            }
+            DecodeError::InvalidLength { length } => {
+                write!(f, "{} characters can not be decoded", length)
+            }
+            DecodeError::TrailingBits => write!(f, "the unused trailing bits are not zero"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+pub struct Encoding {
+    pub alphabet: &'static [u8],
+    pub bits: u32,
+    pub padding: bool,
+    pub case_insensitive: bool,
+}
+
+pub const BASE64: Encoding = Encoding {
+    alphabet: b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+    bits: 6,
+    padding: true,
+    case_insensitive: false,
+};
+
+pub const BASE64_URL: Encoding = Encoding {
+    alphabet: b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+    bits: 6,
+    padding: true,
+    case_insensitive: false,
+};
+
+pub const BASE32: Encoding = Encoding {
+    alphabet: b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+    bits: 5,
+    padding: true,
+    case_insensitive: false,
+};
+
+pub const BASE16: Encoding = Encoding {
+    alphabet: b"0123456789ABCDEF",
+    bits: 4,
+    padding: false,
+    case_insensitive: true,
+};
+
+// Lowercase hexadecimal, as hash digests are usually printed.
+pub const HEX: Encoding = Encoding {
+    alphabet: b"0123456789abcdef",
+    bits: 4,
+    padding: false,
+    case_insensitive: true,
+};
+
+const PAD: u8 = b'=';
+
+impl Encoding {
+    // The number of characters which encode a whole number of bytes.
+    fn block_length(&self) -> usize {
+        let mut length = 1;
+        while !(length * self.bits).is_multiple_of(8) {
+            length += 1;
+        }
+        length as usize
+    }
+
+    pub fn encode(&self, data: &[u8]) -> String {
+        let mask = (1u32 << self.bits) - 1;
+        let mut result = String::with_capacity(data.len() * 8 / self.bits as usize + 8);
+        let mut buffer = 0u32;
+        let mut buffered = 0;
+
+        for byte in data {
+            buffer = (buffer << 8) | *byte as u32;
+            buffered += 8;
+            while buffered >= self.bits {
+                buffered -= self.bits;
+                result.push(self.alphabet[((buffer >> buffered) & mask) as usize] as char);
+            }
+        }
+        if buffered > 0 {
+            let index = (buffer << (self.bits - buffered)) & mask;
+            result.push(self.alphabet[index as usize] as char);
+        }
+
+        if self.padding {
+            while !result.len().is_multiple_of(self.block_length()) {
+                result.push(PAD as char);
+            }
+        }
+        result
+    }
+
+    pub fn decode(&self, text: &str) -> Result<Vec<u8>, DecodeError> {
+        let input = text.as_bytes();
+
+        // Padding is optional, but if it is present, it must complete the
+        // last block, and must not be followed by anything else.
+        let data_length = input.iter().position(|c| *c == PAD).unwrap_or(input.len());
+        if data_length < input.len() {
+            if let Some(position) = input[data_length..].iter().position(|c| *c != PAD) {
+                return Err(DecodeError::InvalidPadding {
+                    position: data_length + position,
+                });
+            }
+            if !input.len().is_multiple_of(self.block_length())
+                || input.len() - data_length >= self.block_length()
+            {
+                return Err(DecodeError::InvalidPadding {
+                    position: data_length,
+                });
+            }
+        }
+
+        let bytes = data_length * self.bits as usize / 8;
+        let needed = (bytes * 8).div_ceil(self.bits as usize);
+        if needed != data_length {
+            return Err(DecodeError::InvalidLength {
+                length: data_length,
+            });
+        }
+
+        let mut result = Vec::with_capacity(bytes);
+        let mut buffer = 0u32;
+        let mut buffered = 0;
+        for (position, c) in text[..data_length].char_indices() {
+            let value = self.value_of(c).ok_or(DecodeError::InvalidCharacter {
+                position,
+                character: c,
+            })?;
+            buffer = (buffer << self.bits) | value;
+            buffered += self.bits;
+            if buffered >= 8 {
+                buffered -= 8;
+                result.push((buffer >> buffered) as u8);
+            }
+        }
+
+        if buffer & ((1 << buffered) - 1) != 0 {
+            return Err(DecodeError::TrailingBits);
+        }
+        Ok(result)
+    }
+
+    fn value_of(&self, c: char) -> Option<u32> {
+        if !c.is_ascii() {
+            return None;
+        }
+        let byte = c as u8;
+        self.alphabet
+            .iter()
+            .position(|symbol| {
+                *symbol == byte || (self.case_insensitive && symbol.eq_ignore_ascii_case(&byte))
+            })
+            .map(|index| index as u32)
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    const INPUTS: [&str; 7] = ["", "f", "fo", "foo", "foob", "fooba", "foobar"];
+    const VECTORS: [(&str, &Encoding, [&str; 7]); 3] = [
+        (
+            "Base64",
+            &BASE64,
+            [
+                "", "Zg==", "Zm8=", "Zm9v", "Zm9vYg==", "Zm9vYmE=", "Zm9vYmFy",
+            ],
+        ),
+        (
+            "Base32",
+            &BASE32,
+            [
+                "",
+                "MY======",
+                "MZXQ====",
+                "MZXW6===",
+                "MZXW6YQ=",
+                "MZXW6YTB",
+                "MZXW6YTBOI======",
+            ],
+        ),
+        (
+            "Base16",
+            &BASE16,
+            [
+                "",
+                "66",
+                "666F",
+                "666F6F",
+                "666F6F62",
+                "666F6F6261",
+                "666F6F626172",
+            ],
+        ),
+    ];
+
+    for (name, encoding, outputs) in VECTORS {
+        for (input, output) in INPUTS.iter().zip(outputs) {
+            let description = format!("{} encoding of {:?} matches RFC 4648", name, input);
+            test!(
+                harness,
+                &description,
+                encoding.encode(input.as_bytes()),
+                output
+            );
+            let description = format!("{} decoding of {:?} matches RFC 4648", name, output);
+            test!(
+                harness,
+                &description,
+                encoding.decode(output),
+                Ok(input.as_bytes().to_vec())
+            );
+        }
+    }
+
+    test!(
+        harness,
+        "URL-safe Base64 uses - and _ instead of + and /",
+        (
+            BASE64.encode(&[0xfb, 0xff]),
+            BASE64_URL.encode(&[0xfb, 0xff])
+        ),
+        ("+/8=".to_string(), "-_8=".to_string())
+    );
+    test!(
+        harness,
+        "decoding accepts input without padding",
+        BASE64_URL.decode("-_8"),
+        Ok(vec![0xfb, 0xff])
+    );
+    test!(
+        harness,
+        "hexadecimal decoding is case-insensitive",
+        HEX.decode("DeadBeef"),
+        Ok(vec![0xde, 0xad, 0xbe, 0xef])
+    );
+    test!(
+        harness,
+        "invalid characters are reported with their position",
+        BASE64.decode("Zm9v!mFy"),
+        Err(DecodeError::InvalidCharacter {
+            position: 4,
+            character: '!'
+        })
+    );
+    test!(
+        harness,
+        "characters after the padding are reported as invalid padding",
+        BASE64.decode("Zg==Zg=="),
+        Err(DecodeError::InvalidPadding { position: 4 })
+    );
+    test!(
+        harness,
+        "incomplete padding is reported as invalid padding",
+        BASE64.decode("Zg="),
+        Err(DecodeError::InvalidPadding { position: 2 })
+    );
+    test!(
+        harness,
+        "a dangling character is reported as invalid length",
+        BASE64.decode("Zm9vY"),
+        Err(DecodeError::InvalidLength { length: 5 })
+    );
+    test!(
+        harness,
+        "non-zero unused bits are rejected",
+        BASE64.decode("Zh=="),
+        Err(DecodeError::TrailingBits)
+    );
+    if let Err(error) = BASE32.decode("MZXW6Y1B") {
+        note!(
+            harness,
+            "Decoding MZXW6Y1B as Base32 fails with: {}\n",
+            error
+        );
+    }
+
+    let binary: Vec<u8> = (0..=255).collect();
+    for (name, encoding) in [
+        ("Base64", &BASE64),
+        ("URL-safe Base64", &BASE64_URL),
+        ("Base32", &BASE32),
+        ("Base16", &BASE16),
+    ] {
+        let description = format!("{} round-trips every byte value", name);
+        let decoded = encoding.decode(&encoding.encode(&binary));
+        test!(harness, &description, decoded, Ok(binary.clone()));
+    }
+}
@@ -0,0 +1,445 @@
+// eventbus.rs
+// An event bus, implementing the observer pattern with typed topics. A topic
+// has a name and the type of its events, so a handler subscribed to it gets
+// the events by reference, without any casting on its side; two topics with
+// the same name but different types are kept apart. Subscribing returns a
+// handle, which unsubscribes the handler later. An event can be published,
+// when the handlers are called right away, in the order of subscription, or
+// posted, when it is queued, and delivered only when the queue is drained.
+// The queue can also be reached through senders, which the handlers can
+// capture, so that they can post further events while they are running;
+// draining goes on until the queue is empty, so these are delivered too,
+// after the events already waiting. Internally, the handlers of a topic are
+// stored with their type erased, and each queued event remembers how to
+// deliver itself. The demonstration simulates a small shop, where orders
+// lead to shipments, low stock warnings, and restocking.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+pub struct Topic<T> {
+    name: &'static str,
+    marker: PhantomData<fn(&T)>,
+}
+
+impl<T> Topic<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T> Clone for Topic<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Topic<T> {}
+
+type Key = (&'static str, TypeId);
+
+fn key<T: 'static>(topic: &Topic<T>) -> Key {
+    (topic.name, TypeId::of::<T>())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Subscription {
+    key: Key,
+    id: u64,
+}
+
+type Handlers<T> = Vec<(u64, Box<dyn FnMut(&T)>)>;
+
+// The handlers of a topic, with the type of the events erased.
+trait Slot {
+    fn len(&self) -> usize;
+    fn remove(&mut self, id: u64) -> bool;
+    fn as_any(&mut self) -> &mut dyn Any;
+}
+
+impl<T: 'static> Slot for Handlers<T> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn remove(&mut self, id: u64) -> bool {
+        let count = self.len();
+        self.retain(|(handler, _)| *handler != id);
+        self.len() != count
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+struct Pending {
+    key: Key,
+    event: Box<dyn Any>,
+    deliver: fn(&mut EventBus, Key, &dyn Any) -> usize,
+}
+
+type Queue = Rc<RefCell<VecDeque<Pending>>>;
+
+fn deliver<T: 'static>(bus: &mut EventBus, key: Key, event: &dyn Any) -> usize {
+    match event.downcast_ref::<T>() {
+        Some(event) => bus.dispatch(key, event),
+        None => 0,
+    }
+}
+
+fn enqueue<T: 'static>(queue: &Queue, topic: &Topic<T>, event: T) {
+    queue.borrow_mut().push_back(Pending {
+        key: key(topic),
+        event: Box::new(event),
+        deliver: deliver::<T>,
+    });
+}
+
+// A handle to the queue of a bus, which can be captured by the handlers.
+#[derive(Clone)]
+pub struct Sender {
+    queue: Queue,
+}
+
+impl Sender {
+    pub fn post<T: 'static>(&self, topic: &Topic<T>, event: T) {
+        enqueue(&self.queue, topic, event);
+    }
+}
+
+pub struct EventBus {
+    topics: HashMap<Key, Box<dyn Slot>>,
+    queue: Queue,
+    next_id: u64,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self {
+            topics: HashMap::new(),
+            queue: Rc::new(RefCell::new(VecDeque::new())),
+            next_id: 0,
+        }
+    }
+
+    fn handlers<T: 'static>(&mut self, key: Key) -> Option<&mut Handlers<T>> {
+        self.topics
+            .get_mut(&key)?
+            .as_any()
+            .downcast_mut::<Handlers<T>>()
+    }
+
+    pub fn subscribe<T: 'static>(
+        &mut self,
+        topic: &Topic<T>,
+        handler: impl FnMut(&T) + 'static,
+    ) -> Subscription {
+        let key = key(topic);
+        let id = self.next_id;
+        self.next_id += 1;
+        self.topics
+            .entry(key)
+            .or_insert_with(|| Box::new(Handlers::<T>::new()))
+            .as_any()
+            .downcast_mut::<Handlers<T>>()
+            .expect("the handlers of a topic have the type of its events")
+            .push((id, Box::new(handler)));
+        Subscription { key, id }
+    }
+
+    // Whether the handler was still subscribed.
+    pub fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+        self.topics
+            .get_mut(&subscription.key)
+            .is_some_and(|handlers| handlers.remove(subscription.id))
+    }
+
+    pub fn subscribers<T: 'static>(&self, topic: &Topic<T>) -> usize {
+        self.topics
+            .get(&key(topic))
+            .map_or(0, |handlers| handlers.len())
+    }
+
+    fn dispatch<T: 'static>(&mut self, key: Key, event: &T) -> usize {
+        let Some(handlers) = self.handlers::<T>(key) else {
+            return 0;
+        };
+        for (_, handler) in handlers.iter_mut() {
+            handler(event);
+        }
+        handlers.len()
+    }
+
+    // Calls every handler of the topic right away, and returns their number.
+    pub fn publish<T: 'static>(&mut self, topic: &Topic<T>, event: &T) -> usize {
+        self.dispatch(key(topic), event)
+    }
+
+    pub fn post<T: 'static>(&mut self, topic: &Topic<T>, event: T) {
+        enqueue(&self.queue, topic, event);
+    }
+
+    pub fn sender(&self) -> Sender {
+        Sender {
+            queue: Rc::clone(&self.queue),
+        }
+    }
+
+    pub fn pending(&self) -> usize {
+        self.queue.borrow().len()
+    }
+
+    // Delivers the queued events, and the ones posted meanwhile, and returns
+    // the number of handler calls.
+    pub fn drain(&mut self) -> usize {
+        let mut calls = 0;
+        loop {
+            let next = self.queue.borrow_mut().pop_front();
+            let Some(pending) = next else {
+                return calls;
+            };
+            calls += (pending.deliver)(self, pending.key, pending.event.as_ref());
+        }
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use std::collections::BTreeMap;
+
+struct OrderPlaced {
+    order: u32,
+    item: &'static str,
+    quantity: u32,
+}
+
+struct Shipped {
+    order: u32,
+}
+
+struct Rejected {
+    order: u32,
+    item: &'static str,
+}
+
+struct StockLow {
+    item: &'static str,
+    left: u32,
+}
+
+struct Restocked {
+    item: &'static str,
+    quantity: u32,
+}
+
+const ORDERS: Topic<OrderPlaced> = Topic::new("orders");
+const SHIPMENTS: Topic<Shipped> = Topic::new("shipments");
+const REJECTIONS: Topic<Rejected> = Topic::new("rejections");
+const STOCK_LOW: Topic<StockLow> = Topic::new("stock low");
+const RESTOCKS: Topic<Restocked> = Topic::new("restocks");
+
+type Stock = Rc<RefCell<BTreeMap<&'static str, u32>>>;
+type Log = Rc<RefCell<Vec<String>>>;
+
+// The warehouse ships or rejects the orders, and asks for more when the
+// stock runs low, while the supplier delivers, and the auditor writes down
+// everything, which happens.
+fn shop(bus: &mut EventBus, stock: &Stock, log: &Log) {
+    let (sender, shelves) = (bus.sender(), Rc::clone(stock));
+    bus.subscribe(&ORDERS, move |placed| {
+        let mut shelves = shelves.borrow_mut();
+        let left = shelves.entry(placed.item).or_insert(0);
+        if *left < placed.quantity {
+            let (order, item) = (placed.order, placed.item);
+            sender.post(&REJECTIONS, Rejected { order, item });
+            return;
+        }
+        *left -= placed.quantity;
+        sender.post(
+            &SHIPMENTS,
+            Shipped {
+                order: placed.order,
+            },
+        );
+        if *left < 3 {
+            let (item, left) = (placed.item, *left);
+            sender.post(&STOCK_LOW, StockLow { item, left });
+        }
+    });
+    let sender = bus.sender();
+    bus.subscribe(&STOCK_LOW, move |low| {
+        let item = low.item;
+        sender.post(&RESTOCKS, Restocked { item, quantity: 10 });
+    });
+    let shelves = Rc::clone(stock);
+    bus.subscribe(&RESTOCKS, move |restocked| {
+        *shelves.borrow_mut().entry(restocked.item).or_insert(0) += restocked.quantity;
+    });
+
+    let audit = Rc::clone(log);
+    bus.subscribe(&ORDERS, move |placed| {
+        let (order, quantity, item) = (placed.order, placed.quantity, placed.item);
+        audit
+            .borrow_mut()
+            .push(format!("order {}: {} {}", order, quantity, item));
+    });
+    let audit = Rc::clone(log);
+    bus.subscribe(&SHIPMENTS, move |shipped| {
+        audit
+            .borrow_mut()
+            .push(format!("shipped {}", shipped.order));
+    });
+    let audit = Rc::clone(log);
+    bus.subscribe(&REJECTIONS, move |rejected| {
+        let (order, item) = (rejected.order, rejected.item);
+        audit
+            .borrow_mut()
+            .push(format!("rejected {}: out of {}", order, item));
+    });
+    let audit = Rc::clone(log);
+    bus.subscribe(&STOCK_LOW, move |low| {
+        audit
+            .borrow_mut()
+            .push(format!("low on {}: {} left", low.item, low.left));
+    });
+    let audit = Rc::clone(log);
+    bus.subscribe(&RESTOCKS, move |restocked| {
+        let (quantity, item) = (restocked.quantity, restocked.item);
+        audit
+            .borrow_mut()
+            .push(format!("restocked {} {}", quantity, item));
+    });
+}
+
+pub fn demo(harness: &mut Harness) {
+    let mut bus = EventBus::new();
+    let seen: Log = Rc::new(RefCell::new(Vec::new()));
+    let (first, second) = (Rc::clone(&seen), Rc::clone(&seen));
+    let numbers: Topic<u32> = Topic::new("numbers");
+    let words: Topic<&str> = Topic::new("numbers");
+    let subscription = bus.subscribe(&numbers, move |n| {
+        first.borrow_mut().push(format!("a{}", n))
+    });
+    bus.subscribe(&numbers, move |n| {
+        second.borrow_mut().push(format!("b{}", n))
+    });
+    let third = Rc::clone(&seen);
+    bus.subscribe(&words, move |word| {
+        third.borrow_mut().push(word.to_string())
+    });
+    test!(
+        harness,
+        "publishing calls the handlers right away, in the order of subscription",
+        (bus.publish(&numbers, &1), seen.borrow().clone()),
+        (2, vec![String::from("a1"), String::from("b1")])
+    );
+    test!(
+        harness,
+        "topics with the same name but different types are kept apart",
+        (
+            bus.subscribers(&numbers),
+            bus.subscribers(&words),
+            bus.publish(&words, &"one")
+        ),
+        (2, 1, 1)
+    );
+    test!(
+        harness,
+        "unsubscribing works once",
+        (bus.unsubscribe(subscription), bus.unsubscribe(subscription)),
+        (true, false)
+    );
+    seen.borrow_mut().clear();
+    test!(
+        harness,
+        "an unsubscribed handler gets no more events",
+        (bus.publish(&numbers, &2), seen.borrow().clone()),
+        (1, vec![String::from("b2")])
+    );
+    test!(
+        harness,
+        "publishing to a topic without subscribers calls nobody",
+        bus.publish(&Topic::<u32>::new("nobody"), &3),
+        0
+    );
+
+    let mut bus = EventBus::default();
+    let stock: Stock = Rc::new(RefCell::new(BTreeMap::from([("apple", 5), ("pear", 1)])));
+    let log: Log = Rc::new(RefCell::new(Vec::new()));
+    shop(&mut bus, &stock, &log);
+    let orders = [(1, "apple", 2), (2, "pear", 3), (3, "apple", 2)];
+    for (order, item, quantity) in orders {
+        bus.post(
+            &ORDERS,
+            OrderPlaced {
+                order,
+                item,
+                quantity,
+            },
+        );
+    }
+    test!(
+        harness,
+        "posted events wait in the queue until it is drained",
+        (bus.pending(), log.borrow().len()),
+        (3, 0)
+    );
+    test!(
+        harness,
+        "draining delivers the events posted by the handlers too",
+        (bus.drain(), bus.pending()),
+        (3 * 2 + 2 + 1 + 2 + 2, 0)
+    );
+    test!(
+        harness,
+        "the events are delivered in the order they were posted",
+        log.borrow().clone(),
+        vec![
+            "order 1: 2 apple",
+            "order 2: 3 pear",
+            "order 3: 2 apple",
+            "shipped 1",
+            "rejected 2: out of pear",
+            "shipped 3",
+            "low on apple: 1 left",
+            "restocked 10 apple",
+        ]
+    );
+    test!(
+        harness,
+        "the handlers keep the stock up to date",
+        stock.borrow().clone(),
+        BTreeMap::from([("apple", 11), ("pear", 1)])
+    );
+    test!(
+        harness,
+        "draining an empty queue calls nobody",
+        bus.drain(),
+        0
+    );
+
+    note!(harness, "The shop log:\n  {}", log.borrow().join("\n  "));
+}
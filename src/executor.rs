@@ -0,0 +1,608 @@
+// executor.rs
+// A single-threaded executor for futures, built from the raw waker primitive,
+// along with a timer, a channel, and join handles, to show what async and await
+// come down to. An async block compiles into a state machine, a future, whose
+// poll runs it until the next await which is not ready yet, and it returns
+// pending, after handing a waker to whatever it waits for. The future below,
+// written by hand, is what the compiler would make of two sleeps in a row. The
+// wakers of the executor are reference-counted task numbers, behind a table of
+// four functions, and waking one puts its task back into the queue of the ready
+// tasks. Only the ready tasks are polled: when there are none, the executor
+// sleeps until the earliest timer, and it wakes the tasks whose timers expired.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use std::time::{Duration, Instant};
+
+// The wakers must be sendable to other threads, so the queue they share is
+// behind a mutex, even if the executor only polls on one thread.
+struct WakeTarget {
+    task: usize,
+    queued: AtomicBool,
+    ready: Arc<Mutex<VecDeque<usize>>>,
+}
+
+impl WakeTarget {
+    // A task is queued only once, however many times it is woken up.
+    fn schedule(&self) {
+        if !self.queued.swap(true, Ordering::AcqRel) {
+            self.ready.lock().unwrap().push_back(self.task);
+        }
+    }
+}
+
+// The data pointer of the raw waker is an Arc of the target, whose count is
+// shared by the clones of the waker.
+unsafe fn clone_waker(data: *const ()) -> RawWaker {
+    unsafe { Arc::increment_strong_count(data as *const WakeTarget) };
+    RawWaker::new(data, &VTABLE)
+}
+
+unsafe fn wake(data: *const ()) {
+    let target = unsafe { Arc::from_raw(data as *const WakeTarget) };
+    target.schedule();
+}
+
+unsafe fn wake_by_ref(data: *const ()) {
+    let target = unsafe { &*(data as *const WakeTarget) };
+    target.schedule();
+}
+
+unsafe fn drop_waker(data: *const ()) {
+    drop(unsafe { Arc::from_raw(data as *const WakeTarget) });
+}
+
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone_waker, wake, wake_by_ref, drop_waker);
+
+fn make_waker(target: Arc<WakeTarget>) -> Waker {
+    let raw = RawWaker::new(Arc::into_raw(target) as *const (), &VTABLE);
+    unsafe { Waker::from_raw(raw) }
+}
+
+type LocalFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+struct Task {
+    future: Option<LocalFuture>,
+    target: Arc<WakeTarget>,
+    waker: Waker,
+}
+
+// The timers of the thread, by their deadlines, and a sequence number, which
+// keeps the timers with the same deadline apart.
+thread_local! {
+    static TIMERS: RefCell<BTreeMap<(Instant, u64), Waker>> = const { RefCell::new(BTreeMap::new()) };
+    static TIMER_SEQUENCE: Cell<u64> = const { Cell::new(0) };
+}
+
+struct Inner {
+    tasks: RefCell<Vec<Task>>,
+    ready: Arc<Mutex<VecDeque<usize>>>,
+    polls: Cell<usize>,
+}
+
+#[derive(Clone)]
+pub struct Spawner {
+    inner: Rc<Inner>,
+}
+
+pub struct Executor {
+    inner: Rc<Inner>,
+}
+
+struct JoinState<T> {
+    result: Option<T>,
+    waker: Option<Waker>,
+}
+
+// A future which completes with the result of a spawned task.
+pub struct JoinHandle<T> {
+    state: Rc<RefCell<JoinState<T>>>,
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<T> {
+        let mut state = self.state.borrow_mut();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(context.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Spawner {
+    pub fn spawn<T: 'static>(&self, future: impl Future<Output = T> + 'static) -> JoinHandle<T> {
+        let state = Rc::new(RefCell::new(JoinState {
+            result: None,
+            waker: None,
+        }));
+        let task_state = Rc::clone(&state);
+        let future = async move {
+            let result = future.await;
+            let mut state = task_state.borrow_mut();
+            state.result = Some(result);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        };
+        let mut tasks = self.inner.tasks.borrow_mut();
+        let target = Arc::new(WakeTarget {
+            task: tasks.len(),
+            queued: AtomicBool::new(false),
+            ready: Arc::clone(&self.inner.ready),
+        });
+        target.schedule();
+        tasks.push(Task {
+            future: Some(Box::pin(future)),
+            waker: make_waker(Arc::clone(&target)),
+            target,
+        });
+        JoinHandle { state }
+    }
+}
+
+impl Executor {
+    pub fn new() -> Self {
+        Self {
+            inner: Rc::new(Inner {
+                tasks: RefCell::new(Vec::new()),
+                ready: Arc::new(Mutex::new(VecDeque::new())),
+                polls: Cell::new(0),
+            }),
+        }
+    }
+
+    pub fn spawner(&self) -> Spawner {
+        Spawner {
+            inner: Rc::clone(&self.inner),
+        }
+    }
+
+    pub fn spawn<T: 'static>(&self, future: impl Future<Output = T> + 'static) -> JoinHandle<T> {
+        self.spawner().spawn(future)
+    }
+
+    // The number of times a task was polled so far.
+    pub fn polls(&self) -> usize {
+        self.inner.polls.get()
+    }
+
+    // The future is taken out of its slot while it is polled, so it can spawn
+    // new tasks, which are pushed onto the table.
+    fn poll_task(&self, index: usize) {
+        let (future, waker) = {
+            let mut tasks = self.inner.tasks.borrow_mut();
+            let task = &mut tasks[index];
+            task.target.queued.store(false, Ordering::Release);
+            match task.future.take() {
+                Some(future) => (future, task.waker.clone()),
+                None => return,
+            }
+        };
+        let mut future = future;
+        self.inner.polls.set(self.inner.polls.get() + 1);
+        if future
+            .as_mut()
+            .poll(&mut Context::from_waker(&waker))
+            .is_pending()
+        {
+            self.inner.tasks.borrow_mut()[index].future = Some(future);
+        }
+    }
+
+    // Wakes the tasks whose timers expired. If no task is ready, it sleeps
+    // until the earliest timer first, and it returns false if there is none.
+    fn fire_timers(&self, wait: bool) -> bool {
+        let next = TIMERS.with(|timers| timers.borrow().keys().next().map(|key| key.0));
+        let Some(deadline) = next else {
+            return false;
+        };
+        let now = Instant::now();
+        if wait && deadline > now {
+            std::thread::sleep(deadline - now);
+        }
+        let now = Instant::now();
+        let expired: Vec<Waker> = TIMERS.with(|timers| {
+            let mut timers = timers.borrow_mut();
+            let later = timers.split_off(&(now, u64::MAX));
+            std::mem::replace(&mut *timers, later)
+                .into_values()
+                .collect()
+        });
+        expired.into_iter().for_each(Waker::wake);
+        true
+    }
+
+    // Runs until every task is finished, or until the remaining ones wait for
+    // something which can never happen, and returns the number of those.
+    pub fn run(&self) -> usize {
+        loop {
+            let next = self.inner.ready.lock().unwrap().pop_front();
+            match next {
+                Some(index) => {
+                    self.poll_task(index);
+                    self.fire_timers(false);
+                }
+                None => {
+                    if !self.fire_timers(true) {
+                        break;
+                    }
+                }
+            }
+        }
+        let tasks = self.inner.tasks.borrow();
+        tasks.iter().filter(|task| task.future.is_some()).count()
+    }
+
+    pub fn block_on<T: 'static>(&self, future: impl Future<Output = T> + 'static) -> Option<T> {
+        let handle = self.spawn(future);
+        self.run();
+        let result = handle.state.borrow_mut().result.take();
+        result
+    }
+}
+
+impl Default for Executor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct Sleep {
+    deadline: Instant,
+    key: Option<(Instant, u64)>,
+}
+
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep {
+        deadline: Instant::now() + duration,
+        key: None,
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    // The timer is registered on the first poll, and its waker is replaced on
+    // the later ones, as the task may be polled with another waker.
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<()> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+        let deadline = self.deadline;
+        let key = *self.key.get_or_insert_with(|| {
+            let sequence = TIMER_SEQUENCE.with(|sequence| sequence.replace(sequence.get() + 1));
+            (deadline, sequence)
+        });
+        TIMERS.with(|timers| timers.borrow_mut().insert(key, context.waker().clone()));
+        Poll::Pending
+    }
+}
+
+// A cancelled sleep takes its timer along.
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            TIMERS.with(|timers| timers.borrow_mut().remove(&key));
+        }
+    }
+}
+
+// Gives the other ready tasks a turn.
+pub async fn yield_now() {
+    let mut yielded = false;
+    std::future::poll_fn(|context| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            context.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+struct ChannelState<T> {
+    queue: VecDeque<T>,
+    senders: usize,
+    waker: Option<Waker>,
+}
+
+// An unbounded channel between the tasks of one thread.
+pub struct Sender<T> {
+    state: Rc<RefCell<ChannelState<T>>>,
+}
+
+pub struct Receiver<T> {
+    state: Rc<RefCell<ChannelState<T>>>,
+}
+
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let state = Rc::new(RefCell::new(ChannelState {
+        queue: VecDeque::new(),
+        senders: 1,
+        waker: None,
+    }));
+    let sender = Sender {
+        state: Rc::clone(&state),
+    };
+    (sender, Receiver { state })
+}
+
+impl<T> Sender<T> {
+    pub fn send(&self, value: T) {
+        let mut state = self.state.borrow_mut();
+        state.queue.push_back(value);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.state.borrow_mut().senders += 1;
+        Self {
+            state: Rc::clone(&self.state),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.state.borrow_mut();
+        state.senders -= 1;
+        if state.senders == 0 {
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+pub struct Recv<'a, T> {
+    receiver: &'a Receiver<T>,
+}
+
+impl<T> Future for Recv<'_, T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<T>> {
+        let mut state = self.receiver.state.borrow_mut();
+        match state.queue.pop_front() {
+            Some(value) => Poll::Ready(Some(value)),
+            None if state.senders == 0 => Poll::Ready(None),
+            None => {
+                state.waker = Some(context.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Receiver<T> {
+    // Completes with None, when the channel is empty, and the senders are gone.
+    pub fn recv(&self) -> Recv<'_, T> {
+        Recv { receiver: self }
+    }
+}
+
+// What the compiler makes of this async block, written by hand:
+//     async move { sleep(first).await; sleep(second).await; value }
+// Each await is a state, which holds the future it waits for, and the local
+// variables which live across it.
+pub enum TwoSleeps<T> {
+    First(Sleep, Duration, T),
+    Second(Sleep, T),
+    Done,
+}
+
+impl<T> TwoSleeps<T> {
+    pub fn new(first: Duration, second: Duration, value: T) -> Self {
+        TwoSleeps::First(sleep(first), second, value)
+    }
+}
+
+// Sleep is Unpin, and so is the state machine, which makes polling the inner
+// futures possible without pin projections.
+impl<T: Unpin> Future for TwoSleeps<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<T> {
+        loop {
+            match &mut *self {
+                TwoSleeps::First(sleep_first, ..) => {
+                    if Pin::new(sleep_first).poll(context).is_pending() {
+                        return Poll::Pending;
+                    }
+                    let TwoSleeps::First(_, second, value) =
+                        std::mem::replace(&mut *self, TwoSleeps::Done)
+                    else {
+                        unreachable!()
+                    };
+                    *self = TwoSleeps::Second(sleep(second), value);
+                }
+                TwoSleeps::Second(sleep_second, _) => {
+                    if Pin::new(sleep_second).poll(context).is_pending() {
+                        return Poll::Pending;
+                    }
+                    let TwoSleeps::Second(_, value) =
+                        std::mem::replace(&mut *self, TwoSleeps::Done)
+                    else {
+                        unreachable!()
+                    };
+                    return Poll::Ready(value);
+                }
+                TwoSleeps::Done => panic!("polled after completion"),
+            }
+        }
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    let executor = Executor::new();
+    test!(
+        harness,
+        "blocking on a ready future",
+        executor.block_on(async { 6 * 7 }),
+        Some(42)
+    );
+
+    // Three delayed tasks run concurrently, so they take the longest delay in
+    // total, and finish in the order of their delays.
+    let executor = Executor::new();
+    let finished = Rc::new(RefCell::new(Vec::new()));
+    let start = Instant::now();
+    for delay in [30, 10, 20] {
+        let finished = Rc::clone(&finished);
+        executor.spawn(async move {
+            sleep(Duration::from_millis(delay)).await;
+            finished.borrow_mut().push(delay);
+        });
+    }
+    let stuck = executor.run();
+    let elapsed = start.elapsed();
+    test!(
+        harness,
+        "delayed tasks finish in the order of their delays",
+        (finished.borrow().clone(), stuck),
+        (vec![10, 20, 30], 0)
+    );
+    test!(
+        harness,
+        "the delays overlap, instead of adding up",
+        elapsed >= Duration::from_millis(30) && elapsed < Duration::from_millis(55),
+        true
+    );
+    test!(
+        harness,
+        "each task is polled once to start, and once when its timer fires",
+        executor.polls(),
+        6
+    );
+
+    let executor = Executor::new();
+    let spawner = executor.spawner();
+    let total = executor.block_on(async move {
+        let handles: Vec<JoinHandle<u64>> = (1..=5)
+            .map(|n| {
+                spawner.spawn(async move {
+                    sleep(Duration::from_millis(n * 2)).await;
+                    n * n
+                })
+            })
+            .collect();
+        let mut total = 0;
+        for handle in handles {
+            total += handle.await;
+        }
+        total
+    });
+    test!(
+        harness,
+        "join handles deliver the results of the spawned tasks",
+        total,
+        Some(55)
+    );
+
+    let executor = Executor::new();
+    let (sender, receiver) = channel();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    for producer in 0..2u32 {
+        let sender = sender.clone();
+        executor.spawn(async move {
+            for index in 0..3 {
+                sleep(Duration::from_millis(3 + producer as u64)).await;
+                sender.send(producer * 10 + index);
+            }
+        });
+    }
+    drop(sender);
+    let consumer_log = Rc::clone(&log);
+    executor.spawn(async move {
+        while let Some(message) = receiver.recv().await {
+            consumer_log.borrow_mut().push(message);
+        }
+    });
+    executor.run();
+    let mut received = log.borrow().clone();
+    received.sort();
+    test!(
+        harness,
+        "the channel delivers every message, and closes with the senders",
+        received,
+        [0, 1, 2, 10, 11, 12]
+    );
+
+    let executor = Executor::new();
+    let order = Rc::new(RefCell::new(String::new()));
+    for name in ["a", "b"] {
+        let order = Rc::clone(&order);
+        executor.spawn(async move {
+            for _ in 0..3 {
+                order.borrow_mut().push_str(name);
+                yield_now().await;
+            }
+        });
+    }
+    executor.run();
+    test!(
+        harness,
+        "yielding interleaves the ready tasks",
+        order.borrow().as_str(),
+        "ababab"
+    );
+
+    let executor = Executor::new();
+    let start = Instant::now();
+    let by_hand = executor.block_on(TwoSleeps::new(
+        Duration::from_millis(5),
+        Duration::from_millis(5),
+        "by hand",
+    ));
+    let by_compiler = executor.block_on(async move {
+        sleep(Duration::from_millis(5)).await;
+        sleep(Duration::from_millis(5)).await;
+        "by the compiler"
+    });
+    test!(
+        harness,
+        "the hand-written state machine behaves like the async block",
+        (
+            by_hand,
+            by_compiler,
+            start.elapsed() >= Duration::from_millis(20)
+        ),
+        (Some("by hand"), Some("by the compiler"), true)
+    );
+
+    let executor = Executor::new();
+    let (_sender, receiver) = channel::<u32>();
+    executor.spawn(async move { receiver.recv().await });
+    test!(
+        harness,
+        "a task waiting for a message which never comes is polled once, and reported as stuck",
+        (executor.run(), executor.polls()),
+        (1, 1)
+    );
+}
@@ -0,0 +1,326 @@
+// fft.rs
+// A small complex number type, and the fast Fourier transform, which calculates
+// the discrete Fourier transform of n samples in O(n log n) time, instead of
+// the O(n^2) of the definition. This is the iterative, in-place, radix-2
+// variant of the Cooley-Tukey algorithm: the samples are first reordered by
+// bit-reversing their indices, and then neighboring transforms of twice the
+// size are combined from the smaller ones with butterflies. The inverse
+// transform is the same, on the conjugated input. As the transform turns
+// convolution into pointwise multiplication, it can multiply polynomials, and
+// large numbers quickly.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::f64::consts::PI;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub const ZERO: Complex = Complex { re: 0.0, im: 0.0 };
+
+    pub const fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    pub fn from_polar(magnitude: f64, angle: f64) -> Self {
+        Self::new(magnitude * angle.cos(), magnitude * angle.sin())
+    }
+
+    pub fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    pub fn abs(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    pub fn arg(self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    pub fn scale(self, factor: f64) -> Self {
+        Self::new(self.re * factor, self.im * factor)
+    }
+}
+
+impl fmt::Display for Complex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision().unwrap_or(3);
+        let sign = if self.im < 0.0 { '-' } else { '+' };
+        write!(
+            f,
+            "{:.*} {} {:.*}i",
+            precision,
+            self.re,
+            sign,
+            precision,
+            self.im.abs()
+        )
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl Neg for Complex {
+    type Output = Complex;
+
+    fn neg(self) -> Complex {
+        Complex::new(-self.re, -self.im)
+    }
+}
+
+pub fn fft(data: &mut [Complex]) {
+    let n = data.len();
+    assert!(
+        n.is_power_of_two() || n == 0,
+        "the length must be a power of two"
+    );
+    if n <= 1 {
+        return;
+    }
+
+    // After the reordering, the input of every transform to be combined is
+    // in a contiguous block.
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    // Each butterfly combines the even and odd halves of a transform of the
+    // given length, with the twiddle factor e^(-2 pi i k / length).
+    let mut length = 2;
+    while length <= n {
+        let step = Complex::from_polar(1.0, -2.0 * PI / length as f64);
+        for block in data.chunks_exact_mut(length) {
+            let (even, odd) = block.split_at_mut(length / 2);
+            let mut twiddle = Complex::new(1.0, 0.0);
+            for (e, o) in even.iter_mut().zip(odd.iter_mut()) {
+                let product = twiddle * *o;
+                *o = *e - product;
+                *e = *e + product;
+                twiddle = twiddle * step;
+            }
+        }
+        length *= 2;
+    }
+}
+
+// The inverse transform is the conjugate of the transform of the conjugate,
+// divided by the length.
+pub fn ifft(data: &mut [Complex]) {
+    for value in data.iter_mut() {
+        *value = value.conj();
+    }
+    fft(data);
+    let scale = 1.0 / data.len() as f64;
+    for value in data.iter_mut() {
+        *value = value.conj().scale(scale);
+    }
+}
+
+// The definition, for comparison: X(k) = sum of x(t) e^(-2 pi i k t / n).
+pub fn dft(data: &[Complex]) -> Vec<Complex> {
+    let n = data.len();
+    (0..n)
+        .map(|k| {
+            data.iter().enumerate().fold(Complex::ZERO, |sum, (t, x)| {
+                let angle = -2.0 * PI * (k * t) as f64 / n as f64;
+                sum + *x * Complex::from_polar(1.0, angle)
+            })
+        })
+        .collect()
+}
+
+// Both sequences are padded to a common power of two length, which fits the
+// whole result, so the cyclic convolution of the transform does not wrap.
+pub fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let length = a.len() + b.len() - 1;
+    let size = length.next_power_of_two();
+    let pad = |values: &[f64]| {
+        let mut result: Vec<Complex> = values.iter().map(|v| Complex::new(*v, 0.0)).collect();
+        result.resize(size, Complex::ZERO);
+        fft(&mut result);
+        result
+    };
+
+    let mut product: Vec<Complex> = pad(a).into_iter().zip(pad(b)).map(|(x, y)| x * y).collect();
+    ifft(&mut product);
+    product.truncate(length);
+    product.into_iter().map(|value| value.re).collect()
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+fn max_error(a: &[Complex], b: &[Complex]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (*x - *y).abs())
+        .fold(0.0, f64::max)
+}
+
+pub fn demo(harness: &mut Harness) {
+    let i = Complex::new(0.0, 1.0);
+    test!(harness, "i * i = -1", i * i, Complex::new(-1.0, 0.0));
+    test!(
+        harness,
+        "(1 + 2i)(3 - i) = 5 + 5i",
+        Complex::new(1.0, 2.0) * Complex::new(3.0, -1.0),
+        Complex::new(5.0, 5.0)
+    );
+    test!(
+        harness,
+        "the magnitude of 3 + 4i is 5",
+        Complex::new(3.0, 4.0).abs(),
+        5.0
+    );
+    test!(
+        harness,
+        "complex numbers are printed with their sign",
+        format!("{:.1}", Complex::new(1.5, -2.0)),
+        "1.5 - 2.0i"
+    );
+
+    let mut impulse = vec![Complex::ZERO; 8];
+    impulse[0] = Complex::new(1.0, 0.0);
+    fft(&mut impulse);
+    test!(
+        harness,
+        "the transform of an impulse is flat",
+        impulse
+            .iter()
+            .all(|value| (*value - Complex::new(1.0, 0.0)).abs() < 1e-12),
+        true
+    );
+
+    // A signal of 64 samples, with a sine of frequency 5, a cosine of
+    // frequency 12 at half amplitude, and a constant offset.
+    const N: usize = 64;
+    let signal: Vec<Complex> = (0..N)
+        .map(|t| {
+            let x = 2.0 * PI * t as f64 / N as f64;
+            Complex::new(0.25 + (5.0 * x).sin() + 0.5 * (12.0 * x).cos(), 0.0)
+        })
+        .collect();
+    let mut spectrum = signal.clone();
+    fft(&mut spectrum);
+    test!(
+        harness,
+        "the fast transform agrees with the definition",
+        max_error(&spectrum, &dft(&signal)) < 1e-9,
+        true
+    );
+
+    let magnitudes: Vec<f64> = spectrum.iter().map(|bin| bin.abs() / N as f64).collect();
+    let peaks: Vec<usize> = (0..N / 2).filter(|k| magnitudes[*k] > 0.01).collect();
+    test!(
+        harness,
+        "the spectrum peaks at the frequencies of the signal",
+        peaks,
+        vec![0, 5, 12]
+    );
+    note!(harness, "Bin  Magnitude  Phase");
+    for k in [0, 5, 12] {
+        note!(
+            harness,
+            "{:>3}  {:>9.4}  {:>5.2}",
+            k,
+            magnitudes[k],
+            spectrum[k].arg()
+        );
+    }
+    note!(harness);
+
+    let mut restored = spectrum.clone();
+    ifft(&mut restored);
+    test!(
+        harness,
+        "the inverse transform restores the signal",
+        max_error(&restored, &signal) < 1e-12,
+        true
+    );
+
+    // (1 + 2x + 3x^2)(4 + 5x) = 4 + 13x + 22x^2 + 15x^3
+    let product: Vec<i64> = convolve(&[1.0, 2.0, 3.0], &[4.0, 5.0])
+        .iter()
+        .map(|c| c.round() as i64)
+        .collect();
+    test!(
+        harness,
+        "convolution multiplies polynomials",
+        product,
+        vec![4, 13, 22, 15]
+    );
+
+    // Numbers are polynomials of their digits, evaluated at x = 10, so the
+    // carries are propagated after the convolution.
+    let digits =
+        |number: &str| -> Vec<f64> { number.bytes().rev().map(|d| (d - b'0') as f64).collect() };
+    let a = "31415926535897932384626433832795";
+    let b = "27182818284590452353602874713527";
+    let mut carry = 0;
+    let mut result = Vec::new();
+    for coefficient in convolve(&digits(a), &digits(b)) {
+        let value = coefficient.round() as u64 + carry;
+        result.push((b'0' + (value % 10) as u8) as char);
+        carry = value / 10;
+    }
+    while carry > 0 {
+        result.push((b'0' + (carry % 10) as u8) as char);
+        carry /= 10;
+    }
+    while result.len() > 1 && result.last() == Some(&'0') {
+        result.pop();
+    }
+    let product: String = result.iter().rev().collect();
+    let expected = &a.parse::<crate::bigint::BigUint>().unwrap()
+        * &b.parse::<crate::bigint::BigUint>().unwrap();
+    test!(
+        harness,
+        "convolution of digits multiplies large numbers",
+        product.clone(),
+        expected.to_string()
+    );
+    note!(harness, "{}\n * {}\n = {}\n", a, b, product);
+}
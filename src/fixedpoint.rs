@@ -0,0 +1,397 @@
+// fixedpoint.rs
+// A Q16.16 fixed-point number, which is a 32-bit integer counting 1/65536
+// parts, so it has 16 bits for the integer part, and 16 bits for the fraction.
+// Unlike floating-point, the precision is the same everywhere on its range of
+// about -32768 to 32768, and the arithmetic is plain integer arithmetic, which
+// makes it fast and deterministic on hardware without a floating-point unit.
+// Like the primitive integers, the operators panic on overflow, and saturating,
+// wrapping, and checked variants are provided, too.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed(i32);
+
+impl Fixed {
+    pub const FRACTION_BITS: u32 = 16;
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(1 << Self::FRACTION_BITS);
+    pub const EPSILON: Fixed = Fixed(1);
+    pub const MIN: Fixed = Fixed(i32::MIN);
+    pub const MAX: Fixed = Fixed(i32::MAX);
+    pub const PI: Fixed = Fixed(205887);
+
+    pub const fn from_bits(bits: i32) -> Self {
+        Fixed(bits)
+    }
+
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    pub const fn from_int(value: i16) -> Self {
+        Fixed((value as i32) << Self::FRACTION_BITS)
+    }
+
+    // Rounds to the nearest representable value, and saturates out of range.
+    pub fn from_f32(value: f32) -> Self {
+        Fixed((value as f64 * 65536.0).round() as i32)
+    }
+
+    pub fn from_f64(value: f64) -> Self {
+        Fixed((value * 65536.0).round() as i32)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / 65536.0
+    }
+
+    // Every Q16.16 value is exactly representable as a double.
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / 65536.0
+    }
+
+    // Rounds towards negative infinity.
+    pub fn floor(self) -> i32 {
+        self.0 >> Self::FRACTION_BITS
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Fixed)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Fixed)
+    }
+
+    // The product of two Q16.16 numbers is a Q32.32 number, which is rounded
+    // and shifted back.
+    fn wide_mul(self, other: Self) -> i64 {
+        (self.0 as i64 * other.0 as i64 + (1 << (Self::FRACTION_BITS - 1))) >> Self::FRACTION_BITS
+    }
+
+    // The dividend is shifted up first, so the quotient keeps its fraction.
+    fn wide_div(self, other: Self) -> Option<i64> {
+        if other.0 == 0 {
+            return None;
+        }
+        Some(((self.0 as i64) << Self::FRACTION_BITS) / other.0 as i64)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        i32::try_from(self.wide_mul(other)).ok().map(Fixed)
+    }
+
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        i32::try_from(self.wide_div(other)?).ok().map(Fixed)
+    }
+
+    pub fn saturating_add(self, other: Self) -> Self {
+        Fixed(self.0.saturating_add(other.0))
+    }
+
+    pub fn saturating_sub(self, other: Self) -> Self {
+        Fixed(self.0.saturating_sub(other.0))
+    }
+
+    pub fn saturating_mul(self, other: Self) -> Self {
+        Fixed(self.wide_mul(other).clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+
+    pub fn wrapping_add(self, other: Self) -> Self {
+        Fixed(self.0.wrapping_add(other.0))
+    }
+
+    pub fn wrapping_sub(self, other: Self) -> Self {
+        Fixed(self.0.wrapping_sub(other.0))
+    }
+
+    pub fn wrapping_mul(self, other: Self) -> Self {
+        Fixed(self.wide_mul(other) as i32)
+    }
+
+    // The square root of the value is the square root of the raw integer,
+    // shifted up by 16 bits, so that the result is in Q16.16, too. The integer
+    // square root is found with Newton's method: x' = (x + n / x) / 2, which
+    // decreases monotonically from an overestimate, until it stops changing.
+    pub fn sqrt(self) -> Option<Self> {
+        if self.0 < 0 {
+            return None;
+        }
+        let n = (self.0 as u64) << Self::FRACTION_BITS;
+        if n == 0 {
+            return Some(Self::ZERO);
+        }
+        let mut x = 1u64 << (n.ilog2() / 2 + 1);
+        loop {
+            let next = (x + n / x) / 2;
+            if next >= x {
+                break;
+            }
+            x = next;
+        }
+        Some(Fixed(x as i32))
+    }
+}
+
+impl From<i16> for Fixed {
+    fn from(value: i16) -> Self {
+        Self::from_int(value)
+    }
+}
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*}", precision, self.to_f64()),
+            None => write!(f, "{}", self.to_f64()),
+        }
+    }
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+
+    fn add(self, other: Fixed) -> Fixed {
+        self.checked_add(other)
+            .expect("attempt to add with overflow")
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+
+    fn sub(self, other: Fixed) -> Fixed {
+        self.checked_sub(other)
+            .expect("attempt to subtract with overflow")
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+
+    fn mul(self, other: Fixed) -> Fixed {
+        self.checked_mul(other)
+            .expect("attempt to multiply with overflow")
+    }
+}
+
+impl Div for Fixed {
+    type Output = Fixed;
+
+    fn div(self, other: Fixed) -> Fixed {
+        self.checked_div(other)
+            .expect("attempt to divide by zero, or with overflow")
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+
+    fn neg(self) -> Fixed {
+        Fixed(-self.0)
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    let half = Fixed::from_f32(0.5);
+    test!(harness, "one half is 0x8000", half.to_bits(), 0x8000);
+    test!(
+        harness,
+        "integers convert exactly",
+        (Fixed::from_int(-3).to_f32(), Fixed::from_int(-3).floor()),
+        (-3.0, -3)
+    );
+    test!(
+        harness,
+        "1.5 + 2.25 = 3.75",
+        (Fixed::from_f32(1.5) + Fixed::from_f32(2.25)).to_f32(),
+        3.75
+    );
+    test!(
+        harness,
+        "-1.5 * 2.5 = -3.75",
+        (Fixed::from_f32(-1.5) * Fixed::from_f32(2.5)).to_f32(),
+        -3.75
+    );
+    test!(
+        harness,
+        "7 / 2 = 3.5",
+        (Fixed::from_int(7) / Fixed::from_int(2)).to_f32(),
+        3.5
+    );
+    test!(
+        harness,
+        "the floor of -0.5 is -1",
+        Fixed::from_f32(-0.5).floor(),
+        -1
+    );
+    test!(
+        harness,
+        "the precision is 1/65536 everywhere",
+        (
+            Fixed::EPSILON.to_f64(),
+            (Fixed::from_int(30000) + Fixed::EPSILON).to_f64()
+        ),
+        (1.0 / 65536.0, 30000.0 + 1.0 / 65536.0)
+    );
+
+    test!(
+        harness,
+        "saturating addition stops at the maximum",
+        Fixed::MAX.saturating_add(Fixed::ONE),
+        Fixed::MAX
+    );
+    test!(
+        harness,
+        "saturating multiplication stops at the minimum",
+        Fixed::from_int(-200).saturating_mul(Fixed::from_int(200)),
+        Fixed::MIN
+    );
+    test!(
+        harness,
+        "wrapping addition wraps around to the minimum",
+        Fixed::MAX.wrapping_add(Fixed::EPSILON),
+        Fixed::MIN
+    );
+    test!(
+        harness,
+        "wrapping multiplication keeps the low bits",
+        Fixed::from_int(256).wrapping_mul(Fixed::from_int(256)),
+        Fixed::ZERO
+    );
+    test!(
+        harness,
+        "checked multiplication detects overflow",
+        Fixed::from_int(256).checked_mul(Fixed::from_int(128)),
+        None
+    );
+    test!(
+        harness,
+        "checked division detects division by zero",
+        Fixed::ONE.checked_div(Fixed::ZERO),
+        None
+    );
+
+    test!(
+        harness,
+        "the square root of 16 is 4",
+        Fixed::from_int(16).sqrt(),
+        Some(Fixed::from_int(4))
+    );
+    let root = Fixed::from_int(2).sqrt().unwrap();
+    test!(
+        harness,
+        "the square root of 2 is accurate to the last bit",
+        (root.to_f64() - 2f64.sqrt()).abs() < Fixed::EPSILON.to_f64(),
+        true
+    );
+    test!(
+        harness,
+        "the square root of the maximum",
+        Fixed::MAX.sqrt().map(|root| root.floor()),
+        Some(181)
+    );
+    test!(
+        harness,
+        "negative numbers have no square root",
+        Fixed::from_int(-1).sqrt(),
+        None
+    );
+
+    // Accumulating a small step shows the difference: Q16.16 has a constant
+    // absolute error, while f32 has a constant relative error.
+    let mut fixed_sum = Fixed::ZERO;
+    let mut float_sum = 0.0f32;
+    let step = 0.01;
+    for _ in 0..10000 {
+        fixed_sum = fixed_sum + Fixed::from_f32(step);
+        float_sum += step;
+    }
+
+    let third = Fixed::ONE / Fixed::from_int(3);
+    let area = Fixed::PI * Fixed::from_f32(2.5) * Fixed::from_f32(2.5);
+    let hypotenuse = (Fixed::from_int(3) * Fixed::from_int(3)
+        + Fixed::from_int(4) * Fixed::from_int(4))
+    .sqrt()
+    .unwrap();
+    let tiny = Fixed::from_f32(0.001) * Fixed::from_f32(0.001);
+    let rows: [(&str, f64, f64, f64); 5] = [
+        (
+            "sum of 10000 * 0.01",
+            100.0,
+            fixed_sum.to_f64(),
+            float_sum as f64,
+        ),
+        (
+            "1 / 3 * 3",
+            1.0,
+            (third * Fixed::from_int(3)).to_f64(),
+            (1.0f32 / 3.0 * 3.0) as f64,
+        ),
+        (
+            "pi * 2.5^2",
+            std::f64::consts::PI * 6.25,
+            area.to_f64(),
+            (std::f32::consts::PI * 2.5 * 2.5) as f64,
+        ),
+        (
+            "sqrt(3^2 + 4^2)",
+            5.0,
+            hypotenuse.to_f64(),
+            (9.0f32 + 16.0).sqrt() as f64,
+        ),
+        (
+            "0.001 * 0.001",
+            0.000001,
+            tiny.to_f64(),
+            (0.001f32 * 0.001) as f64,
+        ),
+    ];
+    note!(
+        harness,
+        "{:<20} {:>12} {:>12} {:>12} {:>10} {:>10}",
+        "Computation",
+        "Exact",
+        "Q16.16",
+        "f32",
+        "Q16.16 err",
+        "f32 err"
+    );
+    for (name, exact, fixed, float) in rows {
+        note!(
+            harness,
+            "{:<20} {:>12.6} {:>12.6} {:>12.6} {:>10.2e} {:>10.2e}",
+            name,
+            exact,
+            fixed,
+            float,
+            (fixed - exact).abs(),
+            (float - exact).abs()
+        );
+    }
+    note!(harness);
+
+    test!(
+        harness,
+        "3-4-5 is an exact right triangle",
+        hypotenuse,
+        Fixed::from_int(5)
+    );
+    test!(
+        harness,
+        "values below the precision are lost",
+        tiny,
+        Fixed::ZERO
+    );
+}
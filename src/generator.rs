@@ -0,0 +1,385 @@
+// generator.rs
+// Resumable computations, written by hand as explicit state machines. A
+// generator is a function which can stop in the middle, yield a value, and
+// later go on from where it stopped. Rust has them as an unstable feature,
+// called coroutines, where the compiler turns a closure with yield statements
+// into a state machine, much like it does with async functions. On stable Rust,
+// the same machine is written by hand: the local variables which live across a
+// yield become fields, the position of the yield becomes the state, and
+// resuming is a method, which is how the iterators below work. The prime
+// generator is an incremental sieve, which maps each upcoming composite to the
+// primes which divide it, so it never needs a limit. The in-order traversal of
+// a tree keeps its own stack of the nodes still to visit, instead of the call
+// stack of the recursive version. The generator trait mirrors the unstable one,
+// with a final return value besides the yielded ones.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::hashmap::HashMap;
+
+// With the unstable feature, the prime generator would read:
+//     #[coroutine] || {
+//         yield 2;
+//         let mut composites = HashMap::new();
+//         for candidate in (3..).step_by(2) {
+//             match composites.remove(&candidate) {
+//                 None => { composites.insert(candidate * candidate, vec![candidate]); yield candidate; }
+//                 Some(primes) => { /* move each prime to its next odd multiple */ }
+//             }
+//         }
+//     }
+// The state is where it stopped: before the first yield, or inside the loop.
+pub struct Primes {
+    started: bool,
+    candidate: u64,
+    // The next odd multiple of each prime found so far, above the candidate.
+    composites: HashMap<u64, Vec<u64>>,
+}
+
+impl Primes {
+    pub fn new() -> Self {
+        Self {
+            started: false,
+            candidate: 1,
+            composites: HashMap::new(),
+        }
+    }
+}
+
+impl Default for Primes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for Primes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if !self.started {
+            self.started = true;
+            return Some(2);
+        }
+        loop {
+            self.candidate += 2;
+            let candidate = self.candidate;
+            match self.composites.remove(&candidate) {
+                None => {
+                    self.composites
+                        .insert(candidate * candidate, vec![candidate]);
+                    return Some(candidate);
+                }
+                Some(primes) => {
+                    // Each prime moves on to its next odd multiple, which may
+                    // be shared with other primes.
+                    for prime in primes {
+                        let next = candidate + 2 * prime;
+                        match self.composites.get_mut(&next) {
+                            Some(divisors) => divisors.push(prime),
+                            None => {
+                                self.composites.insert(next, vec![prime]);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub enum Tree<T> {
+    Leaf,
+    Node(Box<Tree<T>>, T, Box<Tree<T>>),
+}
+
+impl<T: Ord> Tree<T> {
+    pub fn new() -> Self {
+        Tree::Leaf
+    }
+
+    // An unbalanced binary search tree, to have something to traverse.
+    pub fn insert(&mut self, value: T) {
+        match self {
+            Tree::Leaf => *self = Tree::Node(Box::new(Tree::Leaf), value, Box::new(Tree::Leaf)),
+            Tree::Node(left, key, right) => {
+                if value < *key {
+                    left.insert(value)
+                } else {
+                    right.insert(value)
+                }
+            }
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        match self {
+            Tree::Leaf => 0,
+            Tree::Node(left, _, right) => 1 + left.height().max(right.height()),
+        }
+    }
+
+    pub fn in_order(&self) -> InOrder<'_, T> {
+        InOrder {
+            stack: Vec::new(),
+            current: self,
+            deepest: 0,
+        }
+    }
+}
+
+impl<T: Ord> Default for Tree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for Tree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let mut tree = Tree::new();
+        values.into_iter().for_each(|value| tree.insert(value));
+        tree
+    }
+}
+
+// The recursive traversal, as a generator, would be:
+//     fn visit(tree) { if let Node(left, value, right) = tree {
+//         visit(left); yield value; visit(right); } }
+// Each pending call, which has visited its left subtree, but not yet yielded
+// its value, is a node on the stack. The current subtree is where the next
+// call would start.
+pub struct InOrder<'a, T> {
+    stack: Vec<&'a Tree<T>>,
+    current: &'a Tree<T>,
+    deepest: usize,
+}
+
+impl<T> InOrder<'_, T> {
+    // The largest the stack has grown, which is at most the height of the tree.
+    pub fn deepest(&self) -> usize {
+        self.deepest
+    }
+}
+
+impl<'a, T> Iterator for InOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        while let Tree::Node(left, _, _) = self.current {
+            self.stack.push(self.current);
+            self.deepest = self.deepest.max(self.stack.len());
+            self.current = left;
+        }
+        let Tree::Node(_, value, right) = self.stack.pop()? else {
+            unreachable!("only nodes are pushed");
+        };
+        self.current = right;
+        Some(value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GeneratorState<Y, R> {
+    Yielded(Y),
+    Complete(R),
+}
+
+// The unstable trait, with the resume argument left out. Resuming a generator
+// after it completed panics.
+pub trait Generator {
+    type Yield;
+    type Return;
+
+    fn resume(&mut self) -> GeneratorState<Self::Yield, Self::Return>;
+}
+
+// An iterator of the yielded values, which keeps the return value for later.
+pub struct Yields<G: Generator> {
+    generator: G,
+    returned: Option<G::Return>,
+}
+
+impl<G: Generator> Yields<G> {
+    pub fn new(generator: G) -> Self {
+        Self {
+            generator,
+            returned: None,
+        }
+    }
+
+    pub fn returned(&self) -> Option<&G::Return> {
+        self.returned.as_ref()
+    }
+}
+
+impl<G: Generator> Iterator for Yields<G> {
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        if self.returned.is_some() {
+            return None;
+        }
+        match self.generator.resume() {
+            GeneratorState::Yielded(value) => Some(value),
+            GeneratorState::Complete(value) => {
+                self.returned = Some(value);
+                None
+            }
+        }
+    }
+}
+
+// The Collatz sequence from a number, which yields the numbers along the way,
+// and returns the number of steps it took to reach one:
+//     #[coroutine] move || {
+//         let mut steps = 0;
+//         while n != 1 { yield n; n = if n % 2 == 0 { n / 2 } else { 3 * n + 1 }; steps += 1; }
+//         yield 1;
+//         steps
+//     }
+pub enum Collatz {
+    Running { n: u64, steps: u32 },
+    Last { steps: u32 },
+    Done,
+}
+
+impl Collatz {
+    pub fn new(n: u64) -> Self {
+        assert!(n > 0, "the Collatz sequence starts from a positive number");
+        Collatz::Running { n, steps: 0 }
+    }
+}
+
+impl Generator for Collatz {
+    type Yield = u64;
+    type Return = u32;
+
+    fn resume(&mut self) -> GeneratorState<u64, u32> {
+        match *self {
+            Collatz::Running { n: 1, steps } => {
+                *self = Collatz::Last { steps };
+                GeneratorState::Yielded(1)
+            }
+            Collatz::Running { n, steps } => {
+                let next = if n.is_multiple_of(2) {
+                    n / 2
+                } else {
+                    3 * n + 1
+                };
+                *self = Collatz::Running {
+                    n: next,
+                    steps: steps + 1,
+                };
+                GeneratorState::Yielded(n)
+            }
+            Collatz::Last { steps } => {
+                *self = Collatz::Done;
+                GeneratorState::Complete(steps)
+            }
+            Collatz::Done => panic!("generator resumed after completion"),
+        }
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use crate::primes::sieve;
+use crate::rng::{RandomSource, SplitMix64};
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "the first primes from the infinite generator",
+        Primes::new().take(10).collect::<Vec<_>>(),
+        [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]
+    );
+    test!(
+        harness,
+        "the thousandth prime",
+        Primes::new().nth(999),
+        Some(7919)
+    );
+    test!(
+        harness,
+        "the generator agrees with the sieve",
+        Primes::new()
+            .take_while(|prime| *prime < 100_000)
+            .collect::<Vec<_>>(),
+        sieve(100_000)
+    );
+
+    let mut random = SplitMix64::new(80);
+    let values: Vec<u32> = (0..1000).map(|_| random.next_u32() % 10_000).collect();
+    let tree: Tree<u32> = values.iter().copied().collect();
+    let mut sorted = values.clone();
+    sorted.sort();
+    let mut traversal = tree.in_order();
+    let visited: Vec<u32> = traversal.by_ref().copied().collect();
+    test!(
+        harness,
+        "the in-order traversal visits the values in order",
+        visited,
+        sorted
+    );
+    test!(
+        harness,
+        "its stack never grows deeper than the tree",
+        traversal.deepest() <= tree.height() && traversal.deepest() > 0,
+        true
+    );
+    let list: Tree<u32> = (0..100).collect();
+    let mut traversal = list.in_order();
+    test!(
+        harness,
+        "the traversal is lazy, and can stop early",
+        (
+            traversal.by_ref().take(3).copied().collect::<Vec<_>>(),
+            traversal.deepest()
+        ),
+        (vec![0, 1, 2], 1)
+    );
+    test!(
+        harness,
+        "the empty tree has nothing to visit",
+        Tree::<u32>::new().in_order().next(),
+        None
+    );
+
+    let mut collatz = Collatz::new(6);
+    let states: Vec<GeneratorState<u64, u32>> = (0..10).map(|_| collatz.resume()).collect();
+    test!(
+        harness,
+        "the Collatz generator yields the sequence, then returns its steps",
+        states,
+        [6, 3, 10, 5, 16, 8, 4, 2, 1]
+            .map(GeneratorState::Yielded)
+            .into_iter()
+            .chain([GeneratorState::Complete(8)])
+            .collect::<Vec<_>>()
+    );
+    let mut yields = Yields::new(Collatz::new(27));
+    let highest = yields.by_ref().max();
+    test!(
+        harness,
+        "the yields of the generator, and its return value",
+        (highest, yields.returned()),
+        (Some(9232), Some(&111))
+    );
+
+    note!(
+        harness,
+        "The number below 10000 with the longest Collatz sequence: {}",
+        (1..10_000u64)
+            .map(|n| {
+                let mut yields = Yields::new(Collatz::new(n));
+                yields.by_ref().count();
+                (*yields.returned().unwrap(), n)
+            })
+            .max()
+            .map(|(steps, n)| format!("{} in {} steps", n, steps))
+            .unwrap()
+    );
+}
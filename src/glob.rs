@@ -0,0 +1,375 @@
+// glob.rs
+// Matching paths against shell-style glob patterns. A question mark matches a
+// single character, a star matches any run of characters, and brackets match a
+// character of a class, like [a-z], or one not in it, like [!0-9]. None of them
+// match the slash, which separates the segments of the path, but a double star
+// as a whole segment matches any number of segments, so src/**/*.rs finds the
+// Rust files at any depth under src. Backslashes escape special characters.
+// The matcher is iterative: when a star fails to match, only the position of
+// the last star is revisited, both within the segments and between them, so
+// the matching never takes exponential time, like naive recursion can.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnterminatedClass,
+    InvalidRange(char, char),
+    TrailingBackslash,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnterminatedClass => write!(f, "the character class is not closed"),
+            ErrorKind::InvalidRange(from, to) => write!(f, "invalid range {}-{}", from, to),
+            ErrorKind::TrailingBackslash => write!(f, "trailing backslash"),
+        }
+    }
+}
+
+// The position is counted in characters of the pattern.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at position {}", self.kind, self.position)
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Literal(char),
+    Any,
+    Star,
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+}
+
+impl Token {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Token::Literal(expected) => *expected == c,
+            Token::Any => true,
+            Token::Star => false,
+            Token::Class { ranges, negated } => {
+                ranges.iter().any(|(from, to)| (*from..=*to).contains(&c)) != *negated
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    AnyDepth,
+    Tokens(Vec<Token>),
+}
+
+#[derive(Clone, Debug)]
+pub struct Glob {
+    pattern: String,
+    segments: Vec<Segment>,
+}
+
+// The classic wildcard matcher: when an element does not match, the last
+// star takes one more element, and the matching continues after it. Only
+// the last star needs to be revisited, as the earlier ones could only take
+// elements, which the last star can take just as well.
+fn match_sequence<P, T>(
+    pattern: &[P],
+    text: &[T],
+    is_star: impl Fn(&P) -> bool,
+    matches: impl Fn(&P, &T) -> bool,
+) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && is_star(&pattern[p]) {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && matches(&pattern[p], &text[t]) {
+            p += 1;
+            t += 1;
+        } else if let Some((star, taken)) = backtrack {
+            backtrack = Some((star, taken + 1));
+            p = star + 1;
+            t = taken + 1;
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(is_star)
+}
+
+impl Glob {
+    pub fn new(pattern: &str) -> Result<Self, PatternError> {
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut segments = Vec::new();
+        let mut tokens = Vec::new();
+        let mut double_star = false;
+        let mut position = 0;
+        let error = |kind, position| PatternError { kind, position };
+        // A double star, which is a whole segment, matches any depth.
+        let finish = |tokens: Vec<Token>, double_star: bool| {
+            if double_star && tokens == [Token::Star] {
+                Segment::AnyDepth
+            } else {
+                Segment::Tokens(tokens)
+            }
+        };
+
+        while position < chars.len() {
+            let c = chars[position];
+            position += 1;
+            match c {
+                '/' => {
+                    segments.push(finish(std::mem::take(&mut tokens), double_star));
+                    double_star = false;
+                }
+                '?' => tokens.push(Token::Any),
+                // Consecutive stars are the same as one.
+                '*' if tokens.last() == Some(&Token::Star) => double_star = true,
+                '*' => tokens.push(Token::Star),
+                '\\' => {
+                    let escaped = *chars
+                        .get(position)
+                        .ok_or(error(ErrorKind::TrailingBackslash, position))?;
+                    tokens.push(Token::Literal(escaped));
+                    position += 1;
+                }
+                '[' => {
+                    let open = position - 1;
+                    let negated = matches!(chars.get(position), Some('!' | '^'));
+                    if negated {
+                        position += 1;
+                    }
+                    let mut ranges = Vec::new();
+                    let mut first = true;
+                    loop {
+                        let c = *chars
+                            .get(position)
+                            .ok_or(error(ErrorKind::UnterminatedClass, open))?;
+                        position += 1;
+                        if c == ']' && !first {
+                            break;
+                        }
+                        first = false;
+                        let is_range = chars.get(position) == Some(&'-')
+                            && chars.get(position + 1).is_some_and(|c| *c != ']');
+                        if !is_range {
+                            ranges.push((c, c));
+                            continue;
+                        }
+                        let to = chars[position + 1];
+                        position += 2;
+                        if c > to {
+                            return Err(error(ErrorKind::InvalidRange(c, to), position - 3));
+                        }
+                        ranges.push((c, to));
+                    }
+                    tokens.push(Token::Class { ranges, negated });
+                }
+                c => tokens.push(Token::Literal(c)),
+            }
+        }
+        segments.push(finish(tokens, double_star));
+        Ok(Self {
+            pattern: pattern.to_string(),
+            segments,
+        })
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        let segments: Vec<Vec<char>> = path
+            .split('/')
+            .map(|segment| segment.chars().collect())
+            .collect();
+        match_sequence(
+            &self.segments,
+            &segments,
+            |segment| *segment == Segment::AnyDepth,
+            |segment, text| match segment {
+                Segment::AnyDepth => false,
+                Segment::Tokens(tokens) => match_sequence(
+                    tokens,
+                    text,
+                    |token| *token == Token::Star,
+                    |token, c| token.matches(*c),
+                ),
+            },
+        )
+    }
+
+    pub fn filter<'a>(&self, paths: &[&'a str]) -> Vec<&'a str> {
+        paths
+            .iter()
+            .copied()
+            .filter(|path| self.matches(path))
+            .collect()
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use std::time::Instant;
+
+#[rustfmt::skip]
+const PATHS: [&str; 14] = [
+    "Cargo.toml", "README", "src/lib.rs", "src/glob.rs", "src/bin/glob.rs",
+    "src/bin/regex.rs", "docs/guide.md", "docs/api/index.md", "target/debug/glob",
+    "notes.txt", "notes1.txt", "notes2.txt", "notesA.txt", ".gitignore",
+];
+
+pub fn demo(harness: &mut Harness) {
+    let filter = |pattern: &str| Glob::new(pattern).unwrap().filter(&PATHS);
+    test!(
+        harness,
+        "a star does not cross a slash",
+        filter("src/*.rs"),
+        vec!["src/lib.rs", "src/glob.rs"]
+    );
+    test!(
+        harness,
+        "a double star matches any depth, including none",
+        filter("src/**/*.rs"),
+        vec![
+            "src/lib.rs",
+            "src/glob.rs",
+            "src/bin/glob.rs",
+            "src/bin/regex.rs"
+        ]
+    );
+    test!(
+        harness,
+        "a leading double star",
+        filter("**/glob*"),
+        vec!["src/glob.rs", "src/bin/glob.rs", "target/debug/glob"]
+    );
+    test!(
+        harness,
+        "a question mark matches a single character",
+        filter("notes?.txt"),
+        vec!["notes1.txt", "notes2.txt", "notesA.txt"]
+    );
+    test!(
+        harness,
+        "a character class with a range",
+        filter("notes[0-9].txt"),
+        vec!["notes1.txt", "notes2.txt"]
+    );
+    test!(
+        harness,
+        "a negated character class",
+        filter("notes[!0-9].txt"),
+        vec!["notesA.txt"]
+    );
+    test!(
+        harness,
+        "a double star inside a segment is a star",
+        filter("docs**.md"),
+        Vec::<&str>::new()
+    );
+    test!(
+        harness,
+        "escaped special characters are literals",
+        Glob::new("what\\?\\*").unwrap().matches("what?*"),
+        true
+    );
+    test!(
+        harness,
+        "a closing bracket first in a class is a literal",
+        Glob::new("[]x]").unwrap().matches("]"),
+        true
+    );
+    test!(
+        harness,
+        "stars match empty runs",
+        Glob::new("*a*b*").unwrap().matches("ab"),
+        true
+    );
+    test!(
+        harness,
+        "the pattern has to match the whole path",
+        Glob::new("src").unwrap().matches("src/lib.rs"),
+        false
+    );
+
+    test!(
+        harness,
+        "an unterminated class",
+        Glob::new("file[abc").err().map(|error| error.to_string()),
+        Some("the character class is not closed at position 4".to_string())
+    );
+    test!(
+        harness,
+        "an invalid range",
+        Glob::new("[z-a]").err().map(|error| error.to_string()),
+        Some("invalid range z-a at position 1".to_string())
+    );
+
+    // This pattern makes a naive recursive matcher try every split of the
+    // text between the stars, which is exponential in the number of stars.
+    let pattern = format!("{}b", "a*".repeat(20));
+    let text = "a".repeat(200);
+    let started = Instant::now();
+    let matched = Glob::new(&pattern).unwrap().matches(&text);
+    let elapsed = started.elapsed();
+    test!(
+        harness,
+        "a pathological pattern is rejected quickly",
+        (matched, elapsed.as_millis() < 100),
+        (false, true)
+    );
+
+    let patterns = [
+        "*",
+        "*.*",
+        "src/**",
+        "**/*.md",
+        "docs/*/*.md",
+        "[.]*",
+        "*.t?t",
+    ];
+    let width = PATHS.iter().map(|path| path.len()).max().unwrap();
+    let mut header = format!("{:<width$}", "", width = width);
+    for pattern in patterns {
+        header.push_str(&format!(" {:>11}", pattern));
+    }
+    note!(harness, "{}", header);
+    let globs: Vec<Glob> = patterns.iter().map(|p| Glob::new(p).unwrap()).collect();
+    for path in PATHS {
+        let mut row = format!("{:<width$}", path, width = width);
+        for glob in &globs {
+            row.push_str(&format!(
+                " {:>11}",
+                if glob.matches(path) { "x" } else { "." }
+            ));
+        }
+        note!(harness, "{}", row);
+    }
+    note!(
+        harness,
+        "\n{:?} on {} characters took {:?}\n",
+        pattern,
+        text.len(),
+        elapsed
+    );
+}
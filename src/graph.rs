@@ -0,0 +1,389 @@
+// graph.rs
+// Classic algorithms on a directed graph stored as adjacency lists, with named
+// vertices, and weighted edges. Breadth-first search visits the vertices in the
+// order of their distance from the start, counted in edges, while depth-first
+// search follows each path as far as it goes, before backtracking. Dijkstra's
+// algorithm finds the shortest paths from a vertex, when the weights are not
+// negative: it takes the closest unfinished vertex from a heap, and relaxes its
+// edges, decreasing the keys of the neighbours it finds a shorter path to.
+// Kahn's algorithm sorts the vertices topologically, repeatedly removing the
+// ones with no incoming edges left, and finds out that the graph has a cycle if
+// it runs out of such vertices too early. An undirected graph is made of pairs
+// of edges in both directions.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::heap::{DaryHeap, Handle};
+use std::collections::VecDeque;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Edge {
+    pub to: usize,
+    pub weight: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Graph {
+    names: Vec<String>,
+    edges: Vec<Vec<Edge>>,
+}
+
+// The vertices which could not be sorted, as they are on a cycle, or can only
+// be reached through one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CycleError {
+    pub vertices: Vec<usize>,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the graph has a cycle, {} vertices can not be sorted",
+            self.vertices.len()
+        )
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShortestPaths {
+    pub source: usize,
+    pub distances: Vec<Option<u64>>,
+    pub previous: Vec<Option<usize>>,
+}
+
+impl ShortestPaths {
+    pub fn path_to(&self, target: usize) -> Option<Vec<usize>> {
+        self.distances[target]?;
+        let mut path = vec![target];
+        while let Some(previous) = self.previous[*path.last().unwrap()] {
+            path.push(previous);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_vertex(&mut self, name: &str) -> usize {
+        self.names.push(name.to_string());
+        self.edges.push(Vec::new());
+        self.names.len() - 1
+    }
+
+    pub fn vertex(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|candidate| candidate == name)
+    }
+
+    pub fn name(&self, vertex: usize) -> &str {
+        &self.names[vertex]
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn edges(&self, vertex: usize) -> &[Edge] {
+        &self.edges[vertex]
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: u64) {
+        self.edges[from].push(Edge { to, weight });
+    }
+
+    pub fn add_undirected_edge(&mut self, a: usize, b: usize, weight: u64) {
+        self.add_edge(a, b, weight);
+        self.add_edge(b, a, weight);
+    }
+
+    // The reachable vertices, with their distances in edges, in the order of
+    // their discovery.
+    pub fn bfs(&self, start: usize) -> Vec<(usize, usize)> {
+        let mut visited = vec![false; self.vertex_count()];
+        let mut order = Vec::new();
+        let mut queue = VecDeque::from([(start, 0)]);
+        visited[start] = true;
+        while let Some((vertex, distance)) = queue.pop_front() {
+            order.push((vertex, distance));
+            for edge in &self.edges[vertex] {
+                if !visited[edge.to] {
+                    visited[edge.to] = true;
+                    queue.push_back((edge.to, distance + 1));
+                }
+            }
+        }
+        order
+    }
+
+    // The reachable vertices in preorder, visiting the neighbours in the order
+    // of the edges, like the recursive version would.
+    pub fn dfs(&self, start: usize) -> Vec<usize> {
+        let mut visited = vec![false; self.vertex_count()];
+        let mut order = Vec::new();
+        let mut stack = vec![start];
+        while let Some(vertex) = stack.pop() {
+            if visited[vertex] {
+                continue;
+            }
+            visited[vertex] = true;
+            order.push(vertex);
+            for edge in self.edges[vertex].iter().rev() {
+                if !visited[edge.to] {
+                    stack.push(edge.to);
+                }
+            }
+        }
+        order
+    }
+
+    pub fn dijkstra(&self, source: usize) -> ShortestPaths {
+        let count = self.vertex_count();
+        let mut distances = vec![None; count];
+        let mut previous = vec![None; count];
+        let mut handles: Vec<Option<Handle>> = vec![None; count];
+        let mut heap = DaryHeap::new(4);
+        distances[source] = Some(0);
+        handles[source] = Some(heap.push((0, source)));
+        while let Some((distance, vertex)) = heap.pop() {
+            for edge in &self.edges[vertex] {
+                let candidate = distance + edge.weight;
+                if distances[edge.to].is_some_and(|known| known <= candidate) {
+                    continue;
+                }
+                distances[edge.to] = Some(candidate);
+                previous[edge.to] = Some(vertex);
+                match handles[edge.to] {
+                    Some(handle) if heap.contains(handle) => {
+                        heap.decrease_key(handle, (candidate, edge.to)).unwrap();
+                    }
+                    _ => handles[edge.to] = Some(heap.push((candidate, edge.to))),
+                }
+            }
+        }
+        ShortestPaths {
+            source,
+            distances,
+            previous,
+        }
+    }
+
+    // Among the vertices without incoming edges, the one added first comes
+    // first, so the order is deterministic.
+    pub fn topological_sort(&self) -> Result<Vec<usize>, CycleError> {
+        let mut incoming = vec![0; self.vertex_count()];
+        for edges in &self.edges {
+            for edge in edges {
+                incoming[edge.to] += 1;
+            }
+        }
+        let mut ready: VecDeque<usize> = (0..self.vertex_count())
+            .filter(|vertex| incoming[*vertex] == 0)
+            .collect();
+        let mut order = Vec::new();
+        while let Some(vertex) = ready.pop_front() {
+            order.push(vertex);
+            for edge in &self.edges[vertex] {
+                incoming[edge.to] -= 1;
+                if incoming[edge.to] == 0 {
+                    ready.push_back(edge.to);
+                }
+            }
+        }
+        if order.len() < self.vertex_count() {
+            let vertices = (0..self.vertex_count())
+                .filter(|vertex| incoming[*vertex] > 0)
+                .collect();
+            return Err(CycleError { vertices });
+        }
+        Ok(order)
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+#[rustfmt::skip]
+const ROADS: [(&str, &str, u64); 15] = [
+    ("Budapest", "Győr", 121), ("Budapest", "Székesfehérvár", 66),
+    ("Budapest", "Kecskemét", 86), ("Budapest", "Miskolc", 182),
+    ("Budapest", "Eger", 139), ("Budapest", "Debrecen", 231),
+    ("Győr", "Székesfehérvár", 87), ("Székesfehérvár", "Pécs", 151),
+    ("Kecskemét", "Szeged", 88), ("Kecskemét", "Debrecen", 197),
+    ("Szeged", "Pécs", 189), ("Szeged", "Debrecen", 224),
+    ("Miskolc", "Eger", 66), ("Miskolc", "Nyíregyháza", 89),
+    ("Nyíregyháza", "Debrecen", 50),
+];
+
+fn road_network() -> Graph {
+    let mut graph = Graph::new();
+    for (a, b, distance) in ROADS {
+        let a = graph.vertex(a).unwrap_or_else(|| graph.add_vertex(a));
+        let b = graph.vertex(b).unwrap_or_else(|| graph.add_vertex(b));
+        graph.add_undirected_edge(a, b, distance);
+    }
+    graph
+}
+
+pub fn demo(harness: &mut Harness) {
+    let roads = road_network();
+    let city = |name| roads.vertex(name).unwrap();
+    let names = |vertices: &[usize]| -> Vec<&str> {
+        vertices.iter().map(|vertex| roads.name(*vertex)).collect()
+    };
+
+    let bfs = roads.bfs(city("Pécs"));
+    let hops: Vec<(&str, usize)> = bfs
+        .iter()
+        .map(|(vertex, hops)| (roads.name(*vertex), *hops))
+        .collect();
+    test!(
+        harness,
+        "breadth-first search from Pécs",
+        hops,
+        vec![
+            ("Pécs", 0),
+            ("Székesfehérvár", 1),
+            ("Szeged", 1),
+            ("Budapest", 2),
+            ("Győr", 2),
+            ("Kecskemét", 2),
+            ("Debrecen", 2),
+            ("Miskolc", 3),
+            ("Eger", 3),
+            ("Nyíregyháza", 3)
+        ]
+    );
+    test!(
+        harness,
+        "depth-first search from Pécs",
+        names(&roads.dfs(city("Pécs"))),
+        vec![
+            "Pécs",
+            "Székesfehérvár",
+            "Budapest",
+            "Győr",
+            "Kecskemét",
+            "Szeged",
+            "Debrecen",
+            "Nyíregyháza",
+            "Miskolc",
+            "Eger"
+        ]
+    );
+
+    let from_gyor = roads.dijkstra(city("Győr"));
+    test!(
+        harness,
+        "the shortest path from Győr to Nyíregyháza",
+        (
+            from_gyor.distances[city("Nyíregyháza")],
+            from_gyor
+                .path_to(city("Nyíregyháza"))
+                .map(|path| names(&path))
+        ),
+        (
+            Some(392),
+            Some(vec!["Győr", "Budapest", "Miskolc", "Nyíregyháza"])
+        )
+    );
+    test!(
+        harness,
+        "the shortest path from Győr to Szeged",
+        from_gyor.path_to(city("Szeged")).map(|path| names(&path)),
+        Some(vec!["Győr", "Budapest", "Kecskemét", "Szeged"])
+    );
+    test!(
+        harness,
+        "the shortest path from Szeged to Eger",
+        roads.dijkstra(city("Szeged")).distances[city("Eger")],
+        Some(313)
+    );
+    let mut island = road_network();
+    let tihany = island.add_vertex("Tihany");
+    test!(
+        harness,
+        "an unreachable vertex has no path",
+        island.dijkstra(0).path_to(tihany),
+        None
+    );
+
+    let mut build = Graph::new();
+    #[rustfmt::skip]
+    let steps = ["install", "test", "compile", "configure", "download", "package", "document"];
+    for step in steps {
+        build.add_vertex(step);
+    }
+    let step = |name| steps.iter().position(|step| *step == name).unwrap();
+    for (before, after) in [
+        ("download", "configure"),
+        ("configure", "compile"),
+        ("compile", "test"),
+        ("compile", "document"),
+        ("test", "package"),
+        ("document", "package"),
+        ("package", "install"),
+    ] {
+        build.add_edge(step(before), step(after), 1);
+    }
+    let order: Vec<&str> = build
+        .topological_sort()
+        .unwrap()
+        .iter()
+        .map(|vertex| steps[*vertex])
+        .collect();
+    test!(
+        harness,
+        "the build steps in topological order",
+        order.clone(),
+        vec![
+            "download",
+            "configure",
+            "compile",
+            "test",
+            "document",
+            "package",
+            "install"
+        ]
+    );
+    build.add_edge(step("install"), step("configure"), 1);
+    test!(
+        harness,
+        "a cycle prevents the topological sort",
+        build
+            .topological_sort()
+            .map_err(|error| error.vertices.len()),
+        Err(6)
+    );
+
+    let width = ROADS
+        .iter()
+        .map(|road| road.0.chars().count())
+        .max()
+        .unwrap()
+        + 2;
+    note!(harness, "Shortest paths from Győr:");
+    for (vertex, _) in roads.bfs(city("Győr")) {
+        let path = names(&from_gyor.path_to(vertex).unwrap()).join(" - ");
+        let name = roads.name(vertex);
+        note!(
+            harness,
+            "  {}{} {:>4} km  {}",
+            name,
+            " ".repeat(width - name.chars().count()),
+            from_gyor.distances[vertex].unwrap(),
+            path
+        );
+    }
+    note!(harness, "\nBuild order: {}\n", order.join(", "));
+}
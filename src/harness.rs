@@ -0,0 +1,162 @@
+// harness.rs
+// A minimal test harness for the demonstrations of the Rust snippets. Checks
+// print the expected and actual values along with a verdict, and the harness
+// counts them, so that a demonstration can end with a summary, and an exit code
+// which is non-zero if any of the checks failed.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::args::Parser;
+use std::fmt::{Arguments, Debug};
+
+pub struct Harness {
+    quiet: bool,
+    passed: usize,
+    failed: usize,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        Self {
+            quiet: false,
+            passed: 0,
+            failed: 0,
+        }
+    }
+
+    // A quiet harness only reports failed checks, and suppresses the notes of
+    // the demonstration.
+    pub fn quiet() -> Self {
+        Self {
+            quiet: true,
+            ..Self::new()
+        }
+    }
+
+    pub fn is_quiet(&self) -> bool {
+        self.quiet
+    }
+
+    pub fn passed(&self) -> usize {
+        self.passed
+    }
+
+    pub fn failed(&self) -> usize {
+        self.failed
+    }
+
+    pub fn check<T, U>(&mut self, description: &str, got: T, expected: U) -> bool
+    where
+        T: PartialEq<U> + Debug,
+        U: Debug,
+    {
+        let verdict = got == expected;
+        if verdict {
+            self.passed += 1;
+        } else {
+            self.failed += 1;
+        }
+
+        if !self.quiet || !verdict {
+            println!("Testing if {}", description);
+            println!("  Expected: {:?}", expected);
+            println!("       Got: {:?}", got);
+            println!(
+                "    Result: {}\n",
+                if verdict { "Passed" } else { "Failed" }
+            );
+        }
+        verdict
+    }
+
+    pub fn note(&self, arguments: Arguments) {
+        if !self.quiet {
+            println!("{}", arguments);
+        }
+    }
+
+    pub fn summary(&self) {
+        if self.quiet {
+            return;
+        }
+        let total = self.passed + self.failed;
+        if self.failed == 0 {
+            println!("All {} tests passed", total);
+        } else {
+            println!("{} of {} tests failed", self.failed, total);
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        if self.failed == 0 {
+            0
+        } else {
+            1
+        }
+    }
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The entry point of the demonstration binaries, which accept -q or --quiet
+// on the command line, to only report the failed checks.
+pub fn run(demo: fn(&mut Harness)) -> i32 {
+    let mut arguments = std::env::args();
+    let program = program_name(arguments.next());
+    let parser = Parser::new(&program, "Runs the demonstration of a snippet.").flag(
+        Some('q'),
+        "quiet",
+        "Only report the failed checks",
+    );
+    let matches = match parser.parse_or_report(arguments) {
+        Ok(matches) => matches,
+        Err(code) => return code,
+    };
+    run_demo(demo, matches.flag("quiet"))
+}
+
+// The file name of the program, from the first command-line argument.
+pub fn program_name(path: Option<String>) -> String {
+    path.and_then(|path| {
+        let name = std::path::Path::new(&path).file_name()?;
+        Some(name.to_string_lossy().into_owned())
+    })
+    .unwrap_or_else(|| "demo".to_string())
+}
+
+// Runs the demonstration with a new harness, and returns the exit code, for
+// the binaries which accept more arguments than the quiet flag.
+pub fn run_demo(demo: fn(&mut Harness), quiet: bool) -> i32 {
+    let mut harness = if quiet {
+        Harness::quiet()
+    } else {
+        Harness::new()
+    };
+    demo(&mut harness);
+    harness.summary();
+    harness.exit_code()
+}
+
+#[macro_export]
+macro_rules! test {
+    ($harness:expr, $desc:expr, $got:expr, $expected:expr) => {
+        $harness.check($desc, $got, $expected)
+    };
+}
+
+#[macro_export]
+macro_rules! note {
+    ($harness:expr) => {
+        $harness.note(format_args!(""))
+    };
+    ($harness:expr, $($arg:tt)*) => {
+        $harness.note(format_args!($($arg)*))
+    };
+}
@@ -0,0 +1,489 @@
+// hashmap.rs
+// A hash map with open addressing and Robin Hood hashing, which uses FNV-1a as
+// its hash function. All entries are stored in one array, and a colliding entry
+// is placed in the next free slot. While probing for a free slot, an entry
+// which is closer to its ideal slot than the one being inserted gives up its
+// place to it, and moves further instead. This evens out the probe lengths, and
+// it keeps the entries ordered by their ideal slot, so deletion can shift the
+// following entries back by one, instead of leaving tombstones behind. The
+// table doubles its size when it gets too full. The demonstration times FNV-1a
+// against the default hasher of the standard library, with the bench snippet.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::hash::{Hash, Hasher};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+pub fn fnv1a(data: &[u8]) -> u64 {
+    let mut hasher = Fnv1a::new();
+    hasher.write(data);
+    hasher.finish()
+}
+
+pub struct Fnv1a {
+    state: u64,
+}
+
+impl Fnv1a {
+    pub fn new() -> Self {
+        Self {
+            state: FNV_OFFSET_BASIS,
+        }
+    }
+}
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+struct Entry<K, V> {
+    hash: u64,
+    key: K,
+    value: V,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ProbeStatistics {
+    pub mean: f64,
+    pub max: usize,
+    // The number of entries, which are the given number of slots away from
+    // their ideal slot.
+    pub histogram: Vec<usize>,
+}
+
+pub struct HashMap<K, V> {
+    slots: Vec<Option<Entry<K, V>>>,
+    length: usize,
+}
+
+impl<K: Hash + Eq, V> HashMap<K, V> {
+    const INITIAL_CAPACITY: usize = 8;
+    // Robin Hood hashing copes well with a high load factor.
+    const MAX_LOAD_PERCENT: usize = 90;
+
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        let needed = capacity * 100 / Self::MAX_LOAD_PERCENT + 1;
+        let slots = needed.next_power_of_two().max(Self::INITIAL_CAPACITY);
+        Self {
+            slots: (0..slots).map(|_| None).collect(),
+            length: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len() * Self::MAX_LOAD_PERCENT / 100
+    }
+
+    pub fn load_factor(&self) -> f64 {
+        self.length as f64 / self.slots.len() as f64
+    }
+
+    fn hash(key: &K) -> u64 {
+        let mut hasher = Fnv1a::new();
+        key.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // The table size is a power of two, so the slot is given by the low bits.
+    fn mask(&self) -> usize {
+        self.slots.len() - 1
+    }
+
+    fn ideal_slot(&self, hash: u64) -> usize {
+        hash as usize & self.mask()
+    }
+
+    fn distance(&self, slot: usize, hash: u64) -> usize {
+        slot.wrapping_sub(self.ideal_slot(hash)) & self.mask()
+    }
+
+    // Thanks to the ordering of the entries, the search can stop as soon as
+    // it finds an entry, which is closer to its ideal slot than the key would
+    // be at the same position.
+    fn find(&self, key: &K) -> Option<usize> {
+        let hash = Self::hash(key);
+        let mut slot = self.ideal_slot(hash);
+        let mut distance = 0;
+        while let Some(entry) = &self.slots[slot] {
+            if self.distance(slot, entry.hash) < distance {
+                return None;
+            }
+            if entry.hash == hash && entry.key == *key {
+                return Some(slot);
+            }
+            slot = (slot + 1) & self.mask();
+            distance += 1;
+        }
+        None
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let slot = self.find(key)?;
+        self.slots[slot].as_ref().map(|entry| &entry.value)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let slot = self.find(key)?;
+        self.slots[slot].as_mut().map(|entry| &mut entry.value)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.find(key).is_some()
+    }
+
+    // Returns the previous value, if the key was already present.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(slot) = self.find(&key) {
+            let entry = self.slots[slot].as_mut()?;
+            return Some(std::mem::replace(&mut entry.value, value));
+        }
+
+        if self.length + 1 > self.capacity() {
+            self.grow();
+        }
+        let hash = Self::hash(&key);
+        self.place(Entry { hash, key, value });
+        self.length += 1;
+        None
+    }
+
+    // Places an entry, which is known not to be in the table yet, taking the
+    // slot of any entry which is closer to its ideal slot, and then carrying
+    // on with placing that one.
+    fn place(&mut self, entry: Entry<K, V>) {
+        let mask = self.mask();
+        let mut entry = entry;
+        let mut slot = self.ideal_slot(entry.hash);
+        let mut distance = 0;
+        loop {
+            match &mut self.slots[slot] {
+                None => {
+                    self.slots[slot] = Some(entry);
+                    return;
+                }
+                Some(resident) => {
+                    let resident_distance = slot.wrapping_sub(resident.hash as usize) & mask;
+                    if resident_distance < distance {
+                        std::mem::swap(resident, &mut entry);
+                        distance = resident_distance;
+                    }
+                }
+            }
+            slot = (slot + 1) & mask;
+            distance += 1;
+        }
+    }
+
+    fn grow(&mut self) {
+        let size = self.slots.len() * 2;
+        let old = std::mem::replace(&mut self.slots, (0..size).map(|_| None).collect());
+        for entry in old.into_iter().flatten() {
+            self.place(entry);
+        }
+    }
+
+    // The entries after the removed one are shifted back, until one which is
+    // already in its ideal slot, or an empty slot is found.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let mut slot = self.find(key)?;
+        let removed = self.slots[slot].take()?;
+        loop {
+            let next = (slot + 1) & self.mask();
+            match &self.slots[next] {
+                Some(entry) if self.distance(next, entry.hash) > 0 => {
+                    self.slots[slot] = self.slots[next].take();
+                    slot = next;
+                }
+                _ => break,
+            }
+        }
+        self.length -= 1;
+        Some(removed.value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.slots
+            .iter()
+            .flatten()
+            .map(|entry| (&entry.key, &entry.value))
+    }
+
+    pub fn probe_statistics(&self) -> ProbeStatistics {
+        let mut histogram = Vec::new();
+        let mut total = 0;
+        for (slot, entry) in self.slots.iter().enumerate() {
+            if let Some(entry) = entry {
+                let distance = self.distance(slot, entry.hash);
+                if histogram.len() <= distance {
+                    histogram.resize(distance + 1, 0);
+                }
+                histogram[distance] += 1;
+                total += distance;
+            }
+        }
+        ProbeStatistics {
+            mean: if self.length == 0 {
+                0.0
+            } else {
+                total as f64 / self.length as f64
+            },
+            max: histogram.len().saturating_sub(1),
+            histogram,
+        }
+    }
+}
+
+impl<K: Hash + Eq, V> Default for HashMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Demonstration
+
+use crate::bench::{black_box, compare, Bench};
+use crate::harness::Harness;
+use crate::rng::{RandomSource, SplitMix64};
+
+pub fn demo(harness: &mut Harness) {
+    // Test vectors from the reference implementation of FNV.
+    test!(
+        harness,
+        "FNV-1a of the empty string is the offset basis",
+        fnv1a(b""),
+        0xcbf29ce484222325
+    );
+    test!(
+        harness,
+        "FNV-1a of \"a\" is correct",
+        fnv1a(b"a"),
+        0xaf63dc4c8601ec8c
+    );
+    test!(
+        harness,
+        "FNV-1a of \"foobar\" is correct",
+        fnv1a(b"foobar"),
+        0x85944171f73967e8
+    );
+
+    let mut map = HashMap::new();
+    test!(harness, "a new map is empty", map.is_empty(), true);
+    test!(harness, "inserting a new key", map.insert("one", 1), None);
+    map.insert("two", 2);
+    map.insert("three", 3);
+    test!(harness, "looking up a key", map.get(&"two"), Some(&2));
+    test!(harness, "looking up a missing key", map.get(&"four"), None);
+    test!(
+        harness,
+        "inserting an existing key replaces its value",
+        (map.insert("one", 11), map.get(&"one"), map.len()),
+        (Some(1), Some(&11), 3)
+    );
+    if let Some(value) = map.get_mut(&"three") {
+        *value *= 10;
+    }
+    test!(
+        harness,
+        "values can be modified in place",
+        map.get(&"three"),
+        Some(&30)
+    );
+    test!(harness, "removing a key", map.remove(&"two"), Some(2));
+    test!(
+        harness,
+        "a removed key is gone",
+        (map.contains_key(&"two"), map.len()),
+        (false, 2)
+    );
+    test!(harness, "removing a missing key", map.remove(&"two"), None);
+
+    // Random operations, compared to the hash map of the standard library.
+    let mut random = SplitMix64::new(2026);
+    let mut map = HashMap::new();
+    let mut reference = std::collections::HashMap::new();
+    let mut agrees = true;
+    for _ in 0..20000 {
+        let key = random.next_below(5000);
+        match random.next_below(4) {
+            0 => agrees &= map.remove(&key) == reference.remove(&key),
+            1 => agrees &= map.get(&key) == reference.get(&key),
+            _ => agrees &= map.insert(key, key * 2) == reference.insert(key, key * 2),
+        }
+    }
+    test!(
+        harness,
+        "random operations agree with the standard library",
+        agrees,
+        true
+    );
+    let mut entries: Vec<(u64, u64)> = map.iter().map(|(key, value)| (*key, *value)).collect();
+    let mut expected: Vec<(u64, u64)> = reference.into_iter().collect();
+    entries.sort();
+    expected.sort();
+    test!(
+        harness,
+        "the iterator visits every entry once",
+        entries,
+        expected
+    );
+
+    let mut map = HashMap::new();
+    let mut capacities = vec![map.capacity()];
+    note!(
+        harness,
+        "{:>8} {:>8} {:>6} {:>6} {:>5}",
+        "Entries",
+        "Capacity",
+        "Load",
+        "Mean",
+        "Max"
+    );
+    for key in 0..10000u32 {
+        map.insert(key.to_string(), key);
+        if map.capacity() != *capacities.last().unwrap() {
+            capacities.push(map.capacity());
+        }
+        if [100, 1000, 5000, 7372, 7373, 10000].contains(&(key + 1)) {
+            let statistics = map.probe_statistics();
+            note!(
+                harness,
+                "{:>8} {:>8} {:>5.0}% {:>6.2} {:>5}",
+                map.len(),
+                map.capacity(),
+                map.load_factor() * 100.0,
+                statistics.mean,
+                statistics.max
+            );
+        }
+    }
+    note!(
+        harness,
+        "Probe length histogram at 10000 entries: {:?}\n",
+        map.probe_statistics().histogram
+    );
+
+    test!(
+        harness,
+        "the table grows by doubling when it gets too full",
+        capacities.windows(2).all(|pair| pair[1] > pair[0]) && map.len() <= map.capacity(),
+        true
+    );
+    test!(
+        harness,
+        "every key is found after growing",
+        (0..10000u32).all(|key| map.get(&key.to_string()) == Some(&key)),
+        true
+    );
+    let statistics = map.probe_statistics();
+    test!(
+        harness,
+        "the probe lengths stay short",
+        statistics.mean < 2.0 && statistics.max < 20,
+        true
+    );
+
+    for key in (0..10000u32).filter(|key| key % 3 != 0) {
+        map.remove(&key.to_string());
+    }
+    test!(
+        harness,
+        "after removing two thirds of the keys, the rest are still found",
+        (0..10000u32).all(|key| map.contains_key(&key.to_string()) == key.is_multiple_of(3)),
+        true
+    );
+    test!(
+        harness,
+        "backward shift deletion shortens the probe lengths of the remaining keys",
+        map.probe_statistics().mean < statistics.mean,
+        true
+    );
+
+    if harness.is_quiet() {
+        return;
+    }
+    // FNV-1a against SipHash, the default hasher of the standard library.
+    let keys: Vec<String> = (0..1000u32).map(|key| key.to_string()).collect();
+    let bench = Bench::new().samples(15).batch(10);
+    fn hash_all<H: Hasher>(keys: &[String], build: fn() -> H) -> u64 {
+        keys.iter().fold(0, |sum, key| {
+            let mut hasher = build();
+            black_box(key).hash(&mut hasher);
+            sum.wrapping_add(hasher.finish())
+        })
+    }
+    let hashers = [
+        ("FNV-1a", bench.run(|| hash_all(&keys, Fnv1a::new))),
+        (
+            "SipHash",
+            bench.run(|| hash_all(&keys, std::collections::hash_map::DefaultHasher::new)),
+        ),
+    ];
+    note!(
+        harness,
+        "Time taken to hash {} short strings:\n{}",
+        keys.len(),
+        compare(&hashers)
+    );
+
+    let bench = Bench::new().samples(15);
+    let maps = [
+        (
+            "Robin Hood",
+            bench.run(|| {
+                let mut map = HashMap::new();
+                for key in &keys {
+                    map.insert(key.as_str(), ());
+                }
+                keys.iter().all(|key| map.contains_key(&key.as_str()))
+            }),
+        ),
+        (
+            "standard",
+            bench.run(|| {
+                let mut map = std::collections::HashMap::new();
+                for key in &keys {
+                    map.insert(key.as_str(), ());
+                }
+                keys.iter().all(|key| map.contains_key(key.as_str()))
+            }),
+        ),
+    ];
+    note!(
+        harness,
+        "Time taken to insert and look up {} keys:\n{}",
+        keys.len(),
+        compare(&maps)
+    );
+}
@@ -0,0 +1,364 @@
+// heap.rs
+// A d-ary min-heap, which is a priority queue stored in a vector, where the
+// children of the element at index i are at indices di + 1 to di + d. A higher
+// arity makes the tree shallower, so pushing and decreasing a key take fewer
+// steps, but popping compares more children on each level: 4-ary heaps are
+// often faster than binary ones in practice, as their levels fit in a cache
+// line. Each pushed element gets a handle, through which its key can be
+// decreased later, which is what Dijkstra's algorithm needs. The heap keeps
+// track of the position of each handle, updating it on every swap. Building a
+// heap from a slice sifts down its inner nodes from the bottom up, which takes
+// linear time, instead of the O(n log n) of pushing the elements one by one.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle(usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeapError {
+    // The element of the handle has already been popped.
+    StaleHandle,
+    // The new key is greater than the current one.
+    NotDecreasing,
+}
+
+impl fmt::Display for HeapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HeapError::StaleHandle => write!(f, "the element is no longer in the heap"),
+            HeapError::NotDecreasing => write!(f, "the new key is greater than the current one"),
+        }
+    }
+}
+
+impl std::error::Error for HeapError {}
+
+#[derive(Clone, Debug)]
+pub struct DaryHeap<T: Ord> {
+    arity: usize,
+    entries: Vec<(T, Handle)>,
+    // The index of the entry of each handle, or None, if it was popped.
+    positions: Vec<Option<usize>>,
+}
+
+impl<T: Ord> DaryHeap<T> {
+    pub fn new(arity: usize) -> Self {
+        assert!(arity >= 2, "the arity of a heap has to be at least 2");
+        Self {
+            arity,
+            entries: Vec::new(),
+            positions: Vec::new(),
+        }
+    }
+
+    // The handle of each element is its index in the slice.
+    pub fn from_slice(arity: usize, items: &[T]) -> Self
+    where
+        T: Clone,
+    {
+        let mut heap = Self::new(arity);
+        heap.entries = items
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(index, item)| (item, Handle(index)))
+            .collect();
+        heap.positions = (0..items.len()).map(Some).collect();
+        if items.len() > 1 {
+            for index in (0..=(items.len() - 2) / arity).rev() {
+                heap.sift_down(index);
+            }
+        }
+        heap
+    }
+
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn push(&mut self, item: T) -> Handle {
+        let handle = Handle(self.positions.len());
+        self.positions.push(Some(self.entries.len()));
+        self.entries.push((item, handle));
+        self.sift_up(self.entries.len() - 1);
+        handle
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.entries.first().map(|(item, _)| item)
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.pop_with_handle().map(|(item, _)| item)
+    }
+
+    pub fn pop_with_handle(&mut self) -> Option<(T, Handle)> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let last = self.entries.len() - 1;
+        self.swap(0, last);
+        let (item, handle) = self.entries.pop().unwrap();
+        self.positions[handle.0] = None;
+        self.sift_down(0);
+        Some((item, handle))
+    }
+
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.positions.get(handle.0).is_some_and(Option::is_some)
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        let position = (*self.positions.get(handle.0)?)?;
+        Some(&self.entries[position].0)
+    }
+
+    pub fn decrease_key(&mut self, handle: Handle, item: T) -> Result<(), HeapError> {
+        let position = self
+            .positions
+            .get(handle.0)
+            .copied()
+            .flatten()
+            .ok_or(HeapError::StaleHandle)?;
+        if item > self.entries[position].0 {
+            return Err(HeapError::NotDecreasing);
+        }
+        self.entries[position].0 = item;
+        self.sift_up(position);
+        Ok(())
+    }
+
+    // Checks that no element is less than its parent, and that the positions
+    // of the handles are consistent with the entries.
+    pub fn is_valid(&self) -> bool {
+        let ordered = (1..self.entries.len())
+            .all(|index| self.entries[(index - 1) / self.arity].0 <= self.entries[index].0);
+        let tracked = self
+            .entries
+            .iter()
+            .enumerate()
+            .all(|(index, (_, handle))| self.positions[handle.0] == Some(index));
+        let counted = self.positions.iter().flatten().count() == self.entries.len();
+        ordered && tracked && counted
+    }
+
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        std::iter::from_fn(|| self.pop()).collect()
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.entries.swap(a, b);
+        self.positions[self.entries[a].1 .0] = Some(a);
+        self.positions[self.entries[b].1 .0] = Some(b);
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / self.arity;
+            if self.entries[index].0 >= self.entries[parent].0 {
+                break;
+            }
+            self.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let first = self.arity * index + 1;
+            let last = (first + self.arity).min(self.entries.len());
+            let Some(smallest) =
+                (first..last).min_by(|a, b| self.entries[*a].0.cmp(&self.entries[*b].0))
+            else {
+                return;
+            };
+            if self.entries[smallest].0 >= self.entries[index].0 {
+                return;
+            }
+            self.swap(index, smallest);
+            index = smallest;
+        }
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use crate::rng::{RandomSource, SplitMix64};
+use std::cell::Cell;
+use std::cmp::Ordering;
+
+// A number which counts how many times it is compared.
+#[derive(Clone)]
+struct Counted<'a> {
+    value: u32,
+    comparisons: &'a Cell<u64>,
+}
+
+impl PartialEq for Counted<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Counted<'_> {}
+
+impl PartialOrd for Counted<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Counted<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.comparisons.set(self.comparisons.get() + 1);
+        self.value.cmp(&other.value)
+    }
+}
+
+pub fn demo(harness: &mut Harness) {
+    let mut random = SplitMix64::new(7);
+    let values: Vec<u32> = (0..1000).map(|_| random.next_u32() % 10000).collect();
+    let mut sorted = values.clone();
+    sorted.sort_unstable();
+
+    for arity in [2, 3, 4, 8] {
+        let mut heap = DaryHeap::new(arity);
+        let mut valid = true;
+        for value in &values {
+            heap.push(*value);
+            valid &= heap.is_valid();
+        }
+        test!(
+            harness,
+            &format!("the {}-ary heap stays valid while pushing", arity),
+            valid,
+            true
+        );
+        test!(
+            harness,
+            &format!("the {}-ary heap peeks the minimum", arity),
+            heap.peek(),
+            sorted.first()
+        );
+        let mut popped = Vec::new();
+        while let Some(value) = heap.pop() {
+            popped.push(value);
+            valid &= heap.is_valid();
+        }
+        test!(
+            harness,
+            &format!("the {}-ary heap pops in ascending order", arity),
+            (valid, popped == sorted),
+            (true, true)
+        );
+
+        let heapified = DaryHeap::from_slice(arity, &values);
+        test!(
+            harness,
+            &format!("heapifying a slice into a {}-ary heap", arity),
+            (heapified.is_valid(), heapified.into_sorted_vec() == sorted),
+            (true, true)
+        );
+    }
+
+    let mut heap = DaryHeap::new(4);
+    let handles: Vec<Handle> = [50, 40, 30, 20, 10].iter().map(|v| heap.push(*v)).collect();
+    test!(
+        harness,
+        "decreasing a key moves the element up",
+        (
+            heap.decrease_key(handles[0], 5),
+            heap.peek(),
+            heap.is_valid()
+        ),
+        (Ok(()), Some(&5), true)
+    );
+    test!(
+        harness,
+        "a key can not be increased",
+        heap.decrease_key(handles[1], 45),
+        Err(HeapError::NotDecreasing)
+    );
+    test!(
+        harness,
+        "the handle gives the element",
+        heap.get(handles[2]),
+        Some(&30)
+    );
+    test!(
+        harness,
+        "popping gives the handle",
+        heap.pop_with_handle(),
+        Some((5, handles[0]))
+    );
+    test!(
+        harness,
+        "a popped handle is stale",
+        (heap.contains(handles[0]), heap.decrease_key(handles[0], 1)),
+        (false, Err(HeapError::StaleHandle))
+    );
+    test!(
+        harness,
+        "the rest is in order",
+        heap.into_sorted_vec(),
+        vec![10, 20, 30, 40]
+    );
+
+    // Pushing everything, decreasing some keys, and popping everything, with
+    // comparisons counted, shows the tradeoff of the arity.
+    note!(
+        harness,
+        "{:>6} {:>12} {:>14} {:>12} {:>12}",
+        "arity",
+        "push",
+        "decrease key",
+        "pop",
+        "heapify"
+    );
+    for arity in [2, 3, 4, 8, 16] {
+        let comparisons = Cell::new(0);
+        let counted = |value| Counted {
+            value,
+            comparisons: &comparisons,
+        };
+        let mut heap = DaryHeap::new(arity);
+        let handles: Vec<Handle> = values.iter().map(|v| heap.push(counted(*v))).collect();
+        let pushing = comparisons.replace(0);
+        for handle in handles.iter().step_by(3) {
+            let value = heap.get(*handle).unwrap().value;
+            heap.decrease_key(*handle, counted(value / 2)).unwrap();
+        }
+        let decreasing = comparisons.replace(0);
+        while heap.pop().is_some() {}
+        let popping = comparisons.replace(0);
+        let items: Vec<Counted> = values.iter().map(|v| counted(*v)).collect();
+        let heap = DaryHeap::from_slice(arity, &items);
+        let heapifying = comparisons.get();
+        drop(heap);
+        note!(
+            harness,
+            "{:>6} {:>12} {:>14} {:>12} {:>12}",
+            arity,
+            pushing,
+            decreasing,
+            popping,
+            heapifying
+        );
+    }
+    note!(harness);
+}
@@ -0,0 +1,383 @@
+// hmac.rs
+// Keyed-hash message authentication codes (HMAC), as specified in RFC 2104,
+// and the HMAC-based key derivation function (HKDF) of RFC 5869, generic over
+// the hash snippets of the collection. HMAC hashes the message with the key
+// twice, padded to the block size of the hash and mixed with two constants,
+// which keeps it secure even with hashes which are vulnerable to length
+// extension, like SHA-256, or to collisions, like SHA-1 and MD5. HKDF builds on
+// it in two steps: extracting a fixed-size pseudo-random key from some input
+// keying material and a salt, then expanding it into as many bytes of output
+// keying material as needed, separated by an application-specific info string.
+// The hashes only need to expose their block and digest sizes, and the usual
+// streaming interface, through a common trait.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::md5::Md5;
+use crate::sha1::Sha1;
+use crate::sha2::{Sha224, Sha256, Sha384, Sha512};
+use crate::sha3::{Sha3_256, Sha3_512};
+use std::fmt;
+
+// Hashes can be cloned, so that a keyed HMAC can be reused.
+pub trait HashFunction: Clone {
+    const BLOCK_SIZE: usize;
+    const DIGEST_SIZE: usize;
+
+    fn new() -> Self;
+    fn update(&mut self, data: &[u8]);
+    fn finalize(self) -> Vec<u8>;
+
+    fn digest(data: &[u8]) -> Vec<u8>
+    where
+        Self: Sized,
+    {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+macro_rules! impl_hash_function {
+    ($($type:ty),*) => {
+        $(
+            impl HashFunction for $type {
+                const BLOCK_SIZE: usize = <$type>::BLOCK_SIZE;
+                const DIGEST_SIZE: usize = <$type>::DIGEST_SIZE;
+
+                fn new() -> Self {
+                    <$type>::new()
+                }
+
+                fn update(&mut self, data: &[u8]) {
+                    <$type>::update(self, data);
+                }
+
+                fn finalize(self) -> Vec<u8> {
+                    <$type>::finalize(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_hash_function!(Md5, Sha1, Sha224, Sha256, Sha384, Sha512, Sha3_256, Sha3_512);
+
+const INNER_PAD: u8 = 0x36;
+const OUTER_PAD: u8 = 0x5c;
+
+#[derive(Clone)]
+pub struct Hmac<H: HashFunction> {
+    inner: H,
+    outer_key: Vec<u8>,
+}
+
+impl<H: HashFunction> Hmac<H> {
+    pub fn new(key: &[u8]) -> Self {
+        // Keys longer than a block are hashed first, and all keys are padded
+        // with zeros to the block size.
+        let mut key = if key.len() > H::BLOCK_SIZE {
+            H::digest(key)
+        } else {
+            key.to_vec()
+        };
+        key.resize(H::BLOCK_SIZE, 0x00);
+
+        let inner_key: Vec<u8> = key.iter().map(|byte| byte ^ INNER_PAD).collect();
+        let outer_key = key.iter().map(|byte| byte ^ OUTER_PAD).collect();
+        let mut inner = H::new();
+        inner.update(&inner_key);
+        Self { inner, outer_key }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        let mut outer = H::new();
+        outer.update(&self.outer_key);
+        outer.update(&self.inner.finalize());
+        outer.finalize()
+    }
+
+    pub fn mac(key: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut hmac = Self::new(key);
+        hmac.update(data);
+        hmac.finalize()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LengthError {
+    pub length: usize,
+    pub maximum: usize,
+}
+
+impl fmt::Display for LengthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "cannot derive {} bytes, the maximum is {}",
+            self.length, self.maximum
+        )
+    }
+}
+
+impl std::error::Error for LengthError {}
+
+// A missing salt is the same as a salt of zeros, as long as the digest.
+pub fn hkdf_extract<H: HashFunction>(salt: &[u8], input_key: &[u8]) -> Vec<u8> {
+    if salt.is_empty() {
+        Hmac::<H>::mac(&vec![0x00; H::DIGEST_SIZE], input_key)
+    } else {
+        Hmac::<H>::mac(salt, input_key)
+    }
+}
+
+// The output is made of blocks, where each one is the HMAC of the previous
+// block, the info, and a counter byte, so there can be at most 255 of them.
+pub fn hkdf_expand<H: HashFunction>(
+    pseudo_random_key: &[u8],
+    info: &[u8],
+    length: usize,
+) -> Result<Vec<u8>, LengthError> {
+    let maximum = 255 * H::DIGEST_SIZE;
+    if length > maximum {
+        return Err(LengthError { length, maximum });
+    }
+    let mut output = Vec::with_capacity(length);
+    let mut block = Vec::new();
+    let mut counter = 1u8;
+    while output.len() < length {
+        let mut hmac = Hmac::<H>::new(pseudo_random_key);
+        hmac.update(&block);
+        hmac.update(info);
+        hmac.update(&[counter]);
+        block = hmac.finalize();
+        output.extend_from_slice(&block);
+        counter = counter.wrapping_add(1);
+    }
+    output.truncate(length);
+    Ok(output)
+}
+
+pub fn hkdf<H: HashFunction>(
+    salt: &[u8],
+    input_key: &[u8],
+    info: &[u8],
+    length: usize,
+) -> Result<Vec<u8>, LengthError> {
+    hkdf_expand::<H>(&hkdf_extract::<H>(salt, input_key), info, length)
+}
+
+// Demonstration
+
+use crate::encoding::HEX;
+use crate::harness::Harness;
+
+fn rfc4231_cases() -> Vec<(Vec<u8>, Vec<u8>)> {
+    vec![
+        (vec![0x0b; 20], b"Hi There".to_vec()),
+        (b"Jefe".to_vec(), b"what do ya want for nothing?".to_vec()),
+        (vec![0xaa; 20], vec![0xdd; 50]),
+        ((0x01..=0x19).collect(), vec![0xcd; 50]),
+        (
+            vec![0xaa; 131],
+            b"Test Using Larger Than Block-Size Key - Hash Key First".to_vec(),
+        ),
+        (
+            vec![0xaa; 131],
+            b"This is a test using a larger than block-size key and a larger than block-size \
+              data. The key needs to be hashed before being used by the HMAC algorithm."
+                .to_vec(),
+        ),
+    ]
+}
+
+// The test cases 1 to 4, 6, and 7 of the RFC, for SHA-224, SHA-256, SHA-384,
+// and SHA-512, leaving out the fifth one, which is about truncating the output.
+const RFC4231: [[&str; 4]; 6] = [
+    [
+        "896fb1128abbdf196832107cd49df33f47b4b1169912ba4f53684b22",
+        "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+        "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59c\
+         faea9ea9076ede7f4af152e8b2fa9cb6",
+        "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde\
+         daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+    ],
+    [
+        "a30e01098bc6dbbf45690f3a7e9e6d0f8bbea2a39e6148008fd05e44",
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+        "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e\
+         8e2240ca5e69e2c78b3239ecfab21649",
+        "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554\
+         9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+    ],
+    [
+        "7fb3cb3588c6c1f6ffa9694d7d6ad2649365b0c1f65d69d1ec8333ea",
+        "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+        "88062608d3e6ad8a0aa2ace014c8a86f0aa635d947ac9febe83ef4e55966144b\
+         2a5ab39dc13814b94e3ab6e101a34f27",
+        "fa73b0089d56a284efb0f0756c890be9b1b5dbdd8ee81a3655f83e33b2279d39\
+         bf3e848279a722c806b485a47e67c807b946a337bee8942674278859e13292fb",
+    ],
+    [
+        "6c11506874013cac6a2abc1bb382627cec6a90d86efc012de7afec5a",
+        "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b",
+        "3e8a69b7783c25851933ab6290af6ca77a9981480850009cc5577c6e1f573b4e\
+         6801dd23c4a7d679ccf8a386c674cffb",
+        "b0ba465637458c6990e5a8c5f61d4af7e576d97ff94b872de76f8050361ee3db\
+         a91ca5c11aa25eb4d679275cc5788063a5f19741120c4f2de2adebeb10a298dd",
+    ],
+    [
+        "95e9a0db962095adaebe9b2d6f0dbce2d499f112f2d2b7273fa6870e",
+        "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+        "4ece084485813e9088d2c63a041bc5b44f9ef1012a2b588f3cd11f05033ac4c6\
+         0c2ef6ab4030fe8296248df163f44952",
+        "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f352\
+         6b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+    ],
+    [
+        "3a854166ac5d9f023f54d517d0b39dbd946770db9c2b95c9f6f565d1",
+        "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+        "6617178e941f020d351e2f254e8fd32c602420feb0b8fb9adccebb82461e99c5\
+         a678cc31e799176d3860e6110c46523e",
+        "e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944\
+         b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58",
+    ],
+];
+
+// The input keying material, the salt, the info, the pseudo-random key, and
+// the output keying material of the test cases 1 to 3 of the RFC.
+fn rfc5869_cases() -> Vec<[Vec<u8>; 5]> {
+    let decode = |text: &str| HEX.decode(text).unwrap();
+    vec![
+        [
+            vec![0x0b; 22],
+            (0x00..=0x0c).collect(),
+            (0xf0..=0xf9).collect(),
+            decode("077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5"),
+            decode(
+                "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf\
+                 34007208d5b887185865",
+            ),
+        ],
+        [
+            (0x00..=0x4f).collect(),
+            (0x60..=0xaf).collect(),
+            (0xb0..=0xff).collect(),
+            decode("06a6b88c5853361a06104c9ceb35b45cef760014904671014a193f40c15fc244"),
+            decode(
+                "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c\
+                 59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71\
+                 cc30c58179ec3e87c14c01d5c1f3434f1d87",
+            ),
+        ],
+        [
+            vec![0x0b; 22],
+            vec![],
+            vec![],
+            decode("19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04"),
+            decode(
+                "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d\
+                 9d201395faa4b61a96c8",
+            ),
+        ],
+    ]
+}
+
+pub fn demo(harness: &mut Harness) {
+    for (index, ((key, data), expected)) in rfc4231_cases().iter().zip(RFC4231).enumerate() {
+        let case = if index < 4 { index + 1 } else { index + 2 };
+        let macs = [
+            HEX.encode(&Hmac::<Sha224>::mac(key, data)),
+            HEX.encode(&Hmac::<Sha256>::mac(key, data)),
+            HEX.encode(&Hmac::<Sha384>::mac(key, data)),
+            HEX.encode(&Hmac::<Sha512>::mac(key, data)),
+        ];
+        let description = format!("HMAC-SHA-2 of the RFC 4231 test case {}", case);
+        test!(harness, &description, macs, expected);
+    }
+    test!(
+        harness,
+        "truncated HMAC-SHA-256 of the RFC 4231 test case 5",
+        HEX.encode(&Hmac::<Sha256>::mac(&[0x0c; 20], b"Test With Truncation")[..16]),
+        "a3b6167473100ee06e0c796c2955552b"
+    );
+
+    const FOX: &[u8] = b"The quick brown fox jumps over the lazy dog";
+    test!(
+        harness,
+        "HMAC over the other hash snippets",
+        [
+            HEX.encode(&Hmac::<Md5>::mac(b"key", FOX)),
+            HEX.encode(&Hmac::<Sha1>::mac(b"key", FOX)),
+            HEX.encode(&Hmac::<Sha3_256>::mac(b"key", FOX)),
+        ],
+        [
+            "80070713463e7749b90c2dc24911e275",
+            "de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9",
+            "8c6e0683409427f8931711b10ca92a506eb1fafa48fadd66d76126f47ac2c333",
+        ]
+    );
+    let mut streamed = Hmac::<Sha512>::new(b"key");
+    for word in FOX.chunks(5) {
+        streamed.update(word);
+    }
+    test!(
+        harness,
+        "streaming gives the same tag as authenticating in one go",
+        streamed.finalize(),
+        Hmac::<Sha512>::mac(b"key", FOX)
+    );
+
+    for (index, [input_key, salt, info, prk, okm]) in rfc5869_cases().iter().enumerate() {
+        let description = format!(
+            "HKDF-SHA-256 extraction of the RFC 5869 test case {}",
+            index + 1
+        );
+        test!(
+            harness,
+            &description,
+            &hkdf_extract::<Sha256>(salt, input_key),
+            prk
+        );
+        let description = format!(
+            "HKDF-SHA-256 expansion of the RFC 5869 test case {}",
+            index + 1
+        );
+        test!(
+            harness,
+            &description,
+            hkdf_expand::<Sha256>(prk, info, okm.len()).as_ref(),
+            Ok(okm)
+        );
+    }
+    test!(
+        harness,
+        "HKDF output is limited to 255 blocks",
+        (
+            hkdf::<Sha1>(b"salt", b"key", b"", 255 * 20).map(|okm| okm.len()),
+            hkdf::<Sha1>(b"salt", b"key", b"", 255 * 20 + 1).map_err(|error| error.to_string())
+        ),
+        (
+            Ok(5100),
+            Err("cannot derive 5101 bytes, the maximum is 5100".to_string())
+        )
+    );
+
+    let master = b"correct horse battery staple";
+    let salt = b"snippets";
+    note!(
+        harness,
+        "Keys derived from one master secret with HKDF-SHA-256:"
+    );
+    for purpose in ["encryption", "authentication", "session 1", "session 2"] {
+        let key = hkdf::<Sha256>(salt, master, purpose.as_bytes(), 16).unwrap();
+        note!(harness, "    {:<16} {}", purpose, HEX.encode(&key));
+    }
+}
@@ -0,0 +1,975 @@
+// httpclient.rs
+// A minimal HTTP/1.1 client, which sends GET and POST requests over plain TCP,
+// with the URL parsed by the url snippet. The request is serialized by hand:
+// the request line, the Host header, which HTTP/1.1 requires, and a Connection
+// header which asks the server to close the connection after the response, so
+// a body without a length simply ends where the connection does. The response
+// is parsed line by line: the status line, then the headers, into a map with
+// case-insensitive names, which keeps the repeated ones, and then the body. It
+// is either as long as its Content-Length, or it arrives in chunks, each with
+// its size in hexadecimal, until an empty one, which may be followed by more
+// headers. Redirects are followed up to a limit, and the connection, the reads
+// and the writes all have timeouts, so a stuck server can not hang the client,
+// and the bodies have a length limit, so it can not exhaust its memory either.
+// The binary fetches the URL it is given, and prints the status, the headers,
+// and the first bytes of the body, and without one, it runs the demonstration
+// against a local server with canned responses.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::url::Url;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    InvalidUrl,
+    UnsupportedScheme,
+    Io,
+    Timeout,
+    MalformedStatusLine,
+    MalformedHeader,
+    MalformedChunk,
+    TooManyRedirects,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpError {
+    pub kind: ErrorKind,
+    pub detail: String,
+}
+
+impl HttpError {
+    fn new(kind: ErrorKind, detail: impl Into<String>) -> Self {
+        Self {
+            kind,
+            detail: detail.into(),
+        }
+    }
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.kind {
+            ErrorKind::InvalidUrl => "invalid URL",
+            ErrorKind::UnsupportedScheme => "unsupported scheme",
+            ErrorKind::Io => "I/O error",
+            ErrorKind::Timeout => "timed out",
+            ErrorKind::MalformedStatusLine => "malformed status line",
+            ErrorKind::MalformedHeader => "malformed header",
+            ErrorKind::MalformedChunk => "malformed chunk",
+            ErrorKind::TooManyRedirects => "too many redirects",
+        };
+        write!(f, "{}: {}", message, self.detail)
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+impl From<io::Error> for HttpError {
+    fn from(error: io::Error) -> Self {
+        // A read timeout is reported as either of these, depending on the
+        // platform.
+        match error.kind() {
+            io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => {
+                HttpError::new(ErrorKind::Timeout, error.to_string())
+            }
+            _ => HttpError::new(ErrorKind::Io, error.to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Method::Get => write!(f, "GET"),
+            Method::Post => write!(f, "POST"),
+        }
+    }
+}
+
+// The headers in the order they arrived, with the names compared regardless
+// of their case, as a header may be repeated.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Headers {
+    entries: Vec<(String, String)>,
+}
+
+impl Headers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn append(&mut self, name: &str, value: &str) {
+        self.entries.push((name.to_string(), value.to_string()));
+    }
+
+    // Replaces every earlier value of the header.
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.remove(name);
+        self.append(name, value);
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.entries
+            .retain(|(entry, _)| !entry.eq_ignore_ascii_case(name));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(entry, _)| entry.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(entry, _)| entry.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Request {
+    pub method: Method,
+    pub url: Url,
+    pub headers: Headers,
+    pub body: Vec<u8>,
+}
+
+fn parse_url(url: &str) -> Result<Url, HttpError> {
+    Url::parse(url).map_err(|error| HttpError::new(ErrorKind::InvalidUrl, error.to_string()))
+}
+
+impl Request {
+    pub fn new(method: Method, url: Url) -> Self {
+        Self {
+            method,
+            url,
+            headers: Headers::new(),
+            body: Vec::new(),
+        }
+    }
+
+    pub fn get(url: &str) -> Result<Self, HttpError> {
+        Ok(Self::new(Method::Get, parse_url(url)?))
+    }
+
+    pub fn post(
+        url: &str,
+        content_type: &str,
+        body: impl Into<Vec<u8>>,
+    ) -> Result<Self, HttpError> {
+        let mut request = Self::new(Method::Post, parse_url(url)?);
+        request.headers.set("Content-Type", content_type);
+        request.body = body.into();
+        Ok(request)
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.set(name, value);
+        self
+    }
+
+    // The path and the query of the URL, which is what the server sees of it.
+    pub fn target(&self) -> String {
+        let path = if self.url.path.is_empty() {
+            "/"
+        } else {
+            &self.url.path
+        };
+        match &self.url.query {
+            Some(query) => format!("{}?{}", path, query),
+            None => path.to_string(),
+        }
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut head = format!("{} {} HTTP/1.1\r\n", self.method, self.target());
+        if !self.headers.contains("Host") {
+            let host = self.url.host.as_deref().unwrap_or("");
+            let host = if host.contains(':') {
+                format!("[{}]", host)
+            } else {
+                host.to_string()
+            };
+            match self.url.port {
+                Some(port) => head += &format!("Host: {}:{}\r\n", host, port),
+                None => head += &format!("Host: {}\r\n", host),
+            }
+        }
+        let defaults = [
+            ("User-Agent", "snippets-httpclient/1.0"),
+            ("Accept", "*/*"),
+            ("Connection", "close"),
+        ];
+        for (name, value) in defaults {
+            if !self.headers.contains(name) {
+                head += &format!("{}: {}\r\n", name, value);
+            }
+        }
+        for (name, value) in self.headers.iter() {
+            head += &format!("{}: {}\r\n", name, value);
+        }
+        if self.method == Method::Post || !self.body.is_empty() {
+            head += &format!("Content-Length: {}\r\n", self.body.len());
+        }
+        head += "\r\n";
+        let mut bytes = head.into_bytes();
+        bytes.extend_from_slice(&self.body);
+        bytes
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Response {
+    pub version: String,
+    pub status: u16,
+    pub reason: String,
+    pub headers: Headers,
+    pub body: Vec<u8>,
+    // Where the response came from, after the redirects.
+    pub url: Url,
+    pub redirects: usize,
+}
+
+impl Response {
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    pub fn is_redirect(&self) -> bool {
+        matches!(self.status, 301 | 302 | 303 | 307 | 308)
+    }
+}
+
+const MAX_LINE: usize = 8192;
+// The length of the bodies is chosen by the server, so it is limited, before
+// the client allocates a buffer for it.
+pub const MAX_BODY: usize = 1 << 24;
+
+// A line without its CRLF, or without a lone LF, which servers sometimes send.
+pub fn read_line(reader: &mut impl BufRead, kind: ErrorKind) -> Result<String, HttpError> {
+    let mut line = Vec::new();
+    reader
+        .take(MAX_LINE as u64 + 1)
+        .read_until(b'\n', &mut line)?;
+    if line.last() != Some(&b'\n') {
+        let detail = if line.len() > MAX_LINE {
+            "line too long"
+        } else {
+            "connection closed in the middle of a line"
+        };
+        return Err(HttpError::new(kind, detail));
+    }
+    line.pop();
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    String::from_utf8(line).map_err(|_| HttpError::new(kind, "line is not UTF-8"))
+}
+
+// Reads header lines until the empty one. A line which starts with whitespace
+// continues the previous value, the obsolete way of folding long headers.
+pub fn read_headers(reader: &mut impl BufRead, headers: &mut Headers) -> Result<(), HttpError> {
+    loop {
+        let line = read_line(reader, ErrorKind::MalformedHeader)?;
+        if line.is_empty() {
+            return Ok(());
+        }
+        if line.starts_with([' ', '\t']) {
+            let (_, value) = headers
+                .entries
+                .last_mut()
+                .ok_or_else(|| HttpError::new(ErrorKind::MalformedHeader, line.clone()))?;
+            value.push(' ');
+            value.push_str(line.trim());
+            continue;
+        }
+        let (name, value) = line
+            .split_once(':')
+            .filter(|(name, _)| {
+                !name.is_empty() && name.bytes().all(|byte| byte.is_ascii_graphic())
+            })
+            .ok_or_else(|| HttpError::new(ErrorKind::MalformedHeader, line.clone()))?;
+        headers.append(name, value.trim());
+    }
+}
+
+// Each chunk is its size in hexadecimal, with optional extensions after a
+// semicolon, then the data, and a CRLF. The empty chunk ends the body, and the
+// trailer headers after it are added to the others.
+pub fn decode_chunked(
+    reader: &mut impl BufRead,
+    trailers: &mut Headers,
+) -> Result<Vec<u8>, HttpError> {
+    let mut body = Vec::new();
+    loop {
+        let line = read_line(reader, ErrorKind::MalformedChunk)?;
+        let size = line.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|_| HttpError::new(ErrorKind::MalformedChunk, format!("size {:?}", size)))?;
+        if size == 0 {
+            return read_headers(reader, trailers).map(|_| body);
+        }
+        let start = body.len();
+        let end = start
+            .checked_add(size)
+            .filter(|end| *end <= MAX_BODY)
+            .ok_or_else(|| HttpError::new(ErrorKind::MalformedChunk, "the body is too long"))?;
+        body.resize(end, 0);
+        reader
+            .read_exact(&mut body[start..])
+            .map_err(|error| match error.kind() {
+                io::ErrorKind::UnexpectedEof => {
+                    HttpError::new(ErrorKind::MalformedChunk, "the chunk ends early")
+                }
+                _ => error.into(),
+            })?;
+        if !read_line(reader, ErrorKind::MalformedChunk)?.is_empty() {
+            return Err(HttpError::new(
+                ErrorKind::MalformedChunk,
+                "the chunk is longer than its size",
+            ));
+        }
+    }
+}
+
+// Parses a response from the reader, skipping the informational ones before
+// it, which only tell the client to wait for the real one.
+pub fn read_response(reader: &mut impl BufRead, url: Url) -> Result<Response, HttpError> {
+    let (version, status, reason) = loop {
+        let line = read_line(reader, ErrorKind::MalformedStatusLine)?;
+        let mut parts = line.splitn(3, ' ');
+        let version = parts.next().unwrap_or("");
+        let status = parts.next().and_then(|status| {
+            status
+                .parse::<u16>()
+                .ok()
+                .filter(|status| (100..600).contains(status))
+        });
+        let (true, Some(status)) = (version.starts_with("HTTP/1."), status) else {
+            return Err(HttpError::new(ErrorKind::MalformedStatusLine, line));
+        };
+        let reason = parts.next().unwrap_or("").to_string();
+        if (100..200).contains(&status) {
+            read_headers(reader, &mut Headers::new())?;
+            continue;
+        }
+        break (version.to_string(), status, reason);
+    };
+
+    let mut headers = Headers::new();
+    read_headers(reader, &mut headers)?;
+    let chunked = headers
+        .get_all("Transfer-Encoding")
+        .flat_map(|value| value.split(','))
+        .last()
+        .is_some_and(|coding| coding.trim().eq_ignore_ascii_case("chunked"));
+    let body = if status == 204 || status == 304 {
+        Vec::new()
+    } else if chunked {
+        let mut trailers = Headers::new();
+        let body = decode_chunked(reader, &mut trailers)?;
+        headers.entries.extend(trailers.entries);
+        body
+    } else if let Some(length) = headers.get("Content-Length") {
+        let length = length
+            .parse::<usize>()
+            .ok()
+            .filter(|length| *length <= MAX_BODY)
+            .ok_or_else(|| {
+                HttpError::new(
+                    ErrorKind::MalformedHeader,
+                    format!("Content-Length: {}", length),
+                )
+            })?;
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+        body
+    } else {
+        let mut body = Vec::new();
+        reader.read_to_end(&mut body)?;
+        body
+    };
+
+    Ok(Response {
+        version,
+        status,
+        reason,
+        headers,
+        body,
+        url,
+        redirects: 0,
+    })
+}
+
+// The target of a redirect: an absolute URL, one without a scheme, a path
+// from the root of the same server, or a path relative to the directory of
+// the current one. The dot segments are left for the server to resolve.
+pub fn resolve(base: &Url, location: &str) -> Result<Url, HttpError> {
+    if location.contains("://") {
+        return parse_url(location);
+    }
+    if location.starts_with("//") {
+        return parse_url(&format!("{}:{}", base.scheme, location));
+    }
+    let mut origin = base.clone();
+    origin.path.clear();
+    origin.query = None;
+    origin.fragment = None;
+    if location.starts_with('/') {
+        return parse_url(&format!("{}{}", origin, location));
+    }
+    let directory = match base.path.rfind('/') {
+        Some(index) => &base.path[..=index],
+        None => "/",
+    };
+    parse_url(&format!("{}{}{}", origin, directory, location))
+}
+
+#[derive(Clone, Debug)]
+pub struct Client {
+    timeout: Duration,
+    max_redirects: usize,
+}
+
+impl Client {
+    pub fn new() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            max_redirects: 5,
+        }
+    }
+
+    // The limit on connecting, and on each read and write, not on the whole
+    // exchange.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn max_redirects(mut self, max_redirects: usize) -> Self {
+        self.max_redirects = max_redirects;
+        self
+    }
+
+    pub fn get(&self, url: &str) -> Result<Response, HttpError> {
+        self.send(Request::get(url)?)
+    }
+
+    pub fn post(
+        &self,
+        url: &str,
+        content_type: &str,
+        body: impl Into<Vec<u8>>,
+    ) -> Result<Response, HttpError> {
+        self.send(Request::post(url, content_type, body)?)
+    }
+
+    // A 303 is always followed with a GET, like browsers do with a 301 and a
+    // 302 after a POST, and a 307 and a 308 repeat the request as it was.
+    pub fn send(&self, mut request: Request) -> Result<Response, HttpError> {
+        let mut redirects = 0;
+        loop {
+            let mut response = self.send_once(&request)?;
+            response.redirects = redirects;
+            let location = match response.headers.get("Location") {
+                Some(location) if response.is_redirect() => location,
+                _ => return Ok(response),
+            };
+            if redirects == self.max_redirects {
+                return Err(HttpError::new(
+                    ErrorKind::TooManyRedirects,
+                    format!("{} redirects, the last one to {}", redirects + 1, location),
+                ));
+            }
+            request.url = resolve(&request.url, location)?;
+            let switch = response.status == 303
+                || (matches!(response.status, 301 | 302) && request.method == Method::Post);
+            if switch {
+                request.method = Method::Get;
+                request.body.clear();
+                request.headers.remove("Content-Type");
+            }
+            redirects += 1;
+        }
+    }
+
+    fn send_once(&self, request: &Request) -> Result<Response, HttpError> {
+        let url = &request.url;
+        if url.scheme != "http" {
+            return Err(HttpError::new(
+                ErrorKind::UnsupportedScheme,
+                url.scheme.clone(),
+            ));
+        }
+        let host = url
+            .host
+            .as_deref()
+            .filter(|host| !host.is_empty())
+            .ok_or_else(|| HttpError::new(ErrorKind::InvalidUrl, "missing host"))?;
+        let port = url.effective_port().unwrap_or(80);
+        let mut last_error = HttpError::new(ErrorKind::Io, format!("no address for {}", host));
+        for address in (host, port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, self.timeout) {
+                Ok(mut stream) => {
+                    stream.set_read_timeout(Some(self.timeout))?;
+                    stream.set_write_timeout(Some(self.timeout))?;
+                    stream.write_all(&request.serialize())?;
+                    let mut reader = BufReader::new(stream);
+                    return read_response(&mut reader, url.clone());
+                }
+                Err(error) => last_error = error.into(),
+            }
+        }
+        Err(last_error)
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Prints the status line, the headers, and the start of the body, with the
+// bytes which are not printable escaped.
+pub fn describe(response: &Response, bytes: usize) -> String {
+    let mut text = format!(
+        "{} {} {}\n",
+        response.version, response.status, response.reason
+    );
+    for (name, value) in response.headers.iter() {
+        text += &format!("{}: {}\n", name, value);
+    }
+    let shown = &response.body[..bytes.min(response.body.len())];
+    text += &format!("\n{}", String::from_utf8_lossy(shown).escape_debug());
+    if shown.len() < response.body.len() {
+        text += &format!("... ({} bytes in total)", response.body.len());
+    }
+    text
+}
+
+pub fn main(mut arguments: impl Iterator<Item = String>) -> i32 {
+    use crate::args::Parser;
+    let program = crate::harness::program_name(arguments.next());
+    let parser = Parser::new(&program, "A minimal HTTP/1.1 client over plain TCP.")
+        .optional("URL", "Fetch the URL, instead of running the demonstration")
+        .option(
+            Some('d'),
+            "data",
+            "BODY",
+            "Send the body as a form, with POST",
+        )
+        .option(
+            Some('H'),
+            "header",
+            "HEADER",
+            "Add a header, as name: value",
+        )
+        .option(
+            Some('n'),
+            "bytes",
+            "COUNT",
+            "Print this many bytes of the body (default: 512)",
+        )
+        .option(
+            Some('t'),
+            "timeout",
+            "SECONDS",
+            "Give up waiting after this long (default: 10)",
+        )
+        .flag(
+            Some('q'),
+            "quiet",
+            "Only report the failed checks of the demonstration",
+        );
+    let matches = match parser.parse_or_report(arguments) {
+        Ok(matches) => matches,
+        Err(code) => return code,
+    };
+    let Some(url) = matches.value("URL") else {
+        return crate::harness::run_demo(demo, matches.flag("quiet"));
+    };
+    let (Ok(bytes), Ok(timeout)) = (
+        matches.value_or("bytes", "512").parse::<usize>(),
+        matches.value_or("timeout", "10").parse::<f64>(),
+    ) else {
+        eprintln!(
+            "{}: the byte count and the timeout must be numbers",
+            program
+        );
+        return 2;
+    };
+    let request = match matches.value("data") {
+        Some(data) => Request::post(url, "application/x-www-form-urlencoded", data),
+        None => Request::get(url),
+    };
+    let result = request.and_then(|mut request| {
+        for header in matches.values("header") {
+            let Some((name, value)) = header.split_once(':') else {
+                return Err(HttpError::new(ErrorKind::MalformedHeader, header.clone()));
+            };
+            request.headers.append(name.trim(), value.trim());
+        }
+        Client::new()
+            .timeout(Duration::from_secs_f64(timeout.max(0.001)))
+            .send(request)
+    });
+    match result {
+        Ok(response) => {
+            println!("{}", describe(&response, bytes));
+            0
+        }
+        Err(error) => {
+            eprintln!("{}: {}", program, error);
+            1
+        }
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+// Reads a request, and answers it from a few canned responses, one connection
+// at a time.
+fn answer(stream: TcpStream) -> Result<(), HttpError> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let line = read_line(&mut reader, ErrorKind::MalformedStatusLine)?;
+    let mut headers = Headers::new();
+    read_headers(&mut reader, &mut headers)?;
+    let length = headers
+        .get("Content-Length")
+        .map_or(0, |length| length.parse().unwrap_or(0));
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    let mut parts = line.split(' ');
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+
+    let plain = |status: &str, extra: &str, body: &str| {
+        format!(
+            "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n{}\r\n{}",
+            status,
+            body.len(),
+            extra,
+            body
+        )
+    };
+    let response = match target {
+        "/hello" => plain(
+            "200 OK",
+            "X-Multi: one\r\nX-Multi: two\r\n",
+            "Hello, world!\n",
+        ),
+        "/chunked" => "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+                       7\r\nchunked\r\n1;note=ext\r\n \r\nb\r\ntransfer!\r\n\r\n\
+                       0\r\nX-Trailer: done\r\n\r\n"
+            .to_string(),
+        "/close" => "HTTP/1.1 200 OK\r\n\r\nuntil the connection closes".to_string(),
+        "/redirect/0" => plain("302 Found", "Location: /hello\r\n", ""),
+        "/absolute" => {
+            let address = stream.local_addr()?;
+            let location = format!("Location: http://{}/hello\r\n", address);
+            plain("301 Moved Permanently", &location, "")
+        }
+        "/loop" => plain("302 Found", "Location: /loop\r\n", ""),
+        "/see-other" => plain("303 See Other", "Location: /method\r\n", ""),
+        "/temporary" => plain("307 Temporary Redirect", "Location: /echo\r\n", ""),
+        "/method" => plain("200 OK", "", method),
+        "/echo" => {
+            let echoed = format!("{} {}", method, String::from_utf8_lossy(&body));
+            plain("200 OK", "", &echoed)
+        }
+        "/headers" => {
+            let agent = headers.get("user-agent").unwrap_or("").to_string();
+            plain(
+                "200 OK",
+                "",
+                &format!("{}|{}", headers.get("host").unwrap_or(""), agent),
+            )
+        }
+        "/continue" => format!(
+            "HTTP/1.1 100 Continue\r\n\r\n{}",
+            plain("200 OK", "", "after")
+        ),
+        "/broken" => "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\n".to_string(),
+        "/slow" => {
+            thread::sleep(Duration::from_millis(300));
+            plain("200 OK", "", "too late")
+        }
+        _ => match target
+            .strip_prefix("/redirect/")
+            .and_then(|n| n.parse::<u32>().ok())
+        {
+            Some(n) => plain(
+                "302 Found",
+                &format!("Location: /redirect/{}\r\n", n - 1),
+                "",
+            ),
+            None => plain("404 Not Found", "", "no such page"),
+        },
+    };
+    let mut stream = stream;
+    stream.write_all(response.as_bytes())?;
+    Ok(())
+}
+
+pub fn demo(harness: &mut Harness) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind a local port");
+    let address = listener.local_addr().unwrap();
+    let stop = Arc::new(AtomicBool::new(false));
+    let server = {
+        let stop = Arc::clone(&stop);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    let _ = answer(stream);
+                }
+            }
+        })
+    };
+    let base = format!("http://{}", address);
+    let client = Client::new().timeout(Duration::from_secs(5));
+    let fetch = |path: &str| client.get(&format!("{}{}", base, path));
+
+    let request = Request::post("http://example.com:8080/form?x=1", "text/plain", "body")
+        .unwrap()
+        .header("X-Custom", "yes");
+    test!(
+        harness,
+        "a request is serialized with its headers and body",
+        String::from_utf8(request.serialize()).unwrap(),
+        "POST /form?x=1 HTTP/1.1\r\nHost: example.com:8080\r\n\
+         User-Agent: snippets-httpclient/1.0\r\nAccept: */*\r\nConnection: close\r\n\
+         Content-Type: text/plain\r\nX-Custom: yes\r\nContent-Length: 4\r\n\r\nbody"
+    );
+    let mut chunks = &b"4\r\nWiki\r\n5\r\npedia\r\nE\r\n in\r\n\r\nchunks.\r\n0\r\n\r\n"[..];
+    test!(
+        harness,
+        "a chunked body is decoded",
+        decode_chunked(&mut chunks, &mut Headers::new())
+            .map(|body| String::from_utf8(body).unwrap()),
+        Ok(String::from("Wikipedia in\r\n\r\nchunks."))
+    );
+    let sizes = ["ffffffffffff", "ffffffffffffffff", "1000001"];
+    test!(
+        harness,
+        "chunks longer than the limit are errors, not allocations",
+        sizes.map(|size| {
+            let chunks = format!("{}\r\nxy\r\n0\r\n\r\n", size);
+            let mut chunks = chunks.as_bytes();
+            decode_chunked(&mut chunks, &mut Headers::new())
+                .map(|body| body.len())
+                .map_err(|error| error.kind)
+        }),
+        [Err(ErrorKind::MalformedChunk); 3]
+    );
+    let mut huge = &b"HTTP/1.1 200 OK\r\nContent-Length: 281474976710655\r\n\r\nxy"[..];
+    test!(
+        harness,
+        "a Content-Length longer than the limit is an error",
+        read_response(&mut huge, Url::parse("http://host/").unwrap())
+            .map(|response| response.body.len())
+            .map_err(|error| error.kind),
+        Err(ErrorKind::MalformedHeader)
+    );
+    let base_url = Url::parse("http://host/a/b/page?q").unwrap();
+    test!(
+        harness,
+        "redirect locations are resolved against the current URL",
+        ["other", "/root", "//elsewhere/x", "https://secure/",].map(|location| resolve(
+            &base_url, location
+        )
+        .unwrap()
+        .to_string()),
+        [
+            "http://host/a/b/other",
+            "http://host/root",
+            "http://elsewhere/x",
+            "https://secure/",
+        ]
+        .map(String::from)
+    );
+
+    let hello = fetch("/hello").unwrap();
+    test!(
+        harness,
+        "a page is fetched, with its status and body",
+        (hello.status, hello.reason.as_str(), hello.text()),
+        (200, "OK", String::from("Hello, world!\n"))
+    );
+    test!(
+        harness,
+        "header names are case-insensitive, and repeated ones are kept",
+        (
+            hello.headers.get("content-type"),
+            hello.headers.get_all("x-multi").collect::<Vec<_>>()
+        ),
+        (Some("text/plain"), vec!["one", "two"])
+    );
+    let chunked = fetch("/chunked").unwrap();
+    test!(
+        harness,
+        "a chunked response is decoded, with its trailer",
+        (chunked.text(), chunked.headers.get("X-Trailer")),
+        (String::from("chunked transfer!\r\n"), Some("done"))
+    );
+    test!(
+        harness,
+        "a body without a length ends with the connection",
+        fetch("/close").unwrap().text(),
+        "until the connection closes"
+    );
+    test!(
+        harness,
+        "informational responses are skipped",
+        fetch("/continue").map(|response| response.text()),
+        Ok(String::from("after"))
+    );
+    test!(
+        harness,
+        "the Host and User-Agent headers are sent",
+        fetch("/headers").unwrap().text(),
+        format!("{}|snippets-httpclient/1.0", address)
+    );
+    test!(
+        harness,
+        "a missing page is a response, not an error",
+        fetch("/missing").map(|response| response.status),
+        Ok(404)
+    );
+
+    let redirected = fetch("/redirect/3").unwrap();
+    test!(
+        harness,
+        "a chain of redirects is followed",
+        (redirected.text(), redirected.redirects, redirected.url.path),
+        (String::from("Hello, world!\n"), 4, String::from("/hello"))
+    );
+    test!(
+        harness,
+        "an absolute redirect is followed",
+        fetch("/absolute").map(|response| response.text()),
+        Ok(String::from("Hello, world!\n"))
+    );
+    test!(
+        harness,
+        "a redirect loop is cut off at the limit",
+        fetch("/loop").map_err(|error| error.kind),
+        Err(ErrorKind::TooManyRedirects)
+    );
+    test!(
+        harness,
+        "the limit can be lowered",
+        client
+            .clone()
+            .max_redirects(2)
+            .get(&format!("{}/redirect/2", base))
+            .map_err(|error| error.kind),
+        Err(ErrorKind::TooManyRedirects)
+    );
+    test!(
+        harness,
+        "a POST sends its body",
+        client
+            .post(&format!("{}/echo", base), "text/plain", "ping")
+            .map(|response| response.text()),
+        Ok(String::from("POST ping"))
+    );
+    test!(
+        harness,
+        "a 303 after a POST turns it into a GET",
+        client
+            .post(&format!("{}/see-other", base), "text/plain", "ping")
+            .map(|response| response.text()),
+        Ok(String::from("GET"))
+    );
+    test!(
+        harness,
+        "a 307 repeats the POST with its body",
+        client
+            .post(&format!("{}/temporary", base), "text/plain", "again")
+            .map(|response| response.text()),
+        Ok(String::from("POST again"))
+    );
+
+    test!(
+        harness,
+        "a slow server times out",
+        Client::new()
+            .timeout(Duration::from_millis(50))
+            .get(&format!("{}/slow", base))
+            .map_err(|error| error.kind),
+        Err(ErrorKind::Timeout)
+    );
+    test!(
+        harness,
+        "a malformed chunk size is an error",
+        fetch("/broken").map_err(|error| error.kind),
+        Err(ErrorKind::MalformedChunk)
+    );
+    test!(
+        harness,
+        "HTTPS is not supported over plain TCP",
+        client
+            .get("https://example.com/")
+            .map_err(|error| error.kind),
+        Err(ErrorKind::UnsupportedScheme)
+    );
+    test!(
+        harness,
+        "an invalid URL is an error",
+        client.get("not a url").map_err(|error| error.kind),
+        Err(ErrorKind::InvalidUrl)
+    );
+
+    note!(
+        harness,
+        "GET {}/redirect/1:\n{}\n",
+        base,
+        describe(&fetch("/redirect/1").unwrap(), 64)
+    );
+
+    stop.store(true, Ordering::SeqCst);
+    let _ = TcpStream::connect(address);
+    server.join().unwrap();
+}
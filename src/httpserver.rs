@@ -0,0 +1,680 @@
+// httpserver.rs
+// A minimal HTTP/1.1 server, which serves the static files of a directory. It
+// reads the request line, the method, the target, and the version, then the
+// headers, with the parser of the httpclient snippet, and maps the path of the
+// target to a file under the root directory, after decoding its escapes, with
+// the url snippet. A path which would climb out of the root is not found, and
+// so is one which does not exist, while any other failure to read the file is
+// an internal error, and so is a panic of the handler. The Content-Type comes
+// from a table of the file extensions. A connection is kept alive after the
+// response, for the next request of the client, as HTTP/1.1 does by default,
+// unless either side asks to close it, or it stays idle for too long. Each
+// connection runs on a thread of its own, and the accepting loop polls, like
+// the one of the tcpecho snippet, so the server notices when it is shut down.
+// The binary serves the directory it is given, and without one, it runs the
+// demonstration, against a temporary directory.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::httpclient::{read_headers, read_line, ErrorKind, Headers, HttpError};
+use crate::tcpecho::Log;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+const IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REQUESTS: usize = 100;
+const MAX_BODY: usize = 1 << 20;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub target: String,
+    pub version: String,
+    pub headers: Headers,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    // HTTP/1.1 keeps the connection by default, and HTTP/1.0 only if asked.
+    pub fn keep_alive(&self) -> bool {
+        let connection = self.headers.get("Connection").unwrap_or("");
+        if self.version == "HTTP/1.0" {
+            connection.eq_ignore_ascii_case("keep-alive")
+        } else {
+            !connection.eq_ignore_ascii_case("close")
+        }
+    }
+}
+
+// Parses a request from the reader, where the end of the input before it is
+// not an error, but the end of the connection.
+pub fn read_request(reader: &mut impl BufRead) -> Result<Option<Request>, HttpError> {
+    if reader.fill_buf()?.is_empty() {
+        return Ok(None);
+    }
+    let line = read_line(reader, ErrorKind::MalformedStatusLine)?;
+    let parts: Vec<&str> = line.split(' ').collect();
+    let [method, target, version] = parts[..] else {
+        return Err(HttpError {
+            kind: ErrorKind::MalformedStatusLine,
+            detail: line,
+        });
+    };
+    let valid = !method.is_empty()
+        && method.bytes().all(|byte| byte.is_ascii_uppercase())
+        && target.starts_with('/')
+        && (version == "HTTP/1.0" || version == "HTTP/1.1");
+    if !valid {
+        return Err(HttpError {
+            kind: ErrorKind::MalformedStatusLine,
+            detail: line,
+        });
+    }
+    let mut headers = Headers::new();
+    read_headers(reader, &mut headers)?;
+    let length = match headers.get("Content-Length") {
+        None => 0,
+        Some(length) => match length.parse::<usize>() {
+            Ok(length) if length <= MAX_BODY => length,
+            _ => {
+                return Err(HttpError {
+                    kind: ErrorKind::MalformedHeader,
+                    detail: format!("Content-Length: {}", length),
+                })
+            }
+        },
+    };
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(Request {
+        method: method.to_string(),
+        target: target.to_string(),
+        version: version.to_string(),
+        headers,
+        body,
+    }))
+}
+
+pub fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        301 => "Moved Permanently",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Content Too Large",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        _ => "Unknown",
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Headers,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn new(status: u16, content_type: &str, body: impl Into<Vec<u8>>) -> Self {
+        let mut headers = Headers::new();
+        headers.set("Content-Type", content_type);
+        Self {
+            status,
+            headers,
+            body: body.into(),
+        }
+    }
+
+    // A small page which explains the status.
+    pub fn error(status: u16) -> Self {
+        let page = format!(
+            "<!DOCTYPE html>\n<html><head><title>{0} {1}</title></head>\
+             <body><h1>{0} {1}</h1></body></html>\n",
+            status,
+            reason(status)
+        );
+        Self::new(status, "text/html; charset=utf-8", page)
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.set(name, value);
+        self
+    }
+
+    // The response to a HEAD has the headers of the body, but not the body.
+    pub fn write_to(&self, writer: &mut impl Write, head_only: bool) -> io::Result<()> {
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, reason(self.status));
+        for (name, value) in self.headers.iter() {
+            head += &format!("{}: {}\r\n", name, value);
+        }
+        head += &format!("Content-Length: {}\r\n\r\n", self.body.len());
+        writer.write_all(head.as_bytes())?;
+        if !head_only {
+            writer.write_all(&self.body)?;
+        }
+        writer.flush()
+    }
+}
+
+const CONTENT_TYPES: [(&str, &str); 16] = [
+    ("html", "text/html; charset=utf-8"),
+    ("htm", "text/html; charset=utf-8"),
+    ("css", "text/css; charset=utf-8"),
+    ("js", "text/javascript; charset=utf-8"),
+    ("json", "application/json"),
+    ("txt", "text/plain; charset=utf-8"),
+    ("xml", "application/xml"),
+    ("svg", "image/svg+xml"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("ico", "image/x-icon"),
+    ("wasm", "application/wasm"),
+    ("pdf", "application/pdf"),
+    ("zip", "application/zip"),
+];
+
+// The type of the file from its extension, regardless of its case, and bytes
+// of an unknown kind otherwise.
+pub fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("");
+    CONTENT_TYPES
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(extension))
+        .map_or("application/octet-stream", |(_, content_type)| content_type)
+}
+
+// The file under the root which the target names, if it stays under the root.
+// The query is ignored, and a directory stands for its index page.
+pub fn resolve(root: &Path, target: &str) -> Option<PathBuf> {
+    let path = target.split(['?', '#']).next().unwrap_or("");
+    let path = crate::url::decode(path).ok()?;
+    let mut resolved = root.to_path_buf();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => return None,
+            // A decoded segment may hide a separator, or a drive on Windows.
+            _ if segment.contains(['\\', ':', '\0']) => return None,
+            _ => resolved.push(segment),
+        }
+    }
+    if resolved.is_dir() {
+        resolved.push("index.html");
+    }
+    Some(resolved)
+}
+
+// Serves the file, or the error in its place.
+pub fn respond(root: &Path, request: &Request) -> Response {
+    if request.method != "GET" && request.method != "HEAD" {
+        return Response::error(405).header("Allow", "GET, HEAD");
+    }
+    let Some(path) = resolve(root, &request.target) else {
+        return Response::error(404);
+    };
+    match fs::read(&path) {
+        Ok(contents) => Response::new(200, content_type(&path), contents),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Response::error(404),
+        Err(_) => Response::error(500),
+    }
+}
+
+pub type Handler = Arc<dyn Fn(&Request) -> Response + Send + Sync>;
+
+pub struct HttpServer {
+    listener: TcpListener,
+    shutdown: Arc<AtomicBool>,
+}
+
+fn is_timeout(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+// Waits for the next request to start arriving, and gives up when the
+// connection is idle for too long, or the server shuts down.
+fn wait_for_request(reader: &mut BufReader<TcpStream>, shutdown: &AtomicBool) -> io::Result<bool> {
+    let idle = Instant::now();
+    reader.get_ref().set_read_timeout(Some(POLL_INTERVAL))?;
+    loop {
+        match reader.fill_buf() {
+            Ok(buffer) => return Ok(!buffer.is_empty()),
+            Err(error) if is_timeout(&error) => {
+                if shutdown.load(Ordering::Acquire) || idle.elapsed() > IDLE_TIMEOUT {
+                    return Ok(false);
+                }
+            }
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+// Answers the requests of the connection until it is closed, and returns the
+// number of requests.
+fn handle(
+    stream: TcpStream,
+    handler: &Handler,
+    shutdown: &AtomicBool,
+    log: &Log,
+) -> io::Result<usize> {
+    stream.set_nonblocking(false)?;
+    let peer = stream.peer_addr()?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut served = 0;
+    while served < MAX_REQUESTS && wait_for_request(&mut reader, shutdown)? {
+        // Once it started, the rest of the request should not take long.
+        reader.get_ref().set_read_timeout(Some(IDLE_TIMEOUT))?;
+        let request = match read_request(&mut reader) {
+            Ok(Some(request)) => request,
+            Ok(None) => break,
+            // The connection is out of step after a malformed request.
+            Err(error) if error.kind == ErrorKind::Timeout || error.kind == ErrorKind::Io => break,
+            Err(error) => {
+                log(format!("{} sent a bad request: {}", peer, error));
+                let response = Response::error(400).header("Connection", "close");
+                response.write_to(&mut writer, false)?;
+                break;
+            }
+        };
+        served += 1;
+        let response = panic::catch_unwind(AssertUnwindSafe(|| handler(&request)))
+            .unwrap_or_else(|_| Response::error(500));
+        let keep_alive = request.keep_alive() && served < MAX_REQUESTS;
+        let connection = if keep_alive { "keep-alive" } else { "close" };
+        let response = response.header("Connection", connection);
+        log(format!(
+            "{} \"{} {} {}\" {} {}",
+            peer,
+            request.method,
+            request.target,
+            request.version,
+            response.status,
+            response.body.len()
+        ));
+        response.write_to(&mut writer, request.method == "HEAD")?;
+        if !keep_alive {
+            break;
+        }
+    }
+    Ok(served)
+}
+
+impl HttpServer {
+    pub fn bind(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            shutdown: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    pub fn shutdown_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.shutdown)
+    }
+
+    pub fn serve_directory(self, root: impl Into<PathBuf>, log: Log) -> io::Result<usize> {
+        let root = root.into();
+        self.serve(Arc::new(move |request| respond(&root, request)), log)
+    }
+
+    // Serves until it is shut down, then waits for the connections to finish,
+    // and returns the number of requests served.
+    pub fn serve(self, handler: Handler, log: Log) -> io::Result<usize> {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let mut threads = Vec::new();
+        while !self.shutdown.load(Ordering::Acquire) {
+            let stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(error) if is_timeout(&error) => {
+                    thread::sleep(POLL_INTERVAL / 5);
+                    continue;
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            let (handler, shutdown, log) = (handler.clone(), self.shutdown.clone(), log.clone());
+            let requests = requests.clone();
+            threads.push(thread::spawn(move || {
+                match handle(stream, &handler, &shutdown, &log) {
+                    Ok(served) => requests.fetch_add(served, Ordering::Relaxed),
+                    Err(error) => {
+                        log(format!("connection failed: {}", error));
+                        0
+                    }
+                };
+            }));
+            threads.retain(|thread| !thread.is_finished());
+        }
+        for thread in threads {
+            let _ = thread.join();
+        }
+        Ok(requests.load(Ordering::Relaxed))
+    }
+}
+
+fn serve_until_interrupted(address: &str, root: &str) -> io::Result<usize> {
+    if !Path::new(root).is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} is not a directory", root),
+        ));
+    }
+    let server = HttpServer::bind(address)?;
+    eprintln!(
+        "Serving {} on http://{}, press Ctrl-C to stop",
+        root,
+        server.local_addr()?
+    );
+    crate::tcpecho::install_interrupt_handler();
+    let shutdown = server.shutdown_flag();
+    thread::spawn(move || {
+        while !crate::tcpecho::interrupted() {
+            thread::sleep(POLL_INTERVAL);
+        }
+        shutdown.store(true, Ordering::Release);
+    });
+    server.serve_directory(root, Arc::new(|line| eprintln!("{}", line)))
+}
+
+// The entry point of the binary.
+pub fn main(mut arguments: impl Iterator<Item = String>) -> i32 {
+    use crate::args::Parser;
+    let program = crate::harness::program_name(arguments.next());
+    let parser = Parser::new(&program, "A static file server over HTTP/1.1.")
+        .optional(
+            "DIRECTORY",
+            "Serve the directory, instead of running the demonstration",
+        )
+        .option(
+            Some('a'),
+            "address",
+            "ADDRESS",
+            "Listen on the address (default: 127.0.0.1:8080)",
+        )
+        .flag(
+            Some('q'),
+            "quiet",
+            "Only report the failed checks of the demonstration",
+        );
+    let matches = match parser.parse_or_report(arguments) {
+        Ok(matches) => matches,
+        Err(code) => return code,
+    };
+    let Some(root) = matches.value("DIRECTORY") else {
+        return crate::harness::run_demo(demo, matches.flag("quiet"));
+    };
+    let address = matches.value_or("address", "127.0.0.1:8080");
+    match serve_until_interrupted(address, root) {
+        Ok(requests) => {
+            eprintln!("Served {} requests", requests);
+            0
+        }
+        Err(error) => {
+            eprintln!("{}: {}", program, error);
+            1
+        }
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use crate::httpclient::{read_response, Client};
+use crate::url::Url;
+use std::sync::Mutex;
+
+// Sends the raw requests on one connection, and reads as many responses.
+fn exchange(
+    address: SocketAddr,
+    requests: &str,
+    count: usize,
+) -> Result<Vec<(u16, String)>, HttpError> {
+    let mut stream = TcpStream::connect(address)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    stream.write_all(requests.as_bytes())?;
+    let mut reader = BufReader::new(stream);
+    let url = Url::parse(&format!("http://{}/", address)).unwrap();
+    (0..count)
+        .map(|_| {
+            read_response(&mut reader, url.clone())
+                .map(|response| (response.status, response.text()))
+        })
+        .collect()
+}
+
+pub fn demo(harness: &mut Harness) {
+    let root = std::env::temp_dir().join(format!("snippets-httpserver-{}", std::process::id()));
+    let files: [(&str, &[u8]); 5] = [
+        ("index.html", b"<!DOCTYPE html>\n<h1>Welcome</h1>\n"),
+        ("style.css", b"h1 { color: teal; }\n"),
+        ("data.JSON", b"{\"answer\": 42}"),
+        ("docs/read me.txt", b"spaces are escaped in URLs\n"),
+        (
+            "pixel.png",
+            &[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a],
+        ),
+    ];
+    for (name, contents) in files {
+        let path = root.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    // The index of this directory is a directory itself, so reading it fails,
+    // but not because it is missing.
+    fs::create_dir_all(root.join("broken/index.html")).unwrap();
+
+    test!(
+        harness,
+        "the content type comes from the extension",
+        ["a.html", "b.CSS", "c.png", "d.tar.gz", "Makefile"]
+            .map(|name| content_type(Path::new(name))),
+        [
+            "text/html; charset=utf-8",
+            "text/css; charset=utf-8",
+            "image/png",
+            "application/octet-stream",
+            "application/octet-stream"
+        ]
+    );
+    test!(
+        harness,
+        "targets are decoded, and can not climb out of the root",
+        [
+            "/docs/read%20me.txt?x=1",
+            "/docs/../../secret",
+            "/%2e%2e/secret",
+            "/docs/..%5C..%5Csecret",
+        ]
+        .map(|target| resolve(&root, target)),
+        [
+            Some(root.join("docs").join("read me.txt")),
+            None,
+            None,
+            None
+        ]
+    );
+
+    let server = HttpServer::bind("127.0.0.1:0").unwrap();
+    let address = server.local_addr().unwrap();
+    let stop = server.shutdown_flag();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let serving = {
+        let (root, log) = (root.clone(), log.clone());
+        thread::spawn(move || {
+            // Panics on one path, to show that the connection survives it.
+            let handler: Handler = Arc::new(move |request| {
+                if request.target == "/panic" {
+                    panic!("handler failed");
+                }
+                respond(&root, request)
+            });
+            server.serve(
+                handler,
+                Arc::new(move |line| log.lock().unwrap().push(line)),
+            )
+        })
+    };
+    let client = Client::new().timeout(Duration::from_secs(5));
+    let fetch = |path: &str| client.get(&format!("http://{}{}", address, path)).unwrap();
+
+    let index = fetch("/");
+    test!(
+        harness,
+        "the root serves the index page",
+        (
+            index.status,
+            index.headers.get("Content-Type"),
+            index.text().contains("Welcome")
+        ),
+        (200, Some("text/html; charset=utf-8"), true)
+    );
+    let data = fetch("/data.JSON");
+    test!(
+        harness,
+        "files are served with their type",
+        (data.headers.get("content-type"), data.text()),
+        (Some("application/json"), String::from("{\"answer\": 42}"))
+    );
+    test!(
+        harness,
+        "binary files are served as they are",
+        fetch("/pixel.png").body,
+        files[4].1
+    );
+    test!(
+        harness,
+        "escaped paths are decoded",
+        fetch("/docs/read%20me.txt").text(),
+        "spaces are escaped in URLs\n"
+    );
+    let missing = fetch("/missing.html");
+    test!(
+        harness,
+        "a missing file is not found",
+        (missing.status, missing.text().contains("404 Not Found")),
+        (404, true)
+    );
+    test!(
+        harness,
+        "a path out of the root is not found",
+        fetch("/docs/%2e%2e/%2e%2e/etc/passwd").status,
+        404
+    );
+    test!(
+        harness,
+        "a file which can not be read is an internal error",
+        fetch("/broken/").status,
+        500
+    );
+    let posted = client
+        .post(&format!("http://{}/index.html", address), "text/plain", "x")
+        .unwrap();
+    test!(
+        harness,
+        "only GET and HEAD are allowed",
+        (posted.status, posted.headers.get("Allow")),
+        (405, Some("GET, HEAD"))
+    );
+
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let responses = exchange(
+        address,
+        "GET /panic HTTP/1.1\r\nHost: x\r\n\r\n\
+         GET /style.css HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n",
+        2,
+    );
+    panic::set_hook(hook);
+    test!(
+        harness,
+        "a panicking handler is an internal error, on a connection kept alive",
+        responses.map(|responses| responses
+            .into_iter()
+            .map(|(status, _)| status)
+            .collect::<Vec<_>>()),
+        Ok(vec![500, 200])
+    );
+    let pipelined = exchange(
+        address,
+        "GET /style.css HTTP/1.1\r\nHost: x\r\n\r\n\
+         GET /data.JSON HTTP/1.0\r\n\r\n",
+        2,
+    );
+    test!(
+        harness,
+        "pipelined requests are answered in order, on one connection",
+        pipelined,
+        Ok(vec![
+            (200, String::from("h1 { color: teal; }\n")),
+            (200, String::from("{\"answer\": 42}")),
+        ])
+    );
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream
+        .write_all(b"HEAD /data.JSON HTTP/1.1\r\nConnection: close\r\n\r\n")
+        .unwrap();
+    let mut head = String::new();
+    stream.read_to_string(&mut head).unwrap();
+    test!(
+        harness,
+        "the response to a HEAD has the length of the body, without the body",
+        (
+            head.contains("Content-Length: 14\r\n"),
+            head.ends_with("\r\n\r\n")
+        ),
+        (true, true)
+    );
+    test!(
+        harness,
+        "a malformed request is a bad request",
+        exchange(address, "GARBAGE\r\n\r\n", 1),
+        Ok(vec![(
+            400,
+            String::from_utf8(Response::error(400).body).unwrap()
+        )])
+    );
+
+    stop.store(true, Ordering::Release);
+    let served = serving.join().unwrap().unwrap();
+    test!(
+        harness,
+        "the server shuts down, and counts the requests",
+        served,
+        13
+    );
+    let log = log.lock().unwrap();
+    note!(harness, "The log of the server:");
+    for line in log.iter() {
+        note!(harness, "    {}", line);
+    }
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,466 @@
+// huffman.rs
+// Canonical Huffman coding of bytes. The Huffman tree is built by repeatedly
+// merging the two least frequent subtrees, taken from a binary min-heap,
+// and the depth of each leaf gives the length of the code of its symbol. The
+// codes themselves are then assigned in canonical order (by length, and then by
+// symbol), so the code table can be rebuilt from the code lengths alone, which
+// is how formats like DEFLATE store it, and which also makes decoding simple.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::heap::DaryHeap;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum HuffmanError {
+    UnknownSymbol(u8),
+    InvalidCode { bit: usize },
+    Truncated,
+    InvalidLengths,
+}
+
+impl fmt::Display for HuffmanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HuffmanError::UnknownSymbol(symbol) => {
+                write!(f, "the symbol {:#04x} has no code", symbol)
+            }
+            HuffmanError::InvalidCode { bit } => write!(f, "invalid code at bit {}", bit),
+            HuffmanError::Truncated => write!(f, "the encoded data is truncated"),
+            HuffmanError::InvalidLengths => write!(f, "the code lengths are not a prefix code"),
+        }
+    }
+}
+
+impl std::error::Error for HuffmanError {}
+
+pub fn frequencies(data: &[u8]) -> [usize; 256] {
+    let mut result = [0; 256];
+    for byte in data {
+        result[*byte as usize] += 1;
+    }
+    result
+}
+
+// The nodes of the tree are stored in a vector, and refer to their children
+// by index, which keeps the heap entries small and comparable.
+enum Node {
+    Leaf(u8),
+    Internal(usize, usize),
+}
+
+// The weights are summed in 128 bits, where even the sum of all of the 256
+// largest frequencies fits.
+pub fn code_lengths(frequencies: &[usize; 256]) -> [u8; 256] {
+    let mut nodes = Vec::new();
+    let mut heap = DaryHeap::new(2);
+    for (symbol, frequency) in frequencies.iter().enumerate() {
+        if *frequency > 0 {
+            // The index breaks ties between equal weights, which makes the
+            // tree deterministic.
+            heap.push((*frequency as u128, nodes.len()));
+            nodes.push(Node::Leaf(symbol as u8));
+        }
+    }
+
+    let mut lengths = [0; 256];
+    if heap.len() == 1 {
+        // A single symbol still needs a code of at least one bit.
+        if let Node::Leaf(symbol) = nodes[0] {
+            lengths[symbol as usize] = 1;
+        }
+        return lengths;
+    }
+
+    while heap.len() > 1 {
+        let (first_weight, first) = heap.pop().unwrap();
+        let (second_weight, second) = heap.pop().unwrap();
+        heap.push((first_weight + second_weight, nodes.len()));
+        nodes.push(Node::Internal(first, second));
+    }
+
+    if let Some((_, root)) = heap.pop() {
+        let mut stack = vec![(root, 0u8)];
+        while let Some((index, depth)) = stack.pop() {
+            match nodes[index] {
+                Node::Leaf(symbol) => lengths[symbol as usize] = depth,
+                Node::Internal(left, right) => {
+                    stack.push((left, depth + 1));
+                    stack.push((right, depth + 1));
+                }
+            }
+        }
+    }
+    lengths
+}
+
+// The code lengths, limited to the 64 bits the codes are stored in. Skewed
+// frequencies, like the Fibonacci numbers, make a tree as deep as the number
+// of symbols, so while it is too deep, the frequencies are halved, keeping
+// every symbol at least once, which makes them more even, and the tree
+// flatter, at the cost of slightly longer encodings.
+pub fn limited_code_lengths(frequencies: &[usize; 256]) -> [u8; 256] {
+    let mut frequencies = *frequencies;
+    loop {
+        let lengths = code_lengths(&frequencies);
+        if lengths.iter().all(|length| *length <= 64) {
+            return lengths;
+        }
+        for frequency in frequencies.iter_mut().filter(|frequency| **frequency > 0) {
+            *frequency = frequency.div_ceil(2);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Code {
+    pub symbol: u8,
+    pub length: u8,
+    pub bits: u64,
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:0width$b}", self.bits, width = self.length as usize)
+    }
+}
+
+pub struct Huffman {
+    // The codes in canonical order, and indexed by symbol.
+    codes: Vec<Code>,
+    table: [Option<Code>; 256],
+    counts: Vec<usize>,
+}
+
+impl Huffman {
+    // The codes are stored in 64-bit integers, so they can not be longer than
+    // that, which the plain Huffman tree does not guarantee, with about 2^45
+    // bytes of the right input. The lengths may also come from the encoded
+    // data, so they are checked: a code of length l
+    // takes up 2^-l of the space of the codes, and if they take up more than
+    // all of it (the Kraft inequality), some of them must be prefixes of the
+    // others.
+    pub fn from_lengths(lengths: &[u8; 256]) -> Result<Self, HuffmanError> {
+        if lengths.iter().any(|length| *length > 64) {
+            return Err(HuffmanError::InvalidLengths);
+        }
+        let space: u128 = lengths
+            .iter()
+            .filter(|length| **length > 0)
+            .map(|length| 1 << (64 - length))
+            .sum();
+        if space > 1 << 64 {
+            return Err(HuffmanError::InvalidLengths);
+        }
+        let mut codes: Vec<Code> = (0..=255u8)
+            .filter(|symbol| lengths[*symbol as usize] > 0)
+            .map(|symbol| Code {
+                symbol,
+                length: lengths[symbol as usize],
+                bits: 0,
+            })
+            .collect();
+        codes.sort_by_key(|code| (code.length, code.symbol));
+
+        // Each code is the previous one plus one, shifted left whenever the
+        // length increases. Only the one after the last code can wrap around,
+        // if the codes take up all of the space, and that is never used.
+        let mut bits: u64 = 0;
+        let mut previous_length = codes.first().map_or(0, |code| code.length);
+        for code in codes.iter_mut() {
+            bits <<= code.length - previous_length;
+            code.bits = bits;
+            bits = bits.wrapping_add(1);
+            previous_length = code.length;
+        }
+
+        let mut table = [None; 256];
+        let mut counts = vec![0; previous_length as usize + 1];
+        for code in &codes {
+            table[code.symbol as usize] = Some(*code);
+            counts[code.length as usize] += 1;
+        }
+        Ok(Self {
+            codes,
+            table,
+            counts,
+        })
+    }
+
+    pub fn from_data(data: &[u8]) -> Self {
+        Self::from_lengths(&limited_code_lengths(&frequencies(data)))
+            .expect("the leaves of a Huffman tree form a prefix code")
+    }
+
+    pub fn codes(&self) -> &[Code] {
+        &self.codes
+    }
+
+    pub fn code(&self, symbol: u8) -> Option<Code> {
+        self.table[symbol as usize]
+    }
+
+    // The bits are packed starting from the most significant bit of each
+    // byte, and the last byte is padded with zeros.
+    pub fn encode(&self, data: &[u8]) -> Result<Vec<u8>, HuffmanError> {
+        let mut result = Vec::new();
+        let mut used = 0;
+        for byte in data {
+            let code = self.code(*byte).ok_or(HuffmanError::UnknownSymbol(*byte))?;
+            for i in (0..code.length).rev() {
+                if used % 8 == 0 {
+                    result.push(0);
+                }
+                if (code.bits >> i) & 1 == 1 {
+                    *result.last_mut().unwrap() |= 0x80 >> (used % 8);
+                }
+                used += 1;
+            }
+        }
+        Ok(result)
+    }
+
+    // As the padding could be mistaken for codes, the number of symbols to
+    // decode must be known. Canonical codes of the same length are
+    // consecutive numbers, so the code read so far is looked up by checking
+    // whether it falls into the range of codes of its current length. Each
+    // symbol takes at least a bit, which limits the memory reserved up front.
+    pub fn decode(&self, data: &[u8], symbols: usize) -> Result<Vec<u8>, HuffmanError> {
+        let mut result = Vec::with_capacity(symbols.min(data.len().saturating_mul(8)));
+        let mut bit = 0;
+        while result.len() < symbols {
+            let start = bit;
+            let mut code = 0;
+            let mut first = 0;
+            let mut index = 0;
+            let mut length = 1;
+            loop {
+                if length >= self.counts.len() {
+                    return Err(HuffmanError::InvalidCode { bit: start });
+                }
+                let byte = data.get(bit / 8).ok_or(HuffmanError::Truncated)?;
+                code |= ((byte >> (7 - bit % 8)) & 1) as u64;
+                bit += 1;
+
+                let count = self.counts[length] as u64;
+                if code < first + count {
+                    result.push(self.codes[index + (code - first) as usize].symbol);
+                    break;
+                }
+                index += count as usize;
+                first = (first + count) << 1;
+                code <<= 1;
+                length += 1;
+            }
+        }
+        Ok(result)
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    let mut heap = DaryHeap::new(2);
+    for value in [5, 3, 8, 1, 9, 2, 7] {
+        heap.push(value);
+    }
+    let popped: Vec<i32> = std::iter::from_fn(|| heap.pop()).collect();
+    test!(
+        harness,
+        "the heap pops its elements in ascending order",
+        popped,
+        vec![1, 2, 3, 5, 7, 8, 9]
+    );
+
+    // The classic example: a 45, b 13, c 12, d 16, e 9, f 5.
+    let mut counts = [0; 256];
+    for (symbol, frequency) in [
+        (b'a', 45),
+        (b'b', 13),
+        (b'c', 12),
+        (b'd', 16),
+        (b'e', 9),
+        (b'f', 5),
+    ] {
+        counts[symbol as usize] = frequency;
+    }
+    let lengths = code_lengths(&counts);
+    test!(
+        harness,
+        "the code lengths of the textbook example are optimal",
+        b"abcdef"
+            .iter()
+            .map(|c| lengths[*c as usize])
+            .collect::<Vec<_>>(),
+        vec![1, 3, 3, 3, 4, 4]
+    );
+    let huffman = Huffman::from_lengths(&lengths).unwrap();
+    let codes: Vec<String> = huffman
+        .codes()
+        .iter()
+        .map(|code| code.to_string())
+        .collect();
+    test!(
+        harness,
+        "the canonical codes are assigned in order",
+        codes,
+        vec!["0", "100", "101", "110", "1110", "1111"]
+    );
+
+    test!(
+        harness,
+        "a single symbol gets a one bit code",
+        Huffman::from_data(b"zzzz").code(b'z'),
+        Some(Code {
+            symbol: b'z',
+            length: 1,
+            bits: 0
+        })
+    );
+    let single = Huffman::from_data(b"zzzz");
+    test!(
+        harness,
+        "a single symbol round-trips",
+        single.decode(&single.encode(b"zzzzz").unwrap(), 5),
+        Ok(b"zzzzz".to_vec())
+    );
+    test!(
+        harness,
+        "encoding a symbol without a code fails",
+        huffman.encode(b"abz"),
+        Err(HuffmanError::UnknownSymbol(b'z'))
+    );
+    test!(
+        harness,
+        "decoding more symbols than encoded fails",
+        huffman.decode(&[0b0100_1010], 5),
+        Err(HuffmanError::Truncated)
+    );
+
+    let paragraph = "In computer science and information theory, a Huffman code is a \
+                     particular type of optimal prefix code that is commonly used for \
+                     lossless data compression. The process of finding or using such a \
+                     code is Huffman coding, an algorithm developed by David A. Huffman \
+                     while he was a Sc.D. student at MIT, and published in the 1952 \
+                     paper \"A Method for the Construction of Minimum-Redundancy Codes\".";
+    let data = paragraph.as_bytes();
+    let frequencies = frequencies(data);
+    let huffman = Huffman::from_data(data);
+
+    note!(harness, "Symbol  Count  Length  Code");
+    for code in huffman.codes() {
+        note!(
+            harness,
+            "{:>6}  {:>5}  {:>6}  {}",
+            format!("{:?}", code.symbol as char),
+            frequencies[code.symbol as usize],
+            code.length,
+            code
+        );
+    }
+
+    let encoded = huffman.encode(data).unwrap();
+    let bits: usize = data
+        .iter()
+        .map(|c| huffman.code(*c).unwrap().length as usize)
+        .sum();
+    note!(
+        harness,
+        "\n{} bytes encoded in {} bits ({} bytes, {:.2} bits per symbol)\n",
+        data.len(),
+        bits,
+        encoded.len(),
+        bits as f64 / data.len() as f64
+    );
+
+    test!(
+        harness,
+        "the encoded paragraph is packed into whole bytes",
+        encoded.len(),
+        bits.div_ceil(8)
+    );
+    test!(
+        harness,
+        "the paragraph round-trips",
+        huffman.decode(&encoded, data.len()),
+        Ok(data.to_vec())
+    );
+    let mut lengths = [0; 256];
+    for code in huffman.codes() {
+        lengths[code.symbol as usize] = code.length;
+    }
+    test!(
+        harness,
+        "the code table rebuilt from the code lengths alone decodes the paragraph",
+        Huffman::from_lengths(&lengths).and_then(|huffman| huffman.decode(&encoded, data.len())),
+        Ok(data.to_vec())
+    );
+    let mut too_long = [0; 256];
+    too_long[0] = 200;
+    let mut oversubscribed = [0; 256];
+    oversubscribed[..3].copy_from_slice(&[1, 1, 1]);
+    let mut deepest = [0; 256];
+    deepest[..64].copy_from_slice(&std::array::from_fn::<u8, 64, _>(|i| i as u8 + 1));
+    deepest[64] = 64;
+    test!(
+        harness,
+        "code lengths, which do not form a prefix code, are rejected",
+        [too_long, oversubscribed, deepest].map(|lengths| {
+            Huffman::from_lengths(&lengths).map(|huffman| huffman.code(64).map(|code| code.bits))
+        }),
+        [
+            Err(HuffmanError::InvalidLengths),
+            Err(HuffmanError::InvalidLengths),
+            Ok(Some(u64::MAX)),
+        ]
+    );
+    let mut fibonacci = [0; 256];
+    let (mut a, mut b) = (1, 1);
+    for frequency in fibonacci.iter_mut().take(70) {
+        *frequency = a;
+        (a, b) = (b, a + b);
+    }
+    let max_length = |lengths: [u8; 256]| lengths.iter().copied().max();
+    test!(
+        harness,
+        "Fibonacci frequencies make too deep a tree, unless it is limited",
+        (
+            max_length(code_lengths(&fibonacci)),
+            max_length(limited_code_lengths(&fibonacci))
+                .is_some_and(|length| (1..=64).contains(&length))
+        ),
+        (Some(69), true)
+    );
+    let mut largest = [0; 256];
+    largest[..3].copy_from_slice(&[usize::MAX; 3]);
+    test!(
+        harness,
+        "the weights of the largest frequencies do not overflow",
+        max_length(code_lengths(&largest)),
+        Some(2)
+    );
+    test!(
+        harness,
+        "a symbol count larger than the data can hold reserves no memory for it",
+        huffman
+            .decode(&[0; 4], usize::MAX)
+            .map(|decoded| decoded.len()),
+        Err(HuffmanError::Truncated)
+    );
+    let weighted: usize = huffman
+        .codes()
+        .iter()
+        .map(|code| code.length as usize * frequencies[code.symbol as usize])
+        .sum();
+    test!(
+        harness,
+        "the paragraph is encoded in fewer than 5 bits per symbol",
+        weighted < 5 * data.len(),
+        true
+    );
+}
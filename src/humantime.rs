@@ -0,0 +1,313 @@
+// humantime.rs
+// Durations for humans. Formatting splits a duration into days, hours, minutes,
+// seconds, and the fractions of the second, and writes the components which
+// are not zero, like 2h 13m 5s, optionally rounded to the largest few of them.
+// Parsing accepts the same, with or without spaces, along with the longer names
+// of the units, like 1 hour 20 minutes, weeks and years of 365 days, and
+// decimal fractions, like 1.5h, which are computed in nanoseconds, without the
+// rounding errors of floating-point numbers. Relative times, like 3 minutes
+// ago, or in 2 days, are written with the largest unit which fits, rounded
+// down, and times within a few seconds of now are just now.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+use std::time::Duration;
+
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+
+// The units of formatting, in nanoseconds.
+const UNITS: [(&str, u128); 7] = [
+    ("d", 86400 * NANOS_PER_SECOND),
+    ("h", 3600 * NANOS_PER_SECOND),
+    ("m", 60 * NANOS_PER_SECOND),
+    ("s", NANOS_PER_SECOND),
+    ("ms", 1_000_000),
+    ("µs", 1000),
+    ("ns", 1),
+];
+
+// The names of the units of parsing, in seconds, or in nanoseconds for the
+// ones below a second.
+#[rustfmt::skip]
+const NAMES: [(&[&str], u128); 9] = [
+    (&["y", "yr", "yrs", "year", "years"], 365 * 86400 * NANOS_PER_SECOND),
+    (&["w", "wk", "wks", "week", "weeks"], 7 * 86400 * NANOS_PER_SECOND),
+    (&["d", "day", "days"], 86400 * NANOS_PER_SECOND),
+    (&["h", "hr", "hrs", "hour", "hours"], 3600 * NANOS_PER_SECOND),
+    (&["m", "min", "mins", "minute", "minutes"], 60 * NANOS_PER_SECOND),
+    (&["s", "sec", "secs", "second", "seconds"], NANOS_PER_SECOND),
+    (&["ms", "msec", "millisecond", "milliseconds"], 1_000_000),
+    (&["us", "µs", "usec", "microsecond", "microseconds"], 1000),
+    (&["ns", "nsec", "nanosecond", "nanoseconds"], 1),
+];
+
+// Only the largest units are written, and the rest is rounded to the nearest
+// value of the smallest unit written.
+pub fn format_rounded(duration: Duration, units: usize) -> String {
+    let mut rest = duration.as_nanos();
+    if rest == 0 {
+        return "0s".to_string();
+    }
+    let first = UNITS.iter().position(|(_, size)| rest >= *size).unwrap();
+    let last = (first + units.max(1) - 1).min(UNITS.len() - 1);
+    let smallest = UNITS[last].1;
+    rest = (rest + smallest / 2) / smallest * smallest;
+    let mut parts = Vec::new();
+    for (name, size) in &UNITS[..=last] {
+        if rest >= *size {
+            parts.push(format!("{}{}", rest / size, name));
+            rest %= size;
+        }
+    }
+    parts.join(" ")
+}
+
+pub fn format(duration: Duration) -> String {
+    format_rounded(duration, UNITS.len())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    Empty,
+    ExpectedNumber,
+    MissingUnit,
+    UnknownUnit,
+    Overflow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.kind {
+            ErrorKind::Empty => "empty duration",
+            ErrorKind::ExpectedNumber => "expected a number",
+            ErrorKind::MissingUnit => "missing unit",
+            ErrorKind::UnknownUnit => "unknown unit",
+            ErrorKind::Overflow => "duration too long",
+        };
+        write!(f, "{} at position {}", message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// The end of the longest prefix of the text from the start which satisfies
+// the predicate.
+fn scan(text: &str, start: usize, predicate: impl Fn(char) -> bool) -> usize {
+    text[start..]
+        .find(|c: char| !predicate(c))
+        .map_or(text.len(), |index| start + index)
+}
+
+pub fn parse(text: &str) -> Result<Duration, ParseError> {
+    let error = |kind, position| ParseError { kind, position };
+    let mut total: u128 = 0;
+    let mut position = scan(text, 0, char::is_whitespace);
+    if position == text.len() {
+        return Err(error(ErrorKind::Empty, 0));
+    }
+    while position < text.len() {
+        let start = position;
+        let integer_end = scan(text, start, |c| c.is_ascii_digit());
+        let mut end = integer_end;
+        if text[end..].starts_with('.') {
+            end = scan(text, end + 1, |c| c.is_ascii_digit());
+        }
+        let number = &text[start..end];
+        if number.is_empty() || number == "." {
+            return Err(error(ErrorKind::ExpectedNumber, start));
+        }
+        let unit_start = scan(text, end, char::is_whitespace);
+        let unit_end = scan(text, unit_start, char::is_alphabetic);
+        if unit_start == unit_end {
+            return Err(error(ErrorKind::MissingUnit, unit_start));
+        }
+        let unit = text[unit_start..unit_end].to_lowercase();
+        let (_, size) = NAMES
+            .iter()
+            .find(|(names, _)| names.contains(&unit.as_str()))
+            .ok_or(error(ErrorKind::UnknownUnit, unit_start))?;
+        // The fraction is exact up to the nanoseconds of the unit.
+        let overflow = error(ErrorKind::Overflow, start);
+        let integer: u128 = text[start..integer_end].parse().unwrap_or(0);
+        let mut value = integer.checked_mul(*size).ok_or(overflow)?;
+        let mut scale = *size;
+        for digit in text[integer_end..end].chars().skip(1) {
+            scale /= 10;
+            value += digit.to_digit(10).unwrap() as u128 * scale;
+        }
+        total = total.checked_add(value).ok_or(overflow)?;
+        // Components can be separated by whitespace, commas, or "and".
+        position = scan(text, unit_end, |c| c.is_whitespace() || c == ',');
+        if let Some(rest) = text[position..].strip_prefix("and ") {
+            position = scan(text, text.len() - rest.len(), char::is_whitespace);
+        }
+    }
+    let seconds =
+        u64::try_from(total / NANOS_PER_SECOND).map_err(|_| error(ErrorKind::Overflow, 0))?;
+    Ok(Duration::new(seconds, (total % NANOS_PER_SECOND) as u32))
+}
+
+const RELATIVE_UNITS: [(&str, u64); 7] = [
+    ("year", 365 * 86400),
+    ("month", 30 * 86400),
+    ("week", 7 * 86400),
+    ("day", 86400),
+    ("hour", 3600),
+    ("minute", 60),
+    ("second", 1),
+];
+
+// The time relative to now, where negative seconds are in the past.
+pub fn relative(seconds: i64) -> String {
+    let magnitude = seconds.unsigned_abs();
+    if magnitude < 10 {
+        return "just now".to_string();
+    }
+    let (name, size) = RELATIVE_UNITS
+        .iter()
+        .find(|(_, size)| magnitude >= *size)
+        .unwrap();
+    let count = magnitude / size;
+    let amount = format!("{} {}{}", count, name, if count == 1 { "" } else { "s" });
+    if seconds < 0 {
+        format!("{} ago", amount)
+    } else {
+        format!("in {}", amount)
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "formatting durations",
+        [0, 5, 60, 2 * 3600 + 13 * 60 + 5, 86400 * 3 + 1]
+            .map(|seconds| format(Duration::from_secs(seconds))),
+        ["0s", "5s", "1m", "2h 13m 5s", "3d 1s"]
+    );
+    test!(
+        harness,
+        "formatting fractions of a second",
+        [
+            Duration::from_millis(1500),
+            Duration::from_micros(2500),
+            Duration::from_nanos(42),
+            Duration::new(61, 1)
+        ]
+        .map(format),
+        ["1s 500ms", "2ms 500µs", "42ns", "1m 1s 1ns"]
+    );
+    test!(
+        harness,
+        "rounding to the largest units",
+        [
+            format_rounded(Duration::from_secs(2 * 3600 + 13 * 60 + 45), 2),
+            format_rounded(Duration::from_secs(2 * 3600 + 59 * 60 + 45), 2),
+            format_rounded(Duration::from_millis(1_999), 1),
+            format_rounded(Duration::from_secs(86400 + 7200), 3)
+        ],
+        ["2h 14m", "3h", "2s", "1d 2h"]
+    );
+
+    test!(
+        harness,
+        "parsing compact durations",
+        ["1d4h30m", "2h 13m 5s", "90s", "1500ms"].map(parse),
+        [
+            Ok(Duration::from_secs(86400 + 4 * 3600 + 30 * 60)),
+            Ok(Duration::from_secs(2 * 3600 + 13 * 60 + 5)),
+            Ok(Duration::from_secs(90)),
+            Ok(Duration::from_millis(1500))
+        ]
+    );
+    test!(
+        harness,
+        "parsing long names and fractions",
+        [
+            "1 hour 20 minutes",
+            "2 weeks, 3 days and 4 hours",
+            "1.5h",
+            "0.001s",
+            " 3 Minutes "
+        ]
+        .map(parse),
+        [
+            Ok(Duration::from_secs(4800)),
+            Ok(Duration::from_secs(17 * 86400 + 4 * 3600)),
+            Ok(Duration::from_secs(5400)),
+            Ok(Duration::from_millis(1)),
+            Ok(Duration::from_secs(180))
+        ]
+    );
+    let samples = [
+        Duration::ZERO,
+        Duration::from_nanos(123_456_789),
+        Duration::new(86400 * 400 + 3723, 5_000_000),
+    ];
+    test!(
+        harness,
+        "formatted durations parse back",
+        samples.map(|duration| parse(&format(duration))),
+        samples.map(Ok)
+    );
+
+    let error = |kind, position| Err::<Duration, _>(ParseError { kind, position });
+    test!(
+        harness,
+        "invalid durations",
+        [
+            parse("  "),
+            parse("5"),
+            parse("5 parsecs"),
+            parse("1h x"),
+            parse("99999999999999999999999y")
+        ],
+        [
+            error(ErrorKind::Empty, 0),
+            error(ErrorKind::MissingUnit, 1),
+            error(ErrorKind::UnknownUnit, 2),
+            error(ErrorKind::ExpectedNumber, 3),
+            error(ErrorKind::Overflow, 0)
+        ]
+    );
+
+    test!(
+        harness,
+        "relative times",
+        [-3, -45, -180, -3600, 7200 + 1800, 86400 * 2, -86400 * 400].map(relative),
+        [
+            "just now",
+            "45 seconds ago",
+            "3 minutes ago",
+            "1 hour ago",
+            "in 2 hours",
+            "in 2 days",
+            "1 year ago"
+        ]
+    );
+
+    for seconds in [0.25, 42.0, 3_600.0, 100_000.0, 3.2e7] {
+        let duration = Duration::from_secs_f64(seconds);
+        note!(
+            harness,
+            "{:>12}  {:<22}  {:<9}  {}",
+            seconds,
+            format(duration),
+            format_rounded(duration, 2),
+            relative(-(seconds as i64))
+        );
+    }
+}
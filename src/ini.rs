@@ -0,0 +1,506 @@
+// ini.rs
+// A parser for INI-style configuration files, with a few conveniences borrowed
+// from TOML. The file consists of lines, which are either empty, comments that
+// start with a semicolon or a hash mark, section headers in square brackets, or
+// key-value pairs separated by an equals sign. Pairs before the first header
+// belong to the unnamed global section. Values are either bare, in which case
+// they end at an inline comment, or quoted: double quotes allow the usual
+// escape sequences, single quotes are taken literally. Every value is stored as
+// text, and is converted to integers, floats, and booleans when it is looked
+// up, and errors point at the offending line, or at the section and the key.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnterminatedSection,
+    EmptySectionName,
+    MissingEquals,
+    EmptyKey,
+    UnterminatedString,
+    InvalidEscape(char),
+    TrailingCharacters,
+    DuplicateKey(String),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnterminatedSection => write!(f, "the section header is not closed"),
+            ErrorKind::EmptySectionName => write!(f, "the section name is empty"),
+            ErrorKind::MissingEquals => write!(f, "expected a key and a value separated by '='"),
+            ErrorKind::EmptyKey => write!(f, "the key is empty"),
+            ErrorKind::UnterminatedString => write!(f, "the quoted value is not closed"),
+            ErrorKind::InvalidEscape(c) => write!(f, "invalid escape sequence \\{}", c),
+            ErrorKind::TrailingCharacters => write!(f, "unexpected characters after the value"),
+            ErrorKind::DuplicateKey(key) => write!(f, "the key {:?} is already defined", key),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line_number: usize,
+    pub line: String,
+    pub kind: ErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {}: {}",
+            self.line_number,
+            self.kind,
+            self.line.trim()
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LookupError {
+    Missing {
+        section: String,
+        key: String,
+    },
+    Invalid {
+        section: String,
+        key: String,
+        value: String,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for LookupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = |section: &str, key: &str| match section {
+            "" => key.to_string(),
+            _ => format!("{}.{}", section, key),
+        };
+        match self {
+            LookupError::Missing { section, key } => {
+                write!(f, "{} is not defined", name(section, key))
+            }
+            LookupError::Invalid {
+                section,
+                key,
+                value,
+                expected,
+            } => write!(
+                f,
+                "{} is {:?}, which is not {}",
+                name(section, key),
+                value,
+                expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LookupError {}
+
+pub type Section = BTreeMap<String, String>;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Ini {
+    sections: BTreeMap<String, Section>,
+}
+
+fn is_comment(c: char) -> bool {
+    c == ';' || c == '#'
+}
+
+// Returns the value, and whatever follows its closing quote.
+fn parse_quoted(text: &str) -> Result<(String, &str), ErrorKind> {
+    let mut chars = text.char_indices();
+    let quote = chars.next().unwrap().1;
+    let mut value = String::new();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            c if c == quote => return Ok((value, &text[index + 1..])),
+            '\\' if quote == '"' => {
+                let escaped = match chars.next() {
+                    Some((_, c)) => c,
+                    None => break,
+                };
+                value.push(match escaped {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    '0' => '\0',
+                    '"' | '\\' => escaped,
+                    c => return Err(ErrorKind::InvalidEscape(c)),
+                });
+            }
+            c => value.push(c),
+        }
+    }
+    Err(ErrorKind::UnterminatedString)
+}
+
+// An inline comment has to follow the value, separated by whitespace, so
+// values like colors (#ff8800) or URLs with fragments need no quotes.
+fn parse_value(text: &str) -> Result<String, ErrorKind> {
+    let text = text.trim();
+    if text.starts_with('"') || text.starts_with('\'') {
+        let (value, rest) = parse_quoted(text)?;
+        let rest = rest.trim_start();
+        if !rest.is_empty() && !rest.starts_with(is_comment) {
+            return Err(ErrorKind::TrailingCharacters);
+        }
+        return Ok(value);
+    }
+    let mut end = text.len();
+    let mut after_whitespace = false;
+    for (index, c) in text.char_indices() {
+        if is_comment(c) && after_whitespace {
+            end = index;
+            break;
+        }
+        after_whitespace = c.is_whitespace();
+    }
+    Ok(text[..end].trim_end().to_string())
+}
+
+impl Ini {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut ini = Self::new();
+        let mut section = String::new();
+        for (index, line) in text.lines().enumerate() {
+            let error = |kind| ParseError {
+                line_number: index + 1,
+                line: line.to_string(),
+                kind,
+            };
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with(is_comment) {
+                continue;
+            }
+
+            if let Some(header) = trimmed.strip_prefix('[') {
+                let (name, rest) = header
+                    .split_once(']')
+                    .ok_or_else(|| error(ErrorKind::UnterminatedSection))?;
+                let rest = rest.trim_start();
+                if !rest.is_empty() && !rest.starts_with(is_comment) {
+                    return Err(error(ErrorKind::TrailingCharacters));
+                }
+                section = name.trim().to_string();
+                if section.is_empty() {
+                    return Err(error(ErrorKind::EmptySectionName));
+                }
+                ini.sections.entry(section.clone()).or_default();
+                continue;
+            }
+
+            let (key, value) = trimmed
+                .split_once('=')
+                .ok_or_else(|| error(ErrorKind::MissingEquals))?;
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(error(ErrorKind::EmptyKey));
+            }
+            let value = parse_value(value).map_err(error)?;
+            let entries = ini.sections.entry(section.clone()).or_default();
+            if entries.contains_key(key) {
+                return Err(error(ErrorKind::DuplicateKey(key.to_string())));
+            }
+            entries.insert(key.to_string(), value);
+        }
+        Ok(ini)
+    }
+
+    // The names of the sections, with the global section as the empty name,
+    // if it has any keys.
+    pub fn sections(&self) -> Vec<&str> {
+        self.sections.keys().map(|name| name.as_str()).collect()
+    }
+
+    pub fn section(&self, section: &str) -> Option<&Section> {
+        self.sections.get(section)
+    }
+
+    pub fn set(&mut self, section: &str, key: &str, value: &str) {
+        self.sections
+            .entry(section.to_string())
+            .or_default()
+            .insert(key.to_string(), value.to_string());
+    }
+
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.sections
+            .get(section)?
+            .get(key)
+            .map(|value| value.as_str())
+    }
+
+    fn lookup<T>(
+        &self,
+        section: &str,
+        key: &str,
+        expected: &'static str,
+        convert: impl Fn(&str) -> Option<T>,
+    ) -> Result<T, LookupError> {
+        let value = self.get(section, key).ok_or_else(|| LookupError::Missing {
+            section: section.to_string(),
+            key: key.to_string(),
+        })?;
+        convert(value).ok_or_else(|| LookupError::Invalid {
+            section: section.to_string(),
+            key: key.to_string(),
+            value: value.to_string(),
+            expected,
+        })
+    }
+
+    pub fn get_str(&self, section: &str, key: &str) -> Result<&str, LookupError> {
+        self.get(section, key).ok_or_else(|| LookupError::Missing {
+            section: section.to_string(),
+            key: key.to_string(),
+        })
+    }
+
+    // Underscores can be used to group the digits, and the 0x, 0o, and 0b
+    // prefixes select another radix.
+    pub fn get_int(&self, section: &str, key: &str) -> Result<i64, LookupError> {
+        self.lookup(section, key, "an integer", |value| {
+            let digits = value.replace('_', "");
+            let (negative, digits) = match digits.strip_prefix('-') {
+                Some(rest) => (true, rest),
+                None => (false, digits.strip_prefix('+').unwrap_or(&digits)),
+            };
+            let (radix, digits) = match digits.get(..2) {
+                Some("0x") => (16, &digits[2..]),
+                Some("0o") => (8, &digits[2..]),
+                Some("0b") => (2, &digits[2..]),
+                _ => (10, digits),
+            };
+            let magnitude = i64::from_str_radix(digits, radix).ok()?;
+            Some(if negative { -magnitude } else { magnitude })
+        })
+    }
+
+    pub fn get_float(&self, section: &str, key: &str) -> Result<f64, LookupError> {
+        self.lookup(section, key, "a number", |value| value.parse().ok())
+    }
+
+    pub fn get_bool(&self, section: &str, key: &str) -> Result<bool, LookupError> {
+        self.lookup(section, key, "a boolean", |value| {
+            match value.to_ascii_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => Some(true),
+                "false" | "no" | "off" | "0" => Some(false),
+                _ => None,
+            }
+        })
+    }
+}
+
+fn write_value(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    let needs_quotes = value.is_empty()
+        || value != value.trim()
+        || value.starts_with(['"', '\''])
+        || value.contains(|c: char| is_comment(c) || c.is_control());
+    if !needs_quotes {
+        return write!(f, "{}", value);
+    }
+    write!(f, "\"")?;
+    for c in value.chars() {
+        match c {
+            '\n' => write!(f, "\\n")?,
+            '\t' => write!(f, "\\t")?,
+            '\r' => write!(f, "\\r")?,
+            '\0' => write!(f, "\\0")?,
+            '"' | '\\' => write!(f, "\\{}", c)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+// The output parses back to the same configuration.
+impl fmt::Display for Ini {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut first = true;
+        for (name, entries) in &self.sections {
+            if !first {
+                writeln!(f)?;
+            }
+            first = false;
+            if !name.is_empty() {
+                writeln!(f, "[{}]", name)?;
+            }
+            for (key, value) in entries {
+                write!(f, "{} = ", key)?;
+                write_value(f, value)?;
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+const CONFIGURATION: &str = r#"
+; Global settings come before the first section.
+name = snippets
+debug = off
+
+[server]
+host = 127.0.0.1      ; inline comments follow whitespace
+port = 8_080
+timeout = 2.5
+color = #ff8800
+greeting = "Hello, \"world\"!\n"
+path = 'C:\Program Files\snippets'
+
+# Dotted names are plain names, like in TOML.
+[server.tls]
+enabled = yes
+mode = 0o644
+"#;
+
+pub fn demo(harness: &mut Harness) {
+    let ini = Ini::parse(CONFIGURATION).unwrap();
+    test!(
+        harness,
+        "the sections are collected",
+        ini.sections(),
+        vec!["", "server", "server.tls"]
+    );
+    test!(
+        harness,
+        "global keys are in the unnamed section",
+        ini.get("", "name"),
+        Some("snippets")
+    );
+    test!(
+        harness,
+        "inline comments are removed",
+        ini.get_str("server", "host"),
+        Ok("127.0.0.1")
+    );
+    test!(
+        harness,
+        "a hash mark without whitespace before it is not a comment",
+        ini.get_str("server", "color"),
+        Ok("#ff8800")
+    );
+    test!(
+        harness,
+        "double-quoted values are unescaped",
+        ini.get_str("server", "greeting"),
+        Ok("Hello, \"world\"!\n")
+    );
+    test!(
+        harness,
+        "single-quoted values are literal",
+        ini.get_str("server", "path"),
+        Ok("C:\\Program Files\\snippets")
+    );
+    test!(
+        harness,
+        "integers with digit separators and prefixes",
+        (
+            ini.get_int("server", "port"),
+            ini.get_int("server.tls", "mode")
+        ),
+        (Ok(8080), Ok(0o644))
+    );
+    test!(
+        harness,
+        "floats",
+        ini.get_float("server", "timeout"),
+        Ok(2.5)
+    );
+    test!(
+        harness,
+        "booleans",
+        (
+            ini.get_bool("", "debug"),
+            ini.get_bool("server.tls", "enabled")
+        ),
+        (Ok(false), Ok(true))
+    );
+    test!(
+        harness,
+        "the printed configuration parses back",
+        Ini::parse(&ini.to_string()),
+        Ok(ini.clone())
+    );
+
+    let mut lookup_errors = Vec::new();
+    for error in [
+        ini.get_int("server", "host").unwrap_err(),
+        ini.get_bool("", "name").unwrap_err(),
+        ini.get_float("client", "timeout").unwrap_err(),
+    ] {
+        lookup_errors.push(error.to_string());
+    }
+    test!(
+        harness,
+        "conversion errors name the section and the key",
+        lookup_errors.clone(),
+        vec![
+            "server.host is \"127.0.0.1\", which is not an integer",
+            "name is \"snippets\", which is not a boolean",
+            "client.timeout is not defined"
+        ]
+    );
+
+    let broken: [(&str, &str); 7] = [
+        (
+            "[server\nport = 1",
+            "line 1: the section header is not closed: [server",
+        ),
+        ("a = 1\n[ ]", "line 2: the section name is empty: [ ]"),
+        (
+            "[a]\nkey = 1\n\njust a line",
+            "line 4: expected a key and a value separated by '=': just a line",
+        ),
+        ("= 1", "line 1: the key is empty: = 1"),
+        (
+            "key = \"open",
+            "line 1: the quoted value is not closed: key = \"open",
+        ),
+        (
+            "key = \"\\q\"",
+            "line 1: invalid escape sequence \\q: key = \"\\q\"",
+        ),
+        (
+            "[a]\nx = 1\n[b]\nx = 2\n[a]\nx = 3",
+            "line 6: the key \"x\" is already defined: x = 3",
+        ),
+    ];
+    let mut parse_errors = Vec::new();
+    for (text, expected) in broken {
+        let error = Ini::parse(text).unwrap_err().to_string();
+        test!(
+            harness,
+            &format!("the error in {:?}", text),
+            error.clone(),
+            expected
+        );
+        parse_errors.push(error);
+    }
+
+    note!(harness, "{}", ini);
+    for error in lookup_errors.iter().chain(&parse_errors) {
+        note!(harness, "{}", error);
+    }
+    note!(harness);
+}
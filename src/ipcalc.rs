@@ -0,0 +1,711 @@
+// ipcalc.rs
+// Parsing and formatting IPv4 and IPv6 addresses, and the arithmetic of the
+// subnets written in CIDR notation, like 192.168.1.0/24, where the number
+// after the slash is the length of the prefix which is shared by the addresses
+// of the network. The addresses are held as plain integers, the mask is a run
+// of ones at the top, and the network, the broadcast address, the usable
+// range of hosts, the containment checks, and the splitting into smaller
+// subnets are all a few bitwise operations on them. The IPv4 parser rejects
+// the leading zeros, which some systems read as octal, and the IPv6 parser
+// handles the elision of zero groups, and the embedded IPv4 address at the
+// end, while the formatter writes the canonical text form of RFC 5952, with
+// the longest run of zero groups elided. The demonstration prints a summary
+// table of a few subnets.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    Empty,
+    InvalidDigit,
+    LeadingZero,
+    OutOfRange,
+    WrongGroupCount,
+    MultipleElisions,
+    InvalidPrefix,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IpError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for IpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.kind {
+            ErrorKind::Empty => "missing number",
+            ErrorKind::InvalidDigit => "invalid digit",
+            ErrorKind::LeadingZero => "leading zero in an octet",
+            ErrorKind::OutOfRange => "number out of range",
+            ErrorKind::WrongGroupCount => "wrong number of groups",
+            ErrorKind::MultipleElisions => "more than one elided run",
+            ErrorKind::InvalidPrefix => "invalid prefix length",
+        };
+        write!(f, "{} at position {}", message, self.position)
+    }
+}
+
+impl std::error::Error for IpError {}
+
+fn error(kind: ErrorKind, position: usize) -> IpError {
+    IpError { kind, position }
+}
+
+// The offset of the text in a larger string is added to the positions.
+fn parse_ipv4_at(text: &str, offset: usize) -> Result<u32, IpError> {
+    let mut value = 0;
+    let mut position = offset;
+    let mut count = 0;
+    for part in text.split('.') {
+        count += 1;
+        if count > 4 {
+            return Err(error(ErrorKind::WrongGroupCount, position - 1));
+        }
+        if part.is_empty() {
+            return Err(error(ErrorKind::Empty, position));
+        }
+        if let Some(index) = part.bytes().position(|byte| !byte.is_ascii_digit()) {
+            return Err(error(ErrorKind::InvalidDigit, position + index));
+        }
+        if part.len() > 1 && part.starts_with('0') {
+            return Err(error(ErrorKind::LeadingZero, position));
+        }
+        match part.parse::<u8>() {
+            Ok(octet) => value = value << 8 | octet as u32,
+            Err(_) => return Err(error(ErrorKind::OutOfRange, position)),
+        }
+        position += part.len() + 1;
+    }
+    if count < 4 {
+        return Err(error(ErrorKind::WrongGroupCount, offset + text.len()));
+    }
+    Ok(value)
+}
+
+pub fn parse_ipv4(text: &str) -> Result<u32, IpError> {
+    parse_ipv4_at(text, 0)
+}
+
+// The groups of one side of the elision, with an IPv4 address allowed at
+// the very end of the whole address.
+fn parse_groups(
+    text: &str,
+    offset: usize,
+    last: bool,
+    groups: &mut Vec<u16>,
+) -> Result<(), IpError> {
+    if text.is_empty() {
+        return Ok(());
+    }
+    let mut position = offset;
+    let parts: Vec<&str> = text.split(':').collect();
+    for (index, part) in parts.iter().enumerate() {
+        if last && index + 1 == parts.len() && part.contains('.') {
+            let value = parse_ipv4_at(part, position)?;
+            groups.push((value >> 16) as u16);
+            groups.push(value as u16);
+            return Ok(());
+        }
+        if part.is_empty() {
+            return Err(error(ErrorKind::Empty, position));
+        }
+        if let Some(index) = part.bytes().position(|byte| !byte.is_ascii_hexdigit()) {
+            return Err(error(ErrorKind::InvalidDigit, position + index));
+        }
+        if part.len() > 4 {
+            return Err(error(ErrorKind::OutOfRange, position));
+        }
+        groups.push(u16::from_str_radix(part, 16).unwrap());
+        position += part.len() + 1;
+    }
+    Ok(())
+}
+
+pub fn parse_ipv6(text: &str) -> Result<u128, IpError> {
+    let (head, tail) = match text.find("::") {
+        Some(index) => {
+            let tail = &text[index + 2..];
+            if let Some(second) = tail.find("::") {
+                return Err(error(ErrorKind::MultipleElisions, index + 2 + second));
+            }
+            (&text[..index], Some((tail, index + 2)))
+        }
+        None => (text, None),
+    };
+    let mut groups = Vec::with_capacity(8);
+    parse_groups(head, 0, tail.is_none(), &mut groups)?;
+    let mut value = 0u128;
+    match tail {
+        None if groups.len() != 8 => {
+            return Err(error(ErrorKind::WrongGroupCount, text.len()));
+        }
+        None => {}
+        Some((tail, offset)) => {
+            let mut rest = Vec::with_capacity(8);
+            parse_groups(tail, offset, true, &mut rest)?;
+            // The elision stands for at least one group of zeros.
+            if groups.len() + rest.len() > 7 {
+                return Err(error(ErrorKind::WrongGroupCount, text.len()));
+            }
+            groups.resize(8 - rest.len(), 0);
+            groups.extend(rest);
+        }
+    }
+    for group in groups {
+        value = value << 16 | group as u128;
+    }
+    Ok(value)
+}
+
+pub fn format_ipv4(value: u32) -> String {
+    let octets = value.to_be_bytes();
+    format!("{}.{}.{}.{}", octets[0], octets[1], octets[2], octets[3])
+}
+
+// The longest run of at least two zero groups is elided, the first one of
+// the equally long ones, and the IPv4-mapped addresses end in dotted form.
+pub fn format_ipv6(value: u128) -> String {
+    if value >> 32 == 0xffff {
+        return format!("::ffff:{}", format_ipv4(value as u32));
+    }
+    let groups: Vec<u16> = (0..8)
+        .rev()
+        .map(|index| (value >> (index * 16)) as u16)
+        .collect();
+    let mut best = (0, 0);
+    let mut start = 0;
+    // A nonzero group after the last one ends the last run.
+    for (index, group) in groups.iter().chain([1].iter()).enumerate() {
+        if *group == 0 {
+            continue;
+        }
+        if index - start > best.1 - best.0 && index - start >= 2 {
+            best = (start, index);
+        }
+        start = index + 1;
+    }
+    let join = |groups: &[u16]| {
+        groups
+            .iter()
+            .map(|group| format!("{:x}", group))
+            .collect::<Vec<_>>()
+            .join(":")
+    };
+    if best.0 == best.1 {
+        join(&groups)
+    } else {
+        format!("{}::{}", join(&groups[..best.0]), join(&groups[best.1..]))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Address {
+    V4(u32),
+    V6(u128),
+}
+
+impl Address {
+    pub fn bits(&self) -> u8 {
+        match self {
+            Address::V4(_) => 32,
+            Address::V6(_) => 128,
+        }
+    }
+
+    pub fn value(&self) -> u128 {
+        match self {
+            Address::V4(value) => *value as u128,
+            Address::V6(value) => *value,
+        }
+    }
+
+    // An address of the same family, the value cut to its width.
+    fn with_value(&self, value: u128) -> Address {
+        match self {
+            Address::V4(_) => Address::V4(value as u32),
+            Address::V6(_) => Address::V6(value),
+        }
+    }
+
+    pub fn is_ipv4(&self) -> bool {
+        matches!(self, Address::V4(_))
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Address::V4(value) => write!(f, "{}", format_ipv4(*value)),
+            Address::V6(value) => write!(f, "{}", format_ipv6(*value)),
+        }
+    }
+}
+
+impl FromStr for Address {
+    type Err = IpError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        if text.contains(':') {
+            parse_ipv6(text).map(Address::V6)
+        } else {
+            parse_ipv4(text).map(Address::V4)
+        }
+    }
+}
+
+// Only the addresses of the same family are ordered.
+impl PartialOrd for Address {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Address::V4(left), Address::V4(right)) => left.partial_cmp(right),
+            (Address::V6(left), Address::V6(right)) => left.partial_cmp(right),
+            _ => None,
+        }
+    }
+}
+
+// An address, and the length of the prefix of its network. The address may
+// have host bits set, like the address of an interface.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cidr {
+    address: Address,
+    prefix: u8,
+}
+
+// The top bits of the width of the address.
+fn mask(bits: u8, prefix: u8) -> u128 {
+    let all = u128::MAX >> (128 - bits);
+    all & !all.checked_shr(prefix as u32).unwrap_or(0)
+}
+
+impl Cidr {
+    pub fn new(address: Address, prefix: u8) -> Option<Self> {
+        (prefix <= address.bits()).then_some(Self { address, prefix })
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    pub fn netmask(&self) -> Address {
+        self.address
+            .with_value(mask(self.address.bits(), self.prefix))
+    }
+
+    pub fn hostmask(&self) -> Address {
+        let bits = self.address.bits();
+        self.address
+            .with_value(mask(bits, self.prefix) ^ mask(bits, bits))
+    }
+
+    pub fn network(&self) -> Address {
+        self.address
+            .with_value(self.address.value() & self.netmask().value())
+    }
+
+    pub fn last_address(&self) -> Address {
+        self.address
+            .with_value(self.network().value() | self.hostmask().value())
+    }
+
+    // IPv6 has no broadcast, and neither have the point-to-point /31 links
+    // of RFC 3021, and the single hosts.
+    pub fn broadcast(&self) -> Option<Address> {
+        (self.address.is_ipv4() && self.prefix < 31).then(|| self.last_address())
+    }
+
+    // The network address of IPv4 is not a host, except on /31 and /32.
+    pub fn first_host(&self) -> Address {
+        match self.broadcast() {
+            Some(_) => self.address.with_value(self.network().value() + 1),
+            None => self.network(),
+        }
+    }
+
+    pub fn last_host(&self) -> Address {
+        match self.broadcast() {
+            Some(broadcast) => self.address.with_value(broadcast.value() - 1),
+            None => self.last_address(),
+        }
+    }
+
+    // The number of usable addresses, which saturates only for ::/0.
+    pub fn host_count(&self) -> u128 {
+        let size = match self.address.bits() - self.prefix {
+            128 => u128::MAX,
+            host_bits => 1 << host_bits,
+        };
+        match self.broadcast() {
+            Some(_) => size - 2,
+            None => size,
+        }
+    }
+
+    pub fn contains(&self, address: Address) -> bool {
+        address.is_ipv4() == self.address.is_ipv4()
+            && address.value() & self.netmask().value() == self.network().value()
+    }
+
+    pub fn contains_network(&self, other: &Cidr) -> bool {
+        other.prefix >= self.prefix && self.contains(other.network())
+    }
+
+    pub fn overlaps(&self, other: &Cidr) -> bool {
+        self.contains_network(other) || other.contains_network(self)
+    }
+
+    // The consecutive subnets of the longer prefix, which cover the network.
+    pub fn subnets(&self, prefix: u8) -> Option<Subnets> {
+        let bits = self.address.bits();
+        if prefix < self.prefix || prefix > bits {
+            return None;
+        }
+        let count = match prefix - self.prefix {
+            128 => u128::MAX,
+            split => 1 << split,
+        };
+        Some(Subnets {
+            next: self.network().value(),
+            step: if prefix == 0 { 0 } else { 1 << (bits - prefix) },
+            remaining: count,
+            template: self.address,
+            prefix,
+        })
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix)
+    }
+}
+
+// Without a slash, the prefix covers the whole address.
+impl FromStr for Cidr {
+    type Err = IpError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (address, prefix) = match text.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (text, None),
+        };
+        let address: Address = address.parse()?;
+        let prefix = match prefix {
+            None => address.bits(),
+            Some(digits) => {
+                let position = text.len() - digits.len();
+                let valid = !digits.is_empty()
+                    && digits.bytes().all(|byte| byte.is_ascii_digit())
+                    && !(digits.len() > 1 && digits.starts_with('0'));
+                match digits.parse::<u8>() {
+                    Ok(prefix) if valid && prefix <= address.bits() => prefix,
+                    _ => return Err(error(ErrorKind::InvalidPrefix, position)),
+                }
+            }
+        };
+        Ok(Self { address, prefix })
+    }
+}
+
+pub struct Subnets {
+    next: u128,
+    step: u128,
+    remaining: u128,
+    template: Address,
+    prefix: u8,
+}
+
+impl Iterator for Subnets {
+    type Item = Cidr;
+
+    fn next(&mut self) -> Option<Cidr> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let subnet = Cidr {
+            address: self.template.with_value(self.next),
+            prefix: self.prefix,
+        };
+        self.next = self.next.wrapping_add(self.step);
+        Some(subnet)
+    }
+}
+
+// The summary of the networks, one row each.
+pub fn summary(networks: &[Cidr]) -> String {
+    use crate::table::{Alignment, Table};
+    let mut table = Table::new()
+        .header(&[
+            "network",
+            "netmask",
+            "broadcast",
+            "first host",
+            "last host",
+            "hosts",
+        ])
+        .align(5, Alignment::Right);
+    for network in networks {
+        let netmask = match network.address.is_ipv4() {
+            true => network.netmask().to_string(),
+            false => format!("/{}", network.prefix),
+        };
+        let broadcast = network.broadcast().map(|broadcast| broadcast.to_string());
+        table.push(&[
+            format!("{}/{}", network.network(), network.prefix),
+            netmask,
+            broadcast.unwrap_or_else(|| "-".to_string()),
+            network.first_host().to_string(),
+            network.last_host().to_string(),
+            network.host_count().to_string(),
+        ]);
+    }
+    table.render()
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "IPv4 addresses are parsed",
+        ["192.168.1.1", "0.0.0.0", "255.255.255.255"].map(parse_ipv4),
+        [Ok(0xc0a8_0101), Ok(0), Ok(u32::MAX)]
+    );
+    test!(
+        harness,
+        "invalid IPv4 addresses are rejected where they go wrong",
+        [
+            "1.2.3",
+            "1.2.3.4.5",
+            "1.2.256.4",
+            "1.2.03.4",
+            "1..3.4",
+            "1.2.x.4"
+        ]
+        .map(parse_ipv4),
+        [
+            Err(error(ErrorKind::WrongGroupCount, 5)),
+            Err(error(ErrorKind::WrongGroupCount, 7)),
+            Err(error(ErrorKind::OutOfRange, 4)),
+            Err(error(ErrorKind::LeadingZero, 4)),
+            Err(error(ErrorKind::Empty, 2)),
+            Err(error(ErrorKind::InvalidDigit, 4)),
+        ]
+    );
+    test!(
+        harness,
+        "IPv6 addresses are parsed, with elisions and embedded IPv4",
+        [
+            "2001:db8::1",
+            "::",
+            "::1",
+            "fe80::",
+            "::ffff:192.0.2.128",
+            "1:2:3:4:5:6:7:8"
+        ]
+        .map(parse_ipv6),
+        [
+            Ok(0x2001_0db8_0000_0000_0000_0000_0000_0001),
+            Ok(0),
+            Ok(1),
+            Ok(0xfe80 << 112),
+            Ok(0xffff_c000_0280),
+            Ok(0x0001_0002_0003_0004_0005_0006_0007_0008),
+        ]
+    );
+    test!(
+        harness,
+        "invalid IPv6 addresses are rejected where they go wrong",
+        [
+            "1::2::3",
+            "1:2:3:4:5:6:7",
+            "1:2:3:4::5:6:7:8",
+            "12345::",
+            "::g",
+            ":1::"
+        ]
+        .map(parse_ipv6),
+        [
+            Err(error(ErrorKind::MultipleElisions, 4)),
+            Err(error(ErrorKind::WrongGroupCount, 13)),
+            Err(error(ErrorKind::WrongGroupCount, 16)),
+            Err(error(ErrorKind::OutOfRange, 0)),
+            Err(error(ErrorKind::InvalidDigit, 2)),
+            Err(error(ErrorKind::Empty, 0)),
+        ]
+    );
+    test!(
+        harness,
+        "IPv6 addresses are written in the canonical form",
+        [
+            "2001:0DB8:0000:0000:0000:0000:0000:0001",
+            "2001:db8:0:1:1:1:1:1",
+            "2001:0:0:1:0:0:0:1",
+            "2001:db8:0:0:1:0:0:1",
+            "0:0:0:0:0:0:0:0",
+            "0:0:0:0:0:ffff:7f00:1",
+        ]
+        .map(|text| format_ipv6(parse_ipv6(text).unwrap())),
+        [
+            "2001:db8::1",
+            "2001:db8:0:1:1:1:1:1",
+            "2001:0:0:1::1",
+            "2001:db8::1:0:0:1",
+            "::",
+            "::ffff:127.0.0.1",
+        ]
+        .map(String::from)
+    );
+
+    let home: Cidr = "192.168.1.77/24".parse().unwrap();
+    test!(
+        harness,
+        "the addresses of an IPv4 network are computed",
+        [
+            home.network(),
+            home.netmask(),
+            home.hostmask(),
+            home.broadcast().unwrap(),
+            home.first_host(),
+            home.last_host(),
+        ]
+        .map(|address| address.to_string()),
+        [
+            "192.168.1.0",
+            "255.255.255.0",
+            "0.0.0.255",
+            "192.168.1.255",
+            "192.168.1.1",
+            "192.168.1.254",
+        ]
+        .map(String::from)
+    );
+    test!(
+        harness,
+        "the edge prefixes have the right number of hosts",
+        [
+            "10.0.0.0/8",
+            "10.0.0.0/30",
+            "10.0.0.0/31",
+            "10.0.0.1/32",
+            "0.0.0.0/0",
+            "::/0",
+            "::/64"
+        ]
+        .map(|text| text.parse::<Cidr>().unwrap().host_count()),
+        [16_777_214, 2, 2, 1, 4_294_967_294, u128::MAX, 1 << 64]
+    );
+    test!(
+        harness,
+        "invalid prefixes are rejected",
+        [
+            "10.0.0.0/33",
+            "10.0.0.0/",
+            "10.0.0.0/08",
+            "::/129",
+            "10.0.0.0/-1"
+        ]
+        .map(|text| text.parse::<Cidr>().err()),
+        [
+            Some(error(ErrorKind::InvalidPrefix, 9)),
+            Some(error(ErrorKind::InvalidPrefix, 9)),
+            Some(error(ErrorKind::InvalidPrefix, 9)),
+            Some(error(ErrorKind::InvalidPrefix, 3)),
+            Some(error(ErrorKind::InvalidPrefix, 9)),
+        ]
+    );
+
+    let address = |text: &str| text.parse::<Address>().unwrap();
+    let documentation: Cidr = "2001:db8::/32".parse().unwrap();
+    test!(
+        harness,
+        "containment is checked on the prefix, and the family",
+        [
+            home.contains(address("192.168.1.200")),
+            home.contains(address("192.168.2.1")),
+            home.contains(address("::ffff:192.168.1.200")),
+            documentation.contains(address("2001:db8:ffff::1")),
+            documentation.contains(address("2001:db9::1")),
+        ],
+        [true, false, false, true, false]
+    );
+    let private: Cidr = "192.168.0.0/16".parse().unwrap();
+    test!(
+        harness,
+        "networks contain and overlap each other",
+        [
+            private.contains_network(&home),
+            home.contains_network(&private),
+            home.overlaps(&private),
+            home.overlaps(&"192.168.2.0/24".parse().unwrap()),
+        ],
+        [true, false, true, false]
+    );
+    test!(
+        harness,
+        "a network is split into equal subnets",
+        home.subnets(26)
+            .unwrap()
+            .map(|subnet| subnet.to_string())
+            .collect::<Vec<_>>(),
+        [
+            "192.168.1.0/26",
+            "192.168.1.64/26",
+            "192.168.1.128/26",
+            "192.168.1.192/26"
+        ]
+        .map(String::from)
+        .to_vec()
+    );
+    test!(
+        harness,
+        "huge IPv6 splits are produced lazily",
+        documentation
+            .subnets(64)
+            .unwrap()
+            .skip(1)
+            .take(2)
+            .map(|subnet| subnet.to_string())
+            .collect::<Vec<_>>(),
+        vec![
+            "2001:db8:0:1::/64".to_string(),
+            "2001:db8:0:2::/64".to_string()
+        ]
+    );
+    test!(
+        harness,
+        "a shorter prefix is not a split",
+        home.subnets(16).is_none() && home.subnets(33).is_none(),
+        true
+    );
+
+    let networks: Vec<Cidr> = [
+        "10.0.0.0/8",
+        "172.16.0.0/12",
+        "192.168.1.77/24",
+        "100.64.0.0/30",
+        "192.0.2.0/31",
+        "203.0.113.9/32",
+        "2001:db8::/32",
+        "fe80::1/64",
+    ]
+    .iter()
+    .map(|text| text.parse().unwrap())
+    .collect();
+    note!(harness, "Subnets:\n{}", summary(&networks));
+}
@@ -0,0 +1,1362 @@
+// json.rs
+// A JSON parser, and a printer for the parsed values. The parser is recursive
+// descent: each kind of value is parsed by its own function, which calls back
+// to parse the nested values of arrays and objects, with a limit on the depth,
+// so a malicious document can not overflow the stack. The grammar is followed
+// strictly, so leading zeros, trailing commas, and unescaped control characters
+// are rejected, and errors are reported with the line and the column in which
+// they were found. Strings are unescaped, including UTF-16 surrogate pairs. The
+// members of objects keep their order, and the printer writes either compact or
+// indented output, with a configurable indentation. Nested values can be looked
+// up by the JSON Pointers of RFC 6901, like /tags/0, and queried with the same
+// syntax, where a * matches every member or element. Structures are mapped to
+// and from values by hand, through the ToJson and FromJson traits, which the
+// json_object! and from_json_fields! macros make short to write, and the
+// mapping errors tell the pointer of the value which was wrong.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::Index;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnexpectedEnd,
+    UnexpectedCharacter(char),
+    InvalidNumber,
+    InvalidEscape(char),
+    InvalidUnicode,
+    ControlCharacter,
+    TooDeep,
+    TrailingCharacters,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character {:?}", c),
+            ErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ErrorKind::InvalidEscape(c) => write!(f, "invalid escape sequence \\{}", c),
+            ErrorKind::InvalidUnicode => write!(f, "invalid unicode escape sequence"),
+            ErrorKind::ControlCharacter => write!(f, "unescaped control character in string"),
+            ErrorKind::TooDeep => write!(f, "too deeply nested"),
+            ErrorKind::TrailingCharacters => write!(f, "trailing characters after the value"),
+        }
+    }
+}
+
+// Lines and columns are counted from one, and columns are counted in
+// characters, not in bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ErrorKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.kind, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error_at(&self, position: usize, kind: ErrorKind) -> ParseError {
+        let before = &self.text[..position];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        ParseError {
+            kind,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    fn error(&self, kind: ErrorKind) -> ParseError {
+        self.error_at(self.position, kind)
+    }
+
+    fn unexpected(&self) -> ParseError {
+        match self.text[self.position..].chars().next() {
+            Some(c) => self.error(ErrorKind::UnexpectedCharacter(c)),
+            None => self.error(ErrorKind::UnexpectedEnd),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), ParseError> {
+        if self.peek() != Some(byte) {
+            return Err(self.unexpected());
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue, ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'n') => self.parse_literal("null", JsonValue::Null),
+            Some(b't') => self.parse_literal("true", JsonValue::Bool(true)),
+            Some(b'f') => self.parse_literal("false", JsonValue::Bool(false)),
+            Some(b'"') => Ok(JsonValue::String(self.parse_string()?)),
+            Some(b'[') => self.parse_array(),
+            Some(b'{') => self.parse_object(),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn parse_literal(&mut self, word: &str, value: JsonValue) -> Result<JsonValue, ParseError> {
+        for byte in word.bytes() {
+            self.expect(byte)?;
+        }
+        Ok(value)
+    }
+
+    fn enter(&mut self) -> Result<(), ParseError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(self.error(ErrorKind::TooDeep));
+        }
+        self.position += 1;
+        self.skip_whitespace();
+        Ok(())
+    }
+
+    // The separator after each element is either a comma, or the closing
+    // bracket, so a trailing comma is followed by an unexpected bracket.
+    fn parse_array(&mut self) -> Result<JsonValue, ParseError> {
+        self.enter()?;
+        let mut elements = Vec::new();
+        if self.peek() != Some(b']') {
+            loop {
+                elements.push(self.parse_value()?);
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b',') => self.position += 1,
+                    Some(b']') => break,
+                    _ => return Err(self.unexpected()),
+                }
+            }
+        }
+        self.position += 1;
+        self.depth -= 1;
+        Ok(JsonValue::Array(elements))
+    }
+
+    // A repeated key replaces the value of the earlier member, which is found by
+    // an index of the keys, so that large objects are parsed in linear time.
+    fn parse_object(&mut self) -> Result<JsonValue, ParseError> {
+        self.enter()?;
+        let mut members: Vec<(String, JsonValue)> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        if self.peek() != Some(b'}') {
+            loop {
+                self.skip_whitespace();
+                if self.peek() != Some(b'"') {
+                    return Err(self.unexpected());
+                }
+                let key = self.parse_string()?;
+                self.skip_whitespace();
+                self.expect(b':')?;
+                let value = self.parse_value()?;
+                match index.get(&key) {
+                    Some(&existing) => members[existing].1 = value,
+                    None => {
+                        index.insert(key.clone(), members.len());
+                        members.push((key, value));
+                    }
+                }
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b',') => self.position += 1,
+                    Some(b'}') => break,
+                    _ => return Err(self.unexpected()),
+                }
+            }
+        }
+        self.position += 1;
+        self.depth -= 1;
+        Ok(JsonValue::Object(members))
+    }
+
+    // The runs of characters between escape sequences are copied as they are,
+    // and as they end at ASCII characters, they are always valid slices.
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        self.position += 1;
+        let mut result = String::new();
+        loop {
+            let start = self.position;
+            while let Some(byte) = self.peek() {
+                if byte == b'"' || byte == b'\\' || byte < 0x20 {
+                    break;
+                }
+                self.position += 1;
+            }
+            result.push_str(&self.text[start..self.position]);
+            match self.peek() {
+                Some(b'"') => {
+                    self.position += 1;
+                    return Ok(result);
+                }
+                Some(b'\\') => result.push(self.parse_escape()?),
+                Some(_) => return Err(self.error(ErrorKind::ControlCharacter)),
+                None => return Err(self.error(ErrorKind::UnexpectedEnd)),
+            }
+        }
+    }
+
+    fn parse_escape(&mut self) -> Result<char, ParseError> {
+        let start = self.position;
+        self.position += 1;
+        let c = match self.text[self.position..].chars().next() {
+            Some(c) => c,
+            None => return Err(self.error(ErrorKind::UnexpectedEnd)),
+        };
+        self.position += c.len_utf8();
+        let unescaped = match c {
+            '"' | '\\' | '/' => c,
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => return self.parse_unicode(start),
+            _ => return Err(self.error_at(start, ErrorKind::InvalidEscape(c))),
+        };
+        Ok(unescaped)
+    }
+
+    // Characters outside the Basic Multilingual Plane are escaped as a pair
+    // of UTF-16 surrogates, \uD800-\uDBFF followed by \uDC00-\uDFFF.
+    fn parse_unicode(&mut self, start: usize) -> Result<char, ParseError> {
+        let high = self.parse_hex(start)?;
+        let code = match high {
+            0xD800..=0xDBFF => {
+                if !self.text[self.position..].starts_with("\\u") {
+                    return Err(self.error_at(start, ErrorKind::InvalidUnicode));
+                }
+                self.position += 2;
+                let low = self.parse_hex(start)?;
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(self.error_at(start, ErrorKind::InvalidUnicode));
+                }
+                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+            }
+            0xDC00..=0xDFFF => return Err(self.error_at(start, ErrorKind::InvalidUnicode)),
+            _ => high,
+        };
+        Ok(char::from_u32(code).unwrap())
+    }
+
+    fn parse_hex(&mut self, start: usize) -> Result<u32, ParseError> {
+        let digits = self
+            .text
+            .get(self.position..self.position + 4)
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or_else(|| self.error_at(start, ErrorKind::InvalidUnicode))?;
+        self.position += 4;
+        Ok(u32::from_str_radix(digits, 16).unwrap())
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.position;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.position += 1;
+        }
+        self.position - start
+    }
+
+    // -?(0|[1-9][0-9]*)(.[0-9]+)?([eE][+-]?[0-9]+)?
+    fn parse_number(&mut self) -> Result<JsonValue, ParseError> {
+        let start = self.position;
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+        match self.peek() {
+            Some(b'0') => self.position += 1,
+            Some(b'1'..=b'9') => {
+                self.skip_digits();
+            }
+            _ => return Err(self.error_at(start, ErrorKind::InvalidNumber)),
+        }
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            if self.skip_digits() == 0 {
+                return Err(self.error_at(start, ErrorKind::InvalidNumber));
+            }
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            self.position += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.position += 1;
+            }
+            if self.skip_digits() == 0 {
+                return Err(self.error_at(start, ErrorKind::InvalidNumber));
+            }
+        }
+        match self.text[start..self.position].parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(JsonValue::Number(value)),
+            _ => Err(self.error_at(start, ErrorKind::InvalidNumber)),
+        }
+    }
+}
+
+pub fn parse(text: &str) -> Result<JsonValue, ParseError> {
+    let mut parser = Parser {
+        text,
+        position: 0,
+        depth: 0,
+    };
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.position < text.len() {
+        return Err(parser.error(ErrorKind::TrailingCharacters));
+    }
+    Ok(value)
+}
+
+fn write_string(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+// Integers are written without a fraction, very large and very small numbers
+// with an exponent. JSON has no representation for infinities and NaN.
+fn write_number(out: &mut String, value: f64) {
+    let magnitude = value.abs();
+    if !value.is_finite() {
+        out.push_str("null");
+    } else if value.fract() == 0.0 && magnitude < 1e15 {
+        out.push_str(&(value as i64).to_string());
+    } else if !(1e-6..1e15).contains(&magnitude) {
+        out.push_str(&format!("{:e}", value));
+    } else {
+        out.push_str(&value.to_string());
+    }
+}
+
+impl JsonValue {
+    pub fn is_null(&self) -> bool {
+        matches!(self, JsonValue::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonValue::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    // Only numbers without a fraction, which fit exactly.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            JsonValue::Number(value) if value.fract() == 0.0 && value.abs() < 9.007e15 => {
+                Some(*value as i64)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[JsonValue]> {
+        match self {
+            JsonValue::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, JsonValue)]> {
+        match self {
+            JsonValue::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        self.as_object()?
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+    }
+
+    pub fn to_pretty(&self, indent: usize) -> String {
+        let mut out = String::new();
+        self.write(&mut out, Some(indent), 0);
+        out
+    }
+
+    // Without an indentation, everything is written on a single line, without
+    // any whitespace. Empty arrays and objects are never broken into lines.
+    fn write(&self, out: &mut String, indent: Option<usize>, level: usize) {
+        let newline = |out: &mut String, level: usize| {
+            if let Some(width) = indent {
+                out.push('\n');
+                out.push_str(&" ".repeat(width * level));
+            }
+        };
+        match self {
+            JsonValue::Null => out.push_str("null"),
+            JsonValue::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+            JsonValue::Number(value) => write_number(out, *value),
+            JsonValue::String(value) => write_string(out, value),
+            JsonValue::Array(elements) if elements.is_empty() => out.push_str("[]"),
+            JsonValue::Object(members) if members.is_empty() => out.push_str("{}"),
+            JsonValue::Array(elements) => {
+                out.push('[');
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    newline(out, level + 1);
+                    element.write(out, indent, level + 1);
+                }
+                newline(out, level);
+                out.push(']');
+            }
+            JsonValue::Object(members) => {
+                out.push('{');
+                for (index, (key, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    newline(out, level + 1);
+                    write_string(out, key);
+                    out.push(':');
+                    if indent.is_some() {
+                        out.push(' ');
+                    }
+                    value.write(out, indent, level + 1);
+                }
+                newline(out, level);
+                out.push('}');
+            }
+        }
+    }
+}
+
+// The tokens of a JSON Pointer, which is either empty, for the whole
+// document, or a slash before each token. In the tokens, ~1 stands for a
+// slash, and ~0 for a tilde, and a tilde is not allowed otherwise.
+fn pointer_tokens(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    let mut tokens = Vec::new();
+    for escaped in pointer.strip_prefix('/')?.split('/') {
+        let mut token = String::with_capacity(escaped.len());
+        let mut chars = escaped.chars();
+        while let Some(c) = chars.next() {
+            if c != '~' {
+                token.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('0') => token.push('~'),
+                Some('1') => token.push('/'),
+                _ => return None,
+            }
+        }
+        tokens.push(token);
+    }
+    Some(tokens)
+}
+
+pub fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+// An index of an array is written without leading zeros, and the - of the
+// RFC, the element after the last one, never exists.
+fn pointer_index(token: &str) -> Option<usize> {
+    let canonical = token == "0"
+        || (!token.starts_with('0') && token.bytes().all(|byte| byte.is_ascii_digit()));
+    canonical.then(|| token.parse().ok()).flatten()
+}
+
+fn pointer_step<'a>(value: &'a JsonValue, token: &str) -> Option<&'a JsonValue> {
+    match value {
+        JsonValue::Object(_) => value.get(token),
+        JsonValue::Array(elements) => elements.get(pointer_index(token)?),
+        _ => None,
+    }
+}
+
+fn pointer_step_mut<'a>(value: &'a mut JsonValue, token: &str) -> Option<&'a mut JsonValue> {
+    match value {
+        JsonValue::Object(members) => members
+            .iter_mut()
+            .find(|(key, _)| key == token)
+            .map(|(_, value)| value),
+        JsonValue::Array(elements) => elements.get_mut(pointer_index(token)?),
+        _ => None,
+    }
+}
+
+impl JsonValue {
+    // The value which the pointer refers to, or nothing, if the pointer is
+    // malformed, or there is no such value.
+    pub fn pointer(&self, pointer: &str) -> Option<&JsonValue> {
+        pointer_tokens(pointer)?
+            .iter()
+            .try_fold(self, |value, token| pointer_step(value, token))
+    }
+
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut JsonValue> {
+        pointer_tokens(pointer)?
+            .iter()
+            .try_fold(self, |value, token| pointer_step_mut(value, token))
+    }
+
+    // The values matching the pattern, with the pointers to them, in the
+    // order of the document. A member which is literally called * can only
+    // be reached by a pointer.
+    pub fn query(&self, pattern: &str) -> Vec<(String, &JsonValue)> {
+        let Some(tokens) = pointer_tokens(pattern) else {
+            return Vec::new();
+        };
+        let mut matches = vec![(String::new(), self)];
+        for token in tokens {
+            let mut next = Vec::new();
+            for (path, value) in matches {
+                match (token.as_str(), value) {
+                    ("*", JsonValue::Array(elements)) => {
+                        for (index, element) in elements.iter().enumerate() {
+                            next.push((format!("{}/{}", path, index), element));
+                        }
+                    }
+                    ("*", JsonValue::Object(members)) => {
+                        for (key, member) in members {
+                            next.push((format!("{}/{}", path, escape_pointer_token(key)), member));
+                        }
+                    }
+                    _ => {
+                        if let Some(child) = pointer_step(value, &token) {
+                            next.push((
+                                format!("{}/{}", path, escape_pointer_token(&token)),
+                                child,
+                            ));
+                        }
+                    }
+                }
+            }
+            matches = next;
+        }
+        matches
+    }
+}
+
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut out = String::new();
+        self.write(&mut out, None, 0);
+        f.write_str(&out)
+    }
+}
+
+impl FromStr for JsonValue {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        parse(text)
+    }
+}
+
+static NULL: JsonValue = JsonValue::Null;
+
+// Missing members and elements are null, so lookups can be chained.
+impl Index<&str> for JsonValue {
+    type Output = JsonValue;
+
+    fn index(&self, key: &str) -> &JsonValue {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+impl Index<usize> for JsonValue {
+    type Output = JsonValue;
+
+    fn index(&self, index: usize) -> &JsonValue {
+        self.as_array()
+            .and_then(|elements| elements.get(index))
+            .unwrap_or(&NULL)
+    }
+}
+
+impl From<bool> for JsonValue {
+    fn from(value: bool) -> Self {
+        JsonValue::Bool(value)
+    }
+}
+
+impl From<f64> for JsonValue {
+    fn from(value: f64) -> Self {
+        JsonValue::Number(value)
+    }
+}
+
+impl From<i32> for JsonValue {
+    fn from(value: i32) -> Self {
+        JsonValue::Number(value as f64)
+    }
+}
+
+impl From<&str> for JsonValue {
+    fn from(value: &str) -> Self {
+        JsonValue::String(value.to_string())
+    }
+}
+
+impl From<String> for JsonValue {
+    fn from(value: String) -> Self {
+        JsonValue::String(value)
+    }
+}
+
+impl From<Vec<JsonValue>> for JsonValue {
+    fn from(elements: Vec<JsonValue>) -> Self {
+        JsonValue::Array(elements)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MappingErrorKind {
+    Missing,
+    WrongType(&'static str),
+    OutOfRange,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MappingError {
+    pub kind: MappingErrorKind,
+    pub pointer: String,
+}
+
+impl MappingError {
+    pub fn new(kind: MappingErrorKind) -> Self {
+        Self {
+            kind,
+            pointer: String::new(),
+        }
+    }
+
+    // The error of a nested value, seen from its parent.
+    pub fn within(mut self, token: &str) -> Self {
+        self.pointer = format!("/{}{}", escape_pointer_token(token), self.pointer);
+        self
+    }
+}
+
+impl fmt::Display for MappingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            MappingErrorKind::Missing => write!(f, "missing member")?,
+            MappingErrorKind::WrongType(expected) => write!(f, "expected {}", expected)?,
+            MappingErrorKind::OutOfRange => write!(f, "number out of range")?,
+        }
+        match self.pointer.as_str() {
+            "" => write!(f, " at the root"),
+            pointer => write!(f, " at {}", pointer),
+        }
+    }
+}
+
+impl std::error::Error for MappingError {}
+
+pub trait ToJson {
+    fn to_json(&self) -> JsonValue;
+}
+
+pub trait FromJson: Sized {
+    fn from_json(value: &JsonValue) -> Result<Self, MappingError>;
+
+    // The value of a missing member, if it may be missing.
+    fn missing() -> Option<Self> {
+        None
+    }
+}
+
+fn wrong_type(expected: &'static str) -> MappingError {
+    MappingError::new(MappingErrorKind::WrongType(expected))
+}
+
+impl ToJson for JsonValue {
+    fn to_json(&self) -> JsonValue {
+        self.clone()
+    }
+}
+
+impl FromJson for JsonValue {
+    fn from_json(value: &JsonValue) -> Result<Self, MappingError> {
+        Ok(value.clone())
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Bool(*self)
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &JsonValue) -> Result<Self, MappingError> {
+        value.as_bool().ok_or(wrong_type("a boolean"))
+    }
+}
+
+impl ToJson for f64 {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Number(*self)
+    }
+}
+
+impl FromJson for f64 {
+    fn from_json(value: &JsonValue) -> Result<Self, MappingError> {
+        value.as_f64().ok_or(wrong_type("a number"))
+    }
+}
+
+// The integers are exact in a double up to 2^53, and the larger ones are
+// out of range, even if they fit the type.
+macro_rules! impl_integer {
+    ($($type:ty),*) => {
+        $(
+            impl ToJson for $type {
+                fn to_json(&self) -> JsonValue {
+                    JsonValue::Number(*self as f64)
+                }
+            }
+
+            impl FromJson for $type {
+                fn from_json(value: &JsonValue) -> Result<Self, MappingError> {
+                    let number = value.as_f64().ok_or(wrong_type("a number"))?;
+                    if number.fract() != 0.0 {
+                        return Err(wrong_type("an integer"));
+                    }
+                    if number.abs() > 9_007_199_254_740_992.0
+                        || number < <$type>::MIN as f64
+                        || number > <$type>::MAX as f64
+                    {
+                        return Err(MappingError::new(MappingErrorKind::OutOfRange));
+                    }
+                    Ok(number as $type)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i32, i64, u8, u16, u32, u64, usize);
+
+impl ToJson for str {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(self.to_string())
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(self.clone())
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: &JsonValue) -> Result<Self, MappingError> {
+        value
+            .as_str()
+            .map(String::from)
+            .ok_or(wrong_type("a string"))
+    }
+}
+
+// None is null, and a member which is missing is None, too.
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            Some(value) => value.to_json(),
+            None => JsonValue::Null,
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &JsonValue) -> Result<Self, MappingError> {
+        match value {
+            JsonValue::Null => Ok(None),
+            value => T::from_json(value).map(Some),
+        }
+    }
+
+    fn missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> JsonValue {
+        self.as_slice().to_json()
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &JsonValue) -> Result<Self, MappingError> {
+        let elements = value.as_array().ok_or(wrong_type("an array"))?;
+        elements
+            .iter()
+            .enumerate()
+            .map(|(index, element)| {
+                T::from_json(element).map_err(|error| error.within(&index.to_string()))
+            })
+            .collect()
+    }
+}
+
+impl<T: ToJson> ToJson for BTreeMap<String, T> {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Object(
+            self.iter()
+                .map(|(key, value)| (key.clone(), value.to_json()))
+                .collect(),
+        )
+    }
+}
+
+impl<T: FromJson> FromJson for BTreeMap<String, T> {
+    fn from_json(value: &JsonValue) -> Result<Self, MappingError> {
+        let members = value.as_object().ok_or(wrong_type("an object"))?;
+        members
+            .iter()
+            .map(|(key, value)| match T::from_json(value) {
+                Ok(value) => Ok((key.clone(), value)),
+                Err(error) => Err(error.within(key)),
+            })
+            .collect()
+    }
+}
+
+// The member of an object, for the from_json_fields! macro.
+pub fn member<T: FromJson>(object: &JsonValue, key: &str) -> Result<T, MappingError> {
+    match object.get(key) {
+        Some(value) => T::from_json(value).map_err(|error| error.within(key)),
+        None => T::missing().ok_or(MappingError::new(MappingErrorKind::Missing).within(key)),
+    }
+}
+
+pub fn expect_object(value: &JsonValue) -> Result<&JsonValue, MappingError> {
+    match value {
+        JsonValue::Object(_) => Ok(value),
+        _ => Err(wrong_type("an object")),
+    }
+}
+
+// An object, with the members in the given order, from anything which has
+// a JSON form: json_object! { "name": self.name, "tags": self.tags }.
+#[macro_export]
+macro_rules! json_object {
+    ($($key:literal : $value:expr),* $(,)?) => {
+        $crate::json::JsonValue::Object(vec![
+            $((String::from($key), $crate::json::ToJson::to_json(&$value)),)*
+        ])
+    };
+}
+
+// A structure, from the members of an object, which are named after the
+// fields, unless they are given a key:
+// from_json_fields!(value, Point { x, y, label: "name" }).
+#[macro_export]
+macro_rules! from_json_fields {
+    ($value:expr, $type:ident { $($field:ident $(: $key:literal)?),* $(,)? }) => {
+        $crate::json::expect_object($value).and_then(
+            |object| -> Result<$type, $crate::json::MappingError> {
+                Ok($type {
+                    $($field: $crate::json::member(
+                        object,
+                        $crate::from_json_fields!(@key $field $($key)?),
+                    )?,)*
+                })
+            },
+        )
+    };
+    (@key $field:ident) => {
+        stringify!($field)
+    };
+    (@key $field:ident $key:literal) => {
+        $key
+    };
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+const DOCUMENT: &str = r#"{
+    "name": "snippets",
+    "version": 1.5,
+    "tags": ["rust", "json", "\u00e1rv\u00edz"],
+    "emoji": "\ud83e\udd80",
+    "escapes": "tab\tquote\"slash\\/",
+    "nested": {"empty": [], "object": {}, "flag": false, "nothing": null},
+    "numbers": [0, -12, 3.25e2, 1e-9, 6.02214076e23]
+}"#;
+
+// The structures of the mapping demonstration, each with its own JSON shape.
+#[derive(Clone, Debug, PartialEq)]
+struct Author {
+    name: String,
+    email: Option<String>,
+    commits: u32,
+}
+
+impl ToJson for Author {
+    fn to_json(&self) -> JsonValue {
+        json_object! { "name": self.name, "email": self.email, "commits": self.commits }
+    }
+}
+
+impl FromJson for Author {
+    fn from_json(value: &JsonValue) -> Result<Self, MappingError> {
+        from_json_fields!(
+            value,
+            Author {
+                name,
+                email,
+                commits
+            }
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Project {
+    name: String,
+    version: f64,
+    stable: bool,
+    tags: Vec<String>,
+    authors: Vec<Author>,
+    downloads: BTreeMap<String, u64>,
+}
+
+impl ToJson for Project {
+    fn to_json(&self) -> JsonValue {
+        json_object! {
+            "name": self.name,
+            "version": self.version,
+            "is_stable": self.stable,
+            "tags": self.tags,
+            "authors": self.authors,
+            "downloads": self.downloads,
+        }
+    }
+}
+
+impl FromJson for Project {
+    fn from_json(value: &JsonValue) -> Result<Self, MappingError> {
+        from_json_fields!(
+            value,
+            Project {
+                name,
+                version,
+                stable: "is_stable",
+                tags,
+                authors,
+                downloads
+            }
+        )
+    }
+}
+
+pub fn demo(harness: &mut Harness) {
+    let value = parse(DOCUMENT).unwrap();
+    test!(
+        harness,
+        "members are looked up by key",
+        (value["name"].as_str(), value["version"].as_f64()),
+        (Some("snippets"), Some(1.5))
+    );
+    test!(
+        harness,
+        "unicode escapes are decoded",
+        value["tags"][2].as_str(),
+        Some("árvíz")
+    );
+    test!(
+        harness,
+        "surrogate pairs are decoded",
+        value["emoji"].as_str(),
+        Some("🦀")
+    );
+    test!(
+        harness,
+        "simple escapes are decoded",
+        value["escapes"].as_str(),
+        Some("tab\tquote\"slash\\/")
+    );
+    test!(
+        harness,
+        "numbers are parsed with exponents",
+        value["numbers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|number| number.as_f64().unwrap())
+            .collect::<Vec<_>>(),
+        vec![0.0, -12.0, 325.0, 1e-9, 6.02214076e23]
+    );
+    test!(
+        harness,
+        "missing members and elements are null",
+        (
+            value["nested"]["missing"].is_null(),
+            value["tags"][9].is_null()
+        ),
+        (true, true)
+    );
+    test!(
+        harness,
+        "members keep their order",
+        value
+            .as_object()
+            .unwrap()
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect::<Vec<_>>(),
+        vec!["name", "version", "tags", "emoji", "escapes", "nested", "numbers"]
+    );
+    test!(
+        harness,
+        "a repeated key replaces the earlier value",
+        parse(r#"{"a": 1, "b": 2, "a": 3}"#).unwrap().to_string(),
+        r#"{"a":3,"b":2}"#
+    );
+    let keys: Vec<String> = (0..100_000)
+        .map(|i| format!("\"{}\": {}", i % 50_000, i))
+        .collect();
+    let large = parse(&format!("{{{}}}", keys.join(", "))).unwrap();
+    test!(
+        harness,
+        "the repeated keys of a large object",
+        (
+            large.as_object().map(|members| members.len()),
+            large.get("0").cloned()
+        ),
+        (Some(50_000), Some(JsonValue::Number(50_000.0)))
+    );
+
+    // The example of RFC 6901.
+    let example = parse(
+        r#"{"foo": ["bar", "baz"], "": 0, "a/b": 1, "c%d": 2, "e^f": 3,
+            "g|h": 4, "i\\j": 5, "k\"l": 6, " ": 7, "m~n": 8}"#,
+    )
+    .unwrap();
+    test!(
+        harness,
+        "the pointers of the RFC are resolved",
+        ["/foo/0", "/", "/a~1b", "/c%d", "/e^f", "/g|h", "/i\\j", "/k\"l", "/ ", "/m~0n"]
+            .map(|pointer| example.pointer(pointer).map(JsonValue::to_string)),
+        ["\"bar\"", "0", "1", "2", "3", "4", "5", "6", "7", "8"].map(|text| Some(text.to_string()))
+    );
+    let mut edited = example.clone();
+    if let Some(element) = edited.pointer_mut("/foo/1") {
+        *element = JsonValue::from("qux");
+    }
+    test!(
+        harness,
+        "a value is changed through a pointer",
+        edited["foo"].to_string(),
+        r#"["bar","qux"]"#
+    );
+    test!(
+        harness,
+        "the empty pointer is the whole document, and elements are indexed",
+        (
+            example.pointer("") == Some(&example),
+            example.pointer("/foo/1").and_then(JsonValue::as_str)
+        ),
+        (true, Some("baz"))
+    );
+    test!(
+        harness,
+        "malformed and dangling pointers resolve to nothing",
+        ["foo", "/foo/2", "/foo/-", "/foo/01", "/foo/+1", "/m~2n", "/foo/0/x"]
+            .map(|pointer| example.pointer(pointer)),
+        [None; 7]
+    );
+    test!(
+        harness,
+        "a query matches every element with a wildcard",
+        value
+            .query("/tags/*")
+            .into_iter()
+            .map(|(path, value)| format!("{}={}", path, value))
+            .collect::<Vec<_>>(),
+        vec!["/tags/0=\"rust\"", "/tags/1=\"json\"", "/tags/2=\"árvíz\""]
+    );
+    test!(
+        harness,
+        "a wildcard matches every member, and the query goes on below it",
+        value
+            .query("/*/flag")
+            .into_iter()
+            .map(|(path, value)| (path, value.clone()))
+            .collect::<Vec<_>>(),
+        vec![("/nested/flag".to_string(), JsonValue::Bool(false))]
+    );
+    test!(
+        harness,
+        "the keys are escaped in the paths of the matches",
+        example
+            .query("/*")
+            .into_iter()
+            .map(|(path, _)| path)
+            .filter(|path| path.contains('~'))
+            .collect::<Vec<_>>(),
+        vec!["/a~1b".to_string(), "/m~0n".to_string()]
+    );
+
+    let compact = value.to_string();
+    test!(
+        harness,
+        "the compact output has no whitespace",
+        compact.starts_with(r#"{"name":"snippets","version":1.5,"tags":["rust","#),
+        true
+    );
+    test!(
+        harness,
+        "the compact output parses back to the same value",
+        parse(&compact),
+        Ok(value.clone())
+    );
+    test!(
+        harness,
+        "the pretty output parses back to the same value",
+        parse(&value.to_pretty(4)),
+        Ok(value.clone())
+    );
+    test!(
+        harness,
+        "pretty printing with an indentation of two",
+        parse(r#"{"a":[1,{"b":null}],"c":[]}"#)
+            .unwrap()
+            .to_pretty(2),
+        "{\n  \"a\": [\n    1,\n    {\n      \"b\": null\n    }\n  ],\n  \"c\": []\n}"
+    );
+    test!(
+        harness,
+        "strings are escaped on output",
+        JsonValue::from("line\nbreak \"quoted\" \u{1}").to_string(),
+        r#""line\nbreak \"quoted\" \u0001""#
+    );
+
+    let deep = format!("{}{}", "[".repeat(MAX_DEPTH + 1), "]".repeat(MAX_DEPTH + 1));
+    let broken: [(&str, &str); 12] = [
+        ("", "unexpected end of input at line 1, column 1"),
+        ("[1, 2,]", "unexpected character ']' at line 1, column 7"),
+        ("{\"a\" 1}", "unexpected character '1' at line 1, column 6"),
+        (
+            "{\"a\": 1,\n \"b\"",
+            "unexpected end of input at line 2, column 5",
+        ),
+        (
+            "{\n  \"key\": \"value\",\n  key: 1\n}",
+            "unexpected character 'k' at line 3, column 3",
+        ),
+        (
+            "01",
+            "trailing characters after the value at line 1, column 2",
+        ),
+        ("-.5", "invalid number at line 1, column 1"),
+        ("[1e400]", "invalid number at line 1, column 2"),
+        ("\"\\x\"", "invalid escape sequence \\x at line 1, column 2"),
+        (
+            "\"\\ud800\"",
+            "invalid unicode escape sequence at line 1, column 2",
+        ),
+        (
+            "\"tab\there\"",
+            "unescaped control character in string at line 1, column 5",
+        ),
+        ("tru", "unexpected end of input at line 1, column 4"),
+    ];
+    let mut rows = Vec::new();
+    for (text, expected) in broken {
+        let error = parse(text).unwrap_err().to_string();
+        test!(
+            harness,
+            &format!("the error in {:?}", text),
+            error.clone(),
+            expected
+        );
+        rows.push((text, error));
+    }
+    test!(
+        harness,
+        "nesting is limited",
+        parse(&deep).map_err(|error| error.kind),
+        Err(ErrorKind::TooDeep)
+    );
+    test!(
+        harness,
+        "nesting up to the limit is allowed",
+        parse(&deep[1..deep.len() - 1]).is_ok(),
+        true
+    );
+
+    let project = Project {
+        name: "snippets".to_string(),
+        version: 1.5,
+        stable: true,
+        tags: vec!["rust".to_string(), "json".to_string()],
+        authors: vec![
+            Author {
+                name: "Dénes Fintha".to_string(),
+                email: Some("dfintha@example.com".to_string()),
+                commits: 1200,
+            },
+            Author {
+                name: "Ada".to_string(),
+                email: None,
+                commits: 3,
+            },
+        ],
+        downloads: BTreeMap::from([("2025".to_string(), 150), ("2026".to_string(), 4200)]),
+    };
+    let mapped = project.to_json();
+    test!(
+        harness,
+        "a structure is mapped to an object, with the members in order",
+        mapped.to_string(),
+        concat!(
+            r#"{"name":"snippets","version":1.5,"is_stable":true,"tags":["rust","json"],"#,
+            r#""authors":[{"name":"Dénes Fintha","email":"dfintha@example.com","commits":1200},"#,
+            r#"{"name":"Ada","email":null,"commits":3}],"downloads":{"2025":150,"2026":4200}}"#
+        )
+    );
+    test!(
+        harness,
+        "the object is mapped back to the same structure, through the text",
+        parse(&mapped.to_string()).map(|value| Project::from_json(&value)),
+        Ok(Ok(project.clone()))
+    );
+    test!(
+        harness,
+        "a missing optional member is None",
+        Author::from_json(&parse(r#"{"name": "Ada", "commits": 3}"#).unwrap()),
+        Ok(project.authors[1].clone())
+    );
+    // The object, with the member replaced, or removed, if it is null.
+    let broken = |pointer: &str, replacement: JsonValue| {
+        let mut value = mapped.clone();
+        let (parent, key) = pointer.rsplit_once('/').unwrap();
+        if let Some(JsonValue::Object(members)) = value.pointer_mut(parent) {
+            members.retain(|(name, _)| name != key);
+            if !replacement.is_null() {
+                members.push((key.to_string(), replacement));
+            }
+        }
+        Project::from_json(&value).unwrap_err().to_string()
+    };
+    test!(
+        harness,
+        "the mapping errors point at the wrong value",
+        [
+            broken("/authors/1/name", JsonValue::Null),
+            broken("/authors/0/commits", JsonValue::from(-1)),
+            broken("/authors/0/commits", JsonValue::from(2.5)),
+            broken("/tags", JsonValue::from(vec![JsonValue::from(1)])),
+            broken("/downloads/2026", JsonValue::from("many")),
+            broken("/is_stable", JsonValue::from("yes")),
+        ],
+        [
+            "missing member at /authors/1/name",
+            "number out of range at /authors/0/commits",
+            "expected an integer at /authors/0/commits",
+            "expected a string at /tags/0",
+            "expected a number at /downloads/2026",
+            "expected a boolean at /is_stable",
+        ]
+        .map(String::from)
+    );
+    test!(
+        harness,
+        "only an object is mapped to a structure",
+        Author::from_json(&JsonValue::from(vec![])).map_err(|error| error.to_string()),
+        Err("expected an object at the root".to_string())
+    );
+
+    note!(harness, "{}\n", value.to_pretty(2));
+    note!(harness, "{}\n", compact);
+    for (text, error) in rows {
+        note!(harness, "{:<40} {}", format!("{:?}", text), error);
+    }
+    note!(harness);
+}
@@ -1,113 +1,519 @@
-// leftpad.cpp
-// The infamous leftpad function, rewritten in Rust.
+// leftpad.rs
+// The infamous leftpad function, rewritten in Rust, along with its rightpad and
+// centerpad counterparts. Each of them has a variant that measures lengths in
+// terminal columns instead of bytes, for strings with wide or combining
+// characters. String fillers are repeated as a pattern. The Pad trait makes all
+// of these available as methods on anything that implements Display, and
+// leftpad_number zero pads numbers while keeping their sign in front.
 // Author: Dénes Fintha
 // Year: 2022
 // -------------------------------------------------------------------------- //
 
 // Implementation
 
-enum LeftpadFiller {
+use std::fmt::{self, Display};
+
+pub enum LeftpadFiller {
     Character(char),
     String(String),
     Number(i64),
 }
 
-fn leftpad(string: &str, length: isize, fill: LeftpadFiller) -> String {
-    let old_length = string.len() as isize;
+impl From<char> for LeftpadFiller {
+    fn from(c: char) -> Self {
+        LeftpadFiller::Character(c)
+    }
+}
+
+impl From<&str> for LeftpadFiller {
+    fn from(s: &str) -> Self {
+        LeftpadFiller::String(s.to_string())
+    }
+}
+
+impl From<String> for LeftpadFiller {
+    fn from(s: String) -> Self {
+        LeftpadFiller::String(s)
+    }
+}
+
+impl From<i32> for LeftpadFiller {
+    fn from(n: i32) -> Self {
+        LeftpadFiller::Number(n as i64)
+    }
+}
+
+impl From<i64> for LeftpadFiller {
+    fn from(n: i64) -> Self {
+        LeftpadFiller::Number(n)
+    }
+}
+
+// Anything that can be displayed can be padded, by padding its displayed form.
+// The display variants measure both the string and the fill in terminal
+// columns instead of bytes, so wide (e.g. CJK or emoji) and zero-width (e.g.
+// combining) characters line up as they would on screen.
+pub trait Pad {
+    fn leftpad(&self, length: isize, fill: impl Into<LeftpadFiller>) -> String;
+    fn rightpad(&self, length: isize, fill: impl Into<LeftpadFiller>) -> String;
+    fn centerpad(&self, length: isize, fill: impl Into<LeftpadFiller>) -> String;
+    fn leftpad_display(&self, length: isize, fill: impl Into<LeftpadFiller>) -> String;
+    fn rightpad_display(&self, length: isize, fill: impl Into<LeftpadFiller>) -> String;
+    fn centerpad_display(&self, length: isize, fill: impl Into<LeftpadFiller>) -> String;
+}
+
+impl<T: Display + ?Sized> Pad for T {
+    fn leftpad(&self, length: isize, fill: impl Into<LeftpadFiller>) -> String {
+        pad(&self.to_string(), length, &fill.into(), Side::Left, false)
+    }
+
+    fn rightpad(&self, length: isize, fill: impl Into<LeftpadFiller>) -> String {
+        pad(&self.to_string(), length, &fill.into(), Side::Right, false)
+    }
+
+    fn centerpad(&self, length: isize, fill: impl Into<LeftpadFiller>) -> String {
+        pad(&self.to_string(), length, &fill.into(), Side::Both, false)
+    }
+
+    fn leftpad_display(&self, length: isize, fill: impl Into<LeftpadFiller>) -> String {
+        pad(&self.to_string(), length, &fill.into(), Side::Left, true)
+    }
+
+    fn rightpad_display(&self, length: isize, fill: impl Into<LeftpadFiller>) -> String {
+        pad(&self.to_string(), length, &fill.into(), Side::Right, true)
+    }
+
+    fn centerpad_display(&self, length: isize, fill: impl Into<LeftpadFiller>) -> String {
+        pad(&self.to_string(), length, &fill.into(), Side::Both, true)
+    }
+}
+
+pub fn leftpad(string: &str, length: isize, fill: LeftpadFiller) -> String {
+    string.leftpad(length, fill)
+}
+
+pub fn rightpad(string: &str, length: isize, fill: LeftpadFiller) -> String {
+    string.rightpad(length, fill)
+}
+
+pub fn centerpad(string: &str, length: isize, fill: LeftpadFiller) -> String {
+    string.centerpad(length, fill)
+}
+
+pub fn leftpad_display(string: &str, length: isize, fill: LeftpadFiller) -> String {
+    string.leftpad_display(length, fill)
+}
+
+pub fn rightpad_display(string: &str, length: isize, fill: LeftpadFiller) -> String {
+    string.rightpad_display(length, fill)
+}
+
+pub fn centerpad_display(string: &str, length: isize, fill: LeftpadFiller) -> String {
+    string.centerpad_display(length, fill)
+}
+
+// Zero padding a number has to keep its sign in front of the zeros, which a
+// plain filler can not do.
+pub fn leftpad_number(value: impl Display, width: usize) -> String {
+    let string = value.to_string();
+    let (sign, digits) = match string.strip_prefix(['-', '+']) {
+        Some(digits) => (&string[..1], digits),
+        None => ("", string.as_str()),
+    };
+    let length = width.saturating_sub(sign.len()) as isize;
+    format!("{}{}", sign, digits.leftpad(length, '0'))
+}
+
+pub fn display_width(string: &str) -> usize {
+    // This is an approximation of grapheme clusters: characters joined by a
+    // zero-width joiner, and pairs of regional indicators (flags) are taken
+    // as a single wide character.
+    let mut width = 0;
+    let mut joined = false;
+    let mut pending_flag = false;
+    for c in string.chars() {
+        if joined {
+            joined = false;
+            continue;
+        }
+        if c == '\u{200D}' {
+            joined = true;
+            continue;
+        }
+        if is_regional_indicator(c) {
+            pending_flag = !pending_flag;
+            if !pending_flag {
+                continue;
+            }
+        } else {
+            pending_flag = false;
+        }
+        width += char_width(c);
+    }
+    width
+}
+
+pub fn char_width(c: char) -> usize {
+    #[rustfmt::skip]
+    const ZERO_WIDTH: &[(u32, u32)] = &[
+        (0x0300, 0x036F), (0x0483, 0x0489), (0x0591, 0x05BD), (0x0610, 0x061A),
+        (0x064B, 0x065F), (0x1AB0, 0x1AFF), (0x1DC0, 0x1DFF), (0x200B, 0x200F),
+        (0x20D0, 0x20FF), (0xFE00, 0xFE0F), (0xFE20, 0xFE2F), (0xFEFF, 0xFEFF),
+        (0x1F3FB, 0x1F3FF), (0xE0100, 0xE01EF),
+    ];
+    #[rustfmt::skip]
+    const WIDE: &[(u32, u32)] = &[
+        (0x1100, 0x115F), (0x231A, 0x231B), (0x2329, 0x232A), (0x23E9, 0x23EC),
+        (0x23F0, 0x23F0), (0x23F3, 0x23F3), (0x25FD, 0x25FE), (0x2614, 0x2615),
+        (0x2648, 0x2653), (0x267F, 0x267F), (0x2693, 0x2693), (0x26A1, 0x26A1),
+        (0x26AA, 0x26AB), (0x26BD, 0x26BE), (0x26C4, 0x26C5), (0x26CE, 0x26CE),
+        (0x26D4, 0x26D4), (0x26EA, 0x26EA), (0x26F2, 0x26F3), (0x26F5, 0x26F5),
+        (0x26FA, 0x26FA), (0x26FD, 0x26FD), (0x2705, 0x2705), (0x270A, 0x270B),
+        (0x2728, 0x2728), (0x274C, 0x274C), (0x274E, 0x274E), (0x2753, 0x2755),
+        (0x2757, 0x2757), (0x2795, 0x2797), (0x27B0, 0x27B0), (0x27BF, 0x27BF),
+        (0x2B1B, 0x2B1C), (0x2B50, 0x2B50), (0x2B55, 0x2B55), (0x2E80, 0x303E),
+        (0x3041, 0x33FF), (0x3400, 0x4DBF), (0x4E00, 0x9FFF), (0xA000, 0xA4CF),
+        (0xA960, 0xA97F), (0xAC00, 0xD7A3), (0xF900, 0xFAFF), (0xFE10, 0xFE19),
+        (0xFE30, 0xFE6F), (0xFF00, 0xFF60), (0xFFE0, 0xFFE6), (0x1F004, 0x1F004),
+        (0x1F0CF, 0x1F0CF), (0x1F18E, 0x1F18E), (0x1F191, 0x1F19A),
+        (0x1F1E6, 0x1F1FF), (0x1F200, 0x1F2FF), (0x1F300, 0x1F64F),
+        (0x1F680, 0x1F6FF), (0x1F900, 0x1F9FF), (0x1FA70, 0x1FAFF),
+        (0x20000, 0x2FFFD), (0x30000, 0x3FFFD),
+    ];
+
+    let code = c as u32;
+    let within = |ranges: &[(u32, u32)]| {
+        ranges
+            .iter()
+            .any(|&(first, last)| first <= code && code <= last)
+    };
+
+    if code < 0x20 || (0x7F..0xA0).contains(&code) || within(ZERO_WIDTH) {
+        0
+    } else if within(WIDE) {
+        2
+    } else {
+        1
+    }
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    Left,
+    Right,
+    Both,
+}
+
+fn pad(string: &str, length: isize, fill: &LeftpadFiller, side: Side, display: bool) -> String {
+    let old_length = if display {
+        display_width(string)
+    } else {
+        string.len()
+    };
+    let pad_length = pad_length(old_length, length);
+
+    // When the padding can not be split evenly, the extra character goes to
+    // the right side.
+    let (left_length, right_length) = match side {
+        Side::Left => (pad_length, 0),
+        Side::Right => (0, pad_length),
+        Side::Both => (pad_length / 2, pad_length - pad_length / 2),
+    };
+
+    let mut result = padding(fill, left_length, display);
+    result.push_str(string);
+    result.push_str(&padding(fill, right_length, display));
+    result
+}
+
+fn pad_length(old_length: usize, length: isize) -> usize {
+    let old_length = old_length as isize;
     let new_length = if length < old_length {
         old_length
     } else {
         length
     };
-    let pad_length = new_length - old_length;
-
-    let mut result = String::with_capacity(new_length as usize);
+    (new_length - old_length) as usize
+}
 
-    let fill = match fill {
-        LeftpadFiller::Character(c) => c,
-        LeftpadFiller::String(s) => s.chars().nth(0).unwrap_or(' '),
-        LeftpadFiller::Number(n) => n.to_string().chars().nth(0).unwrap_or(' '),
+fn padding(fill: &LeftpadFiller, pad_length: usize, display: bool) -> String {
+    // String fillers are repeated as a pattern, and the last repetition is
+    // truncated to fit the padding.
+    let pattern: Vec<char> = match fill {
+        LeftpadFiller::Character(c) => vec![*c],
+        LeftpadFiller::String(s) => s.chars().collect(),
+        LeftpadFiller::Number(n) => n.to_string().chars().take(1).collect(),
+    };
+    let pattern = if pattern.is_empty() {
+        vec![' ']
+    } else {
+        pattern
     };
 
-    for _ in 0..pad_length {
-        result.push(fill);
+    let mut result = String::with_capacity(pad_length);
+    let mut width = 0;
+    for fill in pattern.iter().cycle() {
+        let fill_width = if display { char_width(*fill).max(1) } else { 1 };
+        if width + fill_width > pad_length {
+            break;
+        }
+        result.push(*fill);
+        width += fill_width;
+    }
+
+    // A wide filler may not fit into the last column, which is filled with a
+    // space instead.
+    while width < pad_length {
+        result.push(' ');
+        width += 1;
     }
-    result.push_str(string);
     result
 }
 
 // Demonstration
 
-macro_rules! test {
-    ($desc:expr, $string:expr, $len:expr, $fill:expr, $expected:expr) => {
-        println!("Testing if {}", $desc);
-        let subject = leftpad($string, $len, $fill);
-        println!("  Expected: '{}'", $expected);
-        println!("       Got: '{}'", subject);
-        let verdict = if subject == String::from($expected) {
-            "Passed"
-        } else {
-            "Failed"
-        };
-        println!("    Result: {}\n", verdict);
-    };
+use crate::harness::Harness;
+use std::path::Path;
+
+struct Version(u32, u32);
+
+impl Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "v{}.{}", self.0, self.1)
+    }
 }
 
-fn main() {
+pub fn demo(harness: &mut Harness) {
     test!(
+        harness,
         "padding an empty string to a length to 0 results in an empty string",
-        "",
-        0,
-        LeftpadFiller::Character(' '),
+        leftpad("", 0, LeftpadFiller::Character(' ')),
         ""
     );
     test!(
+        harness,
         "padding to a shorter length results in the same string",
-        "foo",
-        2,
-        LeftpadFiller::Character(' '),
+        leftpad("foo", 2, LeftpadFiller::Character(' ')),
         "foo"
     );
     test!(
+        harness,
         "padding to a negative length results in the same string",
-        "foo",
-        -2,
-        LeftpadFiller::Character(' '),
+        leftpad("foo", -2, LeftpadFiller::Character(' ')),
         "foo"
     );
     test!(
+        harness,
         "padding a non-empty string to its length results in the same string",
-        "foo",
-        3,
-        LeftpadFiller::Character(' '),
+        leftpad("foo", 3, LeftpadFiller::Character(' ')),
         "foo"
     );
     test!(
+        harness,
         "padding to a longer string with a single character fills to the left",
-        "foo",
-        4,
-        LeftpadFiller::Character('_'),
+        leftpad("foo", 4, LeftpadFiller::Character('_')),
         "_foo"
     );
     test!(
+        harness,
         "padding to a longer string with a number fills with its first digit",
-        "foo",
-        4,
-        LeftpadFiller::Number(12),
+        leftpad("foo", 4, LeftpadFiller::Number(12)),
         "1foo"
     );
     test!(
+        harness,
         "padding to a longer string with a negative number fills with -",
-        "foo",
-        4,
-        LeftpadFiller::Number(-12),
+        leftpad("foo", 4, LeftpadFiller::Number(-12)),
         "-foo"
     );
     test!(
-        "padding to a longer string with a string fills with its first char",
-        "foo",
-        4,
-        LeftpadFiller::String("abc".to_string()),
+        harness,
+        "padding by one with a string fills with its first char",
+        leftpad("foo", 4, LeftpadFiller::String("abc".to_string())),
         "afoo"
     );
+    test!(
+        harness,
+        "right padding to a longer string fills to the right",
+        rightpad("foo", 5, LeftpadFiller::Character('_')),
+        "foo__"
+    );
+    test!(
+        harness,
+        "right padding to a shorter length results in the same string",
+        rightpad("foo", -2, LeftpadFiller::Character('_')),
+        "foo"
+    );
+    test!(
+        harness,
+        "center padding with an even pad length splits it evenly",
+        centerpad("foo", 7, LeftpadFiller::Character('_')),
+        "__foo__"
+    );
+    test!(
+        harness,
+        "center padding with an odd pad length puts the extra to the right",
+        centerpad("foo", 6, LeftpadFiller::Character('_')),
+        "_foo__"
+    );
+    test!(
+        harness,
+        "center padding with a pad length of one pads to the right only",
+        centerpad("foo", 4, LeftpadFiller::Number(7)),
+        "foo7"
+    );
+    test!(
+        harness,
+        "padding a CJK string by bytes counts three bytes per character",
+        leftpad("日本", 6, LeftpadFiller::Character('_')),
+        "日本"
+    );
+    test!(
+        harness,
+        "padding a CJK string by display width counts two columns per character",
+        leftpad_display("日本", 6, LeftpadFiller::Character('_')),
+        "__日本"
+    );
+    test!(
+        harness,
+        "padding with a wide filler leaves no gaps for even pad lengths",
+        leftpad_display("foo", 7, LeftpadFiller::Character('＿')),
+        "＿＿foo"
+    );
+    test!(
+        harness,
+        "padding with a wide filler fills the odd column with a space",
+        leftpad_display("foo", 6, LeftpadFiller::Character('＿')),
+        "＿ foo"
+    );
+    test!(
+        harness,
+        "padding an emoji by display width counts two columns",
+        rightpad_display("🦀", 4, LeftpadFiller::Character('_')),
+        "🦀__"
+    );
+    test!(
+        harness,
+        "padding an emoji sequence joined by ZWJ counts it as a single emoji",
+        leftpad_display("👨\u{200D}👩\u{200D}👧", 3, LeftpadFiller::Character('_')),
+        "_👨\u{200D}👩\u{200D}👧"
+    );
+    test!(
+        harness,
+        "padding a flag counts it as a single emoji",
+        centerpad_display("🇭🇺", 6, LeftpadFiller::Character('_')),
+        "__🇭🇺__"
+    );
+    test!(
+        harness,
+        "padding a string with combining marks ignores the marks",
+        leftpad_display("e\u{301}te\u{301}", 5, LeftpadFiller::Character('_')),
+        "__e\u{301}te\u{301}"
+    );
+    test!(
+        harness,
+        "padding with a string repeats it as a pattern",
+        leftpad("foo", 7, LeftpadFiller::String("ab".to_string())),
+        "ababfoo"
+    );
+    test!(
+        harness,
+        "padding with a string truncates the last repetition of the pattern",
+        leftpad("foo", 8, LeftpadFiller::String("ab".to_string())),
+        "ababafoo"
+    );
+    test!(
+        harness,
+        "right padding with a string starts the pattern after the string",
+        rightpad("foo", 8, LeftpadFiller::String("-=".to_string())),
+        "foo-=-=-"
+    );
+    test!(
+        harness,
+        "center padding with a string starts the pattern on both sides",
+        centerpad("foo", 10, LeftpadFiller::String("abc".to_string())),
+        "abcfooabca"
+    );
+    test!(
+        harness,
+        "padding with an empty string fills with spaces",
+        leftpad("foo", 5, LeftpadFiller::String(String::new())),
+        "  foo"
+    );
+    test!(
+        harness,
+        "padding with a mixed-width pattern counts columns",
+        leftpad_display("foo", 8, LeftpadFiller::String("a本".to_string())),
+        "a本a foo"
+    );
+    test!(
+        harness,
+        "padding a number through the trait pads its decimal form",
+        42.leftpad(5, '0'),
+        "00042"
+    );
+    test!(
+        harness,
+        "padding a floating-point number through the trait pads its displayed form",
+        1.5.rightpad(5, '0'),
+        "1.500"
+    );
+    test!(
+        harness,
+        "padding a path through the trait pads its displayed form",
+        Path::new("/tmp").display().leftpad(8, '.'),
+        "..../tmp"
+    );
+    test!(
+        harness,
+        "padding a custom type through the trait pads its displayed form",
+        Version(1, 2).centerpad(8, "-"),
+        "--v1.2--"
+    );
+    test!(
+        harness,
+        "padding a string through the trait is the same as the free function",
+        "foo".leftpad(6, "ab"),
+        leftpad("foo", 6, LeftpadFiller::String("ab".to_string()))
+    );
+    test!(
+        harness,
+        "zero padding a positive number fills with zeros",
+        leftpad_number(42, 5),
+        "00042"
+    );
+    test!(
+        harness,
+        "zero padding a negative number keeps the sign in front",
+        leftpad_number(-12, 5),
+        "-0012"
+    );
+    test!(
+        harness,
+        "zero padding a negative number with the Number filler can not do that",
+        (-12).leftpad(5, 0),
+        "00-12"
+    );
+    test!(
+        harness,
+        "zero padding an explicitly signed number keeps the sign in front",
+        leftpad_number(format!("{:+}", 7), 4),
+        "+007"
+    );
+    test!(
+        harness,
+        "zero padding a floating-point number pads the integer part",
+        leftpad_number(-1.5, 6),
+        "-001.5"
+    );
+    test!(
+        harness,
+        "zero padding to a shorter width results in the same number",
+        leftpad_number(-12345, 3),
+        "-12345"
+    );
 }
@@ -0,0 +1,559 @@
+// lexer.rs
+// A table-driven lexer for the tokens of a C-like language. A table maps each
+// ASCII character to its class, which decides what kind of token starts with
+// it, and the operators are matched against a table, longest first. Numbers are
+// scanned like the C preprocessor does, taking every character that can be part
+// of one, and only then checked: this way, 123abc is a single invalid number,
+// instead of a number followed by an identifier. Strings and character literals
+// support the escape sequences of C, and both kinds of comments are tokens as
+// well. Every token knows its span of bytes in the source. Invalid input turns
+// into an error token, and the lexing continues after it, so a single pass can
+// report all of the problems.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnexpectedCharacter,
+    InvalidNumber,
+    InvalidEscape,
+    UnterminatedString,
+    UnterminatedCharacter,
+    InvalidCharacterLiteral,
+    UnterminatedComment,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedCharacter => write!(f, "unexpected character"),
+            ErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ErrorKind::InvalidEscape => write!(f, "invalid escape sequence"),
+            ErrorKind::UnterminatedString => write!(f, "unterminated string"),
+            ErrorKind::UnterminatedCharacter => write!(f, "unterminated character literal"),
+            ErrorKind::InvalidCharacterLiteral => {
+                write!(f, "a character literal has to contain one character")
+            }
+            ErrorKind::UnterminatedComment => write!(f, "unterminated comment"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKind {
+    Identifier,
+    Keyword,
+    Integer(u64),
+    Float(f64),
+    String(String),
+    Character(char),
+    Operator,
+    Comment,
+    Error(ErrorKind),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Range<usize>,
+}
+
+impl Token {
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.span.clone()]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Class {
+    Other,
+    Whitespace,
+    Letter,
+    Digit,
+    Quote,
+    Apostrophe,
+    Punctuation,
+}
+
+#[rustfmt::skip]
+const CLASSES: [Class; 128] = {
+    let mut classes = [Class::Other; 128];
+    let mut c = 0;
+    while c < 128 {
+        classes[c] = match c as u8 {
+            b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c => Class::Whitespace,
+            b'a'..=b'z' | b'A'..=b'Z' | b'_' => Class::Letter,
+            b'0'..=b'9' => Class::Digit,
+            b'"' => Class::Quote,
+            b'\'' => Class::Apostrophe,
+            b'!' | b'#' | b'%' | b'&' | b'('..=b'/' | b':'..=b'?' | b'[' | b']' | b'^'
+            | b'{'..=b'~' => Class::Punctuation,
+            _ => Class::Other,
+        };
+        c += 1;
+    }
+    classes
+};
+
+fn class(byte: u8) -> Class {
+    CLASSES.get(byte as usize).copied().unwrap_or(Class::Other)
+}
+
+#[rustfmt::skip]
+const OPERATORS: [&str; 48] = [
+    "<<=", ">>=", "...",
+    "->", "++", "--", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||", "+=", "-=",
+    "*=", "/=", "%=", "&=", "|=", "^=", "##",
+    "+", "-", "*", "/", "%", "=", "<", ">", "!", "&", "|", "^", "~", "?", ":", ";",
+    ",", ".", "(", ")", "[", "]", "{", "}", "#",
+];
+
+#[rustfmt::skip]
+pub const KEYWORDS: [&str; 32] = [
+    "auto", "break", "case", "char", "const", "continue", "default", "do", "double",
+    "else", "enum", "extern", "float", "for", "goto", "if", "int", "long", "register",
+    "return", "short", "signed", "sizeof", "static", "struct", "switch", "typedef",
+    "union", "unsigned", "void", "volatile", "while",
+];
+
+// Decodes the escape sequence after a backslash, returning the character
+// and the length of the sequence.
+fn escape(bytes: &[u8]) -> Option<(char, usize)> {
+    let simple = match bytes.first()? {
+        b'n' => '\n',
+        b't' => '\t',
+        b'r' => '\r',
+        b'a' => '\u{7}',
+        b'b' => '\u{8}',
+        b'f' => '\u{c}',
+        b'v' => '\u{b}',
+        c @ (b'\\' | b'\'' | b'"' | b'?') => *c as char,
+        b'x' => {
+            let digits = bytes[1..]
+                .iter()
+                .take(2)
+                .take_while(|c| c.is_ascii_hexdigit())
+                .count();
+            let text = std::str::from_utf8(&bytes[1..1 + digits]).unwrap();
+            let value = u8::from_str_radix(text, 16).ok()?;
+            return Some((value as char, 1 + digits));
+        }
+        b'0'..=b'7' => {
+            let digits = bytes
+                .iter()
+                .take(3)
+                .take_while(|c| (b'0'..=b'7').contains(c))
+                .count();
+            let text = std::str::from_utf8(&bytes[..digits]).unwrap();
+            let value = u8::try_from(u32::from_str_radix(text, 8).unwrap()).ok()?;
+            return Some((value as char, digits));
+        }
+        _ => return None,
+    };
+    Some((simple, 1))
+}
+
+const INTEGER_SUFFIXES: [&str; 8] = ["", "u", "l", "ul", "lu", "ll", "ull", "llu"];
+
+// Checks a scanned number, with its optional suffix: u, l, ul, ll, or ull for
+// integers, in any case and order, and f or l for floating point numbers.
+fn number(text: &str) -> Result<TokenKind, ErrorKind> {
+    let lower = text.to_ascii_lowercase();
+    let (radix, digits) = if let Some(digits) = lower.strip_prefix("0x") {
+        (16, digits)
+    } else if let Some(digits) = lower.strip_prefix("0b") {
+        (2, digits)
+    } else {
+        (10, lower.as_str())
+    };
+    if radix == 10 && digits.contains(['.', 'e']) {
+        let digits = digits.strip_suffix(['f', 'l']).unwrap_or(digits);
+        return digits
+            .parse()
+            .map(TokenKind::Float)
+            .map_err(|_| ErrorKind::InvalidNumber);
+    }
+    let value = digits.trim_end_matches(['u', 'l']);
+    let suffix = &digits[value.len()..];
+    // The two letters of ll have to be of the same case.
+    let original = &text[value.len() + text.len() - digits.len()..];
+    let valid_suffix =
+        INTEGER_SUFFIXES.contains(&suffix) && !original.contains("lL") && !original.contains("Ll");
+    let radix = if radix == 10 && value.len() > 1 && value.starts_with('0') {
+        8
+    } else {
+        radix
+    };
+    match u64::from_str_radix(value, radix) {
+        Ok(value) if valid_suffix => Ok(TokenKind::Integer(value)),
+        _ => Err(ErrorKind::InvalidNumber),
+    }
+}
+
+pub struct Lexer<'a> {
+    source: &'a str,
+    position: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            position: 0,
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<u8> {
+        self.source.as_bytes().get(self.position + offset).copied()
+    }
+
+    fn identifier(&mut self) -> TokenKind {
+        let start = self.position;
+        while self
+            .peek(0)
+            .is_some_and(|c| matches!(class(c), Class::Letter | Class::Digit))
+        {
+            self.position += 1;
+        }
+        if KEYWORDS.contains(&&self.source[start..self.position]) {
+            TokenKind::Keyword
+        } else {
+            TokenKind::Identifier
+        }
+    }
+
+    fn number(&mut self) -> TokenKind {
+        let start = self.position;
+        while let Some(c) = self.peek(0) {
+            // The sign of an exponent, but e is a digit in hexadecimal.
+            let exponent_sign = matches!(c, b'+' | b'-')
+                && matches!(self.source.as_bytes()[self.position - 1], b'e' | b'E')
+                && !self.source[start..].starts_with("0x")
+                && !self.source[start..].starts_with("0X");
+            if c.is_ascii_alphanumeric() || c == b'_' || c == b'.' || exponent_sign {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+        number(&self.source[start..self.position]).unwrap_or_else(TokenKind::Error)
+    }
+
+    // Scans the literal until its closing delimiter, decoding the escape
+    // sequences. A line break or the end of the source ends it prematurely.
+    fn quoted(&mut self, delimiter: u8) -> Result<String, ErrorKind> {
+        let unterminated = if delimiter == b'"' {
+            ErrorKind::UnterminatedString
+        } else {
+            ErrorKind::UnterminatedCharacter
+        };
+        self.position += 1;
+        let mut value = String::new();
+        let mut error = None;
+        loop {
+            match self.peek(0) {
+                None | Some(b'\n') => return Err(unterminated),
+                Some(c) if c == delimiter => {
+                    self.position += 1;
+                    return error.map_or(Ok(value), Err);
+                }
+                Some(b'\\') => {
+                    let rest = &self.source.as_bytes()[self.position + 1..];
+                    match escape(rest) {
+                        Some((c, length)) => {
+                            value.push(c);
+                            self.position += 1 + length;
+                        }
+                        None => {
+                            // The rest of the literal is still consumed.
+                            error = Some(ErrorKind::InvalidEscape);
+                            self.position += 1;
+                        }
+                    }
+                }
+                Some(_) => {
+                    let c = self.source[self.position..].chars().next().unwrap();
+                    value.push(c);
+                    self.position += c.len_utf8();
+                }
+            }
+        }
+    }
+
+    fn comment(&mut self) -> TokenKind {
+        if self.peek(1) == Some(b'/') {
+            let rest = &self.source[self.position..];
+            self.position += rest.find('\n').unwrap_or(rest.len());
+            return TokenKind::Comment;
+        }
+        match self.source[self.position + 2..].find("*/") {
+            Some(end) => {
+                self.position += 2 + end + 2;
+                TokenKind::Comment
+            }
+            None => {
+                self.position = self.source.len();
+                TokenKind::Error(ErrorKind::UnterminatedComment)
+            }
+        }
+    }
+
+    fn operator(&mut self) -> TokenKind {
+        let rest = &self.source[self.position..];
+        let operator = OPERATORS
+            .iter()
+            .find(|operator| rest.starts_with(**operator));
+        match operator {
+            Some(operator) => {
+                self.position += operator.len();
+                TokenKind::Operator
+            }
+            None => self.unexpected(),
+        }
+    }
+
+    fn unexpected(&mut self) -> TokenKind {
+        let c = self.source[self.position..].chars().next().unwrap();
+        self.position += c.len_utf8();
+        TokenKind::Error(ErrorKind::UnexpectedCharacter)
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        while self.peek(0).is_some_and(|c| class(c) == Class::Whitespace) {
+            self.position += 1;
+        }
+        let c = self.peek(0)?;
+        let start = self.position;
+        let kind = match class(c) {
+            Class::Letter => self.identifier(),
+            Class::Digit => self.number(),
+            Class::Punctuation if c == b'.' && self.peek(1).is_some_and(|c| c.is_ascii_digit()) => {
+                self.number()
+            }
+            Class::Punctuation if c == b'/' && matches!(self.peek(1), Some(b'/' | b'*')) => {
+                self.comment()
+            }
+            Class::Punctuation => self.operator(),
+            Class::Quote => self
+                .quoted(b'"')
+                .map_or_else(TokenKind::Error, TokenKind::String),
+            Class::Apostrophe => match self.quoted(b'\'') {
+                Ok(value) if value.chars().count() == 1 => {
+                    TokenKind::Character(value.chars().next().unwrap())
+                }
+                Ok(_) => TokenKind::Error(ErrorKind::InvalidCharacterLiteral),
+                Err(kind) => TokenKind::Error(kind),
+            },
+            Class::Whitespace | Class::Other => self.unexpected(),
+        };
+        Some(Token {
+            kind,
+            span: start..self.position,
+        })
+    }
+}
+
+pub fn tokenize(source: &str) -> Vec<Token> {
+    Lexer::new(source).collect()
+}
+
+// The line and the column of a byte offset, both starting from 1, where the
+// column is counted in characters.
+pub fn line_and_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+const PROGRAM: &str = r#"/* Counts the set bits. */
+static unsigned long popcount(unsigned long x) {
+    int count = 0;  // the result
+    while (x != 0ul) {
+        count += x & 1;
+        x >>= 1;
+    }
+    return count;
+}
+
+const char *message = "bits:\t%d\n";
+double ratio = .5e-3f;
+char newline = '\n', quote = '\'', tab = '\x09';
+"#;
+
+const BROKEN: &str = "int x = 08 + 12abc;\nchar c = '';\nconst char *s = \"bad \\q escape\";\nx @= 0x1Fu; y = \"no end\nz /* open";
+
+pub fn demo(harness: &mut Harness) {
+    let tokens = tokenize(PROGRAM);
+    let kinds = |tokens: &[Token], kind: fn(&TokenKind) -> bool| -> Vec<String> {
+        tokens
+            .iter()
+            .filter(|token| kind(&token.kind))
+            .map(|token| token.text(PROGRAM).to_string())
+            .collect()
+    };
+    test!(
+        harness,
+        "no errors in a valid program",
+        tokens
+            .iter()
+            .filter(|token| matches!(token.kind, TokenKind::Error(_)))
+            .count(),
+        0
+    );
+    test!(
+        harness,
+        "the keywords",
+        kinds(&tokens, |kind| *kind == TokenKind::Keyword).join(" "),
+        "static unsigned long unsigned long int while return const char double char"
+    );
+    test!(
+        harness,
+        "the identifiers",
+        kinds(&tokens, |kind| *kind == TokenKind::Identifier).join(" "),
+        "popcount x count x count x x count message ratio newline quote tab"
+    );
+    let operators = "a<<=b->c...d>>e";
+    test!(
+        harness,
+        "the operators are matched longest first",
+        tokenize(operators)
+            .iter()
+            .map(|token| token.text(operators))
+            .collect::<Vec<_>>(),
+        vec!["a", "<<=", "b", "->", "c", "...", "d", ">>", "e"]
+    );
+    test!(
+        harness,
+        "the comments are tokens",
+        kinds(&tokens, |kind| *kind == TokenKind::Comment),
+        vec!["/* Counts the set bits. */", "// the result"]
+    );
+    let values: Vec<TokenKind> = tokens
+        .iter()
+        .map(|token| token.kind.clone())
+        .filter(|kind| {
+            matches!(
+                kind,
+                TokenKind::Integer(_)
+                    | TokenKind::Float(_)
+                    | TokenKind::String(_)
+                    | TokenKind::Character(_)
+            )
+        })
+        .collect();
+    test!(
+        harness,
+        "the values of the literals",
+        values,
+        vec![
+            TokenKind::Integer(0),
+            TokenKind::Integer(0),
+            TokenKind::Integer(1),
+            TokenKind::Integer(1),
+            TokenKind::String("bits:\t%d\n".to_string()),
+            TokenKind::Float(0.0005),
+            TokenKind::Character('\n'),
+            TokenKind::Character('\''),
+            TokenKind::Character('\t'),
+        ]
+    );
+    test!(
+        harness,
+        "the spans cover the source without overlapping",
+        tokens
+            .windows(2)
+            .all(|pair| pair[0].span.end <= pair[1].span.start),
+        true
+    );
+
+    let numbers = [
+        ("42", Ok(TokenKind::Integer(42))),
+        ("0x1F", Ok(TokenKind::Integer(31))),
+        ("0b1010", Ok(TokenKind::Integer(10))),
+        ("0755", Ok(TokenKind::Integer(493))),
+        ("10ull", Ok(TokenKind::Integer(10))),
+        ("10LLU", Ok(TokenKind::Integer(10))),
+        ("1.5e3", Ok(TokenKind::Float(1500.0))),
+        ("2.f", Ok(TokenKind::Float(2.0))),
+        ("08", Err(ErrorKind::InvalidNumber)),
+        ("1lL", Err(ErrorKind::InvalidNumber)),
+        ("10uu", Err(ErrorKind::InvalidNumber)),
+        ("1.2.3", Err(ErrorKind::InvalidNumber)),
+        ("18446744073709551616", Err(ErrorKind::InvalidNumber)),
+    ];
+    for (text, expected) in numbers {
+        test!(
+            harness,
+            &format!("the number {}", text),
+            number(text),
+            expected
+        );
+    }
+
+    let errors: Vec<(String, String)> = tokenize(BROKEN)
+        .into_iter()
+        .filter_map(|token| match token.kind {
+            TokenKind::Error(kind) => {
+                let (line, column) = line_and_column(BROKEN, token.span.start);
+                Some((
+                    format!("{}:{}", line, column),
+                    format!("{} {:?}", kind, token.text(BROKEN)),
+                ))
+            }
+            _ => None,
+        })
+        .collect();
+    test!(
+        harness,
+        "the errors are reported, and the lexing continues",
+        errors
+            .iter()
+            .map(|(position, _)| position.as_str())
+            .collect::<Vec<_>>(),
+        vec!["1:9", "1:14", "2:10", "3:17", "4:3", "4:17", "5:3"]
+    );
+    test!(
+        harness,
+        "the tokens after an error are found",
+        tokenize("a @ b").len(),
+        3
+    );
+
+    let line = PROGRAM.lines().nth(4).unwrap();
+    note!(harness, "{}", line);
+    let offset = PROGRAM.find(line).unwrap();
+    for token in tokenize(line) {
+        note!(
+            harness,
+            "  {:>3}..{:<3} {:<10} {}",
+            offset + token.span.start,
+            offset + token.span.end,
+            format!("{:?}", token.kind)
+                .split('(')
+                .next()
+                .unwrap()
+                .to_string(),
+            token.text(line)
+        );
+    }
+    note!(harness);
+    for (position, error) in errors {
+        note!(harness, "{:<5} {}", position, error);
+    }
+    note!(harness);
+}
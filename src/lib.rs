@@ -0,0 +1,102 @@
+// lib.rs
+// Library crate collecting the Rust snippets, so that they can be used as
+// dependencies instead of being copied around. Each snippet is a module here,
+// and has a thin demonstration binary with the same name under src/bin.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+#[macro_use]
+pub mod harness;
+
+pub mod aes;
+pub mod ansi;
+pub mod args;
+pub mod astar;
+pub mod bench;
+pub mod bencode;
+pub mod bigint;
+pub mod bloom;
+pub mod brainfuck;
+pub mod bst;
+pub mod calc;
+pub mod chacha20;
+pub mod channel;
+pub mod checksum;
+pub mod classical;
+pub mod compress;
+pub mod crc;
+pub mod cron;
+pub mod csv;
+pub mod datetime;
+pub mod diff;
+pub mod dns;
+pub mod editdist;
+pub mod encoding;
+pub mod eventbus;
+pub mod executor;
+pub mod fft;
+pub mod fixedpoint;
+pub mod generator;
+pub mod glob;
+pub mod graph;
+pub mod hashmap;
+pub mod heap;
+pub mod hmac;
+pub mod httpclient;
+pub mod httpserver;
+pub mod huffman;
+pub mod humantime;
+pub mod ini;
+pub mod ipcalc;
+pub mod json;
+pub mod leftpad;
+pub mod lexer;
+pub mod life;
+pub mod linkedlist;
+pub mod lru;
+pub mod matrix;
+pub mod maze;
+pub mod md5;
+pub mod memo;
+pub mod morse;
+pub mod msgpack;
+pub mod mt19937;
+pub mod ntp;
+pub mod numwords;
+pub mod pbkdf2;
+pub mod portscan;
+pub mod primes;
+pub mod progress;
+pub mod protowire;
+pub mod punycode;
+pub mod rational;
+pub mod regex;
+pub mod registry;
+pub mod ringbuffer;
+pub mod rng;
+pub mod roman;
+pub mod scheduler;
+pub mod semver;
+pub mod serialize;
+pub mod sha1;
+pub mod sha2;
+pub mod sha3;
+pub mod sorting;
+pub mod statemachine;
+pub mod strsearch;
+pub mod sync;
+pub mod table;
+pub mod tcpecho;
+pub mod template;
+pub mod threadpool;
+pub mod trie;
+pub mod unionfind;
+pub mod url;
+pub mod utf8;
+pub mod uuid;
+pub mod vm;
+pub mod websocket;
+pub mod wordwrap;
+pub mod xml;
+pub mod xtea;
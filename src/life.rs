@@ -0,0 +1,430 @@
+// life.rs
+// Conway's Game of Life, on a toroidal grid, where the edges wrap around, so
+// each cell has exactly eight neighbours. In each generation, a dead cell with
+// three living neighbours is born, a living cell with two or three living
+// neighbours survives, and every other cell dies, or stays dead: the rule
+// known as B3/S23. Patterns are loaded from the run length encoded (RLE) format
+// used by most Life software, where a header gives the size of the pattern,
+// and the body consists of runs of dead (b) and living (o) cells, with rows
+// ending in $, and the pattern in !. The animation redraws the grid in place,
+// moving the cursor to the top left corner before each frame, instead of
+// scrolling the terminal, and hides the cursor while it plays.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+use std::io::{self, Write};
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    MissingHeader,
+    InvalidHeader,
+    UnsupportedRule(String),
+    UnexpectedCharacter(char),
+    OutOfBounds,
+    MissingEnd,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RleError {
+    pub kind: ErrorKind,
+    pub line: usize,
+}
+
+impl fmt::Display for RleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            ErrorKind::MissingHeader => write!(f, "missing header"),
+            ErrorKind::InvalidHeader => write!(f, "invalid header"),
+            ErrorKind::UnsupportedRule(rule) => write!(f, "unsupported rule '{}'", rule),
+            ErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character '{}'", c),
+            ErrorKind::OutOfBounds => write!(f, "cell outside of the declared size"),
+            ErrorKind::MissingEnd => write!(f, "missing '!' at the end of the pattern"),
+        }
+    }
+}
+
+impl std::error::Error for RleError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    pub name: Option<String>,
+    pub width: usize,
+    pub height: usize,
+    // The living cells, in row-major order.
+    pub cells: Vec<(usize, usize)>,
+}
+
+impl Pattern {
+    pub fn from_rle(text: &str) -> Result<Self, RleError> {
+        let mut name = None;
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()));
+        let (width, height, header_line) = loop {
+            let Some((number, line)) = lines.next() else {
+                return Err(RleError {
+                    kind: ErrorKind::MissingHeader,
+                    line: text.lines().count(),
+                });
+            };
+            if let Some(title) = line.strip_prefix("#N") {
+                name = Some(title.trim().to_string());
+            } else if !line.is_empty() && !line.starts_with('#') {
+                let (width, height) =
+                    parse_header(line).map_err(|kind| RleError { kind, line: number })?;
+                break (width, height, number);
+            }
+        };
+
+        let mut cells = Vec::new();
+        let (mut x, mut y) = (0, 0);
+        let mut count: Option<usize> = None;
+        for (number, line) in lines {
+            let error = |kind| RleError { kind, line: number };
+            for c in line.chars() {
+                if let Some(digit) = c.to_digit(10) {
+                    count = Some(count.unwrap_or(0) * 10 + digit as usize);
+                    continue;
+                }
+                let run = count.take().unwrap_or(1);
+                match c {
+                    'b' => x += run,
+                    'o' => {
+                        if x + run > width || y >= height {
+                            return Err(error(ErrorKind::OutOfBounds));
+                        }
+                        cells.extend((x..x + run).map(|x| (x, y)));
+                        x += run;
+                    }
+                    '$' => {
+                        x = 0;
+                        y += run;
+                    }
+                    '!' => {
+                        cells.sort_by_key(|(x, y)| (*y, *x));
+                        return Ok(Self {
+                            name,
+                            width,
+                            height,
+                            cells,
+                        });
+                    }
+                    c if c.is_whitespace() => {}
+                    c => return Err(error(ErrorKind::UnexpectedCharacter(c))),
+                }
+            }
+        }
+        Err(RleError {
+            kind: ErrorKind::MissingEnd,
+            line: text.lines().count().max(header_line),
+        })
+    }
+}
+
+// The header looks like "x = 3, y = 3, rule = B3/S23", where the rule is
+// optional.
+fn parse_header(line: &str) -> Result<(usize, usize), ErrorKind> {
+    let (mut width, mut height) = (None, None);
+    for field in line.split(',') {
+        let (key, value) = field.split_once('=').ok_or(ErrorKind::InvalidHeader)?;
+        let value = value.trim();
+        match key.trim() {
+            "x" => width = Some(value.parse().map_err(|_| ErrorKind::InvalidHeader)?),
+            "y" => height = Some(value.parse().map_err(|_| ErrorKind::InvalidHeader)?),
+            "rule" if value.eq_ignore_ascii_case("B3/S23") => {}
+            "rule" => return Err(ErrorKind::UnsupportedRule(value.to_string())),
+            _ => return Err(ErrorKind::InvalidHeader),
+        }
+    }
+    width.zip(height).ok_or(ErrorKind::InvalidHeader)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Life {
+    width: usize,
+    height: usize,
+    cells: Vec<bool>,
+    generation: u64,
+}
+
+impl Life {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![false; width * height],
+            generation: 0,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.cells[(y % self.height) * self.width + x % self.width]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, alive: bool) {
+        self.cells[(y % self.height) * self.width + x % self.width] = alive;
+    }
+
+    // Places the pattern with its top left corner at the given cell, wrapping
+    // around the edges.
+    pub fn place(&mut self, pattern: &Pattern, x: usize, y: usize) {
+        for (dx, dy) in &pattern.cells {
+            self.set(x + dx, y + dy, true);
+        }
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|alive| **alive).count()
+    }
+
+    // The living cells, in row-major order.
+    pub fn living_cells(&self) -> Vec<(usize, usize)> {
+        (0..self.cells.len())
+            .filter(|index| self.cells[*index])
+            .map(|index| (index % self.width, index / self.width))
+            .collect()
+    }
+
+    fn neighbours(&self, x: usize, y: usize) -> usize {
+        let mut count = 0;
+        for dy in [self.height - 1, 0, 1] {
+            for dx in [self.width - 1, 0, 1] {
+                if (dx, dy) != (0, 0) && self.get(x + dx, y + dy) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    pub fn step(&mut self) {
+        let mut next = vec![false; self.cells.len()];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                next[y * self.width + x] =
+                    matches!((self.get(x, y), self.neighbours(x, y)), (true, 2) | (_, 3));
+            }
+        }
+        self.cells = next;
+        self.generation += 1;
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::with_capacity((self.width + 1) * self.height * 3);
+        for row in self.cells.chunks(self.width) {
+            out.extend(row.iter().map(|alive| if *alive { '█' } else { '·' }));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+const CLEAR_SCREEN: &str = "\x1b[2J";
+const CURSOR_HOME: &str = "\x1b[H";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Animation {
+    pub generations: u64,
+    pub delay: Duration,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self {
+            generations: 100,
+            delay: Duration::from_millis(100),
+        }
+    }
+}
+
+impl Animation {
+    // Draws the current generation, and then each of the following ones,
+    // waiting the delay between the frames.
+    pub fn play(&self, life: &mut Life, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "{}{}", CLEAR_SCREEN, HIDE_CURSOR)?;
+        for frame in 0..=self.generations {
+            if frame > 0 {
+                std::thread::sleep(self.delay);
+                life.step();
+            }
+            writeln!(
+                out,
+                "{}{}generation {}, population {}\x1b[K",
+                CURSOR_HOME,
+                life.render(),
+                life.generation(),
+                life.population()
+            )?;
+            out.flush()?;
+        }
+        write!(out, "{}", SHOW_CURSOR)?;
+        out.flush()
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use std::io::IsTerminal;
+
+const GLIDER: &str = "#N Glider
+x = 3, y = 3, rule = B3/S23
+bob$2bo$3o!";
+
+const GOSPER_GUN: &str = "#N Gosper glider gun
+#C The first known finite pattern with unbounded growth.
+x = 36, y = 9, rule = B3/S23
+24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b
+obo$10bo5bo7bo$11bo3bo$12b2o!";
+
+pub fn demo(harness: &mut Harness) {
+    let glider = Pattern::from_rle(GLIDER).unwrap();
+    test!(
+        harness,
+        "loading a glider",
+        glider.clone(),
+        Pattern {
+            name: Some("Glider".to_string()),
+            width: 3,
+            height: 3,
+            cells: vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+        }
+    );
+    let gun = Pattern::from_rle(GOSPER_GUN).unwrap();
+    test!(
+        harness,
+        "loading a Gosper glider gun, split across lines",
+        (gun.name.as_deref(), gun.width, gun.height, gun.cells.len()),
+        (Some("Gosper glider gun"), 36, 9, 36)
+    );
+    test!(
+        harness,
+        "an unsupported rule",
+        Pattern::from_rle("x = 3, y = 1, rule = B36/S23\n3o!").map_err(|e| e.to_string()),
+        Err("line 1: unsupported rule 'B36/S23'".to_string())
+    );
+    test!(
+        harness,
+        "a cell outside of the declared size",
+        Pattern::from_rle("x = 2, y = 2\nobo$2o!").map_err(|e| e.to_string()),
+        Err("line 2: cell outside of the declared size".to_string())
+    );
+    test!(
+        harness,
+        "a pattern without an end",
+        Pattern::from_rle("x = 2, y = 1\n2o").map(|_| ()),
+        Err(RleError {
+            kind: ErrorKind::MissingEnd,
+            line: 2
+        })
+    );
+
+    let mut blinker = Life::new(5, 5);
+    blinker.place(&Pattern::from_rle("x = 3, y = 1\n3o!").unwrap(), 1, 2);
+    let horizontal = blinker.clone();
+    blinker.step();
+    test!(
+        harness,
+        "a blinker turns vertical",
+        blinker.living_cells(),
+        vec![(2, 1), (2, 2), (2, 3)]
+    );
+    blinker.step();
+    test!(
+        harness,
+        "a blinker has a period of two",
+        blinker.living_cells(),
+        horizontal.living_cells()
+    );
+
+    let mut life = Life::new(8, 8);
+    life.place(&glider, 0, 0);
+    let start = life.living_cells();
+    for _ in 0..4 {
+        life.step();
+    }
+    let moved: Vec<(usize, usize)> = start.iter().map(|(x, y)| (x + 1, y + 1)).collect();
+    test!(
+        harness,
+        "a glider moves diagonally every four generations",
+        life.living_cells(),
+        moved
+    );
+    for _ in 4..32 {
+        life.step();
+    }
+    test!(
+        harness,
+        "a glider wraps around the torus",
+        (life.generation(), life.living_cells()),
+        (32, start)
+    );
+
+    let mut colony = Life::new(64, 40);
+    colony.place(&gun, 1, 1);
+    for _ in 0..120 {
+        colony.step();
+    }
+    test!(
+        harness,
+        "a Gosper gun emits a glider every 30 generations",
+        colony.population(),
+        gun.cells.len() + 4 * glider.cells.len()
+    );
+
+    let mut frames = Vec::new();
+    let animation = Animation {
+        generations: 3,
+        delay: Duration::ZERO,
+    };
+    animation.play(&mut life.clone(), &mut frames).unwrap();
+    let frames = String::from_utf8(frames).unwrap();
+    test!(
+        harness,
+        "the animation redraws each frame in place",
+        (
+            frames.matches(CURSOR_HOME).count(),
+            frames.starts_with(CLEAR_SCREEN),
+            frames.ends_with(SHOW_CURSOR)
+        ),
+        (4, true, true)
+    );
+
+    note!(
+        harness,
+        "Generation {} of the Gosper glider gun:\n{}",
+        colony.generation(),
+        colony.render()
+    );
+    // Only animate when someone is watching.
+    if !harness.is_quiet() && io::stdout().is_terminal() {
+        let mut colony = Life::new(64, 40);
+        colony.place(&gun, 1, 1);
+        let animation = Animation {
+            generations: 60,
+            delay: Duration::from_millis(50),
+        };
+        animation.play(&mut colony, &mut io::stdout()).unwrap();
+    }
+}
@@ -0,0 +1,359 @@
+// linkedlist.rs
+// A doubly linked list in safe Rust. In a doubly linked list, every node is
+// pointed to by both of its neighbors, but Rust allows only one owner for each
+// value, and a mutable reference must be exclusive. So the nodes are shared and
+// reference counted (Rc), with their contents behind a RefCell, which moves the
+// borrow checking to run time. The forward links own the next node, while the
+// backward links are weak, which do not keep the node alive, and so they avoid
+// reference cycles, which would be never freed.
+// The cost of this is run-time bookkeeping, and that references to the elements
+// can not outlive the RefCell borrows. The borrowing iterator is the exception,
+// where the compiler needs some help: while it borrows the list, nothing can
+// change the nodes, so it reads them without RefCell guards, which only unsafe
+// code can do. The standard library uses raw pointers and unsafe code for the
+// whole list, where the programmer, not the compiler, guarantees that no node
+// is freed while it is still pointed to, and that no two mutable references
+// exist at once.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::cell::{Ref, RefCell};
+use std::rc::{Rc, Weak};
+
+type Link<T> = Option<Rc<RefCell<Node<T>>>>;
+
+struct Node<T> {
+    value: T,
+    next: Link<T>,
+    previous: Option<Weak<RefCell<Node<T>>>>,
+}
+
+pub struct LinkedList<T> {
+    head: Link<T>,
+    // The tail is also weak, so the only strong pointer to each node is the
+    // one from its predecessor, or the head, which makes popping easy.
+    tail: Option<Weak<RefCell<Node<T>>>>,
+    length: usize,
+}
+
+impl<T> LinkedList<T> {
+    pub fn new() -> Self {
+        Self {
+            head: None,
+            tail: None,
+            length: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        let node = Rc::new(RefCell::new(Node {
+            value,
+            next: self.head.take(),
+            previous: None,
+        }));
+        match &node.borrow().next {
+            Some(next) => next.borrow_mut().previous = Some(Rc::downgrade(&node)),
+            None => self.tail = Some(Rc::downgrade(&node)),
+        }
+        self.head = Some(node);
+        self.length += 1;
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        let node = Rc::new(RefCell::new(Node {
+            value,
+            next: None,
+            previous: self.tail.clone(),
+        }));
+        let weak = Rc::downgrade(&node);
+        match self.tail.as_ref().and_then(Weak::upgrade) {
+            Some(tail) => tail.borrow_mut().next = Some(node),
+            None => self.head = Some(node),
+        }
+        self.tail = Some(weak);
+        self.length += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let head = self.head.take()?;
+        match head.borrow_mut().next.take() {
+            Some(next) => {
+                next.borrow_mut().previous = None;
+                self.head = Some(next);
+            }
+            None => self.tail = None,
+        }
+        self.length -= 1;
+        Some(Self::unwrap_node(head))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let tail = self.tail.take()?.upgrade()?;
+        let previous = tail.borrow_mut().previous.take();
+        // The strong pointer to the tail is held by its predecessor, which has
+        // to let go of it, before the value can be moved out.
+        let node = match previous.as_ref().and_then(Weak::upgrade) {
+            Some(previous) => {
+                self.tail = Some(Rc::downgrade(&previous));
+                previous.borrow_mut().next.take()
+            }
+            None => self.head.take(),
+        };
+        drop(tail);
+        self.length -= 1;
+        node.map(Self::unwrap_node)
+    }
+
+    // The values can only be borrowed through a RefCell guard, which keeps
+    // the node borrowed while it exists.
+    pub fn peek_front(&self) -> Option<Ref<'_, T>> {
+        self.head
+            .as_ref()
+            .map(|node| Ref::map(node.borrow(), |node| &node.value))
+    }
+
+    pub fn peek_back(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let tail = self.tail.as_ref()?.upgrade()?;
+        let value = tail.borrow().value.clone();
+        Some(value)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref().map(unguarded),
+        }
+    }
+
+    // Once the list holds the only strong pointer to a node, and the node
+    // has no neighbors, its value can be moved out of the Rc and the RefCell.
+    fn unwrap_node(node: Rc<RefCell<Node<T>>>) -> T {
+        match Rc::try_unwrap(node) {
+            Ok(cell) => cell.into_inner().value,
+            Err(_) => panic!("a popped node is still shared"),
+        }
+    }
+}
+
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The nodes would be dropped recursively, each one dropping its successor,
+// which overflows the stack for long lists, so they are unlinked one by one.
+impl<T> Drop for LinkedList<T> {
+    fn drop(&mut self) {
+        let mut next = self.head.take();
+        while let Some(node) = next {
+            next = node.borrow_mut().next.take();
+        }
+    }
+}
+
+// The nodes are only borrowed mutably by the methods taking the list by a
+// mutable reference, so while the list is borrowed for the lifetime of the
+// iterator, they can be read without a guard, and the references outlive the
+// borrow of the RefCell, but not the borrow of the list.
+fn unguarded<T>(node: &RefCell<Node<T>>) -> &Node<T> {
+    unsafe { node.try_borrow_unguarded() }.expect("a node is borrowed mutably")
+}
+
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.next.take()?;
+        self.next = node.next.as_deref().map(unguarded);
+        Some(&node.value)
+    }
+}
+
+pub struct IntoIter<T>(LinkedList<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_front()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> IntoIterator for LinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(items: I) -> Self {
+        let mut list = Self::new();
+        for item in items {
+            list.push_back(item);
+        }
+        list
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use std::cell::Cell;
+
+// Counts how many times it was dropped, to show that no node is leaked.
+struct Tracked(Rc<Cell<usize>>);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+pub fn demo(harness: &mut Harness) {
+    let mut list = LinkedList::new();
+    test!(harness, "a new list is empty", list.is_empty(), true);
+    test!(
+        harness,
+        "popping an empty list gives nothing",
+        list.pop_back(),
+        None
+    );
+
+    list.push_back(2);
+    list.push_back(3);
+    list.push_front(1);
+    list.push_front(0);
+    test!(
+        harness,
+        "pushing at both ends keeps the order",
+        list.iter().copied().collect::<Vec<_>>(),
+        vec![0, 1, 2, 3]
+    );
+    test!(harness, "the length is tracked", list.len(), 4);
+    test!(
+        harness,
+        "the front can be peeked at through a borrow",
+        list.peek_front().map(|value| *value),
+        Some(0)
+    );
+    test!(
+        harness,
+        "the back can be peeked at",
+        list.peek_back(),
+        Some(3)
+    );
+
+    test!(harness, "popping from the front", list.pop_front(), Some(0));
+    test!(harness, "popping from the back", list.pop_back(), Some(3));
+    test!(
+        harness,
+        "the remaining elements are in the middle",
+        list.iter().copied().collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    test!(
+        harness,
+        "popping the last element",
+        list.pop_back(),
+        Some(2)
+    );
+    test!(
+        harness,
+        "popping the only element",
+        list.pop_back(),
+        Some(1)
+    );
+    test!(
+        harness,
+        "the list is empty again",
+        (list.len(), list.pop_front()),
+        (0, None)
+    );
+    list.push_front(7);
+    test!(
+        harness,
+        "an emptied list can be reused",
+        list.peek_back(),
+        Some(7)
+    );
+
+    let words: LinkedList<String> = "the quick brown fox".split(' ').map(String::from).collect();
+    test!(
+        harness,
+        "the borrowing iterator hands out references",
+        words.iter().map(String::len).collect::<Vec<_>>(),
+        vec![3, 5, 5, 3]
+    );
+    test!(
+        harness,
+        "the consuming iterator runs backwards",
+        words.into_iter().rev().collect::<Vec<_>>(),
+        vec!["fox", "brown", "quick", "the"]
+    );
+
+    let mut mixed: LinkedList<i32> = (1..=6).collect();
+    let mut zigzag = Vec::new();
+    while let (Some(front), Some(back)) = (mixed.pop_front(), mixed.pop_back()) {
+        zigzag.push(front);
+        zigzag.push(back);
+    }
+    test!(
+        harness,
+        "popping from both ends meets in the middle",
+        zigzag,
+        vec![1, 6, 2, 5, 3, 4]
+    );
+
+    // Without weak backward links, every pair of neighbors would keep each
+    // other alive forever.
+    let drops = Rc::new(Cell::new(0));
+    {
+        let mut tracked = LinkedList::new();
+        for _ in 0..1000 {
+            tracked.push_back(Tracked(drops.clone()));
+        }
+        tracked.pop_front();
+        tracked.pop_back();
+    }
+    test!(
+        harness,
+        "every node is freed when the list is dropped",
+        drops.get(),
+        1000
+    );
+
+    let long: LinkedList<u32> = (0..200000).collect();
+    test!(harness, "a long list is built", long.len(), 200000);
+    drop(long);
+    note!(
+        harness,
+        "Dropping a list of 200000 nodes did not overflow the stack.\n"
+    );
+}
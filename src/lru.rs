@@ -0,0 +1,303 @@
+// lru.rs
+// A least recently used (LRU) cache, which holds a limited number of entries,
+// and when it is full, evicts the entry which was used the longest time ago.
+// The entries are kept in a doubly linked list, ordered by their last use, and
+// a hash map finds the node of each key, so both lookup and reordering are done
+// in a constant time. The list nodes are stored in a vector, and link to each
+// other by index, which avoids the shared ownership a pointer-based list needs
+// in Rust.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::hashmap::HashMap;
+use std::hash::Hash;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    previous: Option<usize>,
+    next: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Statistics {
+    pub hits: usize,
+    pub misses: usize,
+    pub evictions: usize,
+}
+
+impl Statistics {
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+pub struct LruCache<K, V> {
+    capacity: usize,
+    index: HashMap<K, usize>,
+    nodes: Vec<Node<K, V>>,
+    // The most recently used entry is at the head, and the least recently
+    // used one is at the tail.
+    head: Option<usize>,
+    tail: Option<usize>,
+    statistics: Statistics,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "the capacity must be positive");
+        Self {
+            capacity,
+            index: HashMap::with_capacity(capacity),
+            nodes: Vec::with_capacity(capacity),
+            head: None,
+            tail: None,
+            statistics: Statistics {
+                hits: 0,
+                misses: 0,
+                evictions: 0,
+            },
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn statistics(&self) -> Statistics {
+        self.statistics
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.index.contains_key(key)
+    }
+
+    fn unlink(&mut self, node: usize) {
+        let (previous, next) = (self.nodes[node].previous, self.nodes[node].next);
+        match previous {
+            Some(previous) => self.nodes[previous].next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => self.nodes[next].previous = previous,
+            None => self.tail = previous,
+        }
+    }
+
+    fn push_front(&mut self, node: usize) {
+        self.nodes[node].previous = None;
+        self.nodes[node].next = self.head;
+        match self.head {
+            Some(head) => self.nodes[head].previous = Some(node),
+            None => self.tail = Some(node),
+        }
+        self.head = Some(node);
+    }
+
+    // Marks the entry as the most recently used.
+    fn touch(&mut self, node: usize) {
+        if self.head != Some(node) {
+            self.unlink(node);
+            self.push_front(node);
+        }
+    }
+
+    // Looking up an entry counts as using it.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        match self.index.get(key).copied() {
+            Some(node) => {
+                self.statistics.hits += 1;
+                self.touch(node);
+                Some(&self.nodes[node].value)
+            }
+            None => {
+                self.statistics.misses += 1;
+                None
+            }
+        }
+    }
+
+    // Looks at an entry without using it, or counting it as a hit or miss.
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        let node = *self.index.get(key)?;
+        Some(&self.nodes[node].value)
+    }
+
+    // Returns the evicted entry, if the cache was full. Updating an existing
+    // key replaces its value, and does not evict anything.
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if let Some(node) = self.index.get(&key).copied() {
+            self.nodes[node].value = value;
+            self.touch(node);
+            return None;
+        }
+
+        let node = Node {
+            key: key.clone(),
+            value,
+            previous: None,
+            next: None,
+        };
+        if self.nodes.len() < self.capacity {
+            self.nodes.push(node);
+            let index = self.nodes.len() - 1;
+            self.index.insert(key, index);
+            self.push_front(index);
+            return None;
+        }
+
+        // The node of the evicted entry is reused for the new one.
+        let tail = self.tail?;
+        self.unlink(tail);
+        let evicted = std::mem::replace(&mut self.nodes[tail], node);
+        self.index.remove(&evicted.key);
+        self.index.insert(key, tail);
+        self.push_front(tail);
+        self.statistics.evictions += 1;
+        Some((evicted.key, evicted.value))
+    }
+
+    // Iterates from the most recently used entry to the least recently used.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        let mut current = self.head;
+        std::iter::from_fn(move || {
+            let node = &self.nodes[current?];
+            current = node.next;
+            Some((&node.key, &node.value))
+        })
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    let mut cache = LruCache::new(3);
+    cache.put("a", 1);
+    cache.put("b", 2);
+    cache.put("c", 3);
+    test!(
+        harness,
+        "entries are ordered from the most recently used",
+        cache.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+        vec!["c", "b", "a"]
+    );
+    test!(
+        harness,
+        "looking up a cached key",
+        cache.get(&"a"),
+        Some(&1)
+    );
+    test!(
+        harness,
+        "a lookup moves the entry to the front",
+        cache.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+        vec!["a", "c", "b"]
+    );
+    test!(
+        harness,
+        "putting into a full cache evicts the least recently used entry",
+        cache.put("d", 4),
+        Some(("b", 2))
+    );
+    test!(harness, "the evicted key is gone", cache.get(&"b"), None);
+    test!(
+        harness,
+        "updating a key replaces its value without evicting",
+        (cache.put("c", 30), cache.peek(&"c"), cache.len()),
+        (None, Some(&30), 3)
+    );
+    test!(
+        harness,
+        "peeking does not change the order",
+        cache.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+        vec!["c", "d", "a"]
+    );
+    test!(
+        harness,
+        "hits and misses are counted",
+        cache.statistics(),
+        Statistics {
+            hits: 1,
+            misses: 1,
+            evictions: 1
+        }
+    );
+
+    // A page reference string, as used to explain page replacement.
+    let references = [7, 0, 1, 2, 0, 3, 0, 4, 2, 3, 0, 3, 2, 1, 2, 0, 1, 7, 0, 1];
+    let mut pages = LruCache::new(3);
+    let mut evictions = Vec::new();
+    note!(harness, "Page  Cache (most recent first)  Evicted");
+    for page in references {
+        let evicted = if pages.get(&page).is_none() {
+            pages.put(page, ())
+        } else {
+            None
+        };
+        let contents: Vec<i32> = pages.iter().map(|(page, _)| *page).collect();
+        note!(
+            harness,
+            "{:>4}  {:<26} {}",
+            page,
+            format!("{:?}", contents),
+            evicted.map_or(String::from("-"), |(page, _)| page.to_string())
+        );
+        evictions.extend(evicted.map(|(page, _)| page));
+    }
+    let statistics = pages.statistics();
+    note!(
+        harness,
+        "\n{} hits, {} misses, {:.0}% hit rate\n",
+        statistics.hits,
+        statistics.misses,
+        statistics.hit_rate() * 100.0
+    );
+    test!(
+        harness,
+        "the page reference string causes 12 page faults",
+        statistics.misses,
+        12
+    );
+    test!(
+        harness,
+        "the pages are evicted in LRU order",
+        evictions,
+        vec![7, 1, 2, 3, 0, 4, 0, 3, 2]
+    );
+
+    // Memoizing a slow function with a cache which can hold a working set.
+    let mut squares = LruCache::new(16);
+    let mut computed = 0;
+    for round in 0..10 {
+        for x in (0..8).chain(round..round + 4) {
+            if squares.get(&x).is_none() {
+                computed += 1;
+                squares.put(x, x * x);
+            }
+        }
+    }
+    test!(
+        harness,
+        "a cache larger than the working set computes each value once",
+        computed,
+        13
+    );
+}
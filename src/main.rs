@@ -0,0 +1,3 @@
+fn main() {
+    std::process::exit(snippets::registry::main(std::env::args()));
+}
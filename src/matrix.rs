@@ -0,0 +1,446 @@
+// matrix.rs
+// A dense matrix of floating-point numbers, stored in a single vector on the
+// heap, in row-major order, with the usual operations. The determinant and the
+// solution of linear systems are calculated through the LU decomposition, which
+// factors the matrix into a lower and an upper triangular matrix, PA = LU, with
+// Gaussian elimination. At each step, the row with the largest element in the
+// current column is swapped into place, which is called partial pivoting, and
+// keeps the elimination numerically stable, and working on zero diagonals.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix {
+    rows: usize,
+    columns: usize,
+    data: Vec<f64>,
+}
+
+impl Matrix {
+    pub fn zeros(rows: usize, columns: usize) -> Self {
+        Self {
+            rows,
+            columns,
+            data: vec![0.0; rows * columns],
+        }
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut result = Self::zeros(size, size);
+        for i in 0..size {
+            result[(i, i)] = 1.0;
+        }
+        result
+    }
+
+    pub fn from_rows(rows: &[&[f64]]) -> Self {
+        let columns = rows.first().map_or(0, |row| row.len());
+        assert!(
+            rows.iter().all(|row| row.len() == columns),
+            "every row must have the same length"
+        );
+        Self {
+            rows: rows.len(),
+            columns,
+            data: rows.concat(),
+        }
+    }
+
+    pub fn from_fn(rows: usize, columns: usize, f: impl Fn(usize, usize) -> f64) -> Self {
+        let data = (0..rows * columns)
+            .map(|index| f(index / columns, index % columns))
+            .collect();
+        Self {
+            rows,
+            columns,
+            data,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.columns
+    }
+
+    pub fn row(&self, row: usize) -> &[f64] {
+        &self.data[row * self.columns..(row + 1) * self.columns]
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.columns, self.rows, |i, j| self[(j, i)])
+    }
+
+    pub fn swap_rows(&mut self, a: usize, b: usize) {
+        for j in 0..self.columns {
+            self.data.swap(a * self.columns + j, b * self.columns + j);
+        }
+    }
+
+    pub fn mul_vector(&self, vector: &[f64]) -> Vec<f64> {
+        assert_eq!(self.columns, vector.len(), "the dimensions do not match");
+        (0..self.rows)
+            .map(|i| self.row(i).iter().zip(vector).map(|(a, b)| a * b).sum())
+            .collect()
+    }
+
+    // The largest absolute difference between the elements, which is how
+    // floating-point results are compared.
+    pub fn max_difference(&self, other: &Matrix) -> f64 {
+        assert_eq!(
+            (self.rows, self.columns),
+            (other.rows, other.columns),
+            "the dimensions do not match"
+        );
+        self.data
+            .iter()
+            .zip(&other.data)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max)
+    }
+
+    pub fn lu(&self) -> Option<Lu> {
+        Lu::decompose(self)
+    }
+
+    pub fn determinant(&self) -> f64 {
+        assert!(self.is_square(), "the matrix must be square");
+        self.lu().map_or(0.0, |lu| lu.determinant())
+    }
+
+    pub fn solve(&self, b: &[f64]) -> Option<Vec<f64>> {
+        Some(self.lu()?.solve(b))
+    }
+
+    pub fn inverse(&self) -> Option<Matrix> {
+        Some(self.lu()?.inverse())
+    }
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = f64;
+
+    fn index(&self, (row, column): (usize, usize)) -> &f64 {
+        assert!(
+            row < self.rows && column < self.columns,
+            "index out of range"
+        );
+        &self.data[row * self.columns + column]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut f64 {
+        assert!(
+            row < self.rows && column < self.columns,
+            "index out of range"
+        );
+        &mut self.data[row * self.columns + column]
+    }
+}
+
+impl fmt::Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision().unwrap_or(3);
+        let cells: Vec<String> = self
+            .data
+            .iter()
+            .map(|value| format!("{:.*}", precision, value))
+            .collect();
+        let width = cells.iter().map(|cell| cell.len()).max().unwrap_or(0);
+        for row in cells.chunks(self.columns.max(1)) {
+            write!(f, "[")?;
+            for (j, cell) in row.iter().enumerate() {
+                let separator = if j == 0 { "" } else { " " };
+                write!(f, "{}{:>width$}", separator, cell, width = width)?;
+            }
+            writeln!(f, "]")?;
+        }
+        Ok(())
+    }
+}
+
+impl Add for &Matrix {
+    type Output = Matrix;
+
+    fn add(self, other: &Matrix) -> Matrix {
+        assert_eq!(
+            (self.rows, self.columns),
+            (other.rows, other.columns),
+            "the dimensions do not match"
+        );
+        Matrix::from_fn(self.rows, self.columns, |i, j| self[(i, j)] + other[(i, j)])
+    }
+}
+
+impl Sub for &Matrix {
+    type Output = Matrix;
+
+    fn sub(self, other: &Matrix) -> Matrix {
+        assert_eq!(
+            (self.rows, self.columns),
+            (other.rows, other.columns),
+            "the dimensions do not match"
+        );
+        Matrix::from_fn(self.rows, self.columns, |i, j| self[(i, j)] - other[(i, j)])
+    }
+}
+
+// The loops are ordered i-k-j, so the inner loop walks both the result and
+// the right operand along a row, which is friendly to the cache.
+impl Mul for &Matrix {
+    type Output = Matrix;
+
+    fn mul(self, other: &Matrix) -> Matrix {
+        assert_eq!(self.columns, other.rows, "the dimensions do not match");
+        let mut result = Matrix::zeros(self.rows, other.columns);
+        for i in 0..self.rows {
+            for k in 0..self.columns {
+                let a = self[(i, k)];
+                for j in 0..other.columns {
+                    result[(i, j)] += a * other[(k, j)];
+                }
+            }
+        }
+        result
+    }
+}
+
+impl Mul<f64> for &Matrix {
+    type Output = Matrix;
+
+    fn mul(self, factor: f64) -> Matrix {
+        Matrix::from_fn(self.rows, self.columns, |i, j| self[(i, j)] * factor)
+    }
+}
+
+// Both triangular factors are stored in one matrix: U on and above the
+// diagonal, and L below it, whose diagonal elements are all ones.
+pub struct Lu {
+    factors: Matrix,
+    permutation: Vec<usize>,
+    swaps: usize,
+}
+
+impl Lu {
+    const SINGULAR_TOLERANCE: f64 = 1e-12;
+
+    // Returns nothing if the matrix is singular.
+    pub fn decompose(matrix: &Matrix) -> Option<Self> {
+        assert!(matrix.is_square(), "the matrix must be square");
+        let n = matrix.rows;
+        let mut factors = matrix.clone();
+        let mut permutation: Vec<usize> = (0..n).collect();
+        let mut swaps = 0;
+
+        for k in 0..n {
+            let pivot = (k..n)
+                .max_by(|a, b| factors[(*a, k)].abs().total_cmp(&factors[(*b, k)].abs()))
+                .unwrap();
+            if factors[(pivot, k)].abs() < Self::SINGULAR_TOLERANCE {
+                return None;
+            }
+            if pivot != k {
+                factors.swap_rows(pivot, k);
+                permutation.swap(pivot, k);
+                swaps += 1;
+            }
+            for i in k + 1..n {
+                let multiplier = factors[(i, k)] / factors[(k, k)];
+                factors[(i, k)] = multiplier;
+                for j in k + 1..n {
+                    factors[(i, j)] -= multiplier * factors[(k, j)];
+                }
+            }
+        }
+
+        Some(Self {
+            factors,
+            permutation,
+            swaps,
+        })
+    }
+
+    pub fn lower(&self) -> Matrix {
+        let n = self.factors.rows;
+        Matrix::from_fn(n, n, |i, j| match i.cmp(&j) {
+            std::cmp::Ordering::Greater => self.factors[(i, j)],
+            std::cmp::Ordering::Equal => 1.0,
+            std::cmp::Ordering::Less => 0.0,
+        })
+    }
+
+    pub fn upper(&self) -> Matrix {
+        let n = self.factors.rows;
+        Matrix::from_fn(n, n, |i, j| if i <= j { self.factors[(i, j)] } else { 0.0 })
+    }
+
+    pub fn permutation(&self) -> Matrix {
+        let n = self.factors.rows;
+        Matrix::from_fn(n, n, |i, j| (self.permutation[i] == j) as u8 as f64)
+    }
+
+    // The determinant of a triangular matrix is the product of its diagonal,
+    // and each row swap negates it.
+    pub fn determinant(&self) -> f64 {
+        let n = self.factors.rows;
+        let product: f64 = (0..n).map(|i| self.factors[(i, i)]).product();
+        if self.swaps.is_multiple_of(2) {
+            product
+        } else {
+            -product
+        }
+    }
+
+    // Ax = b is solved as Ly = Pb by forward, and then as Ux = y by backward
+    // substitution.
+    pub fn solve(&self, b: &[f64]) -> Vec<f64> {
+        let n = self.factors.rows;
+        assert_eq!(n, b.len(), "the dimensions do not match");
+        let mut x: Vec<f64> = self.permutation.iter().map(|i| b[*i]).collect();
+        for i in 0..n {
+            for j in 0..i {
+                x[i] -= self.factors[(i, j)] * x[j];
+            }
+        }
+        for i in (0..n).rev() {
+            for j in i + 1..n {
+                x[i] -= self.factors[(i, j)] * x[j];
+            }
+            x[i] /= self.factors[(i, i)];
+        }
+        x
+    }
+
+    // Each column of the inverse is the solution for a column of the identity.
+    pub fn inverse(&self) -> Matrix {
+        let n = self.factors.rows;
+        let mut result = Matrix::zeros(n, n);
+        for j in 0..n {
+            let mut unit = vec![0.0; n];
+            unit[j] = 1.0;
+            for (i, value) in self.solve(&unit).into_iter().enumerate() {
+                result[(i, j)] = value;
+            }
+        }
+        result
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+fn round(values: &[f64]) -> Vec<f64> {
+    values.iter().map(|v| (v * 1e9).round() / 1e9).collect()
+}
+
+pub fn demo(harness: &mut Harness) {
+    let a = Matrix::from_rows(&[&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]]);
+    let b = Matrix::from_rows(&[&[7.0, 8.0], &[9.0, 10.0], &[11.0, 12.0]]);
+    test!(
+        harness,
+        "a 2x3 matrix times a 3x2 matrix",
+        &a * &b,
+        Matrix::from_rows(&[&[58.0, 64.0], &[139.0, 154.0]])
+    );
+    test!(
+        harness,
+        "the transpose swaps rows and columns",
+        a.transpose(),
+        Matrix::from_rows(&[&[1.0, 4.0], &[2.0, 5.0], &[3.0, 6.0]])
+    );
+    test!(
+        harness,
+        "the transpose of a product is the reversed product of the transposes",
+        (&a * &b).transpose(),
+        &b.transpose() * &a.transpose()
+    );
+    test!(
+        harness,
+        "the identity is neutral",
+        &Matrix::identity(2) * &a,
+        a.clone()
+    );
+    test!(
+        harness,
+        "a matrix times a vector",
+        a.mul_vector(&[1.0, 0.0, -1.0]),
+        vec![-2.0, -2.0]
+    );
+
+    // 2x + y - z = 8, -3x - y + 2z = -11, -2x + y + 2z = -3
+    let system = Matrix::from_rows(&[&[2.0, 1.0, -1.0], &[-3.0, -1.0, 2.0], &[-2.0, 1.0, 2.0]]);
+    let rhs = [8.0, -11.0, -3.0];
+    let solution = system.solve(&rhs).unwrap();
+    test!(
+        harness,
+        "the solution of a 3x3 system",
+        round(&solution),
+        vec![2.0, 3.0, -1.0]
+    );
+    test!(
+        harness,
+        "the determinant of the system",
+        round(&[system.determinant()]),
+        vec![-1.0]
+    );
+    let lu = system.lu().unwrap();
+    test!(
+        harness,
+        "the factors multiply back to the permuted matrix",
+        (&lu.permutation() * &system).max_difference(&(&lu.lower() * &lu.upper())) < 1e-12,
+        true
+    );
+    let inverse = system.inverse().unwrap();
+    test!(
+        harness,
+        "a matrix times its inverse is the identity",
+        (&system * &inverse).max_difference(&Matrix::identity(3)) < 1e-12,
+        true
+    );
+
+    let swapped = Matrix::from_rows(&[&[0.0, 1.0], &[1.0, 0.0]]);
+    test!(
+        harness,
+        "pivoting handles a zero on the diagonal",
+        (swapped.solve(&[2.0, 3.0]), swapped.determinant()),
+        (Some(vec![3.0, 2.0]), -1.0)
+    );
+    let singular = Matrix::from_rows(&[&[1.0, 2.0], &[2.0, 4.0]]);
+    test!(
+        harness,
+        "a singular matrix has no solution",
+        (singular.solve(&[1.0, 2.0]), singular.determinant()),
+        (None, 0.0)
+    );
+
+    // The Hilbert matrix, H(i, j) = 1 / (i + j + 1), is notoriously badly
+    // conditioned. The determinant of the 4x4 one is 1/6048000.
+    let hilbert = Matrix::from_fn(4, 4, |i, j| 1.0 / (i + j + 1) as f64);
+    test!(
+        harness,
+        "the determinant of the 4x4 Hilbert matrix",
+        (hilbert.determinant() * 6048000.0 - 1.0).abs() < 1e-9,
+        true
+    );
+
+    note!(harness, "A =\n{:.0}", system);
+    note!(harness, "L =\n{:.3}", lu.lower());
+    note!(harness, "U =\n{:.3}", lu.upper());
+    note!(harness, "P =\n{:.0}", lu.permutation());
+    note!(harness, "A^-1 =\n{:.3}", inverse);
+    note!(harness, "A x = {:?} for x = {:?}\n", rhs, round(&solution));
+}
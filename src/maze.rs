@@ -0,0 +1,357 @@
+// maze.rs
+// Generating perfect mazes, where there is exactly one path between any two
+// cells, with two randomized algorithms, solving them with breadth-first
+// search, and drawing them with box-drawing characters. Recursive backtracking
+// walks to a random unvisited neighbour, carving through the wall, and steps
+// back when it is stuck, which makes long, winding corridors with few dead
+// ends. Prim's algorithm grows the maze from a cell, carving to a random cell
+// of its frontier each time, which makes short branches, and many dead ends.
+// The recursion of the backtracker is an explicit stack, so large mazes do not
+// overflow the call stack. Each cell stores its openings as four bits.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::rng::RandomSource;
+use std::collections::VecDeque;
+
+pub const NORTH: u8 = 1;
+pub const EAST: u8 = 2;
+pub const SOUTH: u8 = 4;
+pub const WEST: u8 = 8;
+
+const DIRECTIONS: [u8; 4] = [NORTH, EAST, SOUTH, WEST];
+
+fn opposite(direction: u8) -> u8 {
+    match direction {
+        NORTH => SOUTH,
+        EAST => WEST,
+        SOUTH => NORTH,
+        _ => EAST,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Maze {
+    width: usize,
+    height: usize,
+    openings: Vec<u8>,
+}
+
+impl Maze {
+    // A maze with all the walls standing.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            openings: vec![0; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn cell(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    pub fn is_open(&self, cell: usize, direction: u8) -> bool {
+        self.openings[cell] & direction != 0
+    }
+
+    pub fn neighbour(&self, cell: usize, direction: u8) -> Option<usize> {
+        let (x, y) = (cell % self.width, cell / self.width);
+        match direction {
+            NORTH if y > 0 => Some(cell - self.width),
+            EAST if x + 1 < self.width => Some(cell + 1),
+            SOUTH if y + 1 < self.height => Some(cell + self.width),
+            WEST if x > 0 => Some(cell - 1),
+            _ => None,
+        }
+    }
+
+    // Removes the wall between the cell and its neighbour in the direction.
+    pub fn carve(&mut self, cell: usize, direction: u8) {
+        let neighbour = self
+            .neighbour(cell, direction)
+            .expect("the outer walls of a maze can not be carved");
+        self.openings[cell] |= direction;
+        self.openings[neighbour] |= opposite(direction);
+    }
+
+    pub fn passages(&self) -> usize {
+        self.openings
+            .iter()
+            .map(|openings| openings.count_ones() as usize)
+            .sum::<usize>()
+            / 2
+    }
+
+    pub fn dead_ends(&self) -> usize {
+        self.openings
+            .iter()
+            .filter(|openings| openings.count_ones() == 1)
+            .count()
+    }
+
+    fn open_neighbours(&self, cell: usize) -> impl Iterator<Item = usize> + '_ {
+        DIRECTIONS
+            .into_iter()
+            .filter(move |direction| self.is_open(cell, *direction))
+            .filter_map(move |direction| self.neighbour(cell, direction))
+    }
+
+    // The cells of the shortest path, including both ends.
+    pub fn solve(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let mut previous = vec![None; self.openings.len()];
+        let mut visited = vec![false; self.openings.len()];
+        let mut queue = VecDeque::from([from]);
+        visited[from] = true;
+        while let Some(cell) = queue.pop_front() {
+            if cell == to {
+                let mut path = vec![to];
+                while let Some(before) = previous[*path.last().unwrap()] {
+                    path.push(before);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for next in self.open_neighbours(cell) {
+                if !visited[next] {
+                    visited[next] = true;
+                    previous[next] = Some(cell);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    // Each cell is three characters wide, between the columns of corners, and
+    // the cells of the path are joined with dots.
+    pub fn render(&self, path: &[usize]) -> String {
+        let mut on_path = vec![false; self.openings.len()];
+        for cell in path {
+            on_path[*cell] = true;
+        }
+        let joined = |a: usize, b: usize| {
+            path.windows(2)
+                .any(|pair| (pair[0], pair[1]) == (a, b) || (pair[0], pair[1]) == (b, a))
+        };
+
+        let mut out = String::new();
+        for y in 0..=self.height {
+            for x in 0..=self.width {
+                out.push(self.corner(x, y));
+                if x == self.width {
+                    break;
+                }
+                let wall = y == 0 || y == self.height || !self.is_open(self.cell(x, y), NORTH);
+                out.push_str(if wall {
+                    "───"
+                } else if joined(self.cell(x, y - 1), self.cell(x, y)) {
+                    " • "
+                } else {
+                    "   "
+                });
+            }
+            out.push('\n');
+            if y == self.height {
+                break;
+            }
+            for x in 0..=self.width {
+                let wall = x == 0 || x == self.width || !self.is_open(self.cell(x, y), WEST);
+                out.push(if wall {
+                    '│'
+                } else if joined(self.cell(x - 1, y), self.cell(x, y)) {
+                    '•'
+                } else {
+                    ' '
+                });
+                if x < self.width {
+                    out.push_str(if on_path[self.cell(x, y)] {
+                        " • "
+                    } else {
+                        "   "
+                    });
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    // The character at a crossing of the lattice of walls, depending on which
+    // of the four wall segments meet there.
+    fn corner(&self, x: usize, y: usize) -> char {
+        const BOX: [char; 16] = [
+            ' ', '╵', '╶', '└', '╷', '│', '┌', '├', '╴', '┘', '─', '┴', '┐', '┤', '┬', '┼',
+        ];
+        let vertical =
+            |row: usize| x == 0 || x == self.width || !self.is_open(self.cell(x - 1, row), EAST);
+        let horizontal = |column: usize| {
+            y == 0 || y == self.height || !self.is_open(self.cell(column, y - 1), SOUTH)
+        };
+        let up = y > 0 && vertical(y - 1);
+        let down = y < self.height && vertical(y);
+        let left = x > 0 && horizontal(x - 1);
+        let right = x < self.width && horizontal(x);
+        BOX[up as usize | (right as usize) << 1 | (down as usize) << 2 | (left as usize) << 3]
+    }
+}
+
+pub fn recursive_backtracker(width: usize, height: usize, random: &mut impl RandomSource) -> Maze {
+    let mut maze = Maze::new(width, height);
+    let mut visited = vec![false; width * height];
+    let mut stack = vec![random.next_below((width * height) as u64) as usize];
+    visited[stack[0]] = true;
+    while let Some(&cell) = stack.last() {
+        let unvisited: Vec<u8> = DIRECTIONS
+            .into_iter()
+            .filter(|direction| {
+                maze.neighbour(cell, *direction)
+                    .is_some_and(|next| !visited[next])
+            })
+            .collect();
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let direction = unvisited[random.next_below(unvisited.len() as u64) as usize];
+        let next = maze.neighbour(cell, direction).unwrap();
+        maze.carve(cell, direction);
+        visited[next] = true;
+        stack.push(next);
+    }
+    maze
+}
+
+// The frontier holds the walls between the maze and the cells outside of it.
+// A wall may lead to a cell which joined the maze since it was added, those
+// are skipped.
+pub fn prim(width: usize, height: usize, random: &mut impl RandomSource) -> Maze {
+    let mut maze = Maze::new(width, height);
+    let mut in_maze = vec![false; width * height];
+    let mut frontier = Vec::new();
+    let mut cell = random.next_below((width * height) as u64) as usize;
+    loop {
+        in_maze[cell] = true;
+        for direction in DIRECTIONS {
+            if maze
+                .neighbour(cell, direction)
+                .is_some_and(|next| !in_maze[next])
+            {
+                frontier.push((cell, direction));
+            }
+        }
+        let next = loop {
+            if frontier.is_empty() {
+                return maze;
+            }
+            let index = random.next_below(frontier.len() as u64) as usize;
+            let (from, direction) = frontier.swap_remove(index);
+            let next = maze.neighbour(from, direction).unwrap();
+            if !in_maze[next] {
+                maze.carve(from, direction);
+                break next;
+            }
+        };
+        cell = next;
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use crate::rng::SplitMix64;
+
+pub fn demo(harness: &mut Harness) {
+    let mut corridor = Maze::new(2, 1);
+    corridor.carve(0, EAST);
+    test!(
+        harness,
+        "carving opens the wall on both sides",
+        (corridor.is_open(0, EAST), corridor.is_open(1, WEST)),
+        (true, true)
+    );
+    test!(
+        harness,
+        "drawing a corridor with its path",
+        corridor.render(&[0, 1]),
+        "┌───────┐\n│ • • • │\n└───────┘\n"
+    );
+    let mut corner = Maze::new(2, 2);
+    corner.carve(0, EAST);
+    corner.carve(1, SOUTH);
+    corner.carve(3, WEST);
+    test!(
+        harness,
+        "drawing the walls meeting inside",
+        corner.render(&[]),
+        "┌───────┐\n│       │\n├───╴   │\n│       │\n└───────┘\n"
+    );
+
+    let (width, height) = (24, 10);
+    let mut random = SplitMix64::new(2026);
+    let mazes = [
+        (
+            "recursive backtracking",
+            recursive_backtracker(width, height, &mut random),
+        ),
+        ("Prim's algorithm", prim(width, height, &mut random)),
+    ];
+    let (entrance, exit) = (0, width * height - 1);
+    for (name, maze) in &mazes {
+        test!(
+            harness,
+            &format!("{} carves a spanning tree", name),
+            maze.passages(),
+            width * height - 1
+        );
+        let everything = (0..width * height).all(|cell| maze.solve(entrance, cell).is_some());
+        test!(
+            harness,
+            &format!("every cell is reachable in the maze of {}", name),
+            everything,
+            true
+        );
+        let path = maze.solve(entrance, exit).unwrap();
+        let connected = path.windows(2).all(|pair| {
+            DIRECTIONS.into_iter().any(|direction| {
+                maze.is_open(pair[0], direction)
+                    && maze.neighbour(pair[0], direction) == Some(pair[1])
+            })
+        });
+        test!(
+            harness,
+            &format!("the solution of the maze of {} is connected", name),
+            (path.first(), path.last(), connected),
+            (Some(&entrance), Some(&exit), true)
+        );
+    }
+    test!(
+        harness,
+        "backtracking makes fewer dead ends than Prim's algorithm",
+        mazes[0].1.dead_ends() < mazes[1].1.dead_ends(),
+        true
+    );
+
+    for (name, maze) in &mazes {
+        let path = maze.solve(entrance, exit).unwrap();
+        note!(
+            harness,
+            "Generated with {}, {} dead ends, the solution is {} cells long:",
+            name,
+            maze.dead_ends(),
+            path.len()
+        );
+        note!(harness, "{}", maze.render(&path));
+    }
+}
@@ -0,0 +1,186 @@
+// md5.rs
+// The MD5 message digest algorithm, as described in RFC 1321, with the same
+// streaming interface as the SHA-2 snippet. Like SHA-2, it is a Merkle-Damgård
+// construction over 64-byte blocks, but it uses little-endian words, and only
+// has a 128-bit state. It is broken for cryptographic purposes, and should only
+// be used for checksums and compatibility.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::encoding::HEX;
+
+#[rustfmt::skip]
+const SHIFTS: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22,
+    5,  9, 14, 20, 5,  9, 14, 20, 5,  9, 14, 20, 5,  9, 14, 20,
+    4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23,
+    6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+#[derive(Clone)]
+pub struct Md5 {
+    state: [u32; 4],
+    constants: [u32; 64],
+    buffer: Vec<u8>,
+    length: u64,
+}
+
+impl Md5 {
+    pub const BLOCK_SIZE: usize = 64;
+    pub const DIGEST_SIZE: usize = 16;
+
+    pub fn new() -> Self {
+        // The constants are the integer parts of abs(sin(i)) * 2^32, where i
+        // is in radians, which double precision is accurate enough for.
+        let mut constants = [0; 64];
+        for (i, constant) in constants.iter_mut().enumerate() {
+            *constant = (((i + 1) as f64).sin().abs() * 4294967296.0) as u32;
+        }
+
+        Self {
+            state: [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476],
+            constants,
+            buffer: Vec::with_capacity(Self::BLOCK_SIZE),
+            length: 0,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        let mut data = data;
+        if !self.buffer.is_empty() {
+            let missing = (Self::BLOCK_SIZE - self.buffer.len()).min(data.len());
+            self.buffer.extend_from_slice(&data[..missing]);
+            data = &data[missing..];
+            if self.buffer.len() < Self::BLOCK_SIZE {
+                return;
+            }
+            let block = std::mem::take(&mut self.buffer);
+            self.compress(&block);
+        }
+
+        let mut blocks = data.chunks_exact(Self::BLOCK_SIZE);
+        for block in &mut blocks {
+            self.compress(block);
+        }
+        self.buffer.extend_from_slice(blocks.remainder());
+    }
+
+    pub fn finalize(mut self) -> Vec<u8> {
+        let bits = self.length.wrapping_mul(8).to_le_bytes();
+        let mut padding = vec![0x80];
+        while (self.buffer.len() + padding.len()) % Self::BLOCK_SIZE != Self::BLOCK_SIZE - 8 {
+            padding.push(0x00);
+        }
+        padding.extend_from_slice(&bits);
+        self.update(&padding);
+
+        self.state
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect()
+    }
+
+    pub fn hexdigest(self) -> String {
+        HEX.encode(&self.finalize())
+    }
+
+    pub fn digest(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    fn compress(&mut self, block: &[u8]) {
+        let words: Vec<u32> = block
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .collect();
+
+        let [mut a, mut b, mut c, mut d] = self.state;
+        for (i, (constant, shift)) in self.constants.iter().zip(SHIFTS).enumerate() {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let rotated = a
+                .wrapping_add(f)
+                .wrapping_add(*constant)
+                .wrapping_add(words[g])
+                .rotate_left(shift);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(rotated);
+        }
+
+        for (word, value) in self.state.iter_mut().zip([a, b, c, d]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+}
+
+impl Default for Md5 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    const SUITE: [(&str, &str); 7] = [
+        ("", "d41d8cd98f00b204e9800998ecf8427e"),
+        ("a", "0cc175b9c0f1b6a831c399e269772661"),
+        ("abc", "900150983cd24fb0d6963f7d28e17f72"),
+        ("message digest", "f96b697d7cb7938d525a2f31aaf161d0"),
+        (
+            "abcdefghijklmnopqrstuvwxyz",
+            "c3fcd3d76192e4007dfb496cca67e13b",
+        ),
+        (
+            "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+            "d174ab98d277d9f5a5611c2c9f419d9f",
+        ),
+        (
+            "1234567890123456789012345678901234567890\
+             1234567890123456789012345678901234567890",
+            "57edf4a22be3c955ac49da2e2107b67a",
+        ),
+    ];
+
+    for (message, expected) in SUITE {
+        let mut hasher = Md5::new();
+        hasher.update(message.as_bytes());
+        let description = format!("MD5 of {:?} matches the RFC 1321 test suite", message);
+        test!(harness, &description, hasher.hexdigest(), expected);
+    }
+
+    let mut streamed = Md5::new();
+    for word in SUITE[6].0.as_bytes().chunks(7) {
+        streamed.update(word);
+    }
+    test!(
+        harness,
+        "streaming in pieces gives the same digest as hashing in one go",
+        streamed.finalize(),
+        Md5::digest(SUITE[6].0.as_bytes())
+    );
+
+    // Unlike SHA-256, which has a 256-bit digest, MD5 only has 128 bits.
+    let sha256 = crate::sha2::Sha256::digest(b"abc");
+    note!(
+        harness,
+        "MD5 of \"abc\":     {}",
+        HEX.encode(&Md5::digest(b"abc"))
+    );
+    note!(harness, "SHA-256 of \"abc\": {}\n", HEX.encode(&sha256));
+}
@@ -0,0 +1,272 @@
+// memo.rs
+// Memoization of pure functions, which stores the result of each call in a hash
+// map, and answers the repeated calls from there, instead of computing them
+// again. A recursive function is given the memo itself, so that its recursive
+// calls also go through the cache: this turns the exponential naive recursion
+// of the Fibonacci numbers into a linear one. The cache can also be bounded, to
+// hold only the most recently used results, with the LRU cache snippet, which
+// trades calls computed again for a limited memory. Each memo counts its hits,
+// misses, and evictions, like the LRU cache does.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::hashmap::HashMap;
+use crate::lru::LruCache;
+pub use crate::lru::Statistics;
+use std::hash::Hash;
+
+enum Cache<K, V> {
+    Unbounded(HashMap<K, V>),
+    Bounded(LruCache<K, V>),
+}
+
+// The function is a plain function pointer, which receives the memo as its
+// first argument, so it can call itself through it.
+pub struct Memo<K, V> {
+    function: fn(&mut Memo<K, V>, K) -> V,
+    cache: Cache<K, V>,
+    statistics: Statistics,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Memo<K, V> {
+    pub fn new(function: fn(&mut Memo<K, V>, K) -> V) -> Self {
+        Self {
+            function,
+            cache: Cache::Unbounded(HashMap::new()),
+            statistics: Statistics {
+                hits: 0,
+                misses: 0,
+                evictions: 0,
+            },
+        }
+    }
+
+    // Only the most recently used results are kept.
+    pub fn bounded(function: fn(&mut Memo<K, V>, K) -> V, capacity: usize) -> Self {
+        Self {
+            cache: Cache::Bounded(LruCache::new(capacity)),
+            ..Self::new(function)
+        }
+    }
+
+    pub fn call(&mut self, argument: K) -> V {
+        let cached = match &mut self.cache {
+            Cache::Unbounded(map) => map.get(&argument).cloned(),
+            Cache::Bounded(cache) => cache.get(&argument).cloned(),
+        };
+        if let Some(value) = cached {
+            self.statistics.hits += 1;
+            return value;
+        }
+        self.statistics.misses += 1;
+        let value = (self.function)(self, argument.clone());
+        match &mut self.cache {
+            Cache::Unbounded(map) => {
+                map.insert(argument, value.clone());
+            }
+            Cache::Bounded(cache) => {
+                if cache.put(argument, value.clone()).is_some() {
+                    self.statistics.evictions += 1;
+                }
+            }
+        }
+        value
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.cache {
+            Cache::Unbounded(map) => map.len(),
+            Cache::Bounded(cache) => cache.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn statistics(&self) -> Statistics {
+        self.statistics
+    }
+
+    // Forgets the results, but keeps the statistics.
+    pub fn clear(&mut self) {
+        self.cache = match &self.cache {
+            Cache::Unbounded(_) => Cache::Unbounded(HashMap::new()),
+            Cache::Bounded(cache) => Cache::Bounded(LruCache::new(cache.capacity())),
+        };
+    }
+}
+
+// Wraps a function which does not call itself, into a memoizing closure.
+pub fn memoize<K, V>(function: impl Fn(&K) -> V) -> impl FnMut(K) -> V
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    let mut cache = HashMap::new();
+    move |argument| {
+        if let Some(value) = cache.get(&argument) {
+            return V::clone(value);
+        }
+        let value = function(&argument);
+        cache.insert(argument, value.clone());
+        value
+    }
+}
+
+// Demonstration
+
+use crate::bench::{black_box, compare, Bench};
+use crate::harness::Harness;
+use std::cell::Cell;
+
+fn naive_fibonacci(n: u64) -> u64 {
+    if n < 2 {
+        n
+    } else {
+        naive_fibonacci(n - 1) + naive_fibonacci(n - 2)
+    }
+}
+
+fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+    if n < 2 {
+        n
+    } else {
+        memo.call(n - 1) + memo.call(n - 2)
+    }
+}
+
+// The binomial coefficients, by the recurrence of Pascal's triangle.
+fn binomial(memo: &mut Memo<(u64, u64), u128>, (n, k): (u64, u64)) -> u128 {
+    if k == 0 || k == n {
+        1
+    } else {
+        memo.call((n - 1, k - 1)) + memo.call((n - 1, k))
+    }
+}
+
+pub fn demo(harness: &mut Harness) {
+    let mut memo = Memo::new(fibonacci);
+    test!(
+        harness,
+        "the memoized Fibonacci numbers agree with the naive ones",
+        (0..25).all(|n| memo.call(n) == naive_fibonacci(n)),
+        true
+    );
+    let mut memo = Memo::new(fibonacci);
+    test!(
+        harness,
+        "the 90th Fibonacci number",
+        memo.call(90),
+        2880067194370816120
+    );
+    test!(
+        harness,
+        "each Fibonacci number is computed only once",
+        (memo.statistics().misses, memo.len()),
+        (91, 91)
+    );
+    test!(
+        harness,
+        "a repeated call is a hit",
+        (
+            memo.call(90),
+            memo.statistics().misses,
+            memo.statistics().hits
+        ),
+        (2880067194370816120, 91, 89)
+    );
+    memo.clear();
+    test!(
+        harness,
+        "clearing the memo forgets the results",
+        (memo.is_empty(), memo.call(10), memo.statistics().misses),
+        (true, 55, 91 + 11)
+    );
+
+    // The recursion only ever needs the last three results.
+    let mut bounded = Memo::bounded(fibonacci, 3);
+    test!(
+        harness,
+        "a bound of three keeps the recursion linear",
+        (bounded.call(90), bounded.statistics().misses, bounded.len()),
+        (2880067194370816120, 91, 3)
+    );
+    test!(
+        harness,
+        "the bounded memo evicts the older results",
+        bounded.statistics().evictions,
+        88
+    );
+    let mut tight = Memo::bounded(fibonacci, 2);
+    tight.call(20);
+    test!(
+        harness,
+        "a bound of two computes results again",
+        tight.statistics().misses > 21,
+        true
+    );
+
+    let mut memo = Memo::new(binomial);
+    test!(
+        harness,
+        "binomial coefficients from Pascal's triangle",
+        [(5, 2), (10, 5), (64, 32), (100, 50)].map(|pair| memo.call(pair)),
+        [10, 252, 1832624140942590534, 100891344545564193334812497256]
+    );
+
+    let calls = Cell::new(0);
+    let mut square = memoize(|x: &u32| {
+        calls.set(calls.get() + 1);
+        x * x
+    });
+    test!(
+        harness,
+        "a memoized closure is called once per argument",
+        [3, 4, 3, 3, 4, 5].map(&mut square),
+        [9, 16, 9, 9, 16, 25]
+    );
+    drop(square);
+    test!(
+        harness,
+        "the closure was called three times",
+        calls.get(),
+        3
+    );
+
+    let mut tight = Memo::bounded(fibonacci, 2);
+    for n in [10, 20, 30] {
+        tight.call(n);
+        note!(
+            harness,
+            "Calls to the memo with a bound of two, after fib({}): {:?}",
+            n,
+            tight.statistics()
+        );
+    }
+    note!(harness);
+
+    if harness.is_quiet() {
+        return;
+    }
+    let bench = Bench::new().samples(10);
+    let results = [
+        ("naive", bench.run(|| naive_fibonacci(black_box(25)))),
+        (
+            "memoized",
+            bench.run(|| Memo::new(fibonacci).call(black_box(25))),
+        ),
+        (
+            "bounded",
+            bench.run(|| Memo::bounded(fibonacci, 3).call(black_box(25))),
+        ),
+    ];
+    note!(
+        harness,
+        "Time taken to compute the 25th Fibonacci number:\n{}",
+        compare(&results)
+    );
+}
@@ -0,0 +1,435 @@
+// morse.rs
+// Translation between text and International Morse code. Each character is a
+// sequence of short and long signals, dots and dashes, which are separated by
+// a short gap within the character, a longer gap between the characters, and
+// an even longer one between the words. The symbols of the written form are
+// configurable, dots and dashes can be written as anything, like the dits and
+// dahs of the radio operators. Prosigns, procedural signals which are sent as a
+// single character without the gaps of their letters, like SOS, are written in
+// angle brackets, and decoded into them, when their codes have no character of
+// their own. For sending the code, the text is also converted to the timing of
+// the signal, as pairs of whether the tone is on, and for how many units, where
+// the unit is derived from the speed in words per minute, using the 50 units of
+// the standard word PARIS. An optional hook receives the same as durations, to
+// drive a tone generator, or to render the samples of an audio file.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+use std::time::Duration;
+
+#[rustfmt::skip]
+const CHARACTERS: [(char, &str); 54] = [
+    ('A', ".-"), ('B', "-..."), ('C', "-.-."), ('D', "-.."), ('E', "."), ('F', "..-."),
+    ('G', "--."), ('H', "...."), ('I', ".."), ('J', ".---"), ('K', "-.-"), ('L', ".-.."),
+    ('M', "--"), ('N', "-."), ('O', "---"), ('P', ".--."), ('Q', "--.-"), ('R', ".-."),
+    ('S', "..."), ('T', "-"), ('U', "..-"), ('V', "...-"), ('W', ".--"), ('X', "-..-"),
+    ('Y', "-.--"), ('Z', "--.."),
+    ('0', "-----"), ('1', ".----"), ('2', "..---"), ('3', "...--"), ('4', "....-"),
+    ('5', "....."), ('6', "-...."), ('7', "--..."), ('8', "---.."), ('9', "----."),
+    ('.', ".-.-.-"), (',', "--..--"), ('?', "..--.."), ('\'', ".----."), ('!', "-.-.--"),
+    ('/', "-..-."), ('(', "-.--."), (')', "-.--.-"), ('&', ".-..."), (':', "---..."),
+    (';', "-.-.-."), ('=', "-...-"), ('+', ".-.-."), ('-', "-....-"), ('_', "..--.-"),
+    ('"', ".-..-."), ('$', "...-..-"), ('@', ".--.-."),
+];
+
+// Some prosigns share their codes with punctuation, like AR with the plus sign,
+// so these are only decoded as the characters.
+#[rustfmt::skip]
+const PROSIGNS: [(&str, &str); 10] = [
+    ("SOS", "...---..."), ("SK", "...-.-"), ("HH", "........"), ("KA", "-.-.-"),
+    ("SN", "...-."), ("AR", ".-.-."), ("BT", "-...-"), ("KN", "-.--."), ("AS", ".-..."),
+    ("CL", "-.-..-.."),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Symbols<'a> {
+    pub dot: &'a str,
+    pub dash: &'a str,
+    pub letter_gap: &'a str,
+    pub word_gap: &'a str,
+}
+
+impl Symbols<'static> {
+    pub const STANDARD: Self = Self {
+        dot: ".",
+        dash: "-",
+        letter_gap: " ",
+        word_gap: " / ",
+    };
+}
+
+impl Default for Symbols<'static> {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnknownCharacter,
+    UnknownProsign,
+    UnterminatedProsign,
+    UnknownCode,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MorseError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for MorseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.kind {
+            ErrorKind::UnknownCharacter => "character without a Morse code",
+            ErrorKind::UnknownProsign => "unknown prosign",
+            ErrorKind::UnterminatedProsign => "unterminated prosign",
+            ErrorKind::UnknownCode => "code of no character or prosign",
+        };
+        write!(f, "{} at position {}", message, self.position)
+    }
+}
+
+impl std::error::Error for MorseError {}
+
+// The codes of the characters in a word, in the standard dots and dashes, where
+// the offset is the position of the word in the text, for the errors.
+fn characters(word: &str, offset: usize) -> Result<Vec<&'static str>, MorseError> {
+    let mut codes = Vec::new();
+    let mut index = 0;
+    while let Some(c) = word[index..].chars().next() {
+        let position = offset + index;
+        if c == '<' {
+            let end = word[index..].find('>').ok_or(MorseError {
+                kind: ErrorKind::UnterminatedProsign,
+                position,
+            })?;
+            let name = word[index + 1..index + end].to_ascii_uppercase();
+            let (_, code) = PROSIGNS
+                .iter()
+                .find(|(prosign, _)| *prosign == name)
+                .ok_or(MorseError {
+                    kind: ErrorKind::UnknownProsign,
+                    position,
+                })?;
+            codes.push(*code);
+            index += end + 1;
+        } else {
+            let upper = c.to_ascii_uppercase();
+            let (_, code) = CHARACTERS
+                .iter()
+                .find(|(character, _)| *character == upper)
+                .ok_or(MorseError {
+                    kind: ErrorKind::UnknownCharacter,
+                    position,
+                })?;
+            codes.push(*code);
+            index += c.len_utf8();
+        }
+    }
+    Ok(codes)
+}
+
+// The words of the text, with their byte positions.
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split_whitespace()
+        .map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
+
+pub fn encode(text: &str, symbols: &Symbols) -> Result<String, MorseError> {
+    let mut encoded = Vec::new();
+    for (offset, word) in words(text) {
+        let letters: Vec<String> = characters(word, offset)?
+            .into_iter()
+            .map(|code| {
+                code.chars()
+                    .map(|c| if c == '.' { symbols.dot } else { symbols.dash })
+                    .collect()
+            })
+            .collect();
+        encoded.push(letters.join(symbols.letter_gap));
+    }
+    Ok(encoded.join(symbols.word_gap))
+}
+
+// The standard form of the code of a single character, or None if it contains
+// anything other than the symbols of the dots and dashes.
+fn standardize(mut code: &str, symbols: &Symbols) -> Option<String> {
+    let mut standard = String::new();
+    while !code.is_empty() {
+        if let Some(rest) = code.strip_prefix(symbols.dot) {
+            standard.push('.');
+            code = rest;
+        } else if let Some(rest) = code.strip_prefix(symbols.dash) {
+            standard.push('-');
+            code = rest;
+        } else {
+            return None;
+        }
+    }
+    Some(standard)
+}
+
+fn decode_character(code: &str) -> Option<String> {
+    if let Some((c, _)) = CHARACTERS.iter().find(|(_, known)| *known == code) {
+        return Some(c.to_string());
+    }
+    PROSIGNS
+        .iter()
+        .find(|(_, known)| *known == code)
+        .map(|(prosign, _)| format!("<{}>", prosign))
+}
+
+// The gaps are matched exactly, but repeated gaps, and gaps at the ends are
+// ignored, so the symbols of the dots and dashes must not contain them.
+pub fn decode(code: &str, symbols: &Symbols) -> Result<String, MorseError> {
+    let position = |piece: &str| piece.as_ptr() as usize - code.as_ptr() as usize;
+    let mut words = Vec::new();
+    for word in code.split(symbols.word_gap) {
+        let mut decoded = String::new();
+        for letter in word.split(symbols.letter_gap).filter(|s| !s.is_empty()) {
+            let character = standardize(letter, symbols)
+                .as_deref()
+                .and_then(decode_character)
+                .ok_or(MorseError {
+                    kind: ErrorKind::UnknownCode,
+                    position: position(letter),
+                })?;
+            decoded.push_str(&character);
+        }
+        if !decoded.is_empty() {
+            words.push(decoded);
+        }
+    }
+    Ok(words.join(" "))
+}
+
+// The signal of the text, as pairs of whether the tone is on, and its length in
+// units: a dot is one unit long, a dash is three, and they are separated by one
+// unit of silence within characters, three between characters, and seven
+// between words.
+pub fn timings(text: &str) -> Result<Vec<(bool, u32)>, MorseError> {
+    let mut signal = Vec::new();
+    for (offset, word) in words(text) {
+        if !signal.is_empty() {
+            signal.push((false, 7));
+        }
+        for (index, code) in characters(word, offset)?.into_iter().enumerate() {
+            if index > 0 {
+                signal.push((false, 3));
+            }
+            for (index, element) in code.chars().enumerate() {
+                if index > 0 {
+                    signal.push((false, 1));
+                }
+                signal.push((true, if element == '.' { 1 } else { 3 }));
+            }
+        }
+    }
+    Ok(signal)
+}
+
+// The standard word PARIS, with the gap after it, is 50 units long, so sending
+// it a given number of times a minute gives the length of the unit.
+pub fn unit(words_per_minute: u32) -> Duration {
+    Duration::from_secs(60) / (50 * words_per_minute.max(1))
+}
+
+pub fn transmit<F>(text: &str, words_per_minute: u32, mut hook: F) -> Result<(), MorseError>
+where
+    F: FnMut(bool, Duration),
+{
+    let unit = unit(words_per_minute);
+    for (on, units) in timings(text)? {
+        hook(on, unit * units);
+    }
+    Ok(())
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    let standard = Symbols::default();
+    test!(
+        harness,
+        "encoding a sentence",
+        encode("Hello, World!", &standard),
+        Ok(".... . .-.. .-.. --- --..-- / .-- --- .-. .-.. -.. -.-.--".to_string())
+    );
+    test!(
+        harness,
+        "decoding a sentence",
+        decode("- .... . / --.- ..- .. -.-. -.- / ..-. --- -..-", &standard),
+        Ok("THE QUICK FOX".to_string())
+    );
+    let sentence = "THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG 0123456789 ?!.,'\"/()&:;=+-_$@";
+    test!(
+        harness,
+        "every character round trips",
+        decode(&encode(sentence, &standard).unwrap(), &standard),
+        Ok(sentence.to_string())
+    );
+    test!(
+        harness,
+        "prosigns are sent without gaps",
+        (
+            encode("<SOS>", &standard),
+            encode("sos", &standard),
+            encode("73 <sk>", &standard)
+        ),
+        (
+            Ok("...---...".to_string()),
+            Ok("... --- ...".to_string()),
+            Ok("--... ...-- / ...-.-".to_string())
+        )
+    );
+    test!(
+        harness,
+        "prosigns are decoded unless they are characters",
+        (
+            decode("...---... / ...-.- / .-.-.", &standard),
+            decode(&encode("<AR>", &standard).unwrap(), &standard)
+        ),
+        (Ok("<SOS> <SK> +".to_string()), Ok("+".to_string()))
+    );
+
+    let radio = Symbols {
+        dot: "dit",
+        dash: "dah",
+        letter_gap: " ",
+        word_gap: "   ",
+    };
+    test!(
+        harness,
+        "custom symbols",
+        encode("CQ DX", &radio),
+        Ok("dahditdahdit dahdahditdah   dahditdit dahditditdah".to_string())
+    );
+    test!(
+        harness,
+        "decoding custom symbols",
+        decode("dahditdahdit dahdahditdah   dahditdit dahditditdah", &radio),
+        Ok("CQ DX".to_string())
+    );
+    let dots = Symbols {
+        dot: "·",
+        dash: "–",
+        letter_gap: "|",
+        word_gap: "||",
+    };
+    test!(
+        harness,
+        "symbols outside ASCII",
+        decode(&encode("Rust 2026", &dots).unwrap(), &dots),
+        Ok("RUST 2026".to_string())
+    );
+
+    test!(
+        harness,
+        "errors of encoding",
+        [
+            encode("héllo", &standard),
+            encode("SOS <XY>", &standard),
+            encode("<SOS", &standard)
+        ],
+        [
+            Err(MorseError {
+                kind: ErrorKind::UnknownCharacter,
+                position: 1
+            }),
+            Err(MorseError {
+                kind: ErrorKind::UnknownProsign,
+                position: 4
+            }),
+            Err(MorseError {
+                kind: ErrorKind::UnterminatedProsign,
+                position: 0
+            })
+        ]
+    );
+    test!(
+        harness,
+        "errors of decoding",
+        [
+            decode("... ---.---- ...", &standard),
+            decode("... / .x.", &standard)
+        ],
+        [
+            Err(MorseError {
+                kind: ErrorKind::UnknownCode,
+                position: 4
+            }),
+            Err(MorseError {
+                kind: ErrorKind::UnknownCode,
+                position: 6
+            })
+        ]
+    );
+
+    let paris = timings("PARIS").unwrap();
+    test!(
+        harness,
+        "PARIS is 50 units long with the word gap",
+        paris.iter().map(|(_, units)| units).sum::<u32>() + 7,
+        50
+    );
+    test!(
+        harness,
+        "the timing of a character",
+        timings("R").unwrap(),
+        vec![(true, 1), (false, 1), (true, 3), (false, 1), (true, 1)]
+    );
+    test!(
+        harness,
+        "the timing of the gaps",
+        timings("E E  T").unwrap(),
+        vec![(true, 1), (false, 7), (true, 1), (false, 7), (true, 3)]
+    );
+    test!(
+        harness,
+        "the unit at 20 words per minute",
+        unit(20),
+        Duration::from_millis(60)
+    );
+    let mut total = Duration::ZERO;
+    let mut tones = 0;
+    transmit("PARIS PARIS", 20, |on, duration| {
+        total += duration;
+        tones += on as usize;
+    })
+    .unwrap();
+    test!(
+        harness,
+        "transmitting calls the hook with the durations",
+        (total, tones),
+        (Duration::from_millis(5580), 28)
+    );
+
+    let message = "What hath God wrought? <AR>";
+    let code = encode(message, &standard).unwrap();
+    note!(harness, "Message: {}", message);
+    note!(harness, "Morse:   {}", code);
+    note!(
+        harness,
+        "Radio:   {}",
+        encode("What hath God wrought?", &radio).unwrap()
+    );
+    note!(harness, "Decoded: {}", decode(&code, &standard).unwrap());
+    let mut keying = String::new();
+    transmit("SOS", 20, |on, duration| {
+        let units = (duration.as_millis() / 60) as usize;
+        keying.push_str(&(if on { "=" } else { "_" }).repeat(units));
+    })
+    .unwrap();
+    note!(
+        harness,
+        "Keying of SOS at 20 WPM, 60 ms per unit: {}",
+        keying
+    );
+}
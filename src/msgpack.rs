@@ -0,0 +1,639 @@
+// msgpack.rs
+// An encoder and a decoder of the core of MessagePack, a binary format with
+// the same kinds of values as JSON, and byte arrays, which is smaller and
+// faster to read, as nothing needs to be written out in text, or escaped.
+// Every value starts with a byte, which tells its type, and for the small
+// values, the value itself, or the length: the integers from -32 to 127, the
+// strings shorter than 32 bytes, and the arrays and the maps with less than
+// 16 elements fit in that byte, and the larger ones take a few more bytes, in
+// big-endian order. The encoder always picks the shortest form, and the
+// decoder accepts every form, but not the extension types, which are left
+// out, nor the reserved byte. The maps keep their order, and their keys can
+// be any value. The demonstration round-trips the values, and compares the
+// size of a document with its compact JSON form, from the JSON snippet.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::json::JsonValue;
+use std::fmt;
+
+// The decoder, and the conversion from i64, make the nonnegative integers
+// unsigned, so that they are equal to themselves after a round-trip.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MsgPackValue {
+    Nil,
+    Bool(bool),
+    UInt(u64),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bin(Vec<u8>),
+    Array(Vec<MsgPackValue>),
+    Map(Vec<(MsgPackValue, MsgPackValue)>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnexpectedEnd,
+    ReservedByte,
+    Unsupported(u8),
+    InvalidUtf8,
+    TooDeep,
+    TrailingData,
+    TooLong,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ErrorKind::ReservedByte => write!(f, "reserved type byte 0xc1"),
+            ErrorKind::Unsupported(byte) => write!(f, "unsupported type byte 0x{:02x}", byte),
+            ErrorKind::InvalidUtf8 => write!(f, "string is not valid UTF-8"),
+            ErrorKind::TooDeep => write!(f, "too deeply nested"),
+            ErrorKind::TrailingData => write!(f, "trailing data after the value"),
+            ErrorKind::TooLong => write!(f, "length does not fit in 32 bits"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MsgPackError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for MsgPackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at position {}", self.kind, self.position)
+    }
+}
+
+impl std::error::Error for MsgPackError {}
+
+pub const MAX_DEPTH: usize = 128;
+
+// The short form of the length, if it fits in the low bits of the first
+// byte, or the first byte of the sizes of eight, sixteen, and thirty-two bits.
+// Longer values can not be encoded, and the error is at the end of the output.
+fn write_length(
+    output: &mut Vec<u8>,
+    length: usize,
+    short: Option<(u8, usize)>,
+    long: [u8; 3],
+) -> Result<(), MsgPackError> {
+    match short {
+        Some((prefix, limit)) if length < limit => output.push(prefix | length as u8),
+        _ if length <= 0xff && long[0] != 0 => output.extend([long[0], length as u8]),
+        _ if length <= 0xffff => {
+            output.push(long[1]);
+            output.extend((length as u16).to_be_bytes());
+        }
+        _ => {
+            let length = u32::try_from(length).map_err(|_| MsgPackError {
+                kind: ErrorKind::TooLong,
+                position: output.len(),
+            })?;
+            output.push(long[2]);
+            output.extend(length.to_be_bytes());
+        }
+    }
+    Ok(())
+}
+
+impl MsgPackValue {
+    pub fn encode(&self) -> Result<Vec<u8>, MsgPackError> {
+        let mut output = Vec::new();
+        self.encode_into(&mut output)?;
+        Ok(output)
+    }
+
+    pub fn encode_into(&self, output: &mut Vec<u8>) -> Result<(), MsgPackError> {
+        match self {
+            MsgPackValue::Nil => output.push(0xc0),
+            MsgPackValue::Bool(value) => output.push(if *value { 0xc3 } else { 0xc2 }),
+            MsgPackValue::UInt(value) => match *value {
+                0..=0x7f => output.push(*value as u8),
+                0x80..=0xff => output.extend([0xcc, *value as u8]),
+                0x100..=0xffff => {
+                    output.push(0xcd);
+                    output.extend((*value as u16).to_be_bytes());
+                }
+                0x1_0000..=0xffff_ffff => {
+                    output.push(0xce);
+                    output.extend((*value as u32).to_be_bytes());
+                }
+                _ => {
+                    output.push(0xcf);
+                    output.extend(value.to_be_bytes());
+                }
+            },
+            MsgPackValue::Int(value) if *value >= 0 => {
+                return MsgPackValue::UInt(*value as u64).encode_into(output);
+            }
+            MsgPackValue::Int(value) => match *value {
+                -32..=-1 => output.push(*value as u8),
+                -0x80..=-33 => output.extend([0xd0, *value as u8]),
+                -0x8000..=-0x81 => {
+                    output.push(0xd1);
+                    output.extend((*value as i16).to_be_bytes());
+                }
+                -0x8000_0000..=-0x8001 => {
+                    output.push(0xd2);
+                    output.extend((*value as i32).to_be_bytes());
+                }
+                _ => {
+                    output.push(0xd3);
+                    output.extend(value.to_be_bytes());
+                }
+            },
+            MsgPackValue::Float(value) => {
+                output.push(0xcb);
+                output.extend(value.to_be_bytes());
+            }
+            MsgPackValue::Str(value) => {
+                write_length(output, value.len(), Some((0xa0, 32)), [0xd9, 0xda, 0xdb])?;
+                output.extend_from_slice(value.as_bytes());
+            }
+            MsgPackValue::Bin(value) => {
+                write_length(output, value.len(), None, [0xc4, 0xc5, 0xc6])?;
+                output.extend_from_slice(value);
+            }
+            MsgPackValue::Array(elements) => {
+                write_length(output, elements.len(), Some((0x90, 16)), [0, 0xdc, 0xdd])?;
+                for element in elements {
+                    element.encode_into(output)?;
+                }
+            }
+            MsgPackValue::Map(entries) => {
+                write_length(output, entries.len(), Some((0x80, 16)), [0, 0xde, 0xdf])?;
+                for (key, value) in entries {
+                    key.encode_into(output)?;
+                    value.encode_into(output)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            MsgPackValue::UInt(value) => i64::try_from(*value).ok(),
+            MsgPackValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            MsgPackValue::Str(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&MsgPackValue> {
+        match self {
+            MsgPackValue::Map(entries) => entries
+                .iter()
+                .find(|(name, _)| name.as_str() == Some(key))
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    // The integral numbers become integers, and the others floats.
+    pub fn from_json(value: &JsonValue) -> Self {
+        match value {
+            JsonValue::Null => MsgPackValue::Nil,
+            JsonValue::Bool(value) => MsgPackValue::Bool(*value),
+            JsonValue::Number(value)
+                if value.fract() == 0.0 && value.abs() < 9_007_199_254_740_992.0 =>
+            {
+                MsgPackValue::from(*value as i64)
+            }
+            JsonValue::Number(value) => MsgPackValue::Float(*value),
+            JsonValue::String(value) => MsgPackValue::Str(value.clone()),
+            JsonValue::Array(elements) => {
+                MsgPackValue::Array(elements.iter().map(Self::from_json).collect())
+            }
+            JsonValue::Object(members) => MsgPackValue::Map(
+                members
+                    .iter()
+                    .map(|(key, value)| (MsgPackValue::Str(key.clone()), Self::from_json(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    // JSON has no byte arrays, nor keys which are not strings.
+    pub fn to_json(&self) -> Option<JsonValue> {
+        Some(match self {
+            MsgPackValue::Nil => JsonValue::Null,
+            MsgPackValue::Bool(value) => JsonValue::Bool(*value),
+            MsgPackValue::UInt(value) => JsonValue::Number(*value as f64),
+            MsgPackValue::Int(value) => JsonValue::Number(*value as f64),
+            MsgPackValue::Float(value) => JsonValue::Number(*value),
+            MsgPackValue::Str(value) => JsonValue::String(value.clone()),
+            MsgPackValue::Bin(_) => return None,
+            MsgPackValue::Array(elements) => {
+                JsonValue::Array(elements.iter().map(Self::to_json).collect::<Option<_>>()?)
+            }
+            MsgPackValue::Map(entries) => JsonValue::Object(
+                entries
+                    .iter()
+                    .map(|(key, value)| Some((key.as_str()?.to_string(), value.to_json()?)))
+                    .collect::<Option<_>>()?,
+            ),
+        })
+    }
+}
+
+impl From<i64> for MsgPackValue {
+    fn from(value: i64) -> Self {
+        match u64::try_from(value) {
+            Ok(value) => MsgPackValue::UInt(value),
+            Err(_) => MsgPackValue::Int(value),
+        }
+    }
+}
+
+impl From<&str> for MsgPackValue {
+    fn from(value: &str) -> Self {
+        MsgPackValue::Str(value.to_string())
+    }
+}
+
+struct Decoder<'a> {
+    input: &'a [u8],
+    position: usize,
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn error(&self, kind: ErrorKind, position: usize) -> MsgPackError {
+        MsgPackError { kind, position }
+    }
+
+    fn take(&mut self, count: usize) -> Result<&'a [u8], MsgPackError> {
+        if self.input.len() - self.position < count {
+            return Err(self.error(ErrorKind::UnexpectedEnd, self.position));
+        }
+        let taken = &self.input[self.position..self.position + count];
+        self.position += count;
+        Ok(taken)
+    }
+
+    fn unsigned(&mut self, size: usize) -> Result<u64, MsgPackError> {
+        let bytes = self.take(size)?;
+        Ok(bytes
+            .iter()
+            .fold(0, |value, byte| value << 8 | *byte as u64))
+    }
+
+    // The bytes of the unsigned number, sign extended from its size.
+    fn signed(&mut self, size: usize) -> Result<i64, MsgPackError> {
+        let shift = 64 - size * 8;
+        Ok(((self.unsigned(size)? << shift) as i64) >> shift)
+    }
+
+    fn string(&mut self, length: usize, start: usize) -> Result<MsgPackValue, MsgPackError> {
+        let bytes = self.take(length)?;
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Ok(MsgPackValue::Str(text.to_string())),
+            Err(_) => Err(self.error(ErrorKind::InvalidUtf8, start)),
+        }
+    }
+
+    // The number of elements is not trusted for the allocation, as each
+    // element takes at least a byte.
+    fn array(&mut self, count: usize) -> Result<MsgPackValue, MsgPackError> {
+        let mut elements = Vec::with_capacity(count.min(self.input.len() - self.position));
+        for _ in 0..count {
+            elements.push(self.value()?);
+        }
+        Ok(MsgPackValue::Array(elements))
+    }
+
+    fn map(&mut self, count: usize) -> Result<MsgPackValue, MsgPackError> {
+        let mut entries = Vec::with_capacity(count.min(self.input.len() - self.position));
+        for _ in 0..count {
+            entries.push((self.value()?, self.value()?));
+        }
+        Ok(MsgPackValue::Map(entries))
+    }
+
+    fn nested(
+        &mut self,
+        start: usize,
+        parse: impl FnOnce(&mut Self) -> Result<MsgPackValue, MsgPackError>,
+    ) -> Result<MsgPackValue, MsgPackError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(self.error(ErrorKind::TooDeep, start));
+        }
+        let value = parse(self)?;
+        self.depth -= 1;
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<MsgPackValue, MsgPackError> {
+        let start = self.position;
+        let byte = self.take(1)?[0];
+        let value = match byte {
+            0x00..=0x7f => MsgPackValue::UInt(byte as u64),
+            0x80..=0x8f => self.nested(start, |this| this.map((byte & 0x0f) as usize))?,
+            0x90..=0x9f => self.nested(start, |this| this.array((byte & 0x0f) as usize))?,
+            0xa0..=0xbf => self.string((byte & 0x1f) as usize, start)?,
+            0xc0 => MsgPackValue::Nil,
+            0xc1 => return Err(self.error(ErrorKind::ReservedByte, start)),
+            0xc2 => MsgPackValue::Bool(false),
+            0xc3 => MsgPackValue::Bool(true),
+            0xc4..=0xc6 => {
+                let length = self.unsigned(1 << (byte - 0xc4))? as usize;
+                MsgPackValue::Bin(self.take(length)?.to_vec())
+            }
+            0xca => {
+                let bits = self.unsigned(4)? as u32;
+                MsgPackValue::Float(f32::from_bits(bits) as f64)
+            }
+            0xcb => MsgPackValue::Float(f64::from_bits(self.unsigned(8)?)),
+            0xcc..=0xcf => MsgPackValue::UInt(self.unsigned(1 << (byte - 0xcc))?),
+            0xd0..=0xd3 => MsgPackValue::from(self.signed(1 << (byte - 0xd0))?),
+            0xd9..=0xdb => {
+                let length = self.unsigned(1 << (byte - 0xd9))? as usize;
+                self.string(length, start)?
+            }
+            0xdc | 0xdd => {
+                let count = self.unsigned(2 << (byte - 0xdc))? as usize;
+                self.nested(start, |this| this.array(count))?
+            }
+            0xde | 0xdf => {
+                let count = self.unsigned(2 << (byte - 0xde))? as usize;
+                self.nested(start, |this| this.map(count))?
+            }
+            0xe0..=0xff => MsgPackValue::Int(byte as i8 as i64),
+            _ => return Err(self.error(ErrorKind::Unsupported(byte), start)),
+        };
+        Ok(value)
+    }
+}
+
+pub fn decode(input: &[u8]) -> Result<MsgPackValue, MsgPackError> {
+    let mut decoder = Decoder {
+        input,
+        position: 0,
+        depth: 0,
+    };
+    let value = decoder.value()?;
+    if decoder.position != input.len() {
+        return Err(decoder.error(ErrorKind::TrailingData, decoder.position));
+    }
+    Ok(value)
+}
+
+impl fmt::Display for MsgPackValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MsgPackValue::Nil => write!(f, "nil"),
+            MsgPackValue::Bool(value) => write!(f, "{}", value),
+            MsgPackValue::UInt(value) => write!(f, "{}", value),
+            MsgPackValue::Int(value) => write!(f, "{}", value),
+            MsgPackValue::Float(value) => write!(f, "{:?}", value),
+            MsgPackValue::Str(value) => write!(f, "{:?}", value),
+            MsgPackValue::Bin(value) => write!(f, "<{} bytes>", value.len()),
+            MsgPackValue::Array(elements) => {
+                write!(f, "[")?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]")
+            }
+            MsgPackValue::Map(entries) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+// Demonstration
+
+use crate::encoding::HEX;
+use crate::harness::Harness;
+
+const DOCUMENT: &str = r#"{
+    "name": "snippets",
+    "version": 3,
+    "stable": true,
+    "license": null,
+    "ratio": 0.875,
+    "downloads": [120, 4500, 70000, 1048576],
+    "offsets": [-1, -32, -100, -40000],
+    "authors": [{"name": "Dénes Fintha", "commits": 1200}],
+    "description": "A collection of small, self-contained programs, each demonstrating an algorithm or a technique."
+}"#;
+
+pub fn demo(harness: &mut Harness) {
+    let hex = |value: MsgPackValue| HEX.encode(&value.encode().unwrap());
+    test!(
+        harness,
+        "the integers take the shortest form",
+        [
+            0,
+            127,
+            128,
+            256,
+            65536,
+            -1,
+            -32,
+            -33,
+            -129,
+            -40000,
+            i64::MIN
+        ]
+        .map(|value| hex(MsgPackValue::from(value))),
+        [
+            "00",
+            "7f",
+            "cc80",
+            "cd0100",
+            "ce00010000",
+            "ff",
+            "e0",
+            "d0df",
+            "d1ff7f",
+            "d2ffff63c0",
+            "d38000000000000000",
+        ]
+        .map(String::from)
+    );
+    test!(
+        harness,
+        "the other scalars have their own type bytes",
+        [
+            MsgPackValue::Nil,
+            MsgPackValue::Bool(false),
+            MsgPackValue::Bool(true),
+            MsgPackValue::Float(1.5),
+            MsgPackValue::from("hi"),
+            MsgPackValue::Bin(vec![1, 2]),
+        ]
+        .map(hex),
+        ["c0", "c2", "c3", "cb3ff8000000000000", "a26869", "c4020102"].map(String::from)
+    );
+    test!(
+        harness,
+        "the lengths grow with the strings and the arrays",
+        [
+            hex(MsgPackValue::Str("x".repeat(31)))[..2].to_string(),
+            hex(MsgPackValue::Str("x".repeat(32)))[..4].to_string(),
+            hex(MsgPackValue::Str("x".repeat(300)))[..6].to_string(),
+            hex(MsgPackValue::Array(vec![MsgPackValue::Nil; 15]))[..2].to_string(),
+            hex(MsgPackValue::Array(vec![MsgPackValue::Nil; 16]))[..6].to_string(),
+            hex(MsgPackValue::Map(vec![
+                (
+                    MsgPackValue::Nil,
+                    MsgPackValue::Nil
+                );
+                70000
+            ]))[..10]
+                .to_string(),
+        ],
+        ["bf", "d920", "da012c", "9f", "dc0010", "df00011170"].map(String::from)
+    );
+
+    let values = [
+        MsgPackValue::Nil,
+        MsgPackValue::from(i64::MIN),
+        MsgPackValue::UInt(u64::MAX),
+        MsgPackValue::Float(-0.25),
+        MsgPackValue::from("árvíztűrő tükörfúrógép"),
+        MsgPackValue::Bin((0..=255).collect()),
+        MsgPackValue::Map(vec![
+            (MsgPackValue::from(1), MsgPackValue::from("integer key")),
+            (
+                MsgPackValue::Array(vec![MsgPackValue::Bool(true)]),
+                MsgPackValue::from("array key"),
+            ),
+        ]),
+    ];
+    test!(
+        harness,
+        "values are round-tripped",
+        values
+            .iter()
+            .map(|value| decode(&value.encode().unwrap()))
+            .collect::<Vec<_>>(),
+        values.iter().cloned().map(Ok).collect::<Vec<_>>()
+    );
+    test!(
+        harness,
+        "the longer forms of small values are accepted too",
+        [
+            decode(&[0xcf, 0, 0, 0, 0, 0, 0, 0, 5]),
+            decode(&[0xd0, 0x05]),
+            decode(&[0xca, 0x3f, 0xc0, 0, 0]),
+            decode(&[0xdb, 0, 0, 0, 1, b'a']),
+        ],
+        [
+            Ok(MsgPackValue::UInt(5)),
+            Ok(MsgPackValue::UInt(5)),
+            Ok(MsgPackValue::Float(1.5)),
+            Ok(MsgPackValue::from("a")),
+        ]
+    );
+    let error = |kind, position| Err(MsgPackError { kind, position });
+    let nested = [vec![0x91; MAX_DEPTH + 1], vec![0xc0]].concat();
+    test!(
+        harness,
+        "broken inputs are rejected where they go wrong",
+        [
+            decode(&[]),
+            decode(&[0xc1]),
+            decode(&[0xd4, 0x01, 0x02]),
+            decode(&[0x92, 0x01]),
+            decode(&[0xa2, 0xc3, 0x28]),
+            decode(&[0x01, 0x02]),
+            decode(&[0xdd, 0xff, 0xff, 0xff, 0xff]),
+            decode(&nested),
+        ],
+        [
+            error(ErrorKind::UnexpectedEnd, 0),
+            error(ErrorKind::ReservedByte, 0),
+            error(ErrorKind::Unsupported(0xd4), 0),
+            error(ErrorKind::UnexpectedEnd, 2),
+            error(ErrorKind::InvalidUtf8, 0),
+            error(ErrorKind::TrailingData, 1),
+            error(ErrorKind::UnexpectedEnd, 5),
+            error(ErrorKind::TooDeep, MAX_DEPTH),
+        ]
+    );
+    let mut output = vec![0x91];
+    test!(
+        harness,
+        "lengths of 4 GiB and more are not encoded",
+        (
+            write_length(&mut output, 1 << 32, None, [0xc4, 0xc5, 0xc6]),
+            output
+        ),
+        (
+            Err(MsgPackError {
+                kind: ErrorKind::TooLong,
+                position: 1
+            }),
+            vec![0x91]
+        )
+    );
+
+    let json = crate::json::parse(DOCUMENT).unwrap();
+    let packed = MsgPackValue::from_json(&json);
+    let bytes = packed.encode().unwrap();
+    let compact = json.to_string();
+    test!(
+        harness,
+        "a JSON document survives the round-trip through MessagePack",
+        decode(&bytes).map(|value| value.to_json()),
+        Ok(Some(json.clone()))
+    );
+    test!(
+        harness,
+        "the integral numbers of JSON become integers",
+        (
+            packed.get("version").and_then(MsgPackValue::as_i64),
+            packed.get("ratio").cloned()
+        ),
+        (Some(3), Some(MsgPackValue::Float(0.875)))
+    );
+    test!(
+        harness,
+        "byte arrays have no JSON form",
+        MsgPackValue::Array(vec![MsgPackValue::Bin(vec![0])]).to_json(),
+        None
+    );
+    test!(
+        harness,
+        "the MessagePack form is smaller than the compact JSON",
+        bytes.len() < compact.len(),
+        true
+    );
+    note!(
+        harness,
+        "The document takes {} bytes as compact JSON, {} bytes as MessagePack ({:.0}%):\n{}",
+        compact.len(),
+        bytes.len(),
+        100.0 * bytes.len() as f64 / compact.len() as f64,
+        packed
+    );
+}
@@ -0,0 +1,711 @@
+// ntp.rs
+// A client of the Simple Network Time Protocol of RFC 4330, which asks a time
+// server for the time over UDP, and works out how far the local clock is off.
+// A packet is 48 bytes: the leap indicator, the version, and the mode in the
+// first byte, then the stratum, the distance from a reference clock, and then
+// four timestamps, as seconds since 1900, with a 32-bit fraction. The client
+// sends the time it sent the request at, t1, and the server answers with it,
+// along with the time it received the request at, t2, and the time it sent the
+// response at, t3. With the time the response arrived at, t4, the offset of the
+// local clock is the average of the two one-way differences, ((t2 - t1) + (t3 -
+// t4)) / 2, which is exact if the network is as slow in both directions, and
+// the round trip delay is (t4 - t1) - (t3 - t2), the time spent on the network.
+// The 32-bit seconds wrap around in 2036, so a timestamp with its highest bit
+// clear is taken to be in the next era. The sample with the shortest delay of
+// a few is the most accurate. The binary asks the server it is given, and the
+// demonstration asks a local server, whose clock is off on purpose.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::datetime::DateTime;
+use std::fmt;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    Io,
+    Timeout,
+    TooShort,
+    WrongMode,
+    BogusOrigin,
+    Unsynchronized,
+    KissOfDeath,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NtpError {
+    pub kind: ErrorKind,
+    pub detail: String,
+}
+
+impl NtpError {
+    fn new(kind: ErrorKind, detail: impl Into<String>) -> Self {
+        Self {
+            kind,
+            detail: detail.into(),
+        }
+    }
+}
+
+impl fmt::Display for NtpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.kind {
+            ErrorKind::Io => "I/O error",
+            ErrorKind::Timeout => "timed out",
+            ErrorKind::TooShort => "packet too short",
+            ErrorKind::WrongMode => "not a server response",
+            ErrorKind::BogusOrigin => "the response is not for the request",
+            ErrorKind::Unsynchronized => "the server is not synchronized",
+            ErrorKind::KissOfDeath => "the server refused to answer",
+        };
+        write!(f, "{}: {}", message, self.detail)
+    }
+}
+
+impl std::error::Error for NtpError {}
+
+impl From<io::Error> for NtpError {
+    fn from(error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+                NtpError::new(ErrorKind::Timeout, error.to_string())
+            }
+            _ => NtpError::new(ErrorKind::Io, error.to_string()),
+        }
+    }
+}
+
+// The seconds from the NTP epoch of 1900 to the Unix epoch of 1970.
+pub const UNIX_OFFSET: i64 = 2_208_988_800;
+const ERA: i64 = 1 << 32;
+const NANOSECONDS: i128 = 1_000_000_000;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp {
+    pub seconds: u32,
+    pub fraction: u32,
+}
+
+impl Timestamp {
+    pub const ZERO: Timestamp = Timestamp {
+        seconds: 0,
+        fraction: 0,
+    };
+
+    // The fraction is rounded to the nearest 2^-32 seconds.
+    pub fn from_unix_nanos(nanoseconds: i128) -> Self {
+        let seconds = nanoseconds.div_euclid(NANOSECONDS);
+        let rest = nanoseconds.rem_euclid(NANOSECONDS);
+        let mut fraction = ((rest << 32) + NANOSECONDS / 2) / NANOSECONDS;
+        let mut seconds = seconds + UNIX_OFFSET as i128;
+        if fraction >> 32 != 0 {
+            fraction = 0;
+            seconds += 1;
+        }
+        Self {
+            seconds: seconds.rem_euclid(ERA as i128) as u32,
+            fraction: fraction as u32,
+        }
+    }
+
+    // Timestamps with the highest bit of the seconds clear are after 2036,
+    // in the second era, so the range is from 1968 to 2104.
+    pub fn to_unix_nanos(self) -> i128 {
+        let mut seconds = self.seconds as i64 - UNIX_OFFSET;
+        if self.seconds & 0x8000_0000 == 0 {
+            seconds += ERA;
+        }
+        let fraction = (self.fraction as i128 * NANOSECONDS + (1 << 31)) >> 32;
+        seconds as i128 * NANOSECONDS + fraction
+    }
+
+    pub fn now() -> Self {
+        Self::from_system(SystemTime::now())
+    }
+
+    pub fn from_system(time: SystemTime) -> Self {
+        let nanoseconds = match time.duration_since(UNIX_EPOCH) {
+            Ok(after) => after.as_nanos() as i128,
+            Err(before) => -(before.duration().as_nanos() as i128),
+        };
+        Self::from_unix_nanos(nanoseconds)
+    }
+
+    pub fn to_bytes(self) -> [u8; 8] {
+        let mut bytes = [0; 8];
+        bytes[..4].copy_from_slice(&self.seconds.to_be_bytes());
+        bytes[4..].copy_from_slice(&self.fraction.to_be_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: [u8; 8]) -> Self {
+        Self {
+            seconds: u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            fraction: u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+        }
+    }
+}
+
+// The calendar date and time of the instant, in UTC.
+pub fn date_time(nanoseconds: i128) -> DateTime {
+    let seconds = nanoseconds.div_euclid(NANOSECONDS) as i64;
+    let mut date_time = DateTime::from_unix_timestamp(seconds);
+    date_time.time.nanosecond = nanoseconds.rem_euclid(NANOSECONDS) as u32;
+    date_time
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Client,
+    Server,
+    Broadcast,
+    Other(u8),
+}
+
+impl Mode {
+    pub fn code(self) -> u8 {
+        match self {
+            Mode::Client => 3,
+            Mode::Server => 4,
+            Mode::Broadcast => 5,
+            Mode::Other(code) => code & 0x7,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            3 => Mode::Client,
+            4 => Mode::Server,
+            5 => Mode::Broadcast,
+            code => Mode::Other(code),
+        }
+    }
+}
+
+pub const LEAP_UNSYNCHRONIZED: u8 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Packet {
+    pub leap: u8,
+    pub version: u8,
+    pub mode: Mode,
+    pub stratum: u8,
+    // The poll interval and the precision, as powers of two seconds.
+    pub poll: i8,
+    pub precision: i8,
+    // In seconds, with a 16-bit fraction.
+    pub root_delay: u32,
+    pub root_dispersion: u32,
+    // The kind of the reference clock at stratum 1, the address of the server
+    // it synchronizes to above, and the kiss code at stratum 0.
+    pub reference_id: [u8; 4],
+    pub reference: Timestamp,
+    pub originate: Timestamp,
+    pub receive: Timestamp,
+    pub transmit: Timestamp,
+}
+
+impl Packet {
+    pub const SIZE: usize = 48;
+
+    // A request only needs the version, the mode, and the time it was sent at.
+    pub fn request(transmit: Timestamp) -> Self {
+        Self {
+            leap: 0,
+            version: 4,
+            mode: Mode::Client,
+            stratum: 0,
+            poll: 0,
+            precision: 0,
+            root_delay: 0,
+            root_dispersion: 0,
+            reference_id: [0; 4],
+            reference: Timestamp::ZERO,
+            originate: Timestamp::ZERO,
+            receive: Timestamp::ZERO,
+            transmit,
+        }
+    }
+
+    pub fn encode(&self) -> [u8; Packet::SIZE] {
+        let mut bytes = [0; Packet::SIZE];
+        bytes[0] = (self.leap & 0x3) << 6 | (self.version & 0x7) << 3 | self.mode.code();
+        bytes[1] = self.stratum;
+        bytes[2] = self.poll as u8;
+        bytes[3] = self.precision as u8;
+        bytes[4..8].copy_from_slice(&self.root_delay.to_be_bytes());
+        bytes[8..12].copy_from_slice(&self.root_dispersion.to_be_bytes());
+        bytes[12..16].copy_from_slice(&self.reference_id);
+        let timestamps = [self.reference, self.originate, self.receive, self.transmit];
+        for (index, timestamp) in timestamps.iter().enumerate() {
+            let start = 16 + index * 8;
+            bytes[start..start + 8].copy_from_slice(&timestamp.to_bytes());
+        }
+        bytes
+    }
+
+    // The extension fields and the authenticator after the first 48 bytes are
+    // ignored.
+    pub fn parse(bytes: &[u8]) -> Result<Self, NtpError> {
+        if bytes.len() < Packet::SIZE {
+            return Err(NtpError::new(
+                ErrorKind::TooShort,
+                format!("{} bytes", bytes.len()),
+            ));
+        }
+        let word = |start: usize| {
+            u32::from_be_bytes([
+                bytes[start],
+                bytes[start + 1],
+                bytes[start + 2],
+                bytes[start + 3],
+            ])
+        };
+        let timestamp =
+            |start: usize| Timestamp::from_bytes(bytes[start..start + 8].try_into().unwrap());
+        Ok(Self {
+            leap: bytes[0] >> 6,
+            version: bytes[0] >> 3 & 0x7,
+            mode: Mode::from_code(bytes[0] & 0x7),
+            stratum: bytes[1],
+            poll: bytes[2] as i8,
+            precision: bytes[3] as i8,
+            root_delay: word(4),
+            root_dispersion: word(8),
+            reference_id: [bytes[12], bytes[13], bytes[14], bytes[15]],
+            reference: timestamp(16),
+            originate: timestamp(24),
+            receive: timestamp(32),
+            transmit: timestamp(40),
+        })
+    }
+
+    pub fn reference_name(&self) -> String {
+        let id = self.reference_id;
+        if self.stratum <= 1 {
+            id.iter()
+                .take_while(|byte| **byte != 0)
+                .map(|byte| *byte as char)
+                .collect()
+        } else {
+            format!("{}.{}.{}.{}", id[0], id[1], id[2], id[3])
+        }
+    }
+}
+
+// The result of one exchange, in nanoseconds. A positive offset means that
+// the local clock is behind the server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sample {
+    pub offset: i128,
+    pub delay: i128,
+    pub stratum: u8,
+}
+
+impl Sample {
+    // The current time, by the local clock, corrected with the offset.
+    pub fn corrected_now(&self) -> i128 {
+        Timestamp::now().to_unix_nanos() + self.offset
+    }
+}
+
+// The four timestamps are the request sent, the request received, the
+// response sent, and the response received.
+pub fn offset_and_delay(t1: i128, t2: i128, t3: i128, t4: i128) -> (i128, i128) {
+    (((t2 - t1) + (t3 - t4)) / 2, (t4 - t1) - (t3 - t2))
+}
+
+// Checks the response against the request, and computes the sample.
+pub fn evaluate(
+    request: &Packet,
+    response: &Packet,
+    arrived: Timestamp,
+) -> Result<Sample, NtpError> {
+    if response.mode != Mode::Server && response.mode != Mode::Broadcast {
+        return Err(NtpError::new(
+            ErrorKind::WrongMode,
+            format!("mode {}", response.mode.code()),
+        ));
+    }
+    // The server echoes the transmit timestamp of the request, which an
+    // attacker who did not see the request could not guess.
+    if response.originate != request.transmit {
+        return Err(NtpError::new(
+            ErrorKind::BogusOrigin,
+            "the originate timestamp does not match",
+        ));
+    }
+    if response.stratum == 0 {
+        return Err(NtpError::new(
+            ErrorKind::KissOfDeath,
+            response.reference_name(),
+        ));
+    }
+    if response.leap == LEAP_UNSYNCHRONIZED || response.transmit == Timestamp::ZERO {
+        return Err(NtpError::new(
+            ErrorKind::Unsynchronized,
+            format!("stratum {}", response.stratum),
+        ));
+    }
+    let (offset, delay) = offset_and_delay(
+        request.transmit.to_unix_nanos(),
+        response.receive.to_unix_nanos(),
+        response.transmit.to_unix_nanos(),
+        arrived.to_unix_nanos(),
+    );
+    Ok(Sample {
+        offset,
+        delay,
+        stratum: response.stratum,
+    })
+}
+
+#[derive(Clone, Debug)]
+pub struct Client {
+    server: SocketAddr,
+    timeout: Duration,
+}
+
+impl Client {
+    // The port defaults to 123.
+    pub fn new(server: &str) -> Result<Self, NtpError> {
+        let server =
+            if let Ok(address) = server.parse::<std::net::IpAddr>() {
+                SocketAddr::new(address, 123)
+            } else if server.contains(':') {
+                server.to_socket_addrs()?.next().ok_or_else(|| {
+                    NtpError::new(ErrorKind::Io, format!("no address for {}", server))
+                })?
+            } else {
+                (server, 123).to_socket_addrs()?.next().ok_or_else(|| {
+                    NtpError::new(ErrorKind::Io, format!("no address for {}", server))
+                })?
+            };
+        Ok(Self {
+            server,
+            timeout: Duration::from_secs(2),
+        })
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn server(&self) -> SocketAddr {
+        self.server
+    }
+
+    // One exchange with the server. The responses to other requests, which may
+    // arrive late, are skipped.
+    pub fn query(&self) -> Result<(Packet, Sample), NtpError> {
+        let local = if self.server.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(self.server)?;
+        let request = Packet::request(Timestamp::now());
+        socket.send(&request.encode())?;
+        let deadline = Instant::now() + self.timeout;
+        let mut buffer = [0; 1024];
+        loop {
+            let remaining = deadline
+                .checked_duration_since(Instant::now())
+                .filter(|remaining| !remaining.is_zero())
+                .ok_or_else(|| {
+                    NtpError::new(
+                        ErrorKind::Timeout,
+                        format!("no response from {}", self.server),
+                    )
+                })?;
+            socket.set_read_timeout(Some(remaining))?;
+            let received = socket.recv(&mut buffer)?;
+            let arrived = Timestamp::now();
+            let response = Packet::parse(&buffer[..received])?;
+            match evaluate(&request, &response, arrived) {
+                Err(error) if error.kind == ErrorKind::BogusOrigin => continue,
+                result => return result.map(|sample| (response, sample)),
+            }
+        }
+    }
+
+    // The sample with the shortest round trip of a few, which is the one the
+    // least disturbed by the network.
+    pub fn best_of(&self, count: usize) -> Result<Sample, NtpError> {
+        let mut best: Option<Sample> = None;
+        let mut last_error = None;
+        for _ in 0..count.max(1) {
+            match self.query() {
+                Ok((_, sample)) => {
+                    if best.is_none_or(|best| sample.delay < best.delay) {
+                        best = Some(sample);
+                    }
+                }
+                // The server asks to stop, so it is not asked again.
+                Err(error) if error.kind == ErrorKind::KissOfDeath => return Err(error),
+                Err(error) => last_error = Some(error),
+            }
+        }
+        best.ok_or_else(|| last_error.unwrap())
+    }
+}
+
+// Milliseconds with a sign, and three decimals.
+pub fn format_nanos(nanoseconds: i128) -> String {
+    let sign = if nanoseconds < 0 { "-" } else { "+" };
+    let micros = (nanoseconds.abs() + 500) / 1000;
+    format!("{}{}.{:03} ms", sign, micros / 1000, micros % 1000)
+}
+
+fn report(sample: &Sample) -> String {
+    format!(
+        "offset {}, delay {}, stratum {}\nlocal clock:     {}\ncorrected clock: {}",
+        format_nanos(sample.offset),
+        format_nanos(sample.delay),
+        sample.stratum,
+        date_time(Timestamp::now().to_unix_nanos()),
+        date_time(sample.corrected_now())
+    )
+}
+
+// The entry point of the binary.
+pub fn main(mut arguments: impl Iterator<Item = String>) -> i32 {
+    use crate::args::Parser;
+    let program = crate::harness::program_name(arguments.next());
+    let parser = Parser::new(&program, "A client of the Simple Network Time Protocol.")
+        .optional(
+            "SERVER",
+            "Ask the server, instead of running the demonstration",
+        )
+        .option(
+            Some('c'),
+            "count",
+            "COUNT",
+            "Keep the best of this many samples (default: 4)",
+        )
+        .flag(
+            Some('q'),
+            "quiet",
+            "Only report the failed checks of the demonstration",
+        );
+    let matches = match parser.parse_or_report(arguments) {
+        Ok(matches) => matches,
+        Err(code) => return code,
+    };
+    let Some(server) = matches.value("SERVER") else {
+        return crate::harness::run_demo(demo, matches.flag("quiet"));
+    };
+    let Ok(count) = matches.value_or("count", "4").parse::<usize>() else {
+        eprintln!("{}: the count must be a number", program);
+        return 2;
+    };
+    match Client::new(server).and_then(|client| client.best_of(count)) {
+        Ok(sample) => {
+            println!("{}", report(&sample));
+            0
+        }
+        Err(error) => {
+            eprintln!("{}: {}", program, error);
+            1
+        }
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+const SKEW: i128 = 1_500_000_000;
+
+// A server whose clock is ahead of the local one, which takes a while to
+// answer. Some of the names in the requests change its answers.
+fn serve(socket: UdpSocket, stop: &AtomicBool, kiss: &AtomicBool) {
+    let mut buffer = [0; 1024];
+    let clock = || Timestamp::from_unix_nanos(Timestamp::now().to_unix_nanos() + SKEW);
+    while !stop.load(Ordering::SeqCst) {
+        let Ok((received, peer)) = socket.recv_from(&mut buffer) else {
+            continue;
+        };
+        let receive = clock();
+        let Ok(request) = Packet::parse(&buffer[..received]) else {
+            continue;
+        };
+        thread::sleep(Duration::from_millis(3));
+        let mut response = Packet {
+            leap: 0,
+            mode: Mode::Server,
+            stratum: 1,
+            poll: 6,
+            precision: -20,
+            reference_id: *b"GPS\0",
+            reference: receive,
+            originate: request.transmit,
+            receive,
+            ..Packet::request(Timestamp::ZERO)
+        };
+        if kiss.load(Ordering::SeqCst) {
+            response.stratum = 0;
+            response.reference_id = *b"RATE";
+        }
+        response.transmit = clock();
+        let _ = socket.send_to(&response.encode(), peer);
+    }
+}
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "the Unix epoch is 70 years after the NTP epoch",
+        (
+            Timestamp::from_unix_nanos(0),
+            Timestamp::from_unix_nanos(1_500_000_000)
+        ),
+        (
+            Timestamp {
+                seconds: UNIX_OFFSET as u32,
+                fraction: 0
+            },
+            Timestamp {
+                seconds: UNIX_OFFSET as u32 + 1,
+                fraction: 0x8000_0000
+            }
+        )
+    );
+    let sample = 1_790_000_000_123_456_789;
+    test!(
+        harness,
+        "timestamps round-trip to within a nanosecond",
+        Timestamp::from_unix_nanos(sample)
+            .to_unix_nanos()
+            .abs_diff(sample)
+            <= 1,
+        true
+    );
+    let after_wrap = Timestamp {
+        seconds: 1000,
+        fraction: 0,
+    };
+    test!(
+        harness,
+        "timestamps after the wrap in 2036 are in the next era",
+        date_time(after_wrap.to_unix_nanos()).to_string(),
+        "2036-02-07T06:44:56Z"
+    );
+
+    test!(
+        harness,
+        "the offset and delay of a symmetric exchange",
+        // The local clock is 100 behind the server, and each direction, and
+        // the server itself, take 10.
+        offset_and_delay(0, 110, 120, 30),
+        (100, 20)
+    );
+
+    let request = Packet::request(Timestamp::from_unix_nanos(sample));
+    let bytes = request.encode();
+    test!(
+        harness,
+        "a request is 48 bytes, mostly zeros",
+        (bytes[0], bytes[1..40].iter().all(|byte| *byte == 0)),
+        (0x23, true)
+    );
+    test!(
+        harness,
+        "a packet survives encoding and parsing",
+        Packet::parse(&bytes),
+        Ok(request)
+    );
+    test!(
+        harness,
+        "a short packet is rejected",
+        Packet::parse(&bytes[..40]).map_err(|error| error.kind),
+        Err(ErrorKind::TooShort)
+    );
+    let mut forged = Packet {
+        mode: Mode::Server,
+        stratum: 2,
+        ..request
+    };
+    forged.originate = Timestamp::ZERO;
+    test!(
+        harness,
+        "a response to another request is rejected",
+        evaluate(&request, &forged, Timestamp::now()).map_err(|error| error.kind),
+        Err(ErrorKind::BogusOrigin)
+    );
+
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_millis(20)))
+        .unwrap();
+    let address = socket.local_addr().unwrap();
+    let stop = Arc::new(AtomicBool::new(false));
+    let kiss = Arc::new(AtomicBool::new(false));
+    let server = {
+        let (stop, kiss) = (stop.clone(), kiss.clone());
+        thread::spawn(move || serve(socket, &stop, &kiss))
+    };
+
+    let client = Client::new(&address.to_string()).unwrap();
+    let (packet, _) = client.query().unwrap();
+    test!(
+        harness,
+        "the response names its reference clock",
+        (packet.mode, packet.stratum, packet.reference_name()),
+        (Mode::Server, 1, String::from("GPS"))
+    );
+    let sample = client.best_of(4).unwrap();
+    test!(
+        harness,
+        "the offset of the local clock is found",
+        (sample.offset - SKEW).abs() < 20_000_000,
+        true
+    );
+    test!(
+        harness,
+        "the delay excludes the time the server took",
+        sample.delay >= 0 && sample.delay < 20_000_000,
+        true
+    );
+    let corrected = sample.corrected_now();
+    let server_now = Timestamp::now().to_unix_nanos() + SKEW;
+    test!(
+        harness,
+        "the corrected clock agrees with the server",
+        (corrected - server_now).abs() < 20_000_000,
+        true
+    );
+    kiss.store(true, Ordering::SeqCst);
+    test!(
+        harness,
+        "a kiss of death stops the client",
+        client
+            .best_of(4)
+            .map_err(|error| (error.kind, error.detail)),
+        Err((ErrorKind::KissOfDeath, String::from("RATE")))
+    );
+    test!(
+        harness,
+        "a server which does not answer times out",
+        Client::new("127.0.0.1:9")
+            .unwrap()
+            .timeout(Duration::from_millis(50))
+            .query()
+            .map_err(|error| error.kind == ErrorKind::Timeout || error.kind == ErrorKind::Io),
+        Err(true)
+    );
+
+    stop.store(true, Ordering::SeqCst);
+    server.join().unwrap();
+    note!(harness, "From the local server, {}", report(&sample));
+}
@@ -0,0 +1,303 @@
+// numwords.rs
+// Spelling out integers in words, like on cheques, up to the quintillions of
+// the largest 64-bit integers. Numbers are split into groups of three digits,
+// each of which is spelled as hundreds, tens, and ones, followed by the name of
+// its scale, like thousand or million, skipping empty groups. The words come
+// from a language table, which holds the names of the numbers below twenty, of
+// the tens, and of the scales, along with the separators, and the rules of
+// the ordinals, so a language with a similar structure can be plugged in by
+// writing a table. The American and the British tables only differ in the use
+// of "and" before the tens. Ordinals are formed from the cardinals by changing
+// their last word, either from the irregular ones of the table, like first, or
+// by adding the regular suffix, and the numeric forms, like 42nd, use the
+// English suffixes, where the teens are always "th".
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+pub struct Language {
+    pub zero: &'static str,
+    pub minus: &'static str,
+    // The numbers from one to nineteen, with an empty string for zero.
+    pub ones: [&'static str; 20],
+    // The multiples of ten, with empty strings for zero and ten.
+    pub tens: [&'static str; 10],
+    pub hundred: &'static str,
+    // The powers of a thousand, with an empty string for the units.
+    pub scales: [&'static str; 7],
+    // Between the tens and the ones, like the hyphen of twenty-one.
+    pub tens_separator: &'static str,
+    // Before the tens and ones after hundreds or larger scales, if any.
+    pub and: Option<&'static str>,
+    // The ordinals of the words which do not take the regular suffix.
+    pub ordinals: &'static [(&'static str, &'static str)],
+    pub ordinal_suffix: &'static str,
+}
+
+#[rustfmt::skip]
+const ENGLISH_ONES: [&str; 20] = [
+    "", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen",
+    "eighteen", "nineteen",
+];
+
+#[rustfmt::skip]
+const ENGLISH_TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+#[rustfmt::skip]
+const ENGLISH_SCALES: [&str; 7] = [
+    "", "thousand", "million", "billion", "trillion", "quadrillion", "quintillion",
+];
+
+#[rustfmt::skip]
+const ENGLISH_ORDINALS: [(&str, &str); 15] = [
+    ("one", "first"), ("two", "second"), ("three", "third"), ("five", "fifth"),
+    ("eight", "eighth"), ("nine", "ninth"), ("twelve", "twelfth"), ("twenty", "twentieth"),
+    ("thirty", "thirtieth"), ("forty", "fortieth"), ("fifty", "fiftieth"),
+    ("sixty", "sixtieth"), ("seventy", "seventieth"), ("eighty", "eightieth"),
+    ("ninety", "ninetieth"),
+];
+
+pub const AMERICAN: Language = Language {
+    zero: "zero",
+    minus: "minus",
+    ones: ENGLISH_ONES,
+    tens: ENGLISH_TENS,
+    hundred: "hundred",
+    scales: ENGLISH_SCALES,
+    tens_separator: "-",
+    and: None,
+    ordinals: &ENGLISH_ORDINALS,
+    ordinal_suffix: "th",
+};
+
+pub const BRITISH: Language = Language {
+    and: Some("and"),
+    ..AMERICAN
+};
+
+// The words of a number below a hundred.
+fn tens(number: u64, language: &Language) -> String {
+    let (tens, ones) = ((number / 10) as usize, (number % 10) as usize);
+    if number < 20 {
+        language.ones[number as usize].to_string()
+    } else if ones == 0 {
+        language.tens[tens].to_string()
+    } else {
+        format!(
+            "{}{}{}",
+            language.tens[tens], language.tens_separator, language.ones[ones]
+        )
+    }
+}
+
+pub fn cardinal(number: i64, language: &Language) -> String {
+    if number == 0 {
+        return language.zero.to_string();
+    }
+    let mut groups = Vec::new();
+    let mut rest = number.unsigned_abs();
+    while rest > 0 {
+        groups.push(rest % 1000);
+        rest /= 1000;
+    }
+    let mut words: Vec<String> = Vec::new();
+    if number < 0 {
+        words.push(language.minus.to_string());
+    }
+    let mut larger = false;
+    for (scale, group) in groups.into_iter().enumerate().rev() {
+        if group == 0 {
+            continue;
+        }
+        if group >= 100 {
+            words.push(language.ones[(group / 100) as usize].to_string());
+            words.push(language.hundred.to_string());
+        }
+        if group % 100 > 0 {
+            if let Some(and) = language.and {
+                if group >= 100 || (scale == 0 && larger) {
+                    words.push(and.to_string());
+                }
+            }
+            words.push(tens(group % 100, language));
+        }
+        if scale > 0 {
+            words.push(language.scales[scale].to_string());
+        }
+        larger = true;
+    }
+    words.join(" ")
+}
+
+pub fn ordinal(number: i64, language: &Language) -> String {
+    let cardinal = cardinal(number, language);
+    let split = cardinal
+        .rfind([' ', '-'])
+        .map(|index| index + 1)
+        .unwrap_or(0);
+    let (head, last) = cardinal.split_at(split);
+    match language.ordinals.iter().find(|(word, _)| *word == last) {
+        Some((_, ordinal)) => format!("{}{}", head, ordinal),
+        None => format!("{}{}{}", head, last, language.ordinal_suffix),
+    }
+}
+
+// The English suffix of the ordinal, where eleven to thirteen take "th", even
+// in larger numbers, like 112th.
+pub fn ordinal_suffix(number: i64) -> &'static str {
+    let number = number.unsigned_abs();
+    if (11..=13).contains(&(number % 100)) {
+        return "th";
+    }
+    match number % 10 {
+        1 => "st",
+        2 => "nd",
+        3 => "rd",
+        _ => "th",
+    }
+}
+
+pub fn ordinal_numeral(number: i64) -> String {
+    format!("{}{}", number, ordinal_suffix(number))
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+// The amount in words, like on cheques, where the cents are written as a
+// fraction.
+fn cheque(cents: i64, language: &Language) -> String {
+    let words = cardinal(cents / 100, language);
+    let mut chars = words.chars();
+    let capitalized: String = chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default();
+    format!("{} and {:02}/100 dollars", capitalized, cents % 100)
+}
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "small numbers",
+        [0, 7, 13, 20, 42, 99].map(|n| cardinal(n, &AMERICAN)),
+        [
+            "zero",
+            "seven",
+            "thirteen",
+            "twenty",
+            "forty-two",
+            "ninety-nine"
+        ]
+    );
+    test!(
+        harness,
+        "hundreds and thousands",
+        [100, 101, 999, 1000, 1001, 30_512].map(|n| cardinal(n, &AMERICAN)),
+        [
+            "one hundred",
+            "one hundred one",
+            "nine hundred ninety-nine",
+            "one thousand",
+            "one thousand one",
+            "thirty thousand five hundred twelve"
+        ]
+    );
+    test!(
+        harness,
+        "empty groups are skipped",
+        cardinal(1_000_000_007, &AMERICAN),
+        "one billion seven"
+    );
+    test!(
+        harness,
+        "the largest and smallest 64-bit integers",
+        [i64::MAX, i64::MIN].map(|n| cardinal(n, &AMERICAN)),
+        [
+            "nine quintillion two hundred twenty-three quadrillion three hundred seventy-two \
+             trillion thirty-six billion eight hundred fifty-four million seven hundred \
+             seventy-five thousand eight hundred seven",
+            "minus nine quintillion two hundred twenty-three quadrillion three hundred \
+             seventy-two trillion thirty-six billion eight hundred fifty-four million seven \
+             hundred seventy-five thousand eight hundred eight"
+        ]
+    );
+    test!(
+        harness,
+        "British English uses \"and\" before the tens",
+        [105, 1005, 2350, 1_000_100, 1_000_000].map(|n| cardinal(n, &BRITISH)),
+        [
+            "one hundred and five",
+            "one thousand and five",
+            "two thousand three hundred and fifty",
+            "one million one hundred",
+            "one million"
+        ]
+    );
+    test!(
+        harness,
+        "ordinals in words",
+        [1, 2, 3, 5, 12, 20, 42, 100, 1_000_003].map(|n| ordinal(n, &AMERICAN)),
+        [
+            "first",
+            "second",
+            "third",
+            "fifth",
+            "twelfth",
+            "twentieth",
+            "forty-second",
+            "one hundredth",
+            "one million third"
+        ]
+    );
+    test!(
+        harness,
+        "ordinals of the tens, and of zero",
+        [0, 90, 99].map(|n| ordinal(n, &BRITISH)),
+        ["zeroth", "ninetieth", "ninety-ninth"]
+    );
+    test!(
+        harness,
+        "numeric ordinals",
+        [1, 2, 3, 4, 11, 12, 13, 21, 42, 101, 111, 112, 1003].map(ordinal_numeral),
+        [
+            "1st", "2nd", "3rd", "4th", "11th", "12th", "13th", "21st", "42nd", "101st", "111th",
+            "112th", "1003rd"
+        ]
+    );
+
+    let pirate = Language {
+        zero: "nothin'",
+        hundred: "hunnerd",
+        ..BRITISH
+    };
+    test!(
+        harness,
+        "plugging in a language table",
+        [0, 300].map(|n| cardinal(n, &pirate)),
+        ["nothin'", "three hunnerd"]
+    );
+
+    for cents in [4_250, 123_456, 10_000_001] {
+        note!(
+            harness,
+            "${:>12.2}  {}",
+            cents as f64 / 100.0,
+            cheque(cents, &AMERICAN)
+        );
+    }
+    for place in [1, 2, 3, 21, 42, 100] {
+        note!(
+            harness,
+            "{:>5}  {}",
+            ordinal_numeral(place),
+            ordinal(place, &BRITISH)
+        );
+    }
+}
@@ -0,0 +1,201 @@
+// pbkdf2.rs
+// The PBKDF2 password-based key derivation function, as specified in RFC 8018,
+// over the HMAC snippet, which makes guessing passwords expensive, by running
+// HMAC as many times as the iteration count, and XOR-ing the results together.
+// Each block of the derived key is computed on its own this way, from the salt
+// and the index of the block, so longer keys cost proportionally more. The salt
+// should be random and unique for every password, so that the same password
+// does not give the same key twice, and precomputed tables are useless. Keys
+// and password hashes must be compared in constant time, looking at every byte
+// regardless of where the first difference is, so that the time taken does not
+// leak how much of a guess was correct.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::hmac::{HashFunction, Hmac};
+use crate::sha2::Sha256;
+
+pub fn pbkdf2<H: HashFunction>(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    length: usize,
+) -> Vec<u8> {
+    assert!(iterations > 0, "the iteration count must be positive");
+    // The password is the key of every HMAC, so the padded key is hashed into
+    // the initial state only once.
+    let keyed = Hmac::<H>::new(password);
+    let mut output = Vec::with_capacity(length);
+    let mut index = 1u32;
+    while output.len() < length {
+        let mut hmac = keyed.clone();
+        hmac.update(salt);
+        hmac.update(&index.to_be_bytes());
+        let mut block = hmac.finalize();
+        let mut result = block.clone();
+        for _ in 1..iterations {
+            let mut hmac = keyed.clone();
+            hmac.update(&block);
+            block = hmac.finalize();
+            for (byte, value) in result.iter_mut().zip(&block) {
+                *byte ^= value;
+            }
+        }
+        output.extend_from_slice(&result);
+        index += 1;
+    }
+    output.truncate(length);
+    output
+}
+
+pub fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32, length: usize) -> Vec<u8> {
+    pbkdf2::<Sha256>(password, salt, iterations, length)
+}
+
+// Only the lengths are compared in variable time, which are not secret.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let difference = a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y));
+    std::hint::black_box(difference) == 0
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PasswordHash {
+    pub iterations: u32,
+    pub salt: Vec<u8>,
+    pub key: Vec<u8>,
+}
+
+impl PasswordHash {
+    pub const KEY_SIZE: usize = 32;
+
+    pub fn new(password: &str, salt: &[u8], iterations: u32) -> Self {
+        Self {
+            iterations,
+            salt: salt.to_vec(),
+            key: pbkdf2_sha256(password.as_bytes(), salt, iterations, Self::KEY_SIZE),
+        }
+    }
+
+    pub fn verify(&self, password: &str) -> bool {
+        let key = pbkdf2_sha256(
+            password.as_bytes(),
+            &self.salt,
+            self.iterations,
+            self.key.len(),
+        );
+        constant_time_eq(&key, &self.key)
+    }
+}
+
+// Demonstration
+
+use crate::encoding::HEX;
+use crate::harness::Harness;
+use crate::sha1::Sha1;
+use std::time::Instant;
+
+pub fn demo(harness: &mut Harness) {
+    // The PBKDF2-HMAC-SHA-256 vector of RFC 7914, and further ones, which are
+    // the RFC 6070 test cases with SHA-256 instead of SHA-1.
+    test!(
+        harness,
+        "PBKDF2-HMAC-SHA-256 of the RFC 7914 vector",
+        HEX.encode(&pbkdf2_sha256(b"passwd", b"salt", 1, 64)),
+        "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc\
+         49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783"
+    );
+    test!(
+        harness,
+        "PBKDF2-HMAC-SHA-256 with 4096 iterations",
+        HEX.encode(&pbkdf2_sha256(b"password", b"salt", 4096, 32)),
+        "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a"
+    );
+    test!(
+        harness,
+        "PBKDF2-HMAC-SHA-256 of a key longer than one block",
+        HEX.encode(&pbkdf2_sha256(
+            b"passwordPASSWORDpassword",
+            b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+            4096,
+            40
+        )),
+        "348c89dbcbd32b2f32d814b8116e84cf2b17347ebc1800181c4e2a1fb8dd53e1c635518c7dac47e9"
+    );
+    test!(
+        harness,
+        "PBKDF2-HMAC-SHA-256 of a password and a salt with zero bytes",
+        HEX.encode(&pbkdf2_sha256(b"pass\0word", b"sa\0lt", 4096, 16)),
+        "89b69d0516f829893c696226650a8687"
+    );
+    test!(
+        harness,
+        "PBKDF2-HMAC-SHA-1 of the RFC 6070 vectors",
+        [
+            HEX.encode(&pbkdf2::<Sha1>(b"password", b"salt", 2, 20)),
+            HEX.encode(&pbkdf2::<Sha1>(b"password", b"salt", 4096, 20)),
+        ],
+        [
+            "ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957",
+            "4b007901b765489abead49d926f721d065a429c1"
+        ]
+    );
+    test!(
+        harness,
+        "constant-time comparison",
+        (
+            constant_time_eq(b"secret", b"secret"),
+            constant_time_eq(b"secret", b"secreT"),
+            constant_time_eq(b"secret", b"secrets"),
+            constant_time_eq(b"", b"")
+        ),
+        (true, false, false, true)
+    );
+
+    let stored = PasswordHash::new("correct horse battery staple", b"NaCl and pepper", 10_000);
+    test!(
+        harness,
+        "verifying the right password",
+        stored.verify("correct horse battery staple"),
+        true
+    );
+    test!(
+        harness,
+        "rejecting a wrong password",
+        stored.verify("correct horse battery stapler"),
+        false
+    );
+    test!(
+        harness,
+        "the same password with another salt gives another key",
+        PasswordHash::new("correct horse battery staple", b"another salt", 10_000).key
+            == stored.key,
+        false
+    );
+
+    note!(harness, "Derived key: {}", HEX.encode(&stored.key));
+    if harness.is_quiet() {
+        return;
+    }
+    note!(
+        harness,
+        "Time taken by PBKDF2-HMAC-SHA-256, by iteration count:"
+    );
+    for iterations in [1_000, 10_000, 100_000] {
+        let start = Instant::now();
+        let key = pbkdf2_sha256(b"password", b"salt", iterations, 32);
+        let elapsed = start.elapsed();
+        note!(
+            harness,
+            "    {:>7} iterations: {:>9.3} ms  {}",
+            iterations,
+            elapsed.as_secs_f64() * 1000.0,
+            &HEX.encode(&key)[..16]
+        );
+    }
+}
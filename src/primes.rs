@@ -0,0 +1,291 @@
+// primes.rs
+// Finding, testing, and factoring prime numbers. The Sieve of Eratosthenes
+// finds every prime in a range by crossing out the multiples of the primes
+// below its square root. The segmented variant sieves the range in blocks that
+// fit in the cache, so it only needs memory proportional to the square root of
+// the range. The Miller-Rabin test decides whether a single number is prime, by
+// checking a property of primes for a few witnesses. It is a probabilistic
+// test, but with the first twelve primes as witnesses, there is no 64-bit
+// composite that would pass it. The factorization is simple trial division by
+// 2, 3, and 6k +/- 1.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+const SEGMENT_SIZE: u64 = 32768;
+
+// The simple sieve, which is used for the base primes of the segments.
+pub fn sieve(limit: u64) -> Vec<u64> {
+    let mut composite = vec![false; limit as usize];
+    let mut primes = Vec::new();
+    for n in 2..limit {
+        if composite[n as usize] {
+            continue;
+        }
+        primes.push(n);
+        let mut multiple = n * n;
+        while multiple < limit {
+            composite[multiple as usize] = true;
+            multiple += n;
+        }
+    }
+    primes
+}
+
+// Every prime in the range [low, high).
+pub fn primes_in(low: u64, high: u64) -> Vec<u64> {
+    let low = low.max(2);
+    if low >= high {
+        return Vec::new();
+    }
+    let base = sieve((high - 1).isqrt() + 1);
+    let mut primes = Vec::new();
+    let mut start = low;
+    while start < high {
+        let end = high.min(start + SEGMENT_SIZE);
+        let mut composite = [false; SEGMENT_SIZE as usize];
+        for p in &base {
+            // Smaller multiples were crossed out by smaller primes already.
+            let first = (p * p).max(start.div_ceil(*p) * p);
+            let mut multiple = first;
+            while multiple < end {
+                composite[(multiple - start) as usize] = true;
+                multiple += p;
+            }
+        }
+        primes.extend((start..end).filter(|n| !composite[(n - start) as usize]));
+        start = end;
+    }
+    primes
+}
+
+pub fn primes_below(limit: u64) -> Vec<u64> {
+    primes_in(2, limit)
+}
+
+fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    (a as u128 * b as u128 % modulus as u128) as u64
+}
+
+pub fn pow_mod(base: u64, exponent: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+    let mut base = base % modulus;
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exponent >>= 1;
+    }
+    result
+}
+
+// With n - 1 = d 2^s, for a prime n, either a^d = 1, or one of the squares
+// a^(d 2^r) = -1 modulo n. If neither holds, the witness proves, that n is
+// composite, otherwise n is a strong probable prime to the base.
+pub fn is_probable_prime(n: u64, witnesses: &[u64]) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for p in [2, 3, 5, 7] {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'witness: for a in witnesses {
+        if a % n == 0 {
+            continue;
+        }
+        let mut x = pow_mod(*a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+pub const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+pub fn is_prime(n: u64) -> bool {
+    is_probable_prime(n, &WITNESSES)
+}
+
+// The prime factors with their multiplicities, in increasing order. When the
+// square of the candidate exceeds the rest, or the rest passes the primality
+// test, the rest itself is prime, so a large prime factor is not searched for.
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    let mut factors = Vec::new();
+    let mut rest = n;
+    let mut divide = |rest: &mut u64, factor: u64| {
+        let mut exponent = 0;
+        while rest.is_multiple_of(factor) {
+            *rest /= factor;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((factor, exponent));
+        }
+        exponent > 0
+    };
+    divide(&mut rest, 2);
+    divide(&mut rest, 3);
+    let mut candidate: u64 = 5;
+    let mut prime_rest = is_prime(rest);
+    while !prime_rest
+        && candidate
+            .checked_mul(candidate)
+            .is_some_and(|square| square <= rest)
+    {
+        if divide(&mut rest, candidate) | divide(&mut rest, candidate + 2) {
+            prime_rest = is_prime(rest);
+        }
+        candidate += 6;
+    }
+    if rest > 1 {
+        factors.push((rest, 1));
+    }
+    factors
+}
+
+pub fn format_factors(factors: &[(u64, u32)]) -> String {
+    factors
+        .iter()
+        .map(|(factor, exponent)| match exponent {
+            1 => factor.to_string(),
+            _ => format!("{}^{}", factor, exponent),
+        })
+        .collect::<Vec<_>>()
+        .join(" * ")
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    let small = primes_below(100);
+    test!(harness, "there are 25 primes below 100", small.len(), 25);
+    test!(
+        harness,
+        "the segmented sieve agrees with the simple sieve",
+        primes_below(200000),
+        sieve(200000)
+    );
+    test!(
+        harness,
+        "there are 78498 primes below one million",
+        primes_below(1000000).len(),
+        78498
+    );
+    let high = primes_in(1000000000000, 1000000001000);
+    test!(
+        harness,
+        "the primes after 10^12 are found without sieving from zero",
+        (high.len(), high[..4].to_vec()),
+        (
+            37,
+            vec![1000000000039, 1000000000061, 1000000000063, 1000000000091]
+        )
+    );
+    test!(
+        harness,
+        "the sieve and Miller-Rabin agree after 10^12",
+        (1000000000000..1000000001000)
+            .filter(|n| is_prime(*n))
+            .collect::<Vec<_>>(),
+        high
+    );
+
+    test!(
+        harness,
+        "0 and 1 are not primes",
+        (is_prime(0), is_prime(1)),
+        (false, false)
+    );
+    test!(
+        harness,
+        "the Carmichael number 561 is composite",
+        (is_prime(561), pow_mod(2, 560, 561)),
+        (false, 1)
+    );
+    test!(
+        harness,
+        "the Mersenne number 2^61 - 1 is prime",
+        is_prime((1 << 61) - 1),
+        true
+    );
+    test!(
+        harness,
+        "the largest 64-bit prime",
+        (is_prime(u64::MAX - 58), is_prime(u64::MAX)),
+        (true, false)
+    );
+    // This composite is a strong pseudoprime to every prime base up to 23,
+    // so it takes the 29 witness to be caught.
+    let pseudoprime = 3825123056546413051;
+    test!(
+        harness,
+        "a strong pseudoprime to the first nine bases",
+        (
+            is_probable_prime(pseudoprime, &WITNESSES[..9]),
+            is_prime(pseudoprime)
+        ),
+        (true, false)
+    );
+
+    test!(
+        harness,
+        "factoring 360",
+        factorize(360),
+        vec![(2, 3), (3, 2), (5, 1)]
+    );
+    test!(
+        harness,
+        "factoring 2^64 - 1",
+        factorize(u64::MAX),
+        vec![
+            (3, 1),
+            (5, 1),
+            (17, 1),
+            (257, 1),
+            (641, 1),
+            (65537, 1),
+            (6700417, 1)
+        ]
+    );
+    test!(
+        harness,
+        "a prime is its own factorization",
+        factorize(u64::MAX - 58),
+        vec![(u64::MAX - 58, 1)]
+    );
+
+    note!(harness, "Primes below 100:");
+    for row in small.chunks(10) {
+        let row: Vec<String> = row.iter().map(|p| format!("{:>2}", p)).collect();
+        note!(harness, "  {}", row.join(" "));
+    }
+    note!(harness);
+    for n in [
+        600851475143,
+        1000002936999811,
+        4611686018427387905,
+        pseudoprime,
+        u64::MAX,
+    ] {
+        note!(harness, "{:>20} = {}", n, format_factors(&factorize(n)));
+    }
+    note!(harness);
+}
@@ -0,0 +1,477 @@
+// progress.rs
+// Progress bars and spinners for the terminal, which redraw themselves in
+// place. A single line is redrawn by returning to its start with a carriage
+// return, while a block of lines moves the cursor up with an ANSI sequence,
+// and clears each line before writing it again. Bars show the percentage, the
+// rate, and the estimated time left, which assumes that the average rate so
+// far stays the same. Using eighth blocks for the partial cell makes the bar
+// grow smoothly, even when it is narrow. Bars can be nested, so that advancing
+// a bar advances its parents too, like the overall progress of a download of
+// several files. The time is passed in as the duration since an arbitrary
+// moment, so the bars work with a real clock, and with a simulated one.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::ansi::Control;
+use crate::leftpad::{display_width, rightpad_display};
+use std::time::Duration;
+
+const EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+// A bar of the given width in cells, filled to the fraction.
+pub fn bar(fraction: f64, width: usize) -> String {
+    let eighths = (fraction.clamp(0.0, 1.0) * (width * 8) as f64).round() as usize;
+    let mut out = "█".repeat(eighths / 8);
+    if eighths / 8 < width {
+        out.push(EIGHTHS[eighths % 8]);
+        out.push_str(&" ".repeat(width - eighths / 8 - 1));
+    }
+    out
+}
+
+// Amounts of bytes are shown in binary units, with one decimal.
+pub fn format_amount(amount: f64, bytes: bool) -> String {
+    if !bytes {
+        return if amount.fract() == 0.0 {
+            format!("{}", amount)
+        } else {
+            format!("{:.1}", amount)
+        };
+    }
+    let units = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut amount = amount;
+    let mut unit = 0;
+    while amount >= 1024.0 && unit + 1 < units.len() {
+        amount /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", amount.round())
+    } else {
+        format!("{:.1} {}", amount, units[unit])
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    } else {
+        format!("{:02}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProgressBar {
+    label: String,
+    total: u64,
+    position: u64,
+    width: usize,
+    bytes: bool,
+    started: Duration,
+    finished: Option<Duration>,
+}
+
+impl ProgressBar {
+    pub fn new(label: &str, total: u64) -> Self {
+        Self {
+            label: label.to_string(),
+            total,
+            position: 0,
+            width: 24,
+            bytes: false,
+            started: Duration::ZERO,
+            finished: None,
+        }
+    }
+
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    // Shows the position and the rate in bytes.
+    pub fn bytes(mut self) -> Self {
+        self.bytes = true;
+        self
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    pub fn start(&mut self, now: Duration) {
+        self.started = now;
+        self.finished = None;
+        self.position = 0;
+    }
+
+    // Returns how much the position changed, as it stops at the total.
+    pub fn set(&mut self, position: u64, now: Duration) -> u64 {
+        let previous = self.position;
+        self.position = position.min(self.total);
+        if self.is_finished() && self.finished.is_none() {
+            self.finished = Some(now);
+        }
+        self.position - previous.min(self.position)
+    }
+
+    pub fn advance(&mut self, amount: u64, now: Duration) -> u64 {
+        self.set(self.position.saturating_add(amount), now)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.total
+    }
+
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        self.position as f64 / self.total as f64
+    }
+
+    // The time since the start, or until the end, if the bar is finished.
+    pub fn elapsed(&self, now: Duration) -> Duration {
+        self.finished.unwrap_or(now).saturating_sub(self.started)
+    }
+
+    // The average rate since the start, per second.
+    pub fn rate(&self, now: Duration) -> Option<f64> {
+        let elapsed = self.elapsed(now).as_secs_f64();
+        if elapsed == 0.0 {
+            return None;
+        }
+        Some(self.position as f64 / elapsed)
+    }
+
+    pub fn eta(&self, now: Duration) -> Option<Duration> {
+        let rate = self.rate(now).filter(|rate| *rate > 0.0)?;
+        let remaining = (self.total - self.position) as f64 / rate;
+        Some(Duration::from_secs_f64(remaining.ceil()))
+    }
+
+    pub fn render(&self, now: Duration) -> String {
+        let rate = match self.rate(now) {
+            Some(rate) => format!("{}/s", format_amount(rate, self.bytes)),
+            None => "-".to_string(),
+        };
+        let time = if self.is_finished() {
+            format!("in {}", format_duration(self.elapsed(now)))
+        } else {
+            match self.eta(now) {
+                Some(eta) => format!("ETA {}", format_duration(eta)),
+                None => "ETA --:--".to_string(),
+            }
+        };
+        format!(
+            "{} [{}] {:>3}% {}/{} {} {}",
+            self.label,
+            bar(self.fraction(), self.width),
+            (self.fraction() * 100.0).floor(),
+            format_amount(self.position as f64, self.bytes),
+            format_amount(self.total as f64, self.bytes),
+            rate,
+            time
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Spinner {
+    label: String,
+    frame: usize,
+}
+
+impl Spinner {
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            frame: 0,
+        }
+    }
+
+    pub fn tick(&mut self) {
+        self.frame = (self.frame + 1) % SPINNER.len();
+    }
+
+    pub fn render(&self) -> String {
+        format!("{} {}", SPINNER[self.frame], self.label)
+    }
+
+    pub fn finish(&self, message: &str) -> String {
+        format!("✓ {} {}", self.label, message)
+    }
+}
+
+// A tree of bars, where advancing a bar advances each of its ancestors.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MultiProgress {
+    bars: Vec<(ProgressBar, Option<usize>, usize)>,
+}
+
+impl MultiProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, bar: ProgressBar) -> usize {
+        self.bars.push((bar, None, 0));
+        self.bars.len() - 1
+    }
+
+    pub fn add_child(&mut self, parent: usize, bar: ProgressBar) -> usize {
+        let depth = self.bars[parent].2 + 1;
+        self.bars.push((bar, Some(parent), depth));
+        self.bars.len() - 1
+    }
+
+    pub fn bar(&self, index: usize) -> &ProgressBar {
+        &self.bars[index].0
+    }
+
+    pub fn start(&mut self, index: usize, now: Duration) {
+        self.bars[index].0.start(now);
+    }
+
+    // The ancestors advance as much as the bar did.
+    pub fn advance(&mut self, index: usize, amount: u64, now: Duration) {
+        let amount = self.bars[index].0.advance(amount, now);
+        let mut current = self.bars[index].1;
+        while let Some(index) = current {
+            self.bars[index].0.advance(amount, now);
+            current = self.bars[index].1;
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.bars.iter().all(|(bar, _, _)| bar.is_finished())
+    }
+
+    // The lines of the bars, indented by their depth, and with their labels
+    // padded to the same width.
+    pub fn render(&self, now: Duration) -> Vec<String> {
+        let width = self
+            .bars
+            .iter()
+            .map(|(bar, _, depth)| 2 * depth + display_width(bar.label()))
+            .max()
+            .unwrap_or(0);
+        self.bars
+            .iter()
+            .map(|(bar, _, depth)| {
+                let label = format!("{}{}", "  ".repeat(*depth), bar.label());
+                let mut padded = bar.clone();
+                padded.label = rightpad_display(&label, width as isize, ' '.into());
+                padded.render(now)
+            })
+            .collect()
+    }
+}
+
+// Keeps track of the lines drawn last, so that the next frame can overwrite
+// them. The returned strings are to be written as they are.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Redraw {
+    lines: usize,
+}
+
+impl Redraw {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Overwrites the current line, leaving the cursor at its end.
+    pub fn line(&self, line: &str) -> String {
+        format!("\r{}{}", line, Control::ClearToEndOfLine.sequence())
+    }
+
+    // Overwrites the lines of the previous frame, leaving the cursor below the
+    // new one. A shorter frame clears the rest of the previous one.
+    pub fn frame(&mut self, lines: &[String]) -> String {
+        let mut out = String::new();
+        if self.lines > 0 {
+            out.push_str(&Control::Up(self.lines as u16).sequence());
+        }
+        for line in lines {
+            out.push_str(&format!("\r{}{}\n", Control::ClearLine.sequence(), line));
+        }
+        for _ in lines.len()..self.lines {
+            out.push_str(&format!("\r{}\n", Control::ClearLine.sequence()));
+        }
+        self.lines = lines.len().max(self.lines);
+        out
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use crate::rng::{RandomSource, SplitMix64};
+use std::io::Write;
+
+const FILES: [(&str, u64); 3] = [
+    ("kernel.img", 6 << 20),
+    ("initrd.img", 3 << 20),
+    ("modules.tar", 9 << 20),
+];
+
+// Simulates a download of the files, one at a time, at a varying speed, in
+// steps of 100 milliseconds, and calls the callback after each step.
+fn download(mut show: impl FnMut(&MultiProgress, Duration)) -> (MultiProgress, Duration) {
+    let step = Duration::from_millis(100);
+    let mut random = SplitMix64::new(5);
+    let mut progress = MultiProgress::new();
+    let total = FILES.iter().map(|(_, size)| size).sum();
+    let overall = progress.add(ProgressBar::new("total", total).bytes());
+    let files: Vec<usize> = FILES
+        .iter()
+        .map(|(name, size)| progress.add_child(overall, ProgressBar::new(name, *size).bytes()))
+        .collect();
+    let mut now = Duration::ZERO;
+    for file in files {
+        progress.start(file, now);
+        while !progress.bar(file).is_finished() {
+            // Between 1 and 5 MiB per second.
+            let speed = (1 << 20) + random.next_below(4 << 20);
+            now += step;
+            progress.advance(file, speed / 10, now);
+            show(&progress, now);
+        }
+    }
+    (progress, now)
+}
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "bars with partial cells",
+        (bar(0.0, 4), bar(0.55, 4), bar(1.0, 4)),
+        ("    ".to_string(), "██▎ ".to_string(), "████".to_string())
+    );
+    test!(
+        harness,
+        "amounts in binary units",
+        (
+            format_amount(512.0, true),
+            format_amount(1536.0, true),
+            format_amount(5.5 * 1024.0 * 1024.0, true)
+        ),
+        (
+            "512 B".to_string(),
+            "1.5 KiB".to_string(),
+            "5.5 MiB".to_string()
+        )
+    );
+
+    let mut copy = ProgressBar::new("copy", 1000).width(10);
+    copy.start(Duration::from_secs(2));
+    let now = Duration::from_secs(7);
+    copy.set(550, now);
+    test!(
+        harness,
+        "the rate and the estimated time left",
+        (copy.rate(now), copy.eta(now)),
+        (Some(110.0), Some(Duration::from_secs(5)))
+    );
+    test!(
+        harness,
+        "rendering a bar",
+        copy.render(now),
+        "copy [█████▌    ]  55% 550/1000 110/s ETA 00:05"
+    );
+    copy.advance(4000, Duration::from_secs(3725));
+    test!(
+        harness,
+        "a finished bar shows the time it took",
+        copy.render(Duration::from_secs(5000)),
+        "copy [██████████] 100% 1000/1000 0.3/s in 1:02:03"
+    );
+
+    let mut spinner = Spinner::new("resolving");
+    let first = spinner.render();
+    for _ in 0..SPINNER.len() {
+        spinner.tick();
+    }
+    test!(
+        harness,
+        "the spinner goes around",
+        (first.clone(), spinner.render()),
+        ("⠋ resolving".to_string(), "⠋ resolving".to_string())
+    );
+
+    let mut redraw = Redraw::new();
+    let lines = vec!["one".to_string(), "two".to_string()];
+    let first = redraw.frame(&lines);
+    let second = redraw.frame(&lines[..1]);
+    test!(
+        harness,
+        "redrawing a block of lines in place",
+        (first, second),
+        (
+            "\r\x1b[2Kone\n\r\x1b[2Ktwo\n".to_string(),
+            "\x1b[2A\r\x1b[2Kone\n\r\x1b[2K\n".to_string()
+        )
+    );
+
+    let mut frames = 0;
+    let (progress, elapsed) = download(|_, _| frames += 1);
+    let sizes: Vec<u64> = (0..=FILES.len())
+        .map(|index| progress.bar(index).position())
+        .collect();
+    test!(
+        harness,
+        "the download completes every file",
+        (progress.is_finished(), sizes),
+        (true, vec![18 << 20, 6 << 20, 3 << 20, 9 << 20])
+    );
+    test!(
+        harness,
+        "the download takes a frame in every step",
+        elapsed,
+        Duration::from_millis(100 * frames)
+    );
+
+    note!(harness, "A simulated download, in {} steps:", frames);
+    for line in progress.render(elapsed) {
+        note!(harness, "  {}", line);
+    }
+    note!(harness);
+
+    // Only animate when someone is watching, faster than the simulation.
+    if harness.is_quiet() || !crate::ansi::enabled() {
+        return;
+    }
+    let mut stdout = std::io::stdout();
+    let mut redraw = Redraw::new();
+    let mut resolving = Spinner::new("resolving mirror.example.org");
+    for _ in 0..20 {
+        resolving.tick();
+        print!("{}", redraw.line(&resolving.render()));
+        stdout.flush().unwrap();
+        std::thread::sleep(Duration::from_millis(40));
+    }
+    println!("{}", redraw.line(&resolving.finish("done")));
+    download(|progress, now| {
+        print!("{}", redraw.frame(&progress.render(now)));
+        stdout.flush().unwrap();
+        std::thread::sleep(Duration::from_millis(15));
+    });
+}
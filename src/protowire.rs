@@ -0,0 +1,558 @@
+// protowire.rs
+// A reader of the wire format of Protocol Buffers, which needs no schema, as
+// everything it needs to walk a message is in the bytes themselves. A message
+// is a list of fields, each starting with a varint key, which holds the field
+// number, shifted left by three, and the wire type in the low three bits. The
+// wire type tells how the value is written: as a varint, as four or eight
+// little-endian bytes, or prefixed with its length, which is used for the
+// strings, the byte arrays, the nested messages, and the packed repeated
+// numbers alike, so which one it is can only be guessed. The deprecated
+// groups are delimited by a start and an end key. The signed integers of the
+// sint types are zigzag encoded, and the varints are those of the
+// serialization snippet. The dump tries to read every length-delimited value
+// as a nested message first, then as text, and falls back to the raw bytes,
+// just like protoc --decode_raw. The demonstration encodes a message, and
+// dumps it.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::serialize::{read_varint, unzigzag, write_varint, zigzag};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WireType {
+    Varint,
+    I64,
+    Len,
+    StartGroup,
+    EndGroup,
+    I32,
+}
+
+impl WireType {
+    pub fn code(&self) -> u8 {
+        match self {
+            WireType::Varint => 0,
+            WireType::I64 => 1,
+            WireType::Len => 2,
+            WireType::StartGroup => 3,
+            WireType::EndGroup => 4,
+            WireType::I32 => 5,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(WireType::Varint),
+            1 => Some(WireType::I64),
+            2 => Some(WireType::Len),
+            3 => Some(WireType::StartGroup),
+            4 => Some(WireType::EndGroup),
+            5 => Some(WireType::I32),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for WireType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            WireType::Varint => "varint",
+            WireType::I64 => "i64",
+            WireType::Len => "len",
+            WireType::StartGroup => "sgroup",
+            WireType::EndGroup => "egroup",
+            WireType::I32 => "i32",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnexpectedEnd,
+    VarintOverflow,
+    InvalidWireType(u8),
+    InvalidFieldNumber,
+    UnmatchedEndGroup,
+    UnclosedGroup,
+    TooDeep,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ErrorKind::VarintOverflow => write!(f, "varint does not fit in 64 bits"),
+            ErrorKind::InvalidWireType(code) => write!(f, "invalid wire type {}", code),
+            ErrorKind::InvalidFieldNumber => write!(f, "invalid field number"),
+            ErrorKind::UnmatchedEndGroup => write!(f, "end of a group which was not started"),
+            ErrorKind::UnclosedGroup => write!(f, "group without an end"),
+            ErrorKind::TooDeep => write!(f, "too deeply nested"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WireError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for WireError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at position {}", self.kind, self.position)
+    }
+}
+
+impl std::error::Error for WireError {}
+
+pub const MAX_FIELD_NUMBER: u32 = (1 << 29) - 1;
+pub const MAX_DEPTH: usize = 100;
+
+// The fixed-size values are kept as their bits, as only the schema knows
+// whether they are integers or floating-point numbers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value<'a> {
+    Varint(u64),
+    I64(u64),
+    Len(&'a [u8]),
+    Group(Vec<Field<'a>>),
+    I32(u32),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field<'a> {
+    pub number: u32,
+    pub value: Value<'a>,
+    pub position: usize,
+}
+
+impl Field<'_> {
+    pub fn wire_type(&self) -> WireType {
+        match self.value {
+            Value::Varint(_) => WireType::Varint,
+            Value::I64(_) => WireType::I64,
+            Value::Len(_) => WireType::Len,
+            Value::Group(_) => WireType::StartGroup,
+            Value::I32(_) => WireType::I32,
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, kind: ErrorKind, position: usize) -> WireError {
+        WireError { kind, position }
+    }
+
+    fn varint(&mut self) -> Result<u64, WireError> {
+        match read_varint(&self.input[self.position..]) {
+            Ok((value, length)) => {
+                self.position += length;
+                Ok(value)
+            }
+            Err(crate::serialize::ErrorKind::VarintOverflow) => {
+                Err(self.error(ErrorKind::VarintOverflow, self.position))
+            }
+            Err(_) => Err(self.error(ErrorKind::UnexpectedEnd, self.position)),
+        }
+    }
+
+    fn take(&mut self, count: usize, start: usize) -> Result<&'a [u8], WireError> {
+        if self.input.len() - self.position < count {
+            return Err(self.error(ErrorKind::UnexpectedEnd, start));
+        }
+        let taken = &self.input[self.position..self.position + count];
+        self.position += count;
+        Ok(taken)
+    }
+
+    // The fields up to the end of the input, or up to the end of the group
+    // with the number, which is consumed.
+    fn fields(
+        &mut self,
+        group: Option<(u32, usize)>,
+        depth: usize,
+    ) -> Result<Vec<Field<'a>>, WireError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error(ErrorKind::TooDeep, self.position));
+        }
+        let mut fields = Vec::new();
+        while self.position < self.input.len() {
+            let position = self.position;
+            let key = self.varint()?;
+            let number = key >> 3;
+            if number == 0 || number > MAX_FIELD_NUMBER as u64 {
+                return Err(self.error(ErrorKind::InvalidFieldNumber, position));
+            }
+            let number = number as u32;
+            let code = (key & 7) as u8;
+            let value = match WireType::from_code(code) {
+                None => return Err(self.error(ErrorKind::InvalidWireType(code), position)),
+                Some(WireType::Varint) => Value::Varint(self.varint()?),
+                Some(WireType::I64) => {
+                    let bytes = self.take(8, position)?;
+                    Value::I64(u64::from_le_bytes(bytes.try_into().unwrap()))
+                }
+                Some(WireType::I32) => {
+                    let bytes = self.take(4, position)?;
+                    Value::I32(u32::from_le_bytes(bytes.try_into().unwrap()))
+                }
+                Some(WireType::Len) => {
+                    let length = self.varint()?;
+                    let length = usize::try_from(length).unwrap_or(usize::MAX);
+                    Value::Len(self.take(length, position)?)
+                }
+                Some(WireType::StartGroup) => {
+                    Value::Group(self.fields(Some((number, position)), depth + 1)?)
+                }
+                Some(WireType::EndGroup) => match group {
+                    Some((started, _)) if started == number => return Ok(fields),
+                    _ => return Err(self.error(ErrorKind::UnmatchedEndGroup, position)),
+                },
+            };
+            fields.push(Field {
+                number,
+                value,
+                position,
+            });
+        }
+        match group {
+            Some((_, start)) => Err(self.error(ErrorKind::UnclosedGroup, start)),
+            None => Ok(fields),
+        }
+    }
+}
+
+// The positions of the fields are counted from the start of the input, and
+// those of the nested messages from their own start.
+pub fn parse(input: &[u8]) -> Result<Vec<Field<'_>>, WireError> {
+    Parser { input, position: 0 }.fields(None, 0)
+}
+
+pub fn unpack_varints(input: &[u8]) -> Result<Vec<u64>, WireError> {
+    let mut parser = Parser { input, position: 0 };
+    let mut values = Vec::new();
+    while parser.position < input.len() {
+        values.push(parser.varint()?);
+    }
+    Ok(values)
+}
+
+// An encoder of the fields, for building messages by hand.
+#[derive(Clone, Debug, Default)]
+pub struct Writer {
+    output: Vec<u8>,
+}
+
+impl Writer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn key(&mut self, number: u32, wire_type: WireType) -> &mut Self {
+        write_varint(
+            &mut self.output,
+            (number as u64) << 3 | wire_type.code() as u64,
+        );
+        self
+    }
+
+    pub fn varint(&mut self, number: u32, value: u64) -> &mut Self {
+        self.key(number, WireType::Varint);
+        write_varint(&mut self.output, value);
+        self
+    }
+
+    // The int32 and int64 types write the negative numbers in ten bytes.
+    pub fn int(&mut self, number: u32, value: i64) -> &mut Self {
+        self.varint(number, value as u64)
+    }
+
+    pub fn sint(&mut self, number: u32, value: i64) -> &mut Self {
+        self.varint(number, zigzag(value))
+    }
+
+    pub fn bool(&mut self, number: u32, value: bool) -> &mut Self {
+        self.varint(number, value as u64)
+    }
+
+    pub fn fixed64(&mut self, number: u32, value: u64) -> &mut Self {
+        self.key(number, WireType::I64);
+        self.output.extend(value.to_le_bytes());
+        self
+    }
+
+    pub fn double(&mut self, number: u32, value: f64) -> &mut Self {
+        self.fixed64(number, value.to_bits())
+    }
+
+    pub fn fixed32(&mut self, number: u32, value: u32) -> &mut Self {
+        self.key(number, WireType::I32);
+        self.output.extend(value.to_le_bytes());
+        self
+    }
+
+    pub fn float(&mut self, number: u32, value: f32) -> &mut Self {
+        self.fixed32(number, value.to_bits())
+    }
+
+    pub fn bytes(&mut self, number: u32, value: &[u8]) -> &mut Self {
+        self.key(number, WireType::Len);
+        write_varint(&mut self.output, value.len() as u64);
+        self.output.extend_from_slice(value);
+        self
+    }
+
+    pub fn string(&mut self, number: u32, value: &str) -> &mut Self {
+        self.bytes(number, value.as_bytes())
+    }
+
+    pub fn message(&mut self, number: u32, message: &Writer) -> &mut Self {
+        self.bytes(number, &message.output)
+    }
+
+    pub fn packed(&mut self, number: u32, values: &[u64]) -> &mut Self {
+        let mut packed = Vec::new();
+        for value in values {
+            write_varint(&mut packed, *value);
+        }
+        self.bytes(number, &packed)
+    }
+
+    pub fn group(&mut self, number: u32, group: &Writer) -> &mut Self {
+        self.key(number, WireType::StartGroup);
+        self.output.extend_from_slice(&group.output);
+        self.key(number, WireType::EndGroup)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.output
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.output
+    }
+}
+
+// Printable text, with the usual whitespace, is shown as a string.
+fn looks_like_text(bytes: &[u8]) -> Option<&str> {
+    let text = std::str::from_utf8(bytes).ok()?;
+    let printable = text
+        .chars()
+        .all(|c| !c.is_control() || c == '\n' || c == '\t' || c == '\r');
+    printable.then_some(text)
+}
+
+fn dump_fields(fields: &[Field], level: usize, out: &mut String) {
+    let indent = "  ".repeat(level);
+    for field in fields {
+        let prefix = format!("{}{} ({})", indent, field.number, field.wire_type());
+        match &field.value {
+            Value::Varint(value) => {
+                *out += &format!("{}: {}", prefix, value);
+                if *value > i64::MAX as u64 {
+                    *out += &format!(" (int64 {})", *value as i64);
+                } else if value & 1 == 1 {
+                    *out += &format!(" (sint {})", unzigzag(*value));
+                }
+                out.push('\n');
+            }
+            Value::I64(bits) => {
+                *out += &format!(
+                    "{}: 0x{:016x} (double {:?})\n",
+                    prefix,
+                    bits,
+                    f64::from_bits(*bits)
+                )
+            }
+            Value::I32(bits) => {
+                *out += &format!(
+                    "{}: 0x{:08x} (float {:?})\n",
+                    prefix,
+                    bits,
+                    f32::from_bits(*bits)
+                )
+            }
+            Value::Group(fields) => {
+                *out += &format!("{} {{\n", prefix);
+                dump_fields(fields, level + 1, out);
+                *out += &format!("{}}}\n", indent);
+            }
+            Value::Len(bytes) => match parse(bytes) {
+                Ok(fields) if !bytes.is_empty() => {
+                    *out += &format!("{} {{\n", prefix);
+                    dump_fields(&fields, level + 1, out);
+                    *out += &format!("{}}}\n", indent);
+                }
+                _ => match looks_like_text(bytes) {
+                    Some(text) => *out += &format!("{}: {:?}\n", prefix, text),
+                    None => {
+                        *out += &format!("{}: <{}>\n", prefix, crate::encoding::HEX.encode(bytes))
+                    }
+                },
+            },
+        }
+    }
+}
+
+// The fields, one per line, with the nested messages indented. The odd
+// varints are also shown as the sint values they may be, and the fixed-size
+// ones as floating-point numbers.
+pub fn dump(input: &[u8]) -> Result<String, WireError> {
+    let fields = parse(input)?;
+    let mut out = String::new();
+    dump_fields(&fields, 0, &mut out);
+    Ok(out)
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    let mut writer = Writer::new();
+    writer.varint(1, 150);
+    test!(
+        harness,
+        "the example of the documentation is encoded",
+        writer.finish(),
+        vec![0x08, 0x96, 0x01]
+    );
+    let mut writer = Writer::new();
+    writer.string(2, "testing").int(3, -1);
+    let bytes = writer.finish();
+    test!(
+        harness,
+        "strings are length-delimited, and negative int32 take ten bytes",
+        bytes.len(),
+        2 + 7 + 1 + 10
+    );
+    test!(
+        harness,
+        "the fields are walked without a schema",
+        parse(&bytes),
+        Ok(vec![
+            Field {
+                number: 2,
+                value: Value::Len(b"testing"),
+                position: 0
+            },
+            Field {
+                number: 3,
+                value: Value::Varint(u64::MAX),
+                position: 9
+            },
+        ])
+    );
+
+    let mut phone = Writer::new();
+    phone.string(1, "+36-1-234-5678").varint(2, 1);
+    let mut address = Writer::new();
+    address.string(1, "Budapest");
+    let mut person = Writer::new();
+    person
+        .string(1, "Ada Lovelace")
+        .varint(2, 1815)
+        .string(3, "ada@example.com")
+        .message(4, &phone)
+        .sint(5, -250)
+        .double(6, 4.5)
+        .float(7, 0.25)
+        .packed(8, &[1, 2, 300])
+        .bool(9, true)
+        .group(10, &address)
+        .bytes(11, &[0xde, 0xad, 0xbe, 0xef]);
+    let bytes = person.finish();
+    let dumped = dump(&bytes).unwrap();
+    test!(
+        harness,
+        "the dump guesses the nested messages, the text, and the bytes",
+        dumped.as_str(),
+        concat!(
+            "1 (len): \"Ada Lovelace\"\n",
+            "2 (varint): 1815 (sint -908)\n",
+            "3 (len): \"ada@example.com\"\n",
+            "4 (len) {\n",
+            "  1 (len): \"+36-1-234-5678\"\n",
+            "  2 (varint): 1 (sint -1)\n",
+            "}\n",
+            "5 (varint): 499 (sint -250)\n",
+            "6 (i64): 0x4012000000000000 (double 4.5)\n",
+            "7 (i32): 0x3e800000 (float 0.25)\n",
+            "8 (len): <0102ac02>\n",
+            "9 (varint): 1 (sint -1)\n",
+            "10 (sgroup) {\n",
+            "  1 (len): \"Budapest\"\n",
+            "}\n",
+            "11 (len): <deadbeef>\n",
+        )
+    );
+    let packed = parse(&bytes)
+        .unwrap()
+        .into_iter()
+        .find(|field| field.number == 8)
+        .map(|field| match field.value {
+            Value::Len(bytes) => unpack_varints(bytes),
+            _ => Ok(Vec::new()),
+        });
+    test!(
+        harness,
+        "packed repeated numbers are unpacked",
+        packed,
+        Some(Ok(vec![1, 2, 300]))
+    );
+
+    let error = |kind, position| Err(WireError { kind, position });
+    test!(
+        harness,
+        "broken messages are rejected where they go wrong",
+        [
+            parse(&[0x08]),
+            parse(&[0x0a, 0x05, b'a']),
+            parse(&[0x0e]),
+            parse(&[0x00, 0x01]),
+            parse(&[0x0c]),
+            parse(&[0x0b, 0x08, 0x01]),
+            parse(&[0x0b, 0x14]),
+            parse(&[0x08, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f]),
+        ]
+        .map(|result| result.map(|fields| fields.len())),
+        [
+            error(ErrorKind::UnexpectedEnd, 1),
+            error(ErrorKind::UnexpectedEnd, 0),
+            error(ErrorKind::InvalidWireType(6), 0),
+            error(ErrorKind::InvalidFieldNumber, 0),
+            error(ErrorKind::UnmatchedEndGroup, 0),
+            error(ErrorKind::UnclosedGroup, 0),
+            error(ErrorKind::UnmatchedEndGroup, 1),
+            error(ErrorKind::VarintOverflow, 1),
+        ]
+    );
+    let nested = [vec![0x0b; MAX_DEPTH + 1], vec![0x0c; MAX_DEPTH + 1]].concat();
+    test!(
+        harness,
+        "the depth of the groups is limited",
+        (
+            parse(&nested[1..nested.len() - 1]).is_ok(),
+            parse(&nested).map(|fields| fields.len())
+        ),
+        (true, error(ErrorKind::TooDeep, MAX_DEPTH + 1))
+    );
+
+    note!(
+        harness,
+        "The message, in {} bytes:\n{}\n{}",
+        bytes.len(),
+        crate::encoding::HEX.encode(&bytes),
+        dumped.trim_end()
+    );
+}
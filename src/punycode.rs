@@ -0,0 +1,381 @@
+// punycode.rs
+// Punycode, as specified in RFC 3492, which encodes Unicode strings with the
+// letters, digits, and hyphens allowed in host names, so that internationalized
+// domain names work with the existing DNS. The ASCII characters of the string
+// are copied first, followed by a hyphen, and the rest of the characters are
+// encoded as the deltas of their code points and positions, in increasing order
+// of the code points, written as variable-length integers in base 36, where the
+// thresholds of the digits adapt to the length of the string, like a mix of a
+// run-length and a Huffman code. Domain names are converted label by label,
+// where only the labels with characters outside ASCII are encoded, and are
+// marked with the xn-- prefix, which is a simplified form of the conversions of
+// IDNA, mapping the characters only to lowercase, instead of the full tables of
+// the standard. The URL snippet uses these to parse the hosts of URLs.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+
+const BASE: u32 = 36;
+const T_MIN: u32 = 1;
+const T_MAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+const DELIMITER: char = '-';
+
+pub const PREFIX: &str = "xn--";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    InvalidCharacter,
+    UnexpectedEnd,
+    InvalidCodePoint,
+    Overflow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PunycodeError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for PunycodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.kind {
+            ErrorKind::InvalidCharacter => "invalid character",
+            ErrorKind::UnexpectedEnd => "unexpected end of input",
+            ErrorKind::InvalidCodePoint => "invalid code point",
+            ErrorKind::Overflow => "overflow",
+        };
+        write!(f, "{} at position {}", message, self.position)
+    }
+}
+
+impl std::error::Error for PunycodeError {}
+
+// The bias of the thresholds after each encoded delta, which scales the delta
+// down, so that the next ones are expected to be of a similar size.
+fn adapt(mut delta: u32, points: u32, first: bool) -> u32 {
+    delta /= if first { DAMP } else { 2 };
+    delta += delta / points;
+    let mut k = 0;
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+    k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+}
+
+fn threshold(k: u32, bias: u32) -> u32 {
+    k.saturating_sub(bias).clamp(T_MIN, T_MAX)
+}
+
+fn digit_char(digit: u32) -> char {
+    if digit < 26 {
+        (b'a' + digit as u8) as char
+    } else {
+        (b'0' + (digit - 26) as u8) as char
+    }
+}
+
+// Digits are case-insensitive, as the case only annotates the decoded letters.
+fn digit_value(c: char) -> Option<u32> {
+    match c {
+        'a'..='z' => Some(c as u32 - 'a' as u32),
+        'A'..='Z' => Some(c as u32 - 'A' as u32),
+        '0'..='9' => Some(c as u32 - '0' as u32 + 26),
+        _ => None,
+    }
+}
+
+pub fn encode(input: &str) -> Result<String, PunycodeError> {
+    let code_points: Vec<u32> = input.chars().map(|c| c as u32).collect();
+    let overflow = |position| PunycodeError {
+        kind: ErrorKind::Overflow,
+        position,
+    };
+    let mut output: String = input.chars().filter(char::is_ascii).collect();
+    let basic = output.len() as u32;
+    if basic > 0 {
+        output.push(DELIMITER);
+    }
+    let (mut n, mut delta, mut bias) = (INITIAL_N, 0u32, INITIAL_BIAS);
+    let mut handled = basic;
+    while (handled as usize) < code_points.len() {
+        let m = *code_points.iter().filter(|c| **c >= n).min().unwrap();
+        delta = (m - n)
+            .checked_mul(handled + 1)
+            .and_then(|step| delta.checked_add(step))
+            .ok_or(overflow(output.len()))?;
+        n = m;
+        for c in &code_points {
+            if *c < n {
+                delta = delta.checked_add(1).ok_or(overflow(output.len()))?;
+            }
+            if *c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = threshold(k, bias);
+                    if q < t {
+                        break;
+                    }
+                    output.push(digit_char(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(digit_char(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta = delta.checked_add(1).ok_or(overflow(output.len()))?;
+        n += 1;
+    }
+    Ok(output)
+}
+
+pub fn decode(input: &str) -> Result<String, PunycodeError> {
+    let error = |kind, position| PunycodeError { kind, position };
+    let (basic, encoded, start) = match input.rfind(DELIMITER) {
+        Some(index) => (&input[..index], &input[index + 1..], index + 1),
+        None => ("", input, 0),
+    };
+    if let Some(index) = basic.find(|c: char| !c.is_ascii()) {
+        return Err(error(ErrorKind::InvalidCharacter, index));
+    }
+    let mut output: Vec<char> = basic.chars().collect();
+    let (mut n, mut i, mut bias) = (INITIAL_N, 0u32, INITIAL_BIAS);
+    let mut digits = encoded.char_indices().map(|(index, c)| (start + index, c));
+    while let Some((mut position, mut c)) = digits.next() {
+        let old_i = i;
+        let mut weight = 1u32;
+        let mut k = BASE;
+        loop {
+            let digit = digit_value(c).ok_or(error(ErrorKind::InvalidCharacter, position))?;
+            i = digit
+                .checked_mul(weight)
+                .and_then(|step| i.checked_add(step))
+                .ok_or(error(ErrorKind::Overflow, position))?;
+            let t = threshold(k, bias);
+            if digit < t {
+                break;
+            }
+            weight = weight
+                .checked_mul(BASE - t)
+                .ok_or(error(ErrorKind::Overflow, position))?;
+            k += BASE;
+            (position, c) = digits
+                .next()
+                .ok_or(error(ErrorKind::UnexpectedEnd, input.len()))?;
+        }
+        let length = output.len() as u32 + 1;
+        bias = adapt(i - old_i, length, old_i == 0);
+        n = n
+            .checked_add(i / length)
+            .ok_or(error(ErrorKind::Overflow, position))?;
+        i %= length;
+        let decoded = char::from_u32(n)
+            .filter(|c| !c.is_ascii())
+            .ok_or(error(ErrorKind::InvalidCodePoint, position))?;
+        output.insert(i as usize, decoded);
+        i += 1;
+    }
+    Ok(output.into_iter().collect())
+}
+
+// Converts the labels of the domain name to their ASCII form, where the errors
+// are at positions of the whole domain name.
+pub fn to_ascii(domain: &str) -> Result<String, PunycodeError> {
+    let mut labels = Vec::new();
+    let mut offset = 0;
+    for label in domain.split('.') {
+        if label.is_ascii() {
+            labels.push(label.to_ascii_lowercase());
+        } else {
+            let encoded = encode(&label.to_lowercase()).map_err(|error| PunycodeError {
+                position: offset,
+                ..error
+            })?;
+            labels.push(format!("{}{}", PREFIX, encoded));
+        }
+        offset += label.len() + 1;
+    }
+    Ok(labels.join("."))
+}
+
+// Converts the labels with the prefix back to Unicode, and the rest to
+// lowercase.
+pub fn to_unicode(domain: &str) -> Result<String, PunycodeError> {
+    let mut labels = Vec::new();
+    let mut offset = 0;
+    for label in domain.split('.') {
+        let label = label.to_ascii_lowercase();
+        if let Some(encoded) = label.strip_prefix(PREFIX) {
+            let decoded = decode(encoded).map_err(|error| PunycodeError {
+                position: offset + PREFIX.len() + error.position,
+                ..error
+            })?;
+            labels.push(decoded);
+        } else {
+            labels.push(label.clone());
+        }
+        offset += label.len() + 1;
+    }
+    Ok(labels.join("."))
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use crate::url::Url;
+
+// The sample strings of section 7.1 of the RFC.
+const SAMPLES: [(&str, &str); 11] = [
+    ("ليهمابتكلموشعربي؟", "egbpdaj6bu4bxfgehfvwxn"),
+    ("他们为什么不说中文", "ihqwcrb4cv8a8dqg056pqjye"),
+    ("Pročprostěnemluvíčesky", "Proprostnemluvesky-uyb24dma41a"),
+    ("למההםפשוטלאמדבריםעברית", "4dbcagdahymbxekheh6e0a7fei0b"),
+    (
+        "なぜみんな日本語を話してくれないのか",
+        "n8jok5ay5dzabd5bym9f0cm5685rrjetr6pdxa",
+    ),
+    (
+        "почемужеонинеговорятпорусски",
+        "b1abfaaepdrnnbgefbadotcwatmq2g4l",
+    ),
+    (
+        "PorquénopuedensimplementehablarenEspañol",
+        "PorqunopuedensimplementehablarenEspaol-fmd56a",
+    ),
+    (
+        "TạisaohọkhôngthểchỉnóitiếngViệt",
+        "TisaohkhngthchnitingVit-kjcr8268qyxafd2f1b9g",
+    ),
+    ("3年B組金八先生", "3B-ww4c5e180e575a65lsy2b"),
+    (
+        "安室奈美恵-with-SUPER-MONKEYS",
+        "-with-SUPER-MONKEYS-pc58ag80a8qai00g7n9n",
+    ),
+    ("-> $1.00 <-", "-> $1.00 <--"),
+];
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "encoding the sample strings of the RFC",
+        SAMPLES
+            .iter()
+            .all(|(unicode, ascii)| encode(unicode).as_deref() == Ok(*ascii)),
+        true
+    );
+    test!(
+        harness,
+        "decoding the sample strings of the RFC",
+        SAMPLES
+            .iter()
+            .all(|(unicode, ascii)| decode(ascii).as_deref() == Ok(*unicode)),
+        true
+    );
+    test!(
+        harness,
+        "case annotations are ignored when decoding",
+        decode("b1abfaaepdrnnbgefbaDotcwatmq2g4l"),
+        Ok("почемужеонинеговорятпорусски".to_string())
+    );
+    test!(
+        harness,
+        "strings without characters outside ASCII",
+        (encode(""), encode("abc"), decode("abc-")),
+        (
+            Ok(String::new()),
+            Ok("abc-".to_string()),
+            Ok("abc".to_string())
+        )
+    );
+    test!(
+        harness,
+        "invalid encodings",
+        [
+            decode("égal-abc"),
+            decode("abc-d!"),
+            decode("abc-9"),
+            decode("99999999999")
+        ],
+        [
+            Err(PunycodeError {
+                kind: ErrorKind::InvalidCharacter,
+                position: 0
+            }),
+            Err(PunycodeError {
+                kind: ErrorKind::InvalidCharacter,
+                position: 5
+            }),
+            Err(PunycodeError {
+                kind: ErrorKind::UnexpectedEnd,
+                position: 5
+            }),
+            Err(PunycodeError {
+                kind: ErrorKind::Overflow,
+                position: 7
+            })
+        ]
+    );
+
+    let domains = [
+        ("bücher.example", "xn--bcher-kva.example"),
+        ("München.DE", "xn--mnchen-3ya.de"),
+        ("例え.テスト", "xn--r8jz45g.xn--zckzah"),
+        ("ελληνικά.gr", "xn--hxargifdar.gr"),
+    ];
+    test!(
+        harness,
+        "converting domain names to ASCII",
+        domains.map(|(unicode, _)| to_ascii(unicode)),
+        domains.map(|(_, ascii)| Ok(ascii.to_string()))
+    );
+    test!(
+        harness,
+        "converting domain names to Unicode",
+        domains.map(|(_, ascii)| to_unicode(&ascii.to_uppercase())),
+        domains.map(|(unicode, _)| Ok(unicode.to_lowercase()))
+    );
+    test!(
+        harness,
+        "errors are at positions of the domain name",
+        to_unicode("example.xn--a!"),
+        Err(PunycodeError {
+            kind: ErrorKind::InvalidCharacter,
+            position: 13
+        })
+    );
+
+    let url = Url::parse("https://bücher.example/docs?q=1").unwrap();
+    test!(
+        harness,
+        "the hosts of URLs are converted to ASCII",
+        (url.host.as_deref(), url.unicode_host()),
+        (
+            Some("xn--bcher-kva.example"),
+            Some("bücher.example".to_string())
+        )
+    );
+
+    for (unicode, ascii) in &SAMPLES[..5] {
+        note!(harness, "{}  =>  {}", unicode, ascii);
+    }
+    for (unicode, _) in domains {
+        note!(
+            harness,
+            "{}  =>  {}",
+            unicode,
+            to_ascii(unicode).unwrap_or_else(|error| error.to_string())
+        );
+    }
+}
@@ -0,0 +1,404 @@
+// rational.rs
+// Exact rational numbers, stored as a fraction of two 64-bit integers, which is
+// always kept in lowest terms, with a positive denominator, so every number has
+// exactly one representation, and equality is simple. The arithmetic is exact,
+// and calculated on 128-bit integers, so intermediate results do not overflow.
+// Floating-point numbers are converted to the closest fraction with a bounded
+// denominator, by expanding them into a continued fraction, whose convergents
+// are the best approximations, like 22/7 and 355/113 are for pi.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+pub fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Ratio {
+    numerator: i64,
+    denominator: i64,
+}
+
+impl Ratio {
+    pub const ZERO: Ratio = Ratio {
+        numerator: 0,
+        denominator: 1,
+    };
+    pub const ONE: Ratio = Ratio {
+        numerator: 1,
+        denominator: 1,
+    };
+
+    pub fn new(numerator: i64, denominator: i64) -> Self {
+        Self::reduce(numerator as i128, denominator as i128)
+    }
+
+    pub fn integer(value: i64) -> Self {
+        Self::new(value, 1)
+    }
+
+    // The results are calculated on 128-bit integers, and only have to fit
+    // into 64 bits after they are reduced.
+    fn reduce(numerator: i128, denominator: i128) -> Self {
+        assert!(denominator != 0, "the denominator must not be zero");
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        let convert = |value: i128| i64::try_from(value).expect("the ratio overflowed");
+        Self {
+            numerator: convert(numerator / divisor),
+            denominator: convert(denominator / divisor),
+        }
+    }
+
+    pub fn numerator(&self) -> i64 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i64 {
+        self.denominator
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    pub fn recip(&self) -> Self {
+        Self::new(self.denominator, self.numerator)
+    }
+
+    pub fn abs(&self) -> Self {
+        Self {
+            numerator: self.numerator.abs(),
+            denominator: self.denominator,
+        }
+    }
+
+    // Rounds towards negative infinity.
+    pub fn floor(&self) -> i64 {
+        self.numerator.div_euclid(self.denominator)
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    // The exact decimal expansion, truncated after the given number of
+    // digits, which shows more digits than a float could hold.
+    pub fn to_decimal(&self, digits: usize) -> String {
+        let mut result = String::new();
+        if self.numerator < 0 {
+            result.push('-');
+        }
+        let numerator = (self.numerator as i128).abs();
+        let denominator = self.denominator as i128;
+        result.push_str(&(numerator / denominator).to_string());
+        if digits > 0 {
+            result.push('.');
+            let mut remainder = numerator % denominator;
+            for _ in 0..digits {
+                remainder *= 10;
+                result.push(char::from(b'0' + (remainder / denominator) as u8));
+                remainder %= denominator;
+            }
+        }
+        result
+    }
+
+    // The terms of the continued fraction a0 + 1 / (a1 + 1 / (a2 + ...)).
+    pub fn continued_fraction(value: f64, terms: usize) -> Vec<i64> {
+        let mut result = Vec::new();
+        let mut rest = value;
+        for _ in 0..terms {
+            let term = rest.floor();
+            result.push(term as i64);
+            let fraction = rest - term;
+            if fraction.abs() < 1e-12 {
+                break;
+            }
+            rest = 1.0 / fraction;
+        }
+        result
+    }
+
+    // Each convergent is h(n) / k(n), where h(n) = a(n) h(n - 1) + h(n - 2),
+    // and the same recurrence holds for k(n).
+    pub fn convergents(terms: &[i64]) -> Vec<Ratio> {
+        let (mut h, mut previous_h) = (1i128, 0i128);
+        let (mut k, mut previous_k) = (0i128, 1i128);
+        let mut result = Vec::new();
+        for term in terms {
+            (h, previous_h) = (*term as i128 * h + previous_h, h);
+            (k, previous_k) = (*term as i128 * k + previous_k, k);
+            if i64::try_from(h).is_err() || i64::try_from(k).is_err() {
+                break;
+            }
+            result.push(Self::reduce(h, k));
+        }
+        result
+    }
+
+    // The last convergent, whose denominator does not exceed the limit, or
+    // which is within the tolerance.
+    pub fn from_f64(value: f64, max_denominator: i64, tolerance: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+        let mut best = None;
+        for convergent in Self::convergents(&Self::continued_fraction(value, 64)) {
+            if convergent.denominator > max_denominator {
+                break;
+            }
+            best = Some(convergent);
+            if (convergent.to_f64() - value).abs() <= tolerance {
+                break;
+            }
+        }
+        best
+    }
+}
+
+impl Default for Ratio {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl From<i64> for Ratio {
+    fn from(value: i64) -> Self {
+        Self::integer(value)
+    }
+}
+
+impl fmt::Display for Ratio {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+impl Add for Ratio {
+    type Output = Ratio;
+
+    fn add(self, other: Ratio) -> Ratio {
+        let (a, b) = (self.numerator as i128, self.denominator as i128);
+        let (c, d) = (other.numerator as i128, other.denominator as i128);
+        Ratio::reduce(a * d + c * b, b * d)
+    }
+}
+
+impl Sub for Ratio {
+    type Output = Ratio;
+
+    fn sub(self, other: Ratio) -> Ratio {
+        self + -other
+    }
+}
+
+impl Mul for Ratio {
+    type Output = Ratio;
+
+    fn mul(self, other: Ratio) -> Ratio {
+        Ratio::reduce(
+            self.numerator as i128 * other.numerator as i128,
+            self.denominator as i128 * other.denominator as i128,
+        )
+    }
+}
+
+impl Div for Ratio {
+    type Output = Ratio;
+
+    fn div(self, other: Ratio) -> Ratio {
+        assert!(other.numerator != 0, "division by zero");
+        Ratio::reduce(
+            self.numerator as i128 * other.denominator as i128,
+            self.denominator as i128 * other.numerator as i128,
+        )
+    }
+}
+
+impl Neg for Ratio {
+    type Output = Ratio;
+
+    fn neg(self) -> Ratio {
+        Ratio {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+}
+
+// As the denominators are positive, a/b < c/d is the same as ad < cb.
+impl Ord for Ratio {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let left = self.numerator as i128 * other.denominator as i128;
+        let right = other.numerator as i128 * self.denominator as i128;
+        left.cmp(&right)
+    }
+}
+
+impl PartialOrd for Ratio {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use std::f64::consts::PI;
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "fractions are reduced to lowest terms",
+        Ratio::new(6, -8),
+        Ratio::new(-3, 4)
+    );
+    test!(
+        harness,
+        "the sign is kept in the numerator",
+        (
+            Ratio::new(3, -4).numerator(),
+            Ratio::new(3, -4).denominator()
+        ),
+        (-3, 4)
+    );
+    test!(
+        harness,
+        "1/2 + 1/3 = 5/6",
+        Ratio::new(1, 2) + Ratio::new(1, 3),
+        Ratio::new(5, 6)
+    );
+    test!(
+        harness,
+        "1/2 - 3/4 = -1/4",
+        Ratio::new(1, 2) - Ratio::new(3, 4),
+        Ratio::new(-1, 4)
+    );
+    test!(
+        harness,
+        "2/3 * 9/4 = 3/2",
+        Ratio::new(2, 3) * Ratio::new(9, 4),
+        Ratio::new(3, 2)
+    );
+    test!(
+        harness,
+        "(1/2) / (-1/4) = -2",
+        (Ratio::new(1, 2) / Ratio::new(-1, 4)).to_string(),
+        "-2"
+    );
+    test!(
+        harness,
+        "fractions compare by value",
+        (
+            Ratio::new(2, 3) > Ratio::new(3, 5),
+            Ratio::new(-1, 2) < Ratio::new(-1, 3)
+        ),
+        (true, true)
+    );
+    test!(
+        harness,
+        "the floor rounds towards negative infinity",
+        (Ratio::new(7, 2).floor(), Ratio::new(-7, 2).floor()),
+        (3, -4)
+    );
+    test!(
+        harness,
+        "large intermediate products do not overflow",
+        Ratio::new(i64::MAX, 3) * Ratio::new(3, i64::MAX),
+        Ratio::ONE
+    );
+    let harmonic = (1..=20).fold(Ratio::ZERO, |sum, n| sum + Ratio::new(1, n));
+    test!(
+        harness,
+        "the 20th harmonic number is exact",
+        harmonic.to_string(),
+        "55835135/15519504"
+    );
+    test!(
+        harness,
+        "1/7 has a repeating decimal expansion",
+        Ratio::new(1, 7).to_decimal(12),
+        "0.142857142857"
+    );
+
+    test!(
+        harness,
+        "the continued fraction of pi",
+        Ratio::continued_fraction(PI, 5),
+        vec![3, 7, 15, 1, 292]
+    );
+    let convergents = Ratio::convergents(&Ratio::continued_fraction(PI, 6));
+    test!(
+        harness,
+        "the convergents of pi",
+        convergents
+            .iter()
+            .map(|ratio| ratio.to_string())
+            .collect::<Vec<_>>(),
+        vec![
+            "3",
+            "22/7",
+            "333/106",
+            "355/113",
+            "103993/33102",
+            "104348/33215"
+        ]
+    );
+    test!(
+        harness,
+        "the best approximation of pi with a denominator up to 1000",
+        Ratio::from_f64(PI, 1000, 0.0),
+        Some(Ratio::new(355, 113))
+    );
+    test!(
+        harness,
+        "the first approximation of pi within 0.01",
+        Ratio::from_f64(PI, i64::MAX, 0.01),
+        Some(Ratio::new(22, 7))
+    );
+    test!(
+        harness,
+        "a float with an exact binary fraction converts exactly",
+        Ratio::from_f64(-2.375, 1000, 0.0),
+        Some(Ratio::new(-19, 8))
+    );
+    test!(
+        harness,
+        "infinity can not be converted",
+        Ratio::from_f64(f64::INFINITY, 1000, 0.0),
+        None
+    );
+
+    note!(
+        harness,
+        "{:<14} {:<22} {:>10}",
+        "Convergent",
+        "Decimal",
+        "Error"
+    );
+    for convergent in Ratio::convergents(&Ratio::continued_fraction(PI, 8)) {
+        note!(
+            harness,
+            "{:<14} {:<22} {:>10.3e}",
+            convergent.to_string(),
+            convergent.to_decimal(20),
+            (convergent.to_f64() - PI).abs()
+        );
+    }
+    note!(harness, "{:<14} {:<22}\n", "pi", format!("{:.20}", PI));
+}
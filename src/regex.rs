@@ -0,0 +1,737 @@
+// regex.rs
+// A regular expression engine, which compiles the pattern into a
+// nondeterministic finite automaton, and simulates it the way Ken Thompson did:
+// instead of trying the alternatives one after the other, and backtracking when
+// one fails, every state the automaton could be in is tracked at the same time,
+// as a set, which is advanced over the text one character at a time. As the set
+// can not grow larger than the number of states, matching takes O(nm) time for
+// a text of n characters, and a pattern of m states, while backtracking can
+// take exponential time on patterns like a?a?a?aaa. A backtracking matcher is
+// included too, for comparison. The dialect has literals, the dot, the *, +,
+// and ? repetitions, alternation, groups, character classes, and the \d, \w,
+// and \s shorthands.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::cell::Cell;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnbalancedParenthesis,
+    UnterminatedClass,
+    InvalidRange(char, char),
+    NothingToRepeat,
+    TrailingBackslash,
+    TooDeep,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnbalancedParenthesis => write!(f, "unbalanced parenthesis"),
+            ErrorKind::UnterminatedClass => write!(f, "the character class is not closed"),
+            ErrorKind::InvalidRange(from, to) => write!(f, "invalid range {}-{}", from, to),
+            ErrorKind::NothingToRepeat => write!(f, "nothing to repeat"),
+            ErrorKind::TrailingBackslash => write!(f, "trailing backslash"),
+            ErrorKind::TooDeep => write!(f, "too deeply nested"),
+        }
+    }
+}
+
+// The position is counted in characters of the pattern.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at position {}", self.kind, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Class {
+    ranges: Vec<(char, char)>,
+    negated: bool,
+}
+
+impl Class {
+    fn contains(&self, c: char) -> bool {
+        let found = self
+            .ranges
+            .iter()
+            .any(|(from, to)| (*from..=*to).contains(&c));
+        found != self.negated
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Node {
+    Empty,
+    Char(char),
+    Any,
+    Class(Class),
+    Concat(Vec<Node>),
+    Alternate(Vec<Node>),
+    Star(Box<Node>),
+    Plus(Box<Node>),
+    Question(Box<Node>),
+}
+
+fn shorthand(c: char) -> Option<Class> {
+    let ranges = match c.to_ascii_lowercase() {
+        'd' => vec![('0', '9')],
+        'w' => vec![('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')],
+        's' => vec![(' ', ' '), ('\t', '\r')],
+        _ => return None,
+    };
+    Some(Class {
+        ranges,
+        negated: c.is_ascii_uppercase(),
+    })
+}
+
+// The groups and the repetitions nest, and each level is a level of
+// recursion while parsing and compiling, so their depth is limited.
+pub const MAX_DEPTH: usize = 128;
+
+// alternation := concatenation ('|' concatenation)*
+// concatenation := repetition*
+// repetition := atom ('*' | '+' | '?')*
+// atom := character | '.' | class | '(' alternation ')'
+struct Parser {
+    pattern: Vec<char>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn error(&self, kind: ErrorKind) -> ParseError {
+        ParseError {
+            kind,
+            position: self.position,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.pattern.get(self.position).copied()
+    }
+
+    fn parse_alternation(&mut self) -> Result<Node, ParseError> {
+        let mut branches = vec![self.parse_concatenation()?];
+        while self.peek() == Some('|') {
+            self.position += 1;
+            branches.push(self.parse_concatenation()?);
+        }
+        Ok(match branches.len() {
+            1 => branches.pop().unwrap(),
+            _ => Node::Alternate(branches),
+        })
+    }
+
+    fn parse_concatenation(&mut self) -> Result<Node, ParseError> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            nodes.push(self.parse_repetition()?);
+        }
+        Ok(match nodes.len() {
+            0 => Node::Empty,
+            1 => nodes.pop().unwrap(),
+            _ => Node::Concat(nodes),
+        })
+    }
+
+    fn parse_repetition(&mut self) -> Result<Node, ParseError> {
+        let mut node = self.parse_atom()?;
+        let mut repeated = 0;
+        while let Some(c) = self.peek() {
+            if "*+?".contains(c) {
+                repeated += 1;
+                if self.depth + repeated > MAX_DEPTH {
+                    return Err(self.error(ErrorKind::TooDeep));
+                }
+            }
+            node = match c {
+                '*' => Node::Star(Box::new(node)),
+                '+' => Node::Plus(Box::new(node)),
+                '?' => Node::Question(Box::new(node)),
+                _ => break,
+            };
+            self.position += 1;
+        }
+        Ok(node)
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, ParseError> {
+        let c = self.peek().unwrap();
+        match c {
+            '*' | '+' | '?' => return Err(self.error(ErrorKind::NothingToRepeat)),
+            '(' => {
+                let open = self.position;
+                self.depth += 1;
+                if self.depth > MAX_DEPTH {
+                    return Err(self.error(ErrorKind::TooDeep));
+                }
+                self.position += 1;
+                let node = self.parse_alternation()?;
+                self.depth -= 1;
+                if self.peek() != Some(')') {
+                    self.position = open;
+                    return Err(self.error(ErrorKind::UnbalancedParenthesis));
+                }
+                self.position += 1;
+                return Ok(node);
+            }
+            '[' => return self.parse_class(),
+            _ => {}
+        }
+        self.position += 1;
+        Ok(match c {
+            '.' => Node::Any,
+            '\\' => {
+                let escaped = self.parse_escape()?;
+                match shorthand(escaped) {
+                    Some(class) => Node::Class(class),
+                    None => Node::Char(escaped),
+                }
+            }
+            c => Node::Char(c),
+        })
+    }
+
+    // Called after the backslash, which escapes any character.
+    fn parse_escape(&mut self) -> Result<char, ParseError> {
+        let escaped = self
+            .peek()
+            .ok_or_else(|| self.error(ErrorKind::TrailingBackslash))?;
+        self.position += 1;
+        Ok(match escaped {
+            'n' => '\n',
+            't' => '\t',
+            c => c,
+        })
+    }
+
+    // A closing bracket right after the opening one is a literal, and so is
+    // a dash at either end of the class. Only the positive shorthands can be
+    // used in a class, \D, \W, and \S are literal characters there.
+    fn parse_class(&mut self) -> Result<Node, ParseError> {
+        let open = self.position;
+        self.position += 1;
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.position += 1;
+        }
+        let mut ranges = Vec::new();
+        let mut first = true;
+        loop {
+            let c = match self.peek() {
+                Some(c) => c,
+                None => {
+                    self.position = open;
+                    return Err(self.error(ErrorKind::UnterminatedClass));
+                }
+            };
+            if c == ']' && !first {
+                self.position += 1;
+                break;
+            }
+            first = false;
+            self.position += 1;
+            let from = if c == '\\' {
+                let escaped = self.parse_escape()?;
+                if let Some(class) = shorthand(escaped).filter(|class| !class.negated) {
+                    ranges.extend(class.ranges);
+                    continue;
+                }
+                escaped
+            } else {
+                c
+            };
+            let is_range = self.peek() == Some('-')
+                && self
+                    .pattern
+                    .get(self.position + 1)
+                    .is_some_and(|c| *c != ']');
+            if !is_range {
+                ranges.push((from, from));
+                continue;
+            }
+            self.position += 1;
+            let mut to = self.peek().unwrap();
+            self.position += 1;
+            if to == '\\' {
+                to = self.parse_escape()?;
+            }
+            if from > to {
+                return Err(self.error(ErrorKind::InvalidRange(from, to)));
+            }
+            ranges.push((from, to));
+        }
+        Ok(Node::Class(Class { ranges, negated }))
+    }
+}
+
+#[derive(Clone, Debug)]
+enum State {
+    Char(char, usize),
+    Any(usize),
+    Class(Class, usize),
+    Split(usize, usize),
+    Match,
+}
+
+pub struct Regex {
+    pattern: String,
+    tree: Node,
+    states: Vec<State>,
+    start: usize,
+}
+
+impl Regex {
+    pub fn new(pattern: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            pattern: pattern.chars().collect(),
+            position: 0,
+            depth: 0,
+        };
+        let tree = parser.parse_alternation()?;
+        if parser.position < parser.pattern.len() {
+            return Err(parser.error(ErrorKind::UnbalancedParenthesis));
+        }
+        let mut states = vec![State::Match];
+        let start = Self::compile(&tree, 0, &mut states);
+        Ok(Self {
+            pattern: pattern.to_string(),
+            tree,
+            states,
+            start,
+        })
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.states.len()
+    }
+
+    // Each node is compiled in front of its continuation, and returns its
+    // entry state. Loops are closed by patching the split state afterwards.
+    fn compile(node: &Node, next: usize, states: &mut Vec<State>) -> usize {
+        let push = |states: &mut Vec<State>, state| {
+            states.push(state);
+            states.len() - 1
+        };
+        match node {
+            Node::Empty => next,
+            Node::Char(c) => push(states, State::Char(*c, next)),
+            Node::Any => push(states, State::Any(next)),
+            Node::Class(class) => push(states, State::Class(class.clone(), next)),
+            Node::Concat(nodes) => nodes
+                .iter()
+                .rev()
+                .fold(next, |next, node| Self::compile(node, next, states)),
+            Node::Alternate(branches) => {
+                let mut entry = Self::compile(branches.last().unwrap(), next, states);
+                for branch in branches.iter().rev().skip(1) {
+                    let start = Self::compile(branch, next, states);
+                    entry = push(states, State::Split(start, entry));
+                }
+                entry
+            }
+            Node::Star(inner) => {
+                let split = push(states, State::Split(0, next));
+                let body = Self::compile(inner, split, states);
+                states[split] = State::Split(body, next);
+                split
+            }
+            Node::Plus(inner) => {
+                let split = push(states, State::Split(0, next));
+                let body = Self::compile(inner, split, states);
+                states[split] = State::Split(body, next);
+                body
+            }
+            Node::Question(inner) => {
+                let body = Self::compile(inner, next, states);
+                push(states, State::Split(body, next))
+            }
+        }
+    }
+
+    // Follows the split states, and adds every state reachable without
+    // consuming a character. A state, which is already in the list, is not
+    // added again, so the earlier thread, which has the priority, keeps it.
+    // The splits can chain as long as the pattern, like in a?a?a?, so they
+    // are followed with a stack instead of recursion, the first branch on top.
+    fn add_thread(
+        &self,
+        list: &mut Vec<(usize, usize)>,
+        marks: &mut [usize],
+        generation: usize,
+        state: usize,
+        start: usize,
+        steps: &mut usize,
+    ) {
+        let mut pending = vec![state];
+        while let Some(state) = pending.pop() {
+            if marks[state] == generation {
+                continue;
+            }
+            marks[state] = generation;
+            *steps += 1;
+            if let State::Split(a, b) = self.states[state] {
+                pending.push(b);
+                pending.push(a);
+            } else {
+                list.push((state, start));
+            }
+        }
+    }
+
+    fn accepts(&self, state: usize, c: char) -> Option<usize> {
+        match &self.states[state] {
+            State::Char(expected, next) if *expected == c => Some(*next),
+            State::Any(next) => Some(*next),
+            State::Class(class, next) if class.contains(c) => Some(*next),
+            _ => None,
+        }
+    }
+
+    // The simulation, which either runs from the beginning of the text, or
+    // starts a new thread at every position, until a match is found. The
+    // leftmost match wins, and of those, the longest one.
+    fn simulate(&self, text: &str, anchored: bool) -> (Option<(usize, usize)>, usize) {
+        let mut marks = vec![usize::MAX; self.states.len()];
+        let mut generation = 0;
+        let mut steps = 0;
+        let mut current = Vec::new();
+        let mut best: Option<(usize, usize)> = None;
+        let positions = text
+            .char_indices()
+            .map(|(index, c)| (index, Some(c)))
+            .chain(std::iter::once((text.len(), None)));
+
+        for (position, c) in positions {
+            if best.is_none() && (!anchored || position == 0) {
+                self.add_thread(
+                    &mut current,
+                    &mut marks,
+                    generation,
+                    self.start,
+                    position,
+                    &mut steps,
+                );
+            }
+            for (state, start) in &current {
+                if matches!(self.states[*state], State::Match)
+                    && (!anchored || c.is_none())
+                    && best.is_none_or(|(best_start, _)| *start <= best_start)
+                {
+                    best = Some((*start, position));
+                }
+            }
+            if let Some((best_start, _)) = best {
+                current.retain(|(_, start)| *start <= best_start);
+            }
+            let Some(c) = c else {
+                break;
+            };
+
+            generation += 1;
+            let mut next = Vec::new();
+            for (state, start) in &current {
+                if let Some(target) = self.accepts(*state, c) {
+                    self.add_thread(
+                        &mut next, &mut marks, generation, target, *start, &mut steps,
+                    );
+                }
+            }
+            current = next;
+            if current.is_empty() && (anchored || best.is_some()) {
+                break;
+            }
+        }
+        (best, steps)
+    }
+
+    // Whether the whole text matches.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.simulate(text, true).0.is_some()
+    }
+
+    // The byte range of the leftmost-longest match.
+    pub fn find(&self, text: &str) -> Option<(usize, usize)> {
+        self.simulate(text, false).0
+    }
+
+    pub fn find_all<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        let mut result = Vec::new();
+        let mut offset = 0;
+        while let Some((start, end)) = self.find(&text[offset..]) {
+            result.push(&text[offset + start..offset + end]);
+            // After an empty match, the next search starts a character later.
+            offset += match text[offset + end..].chars().next() {
+                Some(c) if start == end => end + c.len_utf8(),
+                None if start == end => break,
+                _ => end,
+            };
+        }
+        result
+    }
+
+    // The number of states visited while matching the whole text.
+    pub fn match_steps(&self, text: &str) -> (bool, usize) {
+        let (found, steps) = self.simulate(text, true);
+        (found.is_some(), steps)
+    }
+
+    // The whole text is matched by trying the alternatives in order, with a
+    // continuation, which matches the rest of the pattern, and returns false
+    // to make the matcher try the next alternative.
+    pub fn backtracking_match(&self, text: &str) -> (bool, usize) {
+        let chars: Vec<char> = text.chars().collect();
+        let steps = Cell::new(0);
+        let found = backtrack(&self.tree, &chars, 0, &steps, &|end| end == chars.len());
+        (found, steps.get())
+    }
+}
+
+fn backtrack(
+    node: &Node,
+    text: &[char],
+    position: usize,
+    steps: &Cell<usize>,
+    rest: &dyn Fn(usize) -> bool,
+) -> bool {
+    steps.set(steps.get() + 1);
+    let single = |accepts: &dyn Fn(char) -> bool| {
+        text.get(position).is_some_and(|c| accepts(*c)) && rest(position + 1)
+    };
+    match node {
+        Node::Empty => rest(position),
+        Node::Char(expected) => single(&|c| c == *expected),
+        Node::Any => single(&|_| true),
+        Node::Class(class) => single(&|c| class.contains(c)),
+        Node::Concat(nodes) => backtrack_sequence(nodes, text, position, steps, rest),
+        Node::Alternate(branches) => branches
+            .iter()
+            .any(|branch| backtrack(branch, text, position, steps, rest)),
+        // An iteration has to consume something, otherwise nested stars
+        // would loop forever.
+        Node::Star(inner) => {
+            backtrack(inner, text, position, steps, &|end| {
+                end > position && backtrack(node, text, end, steps, rest)
+            }) || rest(position)
+        }
+        Node::Plus(inner) => backtrack(inner, text, position, steps, &|end| {
+            let star = Node::Star(inner.clone());
+            backtrack(&star, text, end, steps, rest)
+        }),
+        Node::Question(inner) => backtrack(inner, text, position, steps, rest) || rest(position),
+    }
+}
+
+fn backtrack_sequence(
+    nodes: &[Node],
+    text: &[char],
+    position: usize,
+    steps: &Cell<usize>,
+    rest: &dyn Fn(usize) -> bool,
+) -> bool {
+    match nodes.split_first() {
+        None => rest(position),
+        Some((first, others)) => backtrack(first, text, position, steps, &|end| {
+            backtrack_sequence(others, text, end, steps, rest)
+        }),
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use std::time::Instant;
+
+pub fn demo(harness: &mut Harness) {
+    #[rustfmt::skip]
+    let suite: [(&str, &str, bool); 24] = [
+        ("abc", "abc", true),
+        ("abc", "abd", false),
+        ("a.c", "a-c", true),
+        ("ab*c", "ac", true),
+        ("ab*c", "abbbc", true),
+        ("ab+c", "ac", false),
+        ("ab+c", "abbc", true),
+        ("colou?r", "color", true),
+        ("colou?r", "colour", true),
+        ("cat|dog", "dog", true),
+        ("cat|dog", "cow", false),
+        ("(ab)+", "ababab", true),
+        ("(ab)+", "ababa", false),
+        ("(a|b)*abb", "babaabb", true),
+        ("[a-c]+", "abcabc", true),
+        ("[^0-9]+", "abc", true),
+        ("[^0-9]+", "ab1", false),
+        ("\\d+(\\.\\d+)?", "3.14", true),
+        ("\\w+@\\w+\\.com", "user@example.com", true),
+        ("\\s*x\\s*", " \t x ", true),
+        ("[]a]+", "]a]", true),
+        ("[a-]+", "a-a", true),
+        ("(a*)*b", "aaab", true),
+        ("", "", true),
+    ];
+    let mut failures = 0;
+    for (pattern, text, expected) in suite {
+        let regex = Regex::new(pattern).unwrap();
+        let (backtracked, _) = regex.backtracking_match(text);
+        if regex.is_match(text) != expected || backtracked != expected {
+            failures += 1;
+            note!(harness, "{:?} on {:?} is not {}", pattern, text, expected);
+        }
+    }
+    test!(
+        harness,
+        "both matchers agree with the suite of 24 cases",
+        failures,
+        0
+    );
+
+    let number = Regex::new("-?\\d+(\\.\\d+)?").unwrap();
+    test!(
+        harness,
+        "the leftmost match is found",
+        number.find("pi is about 3.14159, e is 2.71828"),
+        Some((12, 19))
+    );
+    test!(
+        harness,
+        "the leftmost match wins over a longer one",
+        Regex::new("abcd|b").unwrap().find("xabcd"),
+        Some((1, 5))
+    );
+    test!(
+        harness,
+        "every match is found",
+        number.find_all("x = -12, y = 3.5, z = 0"),
+        vec!["-12", "3.5", "0"]
+    );
+    test!(
+        harness,
+        "matching works on characters, not bytes",
+        Regex::new("ár.íz").unwrap().find("az árvíz"),
+        Some((3, 10))
+    );
+    test!(
+        harness,
+        "empty matches advance the search",
+        Regex::new("a*").unwrap().find_all("baab"),
+        vec!["", "aa", "", ""]
+    );
+
+    let broken: [(&str, &str); 6] = [
+        ("(ab", "unbalanced parenthesis at position 0"),
+        ("ab)", "unbalanced parenthesis at position 2"),
+        ("[abc", "the character class is not closed at position 0"),
+        ("[z-a]", "invalid range z-a at position 4"),
+        ("*a", "nothing to repeat at position 0"),
+        ("ab\\", "trailing backslash at position 3"),
+    ];
+    for (pattern, expected) in broken {
+        test!(
+            harness,
+            &format!("the error in {:?}", pattern),
+            Regex::new(pattern).err().map(|error| error.to_string()),
+            Some(expected.to_string())
+        );
+    }
+
+    test!(
+        harness,
+        "deep nesting is an error, instead of a stack overflow",
+        [
+            "(".repeat(30000),
+            format!("{}a{}", "(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH)),
+            format!("a{}", "*".repeat(MAX_DEPTH + 1)),
+        ]
+        .map(|pattern| Regex::new(&pattern).err()),
+        [
+            Some(ParseError {
+                kind: ErrorKind::TooDeep,
+                position: MAX_DEPTH
+            }),
+            None,
+            Some(ParseError {
+                kind: ErrorKind::TooDeep,
+                position: MAX_DEPTH + 1
+            }),
+        ]
+    );
+    test!(
+        harness,
+        "long chains of splits are followed without recursion",
+        Regex::new(&"a?".repeat(30000)).map(|regex| regex.is_match("aaa")),
+        Ok(true)
+    );
+
+    // The pattern a?^n a^n on the text a^n forces a backtracking matcher to
+    // try every combination of the optional characters.
+    note!(
+        harness,
+        "{:>3} {:>7} {:>12} {:>12} {:>12} {:>12}",
+        "n",
+        "States",
+        "NFA steps",
+        "NFA time",
+        "BT steps",
+        "BT time"
+    );
+    let mut rows = Vec::new();
+    for n in [5, 10, 15, 20] {
+        let pattern = format!("{}{}", "a?".repeat(n), "a".repeat(n));
+        let text = "a".repeat(n);
+        let regex = Regex::new(&pattern).unwrap();
+        let started = Instant::now();
+        let (nfa_match, nfa_steps) = regex.match_steps(&text);
+        let nfa_time = started.elapsed();
+        let started = Instant::now();
+        let (bt_match, bt_steps) = regex.backtracking_match(&text);
+        let bt_time = started.elapsed();
+        note!(
+            harness,
+            "{:>3} {:>7} {:>12} {:>12?} {:>12} {:>12?}",
+            n,
+            regex.state_count(),
+            nfa_steps,
+            nfa_time,
+            bt_steps,
+            bt_time
+        );
+        rows.push((n, nfa_match && bt_match, nfa_steps, bt_steps));
+    }
+    note!(harness);
+    test!(
+        harness,
+        "both matchers accept the pathological cases",
+        rows.iter().all(|row| row.1),
+        true
+    );
+    test!(
+        harness,
+        "the NFA grows quadratically, while backtracking grows exponentially",
+        rows.iter()
+            .all(|(n, _, nfa, backtracking)| *nfa <= 4 * n * n && *backtracking >= 1 << n),
+        true
+    );
+}
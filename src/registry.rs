@@ -0,0 +1,349 @@
+// registry.rs
+// A registry of the snippets of the crate, so that a single program can list
+// them, and run any of their demonstrations by name, like snippets run
+// leftpad. Crates like inventory let each module register itself, collected
+// by the linker before main starts, but that needs platform-specific tricks,
+// so here the registration is a single line per snippet in the register!
+// macro below, which is checked by the compiler, as it refers to the
+// demonstration function of the module. The macro also embeds the source of
+// each snippet, so that the metadata, the description, the author, and the
+// year, is read from the header of the file itself, and never goes out of
+// date. Names which are not found get a suggestion, the closest registered
+// name by the Levenshtein distance of the editdist snippet. The main
+// function is the driver of the snippets program, with the list, run, and
+// test commands, the last one running the demonstrations quietly, catching
+// their panics, and timing them with the bench snippet. The demonstration
+// checks that the registry and the modules of the library agree, and runs a
+// demonstration through it.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::bench::{format_time, Stopwatch};
+use crate::harness::Harness;
+use crate::table::{Alignment, Table};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+pub struct Snippet {
+    pub name: &'static str,
+    pub source: &'static str,
+    pub demo: fn(&mut Harness),
+}
+
+impl Snippet {
+    // The lines of the header, between the name of the file and the author.
+    fn header(&self) -> impl Iterator<Item = &'static str> {
+        self.source
+            .lines()
+            .skip(1)
+            .take_while(|line| !line.starts_with("// Author:"))
+            .map(|line| line.trim_start_matches("//").trim())
+    }
+
+    fn field(&self, name: &str) -> Option<&'static str> {
+        self.source
+            .lines()
+            .take_while(|line| !line.starts_with("// ---"))
+            .find_map(|line| line.strip_prefix("// ")?.strip_prefix(name))
+            .map(|value| value.trim_start_matches(':').trim())
+    }
+
+    pub fn description(&self) -> String {
+        self.header().collect::<Vec<_>>().join(" ")
+    }
+
+    // The first sentence of the description.
+    pub fn summary(&self) -> String {
+        let description = self.description();
+        match description.find(". ") {
+            Some(end) => description[..=end].to_string(),
+            None => description,
+        }
+    }
+
+    pub fn author(&self) -> Option<&'static str> {
+        self.field("Author")
+    }
+
+    pub fn year(&self) -> Option<u32> {
+        self.field("Year")?.parse().ok()
+    }
+
+    // Runs the demonstration with a new harness, and returns the exit code.
+    pub fn run(&self, quiet: bool) -> i32 {
+        crate::harness::run_demo(self.demo, quiet)
+    }
+}
+
+macro_rules! register {
+    ($($name:ident),* $(,)?) => {
+        static SNIPPETS: &[Snippet] = &[$(
+            Snippet {
+                name: stringify!($name),
+                source: include_str!(concat!(stringify!($name), ".rs")),
+                demo: crate::$name::demo,
+            },
+        )*];
+    };
+}
+
+// In alphabetical order, the same as the modules of the library.
+register! {
+    aes, ansi, args, astar, bench, bencode, bigint, bloom, brainfuck, bst,
+    calc, chacha20, channel, checksum, classical, compress, crc, cron, csv,
+    datetime, diff, dns, editdist, encoding, eventbus, executor, fft,
+    fixedpoint, generator, glob, graph, hashmap, heap, hmac, httpclient,
+    httpserver, huffman, humantime, ini, ipcalc, json, leftpad, lexer, life,
+    linkedlist, lru, matrix, maze, md5, memo, morse, msgpack, mt19937, ntp,
+    numwords, pbkdf2, portscan, primes, progress, protowire, punycode,
+    rational, regex, registry, ringbuffer, rng, roman, scheduler, semver,
+    serialize, sha1, sha2, sha3, sorting, statemachine, strsearch, sync, table,
+    tcpecho, template, threadpool, trie, unionfind, url, utf8, uuid, vm,
+    websocket, wordwrap, xml, xtea,
+}
+
+pub fn snippets() -> &'static [Snippet] {
+    SNIPPETS
+}
+
+pub fn find(name: &str) -> Option<&'static Snippet> {
+    let index = SNIPPETS
+        .binary_search_by(|snippet| snippet.name.cmp(name))
+        .ok()?;
+    Some(&SNIPPETS[index])
+}
+
+// The closest registered name, if it is close enough to be a typo.
+pub fn suggest(name: &str) -> Option<&'static str> {
+    let (distance, closest) = SNIPPETS
+        .iter()
+        .map(|snippet| (crate::editdist::distance(name, snippet.name), snippet.name))
+        .min()?;
+    (distance <= 2.max(name.chars().count() / 3)).then_some(closest)
+}
+
+// Runs the demonstration quietly, and returns whether it passed, counting a
+// panic as a failure, and how long it took.
+pub fn check(snippet: &Snippet) -> (bool, Duration) {
+    let stopwatch = Stopwatch::new();
+    let passed = panic::catch_unwind(AssertUnwindSafe(|| snippet.run(true) == 0));
+    (passed.unwrap_or(false), stopwatch.elapsed())
+}
+
+pub fn list() -> String {
+    let mut table = Table::new()
+        .header(&["Name", "Year", "Author", "Description"])
+        .align(1, Alignment::Right)
+        .max_width(3, 60)
+        .wrap(3);
+    for snippet in snippets() {
+        table.push(&[
+            snippet.name.to_string(),
+            snippet
+                .year()
+                .map_or(String::new(), |year| year.to_string()),
+            snippet.author().unwrap_or_default().to_string(),
+            snippet.summary(),
+        ]);
+    }
+    format!("{}{} snippets\n", table.render(), snippets().len())
+}
+
+fn lookup(program: &str, names: &[String]) -> Option<Vec<&'static Snippet>> {
+    let mut found = Vec::new();
+    for name in names {
+        match (find(name), suggest(name)) {
+            (Some(snippet), _) => found.push(snippet),
+            (None, Some(closest)) => {
+                eprintln!(
+                    "{}: unknown snippet {}, did you mean {}?",
+                    program, name, closest
+                );
+                return None;
+            }
+            (None, None) => {
+                eprintln!("{}: unknown snippet {}", program, name);
+                return None;
+            }
+        }
+    }
+    Some(found)
+}
+
+fn test(tested: &[&Snippet]) -> i32 {
+    let width = tested.iter().map(|snippet| snippet.name.len()).max();
+    let mut failed = Vec::new();
+    let mut total = Duration::ZERO;
+    for snippet in tested {
+        let (passed, time) = check(snippet);
+        println!(
+            "{:<width$} {} ({})",
+            snippet.name,
+            if passed { "passed" } else { "FAILED" },
+            format_time(time),
+            width = width.unwrap_or(0)
+        );
+        if !passed {
+            failed.push(snippet.name);
+        }
+        total += time;
+    }
+    if failed.is_empty() {
+        println!(
+            "All {} snippets passed in {}",
+            tested.len(),
+            format_time(total)
+        );
+        0
+    } else {
+        println!(
+            "{} of {} snippets failed: {}",
+            failed.len(),
+            tested.len(),
+            failed.join(", ")
+        );
+        1
+    }
+}
+
+pub fn main(mut arguments: impl Iterator<Item = String>) -> i32 {
+    use crate::args::Parser;
+    let program = crate::harness::program_name(arguments.next());
+    let parser = Parser::new(&program, "Lists, runs, and tests the snippets.")
+        .positional("COMMAND", "One of list, run, or test")
+        .rest("NAMES", "The snippet to run, or the snippets to test")
+        .flag(Some('a'), "all", "Test every snippet")
+        .flag(
+            Some('q'),
+            "quiet",
+            "Only report the failed checks of the demonstration",
+        );
+    let matches = match parser.parse_or_report(arguments) {
+        Ok(matches) => matches,
+        Err(code) => return code,
+    };
+    let names = matches.values("NAMES");
+    let all = matches.flag("all");
+    match matches.value("COMMAND").unwrap_or_default() {
+        "list" if names.is_empty() => {
+            print!("{}", list());
+            0
+        }
+        "run" if names.len() == 1 => match lookup(&program, names) {
+            Some(found) => found[0].run(matches.flag("quiet")),
+            None => 2,
+        },
+        "test" if all && names.is_empty() => test(&snippets().iter().collect::<Vec<_>>()),
+        "test" if !all && !names.is_empty() => match lookup(&program, names) {
+            Some(found) => test(&found),
+            None => 2,
+        },
+        "list" => {
+            eprintln!("{}: list takes no arguments", program);
+            2
+        }
+        "run" => {
+            eprintln!("{}: run takes the name of one snippet", program);
+            2
+        }
+        "test" => {
+            eprintln!("{}: test takes the names of snippets, or --all", program);
+            2
+        }
+        command => {
+            eprintln!("{}: unknown command {}", program, command);
+            2
+        }
+    }
+}
+
+// Demonstration
+
+pub fn demo(harness: &mut Harness) {
+    let modules: Vec<&str> = include_str!("lib.rs")
+        .lines()
+        .filter_map(|line| line.strip_prefix("pub mod ")?.strip_suffix(';'))
+        .filter(|name| *name != "harness")
+        .collect();
+    let names: Vec<&str> = snippets().iter().map(|snippet| snippet.name).collect();
+    test!(
+        harness,
+        "every module of the library is registered, in alphabetical order",
+        names.clone(),
+        modules
+    );
+    test!(
+        harness,
+        "snippets are found by name",
+        (find("leftpad").map(|s| s.name), find("rightpad").is_none()),
+        (Some("leftpad"), true)
+    );
+    test!(
+        harness,
+        "typos get a suggestion, others do not",
+        (suggest("lefpad"), suggest("sha265"), suggest("kubernetes")),
+        (Some("leftpad"), Some("sha2"), None)
+    );
+    let registry = find("registry").expect("the registry registers itself");
+    test!(
+        harness,
+        "the metadata is read from the header of the file",
+        (registry.author(), registry.year(), registry.summary()),
+        (
+            Some("Dénes Fintha"),
+            Some(2026),
+            String::from(
+                "A registry of the snippets of the crate, so that a single \
+                 program can list them, and run any of their demonstrations by \
+                 name, like snippets run leftpad."
+            )
+        )
+    );
+    test!(
+        harness,
+        "every snippet has a description, an author, and a year",
+        snippets()
+            .iter()
+            .filter(|s| s.description().is_empty() || s.author().is_none() || s.year().is_none())
+            .map(|s| s.name)
+            .collect::<Vec<_>>(),
+        Vec::<&str>::new()
+    );
+    test!(
+        harness,
+        "a demonstration runs through the registry",
+        find("roman").map(|snippet| snippet.run(true)),
+        Some(0)
+    );
+    test!(
+        harness,
+        "checking a demonstration reports whether it passed",
+        find("morse").map(|snippet| check(snippet).0),
+        Some(true)
+    );
+    let listing = list();
+    test!(
+        harness,
+        "the listing has a row for each snippet",
+        (
+            listing.lines().any(|line| line.contains("statemachine")),
+            listing.lines().last()
+        ),
+        (true, Some("91 snippets"))
+    );
+
+    let oldest = snippets()
+        .iter()
+        .min_by_key(|snippet| snippet.year())
+        .expect("there are snippets");
+    note!(
+        harness,
+        "{} snippets are registered, the oldest is {} from {}.",
+        names.len(),
+        oldest.name,
+        oldest.year().unwrap_or_default()
+    );
+}
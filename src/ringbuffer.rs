@@ -0,0 +1,309 @@
+// ringbuffer.rs
+// A fixed-capacity ring buffer, also called a circular queue, whose capacity is
+// a const generic parameter, so it lives in a plain array, without any heap
+// allocation. The elements are stored from a moving start index, which wraps
+// around to the beginning of the array. When the buffer is full, pushing either
+// rejects the new element, or overwrites the oldest one, which is useful for
+// keeping the last N samples, log lines, or key presses.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Reject,
+    Overwrite,
+}
+
+// The slots are options, so that the buffer is safe to use with any type,
+// without uninitialized memory.
+pub struct RingBuffer<T, const N: usize> {
+    slots: [Option<T>; N],
+    start: usize,
+    length: usize,
+    mode: Mode,
+}
+
+impl<T, const N: usize> RingBuffer<T, N> {
+    pub fn new() -> Self {
+        Self::with_mode(Mode::Reject)
+    }
+
+    pub fn overwriting() -> Self {
+        Self::with_mode(Mode::Overwrite)
+    }
+
+    pub fn with_mode(mode: Mode) -> Self {
+        Self {
+            slots: std::array::from_fn(|_| None),
+            start: 0,
+            length: 0,
+            mode,
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.length == N
+    }
+
+    fn index(&self, offset: usize) -> usize {
+        (self.start + offset) % N
+    }
+
+    // Returns the element which did not fit, which is the new one when the
+    // full buffer rejects it, and the oldest one when it is overwritten.
+    pub fn push(&mut self, value: T) -> Option<T> {
+        if N == 0 {
+            return Some(value);
+        }
+        if !self.is_full() {
+            let index = self.index(self.length);
+            self.slots[index] = Some(value);
+            self.length += 1;
+            return None;
+        }
+        match self.mode {
+            Mode::Reject => Some(value),
+            Mode::Overwrite => {
+                let oldest = self.slots[self.start].replace(value);
+                self.start = self.index(1);
+                oldest
+            }
+        }
+    }
+
+    // Removes the oldest element.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let value = self.slots[self.start].take();
+        self.start = self.index(1);
+        self.length -= 1;
+        value
+    }
+
+    // Removes the newest element.
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.length -= 1;
+        let index = self.index(self.length);
+        self.slots[index].take()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    // The element at the given position, counted from the oldest one.
+    pub fn get(&self, offset: usize) -> Option<&T> {
+        if offset >= self.length {
+            return None;
+        }
+        self.slots[self.index(offset)].as_ref()
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop().is_some() {}
+        self.start = 0;
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, N> {
+        Iter {
+            buffer: self,
+            front: 0,
+            back: self.length,
+        }
+    }
+}
+
+impl<T, const N: usize> Default for RingBuffer<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Iterates from the oldest element to the newest one.
+pub struct Iter<'a, T, const N: usize> {
+    buffer: &'a RingBuffer<T, N>,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T, const N: usize> Iterator for Iter<'a, T, N> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        self.buffer.get(self.front - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for Iter<'_, T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.buffer.get(self.back)
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for Iter<'_, T, N> {}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    let mut buffer: RingBuffer<i32, 4> = RingBuffer::new();
+    test!(
+        harness,
+        "the capacity is given by the type",
+        buffer.capacity(),
+        4
+    );
+    test!(harness, "popping an empty buffer", buffer.pop(), None);
+    for value in 1..=4 {
+        buffer.push(value);
+    }
+    test!(harness, "the buffer gets full", buffer.is_full(), true);
+    test!(
+        harness,
+        "a full buffer rejects the new element",
+        buffer.push(5),
+        Some(5)
+    );
+    test!(
+        harness,
+        "popping gives the oldest element",
+        buffer.pop(),
+        Some(1)
+    );
+    test!(
+        harness,
+        "popping from the back gives the newest",
+        buffer.pop_back(),
+        Some(4)
+    );
+
+    // The start is now at index 1, so these wrap around the end of the array.
+    buffer.push(5);
+    buffer.push(6);
+    test!(
+        harness,
+        "pushing wraps around the end of the storage",
+        buffer.iter().copied().collect::<Vec<_>>(),
+        vec![2, 3, 5, 6]
+    );
+    test!(
+        harness,
+        "the iterator runs backwards, too",
+        buffer.iter().rev().copied().collect::<Vec<_>>(),
+        vec![6, 5, 3, 2]
+    );
+    test!(
+        harness,
+        "the iterator knows its length",
+        buffer.iter().len(),
+        4
+    );
+    test!(
+        harness,
+        "elements are indexed from the oldest",
+        (buffer.get(0), buffer.get(3), buffer.get(4)),
+        (Some(&2), Some(&6), None)
+    );
+    let drained: Vec<i32> = std::iter::from_fn(|| buffer.pop()).collect();
+    test!(
+        harness,
+        "popping wraps around, too",
+        drained,
+        vec![2, 3, 5, 6]
+    );
+    test!(
+        harness,
+        "the drained buffer is empty",
+        buffer.is_empty(),
+        true
+    );
+
+    let mut last: RingBuffer<char, 3> = RingBuffer::overwriting();
+    let mut evicted = Vec::new();
+    for c in "abcdefg".chars() {
+        evicted.extend(last.push(c));
+    }
+    test!(
+        harness,
+        "an overwriting buffer keeps the newest elements",
+        last.iter().collect::<String>(),
+        "efg"
+    );
+    test!(
+        harness,
+        "overwriting evicts the oldest elements in order",
+        evicted,
+        vec!['a', 'b', 'c', 'd']
+    );
+    last.clear();
+    last.push('x');
+    test!(
+        harness,
+        "a cleared buffer can be reused",
+        (last.len(), last.peek()),
+        (1, Some(&'x'))
+    );
+
+    let mut empty: RingBuffer<u8, 0> = RingBuffer::overwriting();
+    test!(
+        harness,
+        "a buffer of zero capacity can not hold anything",
+        (empty.push(1), empty.len()),
+        (Some(1), 0)
+    );
+
+    // A moving average of the last five samples.
+    let mut window: RingBuffer<f64, 5> = RingBuffer::overwriting();
+    let samples = [3.0, 5.0, 4.0, 6.0, 8.0, 10.0, 9.0, 7.0];
+    let mut averages = Vec::new();
+    for sample in samples {
+        window.push(sample);
+        averages.push(window.iter().sum::<f64>() / window.len() as f64);
+    }
+    note!(harness, "Samples:         {:?}", samples);
+    note!(harness, "Moving averages: {:?}\n", averages);
+    test!(
+        harness,
+        "the moving average only considers the last five samples",
+        averages[7],
+        8.0
+    );
+}
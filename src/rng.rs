@@ -0,0 +1,259 @@
+// rng.rs
+// Small, fast, non-cryptographic pseudo-random number generators (SplitMix64,
+// xorshift64*, and PCG32) behind a common trait, which the MT19937 snippet also
+// implements, so that the generators can be used interchangeably, and compared
+// to each other. The trait only requires 32-bit output, and builds everything
+// else (64-bit output, bytes, bounded integers, and floating-point numbers) on
+// top of it, although generators with 64-bit output override that, too.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::mt19937::Mt19937;
+
+pub trait RandomSource {
+    fn next_u32(&mut self) -> u32;
+
+    fn next_u64(&mut self) -> u64 {
+        let high = self.next_u32() as u64;
+        let low = self.next_u32() as u64;
+        (high << 32) | low
+    }
+
+    fn fill_bytes(&mut self, bytes: &mut [u8]) {
+        for chunk in bytes.chunks_mut(8) {
+            let random = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&random[..chunk.len()]);
+        }
+    }
+
+    // Uniformly distributed on the [0, bound) interval. Values from the top
+    // of the range, which would make the lower values more likely, are
+    // rejected.
+    fn next_below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "the bound must be positive");
+        let limit = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < limit {
+                return value % bound;
+            }
+        }
+    }
+
+    // Uniformly distributed on the [0, 1) interval, with 53-bit resolution.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / 9007199254740992.0)
+    }
+
+    // Fisher-Yates shuffle.
+    fn shuffle<T>(&mut self, items: &mut [T])
+    where
+        Self: Sized,
+    {
+        for i in (1..items.len()).rev() {
+            let j = self.next_below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl RandomSource for SplitMix64 {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+pub struct XorShift64Star {
+    state: u64,
+}
+
+impl XorShift64Star {
+    // The state must never be zero, as zero is a fixed point of xorshift, so
+    // the seed is scrambled with SplitMix64, as recommended by its authors.
+    pub fn new(seed: u64) -> Self {
+        let mut state = SplitMix64::new(seed).next_u64();
+        if state == 0 {
+            state = 0x9e3779b97f4a7c15;
+        }
+        Self { state }
+    }
+}
+
+impl RandomSource for XorShift64Star {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+}
+
+pub struct Pcg32 {
+    state: u64,
+    increment: u64,
+}
+
+impl Pcg32 {
+    const MULTIPLIER: u64 = 6364136223846793005;
+
+    // The sequence selects one of 2^63 different streams, so generators with
+    // the same seed, but different sequences produce different numbers.
+    pub fn new(seed: u64, sequence: u64) -> Self {
+        let mut generator = Self {
+            state: 0,
+            increment: (sequence << 1) | 1,
+        };
+        generator.next_u32();
+        generator.state = generator.state.wrapping_add(seed);
+        generator.next_u32();
+        generator
+    }
+}
+
+impl RandomSource for Pcg32 {
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old
+            .wrapping_mul(Self::MULTIPLIER)
+            .wrapping_add(self.increment);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rotation = (old >> 59) as u32;
+        xorshifted.rotate_right(rotation)
+    }
+}
+
+impl RandomSource for Mt19937 {
+    // The trait has no room for errors, so using an unseeded generator through
+    // it is a programming error.
+    fn next_u32(&mut self) -> u32 {
+        match self.genrand_u32() {
+            Ok(value) => value,
+            Err(error) => panic!("{}", error),
+        }
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    let mut splitmix = SplitMix64::new(0);
+    test!(
+        harness,
+        "the first output of SplitMix64 seeded with 0 is the reference value",
+        splitmix.next_u64(),
+        0xe220a8397b1dcdaf
+    );
+
+    // The reference output of pcg32-demo from the PCG reference library.
+    let mut pcg = Pcg32::new(42, 54);
+    let first: Vec<u32> = (0..6).map(|_| pcg.next_u32()).collect();
+    test!(
+        harness,
+        "PCG32 seeded with 42 on sequence 54 reproduces the reference output",
+        first,
+        vec![0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e]
+    );
+
+    let mut first = XorShift64Star::new(0);
+    let mut second = XorShift64Star::new(0);
+    test!(
+        harness,
+        "xorshift64* seeded with 0 does not get stuck at 0",
+        (0..100).all(|_| first.next_u64() != 0),
+        true
+    );
+    let first: Vec<u64> = (0..100).map(|_| first.next_u64()).collect();
+    let second: Vec<u64> = (100..300).map(|_| second.next_u64()).collect();
+    test!(
+        harness,
+        "xorshift64* generators with the same seed produce the same sequence",
+        first,
+        second[100..].to_vec()
+    );
+
+    let mut bytes = [0u8; 13];
+    Pcg32::new(1, 1).fill_bytes(&mut bytes);
+    test!(
+        harness,
+        "filling a buffer of odd length fills the last partial chunk, too",
+        bytes[8..].iter().any(|byte| *byte != 0),
+        true
+    );
+
+    let mut items: Vec<u32> = (0..20).collect();
+    SplitMix64::new(7).shuffle(&mut items);
+    let mut sorted = items.clone();
+    sorted.sort();
+    test!(
+        harness,
+        "shuffling keeps every element",
+        sorted,
+        (0..20).collect::<Vec<u32>>()
+    );
+    note!(harness, "Shuffled with SplitMix64: {:?}\n", items);
+
+    // The generators are compared by the distribution of their output: the
+    // mean of uniform numbers on [0, 1) should be close to 1/2, and the
+    // number of values in each of ten buckets should be close to a tenth.
+    let mut generators: Vec<(&str, Box<dyn RandomSource>)> = vec![
+        ("SplitMix64", Box::new(SplitMix64::new(2026))),
+        ("xorshift64*", Box::new(XorShift64Star::new(2026))),
+        ("PCG32", Box::new(Pcg32::new(2026, 1))),
+        ("MT19937", Box::new(Mt19937::with_seed(2026))),
+    ];
+    const SAMPLES: usize = 100000;
+    note!(
+        harness,
+        "{:<12} {:>8} {:>8} {:>8}",
+        "Generator",
+        "Mean",
+        "Min",
+        "Max"
+    );
+    for (name, generator) in generators.iter_mut() {
+        let mut buckets = [0usize; 10];
+        let mut sum = 0.0;
+        for _ in 0..SAMPLES {
+            let value = generator.next_f64();
+            sum += value;
+            buckets[(value * 10.0) as usize] += 1;
+        }
+        let mean = sum / SAMPLES as f64;
+        let min = *buckets.iter().min().unwrap();
+        let max = *buckets.iter().max().unwrap();
+        note!(harness, "{:<12} {:>8.5} {:>8} {:>8}", name, mean, min, max);
+
+        let description = format!("the mean of {} output is close to 1/2", name);
+        test!(harness, &description, (mean - 0.5).abs() < 0.01, true);
+        let description = format!("the buckets of {} output are close to even", name);
+        test!(harness, &description, min > 9500 && max < 10500, true);
+    }
+    note!(harness);
+}
@@ -0,0 +1,389 @@
+// roman.rs
+// Conversion between integers and Roman numerals. Each decimal digit is written
+// with the symbols of its place, one, five, and ten, like I, V, and X for the
+// units, where a symbol repeats at most three times, and the digits four and
+// nine are written in subtractive notation, with a smaller symbol before the
+// larger one, like IV and IX. The largest number written this way is 3999, as
+// MMMM is not a valid numeral. Above that, the optional overline notation, the
+// vinculum, multiplies the symbols under the line by a thousand, which goes up
+// to 3999999, written with the combining overline character. Parsing checks
+// the numeral against the same rules, and reports what is wrong with malformed
+// numerals, like too many repetitions, invalid subtractions, or symbols out of
+// order, instead of just summing the values of the symbols.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+
+pub const MAX: u32 = 3999;
+pub const MAX_OVERLINE: u32 = 3_999_999;
+
+const OVERLINE: char = '\u{0305}';
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Notation {
+    Standard,
+    Overline,
+}
+
+// The symbols of one, five, and ten times the place value: the thousands have
+// no five or ten, so they can only be repeated up to three times.
+const PLACES: [(char, Option<char>, Option<char>); 4] = [
+    ('M', None, None),
+    ('C', Some('D'), Some('M')),
+    ('X', Some('L'), Some('C')),
+    ('I', Some('V'), Some('X')),
+];
+
+// The symbols of a digit, as indices of one, five, and ten.
+const DIGITS: [&[usize]; 10] = [
+    &[],
+    &[0],
+    &[0, 0],
+    &[0, 0, 0],
+    &[0, 1],
+    &[1],
+    &[1, 0],
+    &[1, 0, 0],
+    &[1, 0, 0, 0],
+    &[0, 2],
+];
+
+fn write_standard(number: u32, overline: bool, output: &mut String) {
+    let mut divisor = 1000;
+    for (one, five, ten) in PLACES {
+        let digit = (number / divisor % 10) as usize;
+        // The thousands place repeats M instead of writing larger digits.
+        let symbols: &[usize] = if divisor == 1000 {
+            &[0; 3][..digit]
+        } else {
+            DIGITS[digit]
+        };
+        for index in symbols {
+            output.push([Some(one), five, ten][*index].unwrap());
+            if overline {
+                output.push(OVERLINE);
+            }
+        }
+        divisor /= 10;
+    }
+}
+
+// The numeral of the number, or None if it is zero, or too large for the
+// notation. Numbers up to 3999 are written without overlines in both.
+pub fn to_roman(number: u32, notation: Notation) -> Option<String> {
+    let maximum = match notation {
+        Notation::Standard => MAX,
+        Notation::Overline => MAX_OVERLINE,
+    };
+    if number == 0 || number > maximum {
+        return None;
+    }
+    let mut numeral = String::new();
+    if number > MAX {
+        write_standard(number / 1000, true, &mut numeral);
+        write_standard(number % 1000, false, &mut numeral);
+    } else {
+        write_standard(number, false, &mut numeral);
+    }
+    Some(numeral)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    Empty,
+    InvalidCharacter,
+    TooManyRepetitions,
+    InvalidSubtraction,
+    OutOfOrder,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RomanError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for RomanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.kind {
+            ErrorKind::Empty => "empty numeral",
+            ErrorKind::InvalidCharacter => "invalid character",
+            ErrorKind::TooManyRepetitions => "symbol repeated too many times",
+            ErrorKind::InvalidSubtraction => "invalid subtractive pair",
+            ErrorKind::OutOfOrder => "symbol out of order",
+        };
+        write!(f, "{} at position {}", message, self.position)
+    }
+}
+
+impl std::error::Error for RomanError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Symbol {
+    letter: char,
+    overline: bool,
+    position: usize,
+}
+
+impl Symbol {
+    fn value(&self) -> u32 {
+        let value = match self.letter {
+            'I' => 1,
+            'V' => 5,
+            'X' => 10,
+            'L' => 50,
+            'C' => 100,
+            'D' => 500,
+            _ => 1000,
+        };
+        if self.overline {
+            value * 1000
+        } else {
+            value
+        }
+    }
+
+    fn same(&self, other: &Symbol) -> bool {
+        self.letter == other.letter && self.overline == other.overline
+    }
+}
+
+fn symbols(numeral: &str) -> Result<Vec<Symbol>, RomanError> {
+    let mut symbols: Vec<Symbol> = Vec::new();
+    for (position, c) in numeral.char_indices() {
+        match (c, symbols.last_mut()) {
+            ('I' | 'V' | 'X' | 'L' | 'C' | 'D' | 'M', _) => symbols.push(Symbol {
+                letter: c,
+                overline: false,
+                position,
+            }),
+            (OVERLINE, Some(last)) if !last.overline && last.position + 1 == position => {
+                last.overline = true
+            }
+            _ => {
+                return Err(RomanError {
+                    kind: ErrorKind::InvalidCharacter,
+                    position,
+                })
+            }
+        }
+    }
+    Ok(symbols)
+}
+
+// The digit of a place at the start of the symbols, and how many symbols it
+// takes, choosing the longest match, like IX over I.
+fn digit(symbols: &[Symbol], place: &[Option<char>; 3], overline: bool) -> (u32, usize) {
+    let mut best = (0, 0);
+    for (digit, pattern) in DIGITS.iter().enumerate() {
+        let matches = pattern.len() <= symbols.len()
+            && pattern.iter().zip(symbols).all(|(index, symbol)| {
+                place[*index] == Some(symbol.letter) && symbol.overline == overline
+            });
+        if matches && pattern.len() > best.1 {
+            best = (digit as u32, pattern.len());
+        }
+    }
+    best
+}
+
+// Why the parsing stopped at the symbol: a repetition of the previous symbols,
+// a subtraction from it, or a symbol which should have come earlier.
+fn diagnose(symbols: &[Symbol], index: usize) -> RomanError {
+    let symbol = &symbols[index];
+    let previous = &symbols[..index];
+    let repeated = previous
+        .iter()
+        .rev()
+        .take_while(|other| other.same(symbol))
+        .count();
+    let five = matches!(symbol.letter, 'V' | 'L' | 'D');
+    let (kind, position) = if repeated >= 3 || (repeated >= 1 && five) {
+        (ErrorKind::TooManyRepetitions, symbol.position)
+    } else if let Some(last) = previous.last().filter(|last| last.value() < symbol.value()) {
+        (ErrorKind::InvalidSubtraction, last.position)
+    } else {
+        (ErrorKind::OutOfOrder, symbol.position)
+    };
+    RomanError { kind, position }
+}
+
+pub fn from_roman(numeral: &str) -> Result<u32, RomanError> {
+    let symbols = symbols(numeral)?;
+    if symbols.is_empty() {
+        return Err(RomanError {
+            kind: ErrorKind::Empty,
+            position: 0,
+        });
+    }
+    let mut index = 0;
+    let mut value = 0;
+    for overline in [true, false] {
+        let mut multiplier = if overline { 1_000_000 } else { 1000 };
+        for (one, five, ten) in PLACES {
+            // The thousands are written with overlines, if there are any.
+            let skip = !overline && one == 'M' && value > 0;
+            let (digit, length) = if skip {
+                (0, 0)
+            } else {
+                digit(&symbols[index..], &[Some(one), five, ten], overline)
+            };
+            value += digit * multiplier;
+            index += length;
+            multiplier /= 10;
+        }
+    }
+    if index < symbols.len() {
+        return Err(diagnose(&symbols, index));
+    }
+    Ok(value)
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "converting to Roman numerals",
+        [1, 4, 9, 14, 40, 90, 400, 1994, 2026, 3999].map(|n| to_roman(n, Notation::Standard)),
+        [
+            "I",
+            "IV",
+            "IX",
+            "XIV",
+            "XL",
+            "XC",
+            "CD",
+            "MCMXCIV",
+            "MMXXVI",
+            "MMMCMXCIX"
+        ]
+        .map(|s| Some(s.to_string()))
+    );
+    test!(
+        harness,
+        "numbers without a standard numeral",
+        [0, 4000].map(|n| to_roman(n, Notation::Standard)),
+        [None, None]
+    );
+    test!(
+        harness,
+        "overline notation above 3999",
+        [4000, 5000, 12_345, 3_999_999].map(|n| to_roman(n, Notation::Overline)),
+        [
+            "I\u{305}V\u{305}",
+            "V\u{305}",
+            "X\u{305}I\u{305}I\u{305}CCCXLV",
+            "M\u{305}M\u{305}M\u{305}C\u{305}M\u{305}X\u{305}C\u{305}I\u{305}X\u{305}CMXCIX"
+        ]
+        .map(|s| Some(s.to_string()))
+    );
+    test!(
+        harness,
+        "overline notation below 4000 is standard",
+        to_roman(3999, Notation::Overline),
+        to_roman(3999, Notation::Standard)
+    );
+    test!(
+        harness,
+        "parsing Roman numerals",
+        ["III", "XLII", "MCMXCIV", "MMXXVI", "MMMCMXCIX"].map(from_roman),
+        [Ok(3), Ok(42), Ok(1994), Ok(2026), Ok(3999)]
+    );
+    test!(
+        harness,
+        "parsing numerals with overlines",
+        ["I\u{305}V\u{305}CMXCIX", "X\u{305}I\u{305}I\u{305}CCCXLV"].map(from_roman),
+        [Ok(4999), Ok(12_345)]
+    );
+    test!(
+        harness,
+        "every number round trips",
+        (1..=MAX_OVERLINE).step_by(101).chain(1..=MAX).all(|n| {
+            let numeral = to_roman(n, Notation::Overline).unwrap();
+            from_roman(&numeral) == Ok(n)
+        }),
+        true
+    );
+
+    let error = |kind, position| Err(RomanError { kind, position });
+    test!(
+        harness,
+        "too many repetitions",
+        ["IIII", "MMMM", "VV", "XXXXI"].map(from_roman),
+        [
+            error(ErrorKind::TooManyRepetitions, 3),
+            error(ErrorKind::TooManyRepetitions, 3),
+            error(ErrorKind::TooManyRepetitions, 1),
+            error(ErrorKind::TooManyRepetitions, 3)
+        ]
+    );
+    test!(
+        harness,
+        "invalid subtractions",
+        ["IL", "VX", "IIX", "XM", "MCMIC"].map(from_roman),
+        [
+            error(ErrorKind::InvalidSubtraction, 0),
+            error(ErrorKind::InvalidSubtraction, 0),
+            error(ErrorKind::InvalidSubtraction, 1),
+            error(ErrorKind::InvalidSubtraction, 0),
+            error(ErrorKind::InvalidSubtraction, 3)
+        ]
+    );
+    test!(
+        harness,
+        "symbols out of order",
+        ["IXI", "XCX", "CMD", "VIIIII"].map(from_roman),
+        [
+            error(ErrorKind::OutOfOrder, 2),
+            error(ErrorKind::OutOfOrder, 2),
+            error(ErrorKind::OutOfOrder, 2),
+            error(ErrorKind::TooManyRepetitions, 4)
+        ]
+    );
+    test!(
+        harness,
+        "invalid characters, and the empty numeral",
+        ["", "XIIZ", "xii", "\u{305}X", "X\u{305}\u{305}"].map(from_roman),
+        [
+            error(ErrorKind::Empty, 0),
+            error(ErrorKind::InvalidCharacter, 3),
+            error(ErrorKind::InvalidCharacter, 0),
+            error(ErrorKind::InvalidCharacter, 0),
+            error(ErrorKind::InvalidCharacter, 3)
+        ]
+    );
+    test!(
+        harness,
+        "thousands are not written both ways",
+        from_roman("I\u{305}V\u{305}M"),
+        error(ErrorKind::OutOfOrder, 6)
+    );
+
+    for year in [753, 1066, 1492, 1776, 2026] {
+        note!(
+            harness,
+            "{:>5} = {}",
+            year,
+            to_roman(year, Notation::Standard).unwrap()
+        );
+    }
+    note!(
+        harness,
+        "{:>5} = {}",
+        1_000_000,
+        to_roman(1_000_000, Notation::Overline).unwrap()
+    );
+    for numeral in ["MDCCCLXXXVIII", "MCMLXXXIIII", "IC", "DCD"] {
+        match from_roman(numeral) {
+            Ok(value) => note!(harness, "{} = {}", numeral, value),
+            Err(error) => note!(harness, "{} is invalid: {}", numeral, error),
+        }
+    }
+}
@@ -0,0 +1,413 @@
+// scheduler.rs
+// A work-stealing task scheduler, the way the runtimes of parallel languages
+// spread work over a few threads. Each worker has a deque of its own: the tasks
+// it spawns are pushed to the back, and it takes the next one to run from the
+// back, too, so it keeps working on the most recent, and the hottest in cache.
+// When its deque is empty, it steals from the front of another worker's deque,
+// where the oldest tasks are, which in a fork-join computation are the largest
+// pieces of work, so a steal is rare, and worth it. Tasks spawned from outside
+// go to a shared queue. A task returns a handle, which waits for its result,
+// and a worker waiting for a handle runs other tasks in the meantime, so a task
+// can spawn children and wait for them, without blocking the worker under it.
+// The deques are plain ones behind mutexes, for simplicity: the real ones are
+// lock free, after Chase and Lev, but they are scheduled in the same way.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+type Task = Box<dyn FnOnce() + Send + 'static>;
+
+struct Shared {
+    deques: Vec<Mutex<VecDeque<Task>>>,
+    injector: Mutex<VecDeque<Task>>,
+    // The idle workers sleep until a task is pushed, or for a short while, so
+    // that a wakeup can not be lost for good.
+    idle: Mutex<()>,
+    wakeup: Condvar,
+    shutdown: AtomicBool,
+    executed: AtomicUsize,
+    steals: AtomicUsize,
+}
+
+thread_local! {
+    // The scheduler and the index of the worker the thread runs, if any.
+    static WORKER: Cell<Option<(usize, usize)>> = const { Cell::new(None) };
+}
+
+impl Shared {
+    fn id(self: &Arc<Self>) -> usize {
+        Arc::as_ptr(self) as usize
+    }
+
+    // The index of the current worker, if it belongs to this scheduler.
+    fn current_worker(self: &Arc<Self>) -> Option<usize> {
+        match WORKER.with(Cell::get) {
+            Some((id, index)) if id == self.id() => Some(index),
+            _ => None,
+        }
+    }
+
+    fn push(self: &Arc<Self>, task: Task) {
+        match self.current_worker() {
+            Some(index) => self.deques[index].lock().unwrap().push_back(task),
+            None => self.injector.lock().unwrap().push_back(task),
+        }
+        self.wakeup.notify_one();
+    }
+
+    // The own deque first, then the shared queue, and then the deques of the
+    // other workers, starting from the next one.
+    fn find_task(&self, index: usize) -> Option<Task> {
+        if let Some(task) = self.deques[index].lock().unwrap().pop_back() {
+            return Some(task);
+        }
+        if let Some(task) = self.injector.lock().unwrap().pop_front() {
+            return Some(task);
+        }
+        let count = self.deques.len();
+        for offset in 1..count {
+            let victim = (index + offset) % count;
+            if let Some(task) = self.deques[victim].lock().unwrap().pop_front() {
+                self.steals.fetch_add(1, Ordering::Relaxed);
+                return Some(task);
+            }
+        }
+        None
+    }
+
+    fn run(&self, task: Task) {
+        task();
+        self.executed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn work(self: Arc<Self>, index: usize) {
+        WORKER.with(|worker| worker.set(Some((self.id(), index))));
+        loop {
+            if let Some(task) = self.find_task(index) {
+                self.run(task);
+                continue;
+            }
+            if self.shutdown.load(Ordering::Acquire) {
+                break;
+            }
+            let idle = self.idle.lock().unwrap();
+            let _ = self
+                .wakeup
+                .wait_timeout(idle, Duration::from_millis(1))
+                .unwrap();
+        }
+        WORKER.with(|worker| worker.set(None));
+    }
+}
+
+struct Slot<T> {
+    result: Mutex<Option<thread::Result<T>>>,
+    ready: Condvar,
+}
+
+pub struct JoinHandle<T> {
+    slot: Arc<Slot<T>>,
+    shared: Arc<Shared>,
+}
+
+impl<T> JoinHandle<T> {
+    pub fn is_finished(&self) -> bool {
+        self.slot.result.lock().unwrap().is_some()
+    }
+
+    // A worker runs other tasks while it waits, and any other thread blocks.
+    // The panic of the task is returned, like the handles of threads do.
+    pub fn join(self) -> thread::Result<T> {
+        match self.shared.current_worker() {
+            Some(index) => loop {
+                if let Some(result) = self.slot.result.lock().unwrap().take() {
+                    return result;
+                }
+                match self.shared.find_task(index) {
+                    Some(task) => self.shared.run(task),
+                    None => thread::yield_now(),
+                }
+            },
+            None => {
+                let mut result = self.slot.result.lock().unwrap();
+                loop {
+                    if let Some(result) = result.take() {
+                        return result;
+                    }
+                    result = self.slot.ready.wait(result).unwrap();
+                }
+            }
+        }
+    }
+}
+
+// A handle to the scheduler, which the tasks can take along, to spawn more.
+#[derive(Clone)]
+pub struct Handle {
+    shared: Arc<Shared>,
+}
+
+impl Handle {
+    pub fn spawn<T: Send + 'static>(
+        &self,
+        task: impl FnOnce() -> T + Send + 'static,
+    ) -> JoinHandle<T> {
+        let slot = Arc::new(Slot {
+            result: Mutex::new(None),
+            ready: Condvar::new(),
+        });
+        let task_slot = Arc::clone(&slot);
+        self.shared.push(Box::new(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(task));
+            *task_slot.result.lock().unwrap() = Some(result);
+            task_slot.ready.notify_all();
+        }));
+        JoinHandle {
+            slot,
+            shared: Arc::clone(&self.shared),
+        }
+    }
+
+    // Runs the two functions, possibly in parallel: the second one is spawned,
+    // for another worker to steal, while the first one runs right away.
+    pub fn join<A, B>(
+        &self,
+        first: impl FnOnce() -> A,
+        second: impl FnOnce() -> B + Send + 'static,
+    ) -> (A, B)
+    where
+        B: Send + 'static,
+    {
+        let handle = self.spawn(second);
+        let first = first();
+        match handle.join() {
+            Ok(second) => (first, second),
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+
+    // The number of tasks which ran, and which were stolen, so far.
+    pub fn executed(&self) -> usize {
+        self.shared.executed.load(Ordering::Relaxed)
+    }
+
+    pub fn steals(&self) -> usize {
+        self.shared.steals.load(Ordering::Relaxed)
+    }
+}
+
+pub struct Scheduler {
+    handle: Handle,
+    threads: Vec<thread::JoinHandle<()>>,
+}
+
+impl Scheduler {
+    pub fn new(workers: usize) -> Self {
+        assert!(workers > 0, "a scheduler needs at least one worker");
+        let shared = Arc::new(Shared {
+            deques: (0..workers).map(|_| Mutex::new(VecDeque::new())).collect(),
+            injector: Mutex::new(VecDeque::new()),
+            idle: Mutex::new(()),
+            wakeup: Condvar::new(),
+            shutdown: AtomicBool::new(false),
+            executed: AtomicUsize::new(0),
+            steals: AtomicUsize::new(0),
+        });
+        let threads = (0..workers)
+            .map(|index| {
+                let shared = Arc::clone(&shared);
+                thread::Builder::new()
+                    .name(format!("scheduler-{}", index))
+                    .spawn(move || shared.work(index))
+                    .expect("failed to start a worker thread")
+            })
+            .collect();
+        Self {
+            handle: Handle { shared },
+            threads,
+        }
+    }
+
+    pub fn workers(&self) -> usize {
+        self.threads.len()
+    }
+
+    pub fn handle(&self) -> Handle {
+        self.handle.clone()
+    }
+
+    pub fn spawn<T: Send + 'static>(
+        &self,
+        task: impl FnOnce() -> T + Send + 'static,
+    ) -> JoinHandle<T> {
+        self.handle.spawn(task)
+    }
+}
+
+// The workers finish the remaining tasks before they exit.
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        self.handle.shared.shutdown.store(true, Ordering::Release);
+        self.handle.shared.wakeup.notify_all();
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+// Demonstration
+
+use crate::bench::{format_time, Stopwatch};
+use crate::harness::Harness;
+use crate::threadpool::ThreadPool;
+
+const THRESHOLD: u64 = 18;
+
+fn fibonacci(n: u64) -> u64 {
+    if n < 2 {
+        n
+    } else {
+        fibonacci(n - 1) + fibonacci(n - 2)
+    }
+}
+
+// Forks at every level above the threshold, and waits for both halves.
+fn parallel_fibonacci(handle: &Handle, n: u64) -> u64 {
+    if n < THRESHOLD {
+        return fibonacci(n);
+    }
+    let other = handle.clone();
+    let (first, second) = handle.join(
+        || parallel_fibonacci(handle, n - 1),
+        move || parallel_fibonacci(&other, n - 2),
+    );
+    first + second
+}
+
+// The pool can not wait for the halves inside its jobs: with every worker
+// waiting, none would be left to run them. So the recursion is unfolded up
+// front, and only its leaves are sent to the pool.
+fn leaves(n: u64, out: &mut Vec<u64>) {
+    if n < THRESHOLD {
+        out.push(n);
+    } else {
+        leaves(n - 1, out);
+        leaves(n - 2, out);
+    }
+}
+
+pub fn demo(harness: &mut Harness) {
+    let scheduler = Scheduler::new(4);
+    let handles: Vec<JoinHandle<u64>> =
+        (0..20u64).map(|n| scheduler.spawn(move || n * n)).collect();
+    test!(
+        harness,
+        "spawned tasks return their results through their handles",
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .sum::<u64>(),
+        (0..20).map(|n| n * n).sum::<u64>()
+    );
+
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let failing = scheduler.spawn(|| -> u32 { panic!("task failed") });
+    let failed = failing.join().is_err();
+    panic::set_hook(hook);
+    test!(
+        harness,
+        "a panicking task is reported by its handle, and the workers survive",
+        (failed, scheduler.spawn(|| 42).join().unwrap()),
+        (true, 42)
+    );
+
+    let handle = scheduler.handle();
+    let nested = scheduler.spawn(move || {
+        let children: Vec<JoinHandle<usize>> = (0..8)
+            .map(|index| handle.spawn(move || index * 10))
+            .collect();
+        children
+            .into_iter()
+            .map(|child| child.join().unwrap())
+            .sum::<usize>()
+    });
+    test!(
+        harness,
+        "a task can spawn children, and wait for them",
+        nested.join().unwrap(),
+        280
+    );
+
+    // The same recursive computation, sequentially, on the scheduler, and,
+    // with its leaves unfolded, on the thread pool.
+    const N: u64 = 30;
+    let mut stopwatch = Stopwatch::new();
+    let expected = fibonacci(N);
+    let sequential_time = stopwatch.lap();
+
+    let handle = scheduler.handle();
+    let (executed, steals) = (handle.executed(), handle.steals());
+    let forked = scheduler
+        .spawn(move || parallel_fibonacci(&handle, N))
+        .join()
+        .unwrap();
+    let scheduler_time = stopwatch.lap();
+    let handle = scheduler.handle();
+    let tasks = handle.executed() - executed;
+    test!(
+        harness,
+        "fork-join on the scheduler computes the same result",
+        forked,
+        expected
+    );
+
+    let pool = ThreadPool::new(scheduler.workers());
+    stopwatch.lap();
+    let mut sizes = Vec::new();
+    leaves(N, &mut sizes);
+    let pooled: u64 = pool
+        .map(sizes.clone(), fibonacci)
+        .into_iter()
+        .map(Result::unwrap)
+        .sum();
+    let pool_time = stopwatch.lap();
+    test!(
+        harness,
+        "the unfolded leaves on the thread pool compute the same result",
+        pooled,
+        expected
+    );
+
+    note!(
+        harness,
+        "Fibonacci number {}, forking above {}, on {} workers:",
+        N,
+        THRESHOLD,
+        scheduler.workers()
+    );
+    note!(harness, "    sequential: {}", format_time(sequential_time));
+    note!(
+        harness,
+        "    scheduler:  {}, {} tasks, {} stolen",
+        format_time(scheduler_time),
+        tasks,
+        handle.steals() - steals
+    );
+    note!(
+        harness,
+        "    pool:       {}, {} jobs",
+        format_time(pool_time),
+        sizes.len()
+    );
+}
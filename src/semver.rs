@@ -0,0 +1,821 @@
+// semver.rs
+// Semantic versions, like 1.4.2-beta.3+build.7, as defined by the SemVer 2.0
+// specification, and the requirements on them, like ^1.4 or >=1.2, <1.5. A
+// version is three numbers, which are bumped for incompatible changes, for
+// new features, and for fixes, optionally followed by a prerelease, after a
+// hyphen, and build metadata, after a plus sign, both made of identifiers
+// separated by dots. The precedence compares the numbers first, then a
+// version with a prerelease comes before the same version without one, and
+// the prereleases are compared identifier by identifier, the numeric ones by
+// their value, and before the others, which are compared as text, and a
+// shorter list comes first. The build metadata does not count. The caret of
+// a requirement allows the changes which should be compatible, so ^1.4.2 is
+// at least 1.4.2, but less than 2.0.0, and on the unstable versions, ^0.4.2
+// is less than 0.5.0. The tilde only allows fixes, so ~1.4.2 is less than
+// 1.5.0. A bare version only matches itself, as in npm, the parts of a
+// version can be left out, or be wildcards, like 1.x, and the comparators are
+// joined with commas or spaces, and the alternatives with ||. A prerelease
+// only matches if a comparator of the same version mentions a prerelease,
+// so that ^1.4 does not pull in 2.0.0-alpha. The demonstration sorts a list
+// of versions, and matches them against a few requirements.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    Empty,
+    InvalidNumber,
+    LeadingZero,
+    MissingPart,
+    EmptyIdentifier,
+    InvalidCharacter,
+    InvalidOperator,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SemverError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for SemverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.kind {
+            ErrorKind::Empty => "empty version",
+            ErrorKind::InvalidNumber => "invalid number",
+            ErrorKind::LeadingZero => "leading zero in a number",
+            ErrorKind::MissingPart => "missing part of the version",
+            ErrorKind::EmptyIdentifier => "empty identifier",
+            ErrorKind::InvalidCharacter => "invalid character",
+            ErrorKind::InvalidOperator => "invalid operator",
+        };
+        write!(f, "{} at position {}", message, self.position)
+    }
+}
+
+impl std::error::Error for SemverError {}
+
+fn error(kind: ErrorKind, position: usize) -> SemverError {
+    SemverError { kind, position }
+}
+
+// The numeric identifiers come before the alphanumeric ones.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Identifier {
+    Numeric(u64),
+    Alphanumeric(String),
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Identifier::Numeric(value) => write!(f, "{}", value),
+            Identifier::Alphanumeric(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+fn number(text: &str, offset: usize) -> Result<u64, SemverError> {
+    if text.is_empty() {
+        return Err(error(ErrorKind::MissingPart, offset));
+    }
+    if let Some(index) = text.bytes().position(|byte| !byte.is_ascii_digit()) {
+        return Err(error(ErrorKind::InvalidCharacter, offset + index));
+    }
+    if text.len() > 1 && text.starts_with('0') {
+        return Err(error(ErrorKind::LeadingZero, offset));
+    }
+    text.parse()
+        .map_err(|_| error(ErrorKind::InvalidNumber, offset))
+}
+
+// The identifiers of a prerelease, or of the build metadata, which may have
+// leading zeros, as it is not compared.
+fn identifiers(text: &str, offset: usize, build: bool) -> Result<Vec<String>, SemverError> {
+    let mut result = Vec::new();
+    let mut position = offset;
+    for identifier in text.split('.') {
+        if identifier.is_empty() {
+            return Err(error(ErrorKind::EmptyIdentifier, position));
+        }
+        let valid = |byte: u8| byte.is_ascii_alphanumeric() || byte == b'-';
+        if let Some(index) = identifier.bytes().position(|byte| !valid(byte)) {
+            return Err(error(ErrorKind::InvalidCharacter, position + index));
+        }
+        let numeric = identifier.bytes().all(|byte| byte.is_ascii_digit());
+        if !build && numeric && identifier.len() > 1 && identifier.starts_with('0') {
+            return Err(error(ErrorKind::LeadingZero, position));
+        }
+        result.push(identifier.to_string());
+        position += identifier.len() + 1;
+    }
+    Ok(result)
+}
+
+fn prerelease(text: &str, offset: usize) -> Result<Vec<Identifier>, SemverError> {
+    let mut position = offset;
+    let mut result = Vec::new();
+    for identifier in identifiers(text, offset, false)? {
+        result.push(match identifier.bytes().all(|byte| byte.is_ascii_digit()) {
+            true => Identifier::Numeric(number(&identifier, position)?),
+            false => Identifier::Alphanumeric(identifier.clone()),
+        });
+        position += identifier.len() + 1;
+    }
+    Ok(result)
+}
+
+// The version, and its prerelease and build metadata, split at the first
+// hyphen, and at the plus sign after it.
+fn split(text: &str) -> (&str, Option<&str>, Option<&str>) {
+    let (rest, build) = match text.split_once('+') {
+        Some((rest, build)) => (rest, Some(build)),
+        None => (text, None),
+    };
+    match rest.split_once('-') {
+        Some((core, pre)) => (core, Some(pre), build),
+        None => (rest, None, build),
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Vec<Identifier>,
+    pub build: Vec<String>,
+}
+
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre: Vec::new(),
+            build: Vec::new(),
+        }
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+
+    // The order of the specification, which ignores the build metadata.
+    pub fn precedence(&self, other: &Self) -> Ordering {
+        let triple = |version: &Self| (version.major, version.minor, version.patch);
+        triple(self).cmp(&triple(other)).then_with(|| {
+            match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            }
+        })
+    }
+
+    fn parse_at(text: &str, offset: usize) -> Result<Self, SemverError> {
+        if text.is_empty() {
+            return Err(error(ErrorKind::Empty, offset));
+        }
+        let (core, pre, build) = split(text);
+        let mut parts = [0; 3];
+        let mut position = offset;
+        let mut pieces = core.split('.');
+        for part in parts.iter_mut() {
+            let piece = pieces
+                .next()
+                .ok_or(error(ErrorKind::MissingPart, offset + core.len()))?;
+            *part = number(piece, position)?;
+            position += piece.len() + 1;
+        }
+        if pieces.next().is_some() {
+            return Err(error(ErrorKind::InvalidCharacter, position - 1));
+        }
+        let pre_offset = offset + core.len() + 1;
+        let pre = match pre {
+            Some(pre) => prerelease(pre, pre_offset)?,
+            None => Vec::new(),
+        };
+        let build = match build {
+            Some(build) => identifiers(build, offset + text.len() - build.len(), true)?,
+            None => Vec::new(),
+        };
+        Ok(Self {
+            major: parts[0],
+            minor: parts[1],
+            patch: parts[2],
+            pre,
+            build,
+        })
+    }
+}
+
+// Equality and the order are those of the precedence, with the build
+// metadata as the last resort, so that sorting is deterministic.
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.precedence(other)
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for Version {
+    type Err = SemverError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse_at(text, 0)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        let join = |parts: Vec<String>| parts.join(".");
+        if !self.pre.is_empty() {
+            write!(
+                f,
+                "-{}",
+                join(self.pre.iter().map(|id| id.to_string()).collect())
+            )?;
+        }
+        if !self.build.is_empty() {
+            write!(f, "+{}", join(self.build.clone()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+}
+
+// A version with the trailing parts possibly left out, or wildcards.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comparator {
+    pub operator: Operator,
+    pub major: Option<u64>,
+    pub minor: Option<u64>,
+    pub patch: Option<u64>,
+    pub pre: Vec<Identifier>,
+}
+
+// The bounds of the versions a comparator allows, and whether they are
+// inclusive.
+type Bound = Option<(Version, bool)>;
+
+impl Comparator {
+    fn parse_at(text: &str, offset: usize) -> Result<Self, SemverError> {
+        let operators = [
+            (">=", Operator::GreaterEq),
+            ("<=", Operator::LessEq),
+            (">", Operator::Greater),
+            ("<", Operator::Less),
+            ("=", Operator::Exact),
+            ("~", Operator::Tilde),
+            ("^", Operator::Caret),
+        ];
+        let (operator, length) = operators
+            .iter()
+            .find(|(prefix, _)| text.starts_with(prefix))
+            .map_or((Operator::Exact, 0), |(prefix, operator)| {
+                (*operator, prefix.len())
+            });
+        let version = &text[length..];
+        let offset = offset + length;
+        if version.starts_with(['<', '>', '=', '~', '^']) {
+            return Err(error(ErrorKind::InvalidOperator, offset));
+        }
+        if version.is_empty() {
+            return Err(error(ErrorKind::Empty, offset));
+        }
+        let (core, pre, build) = split(version);
+        let mut parts = [None; 3];
+        let mut position = offset;
+        let mut wildcard = false;
+        for (index, piece) in core.split('.').enumerate() {
+            if index == 3 {
+                return Err(error(ErrorKind::InvalidCharacter, position - 1));
+            }
+            // Everything after a wildcard is a wildcard, too.
+            match piece {
+                "*" | "x" | "X" => wildcard = true,
+                _ if wildcard => return Err(error(ErrorKind::InvalidCharacter, position)),
+                _ => parts[index] = Some(number(piece, position)?),
+            }
+            position += piece.len() + 1;
+        }
+        let pre = match pre {
+            Some(_) if parts[2].is_none() => {
+                return Err(error(ErrorKind::MissingPart, offset + core.len()));
+            }
+            Some(pre) => prerelease(pre, offset + core.len() + 1)?,
+            None => Vec::new(),
+        };
+        if let Some(build) = build {
+            identifiers(build, offset + version.len() - build.len(), true)?;
+        }
+        Ok(Self {
+            operator,
+            major: parts[0],
+            minor: parts[1],
+            patch: parts[2],
+            pre,
+        })
+    }
+
+    // The version with the missing parts as zeros.
+    fn floor(&self) -> Version {
+        Version {
+            pre: self.pre.clone(),
+            ..Version::new(
+                self.major.unwrap_or(0),
+                self.minor.unwrap_or(0),
+                self.patch.unwrap_or(0),
+            )
+        }
+    }
+
+    // The first version after the ones which share the parts given, so 1.2
+    // is followed by 1.3.0, and an empty one by nothing, just like one with a
+    // part, which can not be incremented.
+    fn ceiling(&self) -> Option<Version> {
+        match (self.major, self.minor, self.patch) {
+            (None, _, _) => None,
+            (Some(major), None, _) => Some(Version::new(major.checked_add(1)?, 0, 0)),
+            (Some(major), Some(minor), None) => Some(Version::new(major, minor.checked_add(1)?, 0)),
+            (Some(major), Some(minor), Some(patch)) => {
+                Some(Version::new(major, minor, patch.checked_add(1)?))
+            }
+        }
+    }
+
+    fn tilde_ceiling(&self) -> Option<Version> {
+        match (self.major, self.minor) {
+            (Some(major), Some(minor)) => Some(Version::new(major, minor.checked_add(1)?, 0)),
+            (Some(major), None) => Some(Version::new(major.checked_add(1)?, 0, 0)),
+            _ => None,
+        }
+    }
+
+    // The first nonzero part may not change, or the last one given, if all
+    // of them are zeros.
+    fn caret_ceiling(&self) -> Option<Version> {
+        match (self.major, self.minor, self.patch) {
+            (None, _, _) => None,
+            (Some(major), _, _) if major > 0 => Some(Version::new(major.checked_add(1)?, 0, 0)),
+            (Some(_), Some(minor), _) if minor > 0 => {
+                Some(Version::new(0, minor.checked_add(1)?, 0))
+            }
+            (Some(_), Some(_), Some(patch)) => Some(Version::new(0, 0, patch.checked_add(1)?)),
+            (Some(_), Some(_), None) => Some(Version::new(0, 1, 0)),
+            (Some(_), None, _) => Some(Version::new(1, 0, 0)),
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.patch.is_some()
+    }
+
+    fn bounds(&self) -> (Bound, Bound) {
+        let floor = Some((self.floor(), true));
+        let below = |version: Option<Version>| version.map(|version| (version, false));
+        match self.operator {
+            Operator::Exact if self.is_complete() => (floor.clone(), floor),
+            Operator::Exact => (floor, below(self.ceiling())),
+            Operator::GreaterEq => (floor, None),
+            Operator::Greater if self.is_complete() => (Some((self.floor(), false)), None),
+            Operator::Greater => match self.ceiling() {
+                Some(ceiling) => (Some((ceiling, true)), None),
+                // No version comes after the largest one, which is the case,
+                // when the ceiling does not fit.
+                None if self.major.is_some() => {
+                    let largest = Version::new(u64::MAX, u64::MAX, u64::MAX);
+                    (Some((largest, false)), None)
+                }
+                None => (None, None),
+            },
+            Operator::Less => (None, Some((self.floor(), false))),
+            Operator::LessEq if self.is_complete() => (None, floor),
+            Operator::LessEq => (None, below(self.ceiling())),
+            Operator::Tilde => (floor, below(self.tilde_ceiling())),
+            Operator::Caret => (floor, below(self.caret_ceiling())),
+        }
+    }
+
+    pub fn matches(&self, version: &Version) -> bool {
+        let (lower, upper) = self.bounds();
+        let above = match lower {
+            Some((bound, true)) => version.precedence(&bound) != Ordering::Less,
+            Some((bound, false)) => version.precedence(&bound) == Ordering::Greater,
+            None => true,
+        };
+        let under = match upper {
+            Some((bound, true)) => version.precedence(&bound) != Ordering::Greater,
+            Some((bound, false)) => version.precedence(&bound) == Ordering::Less,
+            None => true,
+        };
+        above && under
+    }
+}
+
+// Alternatives, each of which is a list of comparators, which must all match.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Requirement {
+    alternatives: Vec<Vec<Comparator>>,
+}
+
+impl Requirement {
+    pub fn matches(&self, version: &Version) -> bool {
+        self.alternatives.iter().any(|comparators| {
+            let allows_prerelease = !version.is_prerelease()
+                || comparators.iter().any(|comparator| {
+                    !comparator.pre.is_empty()
+                        && (comparator.major, comparator.minor, comparator.patch)
+                            == (
+                                Some(version.major),
+                                Some(version.minor),
+                                Some(version.patch),
+                            )
+                });
+            allows_prerelease
+                && comparators
+                    .iter()
+                    .all(|comparator| comparator.matches(version))
+        })
+    }
+
+    pub fn max_satisfying<'a>(&self, versions: &'a [Version]) -> Option<&'a Version> {
+        versions
+            .iter()
+            .filter(|version| self.matches(version))
+            .max()
+    }
+}
+
+impl FromStr for Requirement {
+    type Err = SemverError;
+
+    // An operator may be followed by spaces, and the comparators are
+    // separated by commas, or spaces, or both.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut alternatives = Vec::new();
+        let mut offset = 0;
+        for alternative in text.split("||") {
+            let mut comparators = Vec::new();
+            let mut pending: Option<(String, usize)> = None;
+            let bytes = alternative.as_bytes();
+            let mut index = 0;
+            while index < bytes.len() {
+                if bytes[index] == b' ' || bytes[index] == b',' {
+                    index += 1;
+                    continue;
+                }
+                let start = index;
+                while index < bytes.len() && bytes[index] != b' ' && bytes[index] != b',' {
+                    index += 1;
+                }
+                let token = &alternative[start..index];
+                let operator_only = token.bytes().all(|byte| b"<>=~^".contains(&byte));
+                match pending.take() {
+                    // The positions in the version are moved past the spaces
+                    // after the operator.
+                    Some((operator, position)) => {
+                        let joined = format!("{}{}", operator, token);
+                        comparators.push(Comparator::parse_at(&joined, position).map_err(
+                            |mut error| {
+                                if error.position >= position + operator.len() {
+                                    error.position += offset + start - position - operator.len();
+                                }
+                                error
+                            },
+                        )?);
+                    }
+                    None if operator_only => pending = Some((token.to_string(), offset + start)),
+                    None => comparators.push(Comparator::parse_at(token, offset + start)?),
+                }
+            }
+            if let Some((operator, position)) = pending {
+                return Err(error(ErrorKind::Empty, position + operator.len()));
+            }
+            if comparators.is_empty() {
+                return Err(error(ErrorKind::Empty, offset));
+            }
+            alternatives.push(comparators);
+            offset += alternative.len() + 2;
+        }
+        Ok(Self { alternatives })
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use crate::table::{Alignment, Table};
+
+pub fn demo(harness: &mut Harness) {
+    let version = |text: &str| text.parse::<Version>().unwrap();
+    test!(
+        harness,
+        "a full version is parsed into its parts",
+        "1.4.2-beta.3+build.007".parse::<Version>().map(|version| {
+            (
+                version.major,
+                version.minor,
+                version.patch,
+                version.pre,
+                version.build,
+            )
+        }),
+        Ok((
+            1,
+            4,
+            2,
+            vec![
+                Identifier::Alphanumeric("beta".to_string()),
+                Identifier::Numeric(3)
+            ],
+            vec!["build".to_string(), "007".to_string()]
+        ))
+    );
+    test!(
+        harness,
+        "invalid versions are rejected where they go wrong",
+        [
+            "",
+            "1.2",
+            "1.2.3.4",
+            "01.2.3",
+            "1.2.3-",
+            "1.2.3-alpha..1",
+            "1.2.3-01",
+            "1.2.x",
+            "v1.2.3",
+            "1.2.3+b_1"
+        ]
+        .map(|text| text.parse::<Version>().err()),
+        [
+            Some(error(ErrorKind::Empty, 0)),
+            Some(error(ErrorKind::MissingPart, 3)),
+            Some(error(ErrorKind::InvalidCharacter, 5)),
+            Some(error(ErrorKind::LeadingZero, 0)),
+            Some(error(ErrorKind::EmptyIdentifier, 6)),
+            Some(error(ErrorKind::EmptyIdentifier, 12)),
+            Some(error(ErrorKind::LeadingZero, 6)),
+            Some(error(ErrorKind::InvalidCharacter, 4)),
+            Some(error(ErrorKind::InvalidCharacter, 0)),
+            Some(error(ErrorKind::InvalidCharacter, 7)),
+        ]
+    );
+
+    // The example of the specification, shuffled.
+    let ordered = [
+        "1.0.0-alpha",
+        "1.0.0-alpha.1",
+        "1.0.0-alpha.beta",
+        "1.0.0-beta",
+        "1.0.0-beta.2",
+        "1.0.0-beta.11",
+        "1.0.0-rc.1",
+        "1.0.0",
+        "1.0.1",
+        "1.1.0",
+        "2.0.0",
+    ];
+    let mut versions: Vec<Version> = [4, 9, 0, 7, 2, 10, 5, 1, 8, 3, 6]
+        .iter()
+        .map(|index| version(ordered[*index]))
+        .collect();
+    versions.sort();
+    test!(
+        harness,
+        "versions are sorted by the precedence of the specification",
+        versions.iter().map(Version::to_string).collect::<Vec<_>>(),
+        ordered.map(String::from).to_vec()
+    );
+    test!(
+        harness,
+        "the build metadata does not take part in the precedence",
+        (
+            version("1.0.0+a").precedence(&version("1.0.0+b")),
+            version("1.0.0+x") < version("1.0.1+a")
+        ),
+        (Ordering::Equal, true)
+    );
+    test!(
+        harness,
+        "versions are printed back as they were written",
+        version("10.20.30-rc.1.x-y+exp.sha.5114f85").to_string(),
+        "10.20.30-rc.1.x-y+exp.sha.5114f85"
+    );
+
+    let requirement = |text: &str| text.parse::<Requirement>().unwrap();
+    let matching = |text: &str, candidates: &[&str]| {
+        let requirement = requirement(text);
+        candidates
+            .iter()
+            .map(|candidate| requirement.matches(&version(candidate)))
+            .collect::<Vec<_>>()
+    };
+    let candidates = [
+        "0.2.2", "1.2.2", "1.2.3", "1.2.9", "1.3.0", "1.9.0", "2.0.0",
+    ];
+    test!(
+        harness,
+        "the caret allows the compatible changes",
+        matching("^1.2.3", &candidates),
+        vec![false, false, true, true, true, true, false]
+    );
+    test!(
+        harness,
+        "the tilde only allows the fixes",
+        matching("~1.2.3", &candidates),
+        vec![false, false, true, true, false, false, false]
+    );
+    test!(
+        harness,
+        "the caret is stricter on the unstable versions",
+        [
+            matching("^0.2.3", &["0.2.3", "0.2.9", "0.3.0"]),
+            matching("^0.0.3", &["0.0.3", "0.0.4"]),
+            matching("^0.0", &["0.0.9", "0.1.0"]),
+            matching("^1", &["1.9.9", "2.0.0"]),
+        ],
+        [
+            vec![true, true, false],
+            vec![true, false],
+            vec![true, false],
+            vec![true, false],
+        ]
+    );
+    test!(
+        harness,
+        "partial versions, wildcards, ranges, and alternatives",
+        [
+            matching("1.2", &["1.1.9", "1.2.0", "1.2.7", "1.3.0"]),
+            matching("1.x", &["0.9.0", "1.0.0", "1.9.9", "2.0.0"]),
+            matching(">=1.2.0, <1.3", &["1.1.0", "1.2.0", "1.2.9", "1.3.0"]),
+            matching(">1.2 <=2", &["1.2.9", "1.3.0", "2.9.9", "3.0.0"]),
+            matching("<1.0.0 || >= 2.1", &["0.9.0", "1.5.0", "2.0.0", "2.1.0"]),
+            matching("*", &["0.0.1", "99.0.0"]),
+        ],
+        [
+            vec![false, true, true, false],
+            vec![false, true, true, false],
+            vec![false, true, true, false],
+            vec![false, true, true, false],
+            vec![true, false, false, true],
+            vec![true, true],
+        ]
+    );
+    test!(
+        harness,
+        "a prerelease only matches if its version asks for one",
+        [
+            matching("^1.2.3", &["2.0.0-alpha", "1.2.4-beta"]),
+            matching(
+                "^1.2.3-alpha.2",
+                &["1.2.3-alpha.1", "1.2.3-alpha.10", "1.2.4-beta", "1.2.4"]
+            ),
+            matching(">=2.0.0-rc.1", &["2.0.0-rc.2", "2.0.0", "2.1.0-rc.1"]),
+        ],
+        [
+            vec![false, false],
+            vec![false, true, false, true],
+            vec![true, true, false],
+        ]
+    );
+    test!(
+        harness,
+        "invalid requirements are rejected where they go wrong",
+        [
+            ">=",
+            "^1.2 ||",
+            ">>1.0.0",
+            "1.x.3",
+            "~1.2-beta",
+            "1.2.3 ||| 2",
+            "1 || ^ 1x"
+        ]
+        .map(|text| text.parse::<Requirement>().err()),
+        [
+            Some(error(ErrorKind::Empty, 2)),
+            Some(error(ErrorKind::Empty, 7)),
+            Some(error(ErrorKind::InvalidOperator, 1)),
+            Some(error(ErrorKind::InvalidCharacter, 4)),
+            Some(error(ErrorKind::MissingPart, 4)),
+            Some(error(ErrorKind::InvalidCharacter, 8)),
+            Some(error(ErrorKind::InvalidCharacter, 8)),
+        ]
+    );
+    let largest = u64::MAX;
+    test!(
+        harness,
+        "the bounds of the largest parts are not incremented past them",
+        [
+            matching(
+                &format!("^{}.0.0", largest),
+                &["1.0.0", "18446744073709551615.9.9"]
+            ),
+            matching(
+                &format!("~0.{}", largest),
+                &["0.1.0", "0.18446744073709551615.3"]
+            ),
+            matching(
+                &format!(">{}.x", largest),
+                &["1.0.0", "18446744073709551615.1.0"]
+            ),
+            matching(
+                &format!("<={}", largest),
+                &["1.0.0", "18446744073709551615.1.0"]
+            ),
+        ],
+        [
+            vec![false, true],
+            vec![false, true],
+            vec![false, false],
+            vec![true, true],
+        ]
+    );
+
+    let releases: Vec<Version> = [
+        "0.9.4",
+        "1.0.0",
+        "1.2.3",
+        "1.2.10",
+        "1.3.0-rc.1",
+        "1.3.0",
+        "1.4.1",
+        "2.0.0-beta.1",
+        "2.0.0",
+    ]
+    .iter()
+    .map(|text| version(text))
+    .collect();
+    test!(
+        harness,
+        "the highest matching version is picked",
+        ["^1.2", "~1.2", "<1.3", ">=2.0.0-beta", "^3"].map(|text| {
+            requirement(text)
+                .max_satisfying(&releases)
+                .map(Version::to_string)
+        }),
+        [
+            Some("1.4.1".to_string()),
+            Some("1.2.10".to_string()),
+            Some("1.2.10".to_string()),
+            Some("2.0.0".to_string()),
+            None
+        ]
+    );
+
+    let requirements = ["^1.2", "~1.2.3", ">=1.0, <1.3", "1.3.0-rc.1", "^0.9 || ^2"];
+    let mut header = vec!["version"];
+    header.extend(requirements);
+    let mut table = Table::new().header(&header);
+    for column in 1..header.len() {
+        table = table.align(column, Alignment::Center);
+    }
+    for release in &releases {
+        let mut row = vec![release.to_string()];
+        for text in requirements {
+            let matched = requirement(text).matches(release);
+            row.push(if matched { "yes" } else { "" }.to_string());
+        }
+        table.push(&row);
+    }
+    note!(
+        harness,
+        "Releases, and the requirements they meet:\n{}",
+        table.render()
+    );
+}
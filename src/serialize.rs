@@ -0,0 +1,735 @@
+// serialize.rs
+// A hand-rolled binary serialization format, which can evolve without
+// breaking the programs which already read it. The integers are varints, in
+// groups of seven bits, least significant first, with the top bit of each
+// byte telling whether more follow, which keeps the small numbers small, and
+// the signed ones are zigzag encoded first, so that the small negative numbers
+// are small, too. Floating-point numbers are eight little-endian bytes, and
+// the strings and byte arrays are prefixed with their length. A record is a
+// version byte, and a list of fields, each prefixed with its number and its
+// length, and closed by a zero. An optional field is simply left out, and the
+// decoder fills in the default for the fields that are missing, which are the
+// fields of the later versions in the records of the earlier ones. A record
+// of a later version than the decoder knows may have fields it has never
+// heard of, which it skips by their length, while an unknown field in a
+// record of its own version means that the data is corrupt. The demonstration
+// round-trips a structure across two versions of the format.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnexpectedEnd,
+    VarintOverflow,
+    InvalidUtf8,
+    InvalidBool,
+    UnknownField,
+    DuplicateField,
+    MissingField,
+    FieldLength,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.kind {
+            ErrorKind::UnexpectedEnd => "unexpected end of data",
+            ErrorKind::VarintOverflow => "varint does not fit in 64 bits",
+            ErrorKind::InvalidUtf8 => "string is not valid UTF-8",
+            ErrorKind::InvalidBool => "boolean is neither 0 nor 1",
+            ErrorKind::UnknownField => "unknown field",
+            ErrorKind::DuplicateField => "duplicate field",
+            ErrorKind::MissingField => "missing required field",
+            ErrorKind::FieldLength => "field length does not match its contents",
+        };
+        write!(f, "{} at position {}", message, self.position)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+pub fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+pub fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+pub fn write_varint(output: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        output.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
+
+// The value, and the number of bytes it took. Ten bytes hold 64 bits, with
+// only the lowest bit used in the last one.
+pub fn read_varint(input: &[u8]) -> Result<(u64, usize), ErrorKind> {
+    let mut value = 0;
+    for (index, byte) in input.iter().enumerate() {
+        if index == 9 && *byte > 1 {
+            return Err(ErrorKind::VarintOverflow);
+        }
+        value |= ((byte & 0x7f) as u64) << (index * 7);
+        if byte & 0x80 == 0 {
+            return Ok((value, index + 1));
+        }
+    }
+    Err(ErrorKind::UnexpectedEnd)
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Writer {
+    output: Vec<u8>,
+}
+
+impl Writer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn u8(&mut self, value: u8) -> &mut Self {
+        self.output.push(value);
+        self
+    }
+
+    pub fn bool(&mut self, value: bool) -> &mut Self {
+        self.u8(value as u8)
+    }
+
+    pub fn varint(&mut self, value: u64) -> &mut Self {
+        write_varint(&mut self.output, value);
+        self
+    }
+
+    pub fn svarint(&mut self, value: i64) -> &mut Self {
+        self.varint(zigzag(value))
+    }
+
+    pub fn f64(&mut self, value: f64) -> &mut Self {
+        self.output.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub fn bytes(&mut self, value: &[u8]) -> &mut Self {
+        self.varint(value.len() as u64);
+        self.output.extend_from_slice(value);
+        self
+    }
+
+    pub fn str(&mut self, value: &str) -> &mut Self {
+        self.bytes(value.as_bytes())
+    }
+
+    pub fn value<T: Encode + ?Sized>(&mut self, value: &T) -> &mut Self {
+        value.encode(self);
+        self
+    }
+
+    // The field is written to the side first, as its length comes before it.
+    pub fn field<T: Encode + ?Sized>(&mut self, number: u64, value: &T) -> &mut Self {
+        let mut contents = Writer::new();
+        value.encode(&mut contents);
+        self.varint(number).bytes(&contents.output)
+    }
+
+    pub fn optional_field<T: Encode>(&mut self, number: u64, value: &Option<T>) -> &mut Self {
+        match value {
+            Some(value) => self.field(number, value),
+            None => self,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.output.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.output.is_empty()
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.output
+    }
+}
+
+// The positions of the errors are counted from the start of the whole input,
+// even in the readers of the fields.
+#[derive(Clone, Copy, Debug)]
+pub struct Reader<'a> {
+    input: &'a [u8],
+    position: usize,
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            position: 0,
+            offset: 0,
+        }
+    }
+
+    pub fn position(&self) -> usize {
+        self.offset + self.position
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.position == self.input.len()
+    }
+
+    pub fn error(&self, kind: ErrorKind) -> DecodeError {
+        DecodeError {
+            kind,
+            position: self.position(),
+        }
+    }
+
+    fn take(&mut self, count: usize) -> Result<&'a [u8], DecodeError> {
+        if self.input.len() - self.position < count {
+            return Err(self.error(ErrorKind::UnexpectedEnd));
+        }
+        let taken = &self.input[self.position..self.position + count];
+        self.position += count;
+        Ok(taken)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn bool(&mut self) -> Result<bool, DecodeError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(DecodeError {
+                kind: ErrorKind::InvalidBool,
+                position: self.position() - 1,
+            }),
+        }
+    }
+
+    pub fn varint(&mut self) -> Result<u64, DecodeError> {
+        match read_varint(&self.input[self.position..]) {
+            Ok((value, length)) => {
+                self.position += length;
+                Ok(value)
+            }
+            Err(kind) => Err(self.error(kind)),
+        }
+    }
+
+    pub fn svarint(&mut self) -> Result<i64, DecodeError> {
+        self.varint().map(unzigzag)
+    }
+
+    pub fn f64(&mut self) -> Result<f64, DecodeError> {
+        let bytes = self.take(8)?;
+        Ok(f64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    pub fn bytes(&mut self) -> Result<&'a [u8], DecodeError> {
+        let length = self.varint()?;
+        match usize::try_from(length) {
+            Ok(length) => self.take(length),
+            Err(_) => Err(self.error(ErrorKind::UnexpectedEnd)),
+        }
+    }
+
+    pub fn str(&mut self) -> Result<&'a str, DecodeError> {
+        let start = self.position();
+        let bytes = self.bytes()?;
+        std::str::from_utf8(bytes).map_err(|_| DecodeError {
+            kind: ErrorKind::InvalidUtf8,
+            position: start,
+        })
+    }
+
+    pub fn value<T: Decode>(&mut self) -> Result<T, DecodeError> {
+        T::decode(self)
+    }
+
+    // The number of the next field, and a reader over its contents, or
+    // nothing at the zero which closes the record.
+    pub fn next_field(&mut self) -> Result<Option<(u64, Reader<'a>)>, DecodeError> {
+        let number = self.varint()?;
+        if number == 0 {
+            return Ok(None);
+        }
+        let contents = self.bytes()?;
+        let start = self.position - contents.len();
+        Ok(Some((
+            number,
+            Reader {
+                input: contents,
+                position: 0,
+                offset: self.offset + start,
+            },
+        )))
+    }
+}
+
+pub trait Encode {
+    fn encode(&self, writer: &mut Writer);
+}
+
+pub trait Decode: Sized {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError>;
+}
+
+macro_rules! impl_unsigned {
+    ($($type:ty),*) => {
+        $(
+            impl Encode for $type {
+                fn encode(&self, writer: &mut Writer) {
+                    writer.varint(*self as u64);
+                }
+            }
+
+            impl Decode for $type {
+                fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+                    let start = reader.position();
+                    let value = reader.varint()?;
+                    <$type>::try_from(value).map_err(|_| DecodeError {
+                        kind: ErrorKind::VarintOverflow,
+                        position: start,
+                    })
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_signed {
+    ($($type:ty),*) => {
+        $(
+            impl Encode for $type {
+                fn encode(&self, writer: &mut Writer) {
+                    writer.svarint(*self as i64);
+                }
+            }
+
+            impl Decode for $type {
+                fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+                    let start = reader.position();
+                    let value = reader.svarint()?;
+                    <$type>::try_from(value).map_err(|_| DecodeError {
+                        kind: ErrorKind::VarintOverflow,
+                        position: start,
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned!(u16, u32, u64, usize);
+impl_signed!(i16, i32, i64);
+
+impl Encode for bool {
+    fn encode(&self, writer: &mut Writer) {
+        writer.bool(*self);
+    }
+}
+
+impl Decode for bool {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        reader.bool()
+    }
+}
+
+impl Encode for f64 {
+    fn encode(&self, writer: &mut Writer) {
+        writer.f64(*self);
+    }
+}
+
+impl Decode for f64 {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        reader.f64()
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, writer: &mut Writer) {
+        writer.str(self);
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, writer: &mut Writer) {
+        writer.str(self);
+    }
+}
+
+impl Decode for String {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        reader.str().map(String::from)
+    }
+}
+
+// A count, and the elements.
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, writer: &mut Writer) {
+        writer.varint(self.len() as u64);
+        for element in self {
+            element.encode(writer);
+        }
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        let count = reader.varint()?;
+        // The count is not trusted for the allocation, each element takes at
+        // least a byte.
+        let mut elements = Vec::with_capacity(count.min(reader.input.len() as u64) as usize);
+        for _ in 0..count {
+            elements.push(T::decode(reader)?);
+        }
+        Ok(elements)
+    }
+}
+
+// A value read from the contents of a field, which must use all of them.
+pub fn field_value<T: Decode>(mut contents: Reader) -> Result<T, DecodeError> {
+    let value = T::decode(&mut contents)?;
+    if !contents.is_at_end() {
+        return Err(contents.error(ErrorKind::FieldLength));
+    }
+    Ok(value)
+}
+
+// A record is a version byte, then the fields. The closure of the encoder
+// writes the fields, and the closure of the decoder is given each field, and
+// returns whether it knew it.
+pub fn encode_record(version: u8, fields: impl FnOnce(&mut Writer)) -> Vec<u8> {
+    let mut writer = Writer::new();
+    writer.u8(version);
+    fields(&mut writer);
+    writer.varint(0);
+    writer.finish()
+}
+
+pub fn decode_record<'a>(
+    input: &'a [u8],
+    known_version: u8,
+    mut field: impl FnMut(u64, Reader<'a>) -> Result<bool, DecodeError>,
+) -> Result<u8, DecodeError> {
+    let mut reader = Reader::new(input);
+    let version = reader.u8()?;
+    let mut seen = Vec::new();
+    loop {
+        let position = reader.position();
+        let Some((number, contents)) = reader.next_field()? else {
+            break;
+        };
+        if seen.contains(&number) {
+            return Err(DecodeError {
+                kind: ErrorKind::DuplicateField,
+                position,
+            });
+        }
+        seen.push(number);
+        if !field(number, contents)? && version <= known_version {
+            return Err(DecodeError {
+                kind: ErrorKind::UnknownField,
+                position,
+            });
+        }
+    }
+    Ok(version)
+}
+
+pub fn required<T>(value: Option<T>, input: &[u8]) -> Result<T, DecodeError> {
+    value.ok_or(DecodeError {
+        kind: ErrorKind::MissingField,
+        position: input.len(),
+    })
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+// The first version of a user profile, with an optional e-mail address.
+#[derive(Clone, Debug, PartialEq)]
+struct ProfileV1 {
+    id: u64,
+    name: String,
+    email: Option<String>,
+}
+
+impl ProfileV1 {
+    fn encode(&self) -> Vec<u8> {
+        encode_record(1, |writer| {
+            writer
+                .field(1, &self.id)
+                .field(2, &self.name)
+                .optional_field(3, &self.email);
+        })
+    }
+
+    fn decode(input: &[u8]) -> Result<Self, DecodeError> {
+        let (mut id, mut name, mut email) = (None, None, None);
+        decode_record(input, 1, |number, contents| {
+            match number {
+                1 => id = Some(field_value(contents)?),
+                2 => name = Some(field_value(contents)?),
+                3 => email = Some(field_value(contents)?),
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+        Ok(Self {
+            id: required(id, input)?,
+            name: required(name, input)?,
+            email,
+        })
+    }
+}
+
+// The second version adds the tags, and the balance, which is signed, and
+// a floating-point rating, each with a default for the old records.
+#[derive(Clone, Debug, PartialEq)]
+struct ProfileV2 {
+    id: u64,
+    name: String,
+    email: Option<String>,
+    tags: Vec<String>,
+    balance: i64,
+    rating: f64,
+}
+
+impl ProfileV2 {
+    fn encode(&self) -> Vec<u8> {
+        encode_record(2, |writer| {
+            writer
+                .field(1, &self.id)
+                .field(2, &self.name)
+                .optional_field(3, &self.email)
+                .field(4, &self.tags)
+                .field(5, &self.balance)
+                .field(6, &self.rating);
+        })
+    }
+
+    fn decode(input: &[u8]) -> Result<Self, DecodeError> {
+        let (mut id, mut name, mut email) = (None, None, None);
+        let (mut tags, mut balance, mut rating) = (Vec::new(), 0, 0.0);
+        decode_record(input, 2, |number, contents| {
+            match number {
+                1 => id = Some(field_value(contents)?),
+                2 => name = Some(field_value(contents)?),
+                3 => email = Some(field_value(contents)?),
+                4 => tags = field_value(contents)?,
+                5 => balance = field_value(contents)?,
+                6 => rating = field_value(contents)?,
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+        Ok(Self {
+            id: required(id, input)?,
+            name: required(name, input)?,
+            email,
+            tags,
+            balance,
+            rating,
+        })
+    }
+}
+
+pub fn demo(harness: &mut Harness) {
+    let varint = |value| {
+        let mut output = Vec::new();
+        write_varint(&mut output, value);
+        output
+    };
+    test!(
+        harness,
+        "varints take a byte for every seven bits",
+        [0, 127, 128, 300, u64::MAX].map(varint),
+        [
+            vec![0x00],
+            vec![0x7f],
+            vec![0x80, 0x01],
+            vec![0xac, 0x02],
+            vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+        ]
+    );
+    test!(
+        harness,
+        "varints are read back, and the broken ones rejected",
+        [
+            read_varint(&[0xac, 0x02, 0xff]),
+            read_varint(&[0x80, 0x80]),
+            read_varint(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02]),
+        ],
+        [
+            Ok((300, 2)),
+            Err(ErrorKind::UnexpectedEnd),
+            Err(ErrorKind::VarintOverflow),
+        ]
+    );
+    test!(
+        harness,
+        "zigzag encoding keeps the small negative numbers small",
+        [0, -1, 1, -2, i64::MAX, i64::MIN].map(zigzag),
+        [0, 1, 2, 3, u64::MAX - 1, u64::MAX]
+    );
+    test!(
+        harness,
+        "zigzag decoding is the inverse",
+        [0, -1, 1, -64, i64::MAX, i64::MIN].map(|value| unzigzag(zigzag(value)) == value),
+        [true; 6]
+    );
+
+    let mut writer = Writer::new();
+    writer.str("héllo").svarint(-3).bool(true).f64(0.5);
+    let bytes = writer.finish();
+    let mut reader = Reader::new(&bytes);
+    test!(
+        harness,
+        "the primitives are round-tripped",
+        (
+            reader.str(),
+            reader.svarint(),
+            reader.bool(),
+            reader.f64(),
+            reader.is_at_end()
+        ),
+        (Ok("héllo"), Ok(-3), Ok(true), Ok(0.5), true)
+    );
+    test!(
+        harness,
+        "broken primitives are rejected where they start",
+        [
+            Reader::new(&[0x05, b'a', b'b']).str().err(),
+            Reader::new(&[0x02, 0xc3, 0x28]).str().err(),
+            Reader::new(&[0x02]).bool().err(),
+            Reader::new(&[0x00, 0x00]).f64().err(),
+        ],
+        [
+            Some(DecodeError {
+                kind: ErrorKind::UnexpectedEnd,
+                position: 1
+            }),
+            Some(DecodeError {
+                kind: ErrorKind::InvalidUtf8,
+                position: 0
+            }),
+            Some(DecodeError {
+                kind: ErrorKind::InvalidBool,
+                position: 0
+            }),
+            Some(DecodeError {
+                kind: ErrorKind::UnexpectedEnd,
+                position: 0
+            }),
+        ]
+    );
+
+    let old = ProfileV1 {
+        id: 42,
+        name: "Ada".to_string(),
+        email: None,
+    };
+    let new = ProfileV2 {
+        id: 1815,
+        name: "Ada Lovelace".to_string(),
+        email: Some("ada@example.com".to_string()),
+        tags: vec!["analyst".to_string(), "poet".to_string()],
+        balance: -250,
+        rating: 4.5,
+    };
+    let old_bytes = old.encode();
+    let new_bytes = new.encode();
+    test!(
+        harness,
+        "a record is the version, the fields, and a zero",
+        old_bytes.clone(),
+        vec![1, 1, 1, 42, 2, 4, 3, b'A', b'd', b'a', 0]
+    );
+    test!(
+        harness,
+        "both versions are round-tripped",
+        (ProfileV1::decode(&old_bytes), ProfileV2::decode(&new_bytes)),
+        (Ok(old.clone()), Ok(new.clone()))
+    );
+    test!(
+        harness,
+        "the old decoder skips the fields of the new version",
+        ProfileV1::decode(&new_bytes),
+        Ok(ProfileV1 {
+            id: new.id,
+            name: new.name.clone(),
+            email: new.email.clone(),
+        })
+    );
+    test!(
+        harness,
+        "the new decoder fills in the defaults for an old record",
+        ProfileV2::decode(&old_bytes),
+        Ok(ProfileV2 {
+            id: old.id,
+            name: old.name.clone(),
+            email: None,
+            tags: Vec::new(),
+            balance: 0,
+            rating: 0.0,
+        })
+    );
+
+    // A field numbered 9, in a record claiming to be the first version.
+    let mut forged = old_bytes.clone();
+    forged.pop();
+    forged.extend_from_slice(&[9, 1, 0, 0]);
+    let mut duplicated = old_bytes.clone();
+    duplicated.pop();
+    duplicated.extend_from_slice(&[1, 1, 7, 0]);
+    let nameless = vec![1, 1, 1, 42, 0];
+    let mut truncated = new_bytes.clone();
+    truncated.truncate(20);
+    let padded = vec![1, 1, 2, 42, 0];
+    test!(
+        harness,
+        "corrupt records are rejected",
+        [
+            ProfileV1::decode(&forged).err(),
+            ProfileV1::decode(&duplicated).err(),
+            ProfileV1::decode(&nameless).err(),
+            ProfileV2::decode(&truncated).err(),
+            ProfileV1::decode(&padded).err(),
+        ]
+        .map(|error| error.map(|error| error.kind)),
+        [
+            Some(ErrorKind::UnknownField),
+            Some(ErrorKind::DuplicateField),
+            Some(ErrorKind::MissingField),
+            Some(ErrorKind::UnexpectedEnd),
+            Some(ErrorKind::FieldLength),
+        ]
+    );
+
+    note!(
+        harness,
+        "The first version takes {} bytes, the second {} bytes: {}",
+        old_bytes.len(),
+        new_bytes.len(),
+        crate::encoding::HEX.encode(&new_bytes)
+    );
+}
@@ -0,0 +1,199 @@
+// sha1.rs
+// The SHA-1 hash function, as specified in FIPS 180-4, with the same streaming
+// interface as the SHA-2 snippet, so it can be used in its place, for example
+// under HMAC. It is the predecessor of SHA-256, with the same Merkle-Damgård
+// construction over 64-byte blocks of big-endian words, but with a 160-bit
+// state, and a simpler message schedule, which only rotates the mixed words by
+// one bit. Practical collisions have been found, so it should not be used for
+// signatures, but it is still needed where a protocol or format requires it,
+// like version 5 UUIDs.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::encoding::HEX;
+
+const ROUND_CONSTANTS: [u32; 4] = [0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xca62c1d6];
+
+#[derive(Clone)]
+pub struct Sha1 {
+    state: [u32; 5],
+    buffer: Vec<u8>,
+    length: u64,
+}
+
+impl Sha1 {
+    pub const BLOCK_SIZE: usize = 64;
+    pub const DIGEST_SIZE: usize = 20;
+
+    pub fn new() -> Self {
+        Self {
+            state: [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0],
+            buffer: Vec::with_capacity(Self::BLOCK_SIZE),
+            length: 0,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        let mut data = data;
+        if !self.buffer.is_empty() {
+            let missing = (Self::BLOCK_SIZE - self.buffer.len()).min(data.len());
+            self.buffer.extend_from_slice(&data[..missing]);
+            data = &data[missing..];
+            if self.buffer.len() < Self::BLOCK_SIZE {
+                return;
+            }
+            let block = std::mem::take(&mut self.buffer);
+            self.compress(&block);
+        }
+
+        let mut blocks = data.chunks_exact(Self::BLOCK_SIZE);
+        for block in &mut blocks {
+            self.compress(block);
+        }
+        self.buffer.extend_from_slice(blocks.remainder());
+    }
+
+    pub fn finalize(mut self) -> Vec<u8> {
+        let bits = self.length.wrapping_mul(8).to_be_bytes();
+        let mut padding = vec![0x80];
+        while (self.buffer.len() + padding.len()) % Self::BLOCK_SIZE != Self::BLOCK_SIZE - 8 {
+            padding.push(0x00);
+        }
+        padding.extend_from_slice(&bits);
+        self.update(&padding);
+
+        self.state
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect()
+    }
+
+    pub fn hexdigest(self) -> String {
+        HEX.encode(&self.finalize())
+    }
+
+    pub fn digest(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    fn compress(&mut self, block: &[u8]) {
+        let mut schedule: Vec<u32> = block
+            .chunks_exact(4)
+            .map(|word| u32::from_be_bytes([word[0], word[1], word[2], word[3]]))
+            .collect();
+        for i in 16..80 {
+            let word = schedule[i - 3] ^ schedule[i - 8] ^ schedule[i - 14] ^ schedule[i - 16];
+            schedule.push(word.rotate_left(1));
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = self.state;
+        for (i, word) in schedule.iter().enumerate() {
+            let f = match i / 20 {
+                0 => (b & c) | (!b & d),
+                2 => (b & c) | (b & d) | (c & d),
+                _ => b ^ c ^ d,
+            };
+            let temporary = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(ROUND_CONSTANTS[i / 20])
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temporary;
+        }
+
+        for (word, value) in self.state.iter_mut().zip([a, b, c, d, e]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+}
+
+impl Default for Sha1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    const VECTORS: [(&str, &str); 4] = [
+        ("abc", "a9993e364706816aba3e25717850c26c9cd0d89d"),
+        ("", "da39a3ee5e6b4b0d3255bfef95601890afd80709"),
+        (
+            "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            "84983e441c3bd26ebaae4aa1f95129e5e54670f1",
+        ),
+        (
+            "abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmn\
+             hijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+            "a49b2446a02c645bf419f995b67091253a04a259",
+        ),
+    ];
+
+    for (message, expected) in VECTORS {
+        let description = format!("SHA-1 of {:?} matches the NIST vector", message);
+        test!(
+            harness,
+            &description,
+            HEX.encode(&Sha1::digest(message.as_bytes())),
+            expected
+        );
+    }
+
+    let chunk = [b'a'; 999];
+    let mut hasher = Sha1::new();
+    let mut remaining = 1_000_000;
+    while remaining > 0 {
+        let size = remaining.min(chunk.len());
+        hasher.update(&chunk[..size]);
+        remaining -= size;
+    }
+    test!(
+        harness,
+        "streamed SHA-1 of a million 'a' characters matches the NIST vector",
+        hasher.hexdigest(),
+        "34aa973cd4c4daa4f61eeb2bdbad27316534016f"
+    );
+
+    let mut streamed = Sha1::new();
+    for byte in VECTORS[3].0.as_bytes() {
+        streamed.update(&[*byte]);
+    }
+    test!(
+        harness,
+        "streaming byte by byte gives the same digest as hashing in one go",
+        streamed.finalize(),
+        Sha1::digest(VECTORS[3].0.as_bytes())
+    );
+    test!(
+        harness,
+        "the digest size",
+        Sha1::digest(b"").len(),
+        Sha1::DIGEST_SIZE
+    );
+
+    note!(
+        harness,
+        "SHA-1 of \"abc\":   {}",
+        HEX.encode(&Sha1::digest(b"abc"))
+    );
+    note!(
+        harness,
+        "SHA-256 of \"abc\": {}\n",
+        HEX.encode(&crate::sha2::Sha256::digest(b"abc"))
+    );
+}
@@ -0,0 +1,372 @@
+// sha2.rs
+// The SHA-2 family of hash functions (SHA-224, SHA-256, SHA-384, and SHA-512)
+// as specified in FIPS 180-4. The four variants share a single Merkle-Damgård
+// core, which is generic over the word size: the 32-bit variants and the 64-bit
+// variants only differ in their round constants, rotation amounts, and the
+// number of rounds. Data can be hashed in one go, or streamed in pieces with
+// update and finalize.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::ops::{BitAnd, BitXor, Not};
+
+pub trait Word:
+    'static + Copy + BitAnd<Output = Self> + BitXor<Output = Self> + Not<Output = Self>
+{
+    const SIZE: usize;
+    const ROUND_CONSTANTS: &'static [Self];
+    // Big sigma 0, big sigma 1, small sigma 0, and small sigma 1, in this
+    // order. The last amount of the small sigmas is a shift, not a rotation.
+    const ROTATIONS: [[u32; 3]; 4];
+
+    fn add(self, other: Self) -> Self;
+    fn rotr(self, amount: u32) -> Self;
+    fn shr(self, amount: u32) -> Self;
+    fn read(bytes: &[u8]) -> Self;
+    fn write(self, output: &mut Vec<u8>);
+}
+
+macro_rules! impl_word {
+    ($type:ty, $constants:expr, $rotations:expr) => {
+        impl Word for $type {
+            const SIZE: usize = std::mem::size_of::<$type>();
+            const ROUND_CONSTANTS: &'static [Self] = &$constants;
+            const ROTATIONS: [[u32; 3]; 4] = $rotations;
+
+            fn add(self, other: Self) -> Self {
+                self.wrapping_add(other)
+            }
+
+            fn rotr(self, amount: u32) -> Self {
+                self.rotate_right(amount)
+            }
+
+            fn shr(self, amount: u32) -> Self {
+                self >> amount
+            }
+
+            fn read(bytes: &[u8]) -> Self {
+                let mut raw = [0; std::mem::size_of::<$type>()];
+                raw.copy_from_slice(&bytes[..Self::SIZE]);
+                Self::from_be_bytes(raw)
+            }
+
+            fn write(self, output: &mut Vec<u8>) {
+                output.extend_from_slice(&self.to_be_bytes());
+            }
+        }
+    };
+}
+
+#[rustfmt::skip]
+const ROUND_CONSTANTS_32: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+#[rustfmt::skip]
+const ROUND_CONSTANTS_64: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+impl_word!(
+    u32,
+    ROUND_CONSTANTS_32,
+    [[2, 13, 22], [6, 11, 25], [7, 18, 3], [17, 19, 10]]
+);
+impl_word!(
+    u64,
+    ROUND_CONSTANTS_64,
+    [[28, 34, 39], [14, 18, 41], [1, 8, 7], [19, 61, 6]]
+);
+
+#[derive(Clone)]
+pub struct Sha2<W: Word> {
+    state: [W; 8],
+    buffer: Vec<u8>,
+    length: u128,
+    digest_size: usize,
+}
+
+impl<W: Word> Sha2<W> {
+    pub const BLOCK_SIZE: usize = W::SIZE * 16;
+
+    pub fn with_state(state: [W; 8], digest_size: usize) -> Self {
+        Self {
+            state,
+            buffer: Vec::with_capacity(Self::BLOCK_SIZE),
+            length: 0,
+            digest_size,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.length += data.len() as u128;
+
+        let mut data = data;
+        if !self.buffer.is_empty() {
+            let missing = (Self::BLOCK_SIZE - self.buffer.len()).min(data.len());
+            self.buffer.extend_from_slice(&data[..missing]);
+            data = &data[missing..];
+            if self.buffer.len() < Self::BLOCK_SIZE {
+                return;
+            }
+            let block = std::mem::take(&mut self.buffer);
+            self.compress(&block);
+        }
+
+        let mut blocks = data.chunks_exact(Self::BLOCK_SIZE);
+        for block in &mut blocks {
+            self.compress(block);
+        }
+        self.buffer.extend_from_slice(blocks.remainder());
+    }
+
+    pub fn finalize(mut self) -> Vec<u8> {
+        // The message is padded with a single 1 bit, as many 0 bits as needed,
+        // and its length in bits, which takes two words.
+        let length_size = W::SIZE * 2;
+        let bits = (self.length * 8).to_be_bytes();
+        let mut padding = vec![0x80];
+        while (self.buffer.len() + padding.len()) % Self::BLOCK_SIZE
+            != Self::BLOCK_SIZE - length_size
+        {
+            padding.push(0x00);
+        }
+        padding.extend_from_slice(&bits[bits.len() - length_size..]);
+        self.update(&padding);
+
+        let mut digest = Vec::with_capacity(W::SIZE * 8);
+        for word in self.state {
+            word.write(&mut digest);
+        }
+        digest.truncate(self.digest_size);
+        digest
+    }
+
+    fn compress(&mut self, block: &[u8]) {
+        let [big0, big1, small0, small1] = W::ROTATIONS;
+        let rounds = W::ROUND_CONSTANTS.len();
+
+        let mut schedule: Vec<W> = block.chunks_exact(W::SIZE).map(W::read).collect();
+        for i in 16..rounds {
+            let w15 = schedule[i - 15];
+            let w2 = schedule[i - 2];
+            let s0 = w15.rotr(small0[0]) ^ w15.rotr(small0[1]) ^ w15.shr(small0[2]);
+            let s1 = w2.rotr(small1[0]) ^ w2.rotr(small1[1]) ^ w2.shr(small1[2]);
+            let word = schedule[i - 16].add(s0).add(schedule[i - 7]).add(s1);
+            schedule.push(word);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+        for (constant, word) in W::ROUND_CONSTANTS.iter().zip(&schedule) {
+            let s1 = e.rotr(big1[0]) ^ e.rotr(big1[1]) ^ e.rotr(big1[2]);
+            let choice = (e & f) ^ (!e & g);
+            let t1 = h.add(s1).add(choice).add(*constant).add(*word);
+            let s0 = a.rotr(big0[0]) ^ a.rotr(big0[1]) ^ a.rotr(big0[2]);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.add(majority);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.add(t2);
+        }
+
+        for (word, value) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.add(value);
+        }
+    }
+}
+
+macro_rules! sha2_variant {
+    ($name:ident, $word:ty, $digest_size:expr, $state:expr) => {
+        #[derive(Clone)]
+        pub struct $name(Sha2<$word>);
+
+        impl $name {
+            pub const BLOCK_SIZE: usize = Sha2::<$word>::BLOCK_SIZE;
+            pub const DIGEST_SIZE: usize = $digest_size;
+
+            pub fn new() -> Self {
+                Self(Sha2::with_state($state, $digest_size))
+            }
+
+            pub fn update(&mut self, data: &[u8]) {
+                self.0.update(data);
+            }
+
+            pub fn finalize(self) -> Vec<u8> {
+                self.0.finalize()
+            }
+
+            pub fn digest(data: &[u8]) -> Vec<u8> {
+                let mut hasher = Self::new();
+                hasher.update(data);
+                hasher.finalize()
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
+}
+
+#[rustfmt::skip]
+sha2_variant!(Sha224, u32, 28, [
+    0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939,
+    0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4,
+]);
+
+#[rustfmt::skip]
+sha2_variant!(Sha256, u32, 32, [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a,
+    0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+]);
+
+#[rustfmt::skip]
+sha2_variant!(Sha384, u64, 48, [
+    0xcbbb9d5dc1059ed8, 0x629a292a367cd507, 0x9159015a3070dd17, 0x152fecd8f70e5939,
+    0x67332667ffc00b31, 0x8eb44a8768581511, 0xdb0c2e0d64f98fa7, 0x47b5481dbefa4fa4,
+]);
+
+#[rustfmt::skip]
+sha2_variant!(Sha512, u64, 64, [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+]);
+
+// Demonstration
+
+use crate::harness::Harness;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn demo(harness: &mut Harness) {
+    const MESSAGES: [&str; 4] = [
+        "abc",
+        "",
+        "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+        "abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmn\
+         hijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+    ];
+
+    const SHA224: [&str; 4] = [
+        "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7",
+        "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f",
+        "75388b16512776cc5dba5da1fd890150b0c6455cb4f58b1952522525",
+        "c97ca9a559850ce97a04a96def6d99a9e0e0e2ab14e6b8df265fc0b3",
+    ];
+    const SHA256: [&str; 4] = [
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+        "cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1",
+    ];
+    const SHA384: [&str; 4] = [
+        "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed\
+         8086072ba1e7cc2358baeca134c825a7",
+        "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da\
+         274edebfe76f65fbd51ad2f14898b95b",
+        "3391fdddfc8dc7393707a65b1b4709397cf8b1d162af05abfe8f450de5f36bc6\
+         b0455a8520bc4e6f5fe95b1fe3c8452b",
+        "09330c33f71147e83d192fc782cd1b4753111b173b3b05d22fa08086e3b0f712\
+         fcc7c71a557e2db966c3e9fa91746039",
+    ];
+    const SHA512: [&str; 4] = [
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+         2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+        "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+         47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+        "204a8fc6dda82f0a0ced7beb8e08a41657c16ef468b228a8279be331a703c335\
+         96fd15c13b1b07f9aa1d3bea57789ca031ad85c7a71dd70354ec631238ca3445",
+        "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018\
+         501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909",
+    ];
+
+    for (i, message) in MESSAGES.iter().enumerate() {
+        let data = message.as_bytes();
+        let description = format!("SHA-224 of {:?} matches the NIST vector", message);
+        test!(harness, &description, hex(&Sha224::digest(data)), SHA224[i]);
+        let description = format!("SHA-256 of {:?} matches the NIST vector", message);
+        test!(harness, &description, hex(&Sha256::digest(data)), SHA256[i]);
+        let description = format!("SHA-384 of {:?} matches the NIST vector", message);
+        test!(harness, &description, hex(&Sha384::digest(data)), SHA384[i]);
+        let description = format!("SHA-512 of {:?} matches the NIST vector", message);
+        test!(harness, &description, hex(&Sha512::digest(data)), SHA512[i]);
+    }
+
+    // Streaming a million 'a' characters in uneven pieces exercises the
+    // buffering of partial blocks.
+    let chunk = [b'a'; 999];
+    let mut sha256 = Sha256::new();
+    let mut sha512 = Sha512::new();
+    let mut remaining = 1_000_000;
+    while remaining > 0 {
+        let size = remaining.min(chunk.len());
+        sha256.update(&chunk[..size]);
+        sha512.update(&chunk[..size]);
+        remaining -= size;
+    }
+    test!(
+        harness,
+        "streamed SHA-256 of a million 'a' characters matches the NIST vector",
+        hex(&sha256.finalize()),
+        "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+    );
+    test!(
+        harness,
+        "streamed SHA-512 of a million 'a' characters matches the NIST vector",
+        hex(&sha512.finalize()),
+        "e718483d0ce769644e2e42c7bc15b4638e1f98b13b2044285632a803afa973eb\
+         de0ff244877ea60a4cb0432ce577c31beb009c5c2c49aa2e4eadb217ad8cc09b"
+    );
+
+    let mut streamed = Sha384::new();
+    for byte in MESSAGES[3].as_bytes() {
+        streamed.update(&[*byte]);
+    }
+    test!(
+        harness,
+        "streaming byte by byte gives the same digest as hashing in one go",
+        streamed.finalize(),
+        Sha384::digest(MESSAGES[3].as_bytes())
+    );
+}
@@ -0,0 +1,346 @@
+// sha3.rs
+// The SHA-3 family of hash functions (SHA3-256 and SHA3-512) and the SHAKE128
+// and SHAKE256 extendable-output functions, as specified in FIPS 202. All of
+// them are built on the Keccak-f[1600] permutation using the sponge
+// construction. Unlike the Merkle-Damgård construction used by SHA-2, there is
+// no compression function, and no length padding: the input is absorbed by
+// XORing it into the first (rate) bytes of a large state, which is permuted
+// after every block, and the output is squeezed out of the same bytes. The
+// remaining (capacity) bytes are never touched directly, which is what makes
+// length extension attacks impossible, and allows an arbitrarily long output.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+#[rustfmt::skip]
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+// The rotation amounts of the rho step, and the lane order of the pi step, in
+// the order the lanes are visited by the combined rho and pi steps.
+#[rustfmt::skip]
+const ROTATIONS: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+#[rustfmt::skip]
+const LANES: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+pub fn keccak_f1600(state: &mut [u64; 25]) {
+    for round_constant in ROUND_CONSTANTS {
+        // Theta: each column is mixed with its two neighbouring columns.
+        let mut columns = [0u64; 5];
+        for (x, column) in columns.iter_mut().enumerate() {
+            *column = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+        }
+        for x in 0..5 {
+            let mix = columns[(x + 4) % 5] ^ columns[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[5 * y + x] ^= mix;
+            }
+        }
+
+        // Rho and pi: the lanes are rotated, and moved to a new position.
+        let mut carried = state[1];
+        for (lane, rotation) in LANES.iter().zip(ROTATIONS) {
+            let next = state[*lane];
+            state[*lane] = carried.rotate_left(rotation);
+            carried = next;
+        }
+
+        // Chi: the only non-linear step, applied to each row.
+        for y in 0..5 {
+            let row = [
+                state[5 * y],
+                state[5 * y + 1],
+                state[5 * y + 2],
+                state[5 * y + 3],
+                state[5 * y + 4],
+            ];
+            for x in 0..5 {
+                state[5 * y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+
+        // Iota: breaks the symmetry between the rounds.
+        state[0] ^= round_constant;
+    }
+}
+
+#[derive(Clone)]
+pub struct Sponge {
+    state: [u64; 25],
+    rate: usize,
+    position: usize,
+    suffix: u8,
+}
+
+impl Sponge {
+    // The suffix holds the domain separation bits, and the first bit of the
+    // padding, which differ between the fixed and extendable-output variants.
+    pub fn new(rate: usize, suffix: u8) -> Self {
+        Self {
+            state: [0; 25],
+            rate,
+            position: 0,
+            suffix,
+        }
+    }
+
+    fn xor_byte(&mut self, index: usize, byte: u8) {
+        self.state[index / 8] ^= (byte as u64) << (8 * (index % 8));
+    }
+
+    fn byte(&self, index: usize) -> u8 {
+        (self.state[index / 8] >> (8 * (index % 8))) as u8
+    }
+
+    pub fn absorb(&mut self, data: &[u8]) {
+        for byte in data {
+            self.xor_byte(self.position, *byte);
+            self.position += 1;
+            if self.position == self.rate {
+                keccak_f1600(&mut self.state);
+                self.position = 0;
+            }
+        }
+    }
+
+    pub fn pad(&mut self) {
+        self.xor_byte(self.position, self.suffix);
+        self.xor_byte(self.rate - 1, 0x80);
+        keccak_f1600(&mut self.state);
+        self.position = 0;
+    }
+
+    pub fn squeeze(&mut self, output: &mut [u8]) {
+        for byte in output {
+            if self.position == self.rate {
+                keccak_f1600(&mut self.state);
+                self.position = 0;
+            }
+            *byte = self.byte(self.position);
+            self.position += 1;
+        }
+    }
+}
+
+macro_rules! sha3_variant {
+    ($name:ident, $digest_size:expr) => {
+        #[derive(Clone)]
+        pub struct $name(Sponge);
+
+        impl $name {
+            // The capacity is twice the size of the digest.
+            pub const BLOCK_SIZE: usize = 200 - 2 * $digest_size;
+            pub const DIGEST_SIZE: usize = $digest_size;
+
+            pub fn new() -> Self {
+                Self(Sponge::new(Self::BLOCK_SIZE, 0x06))
+            }
+
+            pub fn update(&mut self, data: &[u8]) {
+                self.0.absorb(data);
+            }
+
+            pub fn finalize(mut self) -> Vec<u8> {
+                let mut digest = vec![0; Self::DIGEST_SIZE];
+                self.0.pad();
+                self.0.squeeze(&mut digest);
+                digest
+            }
+
+            pub fn digest(data: &[u8]) -> Vec<u8> {
+                let mut hasher = Self::new();
+                hasher.update(data);
+                hasher.finalize()
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
+}
+
+sha3_variant!(Sha3_256, 32);
+sha3_variant!(Sha3_512, 64);
+
+pub struct XofReader(Sponge);
+
+impl XofReader {
+    pub fn read(&mut self, output: &mut [u8]) {
+        self.0.squeeze(output);
+    }
+
+    pub fn read_vec(&mut self, length: usize) -> Vec<u8> {
+        let mut output = vec![0; length];
+        self.read(&mut output);
+        output
+    }
+}
+
+macro_rules! shake_variant {
+    ($name:ident, $security:expr) => {
+        #[derive(Clone)]
+        pub struct $name(Sponge);
+
+        impl $name {
+            pub const BLOCK_SIZE: usize = 200 - $security / 4;
+
+            pub fn new() -> Self {
+                Self(Sponge::new(Self::BLOCK_SIZE, 0x1f))
+            }
+
+            pub fn update(&mut self, data: &[u8]) {
+                self.0.absorb(data);
+            }
+
+            pub fn finalize_xof(mut self) -> XofReader {
+                self.0.pad();
+                XofReader(self.0)
+            }
+
+            pub fn digest(data: &[u8], length: usize) -> Vec<u8> {
+                let mut hasher = Self::new();
+                hasher.update(data);
+                hasher.finalize_xof().read_vec(length)
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
+}
+
+shake_variant!(Shake128, 128);
+shake_variant!(Shake256, 256);
+
+// Demonstration
+
+use crate::encoding::HEX;
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    const MESSAGES: [&str; 3] = [
+        "",
+        "abc",
+        "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+    ];
+    const SHA3_256: [&str; 3] = [
+        "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a",
+        "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+        "41c0dba2a9d6240849100376a8235e2c82e1b9998a999e21db32dd97496d3376",
+    ];
+    const SHA3_512: [&str; 3] = [
+        "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a6\
+         15b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26",
+        "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e\
+         10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0",
+        "04a371e84ecfb5b8b77cb48610fca8182dd457ce6f326a0fd3d7ec2f1e91636d\
+         ee691fbe0c985302ba1b0d8dc78c086346b533b49c030d99a27daf1139d6e75e",
+    ];
+    const SHAKE128: [&str; 3] = [
+        "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26",
+        "5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc8",
+        "1a96182b50fb8c7e74e0a707788f55e98209b8d91fade8f32f8dd5cff7bf21f5",
+    ];
+    const SHAKE256: [&str; 3] = [
+        "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f\
+         d75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be",
+        "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739\
+         d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4",
+        "4d8c2dd2435a0128eefbb8c36f6f87133a7911e18d979ee1ae6be5d4fd2e3329\
+         40d8688a4e6a59aa8060f1f9bc996c05aca3c696a8b66279dc672c740bb224ec",
+    ];
+
+    for (i, message) in MESSAGES.iter().enumerate() {
+        let data = message.as_bytes();
+        let description = format!("SHA3-256 of {:?} matches the known value", message);
+        test!(
+            harness,
+            &description,
+            HEX.encode(&Sha3_256::digest(data)),
+            SHA3_256[i]
+        );
+        let description = format!("SHA3-512 of {:?} matches the known value", message);
+        test!(
+            harness,
+            &description,
+            HEX.encode(&Sha3_512::digest(data)),
+            SHA3_512[i]
+        );
+        let description = format!("SHAKE128 of {:?} matches the known value", message);
+        test!(
+            harness,
+            &description,
+            HEX.encode(&Shake128::digest(data, 32)),
+            SHAKE128[i]
+        );
+        let description = format!("SHAKE256 of {:?} matches the known value", message);
+        test!(
+            harness,
+            &description,
+            HEX.encode(&Shake256::digest(data, 64)),
+            SHAKE256[i]
+        );
+    }
+
+    // A message longer than the rate needs more than one permutation while
+    // being absorbed, and an output longer than the rate needs more than one
+    // permutation while being squeezed.
+    let mut hasher = Sha3_256::new();
+    for _ in 0..200 {
+        hasher.update(b"a");
+    }
+    test!(
+        harness,
+        "SHA3-256 absorbed byte by byte across several blocks matches the known value",
+        HEX.encode(&hasher.finalize()),
+        "cce34485baf2bf2aca99b94833892a4f52896d3d153f7b840cc4f9fe695f1387"
+    );
+
+    let mut shake = Shake128::new();
+    shake.update(b"abc");
+    let mut reader = shake.finalize_xof();
+    let mut output = Vec::new();
+    for _ in 0..10 {
+        output.extend(reader.read_vec(20));
+    }
+    test!(
+        harness,
+        "SHAKE128 squeezed in pieces across several blocks matches the known value",
+        HEX.encode(&output[168..]),
+        "6aa01b3f5af057805f973ff8ecb8b226ac32ada6f01c1fcd4818cb006aa5b4cd"
+    );
+    test!(
+        harness,
+        "a shorter SHAKE output is a prefix of a longer one",
+        Shake256::digest(b"abc", 16),
+        Shake256::digest(b"abc", 64)[..16].to_vec()
+    );
+
+    note!(
+        harness,
+        "The sponge keeps a 200 byte state, of which the rate is:"
+    );
+    note!(harness, "  SHA3-256: {} bytes", Sha3_256::BLOCK_SIZE);
+    note!(harness, "  SHA3-512: {} bytes", Sha3_512::BLOCK_SIZE);
+    note!(harness, "  SHAKE128: {} bytes", Shake128::BLOCK_SIZE);
+    note!(harness, "  SHAKE256: {} bytes\n", Shake256::BLOCK_SIZE);
+}
@@ -0,0 +1,359 @@
+// sorting.rs
+// A showcase of sorting algorithms, behind a common signature, instrumented to
+// count the comparisons and the swaps they make, along with the moves, which
+// is how merge sort and radix sort rearrange the elements instead of swapping.
+// Insertion sort is quadratic, but linear on sorted input. Merge sort always
+// takes O(n log n) time, with a buffer of n elements. Quicksort partitions
+// around the median of the first, middle, and last elements, which avoids the
+// quadratic case on sorted and reversed input, and recurses only into the
+// smaller part, so its stack stays logarithmic. Heapsort works in place, in
+// O(n log n) time, but jumps around the memory. Radix sort does not compare at
+// all: it distributes the elements by each of their bytes, in four passes. The
+// demonstration also times them against each other, with the bench snippet.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Statistics {
+    pub comparisons: u64,
+    pub swaps: u64,
+    pub moves: u64,
+}
+
+impl Statistics {
+    fn less(&mut self, a: u32, b: u32) -> bool {
+        self.comparisons += 1;
+        a < b
+    }
+
+    fn swap(&mut self, items: &mut [u32], i: usize, j: usize) {
+        self.swaps += 1;
+        items.swap(i, j);
+    }
+}
+
+pub type Sort = fn(&mut [u32], &mut Statistics);
+
+pub fn insertion_sort(items: &mut [u32], statistics: &mut Statistics) {
+    for i in 1..items.len() {
+        let mut j = i;
+        while j > 0 && statistics.less(items[j], items[j - 1]) {
+            statistics.swap(items, j, j - 1);
+            j -= 1;
+        }
+    }
+}
+
+pub fn merge_sort(items: &mut [u32], statistics: &mut Statistics) {
+    // Bottom-up, merging runs of doubling widths back and forth between the
+    // items and the buffer.
+    let n = items.len();
+    let mut buffer = items.to_vec();
+    let mut width = 1;
+    let mut in_buffer = false;
+    while width < n {
+        let (source, target): (&[u32], &mut [u32]) = if in_buffer {
+            (&buffer, items)
+        } else {
+            (items, &mut buffer)
+        };
+        let mut start = 0;
+        while start < n {
+            let middle = (start + width).min(n);
+            let end = (start + 2 * width).min(n);
+            let (mut i, mut j) = (start, middle);
+            for slot in &mut target[start..end] {
+                // Taking from the left run on ties keeps the sort stable.
+                let take_left = j == end || (i < middle && !statistics.less(source[j], source[i]));
+                if take_left {
+                    *slot = source[i];
+                    i += 1;
+                } else {
+                    *slot = source[j];
+                    j += 1;
+                }
+                statistics.moves += 1;
+            }
+            start = end;
+        }
+        in_buffer = !in_buffer;
+        width *= 2;
+    }
+    if in_buffer {
+        items.copy_from_slice(&buffer);
+        statistics.moves += n as u64;
+    }
+}
+
+pub fn quick_sort(items: &mut [u32], statistics: &mut Statistics) {
+    let mut items = items;
+    while items.len() > 1 {
+        let split = partition(items, statistics);
+        let (left, right) = items.split_at_mut(split);
+        if left.len() < right.len() {
+            quick_sort(left, statistics);
+            items = right;
+        } else {
+            quick_sort(right, statistics);
+            items = left;
+        }
+    }
+}
+
+// Hoare's partition scheme around the median of three. Returns the length of
+// the left part, where each element is at most the pivot, and each element of
+// the right part is at least the pivot. Both parts are non-empty, as long as
+// the pivot is not the last element, so the middle is rounded down.
+fn partition(items: &mut [u32], statistics: &mut Statistics) -> usize {
+    let (first, middle, last) = (0, (items.len() - 1) / 2, items.len() - 1);
+    if statistics.less(items[middle], items[first]) {
+        statistics.swap(items, middle, first);
+    }
+    if statistics.less(items[last], items[middle]) {
+        statistics.swap(items, last, middle);
+        if statistics.less(items[middle], items[first]) {
+            statistics.swap(items, middle, first);
+        }
+    }
+    let pivot = items[middle];
+    let (mut i, mut j) = (0, items.len() - 1);
+    loop {
+        while statistics.less(items[i], pivot) {
+            i += 1;
+        }
+        while statistics.less(pivot, items[j]) {
+            j -= 1;
+        }
+        if i >= j {
+            return j + 1;
+        }
+        statistics.swap(items, i, j);
+        i += 1;
+        j -= 1;
+    }
+}
+
+pub fn heap_sort(items: &mut [u32], statistics: &mut Statistics) {
+    let sift_down = |items: &mut [u32], mut root: usize, statistics: &mut Statistics| loop {
+        let mut child = 2 * root + 1;
+        if child >= items.len() {
+            return;
+        }
+        if child + 1 < items.len() && statistics.less(items[child], items[child + 1]) {
+            child += 1;
+        }
+        if !statistics.less(items[root], items[child]) {
+            return;
+        }
+        statistics.swap(items, root, child);
+        root = child;
+    };
+    for root in (0..items.len() / 2).rev() {
+        sift_down(items, root, statistics);
+    }
+    for end in (1..items.len()).rev() {
+        statistics.swap(items, 0, end);
+        sift_down(&mut items[..end], 0, statistics);
+    }
+}
+
+// The least significant digit variant, with counting sort on each byte.
+pub fn radix_sort(items: &mut [u32], statistics: &mut Statistics) {
+    let mut buffer = vec![0; items.len()];
+    for shift in (0..32).step_by(8) {
+        let mut offsets = [0; 257];
+        for item in items.iter() {
+            offsets[((item >> shift) & 0xff) as usize + 1] += 1;
+        }
+        for digit in 0..256 {
+            offsets[digit + 1] += offsets[digit];
+        }
+        for item in items.iter() {
+            let digit = ((item >> shift) & 0xff) as usize;
+            buffer[offsets[digit]] = *item;
+            offsets[digit] += 1;
+        }
+        items.copy_from_slice(&buffer);
+        statistics.moves += 2 * items.len() as u64;
+    }
+}
+
+pub const ALGORITHMS: [(&str, Sort); 5] = [
+    ("insertion", insertion_sort),
+    ("merge", merge_sort),
+    ("quick", quick_sort),
+    ("heap", heap_sort),
+    ("radix", radix_sort),
+];
+
+pub fn sort_with(sort: Sort, items: &mut [u32]) -> Statistics {
+    let mut statistics = Statistics::default();
+    sort(items, &mut statistics);
+    statistics
+}
+
+// Demonstration
+
+use crate::bench::{compare, Bench};
+use crate::harness::Harness;
+use crate::rng::{RandomSource, SplitMix64};
+
+pub fn demo(harness: &mut Harness) {
+    const N: usize = 2000;
+    let mut random = SplitMix64::new(42);
+    let inputs: [(&str, Vec<u32>); 4] = [
+        ("random", (0..N).map(|_| random.next_u32()).collect()),
+        ("sorted", (0..N as u32).collect()),
+        ("reversed", (0..N as u32).rev().collect()),
+        (
+            "few unique",
+            (0..N).map(|_| random.next_u32() % 4).collect(),
+        ),
+    ];
+
+    let mut rows = Vec::new();
+    for (input_name, input) in &inputs {
+        let mut expected = input.clone();
+        expected.sort_unstable();
+        for (name, sort) in ALGORITHMS {
+            let mut items = input.clone();
+            let statistics = sort_with(sort, &mut items);
+            test!(
+                harness,
+                &format!("{} sort on {} input", name, input_name),
+                items == expected,
+                true
+            );
+            rows.push((*input_name, name, statistics));
+        }
+    }
+    let find = |input: &str, name: &str| {
+        rows.iter()
+            .find(|(i, n, _)| *i == input && *n == name)
+            .unwrap()
+            .2
+    };
+
+    let n = N as u64;
+    let n_log_n = n * (N as f64).log2().ceil() as u64;
+    test!(
+        harness,
+        "insertion sort is linear on sorted input",
+        find("sorted", "insertion"),
+        Statistics {
+            comparisons: n - 1,
+            swaps: 0,
+            moves: 0
+        }
+    );
+    test!(
+        harness,
+        "insertion sort swaps each pair of reversed input",
+        find("reversed", "insertion").swaps,
+        n * (n - 1) / 2
+    );
+    test!(
+        harness,
+        "merge sort makes at most n log n comparisons",
+        ["random", "sorted", "reversed", "few unique"]
+            .iter()
+            .all(|input| find(input, "merge").comparisons <= n_log_n),
+        true
+    );
+    test!(
+        harness,
+        "median of three keeps quicksort fast on sorted and reversed input",
+        find("sorted", "quick").comparisons < 2 * n_log_n
+            && find("reversed", "quick").comparisons < 2 * n_log_n,
+        true
+    );
+    test!(
+        harness,
+        "heapsort makes at most 2 n log n comparisons",
+        find("random", "heap").comparisons <= 2 * n_log_n,
+        true
+    );
+    test!(
+        harness,
+        "radix sort does not compare",
+        find("random", "radix"),
+        Statistics {
+            comparisons: 0,
+            swaps: 0,
+            moves: 8 * n
+        }
+    );
+    for (name, sort) in ALGORITHMS {
+        let mut empty: [u32; 0] = [];
+        let mut single = [7];
+        sort_with(sort, &mut empty);
+        sort_with(sort, &mut single);
+        test!(
+            harness,
+            &format!("{} sort on empty and single-element input", name),
+            single,
+            [7]
+        );
+    }
+
+    note!(
+        harness,
+        "{:<11} {:<10} {:>12} {:>10} {:>10}",
+        "input",
+        "algorithm",
+        "comparisons",
+        "swaps",
+        "moves"
+    );
+    for (input, name, statistics) in rows {
+        note!(
+            harness,
+            "{:<11} {:<10} {:>12} {:>10} {:>10}",
+            input,
+            name,
+            statistics.comparisons,
+            statistics.swaps,
+            statistics.moves
+        );
+    }
+    note!(harness);
+
+    if harness.is_quiet() {
+        return;
+    }
+    // The copying of the input is prepared outside of the measured time.
+    let bench = Bench::new().samples(15);
+    let random = &inputs[0].1;
+    let mut results: Vec<(&str, _)> = ALGORITHMS
+        .iter()
+        .map(|(name, sort)| {
+            let report = bench.run_with_input(
+                || random.clone(),
+                |mut items| {
+                    sort_with(*sort, &mut items);
+                    items
+                },
+            );
+            (*name, report)
+        })
+        .collect();
+    results.push((
+        "standard",
+        bench.run_with_input(
+            || random.clone(),
+            |mut items| {
+                items.sort_unstable();
+                items
+            },
+        ),
+    ));
+    note!(
+        harness,
+        "Time taken to sort {} random numbers:\n{}",
+        N,
+        compare(&results)
+    );
+}
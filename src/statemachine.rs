@@ -0,0 +1,538 @@
+// statemachine.rs
+// A table-driven finite state machine. The machine is in one of its states,
+// and an event moves it along the first transition of the table which starts
+// from the current state, is triggered by the event, and whose guard, if it
+// has one, allows it, given the context, which is the data the machine works
+// on. A transition can also start from any state, which is useful for the
+// events like a reset, which are handled the same way everywhere. While
+// moving, the exit action of the old state, the action of the transition,
+// and the entry action of the new state are run, in this order, even if the
+// transition leads back to the same state. An event which has no transition
+// from the current state is rejected, and the machine stays where it was.
+// The guards and the actions are plain functions, so the table can be built
+// by the transitions! macro, which reads like the diagram of the machine:
+// (Red, Timer) => Green, or with a guard and an action, (Idle, Coin)
+// [|c: &Till| c.coins < 10] / insert_coin => Ready. The closures in the table
+// need the type of the context spelled out, as it is not known yet, when the
+// table is built. The demonstration models a traffic light, and the
+// lifecycle of a TCP connection.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+
+pub type Guard<C> = fn(&C) -> bool;
+pub type Action<C> = fn(&mut C);
+
+pub struct Transition<S, E, C> {
+    pub from: Option<S>,
+    pub event: E,
+    pub to: S,
+    pub guard: Option<Guard<C>>,
+    pub action: Option<Action<C>>,
+}
+
+impl<S, E, C> Transition<S, E, C> {
+    pub fn new(from: S, event: E, to: S) -> Self {
+        Self {
+            from: Some(from),
+            event,
+            to,
+            guard: None,
+            action: None,
+        }
+    }
+
+    // A transition, which starts from every state.
+    pub fn any(event: E, to: S) -> Self {
+        Self {
+            from: None,
+            event,
+            to,
+            guard: None,
+            action: None,
+        }
+    }
+
+    pub fn guard(mut self, guard: Guard<C>) -> Self {
+        self.guard = Some(guard);
+        self
+    }
+
+    pub fn action(mut self, action: Action<C>) -> Self {
+        self.action = Some(action);
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransitionError<S, E> {
+    NoTransition { state: S, event: E },
+    Guarded { state: S, event: E },
+}
+
+impl<S: fmt::Debug, E: fmt::Debug> fmt::Display for TransitionError<S, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TransitionError::NoTransition { state, event } => {
+                write!(f, "no transition from {:?} on {:?}", state, event)
+            }
+            TransitionError::Guarded { state, event } => {
+                write!(f, "the guards reject {:?} in {:?}", event, state)
+            }
+        }
+    }
+}
+
+impl<S: fmt::Debug, E: fmt::Debug> std::error::Error for TransitionError<S, E> {}
+
+pub struct StateMachine<S, E, C> {
+    state: S,
+    context: C,
+    transitions: Vec<Transition<S, E, C>>,
+    entry: Vec<(S, Action<C>)>,
+    exit: Vec<(S, Action<C>)>,
+    history: Vec<(S, E, S)>,
+}
+
+impl<S: Copy + Eq, E: Copy + Eq, C> StateMachine<S, E, C> {
+    pub fn new(initial: S, context: C) -> Self {
+        Self {
+            state: initial,
+            context,
+            transitions: Vec::new(),
+            entry: Vec::new(),
+            exit: Vec::new(),
+            history: Vec::new(),
+        }
+    }
+
+    pub fn transitions(mut self, transitions: Vec<Transition<S, E, C>>) -> Self {
+        self.transitions.extend(transitions);
+        self
+    }
+
+    pub fn on_entry(mut self, state: S, action: Action<C>) -> Self {
+        self.entry.push((state, action));
+        self
+    }
+
+    pub fn on_exit(mut self, state: S, action: Action<C>) -> Self {
+        self.exit.push((state, action));
+        self
+    }
+
+    pub fn state(&self) -> S {
+        self.state
+    }
+
+    pub fn context(&self) -> &C {
+        &self.context
+    }
+
+    pub fn context_mut(&mut self) -> &mut C {
+        &mut self.context
+    }
+
+    pub fn into_context(self) -> C {
+        self.context
+    }
+
+    // The transitions taken so far, as the old state, the event, and the new
+    // state.
+    pub fn history(&self) -> &[(S, E, S)] {
+        &self.history
+    }
+
+    // The events which have a transition from the current state, whether
+    // their guards allow them or not.
+    pub fn events(&self) -> Vec<E> {
+        let mut events = Vec::new();
+        for transition in &self.transitions {
+            let from_here = transition.from.is_none_or(|from| from == self.state);
+            if from_here && !events.contains(&transition.event) {
+                events.push(transition.event);
+            }
+        }
+        events
+    }
+
+    pub fn can_fire(&self, event: E) -> bool {
+        self.find(event).is_ok()
+    }
+
+    fn find(&self, event: E) -> Result<usize, TransitionError<S, E>> {
+        let mut guarded = false;
+        for (index, transition) in self.transitions.iter().enumerate() {
+            if transition.event != event || transition.from.is_some_and(|from| from != self.state) {
+                continue;
+            }
+            match transition.guard {
+                Some(guard) if !guard(&self.context) => guarded = true,
+                _ => return Ok(index),
+            }
+        }
+        let state = self.state;
+        Err(match guarded {
+            true => TransitionError::Guarded { state, event },
+            false => TransitionError::NoTransition { state, event },
+        })
+    }
+
+    pub fn fire(&mut self, event: E) -> Result<S, TransitionError<S, E>> {
+        let index = self.find(event)?;
+        let (to, action) = (self.transitions[index].to, self.transitions[index].action);
+        let from = self.state;
+        for (state, exit) in &self.exit {
+            if *state == from {
+                exit(&mut self.context);
+            }
+        }
+        if let Some(action) = action {
+            action(&mut self.context);
+        }
+        self.state = to;
+        for (state, entry) in &self.entry {
+            if *state == to {
+                entry(&mut self.context);
+            }
+        }
+        self.history.push((from, event, to));
+        Ok(to)
+    }
+
+    // The events, one after the other, up to the first one, which is
+    // rejected.
+    pub fn run(&mut self, events: &[E]) -> Result<S, TransitionError<S, E>> {
+        for event in events {
+            self.fire(*event)?;
+        }
+        Ok(self.state)
+    }
+}
+
+// The table of a machine: (From, Event) => To for a transition from a state,
+// and (_, Event) => To for one from any state, each optionally with a guard
+// in brackets, and an action after a slash, before the arrow.
+#[macro_export]
+macro_rules! transitions {
+    (@collect [$($done:expr,)*]) => {
+        vec![$($done,)*]
+    };
+    (@collect [$($done:expr,)*]
+        (_, $event:path) $([$guard:expr])? $(/ $action:expr)? => $to:path $(, $($rest:tt)*)?
+    ) => {
+        $crate::transitions!(@collect [
+            $($done,)*
+            $crate::statemachine::Transition::any($event, $to)
+                $(.guard($guard))? $(.action($action))?,
+        ] $($($rest)*)?)
+    };
+    (@collect [$($done:expr,)*]
+        ($from:path, $event:path) $([$guard:expr])? $(/ $action:expr)? => $to:path $(, $($rest:tt)*)?
+    ) => {
+        $crate::transitions!(@collect [
+            $($done,)*
+            $crate::statemachine::Transition::new($from, $event, $to)
+                $(.guard($guard))? $(.action($action))?,
+        ] $($($rest)*)?)
+    };
+    ($($table:tt)*) => {
+        $crate::transitions!(@collect [] $($table)*)
+    };
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Light {
+    Red,
+    Green,
+    Yellow,
+    Flashing,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LightEvent {
+    Timer,
+    Fault,
+    Repair,
+}
+
+#[derive(Debug, Default)]
+struct Crossing {
+    cycles: u32,
+    technician: bool,
+    log: Vec<&'static str>,
+}
+
+fn traffic_light() -> StateMachine<Light, LightEvent, Crossing> {
+    StateMachine::new(Light::Red, Crossing::default())
+        .transitions(transitions! {
+            (Light::Red, LightEvent::Timer) => Light::Green,
+            (Light::Green, LightEvent::Timer) => Light::Yellow,
+            (Light::Yellow, LightEvent::Timer) => Light::Red,
+            (Light::Flashing, LightEvent::Repair)
+                [|crossing: &Crossing| crossing.technician]
+                / |crossing: &mut Crossing| crossing.log.push("repaired")
+                => Light::Red,
+            (_, LightEvent::Fault)
+                / |crossing: &mut Crossing| crossing.log.push("fault")
+                => Light::Flashing,
+        })
+        .on_entry(Light::Red, |crossing| crossing.cycles += 1)
+        .on_entry(Light::Flashing, |crossing| crossing.log.push("flashing on"))
+        .on_exit(Light::Flashing, |crossing| {
+            crossing.log.push("flashing off")
+        })
+}
+
+// The states of RFC 793, without the data transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tcp {
+    Closed,
+    Listen,
+    SynSent,
+    SynReceived,
+    Established,
+    FinWait1,
+    FinWait2,
+    Closing,
+    TimeWait,
+    CloseWait,
+    LastAck,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Segment {
+    PassiveOpen,
+    ActiveOpen,
+    Close,
+    Syn,
+    SynAck,
+    Ack,
+    Fin,
+    Rst,
+    Timeout,
+}
+
+// The segments the connection sent in response.
+#[derive(Debug, Default)]
+struct Connection {
+    sent: Vec<&'static str>,
+    established: u32,
+}
+
+fn send_syn(connection: &mut Connection) {
+    connection.sent.push("SYN");
+}
+
+fn send_syn_ack(connection: &mut Connection) {
+    connection.sent.push("SYN+ACK");
+}
+
+fn send_ack(connection: &mut Connection) {
+    connection.sent.push("ACK");
+}
+
+fn send_fin(connection: &mut Connection) {
+    connection.sent.push("FIN");
+}
+
+fn tcp_connection() -> StateMachine<Tcp, Segment, Connection> {
+    StateMachine::new(Tcp::Closed, Connection::default())
+        .transitions(transitions! {
+            (Tcp::Closed, Segment::PassiveOpen) => Tcp::Listen,
+            (Tcp::Closed, Segment::ActiveOpen) / send_syn => Tcp::SynSent,
+            (Tcp::Listen, Segment::Syn) / send_syn_ack => Tcp::SynReceived,
+            (Tcp::Listen, Segment::Close) => Tcp::Closed,
+            (Tcp::SynSent, Segment::SynAck) / send_ack => Tcp::Established,
+            (Tcp::SynSent, Segment::Syn) / send_syn_ack => Tcp::SynReceived,
+            (Tcp::SynSent, Segment::Close) => Tcp::Closed,
+            (Tcp::SynReceived, Segment::Ack) => Tcp::Established,
+            (Tcp::SynReceived, Segment::Close) / send_fin => Tcp::FinWait1,
+            (Tcp::Established, Segment::Close) / send_fin => Tcp::FinWait1,
+            (Tcp::Established, Segment::Fin) / send_ack => Tcp::CloseWait,
+            (Tcp::FinWait1, Segment::Ack) => Tcp::FinWait2,
+            (Tcp::FinWait1, Segment::Fin) / send_ack => Tcp::Closing,
+            (Tcp::FinWait2, Segment::Fin) / send_ack => Tcp::TimeWait,
+            (Tcp::Closing, Segment::Ack) => Tcp::TimeWait,
+            (Tcp::CloseWait, Segment::Close) / send_fin => Tcp::LastAck,
+            (Tcp::LastAck, Segment::Ack) => Tcp::Closed,
+            (Tcp::TimeWait, Segment::Timeout) => Tcp::Closed,
+            (_, Segment::Rst) => Tcp::Closed,
+        })
+        .on_entry(Tcp::Established, |connection| connection.established += 1)
+}
+
+pub fn demo(harness: &mut Harness) {
+    let mut light = traffic_light();
+    let timers = [LightEvent::Timer; 6];
+    test!(
+        harness,
+        "the light goes around twice",
+        (light.run(&timers), light.context().cycles),
+        (Ok(Light::Red), 2)
+    );
+    test!(
+        harness,
+        "a fault makes the light flash from any state",
+        (light.fire(LightEvent::Timer), light.fire(LightEvent::Fault)),
+        (Ok(Light::Green), Ok(Light::Flashing))
+    );
+    test!(
+        harness,
+        "the guard keeps the light flashing until the technician arrives",
+        light.fire(LightEvent::Repair),
+        Err(TransitionError::Guarded {
+            state: Light::Flashing,
+            event: LightEvent::Repair
+        })
+    );
+    light.context_mut().technician = true;
+    test!(
+        harness,
+        "the exit, the transition, and the entry actions run in order",
+        (light.fire(LightEvent::Repair), light.context().log.clone()),
+        (
+            Ok(Light::Red),
+            vec!["fault", "flashing on", "flashing off", "repaired"]
+        )
+    );
+    test!(
+        harness,
+        "an event without a transition is rejected, and the state kept",
+        (light.fire(LightEvent::Repair), light.state()),
+        (
+            Err(TransitionError::NoTransition {
+                state: Light::Red,
+                event: LightEvent::Repair
+            }),
+            Light::Red
+        )
+    );
+    test!(
+        harness,
+        "the history records every transition taken",
+        light.history().len(),
+        6 + 2 + 1
+    );
+
+    // The client opens, sends nothing, and closes first.
+    let mut client = tcp_connection();
+    let outcome = client.run(&[
+        Segment::ActiveOpen,
+        Segment::SynAck,
+        Segment::Close,
+        Segment::Ack,
+        Segment::Fin,
+        Segment::Timeout,
+    ]);
+    test!(
+        harness,
+        "the client goes through the three-way handshake and the active close",
+        (
+            outcome,
+            client
+                .history()
+                .iter()
+                .map(|(_, _, state)| *state)
+                .collect::<Vec<_>>(),
+            client.context().sent.clone()
+        ),
+        (
+            Ok(Tcp::Closed),
+            vec![
+                Tcp::SynSent,
+                Tcp::Established,
+                Tcp::FinWait1,
+                Tcp::FinWait2,
+                Tcp::TimeWait,
+                Tcp::Closed
+            ],
+            vec!["SYN", "ACK", "FIN", "ACK"]
+        )
+    );
+    let mut server = tcp_connection();
+    let outcome = server.run(&[
+        Segment::PassiveOpen,
+        Segment::Syn,
+        Segment::Ack,
+        Segment::Fin,
+        Segment::Close,
+        Segment::Ack,
+    ]);
+    test!(
+        harness,
+        "the server listens, accepts, and closes passively",
+        (
+            outcome,
+            server.context().sent.clone(),
+            server.context().established
+        ),
+        (Ok(Tcp::Closed), vec!["SYN+ACK", "ACK", "FIN"], 1)
+    );
+    let mut simultaneous = tcp_connection();
+    test!(
+        harness,
+        "simultaneous open and close meet in the middle",
+        simultaneous.run(&[
+            Segment::ActiveOpen,
+            Segment::Syn,
+            Segment::Ack,
+            Segment::Close,
+            Segment::Fin,
+            Segment::Ack,
+        ]),
+        Ok(Tcp::TimeWait)
+    );
+    let mut reset = tcp_connection();
+    test!(
+        harness,
+        "a reset closes the connection from any state, and running stops at an error",
+        (
+            reset.run(&[Segment::ActiveOpen, Segment::Rst]),
+            reset.run(&[Segment::Ack, Segment::PassiveOpen]),
+            reset.state()
+        ),
+        (
+            Ok(Tcp::Closed),
+            Err(TransitionError::NoTransition {
+                state: Tcp::Closed,
+                event: Segment::Ack
+            }),
+            Tcp::Closed
+        )
+    );
+    test!(
+        harness,
+        "the events allowed in a state are listed",
+        (
+            tcp_connection().events(),
+            simultaneous.can_fire(Segment::Timeout)
+        ),
+        (
+            vec![Segment::PassiveOpen, Segment::ActiveOpen, Segment::Rst],
+            true
+        )
+    );
+
+    let mut trace = String::new();
+    for (from, event, to) in client.history() {
+        trace += &format!("\n  {:?} --{:?}--> {:?}", from, event, to);
+    }
+    note!(harness, "The client connection:{}", trace);
+    note!(
+        harness,
+        "{}",
+        TransitionError::Guarded {
+            state: Light::Flashing,
+            event: LightEvent::Repair
+        }
+    );
+}
@@ -0,0 +1,270 @@
+// strsearch.rs
+// Substring search with the Knuth-Morris-Pratt and Boyer-Moore-Horspool
+// algorithms, compared to the naive approach, which tries every position, and
+// compares the needle from its start. Knuth-Morris-Pratt never steps back in
+// the haystack, as a precomputed failure table tells how much of the needle is
+// still matched after a mismatch. Boyer-Moore-Horspool compares the needle from
+// its end, and skips ahead based on the character of the haystack under the
+// needle's last position, which lets it skip most of the haystack for longer
+// needles.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+pub trait Searcher {
+    const NAME: &'static str;
+
+    // Returns the starting positions of every, possibly overlapping match, and
+    // the number of character comparisons made while searching.
+    fn search(haystack: &[u8], needle: &[u8]) -> (Vec<usize>, usize);
+
+    fn find_all(haystack: &[u8], needle: &[u8]) -> Vec<usize> {
+        Self::search(haystack, needle).0
+    }
+}
+
+// An empty needle matches at every position, including the end, just like
+// with the substring search of the standard library.
+fn empty_matches(haystack: &[u8]) -> (Vec<usize>, usize) {
+    ((0..=haystack.len()).collect(), 0)
+}
+
+pub struct Naive;
+
+impl Searcher for Naive {
+    const NAME: &'static str = "naive";
+
+    fn search(haystack: &[u8], needle: &[u8]) -> (Vec<usize>, usize) {
+        if needle.is_empty() {
+            return empty_matches(haystack);
+        }
+
+        let mut matches = Vec::new();
+        let mut comparisons = 0;
+        for (position, window) in haystack.windows(needle.len()).enumerate() {
+            let mut matched = true;
+            for (a, b) in window.iter().zip(needle) {
+                comparisons += 1;
+                if a != b {
+                    matched = false;
+                    break;
+                }
+            }
+            if matched {
+                matches.push(position);
+            }
+        }
+        (matches, comparisons)
+    }
+}
+
+pub struct KnuthMorrisPratt;
+
+impl KnuthMorrisPratt {
+    // The length of the longest proper prefix of the needle, which is also a
+    // suffix of its first i + 1 characters, for every i.
+    pub fn failure_table(needle: &[u8]) -> Vec<usize> {
+        let mut table = vec![0; needle.len()];
+        let mut length = 0;
+        for i in 1..needle.len() {
+            while length > 0 && needle[i] != needle[length] {
+                length = table[length - 1];
+            }
+            if needle[i] == needle[length] {
+                length += 1;
+            }
+            table[i] = length;
+        }
+        table
+    }
+}
+
+impl Searcher for KnuthMorrisPratt {
+    const NAME: &'static str = "Knuth-Morris-Pratt";
+
+    fn search(haystack: &[u8], needle: &[u8]) -> (Vec<usize>, usize) {
+        if needle.is_empty() {
+            return empty_matches(haystack);
+        }
+
+        let table = Self::failure_table(needle);
+        let mut matches = Vec::new();
+        let mut comparisons = 0;
+        let mut matched = 0;
+        for (i, c) in haystack.iter().enumerate() {
+            loop {
+                comparisons += 1;
+                if *c == needle[matched] {
+                    matched += 1;
+                    break;
+                }
+                if matched == 0 {
+                    break;
+                }
+                matched = table[matched - 1];
+            }
+            if matched == needle.len() {
+                matches.push(i + 1 - matched);
+                matched = table[matched - 1];
+            }
+        }
+        (matches, comparisons)
+    }
+}
+
+pub struct BoyerMooreHorspool;
+
+impl BoyerMooreHorspool {
+    // How far the needle can be shifted, if the given character of the
+    // haystack is under its last position.
+    pub fn shift_table(needle: &[u8]) -> [usize; 256] {
+        let mut table = [needle.len(); 256];
+        for (i, c) in needle
+            .iter()
+            .enumerate()
+            .take(needle.len().saturating_sub(1))
+        {
+            table[*c as usize] = needle.len() - 1 - i;
+        }
+        table
+    }
+}
+
+impl Searcher for BoyerMooreHorspool {
+    const NAME: &'static str = "Boyer-Moore-Horspool";
+
+    fn search(haystack: &[u8], needle: &[u8]) -> (Vec<usize>, usize) {
+        if needle.is_empty() {
+            return empty_matches(haystack);
+        }
+
+        let table = Self::shift_table(needle);
+        let last = needle.len() - 1;
+        let mut matches = Vec::new();
+        let mut comparisons = 0;
+        let mut position = 0;
+        while position + needle.len() <= haystack.len() {
+            let window = &haystack[position..position + needle.len()];
+            let mut matched = true;
+            for (a, b) in window.iter().zip(needle).rev() {
+                comparisons += 1;
+                if a != b {
+                    matched = false;
+                    break;
+                }
+            }
+            if matched {
+                matches.push(position);
+            }
+            position += table[window[last] as usize];
+        }
+        (matches, comparisons)
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+fn compare(harness: &mut Harness, haystack: &[u8], needle: &[u8], expected: &[usize]) {
+    let results = [
+        (Naive::NAME, Naive::search(haystack, needle)),
+        (
+            KnuthMorrisPratt::NAME,
+            KnuthMorrisPratt::search(haystack, needle),
+        ),
+        (
+            BoyerMooreHorspool::NAME,
+            BoyerMooreHorspool::search(haystack, needle),
+        ),
+    ];
+
+    note!(
+        harness,
+        "Searching for {:?} in {} characters:",
+        String::from_utf8_lossy(needle),
+        haystack.len()
+    );
+    for (name, (matches, comparisons)) in &results {
+        note!(harness, "  {:<22} {:>6} comparisons", name, comparisons);
+        let description = format!(
+            "{} search finds {:?} at {:?}",
+            name,
+            String::from_utf8_lossy(needle),
+            expected
+        );
+        test!(harness, &description, matches.clone(), expected.to_vec());
+    }
+    note!(harness);
+}
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "the failure table of \"abacabab\" is correct",
+        KnuthMorrisPratt::failure_table(b"abacabab"),
+        vec![0, 0, 1, 0, 1, 2, 3, 2]
+    );
+    let table = BoyerMooreHorspool::shift_table(b"needle");
+    test!(
+        harness,
+        "the shift table of \"needle\" is correct",
+        (
+            table[b'n' as usize],
+            table[b'e' as usize],
+            table[b'd' as usize],
+            table[b'x' as usize]
+        ),
+        (5, 3, 2, 6)
+    );
+    test!(
+        harness,
+        "the shift table of the empty needle",
+        BoyerMooreHorspool::shift_table(b"") == [0; 256],
+        true
+    );
+
+    let text = b"It was the best of times, it was the worst of times, it was the \
+                 age of wisdom, it was the age of foolishness, it was the epoch of \
+                 belief, it was the epoch of incredulity, it was the season of Light";
+    compare(harness, text, b"it was the", &[26, 53, 79, 110, 138, 171]);
+    compare(harness, text, b"incredulity", &[158]);
+    compare(harness, text, b"darkness", &[]);
+    compare(harness, b"abababababa", b"aba", &[0, 2, 4, 6, 8]);
+    compare(harness, b"abc", b"", &[0, 1, 2, 3]);
+    compare(harness, b"ab", b"abc", &[]);
+
+    // The worst case of the naive search, where almost every position is an
+    // almost complete match, is handled in linear time by Knuth-Morris-Pratt.
+    let haystack = [b'a'; 1000];
+    let needle = [&[b'a'; 19][..], b"b"].concat();
+    let (_, naive) = Naive::search(&haystack, &needle);
+    let (_, kmp) = KnuthMorrisPratt::search(&haystack, &needle);
+    compare(harness, &haystack, &needle, &[]);
+    test!(
+        harness,
+        "Knuth-Morris-Pratt needs fewer comparisons in the worst case of naive search",
+        kmp < naive / 5,
+        true
+    );
+
+    let (_, naive) = Naive::search(text, b"incredulity");
+    let (_, horspool) = BoyerMooreHorspool::search(text, b"incredulity");
+    test!(
+        harness,
+        "Boyer-Moore-Horspool needs fewer comparisons for a long needle in text",
+        horspool < naive / 2,
+        true
+    );
+
+    let text = String::from_utf8_lossy(text);
+    let expected: Vec<usize> = text.match_indices("of").map(|(i, _)| i).collect();
+    test!(
+        harness,
+        "the matches agree with the standard library",
+        KnuthMorrisPratt::find_all(text.as_bytes(), b"of"),
+        expected
+    );
+}
@@ -0,0 +1,389 @@
+// sync.rs
+// Two synchronization primitives, built on atomics: a spinlock, and a cell
+// which is initialized once, lazily, by the first thread which needs it. The
+// spinlock takes its flag with an atomic swap, a test-and-set, and when the
+// flag is taken already, it backs off: it spins for a doubling number of
+// rounds, watching the flag with plain loads, which keep the cache line shared,
+// instead of swapping it back and forth between the cores, and after a while it
+// yields the thread. The once-cell has three states: empty, running, and ready.
+// The thread which moves it from empty to running computes the value, and the
+// others wait until it is ready, or empty again, if the initializer panicked.
+// The comments on the orderings describe the interleavings each of them rules
+// out, in the way a model checker like loom would explore them.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::cell::UnsafeCell;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+
+// Spins for a doubling number of rounds, then yields the thread instead.
+pub struct Backoff {
+    step: u32,
+}
+
+impl Backoff {
+    const SPIN_LIMIT: u32 = 6;
+
+    pub fn new() -> Self {
+        Self { step: 0 }
+    }
+
+    pub fn snooze(&mut self) {
+        if self.step <= Self::SPIN_LIMIT {
+            for _ in 0..1 << self.step {
+                std::hint::spin_loop();
+            }
+            self.step += 1;
+        } else {
+            std::thread::yield_now();
+        }
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct SpinLock<T> {
+    locked: AtomicBool,
+    contended: AtomicUsize,
+    value: UnsafeCell<T>,
+}
+
+// The lock hands the value from one thread to another, so it needs to be Send,
+// but the threads never reach it at the same time, so it need not be Sync.
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+// The guard hands out references to the value, so sharing the guard between
+// threads shares the value too: the marker makes it Sync only if T is Sync,
+// which the reference to the lock alone would not.
+pub struct SpinLockGuard<'a, T> {
+    lock: &'a SpinLock<T>,
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<T> SpinLock<T> {
+    pub const fn new(value: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            contended: AtomicUsize::new(0),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    // Acquire pairs with the Release of the unlock: everything the previous
+    // owner wrote before unlocking is visible to the next one after locking.
+    // With Relaxed instead, a thread could take the lock, and still read the
+    // value as it was before the previous owner changed it.
+    pub fn lock(&self) -> SpinLockGuard<'_, T> {
+        if let Some(guard) = self.try_lock() {
+            return guard;
+        }
+        self.contended.fetch_add(1, Ordering::Relaxed);
+        let mut backoff = Backoff::new();
+        loop {
+            // The loads can be relaxed, as they only decide when to try again.
+            while self.locked.load(Ordering::Relaxed) {
+                backoff.snooze();
+            }
+            if let Some(guard) = self.try_lock() {
+                return guard;
+            }
+        }
+    }
+
+    pub fn try_lock(&self) -> Option<SpinLockGuard<'_, T>> {
+        if self.locked.swap(true, Ordering::Acquire) {
+            None
+        } else {
+            Some(SpinLockGuard {
+                lock: self,
+                _marker: PhantomData,
+            })
+        }
+    }
+
+    // The number of times the lock was already taken when locking it.
+    pub fn contended(&self) -> usize {
+        self.contended.load(Ordering::Relaxed)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T> Deref for SpinLockGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T> DerefMut for SpinLockGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T> Drop for SpinLockGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.locked.store(false, Ordering::Release);
+    }
+}
+
+const EMPTY: u8 = 0;
+const RUNNING: u8 = 1;
+const READY: u8 = 2;
+
+pub struct OnceCell<T> {
+    state: AtomicU8,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// The value is created on one thread, and shared with the others.
+unsafe impl<T: Send + Sync> Sync for OnceCell<T> {}
+unsafe impl<T: Send> Send for OnceCell<T> {}
+
+// Puts the cell back to empty, if the initializer unwinds.
+struct Reset<'a> {
+    state: &'a AtomicU8,
+}
+
+impl Drop for Reset<'_> {
+    fn drop(&mut self) {
+        self.state.store(EMPTY, Ordering::Release);
+    }
+}
+
+impl<T> OnceCell<T> {
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(EMPTY),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    // Acquire pairs with the Release which published the value: a thread which
+    // sees the cell ready, but reads the value with Relaxed, could see it only
+    // partially written, or not at all.
+    pub fn get(&self) -> Option<&T> {
+        if self.state.load(Ordering::Acquire) == READY {
+            Some(unsafe { (*self.value.get()).assume_init_ref() })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_or_init(&self, initialize: impl FnOnce() -> T) -> &T {
+        let mut initialize = Some(initialize);
+        let mut backoff = Backoff::new();
+        loop {
+            // Only one thread wins the exchange from empty to running, so the
+            // value is never written twice. A losing thread either sees the
+            // cell ready, or waits while another one is running.
+            match self
+                .state
+                .compare_exchange(EMPTY, RUNNING, Ordering::Acquire, Ordering::Acquire)
+            {
+                Ok(_) => {
+                    let reset = Reset { state: &self.state };
+                    let value = (initialize.take().unwrap())();
+                    std::mem::forget(reset);
+                    unsafe { (*self.value.get()).write(value) };
+                    self.state.store(READY, Ordering::Release);
+                }
+                Err(READY) => {}
+                Err(_) => {
+                    backoff.snooze();
+                    continue;
+                }
+            }
+            return self.get().unwrap();
+        }
+    }
+
+    // Gives the value back, if the cell was already initialized.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let mut value = Some(value);
+        self.get_or_init(|| value.take().unwrap());
+        match value {
+            None => Ok(()),
+            Some(value) => Err(value),
+        }
+    }
+
+    pub fn into_inner(mut self) -> Option<T> {
+        if *self.state.get_mut() == READY {
+            *self.state.get_mut() = EMPTY;
+            Some(unsafe { (*self.value.get()).assume_init_read() })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Default for OnceCell<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for OnceCell<T> {
+    fn drop(&mut self) {
+        if *self.state.get_mut() == READY {
+            unsafe { self.value.get_mut().assume_init_drop() };
+        }
+    }
+}
+
+// Demonstration
+
+use crate::bench::{format_time, Stopwatch};
+use crate::harness::Harness;
+use std::sync::{Arc, Barrier, Mutex};
+use std::thread;
+
+// Runs the increment on the threads, all at once, and times them.
+fn contend(threads: usize, increments: usize, increment: impl Fn() + Sync) -> std::time::Duration {
+    let barrier = Barrier::new(threads);
+    let stopwatch = Stopwatch::new();
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                barrier.wait();
+                for _ in 0..increments {
+                    increment();
+                }
+            });
+        }
+    });
+    stopwatch.elapsed()
+}
+
+pub fn demo(harness: &mut Harness) {
+    let lock = SpinLock::new(vec![1, 2]);
+    {
+        let mut guard = lock.lock();
+        guard.push(3);
+        test!(
+            harness,
+            "a locked spinlock can not be locked again",
+            lock.try_lock().is_none(),
+            true
+        );
+    }
+    test!(
+        harness,
+        "the guard unlocks when it is dropped",
+        lock.try_lock().map(|guard| guard.clone()),
+        Some(vec![1, 2, 3])
+    );
+    test!(
+        harness,
+        "the value can be taken out of the lock",
+        lock.into_inner(),
+        [1, 2, 3]
+    );
+
+    const THREADS: usize = 4;
+    const INCREMENTS: usize = 20000;
+    let counter = SpinLock::new(0usize);
+    let spin_time = contend(THREADS, INCREMENTS, || *counter.lock() += 1);
+    test!(
+        harness,
+        "no increment is lost under contention",
+        *counter.lock(),
+        THREADS * INCREMENTS
+    );
+    let mutex = Mutex::new(0usize);
+    let mutex_time = contend(THREADS, INCREMENTS, || *mutex.lock().unwrap() += 1);
+    note!(
+        harness,
+        "{} threads, {} increments each: spinlock {} ({} contended), mutex {}\n",
+        THREADS,
+        INCREMENTS,
+        format_time(spin_time),
+        counter.contended(),
+        format_time(mutex_time)
+    );
+
+    let cell = OnceCell::new();
+    test!(harness, "a new cell is empty", cell.get(), None);
+    test!(
+        harness,
+        "the first initializer sets the value",
+        cell.get_or_init(|| "first"),
+        &"first"
+    );
+    test!(
+        harness,
+        "later initializers are not called",
+        (cell.get_or_init(|| unreachable!()), cell.set("second")),
+        (&"first", Err("second"))
+    );
+
+    // Many threads race to initialize the cell, and only one of them wins.
+    let cell = Arc::new(OnceCell::new());
+    let calls = Arc::new(AtomicUsize::new(0));
+    let barrier = Arc::new(Barrier::new(8));
+    let threads: Vec<_> = (0..8)
+        .map(|index| {
+            let (cell, calls, barrier) = (cell.clone(), calls.clone(), barrier.clone());
+            thread::spawn(move || {
+                barrier.wait();
+                *cell.get_or_init(|| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    thread::sleep(std::time::Duration::from_millis(5));
+                    index
+                })
+            })
+        })
+        .collect();
+    let seen: Vec<usize> = threads
+        .into_iter()
+        .map(|thread| thread.join().unwrap())
+        .collect();
+    test!(
+        harness,
+        "racing threads run the initializer once, and all see its value",
+        (
+            calls.load(Ordering::SeqCst),
+            seen.iter().all(|value| *value == seen[0])
+        ),
+        (1, true)
+    );
+
+    // The default hook would print the panic, which is expected here.
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let cell = OnceCell::new();
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        cell.get_or_init(|| panic!("initializer failed"));
+    }));
+    std::panic::set_hook(hook);
+    test!(
+        harness,
+        "a panicking initializer leaves the cell empty, for another try",
+        (panicked.is_err(), cell.get(), cell.get_or_init(|| 7)),
+        (true, None, &7)
+    );
+
+    let cell = OnceCell::new();
+    cell.set(String::from("owned")).unwrap();
+    test!(
+        harness,
+        "the value can be taken out of the cell",
+        cell.into_inner(),
+        Some(String::from("owned"))
+    );
+}
@@ -0,0 +1,357 @@
+// table.rs
+// A pretty-printer for tables of strings, with ASCII or Unicode box-drawing
+// borders. Each column is as wide as its widest cell, measured in terminal
+// columns with the leftpad snippet, so wide and combining characters line up,
+// and the cells are padded to the left, to the right, or on both sides,
+// depending on the alignment of their column. A column can have a maximum
+// width, where longer lines are either truncated with an ellipsis, or wrapped
+// at spaces with the wordwrap snippet, onto more lines of the same row. Cells
+// can also span more lines by containing line breaks.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::leftpad::{char_width, display_width, Pad};
+use crate::wordwrap::wrap;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Border {
+    Ascii,
+    #[default]
+    Unicode,
+}
+
+// The vertical bar, the horizontal bar, and the left, middle, and right
+// corners of the top, separator, and bottom lines.
+struct BorderChars {
+    vertical: char,
+    horizontal: char,
+    top: [char; 3],
+    middle: [char; 3],
+    bottom: [char; 3],
+}
+
+impl Border {
+    fn chars(self) -> BorderChars {
+        match self {
+            Border::Ascii => BorderChars {
+                vertical: '|',
+                horizontal: '-',
+                top: ['+', '+', '+'],
+                middle: ['+', '+', '+'],
+                bottom: ['+', '+', '+'],
+            },
+            Border::Unicode => BorderChars {
+                vertical: '│',
+                horizontal: '─',
+                top: ['┌', '┬', '┐'],
+                middle: ['├', '┼', '┤'],
+                bottom: ['└', '┴', '┘'],
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Column {
+    alignment: Alignment,
+    max_width: Option<usize>,
+    wrap: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Table {
+    header: Option<Vec<String>>,
+    rows: Vec<Vec<String>>,
+    columns: Vec<Column>,
+    border: Border,
+}
+
+// Shortens the line to the width, replacing the end with an ellipsis.
+pub fn truncate(line: &str, width: usize) -> String {
+    if display_width(line) <= width {
+        return line.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in line.chars() {
+        if used + char_width(c) + 1 > width {
+            break;
+        }
+        used += char_width(c);
+        out.push(c);
+    }
+    if width > 0 {
+        out.push('…');
+    }
+    out
+}
+
+impl Table {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn header(mut self, cells: &[&str]) -> Self {
+        self.header = Some(cells.iter().map(|cell| cell.to_string()).collect());
+        self
+    }
+
+    pub fn row<T: AsRef<str>>(mut self, cells: &[T]) -> Self {
+        self.push(cells);
+        self
+    }
+
+    pub fn push<T: AsRef<str>>(&mut self, cells: &[T]) {
+        let cells = cells.iter().map(|cell| cell.as_ref().to_string()).collect();
+        self.rows.push(cells);
+    }
+
+    pub fn border(mut self, border: Border) -> Self {
+        self.border = border;
+        self
+    }
+
+    pub fn align(mut self, column: usize, alignment: Alignment) -> Self {
+        self.column(column).alignment = alignment;
+        self
+    }
+
+    // Longer lines of the column are truncated, unless the column wraps.
+    pub fn max_width(mut self, column: usize, width: usize) -> Self {
+        self.column(column).max_width = Some(width);
+        self
+    }
+
+    pub fn wrap(mut self, column: usize) -> Self {
+        self.column(column).wrap = true;
+        self
+    }
+
+    fn column(&mut self, index: usize) -> &mut Column {
+        if self.columns.len() <= index {
+            self.columns.resize(index + 1, Column::default());
+        }
+        &mut self.columns[index]
+    }
+
+    fn all_rows(&self) -> impl Iterator<Item = &Vec<String>> {
+        self.header.iter().chain(&self.rows)
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        let count = self.all_rows().map(Vec::len).max().unwrap_or(0);
+        (0..count)
+            .map(|index| {
+                let natural = self
+                    .all_rows()
+                    .filter_map(|row| row.get(index))
+                    .flat_map(|cell| cell.lines())
+                    .map(display_width)
+                    .max()
+                    .unwrap_or(0);
+                let column = self.columns.get(index).copied().unwrap_or_default();
+                column.max_width.map_or(natural, |max| natural.min(max))
+            })
+            .collect()
+    }
+
+    fn render_row(&self, row: &[String], widths: &[usize], out: &mut String) {
+        let chars = self.border.chars();
+        let cells: Vec<Vec<String>> = widths
+            .iter()
+            .enumerate()
+            .map(|(index, width)| {
+                let column = self.columns.get(index).copied().unwrap_or_default();
+                let cell = row.get(index).map_or("", String::as_str);
+                let mut lines = Vec::new();
+                for line in cell.split('\n') {
+                    if column.wrap {
+                        lines.extend(wrap(line, *width));
+                    } else {
+                        lines.push(truncate(line, *width));
+                    }
+                }
+                lines
+            })
+            .collect();
+        let height = cells.iter().map(Vec::len).max().unwrap_or(1);
+        for line in 0..height {
+            out.push(chars.vertical);
+            for (index, width) in widths.iter().enumerate() {
+                let text = cells[index].get(line).map_or("", String::as_str);
+                let length = *width as isize;
+                let alignment = self.columns.get(index).map(|c| c.alignment);
+                let padded = match alignment.unwrap_or_default() {
+                    Alignment::Left => text.rightpad_display(length, ' '),
+                    Alignment::Right => text.leftpad_display(length, ' '),
+                    Alignment::Center => text.centerpad_display(length, ' '),
+                };
+                out.push_str(&format!(" {} {}", padded, chars.vertical));
+            }
+            out.push('\n');
+        }
+    }
+
+    fn render_line(&self, widths: &[usize], corners: [char; 3], out: &mut String) {
+        let horizontal = self.border.chars().horizontal.to_string();
+        let segments: Vec<String> = widths
+            .iter()
+            .map(|width| horizontal.repeat(width + 2))
+            .collect();
+        let middle = corners[1].to_string();
+        out.push_str(&format!(
+            "{}{}{}\n",
+            corners[0],
+            segments.join(&middle),
+            corners[2]
+        ));
+    }
+
+    pub fn render(&self) -> String {
+        let widths = self.widths();
+        let chars = self.border.chars();
+        let mut out = String::new();
+        self.render_line(&widths, chars.top, &mut out);
+        if let Some(header) = &self.header {
+            self.render_row(header, &widths, &mut out);
+            self.render_line(&widths, chars.middle, &mut out);
+        }
+        for row in &self.rows {
+            self.render_row(row, &widths, &mut out);
+        }
+        self.render_line(&widths, chars.bottom, &mut out);
+        out
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render())
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+#[rustfmt::skip]
+const PLANETS: [[&str; 4]; 8] = [
+    ["Mercury", "0.39", "0", "The smallest planet, and the closest to the Sun"],
+    ["Venus", "0.72", "0", "The hottest planet, with a thick atmosphere of carbon dioxide"],
+    ["Earth", "1.00", "1", "The only planet known to harbour life"],
+    ["Mars", "1.52", "2", "Named after the Roman god of war"],
+    ["Jupiter", "5.20", "95", "The largest planet, a gas giant"],
+    ["Saturn", "9.54", "274", "Known for its prominent ring system"],
+    ["Uranus", "19.19", "28", "Rotates on its side"],
+    ["Neptune", "30.07", "16", "The windiest planet"],
+];
+
+pub fn demo(harness: &mut Harness) {
+    let small = Table::new()
+        .header(&["name", "n"])
+        .row(&["one", "1"])
+        .row(&["twelve", "12"])
+        .align(1, Alignment::Right);
+    test!(
+        harness,
+        "a table with Unicode borders",
+        small.render(),
+        "┌────────┬────┐\n\
+         │ name   │  n │\n\
+         ├────────┼────┤\n\
+         │ one    │  1 │\n\
+         │ twelve │ 12 │\n\
+         └────────┴────┘\n"
+    );
+    test!(
+        harness,
+        "a table with ASCII borders, and centered cells",
+        small
+            .clone()
+            .border(Border::Ascii)
+            .align(0, Alignment::Center)
+            .render(),
+        "+--------+----+\n\
+         |  name  |  n |\n\
+         +--------+----+\n\
+         |  one   |  1 |\n\
+         | twelve | 12 |\n\
+         +--------+----+\n"
+    );
+    test!(
+        harness,
+        "truncating with an ellipsis",
+        Table::new().row(&["abcdefgh"]).max_width(0, 5).render(),
+        "┌───────┐\n│ abcd… │\n└───────┘\n"
+    );
+    test!(
+        harness,
+        "wrapping at spaces, and inside long words",
+        Table::new()
+            .row(&["to be or not", "abcdefgh"])
+            .max_width(0, 5)
+            .max_width(1, 3)
+            .wrap(0)
+            .wrap(1)
+            .render(),
+        "┌───────┬─────┐\n\
+         │ to be │ abc │\n\
+         │ or    │ def │\n\
+         │ not   │ gh  │\n\
+         └───────┴─────┘\n"
+    );
+    test!(
+        harness,
+        "cells with line breaks, and wide characters",
+        Table::new().row(&["東京\nTokyo", "1"]).render(),
+        "┌───────┬───┐\n│ 東京  │ 1 │\n│ Tokyo │   │\n└───────┴───┘\n"
+    );
+    test!(
+        harness,
+        "missing cells are empty",
+        Table::new()
+            .border(Border::Ascii)
+            .row(&["a", "b"])
+            .row(&["c"])
+            .render(),
+        "+---+---+\n| a | b |\n| c |   |\n+---+---+\n"
+    );
+    test!(
+        harness,
+        "truncating wide characters",
+        truncate("日本語のテキスト", 7),
+        "日本語…"
+    );
+
+    let planets = |table: Table| {
+        let table = table
+            .header(&["planet", "distance (AU)", "moons", "description"])
+            .align(1, Alignment::Right)
+            .align(2, Alignment::Right);
+        PLANETS
+            .iter()
+            .fold(table, |table, planet| table.row(planet))
+    };
+    note!(
+        harness,
+        "{}",
+        planets(Table::new().max_width(3, 30).wrap(3))
+    );
+    note!(
+        harness,
+        "{}",
+        planets(Table::new().max_width(3, 20).border(Border::Ascii))
+    );
+}
@@ -0,0 +1,398 @@
+// tcpecho.rs
+// A TCP echo server, which sends everything it receives back to the client, and
+// a client for it. The server either starts a thread for each connection, or it
+// hands the connections to the thread pool snippet, which bounds the number of
+// threads, but also the number of clients served at the same time. The sockets
+// have short timeouts, so the accepting loop and the connections notice when
+// the server is shut down, and finish what they are doing: the binary shuts it
+// down on Ctrl-C, with a signal handler which only sets a flag. Each connection
+// is logged when it is opened and closed, with the number of bytes it echoed.
+// The client writes on one thread, and reads on another, as a large message
+// would otherwise fill the buffers of both sides, with neither of them reading.
+// The binary runs the server with --serve, the client with --connect, which
+// echoes the lines of the standard input, and the demonstration without them.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::threadpool::ThreadPool;
+use std::io::{self, BufRead, ErrorKind, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    ThreadPerConnection,
+    Pool(usize),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Statistics {
+    pub connections: usize,
+    pub bytes: u64,
+}
+
+pub type Log = Arc<dyn Fn(String) + Send + Sync>;
+
+pub struct EchoServer {
+    listener: TcpListener,
+    shutdown: Arc<AtomicBool>,
+}
+
+fn is_timeout(error: &io::Error) -> bool {
+    matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+// Echoes until the client closes the connection, or the server shuts down.
+fn handle(mut stream: TcpStream, shutdown: &AtomicBool) -> io::Result<u64> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(POLL_INTERVAL))?;
+    let mut buffer = [0; 4096];
+    let mut echoed = 0;
+    loop {
+        match stream.read(&mut buffer) {
+            Ok(0) => return Ok(echoed),
+            Ok(count) => {
+                stream.write_all(&buffer[..count])?;
+                echoed += count as u64;
+            }
+            Err(error) if is_timeout(&error) => {
+                if shutdown.load(Ordering::Acquire) {
+                    return Ok(echoed);
+                }
+            }
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+impl EchoServer {
+    pub fn bind(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        // The accepting loop polls, to notice the shutdown.
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            shutdown: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    // Setting the flag shuts the server down, from any thread.
+    pub fn shutdown_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.shutdown)
+    }
+
+    // Serves until it is shut down, then waits for the connections to finish.
+    pub fn serve(self, mode: Mode, log: Log) -> io::Result<Statistics> {
+        let pool = match mode {
+            Mode::ThreadPerConnection => None,
+            Mode::Pool(workers) => Some(ThreadPool::new(workers)),
+        };
+        let mut threads = Vec::new();
+        let connections = AtomicUsize::new(0);
+        let bytes = Arc::new(AtomicU64::new(0));
+        while !self.shutdown.load(Ordering::Acquire) {
+            let (stream, peer) = match self.listener.accept() {
+                Ok(connection) => connection,
+                Err(error) if is_timeout(&error) => {
+                    thread::sleep(POLL_INTERVAL / 5);
+                    continue;
+                }
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            let id = connections.fetch_add(1, Ordering::Relaxed) + 1;
+            log(format!("connection {} from {} opened", id, peer));
+            let (shutdown, bytes, log) = (self.shutdown.clone(), bytes.clone(), log.clone());
+            let job = move || match handle(stream, &shutdown) {
+                Ok(echoed) => {
+                    bytes.fetch_add(echoed, Ordering::Relaxed);
+                    log(format!("connection {} closed after {} bytes", id, echoed));
+                }
+                Err(error) => log(format!("connection {} failed: {}", id, error)),
+            };
+            match &pool {
+                Some(pool) => pool.execute(job),
+                None => threads.push(thread::spawn(job)),
+            }
+        }
+        for thread in threads {
+            let _ = thread.join();
+        }
+        drop(pool);
+        Ok(Statistics {
+            connections: connections.into_inner(),
+            bytes: bytes.load(Ordering::Relaxed),
+        })
+    }
+}
+
+// Sends the message, and reads the echo, until the server closes the
+// connection after the end of the message.
+pub fn echo(address: impl ToSocketAddrs, message: &[u8]) -> io::Result<Vec<u8>> {
+    let stream = TcpStream::connect(address)?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = stream;
+    thread::scope(|scope| {
+        let sent = scope.spawn(move || {
+            writer.write_all(message)?;
+            writer.shutdown(Shutdown::Write)
+        });
+        let mut reply = Vec::with_capacity(message.len());
+        reader.read_to_end(&mut reply)?;
+        sent.join().unwrap()?;
+        Ok(reply)
+    })
+}
+
+// Echoes the lines of the input, one at a time, until the input ends.
+pub fn client(
+    address: impl ToSocketAddrs,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    let mut stream = TcpStream::connect(address)?;
+    let mut reader = io::BufReader::new(stream.try_clone()?);
+    for line in input.lines() {
+        let line = line?;
+        writeln!(stream, "{}", line)?;
+        let mut reply = String::new();
+        if reader.read_line(&mut reply)? == 0 {
+            break;
+        }
+        write!(output, "{}", reply)?;
+    }
+    stream.shutdown(Shutdown::Both)
+}
+
+// The handler of SIGINT only sets a flag, which is all a signal handler can do
+// safely. There is no signal handling in the standard library, so it calls the
+// C library directly.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+pub fn install_interrupt_handler() {
+    const SIGINT: i32 = 2;
+    extern "C" {
+        fn signal(signal: i32, handler: extern "C" fn(i32)) -> usize;
+    }
+    extern "C" fn on_interrupt(_: i32) {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+    }
+    unsafe {
+        signal(SIGINT, on_interrupt);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn install_interrupt_handler() {}
+
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+fn serve_until_interrupted(address: &str, mode: Mode) -> io::Result<Statistics> {
+    let server = EchoServer::bind(address)?;
+    eprintln!(
+        "Listening on {}, press Ctrl-C to stop",
+        server.local_addr()?
+    );
+    install_interrupt_handler();
+    let shutdown = server.shutdown_flag();
+    thread::spawn(move || {
+        while !interrupted() {
+            thread::sleep(POLL_INTERVAL);
+        }
+        shutdown.store(true, Ordering::Release);
+    });
+    server.serve(mode, Arc::new(|line| eprintln!("{}", line)))
+}
+
+// The entry point of the binary.
+pub fn main(mut arguments: impl Iterator<Item = String>) -> i32 {
+    use crate::args::Parser;
+    let program = crate::harness::program_name(arguments.next());
+    let parser = Parser::new(&program, "An echo server and client over TCP.")
+        .option(
+            Some('s'),
+            "serve",
+            "ADDRESS",
+            "Run the server on the address",
+        )
+        .option(
+            Some('c'),
+            "connect",
+            "ADDRESS",
+            "Echo the standard input through the server",
+        )
+        .option(Some('w'), "workers", "COUNT", "Serve on a pool of threads")
+        .flag(
+            Some('q'),
+            "quiet",
+            "Only report the failed checks of the demonstration",
+        );
+    let matches = match parser.parse_or_report(arguments) {
+        Ok(matches) => matches,
+        Err(code) => return code,
+    };
+    let mode = match matches.value("workers").map(str::parse) {
+        None => Mode::ThreadPerConnection,
+        Some(Ok(workers)) if workers > 0 => Mode::Pool(workers),
+        Some(_) => {
+            eprintln!(
+                "{}: the number of workers must be a positive integer",
+                program
+            );
+            return 2;
+        }
+    };
+    let result = if let Some(address) = matches.value("serve") {
+        serve_until_interrupted(address, mode).map(|statistics| {
+            eprintln!(
+                "Served {} connections, echoed {} bytes",
+                statistics.connections, statistics.bytes
+            )
+        })
+    } else if let Some(address) = matches.value("connect") {
+        client(address, io::stdin().lock(), io::stdout())
+    } else {
+        return crate::harness::run_demo(demo, matches.flag("quiet"));
+    };
+    match result {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("{}: {}", program, error);
+            1
+        }
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use std::sync::Mutex;
+
+fn run_server(harness: &mut Harness, mode: Mode) {
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let log: Log = {
+        let lines = Arc::clone(&lines);
+        Arc::new(move |line| lines.lock().unwrap().push(line))
+    };
+    let server = EchoServer::bind("127.0.0.1:0").unwrap();
+    let address = server.local_addr().unwrap();
+    let shutdown = server.shutdown_flag();
+    let serving = thread::spawn(move || server.serve(mode, log));
+
+    let messages: Vec<Vec<u8>> = (0..6)
+        .map(|index| format!("message {} over {:?}", index, mode).into_bytes())
+        .collect();
+    let replies: Vec<io::Result<Vec<u8>>> = thread::scope(|scope| {
+        let clients: Vec<_> = messages
+            .iter()
+            .map(|message| scope.spawn(|| echo(address, message)))
+            .collect();
+        clients
+            .into_iter()
+            .map(|client| client.join().unwrap())
+            .collect()
+    });
+    test!(
+        harness,
+        &format!("concurrent clients get their messages back, {:?}", mode),
+        replies.into_iter().collect::<io::Result<Vec<_>>>().ok(),
+        Some(messages.clone())
+    );
+    let large: Vec<u8> = (0..1 << 20).map(|index| (index % 251) as u8).collect();
+    test!(
+        harness,
+        &format!("a message of a megabyte is echoed whole, {:?}", mode),
+        echo(address, &large).ok() == Some(large.clone()),
+        true
+    );
+
+    let mut output = Vec::new();
+    let result = client(address, &b"first line\nsecond line\n"[..], &mut output);
+    test!(
+        harness,
+        &format!("the line client echoes its input, {:?}", mode),
+        (result.is_ok(), String::from_utf8(output).unwrap()),
+        (true, "first line\nsecond line\n".to_string())
+    );
+
+    // An idle connection is closed by the server when it shuts down.
+    let mut idle = TcpStream::connect(address).unwrap();
+    thread::sleep(POLL_INTERVAL);
+    shutdown.store(true, Ordering::Release);
+    let statistics = serving.join().unwrap().unwrap();
+    let mut rest = Vec::new();
+    test!(
+        harness,
+        &format!("shutting down closes the idle connections, {:?}", mode),
+        idle.read_to_end(&mut rest).map(|_| rest.len()).ok(),
+        Some(0)
+    );
+    let message_bytes: usize = messages.iter().map(Vec::len).sum();
+    test!(
+        harness,
+        &format!(
+            "the server counts the connections and the bytes, {:?}",
+            mode
+        ),
+        statistics,
+        Statistics {
+            connections: 9,
+            bytes: (message_bytes + large.len() + 23) as u64
+        }
+    );
+    let lines = lines.lock().unwrap();
+    test!(
+        harness,
+        &format!(
+            "every connection is logged when opened and closed, {:?}",
+            mode
+        ),
+        (
+            lines.iter().filter(|line| line.ends_with("opened")).count(),
+            lines
+                .iter()
+                .filter(|line| line.contains("closed after"))
+                .count()
+        ),
+        (9, 9)
+    );
+    for line in lines
+        .iter()
+        .filter(|line| line.contains("closed after 1048576"))
+    {
+        note!(harness, "{:?}: {}", mode, line);
+    }
+    note!(harness);
+}
+
+pub fn demo(harness: &mut Harness) {
+    run_server(harness, Mode::ThreadPerConnection);
+    run_server(harness, Mode::Pool(4));
+    test!(
+        harness,
+        "a server can not bind to an address in use",
+        {
+            let first = EchoServer::bind("127.0.0.1:0").unwrap();
+            EchoServer::bind(first.local_addr().unwrap())
+                .err()
+                .map(|error| error.kind())
+        },
+        Some(ErrorKind::AddrInUse)
+    );
+}
@@ -0,0 +1,301 @@
+// template.rs
+// String templates with shell-like ${name} placeholders, which are replaced by
+// the values of the variables of the same name. A placeholder can have a
+// default value, as in ${name:-fallback}, which is used when the variable is
+// missing, or empty, like in the shell. A dollar sign is escaped by doubling
+// it, so $${name} stands for the literal ${name}, and a dollar sign which does
+// not start a placeholder is kept as it is. The template is parsed once, into
+// a list of literal texts and placeholders, which remember their positions, so
+// that rendering can report all the unknown variables, with their lines and
+// columns in the source, instead of stopping at the first one.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::lexer::line_and_column;
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnterminatedPlaceholder,
+    InvalidName(String),
+    UnknownVariable(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TemplateError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ErrorKind::UnterminatedPlaceholder => write!(f, "unterminated placeholder")?,
+            ErrorKind::InvalidName(name) => write!(f, "invalid name '{}'", name)?,
+            ErrorKind::UnknownVariable(name) => write!(f, "unknown variable '{}'", name)?,
+        }
+        write!(f, " at position {}", self.position)
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Placeholder {
+        name: String,
+        default: Option<String>,
+        position: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+// Names start with a letter or an underscore, and may contain digits and dots
+// after that, like ${user.name}.
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self, TemplateError> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut rest = source;
+        while let Some(index) = rest.find('$') {
+            text.push_str(&rest[..index]);
+            let position = source.len() - rest.len() + index;
+            let after = &rest[index + 1..];
+            if let Some(after) = after.strip_prefix('$') {
+                text.push('$');
+                rest = after;
+                continue;
+            }
+            let Some(body) = after.strip_prefix('{') else {
+                text.push('$');
+                rest = after;
+                continue;
+            };
+            let end = body.find('}').ok_or(TemplateError {
+                kind: ErrorKind::UnterminatedPlaceholder,
+                position,
+            })?;
+            let (name, default) = match body[..end].split_once(":-") {
+                Some((name, default)) => (name, Some(default.to_string())),
+                None => (&body[..end], None),
+            };
+            if !is_valid_name(name) {
+                return Err(TemplateError {
+                    kind: ErrorKind::InvalidName(name.to_string()),
+                    position,
+                });
+            }
+            if !text.is_empty() {
+                segments.push(Segment::Text(std::mem::take(&mut text)));
+            }
+            segments.push(Segment::Placeholder {
+                name: name.to_string(),
+                default,
+                position,
+            });
+            rest = &body[end + 1..];
+        }
+        text.push_str(rest);
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            segments,
+        })
+    }
+
+    // The names of the placeholders, in the order of their first appearance.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        for segment in &self.segments {
+            if let Segment::Placeholder { name, .. } = segment {
+                if !names.contains(&name.as_str()) {
+                    names.push(name.as_str());
+                }
+            }
+        }
+        names
+    }
+
+    pub fn render(
+        &self,
+        variables: &BTreeMap<String, String>,
+    ) -> Result<String, Vec<TemplateError>> {
+        let mut out = String::new();
+        let mut errors = Vec::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => out.push_str(text),
+                Segment::Placeholder {
+                    name,
+                    default,
+                    position,
+                } => match (variables.get(name), default) {
+                    (Some(value), Some(default)) if value.is_empty() => out.push_str(default),
+                    (Some(value), _) => out.push_str(value),
+                    (None, Some(default)) => out.push_str(default),
+                    (None, None) => errors.push(TemplateError {
+                        kind: ErrorKind::UnknownVariable(name.clone()),
+                        position: *position,
+                    }),
+                },
+            }
+        }
+        if errors.is_empty() {
+            Ok(out)
+        } else {
+            Err(errors)
+        }
+    }
+
+    // The line and the column of an error in the source, both starting from 1.
+    pub fn locate(&self, error: &TemplateError) -> (usize, usize) {
+        line_and_column(&self.source, error.position)
+    }
+}
+
+pub fn render(
+    source: &str,
+    variables: &BTreeMap<String, String>,
+) -> Result<String, Vec<TemplateError>> {
+    Template::parse(source)
+        .map_err(|error| vec![error])?
+        .render(variables)
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+const LETTER: &str = "Dear ${title:-customer} ${name},
+
+your order #${order} of ${quantity} × ${item} has shipped on ${date}, and
+should arrive within ${days:-3} days. The total of $$${total} has been charged
+to your card ending in ${card}.
+
+${closing:-Best regards},
+${sender}
+";
+
+fn variables(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+pub fn demo(harness: &mut Harness) {
+    let hello = variables(&[("name", "World"), ("empty", "")]);
+    test!(
+        harness,
+        "substituting a variable",
+        render("Hello, ${name}!", &hello),
+        Ok("Hello, World!".to_string())
+    );
+    test!(
+        harness,
+        "default values for missing and empty variables",
+        render(
+            "${missing:-a} ${empty:-b} ${name:-c} [${empty}] [${missing:-}]",
+            &hello
+        ),
+        Ok("a b World [] []".to_string())
+    );
+    test!(
+        harness,
+        "escaping dollar signs",
+        render("$${name} costs $$5, or $5, or $name", &hello),
+        Ok("${name} costs $5, or $5, or $name".to_string())
+    );
+    test!(
+        harness,
+        "an unterminated placeholder",
+        render("Hello, ${name!", &hello),
+        Err(vec![TemplateError {
+            kind: ErrorKind::UnterminatedPlaceholder,
+            position: 7
+        }])
+    );
+    test!(
+        harness,
+        "an invalid name",
+        render("${1st}", &hello).map_err(|errors| errors[0].to_string()),
+        Err("invalid name '1st' at position 0".to_string())
+    );
+
+    let letter = Template::parse(LETTER).unwrap();
+    test!(
+        harness,
+        "the names of the placeholders",
+        letter.names(),
+        vec![
+            "title", "name", "order", "quantity", "item", "date", "days", "total", "card",
+            "closing", "sender"
+        ]
+    );
+    let mut values = variables(&[
+        ("title", "Ms."),
+        ("name", "Smith"),
+        ("order", "10512"),
+        ("quantity", "2"),
+        ("item", "mechanical keyboard"),
+        ("total", "259.80"),
+    ]);
+    let errors = letter.render(&values).unwrap_err();
+    let unknown: Vec<(String, (usize, usize))> = errors
+        .iter()
+        .map(|error| match &error.kind {
+            ErrorKind::UnknownVariable(name) => (name.clone(), letter.locate(error)),
+            _ => (String::new(), (0, 0)),
+        })
+        .collect();
+    test!(
+        harness,
+        "every unknown variable is reported with its line and column",
+        unknown,
+        vec![
+            ("date".to_string(), (3, 62)),
+            ("card".to_string(), (5, 24)),
+            ("sender".to_string(), (8, 1))
+        ]
+    );
+    for (name, value) in [
+        ("date", "14 October"),
+        ("card", "4242"),
+        ("sender", "The Keyboard Shop"),
+    ] {
+        values.insert(name.to_string(), value.to_string());
+    }
+    let rendered = letter.render(&values).unwrap();
+    test!(
+        harness,
+        "rendering the letter",
+        rendered.lines().nth(3),
+        Some("should arrive within 3 days. The total of $259.80 has been charged")
+    );
+
+    for error in &errors {
+        let (line, column) = letter.locate(error);
+        note!(harness, "{} (line {}, column {})", error, line, column);
+    }
+    note!(harness, "\n{}", rendered);
+}
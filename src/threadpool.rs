@@ -0,0 +1,275 @@
+// threadpool.rs
+// A pool of a fixed number of worker threads, which run the jobs sent to them,
+// instead of starting a new thread for each one. The jobs wait in the queue of
+// an mpsc channel, whose receiving end the workers share behind a mutex, and
+// each idle worker takes the next one. A job which panics is caught within its
+// worker, which counts it, and goes on with the next job, so a failing job can
+// not take the pool down with it. Spawned jobs return a handle, which waits for
+// their result, or reports their panic. Dropping the pool closes the queue, and
+// waits for the workers to finish every job already sent, before they exit.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+pub struct ThreadPool {
+    workers: Vec<JoinHandle<()>>,
+    sender: Option<Sender<Job>>,
+    panicked: Arc<AtomicUsize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JobPanicked {
+    pub message: String,
+}
+
+impl fmt::Display for JobPanicked {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "job panicked: {}", self.message)
+    }
+}
+
+impl std::error::Error for JobPanicked {}
+
+// The message of a panic is a string, unless it was raised with another value.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+pub struct JobHandle<T> {
+    receiver: Receiver<Result<T, JobPanicked>>,
+}
+
+impl<T> JobHandle<T> {
+    // Waits for the job to finish.
+    pub fn join(self) -> Result<T, JobPanicked> {
+        self.receiver.recv().unwrap_or_else(|_| {
+            Err(JobPanicked {
+                message: "the pool was dropped".to_string(),
+            })
+        })
+    }
+}
+
+impl ThreadPool {
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "a thread pool needs at least one worker");
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let panicked = Arc::new(AtomicUsize::new(0));
+        let workers = (0..size)
+            .map(|id| {
+                let receiver = Arc::clone(&receiver);
+                let panicked = Arc::clone(&panicked);
+                thread::Builder::new()
+                    .name(format!("worker-{}", id))
+                    .spawn(move || loop {
+                        // The lock is only held while waiting for the next job,
+                        // and the queue is closed when the pool is dropped.
+                        let job = match receiver.lock().unwrap().recv() {
+                            Ok(job) => job,
+                            Err(_) => break,
+                        };
+                        if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+                            panicked.fetch_add(1, Ordering::Relaxed);
+                        }
+                    })
+                    .expect("failed to start a worker thread")
+            })
+            .collect();
+        Self {
+            workers,
+            sender: Some(sender),
+            panicked,
+        }
+    }
+
+    // The number of threads the machine can run in parallel, or one.
+    pub fn with_available_parallelism() -> Self {
+        Self::new(thread::available_parallelism().map_or(1, |count| count.get()))
+    }
+
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    // The number of jobs which panicked so far.
+    pub fn panicked(&self) -> usize {
+        self.panicked.load(Ordering::Relaxed)
+    }
+
+    pub fn execute(&self, job: impl FnOnce() + Send + 'static) {
+        self.sender
+            .as_ref()
+            .unwrap()
+            .send(Box::new(job))
+            .expect("the workers of the pool have exited");
+    }
+
+    // The panic of a spawned job is reported by its handle, and also counted.
+    pub fn spawn<T: Send + 'static>(
+        &self,
+        job: impl FnOnce() -> T + Send + 'static,
+    ) -> JobHandle<T> {
+        let (sender, receiver) = mpsc::channel();
+        self.execute(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(job));
+            let failed = result.is_err();
+            let _ = sender.send(result.map_err(|payload| JobPanicked {
+                message: panic_message(payload.as_ref()),
+            }));
+            if failed {
+                panic::resume_unwind(Box::new("job panicked"));
+            }
+        });
+        JobHandle { receiver }
+    }
+
+    // Runs the function on each of the items, in parallel, and collects the
+    // results in the order of the items.
+    pub fn map<T, U>(&self, items: Vec<T>, function: fn(T) -> U) -> Vec<Result<U, JobPanicked>>
+    where
+        T: Send + 'static,
+        U: Send + 'static,
+    {
+        let handles: Vec<JobHandle<U>> = items
+            .into_iter()
+            .map(|item| self.spawn(move || function(item)))
+            .collect();
+        handles.into_iter().map(JobHandle::join).collect()
+    }
+
+    // Closes the queue, and waits for the workers to finish the jobs in it.
+    fn shut_down(&mut self) {
+        drop(self.sender.take());
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+
+    // Shuts the pool down, and returns the number of jobs which panicked.
+    pub fn join(mut self) -> usize {
+        self.shut_down();
+        self.panicked()
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        self.shut_down();
+    }
+}
+
+// Demonstration
+
+use crate::bench::{format_time, Stopwatch};
+use crate::harness::Harness;
+use crate::sha2::Sha256;
+use std::collections::BTreeSet;
+
+fn make_input(index: usize) -> Vec<u8> {
+    (0..1 << 16)
+        .map(|byte| (byte * 31 + index * 7) as u8)
+        .collect()
+}
+
+pub fn demo(harness: &mut Harness) {
+    let inputs: Vec<Vec<u8>> = (0..64).map(make_input).collect();
+    let mut stopwatch = Stopwatch::new();
+    let sequential: Vec<Vec<u8>> = inputs.iter().map(|input| Sha256::digest(input)).collect();
+    let sequential_time = stopwatch.lap();
+
+    let pool = ThreadPool::new(4);
+    test!(harness, "the pool has four workers", pool.size(), 4);
+    let parallel = pool.map(inputs, |input| Sha256::digest(&input));
+    let parallel_time = stopwatch.lap();
+    test!(
+        harness,
+        "hashing in parallel gives the same digests in the same order",
+        parallel.into_iter().collect::<Result<Vec<_>, _>>(),
+        Ok(sequential)
+    );
+    note!(
+        harness,
+        "Hashing 64 inputs of 64 KiB with SHA-256: {} sequentially, {} on {} workers\n",
+        format_time(sequential_time),
+        format_time(parallel_time),
+        pool.size()
+    );
+
+    let handles: Vec<JobHandle<Option<String>>> = (0..32)
+        .map(|_| {
+            pool.spawn(|| {
+                thread::sleep(std::time::Duration::from_millis(1));
+                thread::current().name().map(str::to_string)
+            })
+        })
+        .collect();
+    let names: BTreeSet<String> = handles
+        .into_iter()
+        .filter_map(|handle| handle.join().unwrap())
+        .collect();
+    test!(
+        harness,
+        "the jobs are shared among the named workers",
+        names.len() > 1 && names.iter().all(|name| name.starts_with("worker-")),
+        true
+    );
+
+    // The default hook would print the panics, which are expected here.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let failing = pool.spawn(|| -> u32 { panic!("division by zero") });
+    pool.execute(|| panic!("fire and forget"));
+    let result = failing.join();
+    let after = pool.spawn(|| 6 * 7).join();
+    test!(
+        harness,
+        "a panicking job reports its message",
+        result,
+        Err(JobPanicked {
+            message: "division by zero".to_string()
+        })
+    );
+    test!(
+        harness,
+        "the pool keeps working after a job panicked",
+        after,
+        Ok(42)
+    );
+
+    let completed = Arc::new(AtomicUsize::new(0));
+    for _ in 0..100 {
+        let completed = Arc::clone(&completed);
+        pool.execute(move || {
+            thread::sleep(std::time::Duration::from_micros(100));
+            completed.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+    let panicked = pool.join();
+    panic::set_hook(hook);
+    test!(harness, "both panics were counted by the pool", panicked, 2);
+    test!(
+        harness,
+        "shutting the pool down finishes the queued jobs first",
+        completed.load(Ordering::SeqCst),
+        100
+    );
+}
@@ -0,0 +1,250 @@
+// trie.rs
+// A trie, or prefix tree, which stores a set of words in a tree, where each
+// edge is labeled with a character, and each word is the path from the root to
+// a node marked as the end of a word. Words with a common prefix share the
+// nodes of it, so finding every word with a given prefix only needs to walk
+// down the prefix, and then collect the words below it, which is how
+// autocompletion works. The children are kept in an ordered map, so the
+// completions come out sorted.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::collections::BTreeMap;
+
+#[derive(Default)]
+struct Node {
+    children: BTreeMap<char, Node>,
+    terminal: bool,
+}
+
+#[derive(Default)]
+pub struct Trie {
+    root: Node,
+    length: usize,
+}
+
+impl Trie {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    // Returns whether the word was not stored yet.
+    pub fn insert(&mut self, word: &str) -> bool {
+        let mut node = &mut self.root;
+        for c in word.chars() {
+            node = node.children.entry(c).or_default();
+        }
+        let inserted = !node.terminal;
+        node.terminal = true;
+        if inserted {
+            self.length += 1;
+        }
+        inserted
+    }
+
+    fn find(&self, prefix: &str) -> Option<&Node> {
+        let mut node = &self.root;
+        for c in prefix.chars() {
+            node = node.children.get(&c)?;
+        }
+        Some(node)
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.find(word).is_some_and(|node| node.terminal)
+    }
+
+    pub fn has_prefix(&self, prefix: &str) -> bool {
+        self.find(prefix).is_some()
+    }
+
+    // The longest stored word, which is a prefix of the given text, like a
+    // routing table looks up the longest matching network prefix.
+    pub fn longest_prefix<'a>(&self, text: &'a str) -> Option<&'a str> {
+        let mut node = &self.root;
+        let mut longest = node.terminal.then_some(0);
+        for (position, c) in text.char_indices() {
+            match node.children.get(&c) {
+                Some(child) => node = child,
+                None => break,
+            }
+            if node.terminal {
+                longest = Some(position + c.len_utf8());
+            }
+        }
+        longest.map(|length| &text[..length])
+    }
+
+    // Every stored word starting with the prefix, in lexicographic order.
+    pub fn complete(&self, prefix: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        if let Some(node) = self.find(prefix) {
+            let mut word = String::from(prefix);
+            Self::collect(node, &mut word, &mut words);
+        }
+        words
+    }
+
+    fn collect(node: &Node, word: &mut String, words: &mut Vec<String>) {
+        if node.terminal {
+            words.push(word.clone());
+        }
+        for (c, child) in &node.children {
+            word.push(*c);
+            Self::collect(child, word, words);
+            word.pop();
+        }
+    }
+
+    pub fn words(&self) -> Vec<String> {
+        self.complete("")
+    }
+
+    // The number of nodes, not counting the root, which shows how much the
+    // common prefixes save, compared to the total length of the words.
+    pub fn node_count(&self) -> usize {
+        fn count(node: &Node) -> usize {
+            node.children.values().map(|child| 1 + count(child)).sum()
+        }
+        count(&self.root)
+    }
+}
+
+impl<'a> FromIterator<&'a str> for Trie {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(words: I) -> Self {
+        let mut trie = Self::new();
+        for word in words {
+            trie.insert(word);
+        }
+        trie
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+pub fn demo(harness: &mut Harness) {
+    #[rustfmt::skip]
+    const DICTIONARY: [&str; 20] = [
+        "car", "card", "care", "careful", "carefully", "cargo", "carpet", "cart",
+        "cat", "catalog", "dog", "dot", "dote", "double", "doubt", "tea", "team",
+        "tear", "ten", "tent",
+    ];
+    let mut trie: Trie = DICTIONARY.iter().copied().collect();
+
+    test!(harness, "every word is counted once", trie.len(), 20);
+    test!(
+        harness,
+        "inserting a stored word again does nothing",
+        (trie.insert("car"), trie.len()),
+        (false, 20)
+    );
+    test!(
+        harness,
+        "a stored word is found",
+        trie.contains("care"),
+        true
+    );
+    test!(
+        harness,
+        "a prefix of a stored word is not a word itself",
+        trie.contains("ca"),
+        false
+    );
+    test!(
+        harness,
+        "a prefix of a stored word is a prefix",
+        trie.has_prefix("ca"),
+        true
+    );
+    test!(
+        harness,
+        "a missing word is not found",
+        trie.contains("cow"),
+        false
+    );
+
+    test!(
+        harness,
+        "the longest stored prefix of \"carefulness\" is \"careful\"",
+        trie.longest_prefix("carefulness"),
+        Some("careful")
+    );
+    test!(
+        harness,
+        "the longest stored prefix of \"doting\" is \"dot\"",
+        trie.longest_prefix("doting"),
+        Some("dot")
+    );
+    test!(
+        harness,
+        "\"do\" has no stored prefix",
+        trie.longest_prefix("do"),
+        None
+    );
+
+    test!(
+        harness,
+        "completing \"care\" finds its extensions in order",
+        trie.complete("care"),
+        vec!["care", "careful", "carefully"]
+    );
+    test!(
+        harness,
+        "completing \"dou\"",
+        trie.complete("dou"),
+        vec!["double", "doubt"]
+    );
+    test!(
+        harness,
+        "completing a missing prefix finds nothing",
+        trie.complete("x"),
+        Vec::<String>::new()
+    );
+    let mut sorted = DICTIONARY.to_vec();
+    sorted.sort();
+    test!(
+        harness,
+        "completing the empty prefix lists every word in order",
+        trie.words(),
+        sorted
+    );
+
+    trie.insert("árvíztűrő");
+    trie.insert("árvíz");
+    test!(
+        harness,
+        "words with multibyte characters",
+        (trie.complete("árv"), trie.longest_prefix("árvízi")),
+        (
+            vec!["árvíz".to_string(), "árvíztűrő".to_string()],
+            Some("árvíz")
+        )
+    );
+
+    let characters: usize = DICTIONARY.iter().map(|word| word.chars().count()).sum();
+    let shared: Trie = DICTIONARY.iter().copied().collect();
+    note!(
+        harness,
+        "The {} words have {} characters, but only need {} nodes.",
+        DICTIONARY.len(),
+        characters,
+        shared.node_count()
+    );
+    for prefix in ["ca", "care", "do", "te", "tea", "z"] {
+        note!(harness, "{:>6} -> {:?}", prefix, shared.complete(prefix));
+    }
+    note!(harness);
+}
@@ -0,0 +1,381 @@
+// utf8.rs
+// Validating and decoding UTF-8 with a deterministic finite automaton. Bytes
+// are sorted into a dozen classes, like ASCII, the three ranges of continuation
+// bytes, and the lead bytes with special rules for their second byte, and a
+// table of transitions from the states of the automaton, which remember how
+// many continuation bytes are still expected, and which range the next one must
+// be in, tells what each class does. This rejects every malformed sequence
+// without separate checks, like overlong encodings, which use more bytes than
+// needed, the UTF-16 surrogates, which are not characters, code points above
+// U+10FFFF, and sequences cut short, and reports why, with the offset of the
+// sequence. In lossy decoding, each invalid part is replaced with U+FFFD, where
+// the invalid part is the longest prefix of a valid sequence, or a single byte
+// otherwise, as recommended by the Unicode Standard, so that the result is the
+// same as that of the standard library.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    // A continuation byte without a lead byte before it.
+    UnexpectedContinuation,
+    // A byte which never appears in UTF-8, from F5 to FF.
+    InvalidByte,
+    Overlong,
+    Surrogate,
+    TooLarge,
+    // A sequence which ends before all of its continuation bytes.
+    Truncated,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Utf8Error {
+    pub kind: ErrorKind,
+    // The offset of the first byte of the invalid part.
+    pub position: usize,
+    // The length of the invalid part, which is replaced in lossy decoding.
+    pub length: usize,
+}
+
+impl fmt::Display for Utf8Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.kind {
+            ErrorKind::UnexpectedContinuation => "unexpected continuation byte",
+            ErrorKind::InvalidByte => "invalid byte",
+            ErrorKind::Overlong => "overlong encoding",
+            ErrorKind::Surrogate => "encoded surrogate",
+            ErrorKind::TooLarge => "code point above U+10FFFF",
+            ErrorKind::Truncated => "truncated sequence",
+        };
+        write!(f, "{} at position {}", message, self.position)
+    }
+}
+
+impl std::error::Error for Utf8Error {}
+
+pub const REPLACEMENT: char = '\u{fffd}';
+
+// The classes of the bytes, where the lead bytes whose second byte has a
+// narrower range have their own classes.
+const ASCII: u8 = 0; // 00..7F
+const LOW: u8 = 1; // 80..8F, continuation
+const MIDDLE: u8 = 2; // 90..9F, continuation
+const HIGH: u8 = 3; // A0..BF, continuation
+const C0: u8 = 4; // C0..C1, always overlong
+const TWO: u8 = 5; // C2..DF
+const E0: u8 = 6; // E0, followed by A0..BF
+const THREE: u8 = 7; // E1..EC, EE..EF
+const ED: u8 = 8; // ED, followed by 80..9F
+const F0: u8 = 9; // F0, followed by 90..BF
+const FOUR: u8 = 10; // F1..F3
+const F4: u8 = 11; // F4, followed by 80..8F
+const INVALID: u8 = 12; // F5..FF
+
+fn class(byte: u8) -> u8 {
+    match byte {
+        0x00..=0x7f => ASCII,
+        0x80..=0x8f => LOW,
+        0x90..=0x9f => MIDDLE,
+        0xa0..=0xbf => HIGH,
+        0xc0..=0xc1 => C0,
+        0xc2..=0xdf => TWO,
+        0xe0 => E0,
+        0xed => ED,
+        0xe1..=0xef => THREE,
+        0xf0 => F0,
+        0xf1..=0xf3 => FOUR,
+        0xf4 => F4,
+        _ => INVALID,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    Accept,
+    // Expecting one, two, or three more continuation bytes of any range.
+    Tail1,
+    Tail2,
+    Tail3,
+    // Expecting the second byte of the lead bytes with narrower ranges.
+    AfterE0,
+    AfterED,
+    AfterF0,
+    AfterF4,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Step {
+    Next(State),
+    Fail(ErrorKind),
+}
+
+// The transitions from each state, by the classes of the bytes.
+#[rustfmt::skip]
+const fn transitions(state: State) -> [Step; 13] {
+    use ErrorKind::*;
+    use State::*;
+    use Step::{Fail as F, Next as N};
+    match state {
+        Accept => [
+            N(Accept), F(UnexpectedContinuation), F(UnexpectedContinuation),
+            F(UnexpectedContinuation), F(Overlong), N(Tail1), N(AfterE0), N(Tail2),
+            N(AfterED), N(AfterF0), N(Tail3), N(AfterF4), F(InvalidByte),
+        ],
+        Tail1 => continuation(N(Accept), N(Accept), N(Accept)),
+        Tail2 => continuation(N(Tail1), N(Tail1), N(Tail1)),
+        Tail3 => continuation(N(Tail2), N(Tail2), N(Tail2)),
+        AfterE0 => continuation(F(Overlong), F(Overlong), N(Tail1)),
+        AfterED => continuation(N(Tail1), N(Tail1), F(Surrogate)),
+        AfterF0 => continuation(F(Overlong), N(Tail2), N(Tail2)),
+        AfterF4 => continuation(N(Tail2), F(TooLarge), F(TooLarge)),
+    }
+}
+
+// The transitions of a state which expects a continuation byte, where the
+// steps are for the low, middle, and high ranges, and anything else truncates
+// the sequence.
+const fn continuation(low: Step, middle: Step, high: Step) -> [Step; 13] {
+    let mut steps = [Step::Fail(ErrorKind::Truncated); 13];
+    steps[LOW as usize] = low;
+    steps[MIDDLE as usize] = middle;
+    steps[HIGH as usize] = high;
+    steps
+}
+
+const TABLE: [[Step; 13]; 8] = [
+    transitions(State::Accept),
+    transitions(State::Tail1),
+    transitions(State::Tail2),
+    transitions(State::Tail3),
+    transitions(State::AfterE0),
+    transitions(State::AfterED),
+    transitions(State::AfterF0),
+    transitions(State::AfterF4),
+];
+
+// The bits of the code point in the lead byte of each class.
+fn payload(byte: u8) -> u32 {
+    match class(byte) {
+        ASCII => byte as u32,
+        TWO => (byte & 0x1f) as u32,
+        E0 | THREE | ED => (byte & 0x0f) as u32,
+        _ => (byte & 0x07) as u32,
+    }
+}
+
+// Decodes the characters of the bytes one by one, where each invalid part is
+// an error, after which decoding continues.
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl Iterator for Decoder<'_> {
+    type Item = Result<char, Utf8Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.position;
+        let lead = *self.bytes.get(start)?;
+        let mut state = State::Accept;
+        let mut code_point = payload(lead);
+        let fail = |kind, end: usize| Utf8Error {
+            kind,
+            position: start,
+            length: end - start,
+        };
+        loop {
+            let Some(byte) = self.bytes.get(self.position) else {
+                return Some(Err(fail(ErrorKind::Truncated, self.position)));
+            };
+            match TABLE[state as usize][class(*byte) as usize] {
+                Step::Next(next) => {
+                    if self.position > start {
+                        code_point = (code_point << 6) | (byte & 0x3f) as u32;
+                    }
+                    self.position += 1;
+                    state = next;
+                }
+                // The byte which does not fit is not part of the invalid part,
+                // unless it is the first one.
+                Step::Fail(kind) => {
+                    if self.position == start {
+                        self.position += 1;
+                    }
+                    return Some(Err(fail(kind, self.position)));
+                }
+            }
+            if state == State::Accept {
+                return Some(Ok(char::from_u32(code_point).unwrap()));
+            }
+        }
+    }
+}
+
+pub fn validate(bytes: &[u8]) -> Result<(), Utf8Error> {
+    Decoder::new(bytes).try_for_each(|result| result.map(|_| ()))
+}
+
+pub fn decode(bytes: &[u8]) -> Result<String, Utf8Error> {
+    Decoder::new(bytes).collect()
+}
+
+pub fn decode_lossy(bytes: &[u8]) -> String {
+    Decoder::new(bytes)
+        .map(|result| result.unwrap_or(REPLACEMENT))
+        .collect()
+}
+
+// All the errors of the bytes.
+pub fn errors(bytes: &[u8]) -> Vec<Utf8Error> {
+    Decoder::new(bytes).filter_map(Result::err).collect()
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use crate::rng::{RandomSource, SplitMix64};
+
+pub fn demo(harness: &mut Harness) {
+    let text = "ASCII, ő, €, 𝄞, and \u{10ffff}";
+    test!(
+        harness,
+        "decoding valid UTF-8",
+        decode(text.as_bytes()),
+        Ok(text.to_string())
+    );
+    test!(
+        harness,
+        "the boundaries of the sequence lengths",
+        decode(&[
+            0x7f, 0xc2, 0x80, 0xdf, 0xbf, 0xe0, 0xa0, 0x80, 0xef, 0xbf, 0xbf, 0xf0, 0x90, 0x80,
+            0x80, 0xf4, 0x8f, 0xbf, 0xbf
+        ]),
+        Ok("\u{7f}\u{80}\u{7ff}\u{800}\u{ffff}\u{10000}\u{10ffff}".to_string())
+    );
+    test!(harness, "the empty input is valid", validate(b""), Ok(()));
+
+    let error = |kind, position, length| {
+        Err::<(), _>(Utf8Error {
+            kind,
+            position,
+            length,
+        })
+    };
+    test!(
+        harness,
+        "overlong encodings",
+        [
+            validate(b"ab\xc0\xaf"),
+            validate(b"\xe0\x80\xaf"),
+            validate(b"\xf0\x80\x80\xaf")
+        ],
+        [
+            error(ErrorKind::Overlong, 2, 1),
+            error(ErrorKind::Overlong, 0, 1),
+            error(ErrorKind::Overlong, 0, 1)
+        ]
+    );
+    test!(
+        harness,
+        "surrogates and code points above U+10FFFF",
+        [
+            validate(b"\xed\xa0\x80"),
+            validate(b"\xf4\x90\x80\x80"),
+            validate(b"\xf5\x80\x80\x80")
+        ],
+        [
+            error(ErrorKind::Surrogate, 0, 1),
+            error(ErrorKind::TooLarge, 0, 1),
+            error(ErrorKind::InvalidByte, 0, 1)
+        ]
+    );
+    test!(
+        harness,
+        "truncated sequences and stray continuation bytes",
+        [
+            validate(b"x\xe2\x82"),
+            validate(b"\xf0\x9f\x98x"),
+            validate(b"ok\x80")
+        ],
+        [
+            error(ErrorKind::Truncated, 1, 2),
+            error(ErrorKind::Truncated, 0, 3),
+            error(ErrorKind::UnexpectedContinuation, 2, 1)
+        ]
+    );
+    let broken = b"caf\xc3\xa9 \xe2\x82 \xed\xa0\x80 \xf0\x9f\x98\x80 \xff!";
+    test!(
+        harness,
+        "lossy decoding replaces each invalid part",
+        decode_lossy(broken),
+        "café \u{fffd} \u{fffd}\u{fffd}\u{fffd} 😀 \u{fffd}!"
+    );
+    test!(
+        harness,
+        "collecting every error",
+        errors(broken)
+            .iter()
+            .map(|error| (error.kind, error.position))
+            .collect::<Vec<_>>(),
+        vec![
+            (ErrorKind::Truncated, 6),
+            (ErrorKind::Surrogate, 9),
+            (ErrorKind::UnexpectedContinuation, 10),
+            (ErrorKind::UnexpectedContinuation, 11),
+            (ErrorKind::InvalidByte, 18)
+        ]
+    );
+
+    let mut rng = SplitMix64::new(2026);
+    let mut agrees = true;
+    for _ in 0..2000 {
+        let length = rng.next_below(16) as usize;
+        let mut bytes = vec![0; length];
+        rng.fill_bytes(&mut bytes);
+        // Biased towards lead and continuation bytes, to hit more cases.
+        for byte in &mut bytes {
+            if *byte < 0x80 && rng.next_below(2) == 0 {
+                *byte |= 0x80;
+            }
+        }
+        let standard = std::str::from_utf8(&bytes);
+        agrees &= decode_lossy(&bytes) == String::from_utf8_lossy(&bytes)
+            && validate(&bytes).map_err(|error| error.position)
+                == standard.map(|_| ()).map_err(|error| error.valid_up_to());
+    }
+    test!(
+        harness,
+        "the results agree with the standard library on random bytes",
+        agrees,
+        true
+    );
+
+    for (bytes, name) in [
+        (&b"\xc1\xbf"[..], "C1 BF"),
+        (b"\xe0\x9f\xbf", "E0 9F BF"),
+        (b"\xed\xbf\xbf", "ED BF BF"),
+        (b"\xf4\x90\x80\x80", "F4 90 80 80"),
+        (b"\xe6\x97", "E6 97"),
+    ] {
+        match validate(bytes) {
+            Ok(()) => note!(harness, "{:<12} valid", name),
+            Err(error) => note!(harness, "{:<12} {}", name, error),
+        }
+    }
+    note!(harness, "Lossy: {}", decode_lossy(broken));
+}
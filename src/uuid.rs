@@ -0,0 +1,327 @@
+// uuid.rs
+// Universally unique identifiers as specified in RFC 9562 (formerly RFC 4122),
+// which are 128-bit numbers, usually written as 32 hexadecimal digits in five
+// groups separated by hyphens. The top bits of the ninth byte hold the variant,
+// which is 10 for the identifiers of the RFC, and the top four bits of the
+// seventh byte hold the version, which tells how the rest was generated.
+// Version 4 identifiers are random, with 122 random bits, while version 5
+// identifiers are derived from a namespace identifier and a name, by hashing
+// them together with the SHA-1 snippet, so the same name always gives the same
+// identifier. Parsing accepts the canonical hyphenated form, with either case
+// of digits, and reports the position of the first problem.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::rng::RandomSource;
+use crate::sha1::Sha1;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    InvalidLength,
+    InvalidCharacter,
+    MissingHyphen,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UuidError {
+    pub kind: ErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for UuidError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ErrorKind::InvalidLength => write!(f, "invalid length")?,
+            ErrorKind::InvalidCharacter => write!(f, "invalid character")?,
+            ErrorKind::MissingHyphen => write!(f, "missing hyphen")?,
+        }
+        write!(f, " at position {}", self.position)
+    }
+}
+
+impl std::error::Error for UuidError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    // Reserved for backward compatibility with the Apollo NCS.
+    Ncs,
+    Rfc,
+    // Reserved for backward compatibility with old Microsoft GUIDs.
+    Microsoft,
+    Future,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Uuid([u8; 16]);
+
+// The positions of the hyphens in the canonical form.
+const HYPHENS: [usize; 4] = [8, 13, 18, 23];
+
+impl Uuid {
+    pub const NIL: Uuid = Uuid([0; 16]);
+    pub const MAX: Uuid = Uuid([0xff; 16]);
+
+    #[rustfmt::skip]
+    pub const NAMESPACE_DNS: Uuid = Uuid([
+        0x6b, 0xa7, 0xb8, 0x10, 0x9d, 0xad, 0x11, 0xd1,
+        0x80, 0xb4, 0x00, 0xc0, 0x4f, 0xd4, 0x30, 0xc8,
+    ]);
+    #[rustfmt::skip]
+    pub const NAMESPACE_URL: Uuid = Uuid([
+        0x6b, 0xa7, 0xb8, 0x11, 0x9d, 0xad, 0x11, 0xd1,
+        0x80, 0xb4, 0x00, 0xc0, 0x4f, 0xd4, 0x30, 0xc8,
+    ]);
+    #[rustfmt::skip]
+    pub const NAMESPACE_OID: Uuid = Uuid([
+        0x6b, 0xa7, 0xb8, 0x12, 0x9d, 0xad, 0x11, 0xd1,
+        0x80, 0xb4, 0x00, 0xc0, 0x4f, 0xd4, 0x30, 0xc8,
+    ]);
+    #[rustfmt::skip]
+    pub const NAMESPACE_X500: Uuid = Uuid([
+        0x6b, 0xa7, 0xb8, 0x14, 0x9d, 0xad, 0x11, 0xd1,
+        0x80, 0xb4, 0x00, 0xc0, 0x4f, 0xd4, 0x30, 0xc8,
+    ]);
+
+    pub fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    pub fn as_u128(&self) -> u128 {
+        u128::from_be_bytes(self.0)
+    }
+
+    fn with_version(mut bytes: [u8; 16], version: u8) -> Self {
+        bytes[6] = (bytes[6] & 0x0f) | (version << 4);
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        Self(bytes)
+    }
+
+    pub fn new_v4<R: RandomSource>(random: &mut R) -> Self {
+        let mut bytes = [0; 16];
+        random.fill_bytes(&mut bytes);
+        Self::with_version(bytes, 4)
+    }
+
+    pub fn new_v5(namespace: &Uuid, name: &[u8]) -> Self {
+        let mut hasher = Sha1::new();
+        hasher.update(&namespace.0);
+        hasher.update(name);
+        let mut bytes = [0; 16];
+        bytes.copy_from_slice(&hasher.finalize()[..16]);
+        Self::with_version(bytes, 5)
+    }
+
+    // The version is only meaningful for the variant of the RFC.
+    pub fn version(&self) -> u8 {
+        self.0[6] >> 4
+    }
+
+    pub fn variant(&self) -> Variant {
+        match self.0[8] {
+            0x00..=0x7f => Variant::Ncs,
+            0x80..=0xbf => Variant::Rfc,
+            0xc0..=0xdf => Variant::Microsoft,
+            _ => Variant::Future,
+        }
+    }
+
+    // The RFC defines versions 1 to 8, and the nil and max identifiers, which
+    // are special cases with neither a variant nor a version.
+    pub fn is_valid(&self) -> bool {
+        *self == Self::NIL
+            || *self == Self::MAX
+            || (self.variant() == Variant::Rfc && (1..=8).contains(&self.version()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, UuidError> {
+        let bytes = text.as_bytes();
+        let mut digits = Vec::with_capacity(32);
+        for (position, byte) in bytes.iter().enumerate().take(36) {
+            if HYPHENS.contains(&position) {
+                if *byte != b'-' {
+                    return Err(UuidError {
+                        kind: ErrorKind::MissingHyphen,
+                        position,
+                    });
+                }
+                continue;
+            }
+            let digit = (*byte as char).to_digit(16).ok_or(UuidError {
+                kind: ErrorKind::InvalidCharacter,
+                position,
+            })?;
+            digits.push(digit as u8);
+        }
+        if bytes.len() != 36 {
+            return Err(UuidError {
+                kind: ErrorKind::InvalidLength,
+                position: bytes.len().min(36),
+            });
+        }
+        let mut uuid = [0; 16];
+        for (byte, pair) in uuid.iter_mut().zip(digits.chunks_exact(2)) {
+            *byte = (pair[0] << 4) | pair[1];
+        }
+        Ok(Self(uuid))
+    }
+}
+
+impl fmt::Display for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, byte) in self.0.iter().enumerate() {
+            if [4, 6, 8, 10].contains(&index) {
+                write!(f, "-")?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Uuid {
+    type Err = UuidError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse(text)
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use crate::rng::SplitMix64;
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "version 5 of a name in the DNS namespace",
+        Uuid::new_v5(&Uuid::NAMESPACE_DNS, b"www.example.com").to_string(),
+        "2ed6657d-e927-568b-95e1-2665a8aea6a2"
+    );
+    test!(
+        harness,
+        "version 5 of a name in the URL namespace",
+        Uuid::new_v5(&Uuid::NAMESPACE_URL, b"https://www.python.org/").to_string(),
+        "5406f80d-92e9-51cd-a176-77445955e733"
+    );
+
+    let mut random = SplitMix64::new(2026);
+    let random_uuids: Vec<Uuid> = (0..1000).map(|_| Uuid::new_v4(&mut random)).collect();
+    test!(
+        harness,
+        "random identifiers have the version and variant bits set",
+        random_uuids
+            .iter()
+            .all(|uuid| uuid.version() == 4 && uuid.variant() == Variant::Rfc && uuid.is_valid()),
+        true
+    );
+    let mut unique = random_uuids.clone();
+    unique.sort();
+    unique.dedup();
+    test!(harness, "random identifiers are unique", unique.len(), 1000);
+    test!(
+        harness,
+        "formatting and parsing round-trips",
+        random_uuids
+            .iter()
+            .all(|uuid| Uuid::parse(&uuid.to_string()) == Ok(*uuid)),
+        true
+    );
+    test!(
+        harness,
+        "parsing uppercase digits",
+        "6BA7B810-9DAD-11D1-80B4-00C04FD430C8".parse::<Uuid>(),
+        Ok(Uuid::NAMESPACE_DNS)
+    );
+    test!(
+        harness,
+        "the nil and the max identifiers",
+        (
+            Uuid::NIL.to_string(),
+            Uuid::MAX.as_u128(),
+            Uuid::MAX.is_valid()
+        ),
+        (
+            "00000000-0000-0000-0000-000000000000".to_string(),
+            u128::MAX,
+            true
+        )
+    );
+    test!(
+        harness,
+        "the version 1 namespaces are valid",
+        (
+            Uuid::NAMESPACE_OID.version(),
+            Uuid::NAMESPACE_X500.is_valid()
+        ),
+        (1, true)
+    );
+    test!(
+        harness,
+        "an identifier of the Microsoft variant is not valid",
+        Uuid::parse("00000000-0000-4000-c000-000000000000").map(|uuid| uuid.is_valid()),
+        Ok(false)
+    );
+    test!(
+        harness,
+        "an identifier without a version is not valid",
+        Uuid::parse("12345678-9abc-0def-8123-456789abcdef").map(|uuid| uuid.is_valid()),
+        Ok(false)
+    );
+    test!(
+        harness,
+        "rejecting a misplaced hyphen",
+        Uuid::parse("6ba7b8109-dad-11d1-80b4-00c04fd430c8"),
+        Err(UuidError {
+            kind: ErrorKind::MissingHyphen,
+            position: 8
+        })
+    );
+    test!(
+        harness,
+        "rejecting an invalid digit",
+        Uuid::parse("6ba7b810-9dad-11d1-80b4-00c04fd430cg").map_err(|error| error.to_string()),
+        Err("invalid character at position 35".to_string())
+    );
+    test!(
+        harness,
+        "rejecting a short identifier",
+        Uuid::parse("6ba7b810-9dad-11d1-80b4-00c04fd430c"),
+        Err(UuidError {
+            kind: ErrorKind::InvalidLength,
+            position: 35
+        })
+    );
+    test!(
+        harness,
+        "rejecting a long identifier",
+        Uuid::parse("6ba7b810-9dad-11d1-80b4-00c04fd430c8}"),
+        Err(UuidError {
+            kind: ErrorKind::InvalidLength,
+            position: 36
+        })
+    );
+
+    let seed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as u64);
+    let mut random = SplitMix64::new(seed);
+    note!(harness, "Random identifiers (version 4):");
+    for _ in 0..5 {
+        note!(harness, "    {}", Uuid::new_v4(&mut random));
+    }
+    note!(harness, "Name-based identifiers (version 5):");
+    for name in ["example.com", "example.org", "rust-lang.org", "example.com"] {
+        let uuid = Uuid::new_v5(&Uuid::NAMESPACE_DNS, name.as_bytes());
+        note!(harness, "    {}  {}", uuid, name);
+    }
+}
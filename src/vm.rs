@@ -0,0 +1,591 @@
+// vm.rs
+// A tiny stack-based virtual machine, with an assembler for its bytecode. Each
+// instruction is a single opcode byte, followed by its operand, if it has one:
+// push takes a 64-bit little-endian integer, and the jumps and calls take a
+// 32-bit byte offset in the code. The instructions take their arguments from
+// the top of the stack, and push their results back: arithmetic operations,
+// comparisons (which push 1 or 0), conditional jumps, and print. Calls push the
+// return address to a separate call stack. The assembler reads one instruction
+// per line, with labels ending with a colon, and comments after a semicolon,
+// resolving the labels in a second pass, once their addresses are known.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    Push(i64),
+    Pop,
+    Dup,
+    Swap,
+    Over,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Neg,
+    Eq,
+    Lt,
+    Gt,
+    Jump(u32),
+    JumpIfZero(u32),
+    JumpIfNotZero(u32),
+    Call(u32),
+    Return,
+    Print,
+    Halt,
+}
+
+#[rustfmt::skip]
+const MNEMONICS: [&str; 21] = [
+    "push", "pop", "dup", "swap", "over", "add", "sub", "mul", "div", "mod", "neg",
+    "eq", "lt", "gt", "jmp", "jz", "jnz", "call", "ret", "print", "halt",
+];
+
+impl Instruction {
+    pub fn opcode(&self) -> u8 {
+        match self {
+            Instruction::Push(_) => 0,
+            Instruction::Pop => 1,
+            Instruction::Dup => 2,
+            Instruction::Swap => 3,
+            Instruction::Over => 4,
+            Instruction::Add => 5,
+            Instruction::Sub => 6,
+            Instruction::Mul => 7,
+            Instruction::Div => 8,
+            Instruction::Mod => 9,
+            Instruction::Neg => 10,
+            Instruction::Eq => 11,
+            Instruction::Lt => 12,
+            Instruction::Gt => 13,
+            Instruction::Jump(_) => 14,
+            Instruction::JumpIfZero(_) => 15,
+            Instruction::JumpIfNotZero(_) => 16,
+            Instruction::Call(_) => 17,
+            Instruction::Return => 18,
+            Instruction::Print => 19,
+            Instruction::Halt => 20,
+        }
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        MNEMONICS[self.opcode() as usize]
+    }
+
+    // The encoded length of an instruction, which only depends on its opcode.
+    fn length(opcode: u8) -> usize {
+        match opcode {
+            0 => 9,
+            14..=17 => 5,
+            _ => 1,
+        }
+    }
+
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.opcode());
+        match self {
+            Instruction::Push(value) => out.extend_from_slice(&value.to_le_bytes()),
+            Instruction::Jump(address)
+            | Instruction::JumpIfZero(address)
+            | Instruction::JumpIfNotZero(address)
+            | Instruction::Call(address) => out.extend_from_slice(&address.to_le_bytes()),
+            _ => {}
+        }
+    }
+
+    // Decodes the instruction at the start of the code, returning its length
+    // as well, or None if the opcode is invalid or the operand is truncated.
+    pub fn decode(code: &[u8]) -> Option<(Self, usize)> {
+        let opcode = *code.first()?;
+        let length = Self::length(opcode);
+        let operand = code.get(1..length)?;
+        let address = || u32::from_le_bytes(operand.try_into().unwrap());
+        let instruction = match opcode {
+            0 => Instruction::Push(i64::from_le_bytes(operand.try_into().unwrap())),
+            1 => Instruction::Pop,
+            2 => Instruction::Dup,
+            3 => Instruction::Swap,
+            4 => Instruction::Over,
+            5 => Instruction::Add,
+            6 => Instruction::Sub,
+            7 => Instruction::Mul,
+            8 => Instruction::Div,
+            9 => Instruction::Mod,
+            10 => Instruction::Neg,
+            11 => Instruction::Eq,
+            12 => Instruction::Lt,
+            13 => Instruction::Gt,
+            14 => Instruction::Jump(address()),
+            15 => Instruction::JumpIfZero(address()),
+            16 => Instruction::JumpIfNotZero(address()),
+            17 => Instruction::Call(address()),
+            18 => Instruction::Return,
+            19 => Instruction::Print,
+            20 => Instruction::Halt,
+            _ => return None,
+        };
+        Some((instruction, length))
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.mnemonic())?;
+        match self {
+            Instruction::Push(value) => write!(f, " {}", value),
+            Instruction::Jump(address)
+            | Instruction::JumpIfZero(address)
+            | Instruction::JumpIfNotZero(address)
+            | Instruction::Call(address) => write!(f, " {:04x}", address),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AssemblyErrorKind {
+    UnknownMnemonic(String),
+    MissingOperand,
+    UnexpectedOperand,
+    InvalidNumber(String),
+    UndefinedLabel(String),
+    DuplicateLabel(String),
+}
+
+impl fmt::Display for AssemblyErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssemblyErrorKind::UnknownMnemonic(name) => write!(f, "unknown mnemonic {}", name),
+            AssemblyErrorKind::MissingOperand => write!(f, "missing operand"),
+            AssemblyErrorKind::UnexpectedOperand => write!(f, "unexpected operand"),
+            AssemblyErrorKind::InvalidNumber(text) => write!(f, "invalid number {}", text),
+            AssemblyErrorKind::UndefinedLabel(name) => write!(f, "undefined label {}", name),
+            AssemblyErrorKind::DuplicateLabel(name) => write!(f, "duplicate label {}", name),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssemblyError {
+    pub kind: AssemblyErrorKind,
+    pub line: usize,
+}
+
+impl fmt::Display for AssemblyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+impl std::error::Error for AssemblyError {}
+
+pub fn assemble(source: &str) -> Result<Vec<u8>, AssemblyError> {
+    // The first pass collects the instructions and the addresses of the
+    // labels, and the second one encodes the instructions.
+    let mut labels = BTreeMap::new();
+    let mut parsed = Vec::new();
+    let mut address = 0;
+    for (index, line) in source.lines().enumerate() {
+        let number = index + 1;
+        let error = |kind| AssemblyError { kind, line: number };
+        let mut text = line.split(';').next().unwrap().trim();
+        while let Some((label, rest)) = text.split_once(':') {
+            let label = label.trim();
+            if labels.insert(label.to_string(), address).is_some() {
+                return Err(error(AssemblyErrorKind::DuplicateLabel(label.to_string())));
+            }
+            text = rest.trim();
+        }
+        let mut words = text.split_whitespace();
+        let Some(mnemonic) = words.next() else {
+            continue;
+        };
+        let opcode = MNEMONICS
+            .iter()
+            .position(|candidate| *candidate == mnemonic)
+            .ok_or_else(|| error(AssemblyErrorKind::UnknownMnemonic(mnemonic.to_string())))?
+            as u8;
+        let operand = words.next();
+        if words.next().is_some() || (operand.is_some() && Instruction::length(opcode) == 1) {
+            return Err(error(AssemblyErrorKind::UnexpectedOperand));
+        }
+        if operand.is_none() && Instruction::length(opcode) > 1 {
+            return Err(error(AssemblyErrorKind::MissingOperand));
+        }
+        parsed.push((number, opcode, operand));
+        address += Instruction::length(opcode) as u32;
+    }
+
+    let mut code = Vec::new();
+    for (number, opcode, operand) in parsed {
+        let error = |kind| AssemblyError { kind, line: number };
+        let target = || {
+            let label = operand.unwrap();
+            labels
+                .get(label)
+                .copied()
+                .ok_or_else(|| error(AssemblyErrorKind::UndefinedLabel(label.to_string())))
+        };
+        let instruction = match opcode {
+            0 => {
+                let text = operand.unwrap();
+                let value = text
+                    .parse()
+                    .map_err(|_| error(AssemblyErrorKind::InvalidNumber(text.to_string())))?;
+                Instruction::Push(value)
+            }
+            14 => Instruction::Jump(target()?),
+            15 => Instruction::JumpIfZero(target()?),
+            16 => Instruction::JumpIfNotZero(target()?),
+            17 => Instruction::Call(target()?),
+            _ => Instruction::decode(&[opcode]).unwrap().0,
+        };
+        instruction.encode(&mut code);
+    }
+    Ok(code)
+}
+
+// The instructions with their addresses, up to the first invalid one.
+pub fn disassemble(code: &[u8]) -> Vec<(usize, Instruction)> {
+    let mut instructions = Vec::new();
+    let mut address = 0;
+    while let Some((instruction, length)) = Instruction::decode(&code[address..]) {
+        instructions.push((address, instruction));
+        address += length;
+    }
+    instructions
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuntimeErrorKind {
+    InvalidInstruction,
+    InvalidAddress(u32),
+    StackUnderflow,
+    CallStackOverflow,
+    ArithmeticOverflow,
+    DivisionByZero,
+}
+
+impl fmt::Display for RuntimeErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuntimeErrorKind::InvalidInstruction => write!(f, "invalid instruction"),
+            RuntimeErrorKind::InvalidAddress(address) => {
+                write!(f, "invalid address {:04x}", address)
+            }
+            RuntimeErrorKind::StackUnderflow => write!(f, "stack underflow"),
+            RuntimeErrorKind::CallStackOverflow => write!(f, "call stack overflow"),
+            RuntimeErrorKind::ArithmeticOverflow => write!(f, "arithmetic overflow"),
+            RuntimeErrorKind::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+// The address is that of the instruction which failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuntimeError {
+    pub kind: RuntimeErrorKind,
+    pub address: usize,
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {:04x}", self.kind, self.address)
+    }
+}
+
+impl std::error::Error for RuntimeError {}
+
+pub const MAX_CALL_DEPTH: usize = 256;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Execution {
+    pub output: Vec<i64>,
+    pub stack: Vec<i64>,
+    pub steps: u64,
+    pub max_call_depth: usize,
+}
+
+// Runs the code from its start, until a halt instruction, or the end of the
+// code, with the arguments on the stack, the last one on the top.
+pub fn run(code: &[u8], arguments: &[i64]) -> Result<Execution, RuntimeError> {
+    let mut stack = arguments.to_vec();
+    let mut calls = Vec::new();
+    let mut execution = Execution {
+        output: Vec::new(),
+        stack: Vec::new(),
+        steps: 0,
+        max_call_depth: 0,
+    };
+    let mut address = 0;
+    while address < code.len() {
+        let error = |kind| RuntimeError { kind, address };
+        let (instruction, length) = Instruction::decode(&code[address..])
+            .ok_or(error(RuntimeErrorKind::InvalidInstruction))?;
+        execution.steps += 1;
+        let mut next = address + length;
+        let mut pop = || stack.pop().ok_or(error(RuntimeErrorKind::StackUnderflow));
+        let mut jump = |target: u32| {
+            if target as usize >= code.len() {
+                return Err(error(RuntimeErrorKind::InvalidAddress(target)));
+            }
+            next = target as usize;
+            Ok(())
+        };
+        match instruction {
+            Instruction::Push(value) => stack.push(value),
+            Instruction::Pop => {
+                pop()?;
+            }
+            Instruction::Dup => {
+                let top = pop()?;
+                stack.extend([top, top]);
+            }
+            Instruction::Swap => {
+                let (top, below) = (pop()?, pop()?);
+                stack.extend([top, below]);
+            }
+            Instruction::Over => {
+                let (top, below) = (pop()?, pop()?);
+                stack.extend([below, top, below]);
+            }
+            Instruction::Neg => {
+                let top = pop()?;
+                let value = top
+                    .checked_neg()
+                    .ok_or(error(RuntimeErrorKind::ArithmeticOverflow))?;
+                stack.push(value);
+            }
+            Instruction::Add
+            | Instruction::Sub
+            | Instruction::Mul
+            | Instruction::Div
+            | Instruction::Mod
+            | Instruction::Eq
+            | Instruction::Lt
+            | Instruction::Gt => {
+                let (right, left) = (pop()?, pop()?);
+                let result = match instruction {
+                    Instruction::Add => left.checked_add(right),
+                    Instruction::Sub => left.checked_sub(right),
+                    Instruction::Mul => left.checked_mul(right),
+                    Instruction::Div | Instruction::Mod if right == 0 => {
+                        return Err(error(RuntimeErrorKind::DivisionByZero))
+                    }
+                    Instruction::Div => left.checked_div(right),
+                    Instruction::Mod => left.checked_rem(right),
+                    Instruction::Eq => Some((left == right) as i64),
+                    Instruction::Lt => Some((left < right) as i64),
+                    _ => Some((left > right) as i64),
+                };
+                stack.push(result.ok_or(error(RuntimeErrorKind::ArithmeticOverflow))?);
+            }
+            Instruction::Jump(target) => jump(target)?,
+            Instruction::JumpIfZero(target) => {
+                if pop()? == 0 {
+                    jump(target)?;
+                }
+            }
+            Instruction::JumpIfNotZero(target) => {
+                if pop()? != 0 {
+                    jump(target)?;
+                }
+            }
+            Instruction::Call(target) => {
+                if calls.len() == MAX_CALL_DEPTH {
+                    return Err(error(RuntimeErrorKind::CallStackOverflow));
+                }
+                jump(target)?;
+                calls.push(address + length);
+                execution.max_call_depth = execution.max_call_depth.max(calls.len());
+            }
+            Instruction::Return => match calls.pop() {
+                Some(target) => next = target,
+                None => return Err(error(RuntimeErrorKind::StackUnderflow)),
+            },
+            Instruction::Print => {
+                let top = pop()?;
+                execution.output.push(top);
+            }
+            Instruction::Halt => break,
+        }
+        address = next;
+    }
+    execution.stack = stack;
+    Ok(execution)
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+// The accumulator and the counter are kept on the stack, and the product is
+// built from the top down: acc n -> acc*n n-1.
+const ITERATIVE: &str = "
+        push 1
+        swap            ; acc n
+loop:   dup
+        jz done
+        swap            ; n acc
+        over            ; n acc n
+        mul             ; n acc*n
+        swap            ; acc*n n
+        push 1
+        sub             ; acc*n n-1
+        jmp loop
+done:   pop
+        print
+        halt
+";
+
+const RECURSIVE: &str = "
+        call factorial
+        print
+        halt
+
+; n -> n!
+factorial:
+        dup
+        push 1
+        gt
+        jz base         ; n <= 1
+        dup
+        push 1
+        sub
+        call factorial  ; n (n-1)!
+        mul
+        ret
+base:   pop
+        push 1
+        ret
+";
+
+pub fn demo(harness: &mut Harness) {
+    let iterative = assemble(ITERATIVE).unwrap();
+    let recursive = assemble(RECURSIVE).unwrap();
+    let factorials: Vec<i64> = (0..=20)
+        .scan(1i64, |product, n| {
+            *product *= n.max(1);
+            Some(*product)
+        })
+        .collect();
+    for n in [0, 1, 5, 10, 20] {
+        for (name, code) in [("iterative", &iterative), ("recursive", &recursive)] {
+            test!(
+                harness,
+                &format!("the {} factorial of {}", name, n),
+                run(code, &[n]).map(|execution| execution.output),
+                Ok(vec![factorials[n as usize]])
+            );
+        }
+    }
+    test!(
+        harness,
+        "the recursion depth follows the argument",
+        run(&recursive, &[20]).map(|execution| execution.max_call_depth),
+        Ok(20)
+    );
+    test!(
+        harness,
+        "the stack is left empty",
+        run(&iterative, &[7]).map(|execution| execution.stack),
+        Ok(vec![])
+    );
+    test!(
+        harness,
+        "the factorial of 21 overflows",
+        run(&iterative, &[21]).map_err(|error| error.kind),
+        Err(RuntimeErrorKind::ArithmeticOverflow)
+    );
+    test!(
+        harness,
+        "deep recursion exhausts the call stack",
+        run(&recursive, &[1000]).map_err(|error| error.kind),
+        Err(RuntimeErrorKind::CallStackOverflow)
+    );
+    test!(
+        harness,
+        "the recursive program has 16 instructions",
+        disassemble(&recursive).len(),
+        16
+    );
+    let mut reencoded = Vec::new();
+    for (_, instruction) in disassemble(&iterative) {
+        instruction.encode(&mut reencoded);
+    }
+    test!(
+        harness,
+        "decoding and encoding gives the same code",
+        reencoded,
+        iterative.clone()
+    );
+
+    test!(
+        harness,
+        "division by zero",
+        run(&assemble("push 1\npush 0\ndiv").unwrap(), &[]).map_err(|e| e.to_string()),
+        Err("division by zero at 0012".to_string())
+    );
+    test!(
+        harness,
+        "stack underflow",
+        run(&assemble("push 1\nadd").unwrap(), &[]).map_err(|e| e.to_string()),
+        Err("stack underflow at 0009".to_string())
+    );
+    test!(
+        harness,
+        "an invalid opcode",
+        run(&[1, 255], &[1]).map_err(|e| e.to_string()),
+        Err("invalid instruction at 0001".to_string())
+    );
+    let broken = [
+        ("push 1\nfrobnicate", "line 2: unknown mnemonic frobnicate"),
+        ("push", "line 1: missing operand"),
+        ("add 3", "line 1: unexpected operand"),
+        ("push 12x", "line 1: invalid number 12x"),
+        ("jmp nowhere", "line 1: undefined label nowhere"),
+        ("a: halt\na: halt", "line 2: duplicate label a"),
+    ];
+    for (source, expected) in broken {
+        test!(
+            harness,
+            &format!("the error in {:?}", source),
+            assemble(source).map_err(|error| error.to_string()),
+            Err(expected.to_string())
+        );
+    }
+
+    note!(harness, "The recursive factorial:");
+    for (address, instruction) in disassemble(&recursive) {
+        note!(harness, "  {:04x}  {}", address, instruction);
+    }
+    note!(harness);
+    note!(
+        harness,
+        "{:>4} {:>22} {:>10} {:>10}",
+        "n",
+        "n!",
+        "iterative",
+        "recursive"
+    );
+    for n in [1, 5, 10, 15, 20] {
+        let loop_steps = run(&iterative, &[n]).unwrap().steps;
+        let call_steps = run(&recursive, &[n]).unwrap().steps;
+        note!(
+            harness,
+            "{:>4} {:>22} {:>10} {:>10}",
+            n,
+            factorials[n as usize],
+            loop_steps,
+            call_steps
+        );
+    }
+    note!(harness);
+}
@@ -0,0 +1,802 @@
+// websocket.rs
+// The WebSocket protocol of RFC 6455, which turns an HTTP connection into a
+// channel of messages in both directions. The client asks for the upgrade with
+// a random key, and the server proves that it understood the request, by
+// hashing the key with a fixed GUID, with the SHA-1 snippet, and sending the
+// digest back in Base64, with the encoding snippet. After the handshake, both
+// sides send frames: a header with the final-fragment bit, the opcode, and the
+// payload length in 7, 16, or 64 bits, and then the payload. The frames of the
+// client are masked, XORed with a random key sent along with them, so a proxy
+// which does not understand the protocol can not be tricked into caching a
+// payload which looks like an HTTP response. A long message may be cut into
+// fragments, a first frame with its type, and continuation frames after it,
+// while the control frames, ping, pong, and close, are short, and can arrive
+// between the fragments. The demonstration runs an echo server and a client
+// against each other, on localhost.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::encoding::BASE64;
+use crate::httpclient::{read_headers, read_line, Headers, HttpError};
+use crate::rng::{RandomSource, SplitMix64};
+use crate::sha1::Sha1;
+use crate::url::Url;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+
+const GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+const MAX_CONTROL_PAYLOAD: usize = 125;
+const MAX_MESSAGE: usize = 16 << 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    Io,
+    Handshake,
+    Protocol,
+    InvalidUtf8,
+    TooLarge,
+    Closed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WsError {
+    pub kind: ErrorKind,
+    pub detail: String,
+}
+
+impl WsError {
+    fn new(kind: ErrorKind, detail: impl Into<String>) -> Self {
+        Self {
+            kind,
+            detail: detail.into(),
+        }
+    }
+}
+
+impl fmt::Display for WsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.kind {
+            ErrorKind::Io => "I/O error",
+            ErrorKind::Handshake => "handshake failed",
+            ErrorKind::Protocol => "protocol error",
+            ErrorKind::InvalidUtf8 => "invalid UTF-8 in a text message",
+            ErrorKind::TooLarge => "message too large",
+            ErrorKind::Closed => "connection closed",
+        };
+        write!(f, "{}: {}", message, self.detail)
+    }
+}
+
+impl std::error::Error for WsError {}
+
+impl From<io::Error> for WsError {
+    fn from(error: io::Error) -> Self {
+        WsError::new(ErrorKind::Io, error.to_string())
+    }
+}
+
+impl From<HttpError> for WsError {
+    fn from(error: HttpError) -> Self {
+        WsError::new(ErrorKind::Handshake, error.to_string())
+    }
+}
+
+// The value the server answers the key of the client with.
+pub fn accept_key(key: &str) -> String {
+    BASE64.encode(&Sha1::digest(format!("{}{}", key, GUID).as_bytes()))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Opcode {
+    Continuation,
+    Text,
+    Binary,
+    Close,
+    Ping,
+    Pong,
+}
+
+impl Opcode {
+    pub fn code(self) -> u8 {
+        match self {
+            Opcode::Continuation => 0x0,
+            Opcode::Text => 0x1,
+            Opcode::Binary => 0x2,
+            Opcode::Close => 0x8,
+            Opcode::Ping => 0x9,
+            Opcode::Pong => 0xa,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0x0 => Some(Opcode::Continuation),
+            0x1 => Some(Opcode::Text),
+            0x2 => Some(Opcode::Binary),
+            0x8 => Some(Opcode::Close),
+            0x9 => Some(Opcode::Ping),
+            0xa => Some(Opcode::Pong),
+            _ => None,
+        }
+    }
+
+    pub fn is_control(self) -> bool {
+        self.code() & 0x8 != 0
+    }
+}
+
+// Masking and unmasking are the same XOR.
+pub fn apply_mask(payload: &mut [u8], mask: [u8; 4]) {
+    for (index, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[index % 4];
+    }
+}
+
+// The payload is kept unmasked, and the mask is applied when it is encoded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    pub fin: bool,
+    pub opcode: Opcode,
+    pub mask: Option<[u8; 4]>,
+    pub payload: Vec<u8>,
+}
+
+fn protocol(detail: &str) -> WsError {
+    WsError::new(ErrorKind::Protocol, detail)
+}
+
+impl Frame {
+    pub fn new(opcode: Opcode, payload: impl Into<Vec<u8>>) -> Self {
+        Self {
+            fin: true,
+            opcode,
+            mask: None,
+            payload: payload.into(),
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let length = self.payload.len();
+        let mut bytes = Vec::with_capacity(length + 14);
+        bytes.push((self.fin as u8) << 7 | self.opcode.code());
+        let masked = (self.mask.is_some() as u8) << 7;
+        if length < 126 {
+            bytes.push(masked | length as u8);
+        } else if length <= u16::MAX as usize {
+            bytes.push(masked | 126);
+            bytes.extend_from_slice(&(length as u16).to_be_bytes());
+        } else {
+            bytes.push(masked | 127);
+            bytes.extend_from_slice(&(length as u64).to_be_bytes());
+        }
+        let start = bytes.len() + if self.mask.is_some() { 4 } else { 0 };
+        if let Some(mask) = self.mask {
+            bytes.extend_from_slice(&mask);
+        }
+        bytes.extend_from_slice(&self.payload);
+        if let Some(mask) = self.mask {
+            apply_mask(&mut bytes[start..], mask);
+        }
+        bytes
+    }
+
+    // Decodes a frame from the start of the bytes, and returns it with its
+    // length, or nothing, if the bytes end before the frame does.
+    pub fn decode(bytes: &[u8]) -> Result<Option<(Frame, usize)>, WsError> {
+        let (Some(&first), Some(&second)) = (bytes.first(), bytes.get(1)) else {
+            return Ok(None);
+        };
+        // The reserved bits are for extensions, and none was negotiated.
+        if first & 0x70 != 0 {
+            return Err(protocol("reserved bits are set"));
+        }
+        let opcode = Opcode::from_code(first & 0x0f).ok_or_else(|| protocol("unknown opcode"))?;
+        let fin = first & 0x80 != 0;
+        let (length, mut position) = match second & 0x7f {
+            126 => match bytes.get(2..4) {
+                Some(length) => (u16::from_be_bytes([length[0], length[1]]) as u64, 4),
+                None => return Ok(None),
+            },
+            127 => match bytes.get(2..10) {
+                Some(length) => (u64::from_be_bytes(length.try_into().unwrap()), 10),
+                None => return Ok(None),
+            },
+            length => (length as u64, 2),
+        };
+        if length >> 63 != 0 {
+            return Err(protocol("the length has its highest bit set"));
+        }
+        if opcode.is_control() && (!fin || length > MAX_CONTROL_PAYLOAD as u64) {
+            return Err(protocol("control frames are short, and not fragmented"));
+        }
+        if length > MAX_MESSAGE as u64 {
+            return Err(WsError::new(
+                ErrorKind::TooLarge,
+                format!("{} bytes", length),
+            ));
+        }
+        let mask = if second & 0x80 != 0 {
+            let Some(mask) = bytes.get(position..position + 4) else {
+                return Ok(None);
+            };
+            position += 4;
+            Some([mask[0], mask[1], mask[2], mask[3]])
+        } else {
+            None
+        };
+        let end = position + length as usize;
+        let Some(payload) = bytes.get(position..end) else {
+            return Ok(None);
+        };
+        let mut payload = payload.to_vec();
+        if let Some(mask) = mask {
+            apply_mask(&mut payload, mask);
+        }
+        let frame = Frame {
+            fin,
+            opcode,
+            mask,
+            payload,
+        };
+        Ok(Some((frame, end)))
+    }
+
+    // Reads the frame in two steps: the header, whose length is known from
+    // its first two bytes, and then the payload.
+    pub fn read(reader: &mut impl BufRead) -> Result<Frame, WsError> {
+        let mut header = [0; 14];
+        read_all(reader, &mut header[..2])?;
+        let extended = match header[1] & 0x7f {
+            126 => 2,
+            127 => 8,
+            _ => 0,
+        };
+        let mask = if header[1] & 0x80 != 0 { 4 } else { 0 };
+        let size = 2 + extended + mask;
+        read_all(reader, &mut header[2..size])?;
+        let mut bytes = header[..size].to_vec();
+        // Decoding the header alone checks the length, before it is allocated.
+        Frame::decode(&bytes)?;
+        let length = match extended {
+            2 => u16::from_be_bytes([header[2], header[3]]) as usize,
+            8 => u64::from_be_bytes(header[2..10].try_into().unwrap()) as usize,
+            _ => (header[1] & 0x7f) as usize,
+        };
+        bytes.resize(size + length, 0);
+        read_all(reader, &mut bytes[size..])?;
+        Ok(Frame::decode(&bytes)?.expect("the frame is complete").0)
+    }
+}
+
+fn read_all(reader: &mut impl BufRead, buffer: &mut [u8]) -> Result<(), WsError> {
+    reader
+        .read_exact(buffer)
+        .map_err(|error| match error.kind() {
+            io::ErrorKind::UnexpectedEof => {
+                WsError::new(ErrorKind::Closed, "the connection ended in a frame")
+            }
+            _ => error.into(),
+        })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Message {
+    Text(String),
+    Binary(Vec<u8>),
+    Ping(Vec<u8>),
+    Pong(Vec<u8>),
+    // The status code, and the reason, if the peer gave one.
+    Close(Option<(u16, String)>),
+}
+
+pub const NORMAL_CLOSURE: u16 = 1000;
+pub const PROTOCOL_ERROR: u16 = 1002;
+pub const INVALID_DATA: u16 = 1007;
+pub const MESSAGE_TOO_BIG: u16 = 1009;
+
+// Cuts the message into frames with payloads of at most the given size. The
+// control messages are never fragmented.
+pub fn fragment(message: &Message, size: usize) -> Vec<Frame> {
+    let (opcode, payload) = match message {
+        Message::Text(text) => (Opcode::Text, text.as_bytes().to_vec()),
+        Message::Binary(bytes) => (Opcode::Binary, bytes.clone()),
+        Message::Ping(bytes) => return vec![Frame::new(Opcode::Ping, bytes.clone())],
+        Message::Pong(bytes) => return vec![Frame::new(Opcode::Pong, bytes.clone())],
+        Message::Close(None) => return vec![Frame::new(Opcode::Close, Vec::new())],
+        Message::Close(Some((code, reason))) => {
+            let mut payload = code.to_be_bytes().to_vec();
+            payload.extend_from_slice(reason.as_bytes());
+            return vec![Frame::new(Opcode::Close, payload)];
+        }
+    };
+    if payload.is_empty() {
+        return vec![Frame::new(opcode, payload)];
+    }
+    let count = payload.len().div_ceil(size.max(1));
+    payload
+        .chunks(size.max(1))
+        .enumerate()
+        .map(|(index, chunk)| Frame {
+            fin: index + 1 == count,
+            opcode: if index == 0 {
+                opcode
+            } else {
+                Opcode::Continuation
+            },
+            mask: None,
+            payload: chunk.to_vec(),
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    Client,
+    Server,
+}
+
+pub struct WebSocket {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    role: Role,
+    random: SplitMix64,
+    fragment_size: usize,
+    closing: bool,
+}
+
+// The masks only need to be unpredictable to the page which opened the
+// socket, which a browser would run, so a seed from the clock is enough here.
+fn random_source() -> SplitMix64 {
+    let seed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |time| time.as_nanos() as u64);
+    SplitMix64::new(seed ^ (std::process::id() as u64) << 32)
+}
+
+fn handshake_error(detail: &str) -> WsError {
+    WsError::new(ErrorKind::Handshake, detail)
+}
+
+fn has_token(headers: &Headers, name: &str, token: &str) -> bool {
+    headers
+        .get_all(name)
+        .flat_map(|value| value.split(','))
+        .any(|value| value.trim().eq_ignore_ascii_case(token))
+}
+
+impl WebSocket {
+    // Opens a connection to the ws:// URL, and asks for the upgrade.
+    pub fn connect(url: &str) -> Result<Self, WsError> {
+        let url = Url::parse(url).map_err(|error| handshake_error(&error.to_string()))?;
+        if url.scheme != "ws" {
+            return Err(handshake_error("only the ws scheme is supported"));
+        }
+        let host = url
+            .host
+            .clone()
+            .ok_or_else(|| handshake_error("missing host"))?;
+        let port = url.effective_port().unwrap_or(80);
+        let stream = TcpStream::connect((host.as_str(), port))?;
+        let mut random = random_source();
+        let mut key = [0; 16];
+        random.fill_bytes(&mut key);
+        let key = BASE64.encode(&key);
+        let path = if url.path.is_empty() { "/" } else { &url.path };
+        let target = match &url.query {
+            Some(query) => format!("{}?{}", path, query),
+            None => path.to_string(),
+        };
+        let authority = match url.port {
+            Some(port) => format!("{}:{}", host, port),
+            None => host,
+        };
+        let request = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+             Sec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n\r\n",
+            target, authority, key
+        );
+        let mut writer = stream.try_clone()?;
+        writer.write_all(request.as_bytes())?;
+
+        let mut reader = BufReader::new(stream);
+        let line = read_line(
+            &mut reader,
+            crate::httpclient::ErrorKind::MalformedStatusLine,
+        )?;
+        let mut headers = Headers::new();
+        read_headers(&mut reader, &mut headers)?;
+        if line.split(' ').nth(1) != Some("101") {
+            return Err(handshake_error(&format!("the server answered {}", line)));
+        }
+        if !has_token(&headers, "Upgrade", "websocket")
+            || headers.get("Sec-WebSocket-Accept") != Some(&accept_key(&key))
+        {
+            return Err(handshake_error("the server did not accept the key"));
+        }
+        Ok(Self {
+            reader,
+            writer,
+            role: Role::Client,
+            random,
+            fragment_size: 4096,
+            closing: false,
+        })
+    }
+
+    // Reads the upgrade request of a client, and accepts it, or answers it with
+    // a bad request.
+    pub fn accept(stream: TcpStream) -> Result<Self, WsError> {
+        let mut writer = stream.try_clone()?;
+        let mut reader = BufReader::new(stream);
+        let request = crate::httpserver::read_request(&mut reader)?
+            .ok_or_else(|| handshake_error("the client closed the connection"))?;
+        let headers = &request.headers;
+        let key = headers
+            .get("Sec-WebSocket-Key")
+            .filter(|key| BASE64.decode(key).is_ok_and(|key| key.len() == 16));
+        let version = headers.get("Sec-WebSocket-Version");
+        let valid = request.method == "GET"
+            && has_token(headers, "Upgrade", "websocket")
+            && has_token(headers, "Connection", "upgrade")
+            && version == Some("13");
+        let Some(key) = key.filter(|_| valid) else {
+            crate::httpserver::Response::error(400)
+                .header("Sec-WebSocket-Version", "13")
+                .header("Connection", "close")
+                .write_to(&mut writer, false)?;
+            return Err(handshake_error("not a valid upgrade request"));
+        };
+        let response = format!(
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+             Sec-WebSocket-Accept: {}\r\n\r\n",
+            accept_key(key)
+        );
+        writer.write_all(response.as_bytes())?;
+        Ok(Self {
+            reader,
+            writer,
+            role: Role::Server,
+            random: random_source(),
+            fragment_size: 4096,
+            closing: false,
+        })
+    }
+
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    // The largest payload of the frames sent, before a message is fragmented.
+    pub fn set_fragment_size(&mut self, size: usize) {
+        self.fragment_size = size.max(1);
+    }
+
+    fn write_frame(&mut self, mut frame: Frame) -> Result<(), WsError> {
+        if self.role == Role::Client {
+            frame.mask = Some(self.random.next_u32().to_be_bytes());
+        }
+        self.writer.write_all(&frame.encode())?;
+        Ok(())
+    }
+
+    pub fn send(&mut self, message: Message) -> Result<(), WsError> {
+        if self.closing {
+            return Err(WsError::new(ErrorKind::Closed, "the close frame was sent"));
+        }
+        if let Message::Ping(payload) | Message::Pong(payload) = &message {
+            if payload.len() > MAX_CONTROL_PAYLOAD {
+                return Err(protocol("control payloads are at most 125 bytes"));
+            }
+        }
+        self.closing = matches!(message, Message::Close(_));
+        for frame in fragment(&message, self.fragment_size) {
+            self.write_frame(frame)?;
+        }
+        Ok(())
+    }
+
+    // Sends the close frame, if it was not sent yet, and ends the connection
+    // with the error.
+    fn fail(&mut self, code: u16, error: WsError) -> WsError {
+        if !self.closing {
+            let _ = self.send(Message::Close(Some((code, error.detail.clone()))));
+        }
+        error
+    }
+
+    // Reads the next message, with its fragments put together. A ping is
+    // answered with a pong right away, and a close frame with a close frame,
+    // and both are returned, too. The other side has to mask its frames if it
+    // is the client, and must not if it is the server.
+    pub fn receive(&mut self) -> Result<Message, WsError> {
+        let mut partial: Option<(Opcode, Vec<u8>)> = None;
+        loop {
+            let frame = match Frame::read(&mut self.reader) {
+                Ok(frame) => frame,
+                Err(error) if error.kind == ErrorKind::TooLarge => {
+                    return Err(self.fail(MESSAGE_TOO_BIG, error))
+                }
+                Err(error) if error.kind == ErrorKind::Protocol => {
+                    return Err(self.fail(PROTOCOL_ERROR, error))
+                }
+                Err(error) => return Err(error),
+            };
+            if frame.mask.is_some() != (self.role == Role::Server) {
+                return Err(self.fail(PROTOCOL_ERROR, protocol("wrong masking for the role")));
+            }
+            let message = match frame.opcode {
+                Opcode::Ping => {
+                    if !self.closing {
+                        self.write_frame(Frame::new(Opcode::Pong, frame.payload.clone()))?;
+                    }
+                    return Ok(Message::Ping(frame.payload));
+                }
+                Opcode::Pong => return Ok(Message::Pong(frame.payload)),
+                Opcode::Close => {
+                    let status = match frame.payload.len() {
+                        0 => None,
+                        1 => return Err(self.fail(PROTOCOL_ERROR, protocol("truncated close"))),
+                        _ => {
+                            let code = u16::from_be_bytes([frame.payload[0], frame.payload[1]]);
+                            let reason = String::from_utf8_lossy(&frame.payload[2..]);
+                            Some((code, reason.into_owned()))
+                        }
+                    };
+                    if !self.closing {
+                        let echo = status.as_ref().map(|(code, _)| (*code, String::new()));
+                        self.send(Message::Close(echo))?;
+                    }
+                    return Ok(Message::Close(status));
+                }
+                Opcode::Continuation => match partial.as_mut() {
+                    Some((_, payload)) => {
+                        payload.extend_from_slice(&frame.payload);
+                        if payload.len() > MAX_MESSAGE {
+                            let error = WsError::new(ErrorKind::TooLarge, "too many fragments");
+                            return Err(self.fail(MESSAGE_TOO_BIG, error));
+                        }
+                        if !frame.fin {
+                            continue;
+                        }
+                        partial.take().unwrap()
+                    }
+                    None => return Err(self.fail(PROTOCOL_ERROR, protocol("nothing to continue"))),
+                },
+                opcode => {
+                    if partial.is_some() {
+                        let error = protocol("a new message in the middle of another one");
+                        return Err(self.fail(PROTOCOL_ERROR, error));
+                    }
+                    if !frame.fin {
+                        partial = Some((opcode, frame.payload));
+                        continue;
+                    }
+                    (opcode, frame.payload)
+                }
+            };
+            return match message {
+                (Opcode::Text, payload) => match String::from_utf8(payload) {
+                    Ok(text) => Ok(Message::Text(text)),
+                    Err(_) => {
+                        let error = WsError::new(ErrorKind::InvalidUtf8, "in a text message");
+                        Err(self.fail(INVALID_DATA, error))
+                    }
+                },
+                (_, payload) => Ok(Message::Binary(payload)),
+            };
+        }
+    }
+
+    // Starts the closing handshake, and waits for the close frame of the
+    // other side, skipping the messages which arrive before it.
+    pub fn close(mut self, code: u16, reason: &str) -> Result<Option<(u16, String)>, WsError> {
+        self.send(Message::Close(Some((code, reason.to_string()))))?;
+        loop {
+            if let Message::Close(status) = self.receive()? {
+                return Ok(status);
+            }
+        }
+    }
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+use std::net::TcpListener;
+use std::thread;
+
+// Echoes the messages of each client, until it closes the connection.
+fn echo_server(listener: TcpListener, clients: usize) -> Vec<String> {
+    let mut log = Vec::new();
+    for stream in listener.incoming().take(clients) {
+        let mut socket = match WebSocket::accept(stream.unwrap()) {
+            Ok(socket) => socket,
+            Err(error) => {
+                log.push(error.to_string());
+                continue;
+            }
+        };
+        loop {
+            match socket.receive() {
+                Ok(Message::Text(text)) => {
+                    log.push(format!("text of {} bytes", text.len()));
+                    let _ = socket.send(Message::Text(text));
+                }
+                Ok(Message::Binary(bytes)) => {
+                    log.push(format!("binary of {} bytes", bytes.len()));
+                    let _ = socket.send(Message::Binary(bytes));
+                }
+                Ok(Message::Ping(payload)) => {
+                    log.push(format!("ping {:?}", String::from_utf8_lossy(&payload)))
+                }
+                Ok(Message::Pong(_)) => log.push(String::from("pong")),
+                Ok(Message::Close(status)) => {
+                    log.push(format!("close {:?}", status));
+                    break;
+                }
+                Err(error) => {
+                    log.push(error.to_string());
+                    break;
+                }
+            }
+        }
+    }
+    log
+}
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "the accept key of the example in the RFC",
+        accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+        "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+    );
+
+    let hello = Frame::new(Opcode::Text, "Hello");
+    let masked = Frame {
+        mask: Some([0x37, 0xfa, 0x21, 0x3d]),
+        ..hello.clone()
+    };
+    test!(
+        harness,
+        "the frames of the examples in the RFC are encoded",
+        [hello.encode(), masked.encode()],
+        [
+            vec![0x81, 0x05, 0x48, 0x65, 0x6c, 0x6c, 0x6f],
+            vec![0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58]
+        ]
+    );
+    test!(
+        harness,
+        "a masked frame is decoded, and unmasked",
+        Frame::decode(&masked.encode()),
+        Ok(Some((masked.clone(), 11)))
+    );
+    test!(
+        harness,
+        "the length takes 16 or 64 bits, when it does not fit into 7",
+        [256, 65536]
+            .map(|length| Frame::new(Opcode::Binary, vec![0; length]).encode()[..10].to_vec()),
+        [
+            vec![0x82, 0x7e, 0x01, 0x00, 0, 0, 0, 0, 0, 0],
+            vec![0x82, 0x7f, 0, 0, 0, 0, 0, 1, 0, 0]
+        ]
+    );
+    test!(
+        harness,
+        "an incomplete frame needs more bytes",
+        [1, 3, 6].map(|length| Frame::decode(&masked.encode()[..length])),
+        [Ok(None), Ok(None), Ok(None)]
+    );
+    test!(
+        harness,
+        "invalid frames are rejected",
+        [
+            Frame::decode(&[0xc1, 0x00]).map_err(|error| error.kind),
+            Frame::decode(&[0x83, 0x00]).map_err(|error| error.kind),
+            Frame::decode(&[0x09, 0x00]).map_err(|error| error.kind),
+            Frame::decode(&[0x89, 0x7e, 0x00, 0x7e]).map_err(|error| error.kind),
+        ],
+        [
+            Err(ErrorKind::Protocol),
+            Err(ErrorKind::Protocol),
+            Err(ErrorKind::Protocol),
+            Err(ErrorKind::Protocol)
+        ]
+    );
+    let frames = fragment(&Message::Text(String::from("Hello")), 3);
+    test!(
+        harness,
+        "a message is fragmented like in the RFC",
+        frames.iter().map(Frame::encode).collect::<Vec<_>>(),
+        [
+            vec![0x01, 0x03, 0x48, 0x65, 0x6c],
+            vec![0x80, 0x02, 0x6c, 0x6f]
+        ]
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = thread::spawn(move || echo_server(listener, 2));
+
+    let rejected = crate::httpclient::Client::new().get(&format!("http://{}/", address));
+    test!(
+        harness,
+        "a plain HTTP request is not upgraded",
+        rejected.map(|response| (
+            response.status,
+            response
+                .headers
+                .get("Sec-WebSocket-Version")
+                .map(String::from)
+        )),
+        Ok((400, Some(String::from("13"))))
+    );
+
+    let mut socket = WebSocket::connect(&format!("ws://{}/echo", address)).unwrap();
+    socket.set_fragment_size(1000);
+    socket
+        .send(Message::Text(String::from("Hello, WebSocket! ✓")))
+        .unwrap();
+    test!(
+        harness,
+        "a text message is echoed",
+        socket.receive(),
+        Ok(Message::Text(String::from("Hello, WebSocket! ✓")))
+    );
+    let mut random = SplitMix64::new(85);
+    let mut large = vec![0; 100_000];
+    random.fill_bytes(&mut large);
+    socket.send(Message::Binary(large.clone())).unwrap();
+    test!(
+        harness,
+        "a large binary message survives the fragments, both ways",
+        socket.receive(),
+        Ok(Message::Binary(large))
+    );
+    socket
+        .send(Message::Ping(b"are you there?".to_vec()))
+        .unwrap();
+    test!(
+        harness,
+        "a ping is answered with a pong",
+        socket.receive(),
+        Ok(Message::Pong(b"are you there?".to_vec()))
+    );
+    test!(
+        harness,
+        "an overly long ping is refused",
+        socket
+            .send(Message::Ping(vec![0; 126]))
+            .map_err(|error| error.kind),
+        Err(ErrorKind::Protocol)
+    );
+    test!(
+        harness,
+        "the closing handshake returns the status of the server",
+        socket.close(NORMAL_CLOSURE, "done"),
+        Ok(Some((NORMAL_CLOSURE, String::new())))
+    );
+
+    let log = server.join().unwrap();
+    test!(
+        harness,
+        "the server saw the messages of the client",
+        log[1..].to_vec(),
+        [
+            "text of 21 bytes",
+            "binary of 100000 bytes",
+            "ping \"are you there?\"",
+            "close Some((1000, \"done\"))"
+        ]
+        .map(String::from)
+    );
+    note!(harness, "The log of the echo server:");
+    for line in log {
+        note!(harness, "    {}", line);
+    }
+}
@@ -0,0 +1,282 @@
+// wordwrap.rs
+// Wrapping text to a width, measured in terminal columns with the leftpad
+// snippet, so wide characters count twice, and combining ones not at all. The
+// greedy algorithm puts as many words on each line as fit, which is what most
+// editors do. Each line of the input is wrapped on its own, so existing line
+// breaks and empty lines are kept, while the other runs of whitespace become
+// single spaces. Words longer than a line are broken, unless that is turned
+// off, when they overflow instead. The first line of each input line can have a
+// different indent than the rest, which makes hanging indents for lists and
+// definitions. Full justification distributes extra spaces between the words
+// of each line, except for the last one of a paragraph, giving the first gaps
+// one more space when they can not be shared equally. Along with leftpad, and
+// the table printer, which wraps its cells with it, this is one of the text
+// utilities of the collection.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use crate::leftpad::{char_width, display_width};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Wrapper {
+    width: usize,
+    initial_indent: String,
+    subsequent_indent: String,
+    justify: bool,
+    break_words: bool,
+}
+
+// Splits off the longest prefix which fits in the width, but at least one
+// character, so that the splitting always makes progress.
+fn split_at_width(word: &str, width: usize) -> (String, String) {
+    let mut used = 0;
+    let mut end = 0;
+    for (index, c) in word.char_indices() {
+        if used + char_width(c) > width && index > 0 {
+            break;
+        }
+        used += char_width(c);
+        end = index + c.len_utf8();
+    }
+    (word[..end].to_string(), word[end..].to_string())
+}
+
+impl Wrapper {
+    pub fn new(width: usize) -> Self {
+        Self {
+            width,
+            initial_indent: String::new(),
+            subsequent_indent: String::new(),
+            justify: false,
+            break_words: true,
+        }
+    }
+
+    pub fn initial_indent(mut self, indent: &str) -> Self {
+        self.initial_indent = indent.to_string();
+        self
+    }
+
+    pub fn subsequent_indent(mut self, indent: &str) -> Self {
+        self.subsequent_indent = indent.to_string();
+        self
+    }
+
+    pub fn justify(mut self) -> Self {
+        self.justify = true;
+        self
+    }
+
+    pub fn break_words(mut self, break_words: bool) -> Self {
+        self.break_words = break_words;
+        self
+    }
+
+    fn indent(&self, line: usize) -> &str {
+        if line == 0 {
+            &self.initial_indent
+        } else {
+            &self.subsequent_indent
+        }
+    }
+
+    // The room for words on a line, which is at least one column, even if the
+    // indent is wider than the line.
+    fn room(&self, line: usize) -> usize {
+        self.width
+            .saturating_sub(display_width(self.indent(line)))
+            .max(1)
+    }
+
+    pub fn wrap(&self, text: &str) -> Vec<String> {
+        text.split('\n')
+            .flat_map(|line| self.wrap_line(line.trim_end_matches('\r')))
+            .collect()
+    }
+
+    pub fn fill(&self, text: &str) -> String {
+        self.wrap(text).join("\n")
+    }
+
+    fn wrap_line(&self, line: &str) -> Vec<String> {
+        let mut lines: Vec<Vec<String>> = Vec::new();
+        let mut current: Vec<String> = Vec::new();
+        let mut used = 0;
+        for word in line.split_whitespace() {
+            let mut word = word.to_string();
+            loop {
+                let room = self.room(lines.len());
+                let space = usize::from(!current.is_empty());
+                if used + space + display_width(&word) <= room {
+                    used += space + display_width(&word);
+                    current.push(word);
+                    break;
+                }
+                if !current.is_empty() {
+                    lines.push(std::mem::take(&mut current));
+                    used = 0;
+                } else if self.break_words {
+                    let (head, tail) = split_at_width(&word, room);
+                    lines.push(vec![head]);
+                    word = tail;
+                } else {
+                    // Overflowing, and filling the line, so that the next
+                    // word starts a new one.
+                    current.push(word);
+                    used = room;
+                    break;
+                }
+            }
+        }
+        if !current.is_empty() {
+            lines.push(current);
+        }
+        if lines.is_empty() {
+            return vec![String::new()];
+        }
+
+        let last = lines.len() - 1;
+        lines
+            .iter()
+            .enumerate()
+            .map(|(index, words)| {
+                let body = if self.justify && index < last {
+                    justify_words(words, self.room(index))
+                } else {
+                    words.join(" ")
+                };
+                format!("{}{}", self.indent(index), body)
+            })
+            .collect()
+    }
+}
+
+// Joins the words, so that they fill the width exactly, if there are at least
+// two of them.
+fn justify_words(words: &[String], width: usize) -> String {
+    if words.len() < 2 {
+        return words.concat();
+    }
+    let gaps = words.len() - 1;
+    let letters: usize = words.iter().map(|word| display_width(word)).sum();
+    let spaces = width.saturating_sub(letters).max(gaps);
+    let mut out = words[0].clone();
+    for (index, word) in words[1..].iter().enumerate() {
+        let extra = usize::from(index < spaces % gaps);
+        out.push_str(&" ".repeat(spaces / gaps + extra));
+        out.push_str(word);
+    }
+    out
+}
+
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    Wrapper::new(width).wrap(text)
+}
+
+pub fn fill(text: &str, width: usize) -> String {
+    Wrapper::new(width).fill(text)
+}
+
+pub fn justify(text: &str, width: usize) -> String {
+    Wrapper::new(width).justify().fill(text)
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+const TEXT: &str = "This repository contains numerous code snippets, with \
+variable usability. These are just hastily realized ideas, written in various \
+programming languages.\n\nUnder no circumstances should any of these snippets \
+be used in a professional environment without thorough testing.";
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "greedy wrapping",
+        wrap("the quick brown fox jumps over the lazy dog", 12),
+        vec!["the quick", "brown fox", "jumps over", "the lazy dog"]
+    );
+    test!(
+        harness,
+        "keeping line breaks and empty lines, squeezing other whitespace",
+        wrap("one  two\n\n\tthree   four\r\nfive", 20),
+        vec!["one two", "", "three four", "five"]
+    );
+    test!(
+        harness,
+        "breaking a word longer than the line",
+        wrap("a supercalifragilistic word", 8),
+        vec!["a", "supercal", "ifragili", "stic", "word"]
+    );
+    test!(
+        harness,
+        "letting a long word overflow",
+        Wrapper::new(8)
+            .break_words(false)
+            .wrap("a supercalifragilistic word"),
+        vec!["a", "supercalifragilistic", "word"]
+    );
+    test!(
+        harness,
+        "measuring wide characters",
+        wrap("日本語 の テキスト です", 10),
+        vec!["日本語 の", "テキスト", "です"]
+    );
+    test!(
+        harness,
+        "a hanging indent",
+        Wrapper::new(24)
+            .initial_indent("-f, --force  ")
+            .subsequent_indent("             ")
+            .wrap("overwrite the existing files without asking"),
+        vec![
+            "-f, --force  overwrite",
+            "             the",
+            "             existing",
+            "             files",
+            "             without",
+            "             asking"
+        ]
+    );
+    test!(
+        harness,
+        "justifying, except for the last line",
+        justify("the quick brown fox jumps over the lazy dog", 16),
+        "the  quick brown\nfox  jumps  over\nthe lazy dog"
+    );
+    let justified = Wrapper::new(40).justify().wrap(TEXT);
+    let last_lines = [3, 4, 7];
+    let full = justified
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !last_lines.contains(index))
+        .all(|(_, line)| display_width(line) == 40);
+    test!(
+        harness,
+        "a justified line fills the width",
+        (justified.len(), full),
+        (8, true)
+    );
+    test!(
+        harness,
+        "an empty text is an empty line",
+        wrap("", 10),
+        vec![""]
+    );
+
+    note!(harness, "Wrapped to 50 columns:\n{}\n", fill(TEXT, 50));
+    note!(
+        harness,
+        "Justified to 40 columns:\n{}\n",
+        justified.join("\n")
+    );
+    let bullets = Wrapper::new(50)
+        .initial_indent("  * ")
+        .subsequent_indent("    ")
+        .fill(TEXT);
+    note!(harness, "With a hanging indent:\n{}\n", bullets);
+}
@@ -0,0 +1,606 @@
+// xml.rs
+// A streaming pull parser of XML, which does not build a tree of the
+// document, but hands out the events one by one, as the caller asks for them,
+// so it reads documents of any size in constant memory, except for the stack
+// of the open elements, and the caller can stop early, once it found what it
+// was looking for. The events are the start and the end of the elements, with
+// the attributes of the start tags, the text between them, and the comments.
+// An empty element tag produces both a start and an end event, the CDATA
+// sections are text, and the XML declaration, and the other processing
+// instructions, are skipped. The five predefined entities, and the numeric
+// character references, are decoded in the text and in the attribute values.
+// The parser checks that the document is well-formed: the tags must nest,
+// there must be exactly one root element, and no attribute may be repeated,
+// and the errors are reported with the line and the column where they were
+// found, just like in the JSON snippet. The document type declarations are
+// not supported. The demonstration walks a small document.
+// Author: Dénes Fintha
+// Year: 2026
+// -------------------------------------------------------------------------- //
+
+// Implementation
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    StartElement {
+        name: String,
+        attributes: Vec<(String, String)>,
+    },
+    EndElement {
+        name: String,
+    },
+    Text(String),
+    Comment(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnexpectedEnd,
+    UnexpectedCharacter(char),
+    InvalidEntity,
+    DuplicateAttribute(String),
+    MismatchedEndTag(String),
+    UnclosedElement(String),
+    InvalidComment,
+    MultipleRoots,
+    TextOutsideRoot,
+    CdataEndInText,
+    NoRoot,
+    UnsupportedDoctype,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character {:?}", c),
+            ErrorKind::InvalidEntity => write!(f, "invalid entity or character reference"),
+            ErrorKind::DuplicateAttribute(name) => write!(f, "duplicate attribute {}", name),
+            ErrorKind::MismatchedEndTag(name) => write!(f, "end tag does not match <{}>", name),
+            ErrorKind::UnclosedElement(name) => write!(f, "element <{}> is not closed", name),
+            ErrorKind::InvalidComment => write!(f, "double hyphen in a comment"),
+            ErrorKind::MultipleRoots => write!(f, "more than one root element"),
+            ErrorKind::TextOutsideRoot => write!(f, "text outside the root element"),
+            ErrorKind::CdataEndInText => write!(f, "]]> outside a CDATA section"),
+            ErrorKind::NoRoot => write!(f, "no root element"),
+            ErrorKind::UnsupportedDoctype => {
+                write!(f, "document type declarations are not supported")
+            }
+        }
+    }
+}
+
+// Lines and columns are counted from one, and columns are counted in
+// characters, not in bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XmlError {
+    pub kind: ErrorKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.kind, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for XmlError {}
+
+fn is_name_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == ':'
+}
+
+fn is_name_char(c: char) -> bool {
+    is_name_start(c) || c.is_numeric() || c == '-' || c == '.'
+}
+
+pub struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    open: Vec<&'a str>,
+    pending_end: Option<&'a str>,
+    seen_root: bool,
+    trim_whitespace: bool,
+    failed: bool,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            position: 0,
+            open: Vec::new(),
+            pending_end: None,
+            seen_root: false,
+            trim_whitespace: false,
+            failed: false,
+        }
+    }
+
+    // Skip the text events which are only whitespace, like the indentation.
+    pub fn trim_whitespace(mut self, trim: bool) -> Self {
+        self.trim_whitespace = trim;
+        self
+    }
+
+    // The number of the elements which are open.
+    pub fn depth(&self) -> usize {
+        self.open.len()
+    }
+
+    fn error_at(&self, position: usize, kind: ErrorKind) -> XmlError {
+        let before = &self.text[..position];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        XmlError {
+            kind,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    fn unexpected(&self) -> XmlError {
+        match self.rest().chars().next() {
+            Some(c) => self.error_at(self.position, ErrorKind::UnexpectedCharacter(c)),
+            None => self.error_at(self.position, ErrorKind::UnexpectedEnd),
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start_matches([' ', '\t', '\n', '\r']).len();
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), XmlError> {
+        if !self.rest().starts_with(c) {
+            return Err(self.unexpected());
+        }
+        self.position += c.len_utf8();
+        Ok(())
+    }
+
+    fn name(&mut self) -> Result<&'a str, XmlError> {
+        let rest = self.rest();
+        match rest.chars().next() {
+            Some(c) if is_name_start(c) => {}
+            _ => return Err(self.unexpected()),
+        }
+        let length = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+        self.position += length;
+        Ok(&rest[..length])
+    }
+
+    // The text up to the terminator, which is consumed, but not returned.
+    fn until(&mut self, terminator: &str) -> Result<&'a str, XmlError> {
+        match self.rest().find(terminator) {
+            Some(index) => {
+                let content = &self.rest()[..index];
+                self.position += index + terminator.len();
+                Ok(content)
+            }
+            None => Err(self.error_at(self.text.len(), ErrorKind::UnexpectedEnd)),
+        }
+    }
+
+    // The entities start at an ampersand, and end at the next semicolon.
+    fn decode(&self, raw: &str, offset: usize) -> Result<String, XmlError> {
+        let mut result = String::with_capacity(raw.len());
+        let mut rest = raw;
+        while let Some(index) = rest.find('&') {
+            result.push_str(&rest[..index]);
+            let position = offset + (raw.len() - rest.len()) + index;
+            let invalid = || self.error_at(position, ErrorKind::InvalidEntity);
+            let end = rest[index..].find(';').ok_or_else(invalid)?;
+            let entity = &rest[index + 1..index + end];
+            let c = match entity {
+                "lt" => '<',
+                "gt" => '>',
+                "amp" => '&',
+                "apos" => '\'',
+                "quot" => '"',
+                _ => {
+                    // The parsers of the standard library would take a sign.
+                    let code = match entity.strip_prefix("#x") {
+                        Some(hex) if hex.bytes().all(|byte| byte.is_ascii_hexdigit()) => {
+                            u32::from_str_radix(hex, 16).ok()
+                        }
+                        Some(_) => None,
+                        None => entity
+                            .strip_prefix('#')
+                            .filter(|decimal| decimal.bytes().all(|byte| byte.is_ascii_digit()))
+                            .and_then(|decimal| decimal.parse().ok()),
+                    };
+                    code.and_then(char::from_u32)
+                        .filter(|c| *c != '\0')
+                        .ok_or_else(invalid)?
+                }
+            };
+            result.push(c);
+            rest = &rest[index + end + 1..];
+        }
+        result.push_str(rest);
+        Ok(result)
+    }
+
+    fn start_tag(&mut self, start: usize) -> Result<Event, XmlError> {
+        if self.open.is_empty() && self.seen_root {
+            return Err(self.error_at(start, ErrorKind::MultipleRoots));
+        }
+        self.position += 1;
+        let name = self.name()?;
+        let mut attributes: Vec<(String, String)> = Vec::new();
+        loop {
+            let before = self.position;
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.position += 2;
+                self.pending_end = Some(name);
+                break;
+            }
+            if self.rest().starts_with('>') {
+                self.position += 1;
+                self.open.push(name);
+                break;
+            }
+            // The attributes are separated from the name, and from each
+            // other, by whitespace.
+            if self.position == before {
+                return Err(self.unexpected());
+            }
+            let attribute_start = self.position;
+            let attribute = self.name()?;
+            self.skip_whitespace();
+            self.expect('=')?;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(quote @ ('"' | '\'')) => quote,
+                _ => return Err(self.unexpected()),
+            };
+            self.position += 1;
+            let value_start = self.position;
+            let length = self
+                .rest()
+                .find([quote, '<'])
+                .ok_or_else(|| self.error_at(self.text.len(), ErrorKind::UnexpectedEnd))?;
+            self.position += length;
+            let raw = &self.text[value_start..self.position];
+            self.expect(quote)?;
+            if attributes.iter().any(|(existing, _)| existing == attribute) {
+                return Err(self.error_at(
+                    attribute_start,
+                    ErrorKind::DuplicateAttribute(attribute.to_string()),
+                ));
+            }
+            attributes.push((attribute.to_string(), self.decode(raw, value_start)?));
+        }
+        self.seen_root = true;
+        Ok(Event::StartElement {
+            name: name.to_string(),
+            attributes,
+        })
+    }
+
+    fn end_tag(&mut self, start: usize) -> Result<Event, XmlError> {
+        self.position += 2;
+        let name = self.name()?;
+        self.skip_whitespace();
+        self.expect('>')?;
+        match self.open.pop() {
+            Some(open) if open == name => Ok(Event::EndElement {
+                name: name.to_string(),
+            }),
+            Some(open) => Err(self.error_at(start, ErrorKind::MismatchedEndTag(open.to_string()))),
+            None => Err(self.error_at(start, ErrorKind::MultipleRoots)),
+        }
+    }
+
+    fn event(&mut self) -> Result<Option<Event>, XmlError> {
+        if let Some(name) = self.pending_end.take() {
+            return Ok(Some(Event::EndElement {
+                name: name.to_string(),
+            }));
+        }
+        loop {
+            let start = self.position;
+            let rest = self.rest();
+            if rest.is_empty() {
+                return match (self.open.last(), self.seen_root) {
+                    (Some(name), _) => {
+                        Err(self.error_at(start, ErrorKind::UnclosedElement(name.to_string())))
+                    }
+                    (None, false) => Err(self.error_at(start, ErrorKind::NoRoot)),
+                    (None, true) => Ok(None),
+                };
+            }
+            if rest.starts_with("<!--") {
+                self.position += 4;
+                let content = self.until("-->")?;
+                // A hyphen at the end makes a double hyphen with the terminator.
+                let hyphen = content
+                    .find("--")
+                    .or_else(|| content.ends_with('-').then(|| content.len() - 1));
+                if let Some(index) = hyphen {
+                    return Err(self.error_at(start + 4 + index, ErrorKind::InvalidComment));
+                }
+                return Ok(Some(Event::Comment(content.to_string())));
+            }
+            if rest.starts_with("<![CDATA[") {
+                if self.open.is_empty() {
+                    return Err(self.error_at(start, ErrorKind::TextOutsideRoot));
+                }
+                self.position += 9;
+                return Ok(Some(Event::Text(self.until("]]>")?.to_string())));
+            }
+            if rest.starts_with("<?") {
+                self.position += 2;
+                self.until("?>")?;
+                continue;
+            }
+            if rest.starts_with("<!DOCTYPE") {
+                return Err(self.error_at(start, ErrorKind::UnsupportedDoctype));
+            }
+            if rest.starts_with("</") {
+                return self.end_tag(start).map(Some);
+            }
+            if rest.starts_with('<') {
+                return self.start_tag(start).map(Some);
+            }
+            let length = rest.find('<').unwrap_or(rest.len());
+            let raw = &rest[..length];
+            self.position += length;
+            let blank = raw.trim_start_matches([' ', '\t', '\n', '\r']);
+            if self.open.is_empty() {
+                if !blank.is_empty() {
+                    let position = start + (raw.len() - blank.len());
+                    return Err(self.error_at(position, ErrorKind::TextOutsideRoot));
+                }
+                continue;
+            }
+            if let Some(index) = raw.find("]]>") {
+                return Err(self.error_at(start + index, ErrorKind::CdataEndInText));
+            }
+            if self.trim_whitespace && blank.is_empty() {
+                continue;
+            }
+            return Ok(Some(Event::Text(self.decode(raw, start)?)));
+        }
+    }
+}
+
+// After an error, the parser stops.
+impl Iterator for Parser<'_> {
+    type Item = Result<Event, XmlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let event = self.event();
+        self.failed = event.is_err();
+        event.transpose()
+    }
+}
+
+pub fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '&' => result.push_str("&amp;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+// Demonstration
+
+use crate::harness::Harness;
+
+const DOCUMENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- A small library -->
+<library name="Snippets &amp; Co." open='true'>
+    <book id="1" lang="en">
+        <title>The Art of Computer Programming</title>
+        <author>Donald E. Knuth</author>
+        <year>1968</year>
+    </book>
+    <book id="2" lang="hu">
+        <title>A &lt;C&gt; programozási nyelv</title>
+        <author>Brian W. Kernighan</author>
+        <author>Dennis M. Ritchie</author>
+        <year>1978</year>
+        <note><![CDATA[Translated, <not> markup & all]]></note>
+    </book>
+    <shelf id="3"/>
+</library>
+"#;
+
+// The events, in a compact form, which is easy to compare.
+fn outline(text: &str) -> Vec<String> {
+    Parser::new(text)
+        .trim_whitespace(true)
+        .map(|event| match event {
+            Ok(Event::StartElement { name, attributes }) => {
+                let attributes: String = attributes
+                    .iter()
+                    .map(|(name, value)| format!(" {}={:?}", name, value))
+                    .collect();
+                format!("<{}{}>", name, attributes)
+            }
+            Ok(Event::EndElement { name }) => format!("</{}>", name),
+            Ok(Event::Text(text)) => format!("{:?}", text),
+            Ok(Event::Comment(text)) => format!("<!--{}-->", text),
+            Err(error) => error.to_string(),
+        })
+        .collect()
+}
+
+pub fn demo(harness: &mut Harness) {
+    test!(
+        harness,
+        "the events of a small document are produced in order",
+        outline("<a x='1'><b/>hi<!--c--></a>"),
+        ["<a x=\"1\">", "<b>", "</b>", "\"hi\"", "<!--c-->", "</a>"].map(String::from)
+    );
+    test!(
+        harness,
+        "the entities and the character references are decoded",
+        outline("<p q=\"&quot;&#65;&#x42;&quot;\">&lt;&amp;&gt; &apos;&#x1F980;&apos;</p>"),
+        ["<p q=\"\\\"AB\\\"\">", "\"<&> '🦀'\"", "</p>"].map(String::from)
+    );
+    test!(
+        harness,
+        "whitespace is kept, unless asked otherwise",
+        (
+            Parser::new("<a> <b/> </a>").count(),
+            Parser::new("<a> <b/> </a>").trim_whitespace(true).count()
+        ),
+        (6, 4)
+    );
+
+    let errors = [
+        (
+            "<a><b></a>",
+            "end tag does not match <b> at line 1, column 7",
+        ),
+        (
+            "<a>\n  <b>",
+            "element <b> is not closed at line 2, column 6",
+        ),
+        (
+            "<a x='1' x='2'/>",
+            "duplicate attribute x at line 1, column 10",
+        ),
+        ("<a/><b/>", "more than one root element at line 1, column 5"),
+        (
+            "<a>&nbsp;</a>",
+            "invalid entity or character reference at line 1, column 4",
+        ),
+        (
+            "<a>&#0;</a>",
+            "invalid entity or character reference at line 1, column 4",
+        ),
+        (
+            "hello <a/>",
+            "text outside the root element at line 1, column 1",
+        ),
+        (
+            "<a><!-- x -- y --></a>",
+            "double hyphen in a comment at line 1, column 11",
+        ),
+        (
+            "<a><!-- x ---></a>",
+            "double hyphen in a comment at line 1, column 11",
+        ),
+        (
+            "<a>x ]]> y</a>",
+            "]]> outside a CDATA section at line 1, column 6",
+        ),
+        ("<a x='<'/>", "unexpected character '<' at line 1, column 7"),
+        (
+            "<a x='1'y='2'/>",
+            "unexpected character 'y' at line 1, column 9",
+        ),
+        ("<1a/>", "unexpected character '1' at line 1, column 2"),
+        ("  \n", "no root element at line 2, column 1"),
+        (
+            "<!DOCTYPE a><a/>",
+            "document type declarations are not supported at line 1, column 1",
+        ),
+    ];
+    test!(
+        harness,
+        "malformed documents are rejected with the line and the column",
+        errors
+            .iter()
+            .map(|(text, _)| outline(text).pop().unwrap_or_default())
+            .collect::<Vec<_>>(),
+        errors
+            .iter()
+            .map(|(_, message)| message.to_string())
+            .collect::<Vec<_>>()
+    );
+    test!(
+        harness,
+        "the parser stops after the first error",
+        Parser::new("<a><b></a></b>").filter(Result::is_err).count(),
+        1
+    );
+
+    let events: Result<Vec<Event>, XmlError> =
+        Parser::new(DOCUMENT).trim_whitespace(true).collect();
+    test!(
+        harness,
+        "the sample document is well-formed",
+        events.is_ok(),
+        true
+    );
+    let events = events.unwrap_or_default();
+    let mut titles = Vec::new();
+    let mut path: Vec<&str> = Vec::new();
+    for event in &events {
+        match event {
+            Event::StartElement { name, .. } => path.push(name.as_str()),
+            Event::EndElement { .. } => {
+                path.pop();
+            }
+            Event::Text(text) if path.ends_with(&["book", "title"]) => titles.push(text.as_str()),
+            _ => {}
+        }
+    }
+    test!(
+        harness,
+        "the titles of the books are found by their path",
+        titles,
+        vec![
+            "The Art of Computer Programming",
+            "A <C> programozási nyelv"
+        ]
+    );
+    test!(
+        harness,
+        "the CDATA section is taken as it is",
+        events.contains(&Event::Text("Translated, <not> markup & all".to_string())),
+        true
+    );
+    test!(
+        harness,
+        "the escaped text is decoded to the original",
+        outline(&format!("<a>{}</a>", escape("<\"x\" & 'y'>")))[1].as_str(),
+        "\"<\\\"x\\\" & 'y'>\""
+    );
+
+    let mut printed = String::new();
+    let mut depth = 0;
+    for event in &events {
+        match event {
+            Event::StartElement { name, attributes } => {
+                printed += &format!("\n{}{}", "  ".repeat(depth), name);
+                for (name, value) in attributes {
+                    printed += &format!(" @{}={:?}", name, value);
+                }
+                depth += 1;
+            }
+            Event::EndElement { .. } => depth -= 1,
+            Event::Text(text) => printed += &format!(": {:?}", text),
+            Event::Comment(text) => {
+                printed += &format!("\n{}# {}", "  ".repeat(depth), text.trim())
+            }
+        }
+    }
+    note!(harness, "The sample document:{}", printed);
+}